@@ -245,12 +245,13 @@ impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
             Error::InvalidKey { .. }
-            | Error::SchemaNotFound { .. }
             | Error::TableNotFound { .. }
             | Error::CatalogNotFound { .. }
             | Error::InvalidEntryType { .. }
             | Error::ParallelOpenTable { .. } => StatusCode::Unexpected,
 
+            Error::SchemaNotFound { .. } => StatusCode::DatabaseNotFound,
+
             Error::SystemCatalog { .. }
             | Error::EmptyValue { .. }
             | Error::ValueDeserialize { .. } => StatusCode::StorageUnavailable,
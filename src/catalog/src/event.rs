@@ -0,0 +1,177 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_meta::table_name::TableName;
+use common_telemetry::warn;
+use table::metadata::TableId;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+use crate::metrics::METRIC_CATALOG_EVENT_LAGGED;
+
+/// Default number of events buffered for each subscriber.
+pub const DEFAULT_EVENT_BUFFER_SIZE: usize = 1024;
+
+/// A change made to the tables of a catalog manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogEvent {
+    TableCreated {
+        table_name: TableName,
+        table_id: TableId,
+    },
+    TableDropped {
+        table_name: TableName,
+        table_id: TableId,
+    },
+    TableAltered {
+        table_name: TableName,
+        table_id: TableId,
+    },
+}
+
+impl CatalogEvent {
+    pub fn table_name(&self) -> &TableName {
+        match self {
+            CatalogEvent::TableCreated { table_name, .. }
+            | CatalogEvent::TableDropped { table_name, .. }
+            | CatalogEvent::TableAltered { table_name, .. } => table_name,
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        match self {
+            CatalogEvent::TableCreated { table_id, .. }
+            | CatalogEvent::TableDropped { table_id, .. }
+            | CatalogEvent::TableAltered { table_id, .. } => *table_id,
+        }
+    }
+}
+
+/// Fans [CatalogEvent]s out to the subscribers.
+///
+/// Notifying never waits for the subscribers: each of them has a bounded buffer, and a
+/// subscriber that falls behind loses its oldest events.
+#[derive(Debug, Clone)]
+pub struct CatalogEventNotifier {
+    sender: Sender<CatalogEvent>,
+}
+
+impl Default for CatalogEventNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUFFER_SIZE)
+    }
+}
+
+impl CatalogEventNotifier {
+    pub fn new(buffer_size: usize) -> Self {
+        let (sender, _) = broadcast::channel(buffer_size);
+        Self { sender }
+    }
+
+    /// Registers a new subscriber, it only receives the events notified after.
+    /// Dropping the returned [CatalogEventSubscription] removes the subscriber.
+    pub fn subscribe(&self) -> CatalogEventSubscription {
+        CatalogEventSubscription {
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    pub fn notify(&self, event: CatalogEvent) {
+        // An error only means there is no subscriber at the moment.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// Receiving end of the [CatalogEvent]s.
+#[derive(Debug)]
+pub struct CatalogEventSubscription {
+    receiver: Receiver<CatalogEvent>,
+}
+
+impl CatalogEventSubscription {
+    /// Waits for the next event, returns `None` if the notifier has gone.
+    pub async fn recv(&mut self) -> Option<CatalogEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(n)) => on_lagged(n),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Returns the next event if there is one buffered.
+    pub fn try_recv(&mut self) -> Option<CatalogEvent> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(n)) => on_lagged(n),
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+fn on_lagged(n: u64) {
+    warn!(
+        "Catalog event subscriber lagged behind, {} events dropped",
+        n
+    );
+    METRIC_CATALOG_EVENT_LAGGED.inc_by(n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created(table: &str, table_id: TableId) -> CatalogEvent {
+        CatalogEvent::TableCreated {
+            table_name: TableName::new("greptime", "public", table),
+            table_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_and_recv() {
+        let notifier = CatalogEventNotifier::default();
+        // No subscriber.
+        notifier.notify(created("t0", 1024));
+
+        let mut subscription = notifier.subscribe();
+        notifier.notify(created("t1", 1025));
+        assert_eq!(Some(created("t1", 1025)), subscription.recv().await);
+        assert_eq!(None, subscription.try_recv());
+
+        assert_eq!(1, notifier.subscriber_count());
+        drop(subscription);
+        assert_eq!(0, notifier.subscriber_count());
+    }
+
+    #[test]
+    fn test_slow_subscriber_drops_oldest() {
+        let notifier = CatalogEventNotifier::new(2);
+        let mut subscription = notifier.subscribe();
+        for i in 0..5 {
+            notifier.notify(created(&format!("t{i}"), 1024 + i));
+        }
+
+        assert_eq!(Some(created("t3", 1027)), subscription.try_recv());
+        assert_eq!(Some(created("t4", 1028)), subscription.try_recv());
+        assert_eq!(None, subscription.try_recv());
+    }
+}
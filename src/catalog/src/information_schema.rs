@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod columns;
+mod index_statistics;
 mod key_column_usage;
 mod memory_table;
 mod predicate;
@@ -45,6 +46,7 @@ pub use table_names::*;
 
 use self::columns::InformationSchemaColumns;
 use crate::error::Result;
+use crate::information_schema::index_statistics::InformationSchemaIndexStatistics;
 use crate::information_schema::key_column_usage::InformationSchemaKeyColumnUsage;
 use crate::information_schema::memory_table::{get_schema_columns, MemoryTable};
 use crate::information_schema::runtime_metrics::InformationSchemaMetrics;
@@ -156,6 +158,10 @@ impl InformationSchemaProvider {
                 BUILD_INFO.to_string(),
                 self.build_table(BUILD_INFO).unwrap(),
             );
+            tables.insert(
+                INDEX_STATISTICS.to_string(),
+                self.build_table(INDEX_STATISTICS).unwrap(),
+            );
         }
 
         tables.insert(TABLES.to_string(), self.build_table(TABLES).unwrap());
@@ -226,6 +232,7 @@ impl InformationSchemaProvider {
                 self.catalog_manager.clone(),
             )) as _),
             RUNTIME_METRICS => Some(Arc::new(InformationSchemaMetrics::new())),
+            INDEX_STATISTICS => Some(Arc::new(InformationSchemaIndexStatistics::new())),
             _ => None,
         }
     }
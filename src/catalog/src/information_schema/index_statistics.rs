@@ -0,0 +1,223 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_catalog::consts::INFORMATION_SCHEMA_INDEX_STATISTICS_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::scalars::ScalarVectorBuilder;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{
+    Float64VectorBuilder, StringVectorBuilder, UInt32VectorBuilder, UInt64VectorBuilder, VectorRef,
+};
+use snafu::ResultExt;
+use store_api::index_statistics::{self, IndexStatistics};
+use store_api::storage::{ScanRequest, TableId};
+
+use super::{InformationTable, INDEX_STATISTICS};
+use crate::error::{CreateRecordBatchSnafu, InternalSnafu, Result};
+
+const REGION_ID: &str = "region_id";
+const TABLE_ID: &str = "table_id";
+const REGION_NUMBER: &str = "region_number";
+const FILE_ID: &str = "file_id";
+const COLUMN_ID: &str = "column_id";
+const COLUMN_NAME: &str = "column_name";
+const DISTINCT_COUNT: &str = "distinct_count";
+const INDEX_SIZE: &str = "index_size";
+const AVG_POSTINGS_LEN: &str = "avg_postings_len";
+
+/// The `information_schema.index_statistics` virtual table.
+/// It provides statistics of the inverted index of each column in SSTs of the node.
+pub(super) struct InformationSchemaIndexStatistics {
+    schema: SchemaRef,
+}
+
+impl InformationSchemaIndexStatistics {
+    pub(super) fn new() -> Self {
+        Self {
+            schema: Self::schema(),
+        }
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(REGION_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(TABLE_ID, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(REGION_NUMBER, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(FILE_ID, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(COLUMN_ID, ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new(COLUMN_NAME, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(DISTINCT_COUNT, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(INDEX_SIZE, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(
+                AVG_POSTINGS_LEN,
+                ConcreteDataType::float64_datatype(),
+                false,
+            ),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaIndexStatisticsBuilder {
+        InformationSchemaIndexStatisticsBuilder::new(self.schema.clone())
+    }
+}
+
+impl InformationTable for InformationSchemaIndexStatistics {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_INDEX_STATISTICS_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        INDEX_STATISTICS
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, _request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_index_statistics(index_statistics::index_statistics())
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaIndexStatisticsBuilder {
+    schema: SchemaRef,
+
+    region_ids: UInt64VectorBuilder,
+    table_ids: UInt32VectorBuilder,
+    region_numbers: UInt32VectorBuilder,
+    file_ids: StringVectorBuilder,
+    column_ids: UInt32VectorBuilder,
+    column_names: StringVectorBuilder,
+    distinct_counts: UInt64VectorBuilder,
+    index_sizes: UInt64VectorBuilder,
+    avg_postings_lens: Float64VectorBuilder,
+}
+
+impl InformationSchemaIndexStatisticsBuilder {
+    fn new(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            region_ids: UInt64VectorBuilder::with_capacity(42),
+            table_ids: UInt32VectorBuilder::with_capacity(42),
+            region_numbers: UInt32VectorBuilder::with_capacity(42),
+            file_ids: StringVectorBuilder::with_capacity(42),
+            column_ids: UInt32VectorBuilder::with_capacity(42),
+            column_names: StringVectorBuilder::with_capacity(42),
+            distinct_counts: UInt64VectorBuilder::with_capacity(42),
+            index_sizes: UInt64VectorBuilder::with_capacity(42),
+            avg_postings_lens: Float64VectorBuilder::with_capacity(42),
+        }
+    }
+
+    fn add_index_statistics(&mut self, stats: &IndexStatistics) {
+        self.region_ids.push(Some(stats.region_id.as_u64()));
+        self.table_ids.push(Some(stats.region_id.table_id()));
+        self.region_numbers
+            .push(Some(stats.region_id.region_number()));
+        self.file_ids.push(Some(&stats.file_id));
+        self.column_ids.push(Some(stats.column_id));
+        self.column_names.push(Some(&stats.column_name));
+        self.distinct_counts.push(Some(stats.distinct_count));
+        self.index_sizes.push(Some(stats.index_size));
+        self.avg_postings_lens.push(Some(stats.avg_postings_len));
+    }
+
+    fn make_index_statistics(&mut self, statistics: Vec<IndexStatistics>) -> Result<RecordBatch> {
+        for stats in &statistics {
+            self.add_index_statistics(stats);
+        }
+
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.region_ids.finish()),
+            Arc::new(self.table_ids.finish()),
+            Arc::new(self.region_numbers.finish()),
+            Arc::new(self.file_ids.finish()),
+            Arc::new(self.column_ids.finish()),
+            Arc::new(self.column_names.finish()),
+            Arc::new(self.distinct_counts.finish()),
+            Arc::new(self.index_sizes.finish()),
+            Arc::new(self.avg_postings_lens.finish()),
+        ];
+
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use store_api::storage::RegionId;
+
+    use super::*;
+
+    #[test]
+    fn test_make_index_statistics() {
+        let table = InformationSchemaIndexStatistics::new();
+        let mut builder = table.builder();
+        let batch = builder
+            .make_index_statistics(vec![IndexStatistics {
+                region_id: RegionId::new(1024, 1),
+                file_id: "file".to_string(),
+                column_id: 2,
+                column_name: "host".to_string(),
+                distinct_count: 10,
+                index_size: 100,
+                avg_postings_len: 1.5,
+            }])
+            .unwrap();
+
+        assert_eq!(1, batch.num_rows());
+        let row: Vec<_> = (0..batch.num_columns())
+            .map(|i| batch.column(i).get(0).to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                RegionId::new(1024, 1).as_u64().to_string(),
+                "1024".to_string(),
+                "1".to_string(),
+                "file".to_string(),
+                "2".to_string(),
+                "host".to_string(),
+                "10".to_string(),
+                "100".to_string(),
+                "1.5".to_string(),
+            ],
+            row
+        );
+    }
+}
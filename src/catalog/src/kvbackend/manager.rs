@@ -38,6 +38,7 @@ use crate::error::{
     self as catalog_err, ListCatalogsSnafu, ListSchemasSnafu, Result as CatalogResult,
     TableMetadataManagerSnafu,
 };
+use crate::event::CatalogEventNotifier;
 use crate::information_schema::InformationSchemaProvider;
 use crate::CatalogManager;
 
@@ -56,6 +57,7 @@ pub struct KvBackendCatalogManager {
     table_metadata_manager: TableMetadataManagerRef,
     /// A sub-CatalogManager that handles system tables
     system_catalog: SystemCatalog,
+    event_notifier: CatalogEventNotifier,
 }
 
 #[async_trait::async_trait]
@@ -87,6 +89,7 @@ impl KvBackendCatalogManager {
                     me.clone(),
                 )),
             },
+            event_notifier: Default::default(),
         })
     }
 
@@ -227,6 +230,10 @@ impl CatalogManager for KvBackendCatalogManager {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn event_notifier(&self) -> &CatalogEventNotifier {
+        &self.event_notifier
+    }
 }
 
 // TODO: This struct can hold a static map of all system tables when
@@ -25,8 +25,10 @@ use table::requests::CreateTableRequest;
 use table::TableRef;
 
 use crate::error::Result;
+use crate::event::CatalogEventNotifier;
 
 pub mod error;
+pub mod event;
 pub mod information_schema;
 pub mod kvbackend;
 pub mod memory;
@@ -56,6 +58,9 @@ pub trait CatalogManager: Send + Sync {
         schema: &str,
         table_name: &str,
     ) -> Result<Option<TableRef>>;
+
+    /// Returns the notifier of table changes, subscribe to it to get notified.
+    fn event_notifier(&self) -> &CatalogEventNotifier;
 }
 
 pub type CatalogManagerRef = Arc<dyn CatalogManager>;
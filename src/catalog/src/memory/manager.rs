@@ -21,10 +21,12 @@ use common_catalog::build_db_string;
 use common_catalog::consts::{
     DEFAULT_CATALOG_NAME, DEFAULT_PRIVATE_SCHEMA_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME,
 };
+use common_meta::table_name::TableName;
 use snafu::OptionExt;
 use table::TableRef;
 
 use crate::error::{CatalogNotFoundSnafu, Result, SchemaNotFoundSnafu, TableExistsSnafu};
+use crate::event::{CatalogEvent, CatalogEventNotifier};
 use crate::information_schema::InformationSchemaProvider;
 use crate::{CatalogManager, DeregisterTableRequest, RegisterSchemaRequest, RegisterTableRequest};
 
@@ -35,6 +37,7 @@ type SchemaEntries = HashMap<String, HashMap<String, TableRef>>;
 pub struct MemoryCatalogManager {
     /// Collection of catalogs containing schemas and ultimately Tables
     catalogs: Arc<RwLock<HashMap<String, SchemaEntries>>>,
+    event_notifier: CatalogEventNotifier,
 }
 
 #[async_trait::async_trait]
@@ -113,12 +116,17 @@ impl CatalogManager for MemoryCatalogManager {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn event_notifier(&self) -> &CatalogEventNotifier {
+        &self.event_notifier
+    }
 }
 
 impl MemoryCatalogManager {
     pub fn new() -> Arc<Self> {
         Arc::new(Self {
             catalogs: Default::default(),
+            event_notifier: Default::default(),
         })
     }
 
@@ -127,6 +135,7 @@ impl MemoryCatalogManager {
     pub fn with_default_setup() -> Arc<Self> {
         let manager = Arc::new(Self {
             catalogs: Default::default(),
+            event_notifier: Default::default(),
         });
 
         // Safety: default catalog/schema is registered in order so no CatalogNotFound error will occur
@@ -200,10 +209,14 @@ impl MemoryCatalogManager {
                 schema: &request.schema,
             })?;
         let result = schema.remove(&request.table_name);
-        if result.is_some() {
+        if let Some(table) = result {
             crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT
                 .with_label_values(&[build_db_string(&request.catalog, &request.schema).as_str()])
                 .dec();
+            self.event_notifier.notify(CatalogEvent::TableDropped {
+                table_name: TableName::new(request.catalog, request.schema, request.table_name),
+                table_id: table.table_info().ident.table_id,
+            });
         }
         Ok(())
     }
@@ -249,10 +262,14 @@ impl MemoryCatalogManager {
             }
             .fail();
         }
-        schema.insert(request.table_name, request.table);
+        schema.insert(request.table_name.clone(), request.table);
         crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT
             .with_label_values(&[build_db_string(&request.catalog, &request.schema).as_str()])
             .inc();
+        self.event_notifier.notify(CatalogEvent::TableCreated {
+            table_name: TableName::new(request.catalog, request.schema, request.table_name),
+            table_id: request.table_id,
+        });
         Ok(true)
     }
 
@@ -378,4 +395,43 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[tokio::test]
+    async fn test_catalog_events() {
+        let catalog = MemoryCatalogManager::with_default_setup();
+        let mut subscription = catalog.event_notifier().subscribe();
+        let table_name = "foo_table";
+
+        let register_table_req = RegisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_name.to_string(),
+            table_id: 2333,
+            table: NumbersTable::table(2333),
+        };
+        catalog.register_table_sync(register_table_req).unwrap();
+        let deregister_table_req = DeregisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_name.to_string(),
+        };
+        catalog.deregister_table_sync(deregister_table_req).unwrap();
+
+        let expected_name = TableName::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, table_name);
+        assert_eq!(
+            Some(CatalogEvent::TableCreated {
+                table_name: expected_name.clone(),
+                table_id: 2333,
+            }),
+            subscription.recv().await
+        );
+        assert_eq!(
+            Some(CatalogEvent::TableDropped {
+                table_name: expected_name,
+                table_id: 2333,
+            }),
+            subscription.recv().await
+        );
+        assert_eq!(None, subscription.try_recv());
+    }
 }
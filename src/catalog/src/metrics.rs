@@ -32,4 +32,9 @@ lazy_static! {
         register_histogram!("greptime_catalog_kv_get_remote", "catalog kv get remote").unwrap();
     pub static ref METRIC_CATALOG_KV_GET: Histogram =
         register_histogram!("greptime_catalog_kv_get", "catalog kv get").unwrap();
+    pub static ref METRIC_CATALOG_EVENT_LAGGED: IntCounter = register_int_counter!(
+        "greptime_catalog_event_lagged",
+        "catalog events dropped for lagged subscribers"
+    )
+    .unwrap();
 }
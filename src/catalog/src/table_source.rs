@@ -20,10 +20,10 @@ use datafusion::common::{ResolvedTableReference, TableReference};
 use datafusion::datasource::provider_as_source;
 use datafusion::logical_expr::TableSource;
 use session::context::QueryContext;
-use snafu::{ensure, OptionExt};
+use snafu::ensure;
 use table::table::adapter::DfTableProviderAdapter;
 
-use crate::error::{QueryAccessDeniedSnafu, Result, TableNotExistSnafu};
+use crate::error::{QueryAccessDeniedSnafu, Result, SchemaNotFoundSnafu, TableNotExistSnafu};
 use crate::CatalogManagerRef;
 
 pub struct DfTableSourceProvider {
@@ -45,7 +45,7 @@ impl DfTableSourceProvider {
             disallow_cross_catalog_query,
             resolved_tables: HashMap::new(),
             default_catalog: query_ctx.current_catalog().to_owned(),
-            default_schema: query_ctx.current_schema().to_owned(),
+            default_schema: query_ctx.current_schema(),
         }
     }
 
@@ -89,13 +89,27 @@ impl DfTableSourceProvider {
         let schema_name = table_ref.schema.as_ref();
         let table_name = table_ref.table.as_ref();
 
-        let table = self
+        let Some(table) = self
             .catalog_manager
             .table(catalog_name, schema_name, table_name)
             .await?
-            .with_context(|| TableNotExistSnafu {
+        else {
+            // Tells a missing schema apart from a missing table, so that a typo in the
+            // qualifier of a fully-qualified name is reported as such.
+            ensure!(
+                self.catalog_manager
+                    .schema_exists(catalog_name, schema_name)
+                    .await?,
+                SchemaNotFoundSnafu {
+                    catalog: catalog_name,
+                    schema: schema_name,
+                }
+            );
+            return TableNotExistSnafu {
                 table: format_full_table_name(catalog_name, schema_name, table_name),
-            })?;
+            }
+            .fail();
+        };
 
         let provider = DfTableProviderAdapter::new(table);
         let source = provider_as_source(Arc::new(provider));
@@ -111,6 +125,7 @@ mod tests {
     use session::context::QueryContext;
 
     use super::*;
+    use crate::error::Error;
     use crate::memory::MemoryCatalogManager;
 
     #[test]
@@ -184,4 +199,38 @@ mod tests {
         };
         assert!(table_provider.resolve_table_ref(table_ref).is_ok());
     }
+
+    #[tokio::test]
+    async fn test_resolve_table_not_found() {
+        let query_ctx = &QueryContext::with("greptime", "public");
+        let mut table_provider = DfTableSourceProvider::new(
+            MemoryCatalogManager::with_default_setup(),
+            false,
+            query_ctx,
+        );
+
+        let table_ref = TableReference::Full {
+            catalog: Cow::Borrowed("greptime"),
+            schema: Cow::Borrowed("public"),
+            table: Cow::Borrowed("not_exist"),
+        };
+        let err = table_provider.resolve_table(table_ref).await.unwrap_err();
+        assert!(matches!(err, Error::TableNotExist { .. }), "{err:?}");
+
+        let table_ref = TableReference::Full {
+            catalog: Cow::Borrowed("greptime"),
+            schema: Cow::Borrowed("not_exist"),
+            table: Cow::Borrowed("numbers"),
+        };
+        let err = table_provider.resolve_table(table_ref).await.unwrap_err();
+        assert!(matches!(err, Error::SchemaNotFound { .. }), "{err:?}");
+
+        let table_ref = TableReference::Full {
+            catalog: Cow::Borrowed("not_exist"),
+            schema: Cow::Borrowed("public"),
+            table: Cow::Borrowed("numbers"),
+        };
+        let err = table_provider.resolve_table(table_ref).await.unwrap_err();
+        assert!(matches!(err, Error::CatalogNotFound { .. }), "{err:?}");
+    }
 }
@@ -24,7 +24,7 @@ use api::v1::{
 use arrow_flight::Ticket;
 use async_stream::stream;
 use common_error::ext::{BoxedError, ErrorExt};
-use common_grpc::flight::{FlightDecoder, FlightMessage};
+use common_grpc::flight::{FlightDecoder, FlightMessage, FLIGHT_METRICS_HEADER};
 use common_query::Output;
 use common_recordbatch::error::ExternalSnafu;
 use common_recordbatch::RecordBatchStreamWrapper;
@@ -33,6 +33,7 @@ use common_telemetry::tracing_context::W3cTrace;
 use futures_util::StreamExt;
 use prost::Message;
 use snafu::{ensure, ResultExt};
+use tonic::metadata::MetadataValue;
 
 use crate::error::{ConvertFlightDataSnafu, Error, IllegalFlightMessagesSnafu, ServerSnafu};
 use crate::{error, from_grpc_response, metrics, Client, Result, StreamInserter};
@@ -111,6 +112,12 @@ impl Database {
         });
     }
 
+    /// Asks the server to send metrics of queries after their results if
+    /// `request_metrics` is true.
+    pub fn set_request_metrics(&mut self, request_metrics: bool) {
+        self.ctx.request_metrics = request_metrics;
+    }
+
     pub async fn insert(&self, requests: InsertRequests) -> Result<u32> {
         let _timer = metrics::METRIC_GRPC_INSERT.start_timer();
         self.handle(Request::Inserts(requests)).await
@@ -242,9 +249,14 @@ impl Database {
         // FIXME(paomian): should be added some labels for metrics
         let _timer = metrics::METRIC_GRPC_DO_GET.start_timer();
         let request = self.to_rpc_request(request);
-        let request = Ticket {
+        let mut request = tonic::Request::new(Ticket {
             ticket: request.encode_to_vec().into(),
-        };
+        });
+        if self.ctx.request_metrics {
+            request
+                .metadata_mut()
+                .insert(FLIGHT_METRICS_HEADER, MetadataValue::from_static("true"));
+        }
 
         let mut client = self.client.make_flight_client()?;
 
@@ -295,16 +307,22 @@ impl Database {
                 );
                 Ok(Output::AffectedRows(rows))
             }
-            FlightMessage::Recordbatch(_) => IllegalFlightMessagesSnafu {
-                reason: "The first flight message cannot be a RecordBatch message",
+            FlightMessage::Recordbatch(_) | FlightMessage::Metrics(_) => {
+                IllegalFlightMessagesSnafu {
+                    reason: "The first flight message cannot be a RecordBatch or Metrics message",
+                }
+                .fail()
             }
-            .fail(),
             FlightMessage::Schema(schema) => {
                 let stream = Box::pin(stream!({
                     while let Some(flight_message) = flight_message_stream.next().await {
                         let flight_message = flight_message
                             .map_err(BoxedError::new)
                             .context(ExternalSnafu)?;
+                        // Metrics of the query are not exposed by the stream yet.
+                        if let FlightMessage::Metrics(_) = flight_message {
+                            continue;
+                        }
                         let FlightMessage::Recordbatch(record_batch) = flight_message else {
                             yield IllegalFlightMessagesSnafu {reason: "A Schema message must be succeeded exclusively by a set of RecordBatch messages"}
                                         .fail()
@@ -329,6 +347,8 @@ impl Database {
 #[derive(Default, Debug, Clone)]
 pub struct FlightContext {
     auth_header: Option<AuthHeader>,
+    /// Whether to ask the server to send metrics of queries.
+    request_metrics: bool,
 }
 
 #[cfg(test)]
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
 use api::v1::region::{QueryRequest, RegionRequest, RegionResponse};
 use api::v1::ResponseHeader;
 use arrow_flight::Ticket;
@@ -19,15 +23,20 @@ use async_stream::stream;
 use async_trait::async_trait;
 use common_error::ext::{BoxedError, ErrorExt};
 use common_error::status_code::StatusCode;
-use common_grpc::flight::{FlightDecoder, FlightMessage};
+use common_grpc::flight::{FlightDecoder, FlightMessage, FLIGHT_METRICS_HEADER};
 use common_meta::datanode_manager::{AffectedRows, Datanode};
 use common_meta::error::{self as meta_error, Result as MetaResult};
-use common_recordbatch::error::ExternalSnafu;
-use common_recordbatch::{RecordBatchStreamWrapper, SendableRecordBatchStream};
+use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
+use common_recordbatch::{
+    RecordBatch, RecordBatchMetrics, RecordBatchStream, SendableRecordBatchStream,
+};
 use common_telemetry::error;
+use datatypes::schema::SchemaRef;
+use futures_util::Stream;
 use prost::Message;
 use snafu::{location, Location, OptionExt, ResultExt};
 use tokio_stream::StreamExt;
+use tonic::metadata::MetadataValue;
 
 use crate::error::{
     self, ConvertFlightDataSnafu, IllegalDatabaseResponseSnafu, IllegalFlightMessagesSnafu,
@@ -74,10 +83,16 @@ impl RegionRequester {
     }
 
     pub async fn do_get_inner(&self, ticket: Ticket) -> Result<SendableRecordBatchStream> {
+        let mut request = tonic::Request::new(ticket);
+        // Metrics of region scans are reported to the frontend, e.g. for `EXPLAIN ANALYZE`.
+        request
+            .metadata_mut()
+            .insert(FLIGHT_METRICS_HEADER, MetadataValue::from_static("true"));
+
         let mut flight_client = self.client.make_flight_client()?;
         let response = flight_client
             .mut_inner()
-            .do_get(ticket)
+            .do_get(request)
             .await
             .map_err(|e| {
                 let tonic_code = e.code();
@@ -119,11 +134,17 @@ impl RegionRequester {
             .fail();
         };
 
+        let metrics = Arc::new(Mutex::new(None));
+        let stream_metrics = metrics.clone();
         let stream = Box::pin(stream!({
             while let Some(flight_message) = flight_message_stream.next().await {
                 let flight_message = flight_message
                     .map_err(BoxedError::new)
                     .context(ExternalSnafu)?;
+                if let FlightMessage::Metrics(metrics) = flight_message {
+                    *stream_metrics.lock().unwrap() = Some(metrics);
+                    continue;
+                }
                 let FlightMessage::Recordbatch(record_batch) = flight_message else {
                     yield IllegalFlightMessagesSnafu {
                             reason: "A Schema message must be succeeded exclusively by a set of RecordBatch messages"
@@ -136,12 +157,11 @@ impl RegionRequester {
                 yield Ok(record_batch);
             }
         }));
-        let record_batch_stream = RecordBatchStreamWrapper {
+        Ok(Box::pin(RegionQueryStream {
             schema,
             stream,
-            output_ordering: None,
-        };
-        Ok(Box::pin(record_batch_stream))
+            metrics,
+        }))
     }
 
     async fn handle_inner(&self, request: RegionRequest) -> Result<AffectedRows> {
@@ -184,6 +204,32 @@ impl RegionRequester {
     }
 }
 
+/// Stream of a region query that reports the metrics sent by the datanode after
+/// the last record batch.
+struct RegionQueryStream {
+    schema: SchemaRef,
+    stream: Pin<Box<dyn Stream<Item = RecordBatchResult<RecordBatch>> + Send>>,
+    metrics: Arc<Mutex<Option<RecordBatchMetrics>>>,
+}
+
+impl Stream for RegionQueryStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+impl RecordBatchStream for RegionQueryStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        self.metrics.lock().unwrap().clone()
+    }
+}
+
 pub fn check_response_header(header: Option<ResponseHeader>) -> Result<()> {
     let status = header
         .and_then(|header| header.status)
@@ -30,6 +30,7 @@ use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{FrontendInstance, Instance as FeInstance};
 use frontend::server::Services;
 use meta_client::MetaClientOptions;
+use query::query_engine::options::QueryOptions;
 use servers::tls::{TlsMode, TlsOption};
 use servers::Mode;
 use snafu::{OptionExt, ResultExt};
@@ -215,6 +216,9 @@ impl StartCommand {
         let plugins = plugins::setup_frontend_plugins(&mut opts)
             .await
             .context(StartFrontendSnafu)?;
+        let mut query_options = plugins.get::<QueryOptions>().unwrap_or_default();
+        query_options.max_plan_depth = opts.max_plan_depth;
+        plugins.insert(query_options);
 
         logging::info!("Frontend start command: {:#?}", self);
         logging::info!("Frontend options: {:#?}", opts);
@@ -252,6 +256,7 @@ impl StartCommand {
         .try_build()
         .await
         .context(StartFrontendSnafu)?;
+        instance.set_table_write_rate_limit(opts.table_write_rate_limit);
 
         let servers = Services::new(plugins)
             .build(opts.clone(), Arc::new(instance.clone()))
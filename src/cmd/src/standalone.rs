@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fs, path};
 
 use async_trait::async_trait;
@@ -37,7 +38,7 @@ use common_time::timezone::set_default_timezone;
 use datanode::config::{DatanodeOptions, ProcedureConfig, RegionEngineConfig, StorageConfig};
 use datanode::datanode::{Datanode, DatanodeBuilder};
 use file_engine::config::EngineConfig as FileEngineConfig;
-use frontend::frontend::FrontendOptions;
+use frontend::frontend::{FrontendOptions, DEFAULT_DRAIN_TIMEOUT};
 use frontend::instance::builder::FrontendBuilder;
 use frontend::instance::{FrontendInstance, Instance as FeInstance, StandaloneDatanodeManager};
 use frontend::server::Services;
@@ -45,6 +46,7 @@ use frontend::service_config::{
     GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, PostgresOptions, PromStoreOptions,
 };
 use mito2::config::MitoConfig;
+use query::query_engine::options::{QueryOptions, DEFAULT_MAX_PLAN_DEPTH};
 use serde::{Deserialize, Serialize};
 use servers::export_metrics::ExportMetricsOption;
 use servers::http::HttpOptions;
@@ -101,6 +103,10 @@ pub struct StandaloneOptions {
     pub mode: Mode,
     pub enable_telemetry: bool,
     pub default_timezone: Option<String>,
+    #[serde(with = "humantime_serde")]
+    pub drain_timeout: Duration,
+    pub max_plan_depth: usize,
+    pub table_write_rate_limit: u64,
     pub http: HttpOptions,
     pub grpc: GrpcOptions,
     pub mysql: MysqlOptions,
@@ -131,6 +137,9 @@ impl Default for StandaloneOptions {
             mode: Mode::Standalone,
             enable_telemetry: true,
             default_timezone: None,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            max_plan_depth: DEFAULT_MAX_PLAN_DEPTH,
+            table_write_rate_limit: 0,
             http: HttpOptions::default(),
             grpc: GrpcOptions::default(),
             mysql: MysqlOptions::default(),
@@ -158,6 +167,9 @@ impl StandaloneOptions {
         FrontendOptions {
             mode: self.mode,
             default_timezone: self.default_timezone,
+            drain_timeout: self.drain_timeout,
+            max_plan_depth: self.max_plan_depth,
+            table_write_rate_limit: self.table_write_rate_limit,
             http: self.http,
             grpc: self.grpc,
             mysql: self.mysql,
@@ -373,6 +385,9 @@ impl StartCommand {
         let fe_plugins = plugins::setup_frontend_plugins(&mut fe_opts) // mut ref is MUST, DO NOT change it
             .await
             .context(StartFrontendSnafu)?;
+        let mut query_options = fe_plugins.get::<QueryOptions>().unwrap_or_default();
+        query_options.max_plan_depth = fe_opts.max_plan_depth;
+        fe_plugins.insert(query_options);
 
         let dn_opts = opts.datanode.clone();
 
@@ -436,8 +451,10 @@ impl StartCommand {
             .try_build()
             .await
             .context(StartFrontendSnafu)?;
+        frontend.set_table_write_rate_limit(fe_opts.table_write_rate_limit);
 
         let servers = Services::new(fe_plugins)
+            .with_health_indicators(datanode.health_indicators())
             .build(opts.clone(), Arc::new(frontend.clone()))
             .await
             .context(StartFrontendSnafu)?;
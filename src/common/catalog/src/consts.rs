@@ -82,6 +82,8 @@ pub const INFORMATION_SCHEMA_GLOBAL_STATUS_TABLE_ID: u32 = 25;
 pub const INFORMATION_SCHEMA_SESSION_STATUS_TABLE_ID: u32 = 26;
 /// id for information_schema.RUNTIME_METRICS
 pub const INFORMATION_SCHEMA_RUNTIME_METRICS_TABLE_ID: u32 = 27;
+/// id for information_schema.INDEX_STATISTICS
+pub const INFORMATION_SCHEMA_INDEX_STATISTICS_TABLE_ID: u32 = 28;
 /// ----- End of information_schema tables -----
 
 pub const MITO_ENGINE: &str = "mito";
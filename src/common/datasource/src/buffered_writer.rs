@@ -106,6 +106,11 @@ impl<
         }
     }
 
+    /// Returns the size of encoded data, including the data still buffered in memory.
+    pub fn encoded_size(&self) -> u64 {
+        self.bytes_written + self.buffer.buffer.lock().unwrap().len() as u64
+    }
+
     pub async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
         let encoder = self
             .encoder
@@ -220,6 +220,13 @@ impl BufferedWriter {
         Ok(())
     }
 
+    /// Returns the size of the row groups encoded so far.
+    ///
+    /// The row group in progress is not included until it's flushed.
+    pub fn encoded_size(&self) -> u64 {
+        self.inner.encoded_size()
+    }
+
     /// Close parquet writer.
     ///
     /// Return file metadata and bytes written.
@@ -63,6 +63,8 @@ pub enum StatusCode {
     // If mutually exclusive operations are reached at the same time,
     // only one can be executed, another one will get region busy.
     RegionBusy = 4009,
+    /// The region is frozen and rejects writes, flushes and compactions.
+    RegionFrozen = 4010,
     // ====== End of catalog related status code =======
 
     // ====== Begin of storage related status code =====
@@ -76,6 +78,8 @@ pub enum StatusCode {
 
     /// Rate limit exceeded
     RateLimited = 6001,
+    /// The instance is in read-only mode and rejects DDL and DML.
+    InstanceReadOnly = 6002,
     // ====== End of server related status code =======
 
     // ====== Begin of auth related status code =====
@@ -125,10 +129,12 @@ impl StatusCode {
             | StatusCode::RegionNotFound
             | StatusCode::RegionAlreadyExists
             | StatusCode::RegionReadonly
+            | StatusCode::RegionFrozen
             | StatusCode::TableColumnNotFound
             | StatusCode::TableColumnExists
             | StatusCode::DatabaseNotFound
             | StatusCode::RateLimited
+            | StatusCode::InstanceReadOnly
             | StatusCode::UserNotFound
             | StatusCode::UnsupportedPasswordType
             | StatusCode::UserPasswordMismatch
@@ -162,10 +168,12 @@ impl StatusCode {
             | StatusCode::RegionBusy
             | StatusCode::RegionAlreadyExists
             | StatusCode::RegionReadonly
+            | StatusCode::RegionFrozen
             | StatusCode::TableColumnNotFound
             | StatusCode::TableColumnExists
             | StatusCode::DatabaseNotFound
             | StatusCode::RateLimited
+            | StatusCode::InstanceReadOnly
             | StatusCode::UserNotFound
             | StatusCode::UnsupportedPasswordType
             | StatusCode::UserPasswordMismatch
@@ -197,6 +205,7 @@ impl StatusCode {
                 Some(StatusCode::RegionAlreadyExists)
             }
             v if v == StatusCode::RegionReadonly as u32 => Some(StatusCode::RegionReadonly),
+            v if v == StatusCode::RegionFrozen as u32 => Some(StatusCode::RegionFrozen),
             v if v == StatusCode::TableColumnNotFound as u32 => {
                 Some(StatusCode::TableColumnNotFound)
             }
@@ -207,6 +216,7 @@ impl StatusCode {
                 Some(StatusCode::RuntimeResourcesExhausted)
             }
             v if v == StatusCode::RateLimited as u32 => Some(StatusCode::RateLimited),
+            v if v == StatusCode::InstanceReadOnly as u32 => Some(StatusCode::InstanceReadOnly),
             v if v == StatusCode::UserNotFound as u32 => Some(StatusCode::UserNotFound),
             v if v == StatusCode::UnsupportedPasswordType as u32 => {
                 Some(StatusCode::UnsupportedPasswordType)
@@ -49,3 +49,95 @@ pub trait Function: fmt::Display + Sync + Send {
 }
 
 pub type FunctionRef = Arc<dyn Function>;
+
+/// The implementation of a scalar function, evaluates the function on the argument vectors.
+pub type ScalarFunctionEval =
+    Arc<dyn Fn(FunctionContext, &[VectorRef]) -> Result<VectorRef> + Send + Sync>;
+
+/// A function creates the implementation of a scalar function.
+type ScalarFunctionCreator = Arc<dyn Fn() -> ScalarFunctionEval + Send + Sync>;
+
+/// `ScalarFunctionMeta` dynamically creates a scalar [Function] from its name, signature,
+/// return type and the implementation.
+#[derive(Clone)]
+pub struct ScalarFunctionMeta {
+    name: String,
+    signature: Signature,
+    return_type: ConcreteDataType,
+    creator: ScalarFunctionCreator,
+}
+
+pub type ScalarFunctionMetaRef = Arc<ScalarFunctionMeta>;
+
+impl ScalarFunctionMeta {
+    /// Creates the meta of a scalar function accepting arguments of `signature` and
+    /// returning values of `return_type`, the implementation receives one vector for
+    /// each argument and returns a vector with one value for each row.
+    pub fn new(
+        name: &str,
+        signature: Signature,
+        return_type: ConcreteDataType,
+        creator: ScalarFunctionCreator,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            signature,
+            return_type,
+            creator,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    pub fn return_type(&self) -> &ConcreteDataType {
+        &self.return_type
+    }
+
+    /// Creates the [Function] described by the meta.
+    pub fn create(&self) -> FunctionRef {
+        Arc::new(MetaFunction {
+            name: self.name.clone(),
+            signature: self.signature.clone(),
+            return_type: self.return_type.clone(),
+            eval: (self.creator)(),
+        })
+    }
+}
+
+/// [Function] created from a [ScalarFunctionMeta].
+struct MetaFunction {
+    name: String,
+    signature: Signature,
+    return_type: ConcreteDataType,
+    eval: ScalarFunctionEval,
+}
+
+impl fmt::Display for MetaFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name.to_ascii_uppercase())
+    }
+}
+
+impl Function for MetaFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+
+    fn eval(&self, func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        (self.eval)(func_ctx, columns)
+    }
+}
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod approx_percentile;
 mod argmax;
 mod argmin;
 mod diff;
+mod distinct;
 mod mean;
 mod percentile;
 mod polyval;
@@ -23,10 +25,16 @@ mod scipy_stats_norm_pdf;
 
 use std::sync::Arc;
 
+pub use approx_percentile::{ApproxPercentileAccumulatorCreator, P99AccumulatorCreator};
 pub use argmax::ArgmaxAccumulatorCreator;
 pub use argmin::ArgminAccumulatorCreator;
+use common_query::error::{AggregateOverflowSnafu, Result};
 use common_query::logical_plan::AggregateFunctionCreatorRef;
+use datatypes::arrow::datatypes::ArrowNativeTypeOp;
+use datatypes::types::WrapperType;
+use datatypes::value::Value;
 pub use diff::DiffAccumulatorCreator;
+pub use distinct::DistinctValues;
 pub use mean::MeanAccumulatorCreator;
 pub use percentile::PercentileAccumulatorCreator;
 pub use polyval::PolyvalAccumulatorCreator;
@@ -35,6 +43,44 @@ pub use scipy_stats_norm_pdf::ScipyStatsNormPdfAccumulatorCreator;
 
 use crate::function_registry::FunctionRegistry;
 
+/// Adds `rhs` to `lhs` for sum accumulators, returns an error instead of wrapping around
+/// if the sum of integers overflows.
+pub fn checked_add<T>(lhs: T, rhs: T) -> Result<T>
+where
+    T: WrapperType,
+    T::Native: ArrowNativeTypeOp,
+{
+    match lhs.into_native().add_checked(rhs.into_native()) {
+        Ok(sum) => Ok(T::from_native(sum)),
+        Err(_) => overflow(lhs, "+", rhs),
+    }
+}
+
+/// Multiplies `lhs` by `rhs` for accumulators, returns an error instead of wrapping around
+/// if the product of integers overflows.
+pub fn checked_mul<T>(lhs: T, rhs: T) -> Result<T>
+where
+    T: WrapperType,
+    T::Native: ArrowNativeTypeOp,
+{
+    match lhs.into_native().mul_checked(rhs.into_native()) {
+        Ok(product) => Ok(T::from_native(product)),
+        Err(_) => overflow(lhs, "*", rhs),
+    }
+}
+
+/// Returns the error of the overflowed `lhs op rhs`.
+fn overflow<T: WrapperType, R>(lhs: T, op: &str, rhs: T) -> Result<R> {
+    AggregateOverflowSnafu {
+        expr: format!(
+            "{} {op} {}",
+            Into::<Value>::into(lhs),
+            Into::<Value>::into(rhs)
+        ),
+    }
+    .fail()
+}
+
 /// A function creates `AggregateFunctionCreator`.
 /// "Aggregator" *is* AggregatorFunction. Since the later one is long, we named an short alias for it.
 /// The two names might be used interchangeably.
@@ -51,6 +97,8 @@ pub struct AggregateFunctionMeta {
 pub type AggregateFunctionMetaRef = Arc<AggregateFunctionMeta>;
 
 impl AggregateFunctionMeta {
+    /// Creates the meta of an aggregate function that takes `args_count` arguments, the
+    /// accumulators of the function receive one vector for each argument.
     pub fn new(name: &str, args_count: u8, creator: AggregatorCreatorFunction) -> Self {
         Self {
             name: name.to_string(),
@@ -92,6 +140,8 @@ impl AggregateFunctions {
         register_aggr_func!("argmax", 1, ArgmaxAccumulatorCreator);
         register_aggr_func!("argmin", 1, ArgminAccumulatorCreator);
         register_aggr_func!("percentile", 2, PercentileAccumulatorCreator);
+        register_aggr_func!("approx_percentile", 2, ApproxPercentileAccumulatorCreator);
+        register_aggr_func!("p99", 1, P99AccumulatorCreator);
         register_aggr_func!("scipystatsnormcdf", 2, ScipyStatsNormCdfAccumulatorCreator);
         register_aggr_func!("scipystatsnormpdf", 2, ScipyStatsNormPdfAccumulatorCreator);
     }
@@ -0,0 +1,614 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    self, BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu,
+    InvalidFuncArgsSnafu, InvalidInputColSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::types::WrapperType;
+use datatypes::value::OrderedFloat;
+use datatypes::vectors::{BinaryVector, ConstantVector, Float64Vector, Helper};
+use datatypes::with_match_primitive_type_id;
+use num_traits::AsPrimitive;
+use snafu::{ensure, OptionExt, ResultExt};
+
+/// Max number of centroids in a digest, larger digests are more accurate.
+const DEFAULT_MAX_CENTROIDS: usize = 100;
+/// Len of the header of an encoded digest: max size, min and max.
+const DIGEST_HEADER_LEN: usize = 24;
+/// Len of an encoded centroid: mean and weight.
+const CENTROID_LEN: usize = 16;
+
+/// A cluster of values, represented by their mean and number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A t-digest that estimates quantiles from centroids of sorted values.
+///
+/// Centroids near both ends have smaller weights, so extreme quantiles like p99 are
+/// more accurate. Digests can be merged, which makes the estimation correct across
+/// partitions. See <https://github.com/tdunning/t-digest>.
+#[derive(Debug, Clone, PartialEq)]
+struct TDigest {
+    /// Centroids ordered by their means.
+    centroids: Vec<Centroid>,
+    max_size: usize,
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    fn new(max_size: usize) -> TDigest {
+        TDigest {
+            centroids: Vec::new(),
+            max_size,
+            count: 0.0,
+            min: f64::NAN,
+            max: f64::NAN,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.centroids.is_empty()
+    }
+
+    /// Adds `values` to the digest.
+    fn add_values(&mut self, values: impl Iterator<Item = f64>) {
+        let centroids = values
+            .filter(|v| !v.is_nan())
+            .map(|mean| Centroid { mean, weight: 1.0 })
+            .collect();
+        self.merge_centroids(centroids);
+    }
+
+    /// Merges another digest into this digest.
+    fn merge(&mut self, other: TDigest) {
+        // Means of centroids at both ends may not be the min and max.
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.merge_centroids(other.centroids);
+    }
+
+    fn merge_centroids(&mut self, mut centroids: Vec<Centroid>) {
+        if centroids.is_empty() {
+            return;
+        }
+
+        centroids.extend_from_slice(&self.centroids);
+        centroids.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        self.count = centroids.iter().map(|c| c.weight).sum();
+        self.min = self.min.min(centroids[0].mean);
+        self.max = self.max.max(centroids[centroids.len() - 1].mean);
+        self.centroids = compress(centroids, self.count, self.max_size);
+    }
+
+    /// Estimates the value at quantile `q` in `[0, 1]`, returns `None` if the digest is
+    /// empty.
+    fn estimate_quantile(&self, q: f64) -> Option<f64> {
+        let centroids = &self.centroids;
+        if centroids.is_empty() {
+            return None;
+        }
+        if q <= 0.0 {
+            return Some(self.min);
+        }
+        if q >= 1.0 {
+            return Some(self.max);
+        }
+
+        // Finds the centroid containing the rank and the total weight before it.
+        let rank = q * self.count;
+        let mut pos = centroids.len() - 1;
+        let mut weight_before = self.count - centroids[pos].weight;
+        let mut weight_so_far = 0.0;
+        for (i, c) in centroids.iter().enumerate() {
+            if rank < weight_so_far + c.weight {
+                pos = i;
+                weight_before = weight_so_far;
+                break;
+            }
+            weight_so_far += c.weight;
+        }
+
+        // Interpolates inside the centroid by the distance to its neighbors.
+        let (mut min, mut max) = (self.min, self.max);
+        let mut delta = 0.0;
+        if centroids.len() > 1 {
+            if pos == 0 {
+                delta = centroids[1].mean - centroids[0].mean;
+                max = centroids[1].mean;
+            } else if pos == centroids.len() - 1 {
+                delta = centroids[pos].mean - centroids[pos - 1].mean;
+                min = centroids[pos - 1].mean;
+            } else {
+                delta = (centroids[pos + 1].mean - centroids[pos - 1].mean) / 2.0;
+                min = centroids[pos - 1].mean;
+                max = centroids[pos + 1].mean;
+            }
+        }
+        let centroid = &centroids[pos];
+        let value = centroid.mean + ((rank - weight_before) / centroid.weight - 0.5) * delta;
+        Some(value.clamp(min, max))
+    }
+
+    /// Encodes the digest into bytes, in the layout of the max size, min and max,
+    /// followed by the mean and weight of each centroid. All numbers are little endian.
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(DIGEST_HEADER_LEN + self.centroids.len() * CENTROID_LEN);
+        buf.extend_from_slice(&(self.max_size as u64).to_le_bytes());
+        buf.extend_from_slice(&self.min.to_le_bytes());
+        buf.extend_from_slice(&self.max.to_le_bytes());
+        for c in &self.centroids {
+            buf.extend_from_slice(&c.mean.to_le_bytes());
+            buf.extend_from_slice(&c.weight.to_le_bytes());
+        }
+        buf
+    }
+
+    /// Decodes the digest from bytes encoded by [TDigest::encode].
+    fn decode(buf: &[u8]) -> Result<TDigest> {
+        ensure!(
+            buf.len() >= DIGEST_HEADER_LEN && (buf.len() - DIGEST_HEADER_LEN) % CENTROID_LEN == 0,
+            BadAccumulatorImplSnafu {
+                err_msg: format!("invalid t-digest state of {} bytes", buf.len()),
+            }
+        );
+        let max_size = u64::from_le_bytes(buf[..8].try_into().unwrap()) as usize;
+        let centroids: Vec<_> = buf[DIGEST_HEADER_LEN..]
+            .chunks_exact(CENTROID_LEN)
+            .map(|chunk| Centroid {
+                mean: read_f64(&chunk[..8]),
+                weight: read_f64(&chunk[8..]),
+            })
+            .collect();
+        Ok(TDigest {
+            count: centroids.iter().map(|c| c.weight).sum(),
+            centroids,
+            max_size,
+            min: read_f64(&buf[8..16]),
+            max: read_f64(&buf[16..24]),
+        })
+    }
+}
+
+/// Reads a little endian f64 from 8 bytes.
+fn read_f64(buf: &[u8]) -> f64 {
+    f64::from_le_bytes(buf.try_into().unwrap())
+}
+
+/// Maps the scale `k` to the quantile limit of the `k`-th centroid in a digest of at most
+/// `max_size` centroids.
+fn k_to_q(k: f64, max_size: f64) -> f64 {
+    let k_div_d = k / max_size;
+    if k_div_d >= 0.5 {
+        let base = 1.0 - k_div_d;
+        1.0 - 2.0 * base * base
+    } else {
+        2.0 * k_div_d * k_div_d
+    }
+}
+
+/// Merges adjacent `sorted` centroids whose total weight is under the limit of the scale.
+fn compress(sorted: Vec<Centroid>, count: f64, max_size: usize) -> Vec<Centroid> {
+    let mut compressed: Vec<Centroid> = Vec::with_capacity(max_size);
+    let mut k_limit = 1.0;
+    let mut q_limit_times_count = k_to_q(k_limit, max_size as f64) * count;
+    let mut weight_so_far = 0.0;
+    for c in sorted {
+        weight_so_far += c.weight;
+        match compressed.last_mut() {
+            Some(last) if weight_so_far <= q_limit_times_count => {
+                let weight = last.weight + c.weight;
+                last.mean += (c.mean - last.mean) * c.weight / weight;
+                last.weight = weight;
+            }
+            _ => {
+                if !compressed.is_empty() {
+                    k_limit += 1.0;
+                    q_limit_times_count = k_to_q(k_limit, max_size as f64) * count;
+                }
+                compressed.push(c);
+            }
+        }
+    }
+    compressed
+}
+
+/// Approximate percentile of values, estimated by a [TDigest].
+///
+/// Unlike [Percentile](super::percentile::Percentile), it doesn't keep all values in the
+/// state, so it works on large data sets and merges across partitions.
+#[derive(Debug)]
+pub struct ApproxPercentile<T> {
+    digest: TDigest,
+    /// The percentile to estimate, in `[0, 100]`.
+    p: Option<f64>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ApproxPercentile<T> {
+    fn new(p: Option<f64>) -> Self {
+        Self {
+            digest: TDigest::new(DEFAULT_MAX_CENTROIDS),
+            p,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn set_p(&mut self, p: f64) -> Result<()> {
+        ensure!(
+            (0.0..=100.0).contains(&p),
+            InvalidFuncArgsSnafu {
+                err_msg: format!("percentile must be in [0, 100], given: {p}"),
+            }
+        );
+        if let Some(current) = self.p {
+            ensure!(current == p, InvalidInputColSnafu);
+        } else {
+            self.p = Some(p);
+        }
+        Ok(())
+    }
+}
+
+impl<T> Accumulator for ApproxPercentile<T>
+where
+    T: WrapperType,
+    T::Native: AsPrimitive<f64>,
+{
+    fn state(&self) -> Result<Vec<Value>> {
+        Ok(vec![self.digest.encode().into(), self.p.into()])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        // The percentile is the second argument unless it's fixed by the function.
+        ensure!(
+            values.len() == 1 || values.len() == 2,
+            InvalidInputStateSnafu
+        );
+        if values[0].len() == 0 {
+            return Ok(());
+        }
+
+        if let Some(p) = values.get(1) {
+            ensure!(values[0].len() == p.len(), InvalidInputStateSnafu);
+            let p = Helper::check_get_scalar::<f64>(p).context(error::InvalidInputTypeSnafu {
+                err_msg: "expecting \"APPROX_PERCENTILE\" function's second argument to be float64",
+            })?;
+            let first = p.get(0);
+            ensure!(!first.is_null(), InvalidInputColSnafu);
+            for i in 1..p.len() {
+                ensure!(first == p.get(i), InvalidInputColSnafu);
+            }
+            let Value::Float64(OrderedFloat(first)) = first else {
+                // unreachable because we have checked `first` is not null and is f64 above
+                unreachable!()
+            };
+            self.set_p(first)?;
+        }
+
+        let column = &values[0];
+        let mut len = 1;
+        let column: &<T as Scalar>::VectorType = if column.is_const() {
+            len = column.len();
+            let column: &ConstantVector = unsafe { Helper::static_cast(column) };
+            unsafe { Helper::static_cast(column.inner()) }
+        } else {
+            unsafe { Helper::static_cast(column) }
+        };
+        self.digest.add_values(
+            (0..len).flat_map(|_| column.iter_data().flatten().map(|v| v.into_native().as_())),
+        );
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            states.len() == 2,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 2 states in `merge_batch`",
+            }
+        );
+
+        let digests = &states[0];
+        let digests = digests
+            .as_any()
+            .downcast_ref::<BinaryVector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect BinaryVector, got vector type {}",
+                    digests.vector_type_name()
+                ),
+            })?;
+        let ps = &states[1];
+        let ps = ps
+            .as_any()
+            .downcast_ref::<Float64Vector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect Float64Vector, got vector type {}",
+                    ps.vector_type_name()
+                ),
+            })?;
+
+        for (digest, p) in digests.iter_data().zip(ps.iter_data()) {
+            // Accumulators without input don't know the percentile.
+            if let Some(p) = p {
+                self.set_p(p)?;
+            }
+            if let Some(digest) = digest {
+                self.digest.merge(TDigest::decode(digest)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        let Some(p) = self.p else {
+            return Ok(Value::Null);
+        };
+        Ok(self
+            .digest
+            .estimate_quantile(p / 100.0)
+            .map(Value::from)
+            .unwrap_or(Value::Null))
+    }
+}
+
+fn create_accumulator(
+    function: &str,
+    input_type: &ConcreteDataType,
+    p: Option<f64>,
+) -> Result<Box<dyn Accumulator>> {
+    with_match_primitive_type_id!(
+        input_type.logical_type_id(),
+        |$S| {
+            Ok(Box::new(ApproxPercentile::<<$S as LogicalPrimitiveType>::Wrapper>::new(p)))
+        },
+        {
+            let err_msg = format!(
+                "\"{}\" aggregate function not support data type {:?}",
+                function,
+                input_type.logical_type_id(),
+            );
+            CreateAccumulatorSnafu { err_msg }.fail()?
+        }
+    )
+}
+
+/// Creates accumulators of `approx_percentile(column, p)`, which estimates the `p`-th
+/// percentile of the column.
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct ApproxPercentileAccumulatorCreator {}
+
+impl AggregateFunctionCreator for ApproxPercentileAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            create_accumulator("APPROX_PERCENTILE", &types[0], None)
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+        Ok(vec![
+            ConcreteDataType::binary_datatype(),
+            ConcreteDataType::float64_datatype(),
+        ])
+    }
+}
+
+/// Creates accumulators of `p99(column)`, which estimates the 99th percentile of the column.
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct P99AccumulatorCreator {}
+
+impl AggregateFunctionCreator for P99AccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            create_accumulator("P99", &types[0], Some(99.0))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![
+            ConcreteDataType::binary_datatype(),
+            ConcreteDataType::float64_datatype(),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::{Float64Vector, Int32Vector, UInt32Vector};
+
+    use super::*;
+
+    fn percentile_vector(p: f64, len: usize) -> VectorRef {
+        Arc::new(Float64Vector::from_vec(vec![p; len]))
+    }
+
+    fn evaluate_f64<T>(accumulator: &ApproxPercentile<T>) -> f64
+    where
+        T: WrapperType,
+        T::Native: AsPrimitive<f64>,
+    {
+        let Value::Float64(OrderedFloat(v)) = accumulator.evaluate().unwrap() else {
+            unreachable!()
+        };
+        v
+    }
+
+    #[test]
+    fn test_digest_estimate_quantile() {
+        let mut digest = TDigest::new(DEFAULT_MAX_CENTROIDS);
+        assert_eq!(None, digest.estimate_quantile(0.5));
+
+        // Each value is a centroid in a small digest.
+        digest.add_values((1..=10).map(|v| v as f64));
+        assert_eq!(10, digest.centroids.len());
+        assert_eq!(Some(1.0), digest.estimate_quantile(0.0));
+        assert_eq!(Some(5.5), digest.estimate_quantile(0.5));
+        assert_eq!(Some(10.0), digest.estimate_quantile(0.99));
+        assert_eq!(Some(10.0), digest.estimate_quantile(1.0));
+
+        let mut digest = TDigest::new(DEFAULT_MAX_CENTROIDS);
+        digest.add_values((1..=1000).map(|v| v as f64));
+        assert!(digest.centroids.len() <= DEFAULT_MAX_CENTROIDS);
+        assert_eq!(1000.0, digest.count);
+        let p50 = digest.estimate_quantile(0.5).unwrap();
+        assert!((p50 - 500.5).abs() < 1.0, "p50: {p50}");
+        let p99 = digest.estimate_quantile(0.99).unwrap();
+        assert!((p99 - 990.01).abs() < 1.0, "p99: {p99}");
+    }
+
+    #[test]
+    fn test_digest_encode_decode() {
+        let mut digest = TDigest::new(DEFAULT_MAX_CENTROIDS);
+        let decoded = TDigest::decode(&digest.encode()).unwrap();
+        assert!(decoded.is_empty());
+
+        digest.add_values((1..=1000).map(|v| v as f64));
+        let decoded = TDigest::decode(&digest.encode()).unwrap();
+        assert_eq!(digest, decoded);
+
+        assert!(TDigest::decode(&[0; 10]).is_err());
+    }
+
+    #[test]
+    fn test_update_batch() {
+        // test update empty batch, expect not updating anything
+        let mut percentile = ApproxPercentile::<i32>::new(None);
+        percentile.update_batch(&[]).unwrap();
+        assert!(percentile.digest.is_empty());
+        assert_eq!(Value::Null, percentile.evaluate().unwrap());
+
+        // test update null values
+        let mut percentile = ApproxPercentile::<i32>::new(None);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from(vec![Option::<i32>::None])),
+            percentile_vector(50.0, 1),
+        ];
+        percentile.update_batch(&v).unwrap();
+        assert_eq!(Value::Null, percentile.evaluate().unwrap());
+
+        // test update integers
+        let mut percentile = ApproxPercentile::<u32>::new(None);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_vec((1..=10).collect())),
+            percentile_vector(50.0, 10),
+        ];
+        percentile.update_batch(&v).unwrap();
+        assert_eq!(5.5, evaluate_f64(&percentile));
+
+        // test update floats with null values
+        let mut percentile = ApproxPercentile::<f64>::new(Some(99.0));
+        let v: Vec<VectorRef> = vec![Arc::new(Float64Vector::from(vec![
+            Some(-1.5),
+            None,
+            Some(2.5),
+            Some(0.5),
+        ]))];
+        percentile.update_batch(&v).unwrap();
+        assert_eq!(2.5, evaluate_f64(&percentile));
+
+        // test update with constant vector
+        let mut percentile = ApproxPercentile::<i32>::new(None);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(ConstantVector::new(
+                Arc::new(Int32Vector::from_vec(vec![4])),
+                2,
+            )),
+            percentile_vector(50.0, 2),
+        ];
+        percentile.update_batch(&v).unwrap();
+        assert_eq!(4.0, evaluate_f64(&percentile));
+
+        // test invalid percentile
+        let mut percentile = ApproxPercentile::<i32>::new(None);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from_vec(vec![1])),
+            percentile_vector(101.0, 1),
+        ];
+        assert!(percentile.update_batch(&v).is_err());
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        // Estimates the percentile across two partitions.
+        let mut states = Vec::new();
+        for range in [1..=500, 501..=1000] {
+            let mut percentile = ApproxPercentile::<i32>::new(None);
+            let v: Vec<VectorRef> = vec![
+                Arc::new(Int32Vector::from_vec(range.collect())),
+                percentile_vector(99.0, 500),
+            ];
+            percentile.update_batch(&v).unwrap();
+            states.push(percentile.state().unwrap());
+        }
+        let digests = states
+            .iter()
+            .map(|state| match &state[0] {
+                Value::Binary(v) => Some(v.to_vec()),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        let states: Vec<VectorRef> = vec![
+            Arc::new(BinaryVector::from(digests)),
+            percentile_vector(99.0, 2),
+        ];
+
+        let mut percentile = ApproxPercentile::<i32>::new(None);
+        percentile.merge_batch(&states).unwrap();
+        assert_eq!(1.0, percentile.digest.min);
+        assert_eq!(1000.0, percentile.digest.max);
+        let p99 = evaluate_f64(&percentile);
+        assert!((p99 - 990.01).abs() < 1.0, "p99: {p99}");
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use common_query::error::{DowncastVectorSnafu, FromScalarValueSnafu, Result};
+use datatypes::prelude::*;
+use datatypes::value::ListValue;
+use datatypes::vectors::ListVector;
+use snafu::{OptionExt, ResultExt};
+
+/// Distinct values of an aggregate function's argument, can be embedded in an
+/// [Accumulator](common_query::logical_plan::Accumulator) to implement `DISTINCT` aggregates.
+///
+/// Nulls are never collected, so they are not counted as distinct values.
+#[derive(Debug)]
+pub struct DistinctValues {
+    values: HashSet<Value>,
+    data_type: ConcreteDataType,
+}
+
+impl DistinctValues {
+    /// Creates an empty set of values of type `data_type`.
+    pub fn new(data_type: ConcreteDataType) -> Self {
+        Self {
+            values: HashSet::new(),
+            data_type,
+        }
+    }
+
+    /// Collects the non-null values in `column`.
+    pub fn update_batch(&mut self, column: &VectorRef) {
+        for i in 0..column.len() {
+            let value = column.get(i);
+            if !value.is_null() {
+                let _ = self.values.insert(value);
+            }
+        }
+    }
+
+    /// Returns the state of the set as a list value, whose vectors can be passed to
+    /// [merge_batch](Self::merge_batch).
+    pub fn state(&self) -> Value {
+        let values = self.values.iter().cloned().collect::<Vec<_>>();
+        Value::List(ListValue::new(
+            Some(Box::new(values)),
+            self.data_type.clone(),
+        ))
+    }
+
+    /// Merges the states of other sets, `states` must be a [ListVector].
+    pub fn merge_batch(&mut self, states: &VectorRef) -> Result<()> {
+        let states = states
+            .as_any()
+            .downcast_ref::<ListVector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect ListVector, got vector type {}",
+                    states.vector_type_name()
+                ),
+            })?;
+        for state in states.values_iter() {
+            if let Some(state) = state.context(FromScalarValueSnafu)? {
+                self.update_batch(&state);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.values.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datatypes::value::ListValueRef;
+    use datatypes::vectors::{ConstantVector, Int32Vector, ListVectorBuilder};
+
+    use super::*;
+
+    fn new_states(states: &[&DistinctValues]) -> VectorRef {
+        let mut builder =
+            ListVectorBuilder::with_type_capacity(ConcreteDataType::int32_datatype(), states.len());
+        for state in states {
+            let Value::List(state) = state.state() else {
+                unreachable!()
+            };
+            builder.push(Some(ListValueRef::Ref { val: &state }));
+        }
+        builder.to_vector()
+    }
+
+    #[test]
+    fn test_distinct_values() {
+        let mut distinct = DistinctValues::new(ConcreteDataType::int32_datatype());
+        assert!(distinct.is_empty());
+
+        let column: VectorRef = Arc::new(Int32Vector::from(vec![
+            Some(1),
+            None,
+            Some(2),
+            Some(1),
+            None,
+        ]));
+        distinct.update_batch(&column);
+        assert_eq!(2, distinct.len());
+
+        // Values already collected in previous batches are not counted again.
+        let column: VectorRef = Arc::new(Int32Vector::from(vec![Some(2), Some(3), None]));
+        distinct.update_batch(&column);
+        assert_eq!(3, distinct.len());
+
+        let column: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(Int32Vector::from(vec![Option::<i32>::None])),
+            10,
+        ));
+        distinct.update_batch(&column);
+        assert_eq!(3, distinct.len());
+
+        let mut values = distinct.values().cloned().collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(
+            vec![Value::from(1i32), Value::from(2i32), Value::from(3i32)],
+            values
+        );
+    }
+
+    #[test]
+    fn test_merge_distinct_values() {
+        let mut left = DistinctValues::new(ConcreteDataType::int32_datatype());
+        left.update_batch(&(Arc::new(Int32Vector::from(vec![Some(1), None, Some(2)])) as _));
+        let mut right = DistinctValues::new(ConcreteDataType::int32_datatype());
+        right.update_batch(&(Arc::new(Int32Vector::from(vec![None, Some(2), Some(3)])) as _));
+        let empty = DistinctValues::new(ConcreteDataType::int32_datatype());
+
+        let mut merged = DistinctValues::new(ConcreteDataType::int32_datatype());
+        merged
+            .merge_batch(&new_states(&[&left, &right, &empty]))
+            .unwrap();
+        assert_eq!(3, merged.len());
+
+        let states: VectorRef = Arc::new(Int32Vector::from_slice([1]));
+        assert!(merged.merge_batch(&states).is_err());
+    }
+}
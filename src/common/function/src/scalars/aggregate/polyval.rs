@@ -17,11 +17,12 @@ use std::sync::Arc;
 
 use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
 use common_query::error::{
-    self, BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu,
-    FromScalarValueSnafu, InvalidInputColSnafu, Result,
+    self, AggregateOverflowSnafu, BadAccumulatorImplSnafu, CreateAccumulatorSnafu,
+    DowncastVectorSnafu, FromScalarValueSnafu, InvalidInputColSnafu, Result,
 };
 use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
 use common_query::prelude::*;
+use datatypes::arrow::datatypes::ArrowNativeTypeOp;
 use datatypes::prelude::*;
 use datatypes::types::{LogicalPrimitiveType, WrapperType};
 use datatypes::value::ListValue;
@@ -30,6 +31,8 @@ use datatypes::with_match_primitive_type_id;
 use num_traits::AsPrimitive;
 use snafu::{ensure, OptionExt, ResultExt};
 
+use crate::scalars::aggregate::{checked_add, checked_mul};
+
 // https://numpy.org/doc/stable/reference/generated/numpy.polyval.html
 #[derive(Debug, Default)]
 pub struct Polyval<T, PolyT>
@@ -61,8 +64,8 @@ impl<T, PolyT> Accumulator for Polyval<T, PolyT>
 where
     T: WrapperType,
     T::Native: AsPrimitive<PolyT::Native>,
-    PolyT: WrapperType + std::iter::Sum<<PolyT as WrapperType>::Native>,
-    PolyT::Native: std::ops::Mul<Output = PolyT::Native> + std::iter::Sum<PolyT::Native>,
+    PolyT: WrapperType,
+    PolyT::Native: std::ops::Mul<Output = PolyT::Native> + ArrowNativeTypeOp,
     i64: AsPrimitive<<PolyT as WrapperType>::Native>,
 {
     fn state(&self) -> Result<Vec<Value>> {
@@ -198,12 +201,23 @@ where
             return Ok(Value::Null);
         };
         let len = self.values.len();
-        let polyval: PolyT = self
-            .values
-            .iter()
-            .enumerate()
-            .map(|(i, &value)| value.into_native().as_() * x.pow((len - 1 - i) as u32).as_())
-            .sum();
+        let polyval = self.values.iter().enumerate().try_fold(
+            PolyT::from_native(PolyT::Native::ZERO),
+            |polyval, (i, &value)| {
+                let exp = len - 1 - i;
+                let power = u32::try_from(exp)
+                    .ok()
+                    .and_then(|exp| x.checked_pow(exp))
+                    .with_context(|| AggregateOverflowSnafu {
+                        expr: format!("{x} ^ {exp}"),
+                    })?;
+                let term = checked_mul(
+                    PolyT::from_native(value.into_native().as_()),
+                    PolyT::from_native(power.as_()),
+                )?;
+                checked_add(polyval, term)
+            },
+        )?;
         Ok(polyval.into())
     }
 }
@@ -328,4 +342,47 @@ mod test {
         polyval.update_batch(&v).unwrap();
         assert_eq!(Value::Int64(24), polyval.evaluate().unwrap());
     }
+
+    #[test]
+    fn test_evaluate_overflow() {
+        let mut polyval = Polyval::<i64, i64>::default();
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int64Vector::from(vec![Some(i64::MAX), Some(1)])),
+            Arc::new(Int64Vector::from(vec![Some(1_i64), Some(1_i64)])),
+        ];
+        polyval.update_batch(&v).unwrap();
+        let err = polyval.evaluate().unwrap_err();
+        assert!(
+            matches!(err, error::Error::AggregateOverflow { .. }),
+            "unexpected error: {err:?}"
+        );
+
+        // The term overflows.
+        let mut polyval = Polyval::<i64, i64>::default();
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int64Vector::from(vec![Some(i64::MAX / 2), Some(0)])),
+            Arc::new(Int64Vector::from(vec![Some(3_i64), Some(3_i64)])),
+        ];
+        polyval.update_batch(&v).unwrap();
+        let err = polyval.evaluate().unwrap_err();
+        assert!(
+            matches!(err, error::Error::AggregateOverflow { .. }),
+            "unexpected error: {err:?}"
+        );
+
+        // The power of x overflows.
+        let mut polyval = Polyval::<i32, i64>::default();
+        let mut values = vec![Some(0); 64];
+        values[0] = Some(1);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from(values)),
+            Arc::new(Int64Vector::from(vec![Some(2_i64); 64])),
+        ];
+        polyval.update_batch(&v).unwrap();
+        let err = polyval.evaluate().unwrap_err();
+        assert!(
+            matches!(err, error::Error::AggregateOverflow { .. }),
+            "unexpected error: {err:?}"
+        );
+    }
 }
@@ -19,7 +19,7 @@ use api::v1::{AffectedRows, FlightMetadata};
 use arrow_flight::utils::flight_data_to_arrow_batch;
 use arrow_flight::{FlightData, SchemaAsIpc};
 use common_base::bytes::Bytes;
-use common_recordbatch::{RecordBatch, RecordBatches};
+use common_recordbatch::{RecordBatch, RecordBatchMetrics, RecordBatches};
 use datatypes::arrow;
 use datatypes::arrow::datatypes::Schema as ArrowSchema;
 use datatypes::arrow::ipc::{root_as_message, writer, MessageHeader};
@@ -28,17 +28,34 @@ use flatbuffers::FlatBufferBuilder;
 use prost::bytes::Bytes as ProstBytes;
 use prost::Message;
 use snafu::{OptionExt, ResultExt};
+use tonic::metadata::MetadataMap;
 
 use crate::error::{
     ConvertArrowSchemaSnafu, CreateRecordBatchSnafu, DecodeFlightDataSnafu, InvalidFlightDataSnafu,
     Result,
 };
 
+/// Header of a Flight `do_get` request asking the server to send the
+/// [FlightMessage::Metrics] after the last record batch. Servers don't send metrics
+/// unless the client sets the header to `true`.
+pub const FLIGHT_METRICS_HEADER: &str = "x-greptime-flight-metrics";
+
+/// Returns true if the client asks for metrics by the [FLIGHT_METRICS_HEADER] in the
+/// `metadata` of the request.
+pub fn is_metrics_requested(metadata: &MetadataMap) -> bool {
+    metadata
+        .get(FLIGHT_METRICS_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
 #[derive(Debug, Clone)]
 pub enum FlightMessage {
     Schema(SchemaRef),
     Recordbatch(RecordBatch),
     AffectedRows(usize),
+    /// Metrics of executing the query, sent after the last record batch.
+    Metrics(RecordBatchMetrics),
 }
 
 pub struct FlightEncoder {
@@ -94,6 +111,17 @@ impl FlightEncoder {
                     data_body: ProstBytes::default(),
                 }
             }
+            FlightMessage::Metrics(metrics) => {
+                // `FlightMetadata` has no field for metrics, so we carry them in the body
+                // and leave the metadata empty.
+                let body = serde_json::to_vec(&metrics).expect("Metrics are serializable");
+                FlightData {
+                    flight_descriptor: None,
+                    data_header: build_none_flight_msg().into(),
+                    app_metadata: ProstBytes::default(),
+                    data_body: body.into(),
+                }
+            }
         }
     }
 }
@@ -114,6 +142,15 @@ impl FlightDecoder {
         })?;
         match message.header_type() {
             MessageHeader::NONE => {
+                if flight_data.app_metadata.is_empty() && !flight_data.data_body.is_empty() {
+                    let metrics = serde_json::from_slice(&flight_data.data_body).map_err(|e| {
+                        InvalidFlightDataSnafu {
+                            reason: format!("Failed to decode metrics: {e}"),
+                        }
+                        .build()
+                    })?;
+                    return Ok(FlightMessage::Metrics(metrics));
+                }
                 let metadata = FlightMetadata::decode(flight_data.app_metadata)
                     .context(DecodeFlightDataSnafu)?;
                 if let Some(AffectedRows { value }) = metadata.affected_rows {
@@ -186,6 +223,7 @@ pub fn flight_messages_to_recordbatches(messages: Vec<FlightMessage>) -> Result<
         for message in messages.into_iter().skip(1) {
             match message {
                 FlightMessage::Recordbatch(recordbatch) => recordbatches.push(recordbatch),
+                FlightMessage::Metrics(_) => {}
                 _ => {
                     return InvalidFlightDataSnafu {
                         reason: "Expect the following Flight Messages are all Recordbatches!",
@@ -287,6 +325,40 @@ mod test {
         assert_eq!(actual_batch, batch2);
     }
 
+    #[test]
+    fn test_encode_decode_metrics() {
+        let metrics = RecordBatchMetrics {
+            fetched_bytes: 1024,
+            scanned_rows: 100,
+            elapsed_ms: 5,
+            index_applied_files: 2,
+            index_selected_row_groups: 3,
+            index_postings: 4,
+        };
+        let flight_data = FlightEncoder::default().encode(FlightMessage::Metrics(metrics.clone()));
+        let FlightMessage::Metrics(decoded) =
+            FlightDecoder::default().try_decode(flight_data).unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(metrics, decoded);
+
+        // Affected rows are still decoded from the metadata.
+        let flight_data = FlightEncoder::default().encode(FlightMessage::AffectedRows(0));
+        let message = FlightDecoder::default().try_decode(flight_data).unwrap();
+        assert!(matches!(message, FlightMessage::AffectedRows(0)));
+    }
+
+    #[test]
+    fn test_is_metrics_requested() {
+        let mut metadata = MetadataMap::new();
+        assert!(!is_metrics_requested(&metadata));
+        metadata.insert(FLIGHT_METRICS_HEADER, "false".parse().unwrap());
+        assert!(!is_metrics_requested(&metadata));
+        metadata.insert(FLIGHT_METRICS_HEADER, "true".parse().unwrap());
+        assert!(is_metrics_requested(&metadata));
+    }
+
     #[test]
     fn test_flight_messages_to_recordbatches() {
         let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
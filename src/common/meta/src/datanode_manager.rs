@@ -16,8 +16,9 @@ use std::sync::Arc;
 
 use api::v1::region::{QueryRequest, RegionRequest};
 use common_recordbatch::SendableRecordBatchStream;
+use store_api::storage::RegionId;
 
-use crate::error::Result;
+use crate::error::{Result, UnsupportedSnafu};
 use crate::peer::Peer;
 
 pub type AffectedRows = u64;
@@ -28,6 +29,39 @@ pub trait Datanode: Send + Sync {
     async fn handle(&self, request: RegionRequest) -> Result<AffectedRows>;
 
     async fn handle_query(&self, request: QueryRequest) -> Result<SendableRecordBatchStream>;
+
+    /// Syncs the WAL of the region so writes acknowledged before are durable.
+    ///
+    /// The region request protocol has no sync request, so datanodes only support it
+    /// if they can reach the region server without the protocol, e.g. in standalone mode.
+    async fn sync_region(&self, region_id: RegionId) -> Result<()> {
+        UnsupportedSnafu {
+            operation: format!("sync region {region_id}"),
+        }
+        .fail()
+    }
+
+    /// Freezes or unfreezes the region, a frozen region rejects writes and compactions.
+    ///
+    /// Like [Datanode::sync_region()], datanodes only support it if they can reach the
+    /// region server without the region request protocol.
+    async fn set_region_frozen(&self, region_id: RegionId, frozen: bool) -> Result<()> {
+        UnsupportedSnafu {
+            operation: format!("set region {region_id} frozen to {frozen}"),
+        }
+        .fail()
+    }
+
+    /// Pauses or resumes compactions of the region.
+    ///
+    /// Like [Datanode::set_region_frozen()], only datanodes reaching the region server
+    /// directly support it.
+    async fn set_region_compaction_paused(&self, region_id: RegionId, paused: bool) -> Result<()> {
+        UnsupportedSnafu {
+            operation: format!("set compaction of region {region_id} paused to {paused}"),
+        }
+        .fail()
+    }
 }
 
 pub type DatanodeRef = Arc<dyn Datanode>;
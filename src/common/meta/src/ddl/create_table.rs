@@ -36,6 +36,7 @@ use strum::AsRefStr;
 use table::engine::TableReference;
 use table::metadata::{RawTableInfo, TableId};
 
+use crate::ddl::table_meta::{PLACEMENT_HINTS_KEY, PLACEMENT_HINT_KEY};
 use crate::ddl::utils::{handle_operate_region_error, handle_retry_error, region_storage_path};
 use crate::ddl::DdlContext;
 use crate::error::{self, Result, TableRouteNotFoundSnafu};
@@ -184,13 +185,18 @@ impl CreateTableProcedure {
             })
             .collect::<Result<_>>()?;
 
+        let mut options = create_table_expr.table_options.clone();
+        // The placement hints of all partitions are only for allocating regions, each region
+        // request carries its own hint instead.
+        let _ = options.remove(PLACEMENT_HINTS_KEY);
+
         let template = PbCreateRegionRequest {
             region_id: 0,
             engine: create_table_expr.engine.to_string(),
             column_defs,
             primary_key,
             path: String::new(),
-            options: create_table_expr.table_options.clone(),
+            options,
         };
 
         Ok(CreateRequestBuilder {
@@ -248,6 +254,8 @@ impl CreateTableProcedure {
         let schema = &create_table_expr.schema_name;
         let storage_path = region_storage_path(catalog, schema);
 
+        let placement_hints = find_placement_hints(region_routes);
+
         let leaders = find_leaders(region_routes);
         let mut create_region_tasks = Vec::with_capacity(leaders.len());
 
@@ -259,7 +267,12 @@ impl CreateTableProcedure {
             for region_number in regions {
                 let region_id = RegionId::new(self.table_id(), region_number);
                 let create_region_request = request_builder
-                    .build_one(region_id, storage_path.clone(), region_wal_options)
+                    .build_one(
+                        region_id,
+                        storage_path.clone(),
+                        region_wal_options,
+                        &placement_hints,
+                    )
                     .await?;
 
                 requests.push(PbRegionRequest::Create(create_region_request));
@@ -432,6 +445,20 @@ impl CreateTableData {
     }
 }
 
+/// Returns the placement hints of regions in `region_routes`.
+fn find_placement_hints(region_routes: &[RegionRoute]) -> HashMap<RegionNumber, String> {
+    region_routes
+        .iter()
+        .filter_map(|route| {
+            route
+                .region
+                .attrs
+                .get(PLACEMENT_HINT_KEY)
+                .map(|hint| (route.region.id.region_number(), hint.clone()))
+        })
+        .collect()
+}
+
 /// Builder for [PbCreateRegionRequest].
 pub struct CreateRequestBuilder {
     template: PbCreateRegionRequest,
@@ -449,6 +476,7 @@ impl CreateRequestBuilder {
         region_id: RegionId,
         storage_path: String,
         region_wal_options: &HashMap<RegionNumber, String>,
+        placement_hints: &HashMap<RegionNumber, String>,
     ) -> Result<PbCreateRegionRequest> {
         let mut request = self.template.clone();
 
@@ -457,6 +485,12 @@ impl CreateRequestBuilder {
         // Stores the encoded wal options into the request options.
         prepare_wal_option(&mut request.options, region_id, region_wal_options);
 
+        if let Some(placement_hint) = placement_hints.get(&region_id.region_number()) {
+            request
+                .options
+                .insert(PLACEMENT_HINT_KEY.to_string(), placement_hint.clone());
+        }
+
         if let Some(physical_table_id) = self.physical_table_id {
             // Logical table has the same region numbers with physical table, and they have a one-to-one mapping.
             // For example, region 0 of logical table must resides with region 0 of physical table. So here we can
@@ -17,13 +17,13 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_catalog::consts::METRIC_ENGINE;
-use common_telemetry::{debug, info};
-use snafu::{ensure, OptionExt};
+use common_telemetry::{debug, info, warn};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::metric_engine_consts::LOGICAL_TABLE_METADATA_KEY;
 use store_api::storage::{RegionId, RegionNumber, TableId};
 
 use crate::ddl::{TableMetadata, TableMetadataAllocatorContext};
-use crate::error::{Result, TableNotFoundSnafu, UnsupportedSnafu};
+use crate::error::{Result, SerdeJsonSnafu, TableNotFoundSnafu, UnsupportedSnafu};
 use crate::key::table_name::TableNameKey;
 use crate::key::table_route::{LogicalTableRouteValue, PhysicalTableRouteValue, TableRouteValue};
 use crate::key::TableMetadataManagerRef;
@@ -32,6 +32,15 @@ use crate::rpc::ddl::CreateTableTask;
 use crate::rpc::router::{Region, RegionRoute};
 use crate::sequence::SequenceRef;
 use crate::wal::{allocate_region_wal_options, WalOptionsAllocatorRef};
+use crate::DatanodeId;
+
+/// The key of the create table option that carries the placement hints of partitions, encoded as
+/// a json map from the partition's index to the id of the datanode it prefers.
+pub const PLACEMENT_HINTS_KEY: &str = "placement_hints";
+
+/// The key of the [Region] attribute (and the create region request option) that stores the id of
+/// the datanode that the region prefers to be placed on.
+pub const PLACEMENT_HINT_KEY: &str = "placement_hint";
 
 #[derive(Clone)]
 pub struct TableMetadataAllocator {
@@ -151,27 +160,37 @@ impl TableMetadataAllocator {
             TableRouteValue::Logical(LogicalTableRouteValue::new(physical_table_id, region_ids))
         } else {
             let peers = self.peer_allocator.alloc(ctx, regions).await?;
+            let placement_hints = decode_placement_hints(task)?;
+
+            let mut region_routes = Vec::with_capacity(regions);
+            for (i, partition) in task.partitions.iter().enumerate() {
+                let mut region = Region {
+                    id: RegionId::new(table_id, i as u32),
+                    partition: Some(partition.clone().into()),
+                    ..Default::default()
+                };
 
-            let region_routes = task
-                .partitions
-                .iter()
-                .enumerate()
-                .map(|(i, partition)| {
-                    let region = Region {
-                        id: RegionId::new(table_id, i as u32),
-                        partition: Some(partition.clone().into()),
-                        ..Default::default()
-                    };
-
-                    let peer = peers[i % peers.len()].clone();
-
-                    RegionRoute {
-                        region,
-                        leader_peer: Some(peer),
-                        ..Default::default()
+                let mut peer = peers[i % peers.len()].clone();
+                if let Some(datanode_id) = placement_hints.get(&i) {
+                    let _ = region
+                        .attrs
+                        .insert(PLACEMENT_HINT_KEY.to_string(), datanode_id.to_string());
+
+                    match self.peer_allocator.find(ctx, *datanode_id).await? {
+                        Some(hinted) => peer = hinted,
+                        None => warn!(
+                            "The hinted datanode {} of region {} is unavailable, fallback to datanode {}",
+                            datanode_id, region.id, peer.id
+                        ),
                     }
-                })
-                .collect::<Vec<_>>();
+                }
+
+                region_routes.push(RegionRoute {
+                    region,
+                    leader_peer: Some(peer),
+                    ..Default::default()
+                });
+            }
             TableRouteValue::Physical(PhysicalTableRouteValue::new(region_routes))
         };
         Ok(table_route)
@@ -201,12 +220,31 @@ impl TableMetadataAllocator {
 
 pub type PeerAllocatorRef = Arc<dyn PeerAllocator>;
 
+/// Decodes the placement hints of the partitions in `task`.
+fn decode_placement_hints(task: &CreateTableTask) -> Result<HashMap<usize, DatanodeId>> {
+    task.create_table
+        .table_options
+        .get(PLACEMENT_HINTS_KEY)
+        .map_or_else(
+            || Ok(HashMap::new()),
+            |hints| serde_json::from_str(hints).context(SerdeJsonSnafu),
+        )
+}
+
 /// [PeerAllocator] allocates [Peer]s for creating regions.
 #[async_trait]
 pub trait PeerAllocator: Send + Sync {
     /// Allocates `regions` size [Peer]s.
     async fn alloc(&self, ctx: &TableMetadataAllocatorContext, regions: usize)
         -> Result<Vec<Peer>>;
+
+    /// Finds the [Peer] of datanode `datanode_id`, returns `None` if it's unavailable for placing
+    /// regions.
+    async fn find(
+        &self,
+        ctx: &TableMetadataAllocatorContext,
+        datanode_id: DatanodeId,
+    ) -> Result<Option<Peer>>;
 }
 
 struct NoopPeerAllocator;
@@ -220,4 +258,102 @@ impl PeerAllocator for NoopPeerAllocator {
     ) -> Result<Vec<Peer>> {
         Ok(vec![Peer::default(); regions])
     }
+
+    async fn find(
+        &self,
+        _ctx: &TableMetadataAllocatorContext,
+        _datanode_id: DatanodeId,
+    ) -> Result<Option<Peer>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::meta::Partition;
+    use api::v1::CreateTableExpr;
+
+    use super::*;
+    use crate::key::test_utils::new_test_table_info;
+    use crate::key::TableMetadataManager;
+    use crate::kv_backend::memory::MemoryKvBackend;
+    use crate::sequence::SequenceBuilder;
+    use crate::wal::WalOptionsAllocator;
+
+    struct MockPeerAllocator {
+        peers: Vec<Peer>,
+    }
+
+    #[async_trait]
+    impl PeerAllocator for MockPeerAllocator {
+        async fn alloc(
+            &self,
+            _ctx: &TableMetadataAllocatorContext,
+            regions: usize,
+        ) -> Result<Vec<Peer>> {
+            Ok(self.peers.iter().cycle().take(regions).cloned().collect())
+        }
+
+        async fn find(
+            &self,
+            _ctx: &TableMetadataAllocatorContext,
+            datanode_id: DatanodeId,
+        ) -> Result<Option<Peer>> {
+            Ok(self.peers.iter().find(|x| x.id == datanode_id).cloned())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_table_route_with_placement_hints() {
+        let kv_backend = Arc::new(MemoryKvBackend::new());
+        let allocator = TableMetadataAllocator::with_peer_allocator(
+            Arc::new(SequenceBuilder::new("test", kv_backend.clone()).build()),
+            Arc::new(WalOptionsAllocator::default()),
+            Arc::new(TableMetadataManager::new(kv_backend)),
+            Arc::new(MockPeerAllocator {
+                peers: vec![
+                    Peer::new(1, "127.0.0.1:4001"),
+                    Peer::new(2, "127.0.0.1:4002"),
+                ],
+            }),
+        );
+
+        // Region 0 prefers datanode 2, region 1 prefers the unavailable datanode 3 and region 2
+        // has no preference.
+        let create_table = CreateTableExpr {
+            table_options: HashMap::from([(
+                PLACEMENT_HINTS_KEY.to_string(),
+                r#"{"0":2,"1":3}"#.to_string(),
+            )]),
+            ..Default::default()
+        };
+        let task = CreateTableTask::new(
+            create_table,
+            vec![Partition::default(); 3],
+            new_test_table_info(1024, vec![0, 1, 2]).into(),
+        );
+
+        let ctx = TableMetadataAllocatorContext { cluster_id: 0 };
+        let table_route = allocator
+            .create_table_route(&ctx, 1024, &task)
+            .await
+            .unwrap();
+        let region_routes = table_route.region_routes().unwrap();
+
+        let leaders = region_routes
+            .iter()
+            .map(|x| x.leader_peer.as_ref().unwrap().id)
+            .collect::<Vec<_>>();
+        // Region 1 falls back to the peer allocated by the allocator.
+        assert_eq!(leaders, vec![2, 2, 1]);
+
+        let hints = region_routes
+            .iter()
+            .map(|x| x.region.attrs.get(PLACEMENT_HINT_KEY).cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hints,
+            vec![Some("2".to_string()), Some("3".to_string()), None]
+        );
+    }
 }
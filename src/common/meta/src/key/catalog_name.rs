@@ -123,6 +123,14 @@ impl CatalogManager {
         self.kv_backend.exists(&raw_key).await
     }
 
+    /// Deletes `CatalogNameKey`.
+    pub async fn delete(&self, catalog: CatalogNameKey<'_>) -> Result<()> {
+        let raw_key = catalog.as_raw_key();
+        let _ = self.kv_backend.delete(&raw_key, false).await?;
+
+        Ok(())
+    }
+
     pub async fn catalog_names(&self) -> BoxStream<'static, Result<String>> {
         let start_key = CatalogNameKey::range_start_key();
         let req = RangeRequest::new().with_prefix(start_key.as_bytes());
@@ -167,5 +175,8 @@ mod tests {
         let wrong_catalog_key = CatalogNameKey::new("my-wrong");
 
         assert!(!manager.exists(wrong_catalog_key).await.unwrap());
+
+        manager.delete(catalog_key).await.unwrap();
+        assert!(!manager.exists(catalog_key).await.unwrap());
     }
 }
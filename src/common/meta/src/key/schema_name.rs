@@ -22,13 +22,13 @@ use futures::stream::BoxStream;
 use futures::StreamExt;
 use humantime_serde::re::humantime;
 use serde::{Deserialize, Serialize};
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::error::{self, Error, InvalidTableMetadataSnafu, ParseOptionSnafu, Result};
 use crate::key::{TableMetaKey, SCHEMA_NAME_KEY_PATTERN, SCHEMA_NAME_KEY_PREFIX};
 use crate::kv_backend::KvBackendRef;
 use crate::range_stream::{PaginationStream, DEFAULT_PAGE_SIZE};
-use crate::rpc::store::RangeRequest;
+use crate::rpc::store::{CompareAndPutRequest, RangeRequest};
 use crate::rpc::KeyValue;
 
 const OPT_KEY_TTL: &str = "ttl";
@@ -53,6 +53,9 @@ pub struct SchemaNameValue {
     #[serde(default)]
     #[serde(with = "humantime_serde")]
     pub ttl: Option<Duration>,
+    /// Whether the schema is being dropped, a dropping schema doesn't accept new tables.
+    #[serde(default)]
+    pub dropping: bool,
 }
 
 impl TryFrom<&HashMap<String, String>> for SchemaNameValue {
@@ -72,7 +75,10 @@ impl TryFrom<&HashMap<String, String>> for SchemaNameValue {
             })
             .transpose()?
             .map(|ttl| ttl.into());
-        Ok(Self { ttl })
+        Ok(Self {
+            ttl,
+            dropping: false,
+        })
     }
 }
 
@@ -173,6 +179,46 @@ impl SchemaManager {
             .transpose()
     }
 
+    /// Marks the schema as dropping, returns false if the schema doesn't exist.
+    ///
+    /// The mark is set by a compare-and-put so a concurrent update of the schema fails it.
+    pub async fn set_dropping(&self, schema: SchemaNameKey<'_>) -> Result<bool> {
+        let raw_key = schema.as_raw_key();
+        let Some(kv) = self.kv_backend.get(&raw_key).await? else {
+            return Ok(false);
+        };
+        let mut value = SchemaNameValue::try_from_raw_value(&kv.value)?.unwrap_or_default();
+        if value.dropping {
+            return Ok(true);
+        }
+
+        value.dropping = true;
+        let req = CompareAndPutRequest::new()
+            .with_key(raw_key)
+            .with_expect(kv.value)
+            .with_value(value.try_as_raw_value()?);
+        let resp = self.kv_backend.compare_and_put(req).await?;
+        ensure!(
+            resp.success,
+            error::UnexpectedSnafu {
+                err_msg: format!(
+                    "Schema {} is modified while it is being dropped",
+                    schema.schema
+                ),
+            }
+        );
+
+        Ok(true)
+    }
+
+    /// Deletes `SchemaNameKey`.
+    pub async fn delete(&self, schema: SchemaNameKey<'_>) -> Result<()> {
+        let raw_key = schema.as_raw_key();
+        let _ = self.kv_backend.delete(&raw_key, false).await?;
+
+        Ok(())
+    }
+
     /// Returns a schema stream, it lists all schemas belong to the target `catalog`.
     pub async fn schema_names(&self, catalog: &str) -> BoxStream<'static, Result<String>> {
         let start_key = SchemaNameKey::range_start_key(catalog);
@@ -205,6 +251,7 @@ mod tests {
 
         let value = SchemaNameValue {
             ttl: Some(Duration::from_secs(10)),
+            dropping: false,
         };
         let mut opts: HashMap<String, String> = HashMap::new();
         opts.insert("ttl".to_string(), "10s".to_string());
@@ -230,5 +277,15 @@ mod tests {
         let wrong_schema_key = SchemaNameKey::new("my-catalog", "my-wrong");
 
         assert!(!manager.exists(wrong_schema_key).await.unwrap());
+
+        assert!(!manager.get(schema_key).await.unwrap().unwrap().dropping);
+        assert!(manager.set_dropping(schema_key).await.unwrap());
+        assert!(manager.get(schema_key).await.unwrap().unwrap().dropping);
+        // Marking a dropping schema again is a no-op.
+        assert!(manager.set_dropping(schema_key).await.unwrap());
+        assert!(!manager.set_dropping(wrong_schema_key).await.unwrap());
+
+        manager.delete(schema_key).await.unwrap();
+        assert!(!manager.exists(schema_key).await.unwrap());
     }
 }
@@ -93,6 +93,12 @@ pub enum Error {
     #[snafu(display("Bad accumulator implementation: {}", err_msg))]
     BadAccumulatorImpl { err_msg: String, location: Location },
 
+    #[snafu(display("Accumulator doesn't support retracting values"))]
+    RetractNotSupported { location: Location },
+
+    #[snafu(display("Overflow when evaluating {} in aggregation", expr))]
+    AggregateOverflow { expr: String, location: Location },
+
     #[snafu(display("Invalid input type: {}", err_msg))]
     InvalidInputType {
         location: Location,
@@ -195,9 +201,12 @@ impl ErrorExt for Error {
                 StatusCode::Unexpected
             }
 
+            Error::RetractNotSupported { .. } => StatusCode::Unsupported,
+
             Error::UnsupportedInputDataType { .. }
             | Error::TypeCast { .. }
-            | Error::InvalidFuncArgs { .. } => StatusCode::InvalidArguments,
+            | Error::InvalidFuncArgs { .. }
+            | Error::AggregateOverflow { .. } => StatusCode::InvalidArguments,
 
             Error::ConvertDfRecordBatchStream { source, .. } => source.status_code(),
             Error::ExecutePhysicalPlan { source, .. } => source.status_code(),
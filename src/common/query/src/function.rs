@@ -42,7 +42,8 @@ pub type ReturnTypeFunction =
 /// Accumulator creator that will be used by DataFusion
 pub type AccumulatorFunctionImpl = Arc<dyn Fn() -> Result<Box<dyn Accumulator>> + Send + Sync>;
 
-/// Create Accumulator with the data type of input columns.
+/// Create Accumulator with the data type of input columns, one for each argument of the
+/// aggregate function.
 pub type AccumulatorCreatorFunction =
     Arc<dyn Fn(&[ConcreteDataType]) -> Result<Box<dyn Accumulator>> + Sync + Send>;
 
@@ -17,6 +17,7 @@ use std::fmt::{Debug, Formatter};
 use api::greptime_proto::v1::add_column_location::LocationType;
 use api::greptime_proto::v1::AddColumnLocation as Location;
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
+use datatypes::schema::SchemaRef;
 use serde::{Deserialize, Serialize};
 
 pub mod columnar_value;
@@ -35,6 +36,20 @@ pub enum Output {
     Stream(SendableRecordBatchStream),
 }
 
+impl Output {
+    /// Returns the schema of the rows in the output, or `None` for [Output::AffectedRows].
+    ///
+    /// The schema of a stream is available before polling it, so it's valid even if
+    /// the stream yields no rows.
+    pub fn schema(&self) -> Option<SchemaRef> {
+        match self {
+            Output::AffectedRows(_) => None,
+            Output::RecordBatches(recordbatches) => Some(recordbatches.schema()),
+            Output::Stream(stream) => Some(stream.schema()),
+        }
+    }
+}
+
 impl Debug for Output {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -37,6 +37,7 @@ pub type AggregateFunctionCreatorRef = Arc<dyn AggregateFunctionCreator>;
 /// * convert its internal state to a vector of scalar values
 /// * update its state from multiple accumulators' states via `merge_batch`
 /// * compute the final value from its internal state via `evaluate`
+/// * optionally, remove inputs from its state via `retract_batch`
 ///
 /// Modified from DataFusion.
 pub trait Accumulator: Send + Sync + Debug {
@@ -45,7 +46,8 @@ pub trait Accumulator: Send + Sync + Debug {
     // of two values, sum and n.
     fn state(&self) -> Result<Vec<Value>>;
 
-    /// updates the accumulator's state from a vector of arrays.
+    /// updates the accumulator's state from a vector of arrays, one array for each argument
+    /// of the aggregate function in the order of arguments.
     fn update_batch(&mut self, values: &[VectorRef]) -> Result<()>;
 
     /// updates the accumulator's state from a vector of states.
@@ -53,6 +55,21 @@ pub trait Accumulator: Send + Sync + Debug {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<Value>;
+
+    /// retracts (removes) values from the accumulator's state, the opposite of `update_batch`.
+    ///
+    /// The query engine calls it to remove rows that have left a sliding window frame,
+    /// instead of accumulating all rows in the frame again. Accumulators implementing it
+    /// must also override `supports_retract_batch` to return true.
+    fn retract_batch(&mut self, _values: &[VectorRef]) -> Result<()> {
+        error::RetractNotSupportedSnafu.fail()
+    }
+
+    /// returns true if the accumulator implements `retract_batch`, so it can be used in
+    /// sliding window frames.
+    fn supports_retract_batch(&self) -> bool {
+        false
+    }
 }
 
 /// An `AggregateFunctionCreator` dynamically creates `Accumulator`.
@@ -161,6 +178,16 @@ impl DfAccumulator for DfAccumulatorAdaptor {
         Ok(())
     }
 
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> DfResult<()> {
+        let vectors = VectorHelper::try_into_vectors(values).context(FromScalarValueSnafu)?;
+        self.accumulator.retract_batch(&vectors)?;
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        self.accumulator.supports_retract_batch()
+    }
+
     fn evaluate(&self) -> DfResult<ScalarValue> {
         let value = self.accumulator.evaluate()?;
         let output_type = self.creator.output_type()?;
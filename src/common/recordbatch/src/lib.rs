@@ -31,6 +31,7 @@ use error::Result;
 use futures::task::{Context, Poll};
 use futures::{Stream, TryStreamExt};
 pub use recordbatch::RecordBatch;
+use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 
 pub trait RecordBatchStream: Stream<Item = Result<RecordBatch>> {
@@ -39,10 +40,49 @@ pub trait RecordBatchStream: Stream<Item = Result<RecordBatch>> {
     fn output_ordering(&self) -> Option<&[OrderOption]> {
         None
     }
+
+    /// Returns metrics collected while polling the stream.
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        None
+    }
 }
 
 pub type SendableRecordBatchStream = Pin<Box<dyn RecordBatchStream + Send>>;
 
+/// Metrics collected by a [RecordBatchStream].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordBatchMetrics {
+    /// Bytes fetched from the storage, bytes served by caches are not counted.
+    pub fetched_bytes: u64,
+    /// Rows read by the scans of the query.
+    #[serde(default)]
+    pub scanned_rows: u64,
+    /// Time elapsed to execute the query in milliseconds.
+    #[serde(default)]
+    pub elapsed_ms: u64,
+    /// SSTs whose indexes are applied by the scans of the query.
+    #[serde(default)]
+    pub index_applied_files: u64,
+    /// Row groups of SSTs selected by their indexes.
+    #[serde(default)]
+    pub index_selected_row_groups: u64,
+    /// Postings read from indexes of SSTs.
+    #[serde(default)]
+    pub index_postings: u64,
+}
+
+impl RecordBatchMetrics {
+    /// Adds `other` to the metrics, e.g. to sum up metrics of several queries.
+    pub fn merge(&mut self, other: &RecordBatchMetrics) {
+        self.fetched_bytes += other.fetched_bytes;
+        self.scanned_rows += other.scanned_rows;
+        self.elapsed_ms += other.elapsed_ms;
+        self.index_applied_files += other.index_applied_files;
+        self.index_selected_row_groups += other.index_selected_row_groups;
+        self.index_postings += other.index_postings;
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OrderOption {
     pub name: String,
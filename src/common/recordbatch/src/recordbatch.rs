@@ -142,6 +142,20 @@ impl RecordBatch {
         self.df_record_batch.num_rows()
     }
 
+    /// Returns a zero-copy slice of this batch with `len` rows starting from `offset`.
+    pub fn slice(&self, offset: usize, len: usize) -> RecordBatch {
+        let columns = self
+            .columns
+            .iter()
+            .map(|vector| vector.slice(offset, len))
+            .collect();
+        RecordBatch {
+            schema: self.schema.clone(),
+            columns,
+            df_record_batch: self.df_record_batch.slice(offset, len),
+        }
+    }
+
     /// Create an iterator to traverse the data by row
     pub fn rows(&self) -> RecordBatchRowIterator<'_> {
         RecordBatchRowIterator::new(self)
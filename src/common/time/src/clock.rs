@@ -0,0 +1,83 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Clock to read the wall-clock time.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::util::current_time_millis;
+use crate::Timestamp;
+
+/// Source of the wall-clock time.
+///
+/// Features depending on the wall-clock time, e.g. TTL, flushing idle regions and
+/// aligning range queries to now, read the time from the clock so tests can control
+/// the time instead of sleeping.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current time in milliseconds since the unix epoch.
+    fn now_millis(&self) -> i64;
+
+    /// Returns the current time as a millisecond timestamp.
+    fn now(&self) -> Timestamp {
+        Timestamp::new_millisecond(self.now_millis())
+    }
+}
+
+pub type ClockRef = Arc<dyn Clock>;
+
+/// Clock of the system time.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        current_time_millis()
+    }
+}
+
+/// Clock that only moves when it is changed, e.g. by tests.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    millis: AtomicI64,
+}
+
+impl MockClock {
+    /// Creates a clock at `millis` since the unix epoch.
+    pub fn new(millis: i64) -> Self {
+        Self {
+            millis: AtomicI64::new(millis),
+        }
+    }
+
+    /// Sets the clock to `millis` since the unix epoch.
+    pub fn set_millis(&self, millis: i64) {
+        self.millis.store(millis, Ordering::Relaxed);
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let _ = self
+            .millis
+            .fetch_add(duration.as_millis() as i64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> i64 {
+        self.millis.load(Ordering::Relaxed)
+    }
+}
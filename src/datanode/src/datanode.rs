@@ -47,6 +47,7 @@ use query::QueryEngineFactory;
 use servers::export_metrics::ExportMetricsTask;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::GrpcServerConfig;
+use servers::health::{HealthIndicatorRef, ReadinessIndicator};
 use servers::http::HttpServerBuilder;
 use servers::metrics_handler::MetricsHandler;
 use servers::server::{start_server, ServerHandler, ServerHandlers};
@@ -59,7 +60,7 @@ use store_api::storage::RegionId;
 use tokio::fs;
 use tokio::sync::Notify;
 
-use crate::config::{DatanodeOptions, RegionEngineConfig};
+use crate::config::{DatanodeOptions, ObjectStoreConfig, RegionEngineConfig};
 use crate::error::{
     BuildMitoEngineSnafu, CreateDirSnafu, GetMetadataSnafu, MissingKvBackendSnafu,
     MissingNodeIdSnafu, OpenLogStoreSnafu, ParseAddrSnafu, Result, RuntimeResourceSnafu,
@@ -72,7 +73,7 @@ use crate::event_listener::{
 use crate::greptimedb_telemetry::get_greptimedb_telemetry_task;
 use crate::heartbeat::HeartbeatTask;
 use crate::region_server::{DummyTableProviderFactory, RegionServer};
-use crate::store;
+use crate::store::{self, ObjectStoreIndicator};
 
 const OPEN_REGION_PARALLELISM: usize = 16;
 const REGION_SERVER_SERVICE_NAME: &str = "REGION_SERVER_SERVICE";
@@ -88,6 +89,7 @@ pub struct Datanode {
     leases_notifier: Option<Arc<Notify>>,
     plugins: Plugins,
     export_metrics_task: Option<ExportMetricsTask>,
+    health_indicators: Vec<HealthIndicatorRef>,
 }
 
 impl Datanode {
@@ -155,6 +157,8 @@ impl Datanode {
                 .map_err(BoxedError::new)
                 .context(ShutdownInstanceSnafu)?;
         }
+        // Flushes memtables as no more requests arrive after services are shutdown.
+        self.region_server.flush_all_regions().await;
         self.region_server.stop().await?;
         Ok(())
     }
@@ -163,6 +167,11 @@ impl Datanode {
         self.region_server.clone()
     }
 
+    /// Returns the components the readiness of the datanode depends on.
+    pub fn health_indicators(&self) -> Vec<HealthIndicatorRef> {
+        self.health_indicators.clone()
+    }
+
     pub fn plugins(&self) -> Plugins {
         self.plugins.clone()
     }
@@ -240,7 +249,10 @@ impl DatanodeBuilder {
             (Box::new(NoopRegionServerEventListener) as _, None)
         };
 
-        let region_server = self.new_region_server(region_event_listener).await?;
+        let object_store_manager = Self::build_object_store_manager(&self.opts).await?;
+        let region_server = self
+            .new_region_server(object_store_manager.clone(), region_event_listener)
+            .await?;
 
         let datanode_table_manager = DatanodeTableManager::new(kv_backend.clone());
         let table_values = datanode_table_manager
@@ -249,6 +261,18 @@ impl DatanodeBuilder {
             .await
             .context(GetMetadataSnafu)?;
 
+        // Regions are ready after they are opened, which includes replaying their WAL.
+        let regions_indicator = Arc::new(ReadinessIndicator::new(
+            "regions",
+            "opening regions and replaying WAL",
+        ));
+        let health_indicators: Vec<HealthIndicatorRef> = vec![
+            Arc::new(ObjectStoreIndicator::new(
+                object_store_manager.default_object_store().clone(),
+            )),
+            regions_indicator.clone(),
+        ];
+
         let open_all_regions =
             open_all_regions(region_server.clone(), table_values, !controlled_by_metasrv);
 
@@ -257,10 +281,13 @@ impl DatanodeBuilder {
             common_runtime::spawn_bg(async move {
                 if let Err(err) = open_all_regions.await {
                     error!(err; "Failed to open regions during the startup.");
+                } else {
+                    regions_indicator.set_ready();
                 }
             });
         } else {
             open_all_regions.await?;
+            regions_indicator.set_ready();
         }
 
         let heartbeat_task = if let Some(meta_client) = meta_client {
@@ -269,7 +296,7 @@ impl DatanodeBuilder {
             None
         };
 
-        let services = self.create_datanode_services(&region_server)?;
+        let services = self.create_datanode_services(&region_server, &health_indicators)?;
 
         let greptimedb_telemetry_task = get_greptimedb_telemetry_task(
             Some(self.opts.storage.data_home.clone()),
@@ -298,10 +325,15 @@ impl DatanodeBuilder {
             leases_notifier,
             plugins: self.plugins.clone(),
             export_metrics_task,
+            health_indicators,
         })
     }
 
-    fn create_datanode_services(&self, region_server: &RegionServer) -> Result<ServerHandlers> {
+    fn create_datanode_services(
+        &self,
+        region_server: &RegionServer,
+        health_indicators: &[HealthIndicatorRef],
+    ) -> Result<ServerHandlers> {
         let mut services = HashMap::new();
 
         if self.enable_region_server_service {
@@ -314,7 +346,7 @@ impl DatanodeBuilder {
         if self.enable_http_service {
             services.insert(
                 DATANODE_HTTP_SERVICE_NAME.to_string(),
-                self.create_http_service()?,
+                self.create_http_service(health_indicators)?,
             );
         }
 
@@ -344,15 +376,20 @@ impl DatanodeBuilder {
         Ok((server, addr))
     }
 
-    fn create_http_service(&self) -> Result<ServerHandler> {
+    fn create_http_service(
+        &self,
+        health_indicators: &[HealthIndicatorRef],
+    ) -> Result<ServerHandler> {
         let opts = &self.opts;
 
-        let server = Box::new(
-            HttpServerBuilder::new(opts.http.clone())
-                .with_metrics_handler(MetricsHandler)
-                .with_greptime_config_options(opts.to_toml_string())
-                .build(),
-        );
+        let mut builder = HttpServerBuilder::new(opts.http.clone());
+        let _ = builder
+            .with_metrics_handler(MetricsHandler)
+            .with_greptime_config_options(opts.to_toml_string());
+        for indicator in health_indicators {
+            let _ = builder.with_health_indicator(indicator.clone());
+        }
+        let server = Box::new(builder.build());
 
         let addr = opts.http.addr.parse().context(ParseAddrSnafu {
             addr: &opts.http.addr,
@@ -383,6 +420,7 @@ impl DatanodeBuilder {
 
     async fn new_region_server(
         &self,
+        object_store_manager: ObjectStoreManagerRef,
         event_listener: RegionServerEventListenerRef,
     ) -> Result<RegionServer> {
         let opts = &self.opts;
@@ -413,7 +451,6 @@ impl DatanodeBuilder {
             table_provider_factory,
         );
 
-        let object_store_manager = Self::build_object_store_manager(opts).await?;
         let engines = Self::build_store_engines(opts, object_store_manager).await?;
         for engine in engines {
             region_server.register_engine(engine);
@@ -468,6 +505,15 @@ impl DatanodeBuilder {
             );
         }
 
+        if config.sst_retry_max_retries > 0
+            && !matches!(opts.storage.store, ObjectStoreConfig::File(..))
+        {
+            warn!(
+                "Retries of SST operations stack on retries of the object store, max retries: {}",
+                config.sst_retry_max_retries
+            );
+        }
+
         let mito_engine = match &opts.wal {
             WalConfig::RaftEngine(raft_engine_config) => MitoEngine::new(
                 config,
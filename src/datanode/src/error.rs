@@ -118,6 +118,16 @@ pub enum Error {
         error: std::io::Error,
     },
 
+    #[snafu(display(
+        "Failed to lock directory {}, check whether another process uses the same directory",
+        dir
+    ))]
+    LockDir {
+        dir: String,
+        #[snafu(source)]
+        error: std::io::Error,
+    },
+
     #[snafu(display("Failed to open log store"))]
     OpenLogStore {
         location: Location,
@@ -332,6 +342,7 @@ impl ErrorExt for Error {
             ParseAddr { .. }
             | CreateDir { .. }
             | RemoveDir { .. }
+            | LockDir { .. }
             | Catalog { .. }
             | MissingRequiredField { .. }
             | IncorrectInternalState { .. }
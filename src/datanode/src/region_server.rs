@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod aggregate;
+
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -25,6 +27,7 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use common_error::ext::BoxedError;
 use common_error::status_code::StatusCode;
+use common_grpc::flight::is_metrics_requested;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::DfPhysicalPlanAdapter;
 use common_query::{DfPhysicalPlan, Output};
@@ -54,7 +57,9 @@ use snafu::{OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::metric_engine_consts::{METRIC_ENGINE_NAME, PHYSICAL_TABLE_METADATA_KEY};
 use store_api::region_engine::{RegionEngineRef, RegionRole, SetReadonlyResponse};
-use store_api::region_request::{AffectedRows, RegionCloseRequest, RegionRequest};
+use store_api::region_request::{
+    AffectedRows, RegionCloseRequest, RegionFlushRequest, RegionRequest,
+};
 use store_api::storage::{RegionId, ScanRequest};
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::table::scan::StreamScanAdapter;
@@ -166,6 +171,32 @@ impl RegionServer {
             .with_context(|_| HandleRegionRequestSnafu { region_id })
     }
 
+    /// Freezes or unfreezes the region, a frozen region rejects writes and compactions.
+    pub async fn set_frozen(&self, region_id: RegionId, frozen: bool) -> Result<()> {
+        let engine = self
+            .inner
+            .region_map
+            .get(&region_id)
+            .with_context(|| RegionNotFoundSnafu { region_id })?;
+        engine
+            .set_frozen(region_id, frozen)
+            .await
+            .with_context(|_| HandleRegionRequestSnafu { region_id })
+    }
+
+    /// Pauses or resumes compactions of the region.
+    pub async fn set_compaction_paused(&self, region_id: RegionId, paused: bool) -> Result<()> {
+        let engine = self
+            .inner
+            .region_map
+            .get(&region_id)
+            .with_context(|| RegionNotFoundSnafu { region_id })?;
+        engine
+            .set_compaction_paused(region_id, paused)
+            .await
+            .with_context(|_| HandleRegionRequestSnafu { region_id })
+    }
+
     pub async fn set_readonly_gracefully(
         &self,
         region_id: RegionId,
@@ -190,6 +221,35 @@ impl RegionServer {
         }
     }
 
+    /// Flushes all leader regions so their data don't need to be replayed from the WAL
+    /// on the next start.
+    ///
+    /// Failures are logged and don't stop flushing other regions.
+    pub async fn flush_all_regions(&self) {
+        let region_ids: Vec<_> = self
+            .reportable_regions()
+            .into_iter()
+            .filter(|stat| stat.role == RegionRole::Leader)
+            .map(|stat| stat.region_id)
+            .collect();
+        info!("Flushing {} regions", region_ids.len());
+
+        let results = futures_util::future::join_all(region_ids.iter().map(|region_id| {
+            self.handle_request(
+                *region_id,
+                RegionRequest::Flush(RegionFlushRequest {
+                    row_group_size: None,
+                }),
+            )
+        }))
+        .await;
+        for (region_id, result) in region_ids.iter().zip(results) {
+            if let Err(e) = result {
+                warn!(e; "Failed to flush region {}", region_id);
+            }
+        }
+    }
+
     /// Stop the region server.
     pub async fn stop(&self) -> Result<()> {
         self.inner.stop().await
@@ -256,6 +316,7 @@ impl FlightCraft for RegionServer {
         &self,
         request: Request<Ticket>,
     ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        let send_metrics = is_metrics_requested(request.metadata());
         let ticket = request.into_inner().ticket;
         let request = QueryRequest::decode(ticket.as_ref())
             .context(servers_error::InvalidFlightTicketSnafu)?;
@@ -270,7 +331,11 @@ impl FlightCraft for RegionServer {
             .trace(tracing_context.attach(info_span!("RegionServer::handle_read")))
             .await?;
 
-        let stream = Box::pin(FlightRecordBatchStream::new(result, tracing_context));
+        let stream = Box::pin(FlightRecordBatchStream::new(
+            result,
+            tracing_context,
+            send_metrics,
+        ));
         Ok(Response::new(stream))
     }
 }
@@ -446,7 +511,9 @@ impl RegionServerInner {
             | RegionRequest::Flush(_)
             | RegionRequest::Compact(_)
             | RegionRequest::Truncate(_)
-            | RegionRequest::Catchup(_) => RegionChange::None,
+            | RegionRequest::Catchup(_)
+            | RegionRequest::DeleteRange(_)
+            | RegionRequest::Sync(_) => RegionChange::None,
         };
 
         let engine = match self.get_engine(region_id, &region_change)? {
@@ -602,9 +669,16 @@ impl RegionServerInner {
             return error::RegionNotReadySnafu { region_id }.fail();
         }
 
+        let engine = region_status.into_engine();
+        let mut scan_request = ScanRequest::default();
+        if let Some(header) = &header {
+            scan_request.decode_hints(&header.tracing_context);
+        }
+        // Metadata of the region doesn't know which rows a sampled or followed scan returns.
+        let scan_all_rows = scan_request.sample.is_none() && !scan_request.follow;
         let table_provider = self
             .table_provider_factory
-            .create(region_id, region_status.into_engine())
+            .create(region_id, engine.clone(), scan_request)
             .await?;
 
         let catalog_list = Arc::new(DummyCatalogList::with_table_provider(table_provider));
@@ -614,6 +688,11 @@ impl RegionServerInner {
             .decode(Bytes::from(plan), catalog_list, "", "")
             .await
             .context(DecodeLogicalPlanSnafu)?;
+        let logical_plan = if scan_all_rows {
+            aggregate::answer_aggregate_by_metadata(&engine, region_id, logical_plan).await
+        } else {
+            logical_plan
+        };
         let result = self
             .query_engine
             .execute(logical_plan.into(), ctx)
@@ -821,6 +900,7 @@ impl TableProviderFactory for DummyTableProviderFactory {
         &self,
         region_id: RegionId,
         engine: RegionEngineRef,
+        scan_request: ScanRequest,
     ) -> Result<Arc<dyn TableProvider>> {
         let metadata =
             engine
@@ -834,17 +914,20 @@ impl TableProviderFactory for DummyTableProviderFactory {
             region_id,
             engine,
             metadata,
-            scan_request: Default::default(),
+            scan_request: Arc::new(Mutex::new(scan_request)),
         }))
     }
 }
 
 #[async_trait]
 pub trait TableProviderFactory: Send + Sync {
+    /// Creates the provider of the region, `scan_request` has hints of the query to
+    /// scan the region.
     async fn create(
         &self,
         region_id: RegionId,
         engine: RegionEngineRef,
+        scan_request: ScanRequest,
     ) -> Result<Arc<dyn TableProvider>>;
 }
 
@@ -0,0 +1,361 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Answers aggregates of a region from metadata of the region instead of scanning it.
+
+use std::collections::HashMap;
+
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_telemetry::warn;
+use datafusion::error::Result as DfResult;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::ScalarValue;
+use datafusion_expr::expr::AggregateFunction as AggregateExpr;
+use datafusion_expr::utils::split_conjunction;
+use datafusion_expr::{
+    col, Aggregate, AggregateFunction, Expr as DfExpr, LogicalPlan, LogicalPlanBuilder,
+};
+use datatypes::prelude::ConcreteDataType;
+use store_api::region_engine::RegionEngineRef;
+use store_api::storage::{RegionId, ScanRequest};
+
+/// Aggregate function the engine may answer from metadata of the region.
+#[derive(Debug, Clone, PartialEq)]
+enum MetadataAggregate {
+    /// Number of rows, e.g. `COUNT(*)`.
+    CountRows,
+    /// Min value of the column.
+    Min(String),
+    /// Max value of the column.
+    Max(String),
+}
+
+/// Rewrites the aggregate in the `plan` to values read from the `engine` if the
+/// aggregate only counts rows of the region or finds min and max values of its columns,
+/// e.g. `SELECT COUNT(*), MAX(ts) FROM t WHERE ts > 0`, so the region isn't scanned.
+///
+/// Returns the `plan` unchanged if the engine can't answer the aggregate without
+/// scanning the region.
+pub(crate) async fn answer_aggregate_by_metadata(
+    engine: &RegionEngineRef,
+    region_id: RegionId,
+    plan: LogicalPlan,
+) -> LogicalPlan {
+    let Some(aggregate) = find_aggregate(&plan).cloned() else {
+        return plan;
+    };
+    let Some((aggregates, filters)) = metadata_aggregates(&aggregate) else {
+        return plan;
+    };
+
+    let values = match aggregate_values(engine, region_id, &aggregate, &aggregates, filters).await {
+        Ok(Some(values)) => values,
+        Ok(None) => return plan,
+        Err(e) => {
+            warn!(e; "Failed to answer the aggregate of region {} by metadata", region_id);
+            return plan;
+        }
+    };
+
+    match replace_aggregate(plan.clone(), &aggregate, values) {
+        Ok(plan) => plan,
+        Err(e) => {
+            warn!(e; "Failed to replace the aggregate of region {}", region_id);
+            plan
+        }
+    }
+}
+
+/// Reads the value of each aggregate function in `aggregates` from the `engine`.
+///
+/// Returns `None` if the engine must scan the region to find any of them.
+async fn aggregate_values(
+    engine: &RegionEngineRef,
+    region_id: RegionId,
+    aggregate: &Aggregate,
+    aggregates: &[MetadataAggregate],
+    filters: Vec<DfExpr>,
+) -> std::result::Result<Option<Vec<ScalarValue>>, BoxedError> {
+    let request = ScanRequest {
+        filters: filters.into_iter().map(Expr::from).collect(),
+        ..Default::default()
+    };
+    let mut num_rows = None;
+    let mut min_max_values = HashMap::new();
+    let mut values = Vec::with_capacity(aggregates.len());
+    // The aggregate has no group by expressions so each output column is an aggregate.
+    for (func, field) in aggregates.iter().zip(aggregate.schema.fields()) {
+        let value = match func {
+            MetadataAggregate::CountRows => {
+                if num_rows.is_none() {
+                    num_rows = engine.count_rows(region_id, request.clone()).await?;
+                }
+                let Some(num_rows) = num_rows else {
+                    return Ok(None);
+                };
+                ScalarValue::Int64(Some(num_rows as i64))
+            }
+            MetadataAggregate::Min(column) | MetadataAggregate::Max(column) => {
+                if !min_max_values.contains_key(column) {
+                    let min_max = engine.min_max(region_id, column, request.clone()).await?;
+                    min_max_values.insert(column.clone(), min_max);
+                }
+                let Some((min, max)) = &min_max_values[column] else {
+                    return Ok(None);
+                };
+                let value = if matches!(func, MetadataAggregate::Min(_)) {
+                    min
+                } else {
+                    max
+                };
+                let output_type = ConcreteDataType::from_arrow_type(field.data_type());
+                value
+                    .try_to_scalar_value(&output_type)
+                    .map_err(BoxedError::new)?
+            }
+        };
+        values.push(value);
+    }
+
+    Ok(Some(values))
+}
+
+/// Returns the aggregate whose output is the input of all other nodes in the `plan`.
+fn find_aggregate(plan: &LogicalPlan) -> Option<&Aggregate> {
+    match plan {
+        LogicalPlan::Aggregate(aggregate) => Some(aggregate),
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::Sort(_)
+        | LogicalPlan::Limit(_) => match plan.inputs().as_slice() {
+            [input] => find_aggregate(input),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns aggregate functions of the `aggregate` and filters of the scan if the engine
+/// may answer all functions from metadata of the scanned region.
+fn metadata_aggregates(aggregate: &Aggregate) -> Option<(Vec<MetadataAggregate>, Vec<DfExpr>)> {
+    if !aggregate.group_expr.is_empty() || aggregate.aggr_expr.is_empty() {
+        return None;
+    }
+    let aggregates = aggregate
+        .aggr_expr
+        .iter()
+        .map(metadata_aggregate)
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((aggregates, scan_filters(&aggregate.input)?))
+}
+
+/// Returns the aggregate function of the `expr` if it counts all rows, e.g. `COUNT(*)`,
+/// or finds the min or max value of a column.
+fn metadata_aggregate(expr: &DfExpr) -> Option<MetadataAggregate> {
+    let DfExpr::AggregateFunction(AggregateExpr {
+        fun,
+        args,
+        distinct: false,
+        filter: None,
+        ..
+    }) = expr
+    else {
+        return None;
+    };
+
+    match (fun, args.as_slice()) {
+        (AggregateFunction::Count, args)
+            if args
+                .iter()
+                .all(|arg| matches!(arg, DfExpr::Literal(value) if !value.is_null())) =>
+        {
+            Some(MetadataAggregate::CountRows)
+        }
+        (AggregateFunction::Min, [DfExpr::Column(column)]) => {
+            Some(MetadataAggregate::Min(column.name.clone()))
+        }
+        (AggregateFunction::Max, [DfExpr::Column(column)]) => {
+            Some(MetadataAggregate::Max(column.name.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns filters on rows of the table scan if the `plan` only filters rows of the
+/// scan, `None` otherwise.
+fn scan_filters(plan: &LogicalPlan) -> Option<Vec<DfExpr>> {
+    match plan {
+        LogicalPlan::TableScan(scan) if scan.fetch.is_none() => Some(scan.filters.clone()),
+        LogicalPlan::Filter(filter) => {
+            let mut filters = scan_filters(&filter.input)?;
+            filters.extend(split_conjunction(&filter.predicate).into_iter().cloned());
+            Some(filters)
+        }
+        // Filters above the projection refer to the same columns as the scan.
+        LogicalPlan::Projection(projection)
+            if projection
+                .expr
+                .iter()
+                .all(|expr| matches!(expr, DfExpr::Column(_))) =>
+        {
+            scan_filters(&projection.input)
+        }
+        _ => None,
+    }
+}
+
+/// Replaces the `aggregate` in the `plan` with a row of `values`, one value for each
+/// output column of the aggregate.
+fn replace_aggregate(
+    plan: LogicalPlan,
+    aggregate: &Aggregate,
+    values: Vec<ScalarValue>,
+) -> DfResult<LogicalPlan> {
+    let row = values.into_iter().map(DfExpr::Literal).collect();
+    // Columns of values are named `column1`, `column2` and so on.
+    let exprs = aggregate
+        .schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| col(format!("column{}", i + 1)).alias(field.name()));
+    let values_plan = LogicalPlanBuilder::values(vec![row])?
+        .project(exprs)?
+        .build()?;
+
+    let target = LogicalPlan::Aggregate(aggregate.clone());
+    plan.transform_up(&|node| {
+        Ok(if node == target {
+            Transformed::Yes(values_plan.clone())
+        } else {
+            Transformed::No(node)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::datasource::empty::EmptyTable;
+    use datafusion::datasource::provider_as_source;
+    use datafusion_expr::{count, lit, max, min, sum};
+    use datatypes::arrow::datatypes::{DataType, Field, Schema};
+
+    use super::*;
+
+    fn scan_builder() -> LogicalPlanBuilder {
+        let schema = Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("ts", DataType::Int64, false),
+        ]);
+        let table = EmptyTable::new(Arc::new(schema));
+        LogicalPlanBuilder::scan("t", provider_as_source(Arc::new(table)), None).unwrap()
+    }
+
+    #[test]
+    fn test_metadata_aggregates() {
+        let plan = scan_builder()
+            .filter(col("ts").gt(lit(0i64)).and(col("ts").lt(lit(10i64))))
+            .unwrap()
+            .aggregate(Vec::<DfExpr>::new(), vec![count(lit(1u8))])
+            .unwrap()
+            .build()
+            .unwrap();
+        let aggregate = find_aggregate(&plan).unwrap();
+        assert_eq!(
+            (
+                vec![MetadataAggregate::CountRows],
+                vec![col("ts").gt(lit(0i64)), col("ts").lt(lit(10i64))]
+            ),
+            metadata_aggregates(aggregate).unwrap()
+        );
+
+        let plan = scan_builder()
+            .aggregate(
+                Vec::<DfExpr>::new(),
+                vec![count(lit(1u8)), min(col("ts")), max(col("ts"))],
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            vec![
+                MetadataAggregate::CountRows,
+                MetadataAggregate::Min("ts".to_string()),
+                MetadataAggregate::Max("ts".to_string()),
+            ],
+            metadata_aggregates(find_aggregate(&plan).unwrap())
+                .unwrap()
+                .0
+        );
+
+        // Counts values of a column.
+        let plan = scan_builder()
+            .aggregate(Vec::<DfExpr>::new(), vec![count(col("host"))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(metadata_aggregates(find_aggregate(&plan).unwrap()).is_none());
+
+        // Other aggregates.
+        let plan = scan_builder()
+            .aggregate(Vec::<DfExpr>::new(), vec![count(lit(1u8)), sum(col("ts"))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(metadata_aggregates(find_aggregate(&plan).unwrap()).is_none());
+
+        // Group by.
+        let plan = scan_builder()
+            .aggregate(vec![col("host")], vec![count(lit(1u8))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(metadata_aggregates(find_aggregate(&plan).unwrap()).is_none());
+
+        // Limit of the scan.
+        let plan = scan_builder()
+            .limit(0, Some(1))
+            .unwrap()
+            .aggregate(Vec::<DfExpr>::new(), vec![count(lit(1u8))])
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(metadata_aggregates(find_aggregate(&plan).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_replace_aggregate() {
+        let plan = scan_builder()
+            .aggregate(Vec::<DfExpr>::new(), vec![count(lit(1u8))])
+            .unwrap()
+            .project(vec![col("COUNT(UInt8(1))")])
+            .unwrap()
+            .build()
+            .unwrap();
+        let aggregate = find_aggregate(&plan).unwrap().clone();
+        let replaced =
+            replace_aggregate(plan.clone(), &aggregate, vec![ScalarValue::Int64(Some(3))]).unwrap();
+
+        assert_eq!(plan.schema(), replaced.schema());
+        let expected = "\
+Projection: COUNT(UInt8(1))
+  Projection: column1 AS COUNT(UInt8(1))
+    Values: (Int64(3))";
+        assert_eq!(expected, replaced.display_indent().to_string());
+    }
+}
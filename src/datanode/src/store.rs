@@ -24,17 +24,49 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::{env, path};
 
+use async_trait::async_trait;
 use common_base::readable_size::ReadableSize;
 use common_telemetry::logging::info;
 use object_store::layers::{LruCacheLayer, RetryLayer};
 use object_store::services::Fs;
 use object_store::util::{join_dir, normalize_dir, with_instrument_layers};
 use object_store::{HttpClient, ObjectStore, ObjectStoreBuilder};
+use servers::health::{ComponentHealth, HealthIndicator};
 use snafu::prelude::*;
 
 use crate::config::{ObjectStoreConfig, DEFAULT_OBJECT_STORE_CACHE_SIZE};
 use crate::error::{self, Result};
 
+/// Path the [ObjectStoreIndicator] probes, it doesn't need to exist.
+const HEALTH_CHECK_PATH: &str = ".health_check";
+
+/// Reports whether the object store is reachable.
+pub(crate) struct ObjectStoreIndicator {
+    object_store: ObjectStore,
+}
+
+impl ObjectStoreIndicator {
+    pub(crate) fn new(object_store: ObjectStore) -> Self {
+        Self { object_store }
+    }
+}
+
+#[async_trait]
+impl HealthIndicator for ObjectStoreIndicator {
+    fn name(&self) -> &str {
+        "object_store"
+    }
+
+    async fn check(&self) -> ComponentHealth {
+        // Stats a path so the request reaches the backend, a missing path is fine.
+        self.object_store
+            .is_exist(HEALTH_CHECK_PATH)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("object store is unreachable: {e}"))
+    }
+}
+
 pub(crate) async fn new_object_store(
     store: ObjectStoreConfig,
     data_home: &str,
@@ -128,7 +160,13 @@ async fn create_object_store_with_cache(
     }
 }
 
+/// Locks the temp `dir` for the lifetime of the process and cleans it.
+///
+/// Fails without cleaning the dir if another process locks it, as cleaning the dir
+/// deletes files the other process is writing.
 pub(crate) fn clean_temp_dir(dir: &str) -> Result<()> {
+    object_store::util::lock_dir(dir).context(error::LockDirSnafu { dir })?;
+
     if path::Path::new(&dir).exists() {
         info!("Begin to clean temp storage directory: {}", dir);
         std::fs::remove_dir_all(dir).context(error::RemoveDirSnafu { dir })?;
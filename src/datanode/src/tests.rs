@@ -18,7 +18,7 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
-use common_function::function::FunctionRef;
+use common_function::function::{FunctionRef, ScalarFunctionMetaRef};
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::prelude::ScalarUdf;
 use common_query::Output;
@@ -75,6 +75,8 @@ impl QueryEngine for MockQueryEngine {
 
     fn register_function(&self, _func: FunctionRef) {}
 
+    fn register_scalar_function(&self, _func: ScalarFunctionMetaRef) {}
+
     fn read_table(&self, _table: TableRef) -> query::error::Result<DataFrame> {
         unimplemented!()
     }
@@ -201,6 +203,18 @@ impl RegionEngine for MockRegionEngine {
         unimplemented!()
     }
 
+    async fn set_frozen(&self, _region_id: RegionId, _frozen: bool) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn set_compaction_paused(
+        &self,
+        _region_id: RegionId,
+        _paused: bool,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
     fn role(&self, _region_id: RegionId) -> Option<RegionRole> {
         if let Some(role) = self.mock_role {
             return role;
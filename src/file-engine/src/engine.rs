@@ -104,6 +104,26 @@ impl RegionEngine for FileRegionEngine {
             .map_err(BoxedError::new)
     }
 
+    async fn set_frozen(&self, _region_id: RegionId, _frozen: bool) -> Result<(), BoxedError> {
+        UnsupportedSnafu {
+            operation: "set_frozen",
+        }
+        .fail()
+        .map_err(BoxedError::new)
+    }
+
+    async fn set_compaction_paused(
+        &self,
+        _region_id: RegionId,
+        _paused: bool,
+    ) -> Result<(), BoxedError> {
+        UnsupportedSnafu {
+            operation: "set_compaction_paused",
+        }
+        .fail()
+        .map_err(BoxedError::new)
+    }
+
     async fn set_readonly_gracefully(
         &self,
         region_id: RegionId,
@@ -26,6 +26,12 @@ use store_api::storage::RegionNumber;
 #[snafu(visibility(pub))]
 #[stack_trace_debug]
 pub enum Error {
+    #[snafu(display("Results of the query exceed {}", limit))]
+    ResultLimitExceeded { limit: String, location: Location },
+
+    #[snafu(display("The frontend is shutting down"))]
+    ShuttingDown { location: Location },
+
     #[snafu(display("Failed to invalidate table cache"))]
     InvalidateTableCache {
         location: Location,
@@ -292,6 +298,10 @@ impl ErrorExt for Error {
 
             Error::NotSupported { .. } => StatusCode::Unsupported,
 
+            Error::ShuttingDown { .. } => StatusCode::Cancelled,
+
+            Error::ResultLimitExceeded { .. } => StatusCode::RuntimeResourcesExhausted,
+
             Error::Permission { source, .. } => source.status_code(),
 
             Error::DescribeStatement { source, .. } => source.status_code(),
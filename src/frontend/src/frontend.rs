@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use common_telemetry::logging::LoggingOptions;
 use meta_client::MetaClientOptions;
+use query::query_engine::options::DEFAULT_MAX_PLAN_DEPTH;
 use serde::{Deserialize, Serialize};
 use servers::export_metrics::ExportMetricsOption;
 use servers::heartbeat_options::HeartbeatOptions;
@@ -27,12 +30,26 @@ use crate::service_config::{
     PostgresOptions, PromStoreOptions,
 };
 
+/// Default timeout to drain in-flight requests on shutdown.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default)]
 pub struct FrontendOptions {
     pub mode: Mode,
     pub node_id: Option<String>,
     pub default_timezone: Option<String>,
+    /// How long the shutdown waits for in-flight requests before cancelling them.
+    #[serde(with = "humantime_serde")]
+    pub drain_timeout: Duration,
+    /// Max depth of nested queries in a statement, deeper queries are rejected
+    /// before planning.
+    pub max_plan_depth: usize,
+    /// Max rows written to each table per second, 0 means unlimited.
+    ///
+    /// The limit applies to writes through this frontend only, each frontend in
+    /// a cluster limits its writes separately.
+    pub table_write_rate_limit: u64,
     pub heartbeat: HeartbeatOptions,
     pub http: HttpOptions,
     pub grpc: GrpcOptions,
@@ -55,6 +72,9 @@ impl Default for FrontendOptions {
             mode: Mode::Standalone,
             node_id: None,
             default_timezone: None,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            max_plan_depth: DEFAULT_MAX_PLAN_DEPTH,
+            table_write_rate_limit: 0,
             heartbeat: HeartbeatOptions::frontend_default(),
             http: HttpOptions::default(),
             grpc: GrpcOptions::default(),
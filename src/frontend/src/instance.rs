@@ -14,15 +14,18 @@
 
 pub mod builder;
 mod grpc;
+mod inflight;
 mod influxdb;
 mod opentsdb;
 mod otlp;
 mod prom_store;
 mod region_query;
+mod result_limit;
 mod script;
 pub mod standalone;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::meta::Role;
 use async_trait::async_trait;
@@ -39,14 +42,16 @@ use common_procedure::options::ProcedureConfig;
 use common_procedure::ProcedureManagerRef;
 use common_query::Output;
 use common_telemetry::error;
-use common_telemetry::logging::info;
+use common_telemetry::logging::{info, warn};
 use log_store::raft_engine::RaftEngineBackend;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOptions;
 use operator::delete::DeleterRef;
 use operator::insert::InserterRef;
+use operator::read_only::ReadOnlyStateRef;
 use operator::statement::StatementExecutor;
 use operator::table::table_idents_to_full_name;
+use operator::write_limiter::TableWriteLimiterRef;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::plan::LogicalPlan;
 use query::query_engine::options::{validate_catalog_and_schema, QueryOptions};
@@ -84,6 +89,7 @@ use crate::error::{
 };
 use crate::frontend::{FrontendOptions, TomlSerializable};
 use crate::heartbeat::HeartbeatTask;
+use crate::instance::inflight::InflightRequestsRef;
 use crate::metrics;
 use crate::script::ScriptExecutor;
 
@@ -119,6 +125,11 @@ pub struct Instance {
     inserter: InserterRef,
     deleter: DeleterRef,
     export_metrics_task: Option<ExportMetricsTask>,
+    read_only: ReadOnlyStateRef,
+    write_limiter: TableWriteLimiterRef,
+    inflight_requests: InflightRequestsRef,
+    /// How long the shutdown waits for in-flight requests.
+    drain_timeout: Duration,
 }
 
 impl Instance {
@@ -195,6 +206,7 @@ impl Instance {
         self.export_metrics_task =
             ExportMetricsTask::try_new(&opts.export_metrics, Some(&self.plugins))
                 .context(StartServerSnafu)?;
+        self.drain_timeout = opts.drain_timeout;
 
         self.servers = Arc::new(servers);
 
@@ -209,11 +221,47 @@ impl Instance {
         self.plugins.clone()
     }
 
+    /// Stops accepting new connections, then waits for in-flight requests to finish.
+    ///
+    /// Requests still running after the drain timeout are cancelled.
     pub async fn shutdown(&self) -> Result<()> {
-        futures::future::try_join_all(self.servers.values().map(|server| server.0.shutdown()))
-            .await
-            .context(error::ShutdownServerSnafu)
-            .map(|_| ())
+        let _ =
+            futures::future::try_join_all(self.servers.values().map(|server| server.0.shutdown()))
+                .await
+                .context(error::ShutdownServerSnafu)?;
+
+        let num_inflight = self.inflight_requests.num_inflight();
+        if num_inflight > 0 {
+            info!("Waiting for {num_inflight} in-flight requests to finish");
+        }
+        let num_cancelled = self.inflight_requests.drain(self.drain_timeout).await;
+        if num_cancelled > 0 {
+            warn!(
+                "Cancelled {} in-flight requests that didn't finish in {:?}",
+                num_cancelled, self.drain_timeout
+            );
+        }
+        Ok(())
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.is_read_only()
+    }
+
+    /// Switches the instance into or out of read-only mode, in which all the DDL and DML
+    /// are rejected.
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.set_read_only(read_only);
+    }
+
+    /// Sets the max rows written to each table per second through this instance,
+    /// 0 means unlimited.
+    pub fn set_table_write_rate_limit(&self, rows_per_second: u64) {
+        self.write_limiter.set_rate_limit(rows_per_second);
+    }
+
+    pub fn write_limiter(&self) -> &TableWriteLimiterRef {
+        &self.write_limiter
     }
 
     pub fn statement_executor(&self) -> Arc<StatementExecutor> {
@@ -266,13 +314,12 @@ impl Instance {
             .await
             .context(TableOperationSnafu)
     }
-}
 
-#[async_trait]
-impl SqlQueryHandler for Instance {
-    type Error = Error;
-
-    async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
+    async fn handle_sql_query(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> Vec<Result<Output>> {
         let _timer = metrics::METRIC_HANDLE_SQL_ELAPSED.start_timer();
         let query_interceptor_opt = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
         let query_interceptor = query_interceptor_opt.as_ref();
@@ -311,8 +358,9 @@ impl SqlQueryHandler for Instance {
 
                     match self.query_statement(stmt, query_ctx.clone()).await {
                         Ok(output) => {
-                            let output_result =
-                                query_interceptor.post_execute(output, query_ctx.clone());
+                            let output_result = query_interceptor
+                                .post_execute(output, query_ctx.clone())
+                                .and_then(|output| result_limit::limit_output(output, &query_ctx));
                             results.push(output_result);
                         }
                         Err(e) => {
@@ -331,14 +379,26 @@ impl SqlQueryHandler for Instance {
             }
         }
     }
+}
+
+#[async_trait]
+impl SqlQueryHandler for Instance {
+    type Error = Error;
+
+    async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
+        self.inflight_requests
+            .run_query(self.handle_sql_query(query, query_ctx))
+            .await
+            .unwrap_or_else(|e| vec![Err(e)])
+    }
 
     async fn do_exec_plan(&self, plan: LogicalPlan, query_ctx: QueryContextRef) -> Result<Output> {
         let _timer = metrics::METRIC_EXEC_PLAN_ELAPSED.start_timer();
         // plan should be prepared before exec
         // we'll do check there
-        self.query_engine
-            .execute(plan, query_ctx)
-            .await
+        self.inflight_requests
+            .run_query(self.query_engine.execute(plan, query_ctx))
+            .await?
             .context(ExecLogicalPlanSnafu)
     }
 
@@ -401,6 +461,24 @@ impl PrometheusHandler for Instance {
         &self,
         query: &PromQuery,
         query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        self.inflight_requests
+            .run_query(self.handle_promql_query(query, query_ctx))
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::OtherSnafu)?
+    }
+
+    fn catalog_manager(&self) -> CatalogManagerRef {
+        self.catalog_manager.clone()
+    }
+}
+
+impl Instance {
+    async fn handle_promql_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
         let _timer = metrics::METRIC_HANDLE_PROMQL_ELAPSED.start_timer();
         let interceptor = self
@@ -429,10 +507,6 @@ impl PrometheusHandler for Instance {
 
         Ok(interceptor.post_execute(output, query_ctx)?)
     }
-
-    fn catalog_manager(&self) -> CatalogManagerRef {
-        self.catalog_manager.clone()
-    }
 }
 
 pub fn check_permission(
@@ -452,8 +526,14 @@ pub fn check_permission(
     match stmt {
         // These are executed by query engine, and will be checked there.
         Statement::Query(_) | Statement::Explain(_) | Statement::Tql(_) | Statement::Delete(_) => {}
-        // database ops won't be checked
-        Statement::CreateDatabase(_) | Statement::ShowDatabases(_) => {}
+        // database and catalog ops won't be checked
+        Statement::CreateDatabase(_)
+        | Statement::DropDatabase(_)
+        | Statement::CreateCatalog(_)
+        | Statement::DropCatalog(_)
+        | Statement::ShowDatabases(_) => {}
+        // session variables only affect the session itself
+        Statement::SetVariables(_) => {}
         // show create table and alter are not supported yet
         Statement::ShowCreateTable(_) | Statement::CreateExternalTable(_) | Statement::Alter(_) => {
         }
@@ -467,6 +547,11 @@ pub fn check_permission(
         Statement::DropTable(drop_stmt) => {
             validate_param(drop_stmt.table_name(), query_ctx)?;
         }
+        Statement::Use(db) => {
+            validate_catalog_and_schema(query_ctx.current_catalog(), db, query_ctx)
+                .map_err(BoxedError::new)
+                .context(SqlExecInterceptedSnafu)?;
+        }
         Statement::ShowTables(stmt) => {
             if let Some(database) = &stmt.database {
                 validate_catalog_and_schema(query_ctx.current_catalog(), database, query_ctx)
@@ -489,6 +574,15 @@ pub fn check_permission(
         Statement::TruncateTable(stmt) => {
             validate_param(stmt.table_name(), query_ctx)?;
         }
+        Statement::SyncTable(stmt) => {
+            validate_param(stmt.table_name(), query_ctx)?;
+        }
+        Statement::FreezeTable(stmt) => {
+            validate_param(stmt.table_name(), query_ctx)?;
+        }
+        Statement::PauseCompaction(stmt) => {
+            validate_param(stmt.table_name(), query_ctx)?;
+        }
     }
     Ok(())
 }
@@ -521,6 +615,7 @@ mod tests {
         let plugins: Plugins = Plugins::new();
         plugins.insert(QueryOptions {
             disallow_cross_catalog_query: true,
+            ..Default::default()
         });
 
         let sql = r#"
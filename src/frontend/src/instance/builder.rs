@@ -23,13 +23,17 @@ use common_meta::ddl::DdlTaskExecutorRef;
 use common_meta::kv_backend::KvBackendRef;
 use operator::delete::Deleter;
 use operator::insert::Inserter;
+use operator::read_only::ReadOnlyState;
 use operator::statement::StatementExecutor;
 use operator::table::TableMutationOperator;
+use operator::write_limiter::TableWriteLimiter;
 use partition::manager::PartitionRuleManager;
 use query::QueryEngineFactory;
 
 use crate::error::Result;
+use crate::frontend::DEFAULT_DRAIN_TIMEOUT;
 use crate::heartbeat::HeartbeatTask;
+use crate::instance::inflight::InflightRequests;
 use crate::instance::region_query::FrontendRegionQueryHandler;
 use crate::instance::{Instance, StatementExecutorRef};
 use crate::script::ScriptExecutor;
@@ -96,15 +100,20 @@ impl FrontendBuilder {
         let region_query_handler =
             FrontendRegionQueryHandler::arc(partition_manager.clone(), datanode_manager.clone());
 
+        let read_only = Arc::new(ReadOnlyState::default());
+        let write_limiter = Arc::new(TableWriteLimiter::default());
         let inserter = Arc::new(Inserter::new(
             catalog_manager.clone(),
             partition_manager.clone(),
             datanode_manager.clone(),
+            read_only.clone(),
+            write_limiter.clone(),
         ));
         let deleter = Arc::new(Deleter::new(
             catalog_manager.clone(),
             partition_manager,
             datanode_manager.clone(),
+            read_only.clone(),
         ));
         let table_mutation_handler = Arc::new(TableMutationOperator::new(
             inserter.clone(),
@@ -130,6 +139,7 @@ impl FrontendBuilder {
             kv_backend,
             catalog_manager.clone(),
             inserter.clone(),
+            read_only.clone(),
         ));
 
         plugins.insert::<StatementExecutorRef>(statement_executor.clone());
@@ -145,6 +155,10 @@ impl FrontendBuilder {
             inserter,
             deleter,
             export_metrics_task: None,
+            read_only,
+            write_limiter,
+            inflight_requests: Arc::new(InflightRequests::default()),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
         })
     }
 }
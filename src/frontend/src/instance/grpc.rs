@@ -38,6 +38,14 @@ impl GrpcQueryHandler for Instance {
     type Error = Error;
 
     async fn do_query(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
+        self.inflight_requests
+            .run_query(self.handle_grpc_request(request, ctx))
+            .await?
+    }
+}
+
+impl Instance {
+    async fn handle_grpc_request(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
         let interceptor_ref = self.plugins.get::<GrpcQueryInterceptorRef<Error>>();
         let interceptor = interceptor_ref.as_ref();
         interceptor.pre_execute(&request, ctx.clone())?;
@@ -59,7 +67,8 @@ impl GrpcQueryHandler for Instance {
                 })?;
                 match query {
                     Query::Sql(sql) => {
-                        let mut result = SqlQueryHandler::do_query(self, &sql, ctx.clone()).await;
+                        // The request is already in-flight.
+                        let mut result = self.handle_sql_query(&sql, ctx.clone()).await;
                         ensure!(
                             result.len() == 1,
                             NotSupportedSnafu {
@@ -195,6 +204,17 @@ impl Instance {
             .context(TableOperationSnafu)
     }
 
+    /// Handles row inserts of protocols other than gRPC as an in-flight request.
+    pub(crate) async fn handle_inflight_row_inserts(
+        &self,
+        requests: RowInsertRequests,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.inflight_requests
+            .run(self.handle_row_inserts(requests, ctx))
+            .await?
+    }
+
     pub async fn handle_deletes(
         &self,
         requests: DeleteRequests,
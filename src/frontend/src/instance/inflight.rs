@@ -0,0 +1,353 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracks in-flight requests so the frontend can drain them before shutting down.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use common_error::ext::BoxedError;
+use common_query::Output;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{
+    RecordBatch, RecordBatchMetrics, RecordBatchStream, SendableRecordBatchStream,
+};
+use datatypes::schema::SchemaRef;
+use futures::future::BoxFuture;
+use futures::{FutureExt, Stream};
+use snafu::{ensure, ResultExt};
+use tokio::sync::{watch, Notify};
+
+use crate::error::{Result, ShuttingDownSnafu};
+
+pub(crate) type InflightRequestsRef = Arc<InflightRequests>;
+
+/// Requests the frontend is handling.
+pub(crate) struct InflightRequests {
+    num_inflight: AtomicUsize,
+    /// Whether the frontend is shutting down, new requests are rejected once it is set.
+    draining: AtomicBool,
+    /// Notified when the last in-flight request finishes during draining.
+    drained: Notify,
+    /// Sends `true` to cancel all in-flight requests.
+    cancel_tx: watch::Sender<bool>,
+}
+
+impl Default for InflightRequests {
+    fn default() -> Self {
+        let (cancel_tx, _) = watch::channel(false);
+        Self {
+            num_inflight: AtomicUsize::new(0),
+            draining: AtomicBool::new(false),
+            drained: Notify::new(),
+            cancel_tx,
+        }
+    }
+}
+
+impl InflightRequests {
+    /// Runs the request `fut` as an in-flight request, the request finishes once
+    /// `fut` returns.
+    ///
+    /// Returns an error if the frontend is shutting down, or the request is cancelled
+    /// because it doesn't finish before the drain timeout.
+    pub(crate) async fn run<F: Future>(self: &Arc<Self>, fut: F) -> Result<F::Output> {
+        let _guard = self.enter()?;
+        let mut cancel_rx = self.cancel_tx.subscribe();
+        tokio::select! {
+            output = fut => Ok(output),
+            _ = cancel_rx.wait_for(|cancelled| *cancelled) => ShuttingDownSnafu.fail(),
+        }
+    }
+
+    /// Runs the query `fut` as an in-flight request like [InflightRequests::run()],
+    /// but the request stays in-flight until all streams in its output are dropped.
+    ///
+    /// Streams in the output return an error if they are cancelled.
+    pub(crate) async fn run_query<F>(self: &Arc<Self>, fut: F) -> Result<F::Output>
+    where
+        F: Future,
+        F::Output: InflightOutput,
+    {
+        let guard = self.enter()?;
+        let mut cancel_rx = self.cancel_tx.subscribe();
+        tokio::select! {
+            output = fut => Ok(output.hold(&guard)),
+            _ = cancel_rx.wait_for(|cancelled| *cancelled) => ShuttingDownSnafu.fail(),
+        }
+    }
+
+    /// Rejects new requests and waits until all in-flight requests finish or `timeout`
+    /// elapses, then cancels the remaining ones.
+    ///
+    /// Returns the number of requests cancelled.
+    pub(crate) async fn drain(&self, timeout: Duration) -> usize {
+        self.draining.store(true, Ordering::Relaxed);
+
+        let wait_drained = async {
+            while self.num_inflight() > 0 {
+                self.drained.notified().await;
+            }
+        };
+        if tokio::time::timeout(timeout, wait_drained).await.is_ok() {
+            return 0;
+        }
+
+        let num_cancelled = self.num_inflight();
+        let _ = self.cancel_tx.send(true);
+        num_cancelled
+    }
+
+    pub(crate) fn num_inflight(&self) -> usize {
+        self.num_inflight.load(Ordering::Relaxed)
+    }
+
+    fn enter(self: &Arc<Self>) -> Result<InflightGuard> {
+        self.num_inflight.fetch_add(1, Ordering::Relaxed);
+        // Creates the guard first so the counter is decreased if we reject the request.
+        let guard = InflightGuard {
+            requests: self.clone(),
+        };
+        ensure!(!self.draining.load(Ordering::Relaxed), ShuttingDownSnafu);
+        Ok(guard)
+    }
+}
+
+/// Marks a request in-flight until it is dropped.
+///
+/// Cloning the guard counts the request once more, so each stream of a request
+/// can hold its own guard.
+pub(crate) struct InflightGuard {
+    requests: InflightRequestsRef,
+}
+
+impl Clone for InflightGuard {
+    fn clone(&self) -> Self {
+        self.requests.num_inflight.fetch_add(1, Ordering::Relaxed);
+        Self {
+            requests: self.requests.clone(),
+        }
+    }
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        let prev = self.requests.num_inflight.fetch_sub(1, Ordering::Relaxed);
+        if prev == 1 && self.requests.draining.load(Ordering::Relaxed) {
+            // Stores a permit in case the drainer isn't waiting yet.
+            self.requests.drained.notify_one();
+        }
+    }
+}
+
+/// Output of a query that may still be in-flight after the query returns.
+pub(crate) trait InflightOutput {
+    /// Holds a clone of the `guard` in each stream of the output.
+    fn hold(self, guard: &InflightGuard) -> Self;
+}
+
+impl InflightOutput for Output {
+    fn hold(self, guard: &InflightGuard) -> Self {
+        match self {
+            Output::Stream(stream) => {
+                let mut cancel_rx = guard.requests.cancel_tx.subscribe();
+                // The guard holds the sender so waiting the receiver never fails.
+                let cancelled = async move {
+                    let _ = cancel_rx.wait_for(|cancelled| *cancelled).await;
+                }
+                .boxed();
+                Output::Stream(Box::pin(InflightStream {
+                    stream,
+                    cancelled: Some(cancelled),
+                    _guard: guard.clone(),
+                }))
+            }
+            Output::AffectedRows(_) | Output::RecordBatches(_) => self,
+        }
+    }
+}
+
+impl<T: InflightOutput, E> InflightOutput for std::result::Result<T, E> {
+    fn hold(self, guard: &InflightGuard) -> Self {
+        self.map(|output| output.hold(guard))
+    }
+}
+
+impl<T: InflightOutput> InflightOutput for Vec<T> {
+    fn hold(self, guard: &InflightGuard) -> Self {
+        self.into_iter().map(|output| output.hold(guard)).collect()
+    }
+}
+
+impl<K, T: InflightOutput> InflightOutput for (K, T) {
+    fn hold(self, guard: &InflightGuard) -> Self {
+        (self.0, self.1.hold(guard))
+    }
+}
+
+/// Stream that keeps its request in-flight until it is dropped.
+///
+/// It returns an error and stops once in-flight requests are cancelled.
+struct InflightStream {
+    stream: SendableRecordBatchStream,
+    /// Resolves when in-flight requests are cancelled, `None` after the stream is
+    /// cancelled.
+    cancelled: Option<BoxFuture<'static, ()>>,
+    _guard: InflightGuard,
+}
+
+impl RecordBatchStream for InflightStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        self.stream.metrics()
+    }
+}
+
+impl Stream for InflightStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(cancelled) = self.cancelled.as_mut() else {
+            return Poll::Ready(None);
+        };
+        if cancelled.poll_unpin(cx).is_ready() {
+            self.cancelled = None;
+            return Poll::Ready(Some(
+                ShuttingDownSnafu
+                    .fail()
+                    .map_err(BoxedError::new)
+                    .context(ExternalSnafu),
+            ));
+        }
+
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use common_recordbatch::util::collect;
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn test_drain_waits_inflight_requests() {
+        let requests = Arc::new(InflightRequests::default());
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn({
+            let requests = requests.clone();
+            async move {
+                requests
+                    .run(async move {
+                        started_tx.send(()).unwrap();
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        1
+                    })
+                    .await
+            }
+        });
+        started_rx.await.unwrap();
+        assert_eq!(1, requests.num_inflight());
+
+        // The request finishes before the drain returns.
+        assert_eq!(0, requests.drain(Duration::from_secs(10)).await);
+        assert_eq!(1, handle.await.unwrap().unwrap());
+        assert_eq!(0, requests.num_inflight());
+
+        // Rejects new requests.
+        assert_matches!(
+            requests.run(async { 2 }).await,
+            Err(Error::ShuttingDown { .. })
+        );
+        assert_eq!(0, requests.num_inflight());
+    }
+
+    #[tokio::test]
+    async fn test_drain_cancels_hung_requests() {
+        let requests = Arc::new(InflightRequests::default());
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn({
+            let requests = requests.clone();
+            async move {
+                requests
+                    .run(async move {
+                        started_tx.send(()).unwrap();
+                        futures::future::pending::<()>().await
+                    })
+                    .await
+            }
+        });
+        started_rx.await.unwrap();
+
+        assert_eq!(1, requests.drain(Duration::from_millis(50)).await);
+        assert_matches!(handle.await.unwrap(), Err(Error::ShuttingDown { .. }));
+        assert_eq!(0, requests.num_inflight());
+    }
+
+    fn new_stream_output() -> Output {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "v",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        Output::Stream(RecordBatches::try_new(schema, vec![]).unwrap().as_stream())
+    }
+
+    #[tokio::test]
+    async fn test_stream_holds_inflight_request() {
+        let requests = Arc::new(InflightRequests::default());
+        let outputs = requests
+            .run_query(async {
+                vec![
+                    Ok::<_, Error>(new_stream_output()),
+                    Ok(Output::AffectedRows(1)),
+                ]
+            })
+            .await
+            .unwrap();
+        // The request is in-flight until the stream is dropped.
+        assert_eq!(1, requests.num_inflight());
+        drop(outputs);
+        assert_eq!(0, requests.num_inflight());
+    }
+
+    #[tokio::test]
+    async fn test_drain_cancels_streams() {
+        let requests = Arc::new(InflightRequests::default());
+        let output = requests
+            .run_query(async { new_stream_output() })
+            .await
+            .unwrap();
+        assert_eq!(1, requests.drain(Duration::from_millis(50)).await);
+
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        assert!(collect(stream).await.is_err());
+        assert_eq!(0, requests.num_inflight());
+    }
+}
@@ -16,7 +16,7 @@ use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
 use common_error::ext::BoxedError;
 use servers::error::AuthSnafu;
-use servers::influxdb::InfluxdbRequest;
+use servers::influxdb::{to_row_insert_requests, InfluxdbRequest};
 use servers::query_handler::InfluxdbLineProtocolHandler;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
@@ -36,9 +36,9 @@ impl InfluxdbLineProtocolHandler for Instance {
             .check_permission(ctx.current_user(), PermissionReq::LineProtocol)
             .context(AuthSnafu)?;
 
-        let requests = request.try_into()?;
+        let requests = to_row_insert_requests(request, ctx.timestamp_precision_action())?;
         let _ = self
-            .handle_row_inserts(requests, ctx)
+            .handle_inflight_row_inserts(requests, ctx)
             .await
             .map_err(BoxedError::new)
             .context(servers::error::ExecuteGrpcQuerySnafu)?;
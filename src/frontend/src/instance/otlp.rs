@@ -45,7 +45,7 @@ impl OpenTelemetryProtocolHandler for Instance {
             .context(AuthSnafu)?;
         let (requests, rows) = otlp::metrics::to_grpc_insert_requests(request)?;
         let _ = self
-            .handle_row_inserts(requests, ctx)
+            .handle_inflight_row_inserts(requests, ctx)
             .await
             .map_err(BoxedError::new)
             .context(error::ExecuteGrpcQuerySnafu)?;
@@ -81,7 +81,7 @@ impl OpenTelemetryProtocolHandler for Instance {
         let (requests, rows) = otlp::trace::to_grpc_insert_requests(table_name, spans)?;
 
         let _ = self
-            .handle_row_inserts(requests, ctx)
+            .handle_inflight_row_inserts(requests, ctx)
             .await
             .map_err(BoxedError::new)
             .context(error::ExecuteGrpcQuerySnafu)?;
@@ -132,7 +132,7 @@ impl Instance {
         let mut results = Vec::with_capacity(queries.len());
 
         let catalog_name = ctx.current_catalog();
-        let schema_name = ctx.current_schema();
+        let schema_name = &ctx.current_schema();
 
         for query in queries {
             let table_name = prom_store::table_name(query)?;
@@ -161,7 +161,7 @@ impl PromStoreProtocolHandler for Instance {
             .context(AuthSnafu)?;
         let (requests, samples) = prom_store::to_grpc_row_insert_requests(request)?;
         let _ = self
-            .handle_row_inserts(requests, ctx)
+            .handle_inflight_row_inserts(requests, ctx)
             .await
             .map_err(BoxedError::new)
             .context(error::ExecuteGrpcQuerySnafu)?;
@@ -184,7 +184,12 @@ impl PromStoreProtocolHandler for Instance {
         let response_type = negotiate_response_type(&request.accepted_response_types)?;
 
         // TODO(dennis): use read_hints to speedup query if possible
-        let results = self.handle_remote_queries(ctx, &request.queries).await?;
+        let results = self
+            .inflight_requests
+            .run_query(self.handle_remote_queries(ctx, &request.queries))
+            .await
+            .map_err(BoxedError::new)
+            .context(error::OtherSnafu)??;
 
         match response_type {
             ResponseType::Samples => {
@@ -0,0 +1,289 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Enforces the [ResultLimit] of a session on the outputs of queries.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use common_error::ext::BoxedError;
+use common_query::Output;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{
+    RecordBatch, RecordBatchMetrics, RecordBatchStream, RecordBatches, SendableRecordBatchStream,
+};
+use common_telemetry::warn;
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use session::context::{QueryContextRef, ResultLimit, ResultLimitAction};
+use snafu::ResultExt;
+
+use crate::error::{Result, ResultLimitExceededSnafu};
+
+/// Limits the results in the `output` by the [ResultLimit] of the `query_ctx`.
+///
+/// Streams are limited while they are polled, so they are never buffered.
+pub(crate) fn limit_output(output: Output, query_ctx: &QueryContextRef) -> Result<Output> {
+    let limit = query_ctx.result_limit();
+    if limit.is_unlimited() {
+        return Ok(output);
+    }
+
+    let mut limiter = ResultLimiter::new(limit, query_ctx.clone());
+    match output {
+        Output::AffectedRows(_) => Ok(output),
+        Output::RecordBatches(batches) => {
+            let schema = batches.schema();
+            let mut limited = Vec::new();
+            for batch in batches.take() {
+                match limiter.limit(batch)? {
+                    Some(batch) => limited.push(batch),
+                    None => break,
+                }
+            }
+            // Safety: the batches have the same schema as the input.
+            Ok(Output::RecordBatches(
+                RecordBatches::try_new(schema, limited).unwrap(),
+            ))
+        }
+        Output::Stream(stream) => Ok(Output::Stream(Box::pin(ResultLimitStream {
+            stream,
+            limiter,
+        }))),
+    }
+}
+
+/// Counts the results returned and cuts them at the limit.
+struct ResultLimiter {
+    limit: ResultLimit,
+    query_ctx: QueryContextRef,
+    num_rows: usize,
+    num_bytes: usize,
+    /// Whether the limit is reached.
+    reached: bool,
+}
+
+impl ResultLimiter {
+    fn new(limit: ResultLimit, query_ctx: QueryContextRef) -> Self {
+        Self {
+            limit,
+            query_ctx,
+            num_rows: 0,
+            num_bytes: 0,
+            reached: false,
+        }
+    }
+
+    /// Returns the part of the `batch` within the limit, or `None` if the limit is
+    /// already reached.
+    fn limit(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        if self.reached {
+            return Ok(None);
+        }
+
+        let batch_rows = batch.num_rows();
+        let batch_bytes = batch.df_record_batch().get_array_memory_size();
+        let mut rows_to_keep = batch_rows;
+        let mut exceeded = None;
+        if let Some(max_rows) = self.limit.max_rows {
+            if self.num_rows + batch_rows > max_rows {
+                rows_to_keep = max_rows - self.num_rows;
+                exceeded = Some(format!("max_result_rows {max_rows}"));
+            }
+        }
+        if let Some(max_bytes) = self.limit.max_bytes {
+            if batch_rows > 0 && self.num_bytes + batch_bytes > max_bytes {
+                // Assumes rows in the batch have the same size.
+                let rows_fit = (max_bytes - self.num_bytes) * batch_rows / batch_bytes;
+                if rows_fit < rows_to_keep {
+                    rows_to_keep = rows_fit;
+                    exceeded = Some(format!("max_result_bytes {max_bytes}"));
+                }
+            }
+        }
+
+        let Some(exceeded) = exceeded else {
+            self.num_rows += batch_rows;
+            self.num_bytes += batch_bytes;
+            return Ok(Some(batch));
+        };
+
+        self.reached = true;
+        let num_rows = self.num_rows + rows_to_keep;
+        match self.limit.action {
+            ResultLimitAction::Error => ResultLimitExceededSnafu { limit: exceeded }.fail(),
+            ResultLimitAction::Truncate => {
+                let warning = format!("Results are truncated to {num_rows} rows by {exceeded}");
+                warn!("{warning}");
+                self.query_ctx.add_warning(warning);
+                self.num_rows = num_rows;
+                if rows_to_keep == 0 {
+                    Ok(None)
+                } else {
+                    Ok(Some(batch.slice(0, rows_to_keep)))
+                }
+            }
+        }
+    }
+}
+
+/// Stream that stops polling its input once the limit is reached.
+struct ResultLimitStream {
+    stream: SendableRecordBatchStream,
+    limiter: ResultLimiter,
+}
+
+impl RecordBatchStream for ResultLimitStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        self.stream.metrics()
+    }
+}
+
+impl Stream for ResultLimitStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.limiter.reached {
+            return Poll::Ready(None);
+        }
+
+        match self.stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(batch))) => Poll::Ready(
+                self.limiter
+                    .limit(batch)
+                    .map_err(BoxedError::new)
+                    .context(ExternalSnafu)
+                    .transpose(),
+            ),
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_recordbatch::util::collect;
+    use datatypes::prelude::{ConcreteDataType, VectorRef};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::Int32Vector;
+    use session::context::QueryContextBuilder;
+
+    use super::*;
+
+    fn new_batches(num_batches: usize, rows_per_batch: usize) -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "v",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batches = (0..num_batches)
+            .map(|i| {
+                let start = (i * rows_per_batch) as i32;
+                let column: VectorRef = Arc::new(Int32Vector::from_values(
+                    start..start + rows_per_batch as i32,
+                ));
+                RecordBatch::new(schema.clone(), vec![column]).unwrap()
+            })
+            .collect();
+        RecordBatches::try_new(schema, batches).unwrap()
+    }
+
+    fn new_query_ctx(limit: ResultLimit) -> QueryContextRef {
+        QueryContextBuilder::default().result_limit(limit).build()
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[tokio::test]
+    async fn test_truncate_stream() {
+        let query_ctx = new_query_ctx(ResultLimit {
+            max_rows: Some(5),
+            ..Default::default()
+        });
+        let stream = new_batches(3, 2).as_stream();
+        let Output::Stream(stream) = limit_output(Output::Stream(stream), &query_ctx).unwrap()
+        else {
+            unreachable!()
+        };
+        assert!(query_ctx.warnings().is_empty());
+
+        let batches = collect(stream).await.unwrap();
+        assert_eq!(5, num_rows(&batches));
+        assert_eq!(
+            vec!["Results are truncated to 5 rows by max_result_rows 5".to_string()],
+            query_ctx.warnings()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_on_exceed() {
+        let query_ctx = new_query_ctx(ResultLimit {
+            max_rows: Some(3),
+            action: ResultLimitAction::Error,
+            ..Default::default()
+        });
+        let stream = new_batches(3, 2).as_stream();
+        let Output::Stream(stream) = limit_output(Output::Stream(stream), &query_ctx).unwrap()
+        else {
+            unreachable!()
+        };
+        let err = collect(stream).await.unwrap_err();
+        assert!(
+            err.to_string().contains("External error"),
+            "unexpected error: {err}"
+        );
+
+        let output = Output::RecordBatches(new_batches(3, 2));
+        let err = limit_output(output, &query_ctx).unwrap_err();
+        assert!(
+            err.to_string().contains("max_result_rows 3"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_truncate_by_bytes() {
+        let batches = new_batches(4, 100);
+        let batch_bytes = batches
+            .iter()
+            .next()
+            .unwrap()
+            .df_record_batch()
+            .get_array_memory_size();
+        // Allows one batch and a half.
+        let max_bytes = batch_bytes * 3 / 2;
+        let query_ctx = new_query_ctx(ResultLimit {
+            max_bytes: Some(max_bytes),
+            ..Default::default()
+        });
+        let Output::RecordBatches(limited) =
+            limit_output(Output::RecordBatches(batches), &query_ctx).unwrap()
+        else {
+            unreachable!()
+        };
+        let limited = limited.take();
+        assert_eq!(2, limited.len());
+        let expect_rows = 100 + (max_bytes - batch_bytes) * 100 / batch_bytes;
+        assert_eq!(expect_rows, num_rows(&limited));
+        assert_eq!(1, query_ctx.warnings().len());
+    }
+}
@@ -27,6 +27,8 @@ use common_telemetry::tracing_context::{FutureExt, TracingContext};
 use datanode::region_server::RegionServer;
 use servers::grpc::region_server::RegionServerHandler;
 use snafu::{OptionExt, ResultExt};
+use store_api::region_request::{RegionRequest as RegionEngineRequest, RegionSyncRequest};
+use store_api::storage::RegionId;
 
 use crate::error::{InvalidRegionRequestSnafu, InvokeRegionServerSnafu, Result};
 
@@ -96,4 +98,37 @@ impl Datanode for RegionInvoker {
             .map_err(BoxedError::new)
             .context(meta_error::ExternalSnafu)
     }
+
+    async fn sync_region(&self, region_id: RegionId) -> MetaResult<()> {
+        let _ = self
+            .region_server
+            .handle_request(
+                region_id,
+                RegionEngineRequest::Sync(RegionSyncRequest::default()),
+            )
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)?;
+        Ok(())
+    }
+
+    async fn set_region_frozen(&self, region_id: RegionId, frozen: bool) -> MetaResult<()> {
+        self.region_server
+            .set_frozen(region_id, frozen)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
+
+    async fn set_region_compaction_paused(
+        &self,
+        region_id: RegionId,
+        paused: bool,
+    ) -> MetaResult<()> {
+        self.region_server
+            .set_compaction_paused(region_id, paused)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
 }
@@ -22,6 +22,7 @@ use servers::error::InternalIoSnafu;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::greptime_handler::GreptimeRequestHandler;
 use servers::grpc::GrpcServerConfig;
+use servers::health::HealthIndicatorRef;
 use servers::http::HttpServerBuilder;
 use servers::metrics_handler::MetricsHandler;
 use servers::mysql::server::{MysqlServer, MysqlSpawnConfig, MysqlSpawnRef};
@@ -39,11 +40,22 @@ use crate::service_config::GrpcOptions;
 
 pub struct Services {
     plugins: Plugins,
+    health_indicators: Vec<HealthIndicatorRef>,
 }
 
 impl Services {
     pub fn new(plugins: Plugins) -> Self {
-        Self { plugins }
+        Self {
+            plugins,
+            health_indicators: Vec::new(),
+        }
+    }
+
+    /// Sets the components the `/health` endpoint of the HTTP server reports, e.g. the
+    /// components of the datanode in standalone mode.
+    pub fn with_health_indicators(mut self, health_indicators: Vec<HealthIndicatorRef>) -> Self {
+        self.health_indicators = health_indicators;
+        self
     }
 
     pub fn grpc_server_builder(opts: &GrpcOptions) -> Result<GrpcServerBuilder> {
@@ -143,6 +155,10 @@ impl Services {
                 let _ = http_server_builder.with_otlp_handler(instance.clone());
             }
 
+            for indicator in &self.health_indicators {
+                let _ = http_server_builder.with_health_indicator(indicator.clone());
+            }
+
             let http_server = http_server_builder
                 .with_metrics_handler(MetricsHandler)
                 .with_script_handler(instance.clone())
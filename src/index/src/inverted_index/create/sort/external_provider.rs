@@ -36,4 +36,11 @@ pub trait ExternalTempFileProvider: Send + Sync {
     ///
     /// `index_name`: the name of the index to retrieve intermediate files for
     async fn read_all(&self, index_name: &str) -> Result<Vec<Box<dyn AsyncRead + Unpin + Send>>>;
+
+    /// Removes an intermediate file associated with a specific index, e.g. after it
+    /// is merged into another file.
+    ///
+    /// - `index_name`: the name of the index the file is associated with
+    /// - `file_id`: the identifier of the file to remove
+    async fn remove(&self, index_name: &str, file_id: &str) -> Result<()>;
 }
@@ -61,6 +61,13 @@ pub struct ExternalSorter {
     /// The memory usage threshold at which the buffer should be dumped to an external file.
     /// `None` indicates that the buffer should never be dumped.
     memory_usage_threshold: Option<usize>,
+
+    /// Ids of external files (sorted runs) dumped so far
+    run_file_ids: Vec<String>,
+
+    /// The max number of sorted runs. Runs are merged into one when there are more runs.
+    /// `None` indicates that the number of runs is unlimited.
+    max_runs: Option<usize>,
 }
 
 #[async_trait]
@@ -87,29 +94,15 @@ impl Sorter for ExternalSorter {
     /// Finalizes the sorting operation, merging data from both in-memory buffer and external files
     /// into a sorted stream
     async fn output(&mut self) -> Result<SortOutput> {
-        let readers = self.temp_file_provider.read_all(&self.index_name).await?;
-
-        // TODO(zhongzc): k-way merge instead of 2-way merge
-
-        let mut tree_nodes: VecDeque<SortedStream> = VecDeque::with_capacity(readers.len() + 1);
-        tree_nodes.push_back(Box::new(stream::iter(
+        let in_memory: SortedStream = Box::new(stream::iter(
             mem::take(&mut self.values_buffer).into_iter().map(Ok),
-        )));
-        for reader in readers {
-            tree_nodes.push_back(IntermediateReader::new(reader).into_stream().await?);
-        }
-
-        while tree_nodes.len() >= 2 {
-            // every turn, the length of tree_nodes will be reduced by 1 until only one stream left
-            let stream1 = tree_nodes.pop_front().unwrap();
-            let stream2 = tree_nodes.pop_front().unwrap();
-            let merged_stream = MergeSortedStream::merge(stream1, stream2);
-            tree_nodes.push_back(merged_stream);
-        }
+        ));
+        let mut streams = vec![in_memory];
+        streams.extend(self.read_runs().await?);
 
         Ok(SortOutput {
             segment_null_bitmap: mem::take(&mut self.segment_null_bitmap),
-            sorted_stream: tree_nodes.pop_front().unwrap(),
+            sorted_stream: merge_streams(streams),
             total_row_count: self.total_row_count,
         })
     }
@@ -135,21 +128,35 @@ impl ExternalSorter {
 
             current_memory_usage: 0,
             memory_usage_threshold,
+
+            run_file_ids: Vec::new(),
+            max_runs: None,
         }
     }
 
+    /// Limits the number of sorted runs dumped to external files, runs are merged into
+    /// one run when there are more than `max_runs` runs
+    pub fn with_max_runs(mut self, max_runs: Option<usize>) -> Self {
+        self.max_runs = max_runs;
+        self
+    }
+
     /// Generates a factory function that creates new `ExternalSorter` instances
     pub fn factory(
         temp_file_provider: Arc<dyn ExternalTempFileProvider>,
         memory_usage_threshold: Option<usize>,
+        max_runs: Option<usize>,
     ) -> SorterFactory {
         Box::new(move |index_name, segment_row_count| {
-            Box::new(Self::new(
-                index_name,
-                temp_file_provider.clone(),
-                segment_row_count,
-                memory_usage_threshold,
-            ))
+            Box::new(
+                Self::new(
+                    index_name,
+                    temp_file_provider.clone(),
+                    segment_row_count,
+                    memory_usage_threshold,
+                )
+                .with_max_runs(max_runs),
+            )
         })
     }
 
@@ -202,7 +209,52 @@ impl ExternalSorter {
             logging::debug!("Dumped {entries} entries ({memory_usage} bytes) to intermediate file {file_id} for index {index_name}")
         ).inspect_err(|e|
             logging::error!("Failed to dump {entries} entries to intermediate file {file_id} for index {index_name}. Error: {e}")
-        )
+        )?;
+        self.run_file_ids.push(file_id.clone());
+
+        self.may_merge_runs().await
+    }
+
+    /// Merges all sorted runs into one run if there are more runs than the limit,
+    /// so the number of external files is bounded.
+    async fn may_merge_runs(&mut self) -> Result<()> {
+        match self.max_runs {
+            Some(max_runs) if self.run_file_ids.len() > max_runs => {}
+            _ => return Ok(()),
+        }
+
+        // Named by the first and the last run merged, the first part still orders the
+        // file among other runs.
+        let file_id = format!(
+            "{}-{}",
+            self.run_file_ids.first().unwrap(),
+            self.run_file_ids.last().unwrap()
+        );
+        let index_name = &self.index_name;
+        let merged_stream = merge_streams(self.read_runs().await?);
+        let writer = self.temp_file_provider.create(index_name, &file_id).await?;
+        IntermediateWriter::new(writer)
+            .write_stream(merged_stream)
+            .await?;
+
+        for run_file_id in mem::take(&mut self.run_file_ids) {
+            self.temp_file_provider
+                .remove(index_name, &run_file_id)
+                .await?;
+        }
+        logging::debug!("Merged sorted runs to intermediate file {file_id} for index {index_name}");
+        self.run_file_ids.push(file_id);
+        Ok(())
+    }
+
+    /// Reads all sorted runs dumped to external files
+    async fn read_runs(&self) -> Result<Vec<SortedStream>> {
+        let readers = self.temp_file_provider.read_all(&self.index_name).await?;
+        let mut streams = Vec::with_capacity(readers.len());
+        for reader in readers {
+            streams.push(IntermediateReader::new(reader).into_stream().await?);
+        }
+        Ok(streams)
     }
 
     /// Determines the segment index range for the row index range
@@ -219,6 +271,24 @@ impl ExternalSorter {
     }
 }
 
+/// Merges sorted streams into one sorted stream
+fn merge_streams(streams: Vec<SortedStream>) -> SortedStream {
+    // TODO(zhongzc): k-way merge instead of 2-way merge
+
+    let mut tree_nodes: VecDeque<SortedStream> = streams.into();
+    if tree_nodes.is_empty() {
+        return Box::new(stream::empty());
+    }
+    while tree_nodes.len() >= 2 {
+        // every turn, the length of tree_nodes will be reduced by 1 until only one stream left
+        let stream1 = tree_nodes.pop_front().unwrap();
+        let stream2 = tree_nodes.pop_front().unwrap();
+        let merged_stream = MergeSortedStream::merge(stream1, stream2);
+        tree_nodes.push_back(merged_stream);
+    }
+    tree_nodes.pop_front().unwrap()
+}
+
 /// Sets the bits within the specified range in the given `BitVec` to true
 fn set_bits(bitmap: &mut BitVec, index_range: RangeInclusive<usize>) {
     if *index_range.end() >= bitmap.len() {
@@ -385,6 +455,80 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_external_sorter_max_runs() {
+        let mut mock_provider = MockExternalTempFileProvider::new();
+
+        let mock_files: Arc<Mutex<HashMap<String, Box<dyn AsyncRead + Unpin + Send>>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let created_files = Arc::new(Mutex::new(Vec::new()));
+
+        mock_provider.expect_create().returning({
+            let files = Arc::clone(&mock_files);
+            let created_files = Arc::clone(&created_files);
+            move |_, file_id| {
+                let mut files = files.lock().unwrap();
+                // Large enough to hold the whole file.
+                let (writer, reader) = duplex(64 * 1024 * 1024);
+                files.insert(file_id.to_string(), Box::new(reader.compat()));
+                created_files.lock().unwrap().push(file_id.to_string());
+                Ok(Box::new(writer.compat_write()))
+            }
+        });
+        mock_provider.expect_read_all().returning({
+            let files = Arc::clone(&mock_files);
+            move |_| {
+                let mut files = files.lock().unwrap();
+                Ok(files.drain().map(|f| f.1).collect::<Vec<_>>())
+            }
+        });
+        mock_provider.expect_remove().returning({
+            let files = Arc::clone(&mock_files);
+            move |_, file_id| {
+                files.lock().unwrap().remove(file_id);
+                Ok(())
+            }
+        });
+
+        let segment_row_count = 10;
+        let mut sorter = ExternalSorter::new(
+            "test".to_owned(),
+            Arc::new(mock_provider),
+            NonZeroUsize::new(segment_row_count).unwrap(),
+            Some(1024),
+        )
+        .with_max_runs(Some(2));
+
+        let (mock_values, mut sorted_result) =
+            shuffle_values_and_sorted_result(1000, segment_row_count);
+        for value in mock_values {
+            sorter.push(value.as_deref()).await.unwrap();
+        }
+        assert!(sorter.run_file_ids.len() <= 2);
+
+        let SortOutput {
+            segment_null_bitmap,
+            mut sorted_stream,
+            total_row_count,
+        } = sorter.output().await.unwrap();
+        // Runs are merged while pushing values.
+        let created_files = created_files.lock().unwrap();
+        assert!(created_files.iter().any(|file_id| file_id.contains('-')));
+
+        assert_eq!(total_row_count, 1000);
+        let n = sorted_result.remove(&None);
+        assert_eq!(
+            segment_null_bitmap.iter_ones().collect::<Vec<_>>(),
+            n.unwrap_or_default()
+        );
+        for (value, offsets) in sorted_result {
+            let item = sorted_stream.next().await.unwrap().unwrap();
+            assert_eq!(item.0, value.unwrap());
+            assert_eq!(item.1.iter_ones().collect::<Vec<_>>(), offsets);
+        }
+        assert!(sorted_stream.next().await.is_none());
+    }
+
     fn random_option_bytes(size: usize) -> Option<Vec<u8>> {
         let mut rng = rand::thread_rng();
 
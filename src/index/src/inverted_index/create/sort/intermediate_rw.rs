@@ -15,15 +15,18 @@
 mod codec_v1;
 
 use std::collections::BTreeMap;
+use std::io;
 
 use asynchronous_codec::{FramedRead, FramedWrite};
 use common_base::BitVec;
+use common_error::ext::BoxedError;
 use futures::{stream, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, StreamExt};
-use snafu::ResultExt;
+use snafu::{location, IntoError, Location, ResultExt};
 
 use crate::inverted_index::create::sort::SortedStream;
 use crate::inverted_index::error::{
-    CloseSnafu, FlushSnafu, ReadSnafu, Result, UnknownIntermediateCodecMagicSnafu, WriteSnafu,
+    CloseSnafu, Error, FlushSnafu, ReadSnafu, Result, UnknownIntermediateCodecMagicSnafu,
+    WriteSnafu,
 };
 use crate::inverted_index::Bytes;
 
@@ -39,21 +42,66 @@ impl<W: AsyncWrite + Unpin> IntermediateWriter<W> {
     }
 
     /// Serializes and writes all provided values to the wrapped writer
-    pub async fn write_all(mut self, values: BTreeMap<Bytes, BitVec>) -> Result<()> {
+    pub async fn write_all(self, values: BTreeMap<Bytes, BitVec>) -> Result<()> {
+        let value_stream = stream::iter(values.into_iter().map(Ok));
+        self.write_stream(Box::new(value_stream)).await
+    }
+
+    /// Serializes and writes all values of the sorted stream to the wrapped writer
+    pub async fn write_stream(mut self, value_stream: SortedStream) -> Result<()> {
         let (codec_magic, encoder) = (codec_v1::CODEC_V1_MAGIC, codec_v1::IntermediateCodecV1);
 
         self.writer
             .write_all(codec_magic)
             .await
-            .context(WriteSnafu)?;
+            .map_err(|e| convert_io_error(e, WriteSnafu))?;
 
-        let value_stream = stream::iter(values.into_iter().map(Ok));
         let frame_write = FramedWrite::new(&mut self.writer, encoder);
         value_stream.forward(frame_write).await?;
 
-        self.writer.flush().await.context(FlushSnafu)?;
-        self.writer.close().await.context(CloseSnafu)
+        self.writer
+            .flush()
+            .await
+            .map_err(|e| convert_io_error(e, FlushSnafu))?;
+        self.writer
+            .close()
+            .await
+            .map_err(|e| convert_io_error(e, CloseSnafu))
+    }
+}
+
+/// Converts the IO `error` of a writer by the `context`. If the writer of the external
+/// temp file provider fails with a [`BoxedError`], returns it as [`Error::External`]
+/// instead, e.g. the provider runs out of space.
+fn convert_io_error<C>(error: io::Error, context: C) -> Error
+where
+    C: IntoError<Error, Source = io::Error>,
+{
+    match try_into_external(error) {
+        Ok(external) => external,
+        Err(error) => context.into_error(error),
+    }
+}
+
+/// Takes the [`BoxedError`] wrapped in the IO `error` as [`Error::External`], returns
+/// the IO `error` back if it doesn't wrap one.
+fn try_into_external(error: io::Error) -> std::result::Result<Error, io::Error> {
+    if !error
+        .get_ref()
+        .is_some_and(|inner| inner.is::<BoxedError>())
+    {
+        return Err(error);
     }
+    // Safety: the inner error is checked above.
+    let source = error
+        .into_inner()
+        .unwrap()
+        .downcast::<BoxedError>()
+        .unwrap();
+    Ok(Error::External {
+        source: *source,
+        location: location!(),
+    })
 }
 
 /// Reads intermediate serialized data from an `AsyncRead` source and converts it to a [`SortedStream`]
@@ -85,6 +133,8 @@ impl<R: AsyncRead + Unpin + Send + 'static> IntermediateReader<R> {
 
 #[cfg(test)]
 mod tests {
+    use common_error::mock::MockError;
+    use common_error::status_code::StatusCode;
     use futures::io::Cursor;
 
     use super::*;
@@ -138,4 +188,56 @@ mod tests {
             Err(Error::UnknownIntermediateCodecMagic { .. })
         ))
     }
+
+    /// A writer that fails with a [`BoxedError`] after writing `capacity` bytes.
+    struct ExternalFailWriter {
+        capacity: usize,
+    }
+
+    impl AsyncWrite for ExternalFailWriter {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            if buf.len() > self.capacity {
+                let error = BoxedError::new(MockError::new(StatusCode::RuntimeResourcesExhausted));
+                return std::task::Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, error)));
+            }
+            self.capacity -= buf.len();
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_intermediate_write_external_error() {
+        let values = BTreeMap::from_iter([
+            (Bytes::from("a"), BitVec::from_slice(&[0b10101010])),
+            (Bytes::from("b"), BitVec::from_slice(&[0b01010101])),
+        ]);
+
+        // Fails to write the magic.
+        let writer = IntermediateWriter::new(ExternalFailWriter { capacity: 0 });
+        let result = writer.write_all(values.clone()).await;
+        assert!(matches!(result, Err(Error::External { .. })), "{result:?}");
+
+        // Fails to write values.
+        let writer = IntermediateWriter::new(ExternalFailWriter { capacity: 4 });
+        let result = writer.write_all(values).await;
+        assert!(matches!(result, Err(Error::External { .. })), "{result:?}");
+    }
 }
@@ -19,6 +19,7 @@ use bytes::{Buf, BufMut};
 use common_base::BitVec;
 use snafu::{location, Location};
 
+use super::try_into_external;
 use crate::inverted_index::error::{Error, Result};
 use crate::inverted_index::Bytes;
 
@@ -104,10 +105,10 @@ impl Decoder for IntermediateCodecV1 {
 /// Required for [`Encoder`] and [`Decoder`] implementations.
 impl From<io::Error> for Error {
     fn from(error: io::Error) -> Self {
-        Error::CommonIoError {
+        try_into_external(error).unwrap_or_else(|error| Error::CommonIoError {
             error,
             location: location!(),
-        }
+        })
     }
 }
 
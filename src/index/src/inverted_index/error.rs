@@ -175,6 +175,26 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Returns true if the index is corrupt, e.g. it's truncated or can't be decoded.
+    /// IO errors other than an unexpected end of the index aren't decode errors as
+    /// they might be temporary.
+    pub fn is_decode_error(&self) -> bool {
+        match self {
+            Error::Seek { error, .. } | Error::Read { error, .. } => {
+                error.kind() == std::io::ErrorKind::UnexpectedEof
+            }
+            Error::UnexpectedBlobSize { .. }
+            | Error::UnexpectedFooterPayloadSize { .. }
+            | Error::UnexpectedOffsetSize { .. }
+            | Error::UnexpectedZeroSegmentRowCount { .. }
+            | Error::DecodeFst { .. }
+            | Error::DecodeProto { .. } => true,
+            _ => false,
+        }
+    }
+}
+
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         use Error::*;
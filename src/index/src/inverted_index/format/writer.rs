@@ -27,6 +27,17 @@ use crate::inverted_index::Bytes;
 
 pub type ValueStream = Box<dyn Stream<Item = Result<(Bytes, BitVec)>> + Send + Unpin>;
 
+/// Statistics of an index, collected while writing the index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    /// Number of distinct non-null values.
+    pub distinct_count: u64,
+    /// Size of the index in bytes.
+    pub index_size: u64,
+    /// Total length of the postings (number of segments) of all values.
+    pub postings_len: u64,
+}
+
 /// Trait for writing inverted index data to underlying storage.
 #[mockall::automock]
 #[async_trait]
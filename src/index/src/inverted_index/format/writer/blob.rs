@@ -12,18 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 
 use async_trait::async_trait;
 use common_base::BitVec;
-use futures::{AsyncWrite, AsyncWriteExt};
+use futures::{AsyncWrite, AsyncWriteExt, StreamExt};
 use greptime_proto::v1::index::InvertedIndexMetas;
 use prost::Message;
 use snafu::ResultExt;
 
 use crate::inverted_index::error::{CloseSnafu, FlushSnafu, Result, WriteSnafu};
 use crate::inverted_index::format::writer::single::SingleIndexWriter;
-use crate::inverted_index::format::writer::{InvertedIndexWriter, ValueStream};
+use crate::inverted_index::format::writer::{IndexStats, InvertedIndexWriter, ValueStream};
 
 /// `InvertedIndexBlobWriter`, implemented [`InvertedIndexWriter`], manages
 /// writing of an inverted index to a blob storage.
@@ -36,6 +37,9 @@ pub struct InvertedIndexBlobWriter<W> {
 
     /// Metadata about each index that has been written  
     metas: InvertedIndexMetas,
+
+    /// Statistics of each index that has been written
+    stats: HashMap<String, IndexStats>,
 }
 
 #[async_trait]
@@ -46,6 +50,13 @@ impl<W: AsyncWrite + Send + Unpin> InvertedIndexWriter for InvertedIndexBlobWrit
         null_bitmap: BitVec,
         values: ValueStream,
     ) -> Result<()> {
+        // Counts the postings while writing values so we don't need another pass.
+        let mut postings_len = 0;
+        let values = values.inspect(|item| {
+            if let Ok((_, bitmap)) = item {
+                postings_len += bitmap.count_ones() as u64;
+            }
+        });
         let single_writer = SingleIndexWriter::new(
             name.clone(),
             self.written_size,
@@ -56,6 +67,16 @@ impl<W: AsyncWrite + Send + Unpin> InvertedIndexWriter for InvertedIndexBlobWrit
         let metadata = single_writer.write().await?;
 
         self.written_size += metadata.inverted_index_size;
+        let stats = IndexStats {
+            distinct_count: metadata
+                .stats
+                .as_ref()
+                .map(|stats| stats.distinct_count)
+                .unwrap_or_default(),
+            index_size: metadata.inverted_index_size,
+            postings_len,
+        };
+        self.stats.insert(name.clone(), stats);
         self.metas.metas.insert(name, metadata);
 
         Ok(())
@@ -93,8 +114,14 @@ impl<W: AsyncWrite + Send + Unpin> InvertedIndexBlobWriter<W> {
             blob_writer,
             written_size: 0,
             metas: InvertedIndexMetas::default(),
+            stats: HashMap::new(),
         }
     }
+
+    /// Returns statistics of indexes written, keyed by index names.
+    pub fn stats(&self) -> &HashMap<String, IndexStats> {
+        &self.stats
+    }
 }
 
 #[cfg(test)]
@@ -205,4 +232,39 @@ mod tests {
         let bitmap = reader.bitmap(tag1, offset, size).await.unwrap();
         assert_eq!(bitmap, BitVec::from_slice(&[0b0000_0001]));
     }
+
+    #[tokio::test]
+    async fn test_inverted_index_blob_writer_stats() {
+        let mut blob = Vec::new();
+        let mut writer = InvertedIndexBlobWriter::new(&mut blob);
+        writer
+            .add_index(
+                "tag0".to_string(),
+                BitVec::from_slice(&[0b0000_0001]),
+                Box::new(stream::iter(vec![
+                    Ok((Bytes::from("a"), BitVec::from_slice(&[0b0000_0110]))),
+                    Ok((Bytes::from("b"), BitVec::from_slice(&[0b1111_0000]))),
+                ])),
+            )
+            .await
+            .unwrap();
+        writer
+            .finish(8, NonZeroUsize::new(1).unwrap())
+            .await
+            .unwrap();
+
+        let stats = writer.stats().get("tag0").unwrap().clone();
+        let cursor = Cursor::new(blob);
+        let mut reader = InvertedIndexBlobReader::new(cursor);
+        let metadata = reader.metadata().await.unwrap();
+        let tag0 = metadata.metas.get("tag0").unwrap();
+        assert_eq!(
+            IndexStats {
+                distinct_count: 2,
+                index_size: tag0.inverted_index_size,
+                postings_len: 6,
+            },
+            stats
+        );
+    }
 }
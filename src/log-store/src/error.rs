@@ -53,6 +53,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to join the task to sync raft-engine"))]
+    JoinSync {
+        #[snafu(source)]
+        error: tokio::task::JoinError,
+        location: Location,
+    },
+
     #[snafu(display("Log store not started yet"))]
     IllegalState { location: Location },
 
@@ -120,6 +120,10 @@ impl LogStore for NoopLogStore {
         let _ = entry_id;
         Ok(())
     }
+
+    async fn sync(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -31,8 +31,8 @@ use store_api::logstore::{AppendBatchResponse, AppendResponse, LogStore};
 
 use crate::error::{
     AddEntryLogBatchSnafu, DiscontinuousLogIndexSnafu, Error, FetchEntrySnafu,
-    IllegalNamespaceSnafu, IllegalStateSnafu, OverrideCompactedEntrySnafu, RaftEngineSnafu, Result,
-    StartGcTaskSnafu, StopGcTaskSnafu,
+    IllegalNamespaceSnafu, IllegalStateSnafu, JoinSyncSnafu, OverrideCompactedEntrySnafu,
+    RaftEngineSnafu, Result, StartGcTaskSnafu, StopGcTaskSnafu,
 };
 use crate::raft_engine::backend::SYSTEM_NAMESPACE;
 use crate::raft_engine::protos::logstore::{EntryImpl, NamespaceImpl as Namespace};
@@ -422,6 +422,23 @@ impl LogStore for RaftEngineLogStore {
         );
         Ok(())
     }
+
+    /// Syncs the raft-engine log files, including entries appended without `sync_write`
+    /// while waiting for the next `sync_period`.
+    ///
+    /// The sync blocks until the files are flushed to the disk, so it runs in the
+    /// blocking thread pool.
+    async fn sync(&self) -> Result<()> {
+        ensure!(self.started(), IllegalStateSnafu);
+        let engine = self.engine.clone();
+        tokio::task::spawn_blocking(move || engine.sync())
+            .await
+            .context(JoinSyncSnafu)?
+            .context(RaftEngineSnafu)?;
+        self.last_sync_time
+            .store(common_time::util::current_time_millis(), Ordering::Relaxed);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -570,6 +587,45 @@ mod tests {
         assert_eq!(1, entries[0].namespace_id);
     }
 
+    #[tokio::test]
+    async fn test_sync() {
+        let dir = create_temp_dir("raft-engine-logstore-sync-test");
+        {
+            let logstore = RaftEngineLogStore::try_new(
+                dir.path().to_str().unwrap().to_string(),
+                RaftEngineConfig::default(),
+            )
+            .await
+            .unwrap();
+            // Syncing an empty log store is a no-op.
+            logstore.sync().await.unwrap();
+
+            for id in 1..=3 {
+                let _ = logstore
+                    .append(Entry::create(id, 1, id.to_string().into_bytes()))
+                    .await
+                    .unwrap();
+            }
+            logstore.sync().await.unwrap();
+            // Syncing again is idempotent.
+            logstore.sync().await.unwrap();
+            logstore.stop().await.unwrap();
+        }
+
+        let logstore = RaftEngineLogStore::try_new(
+            dir.path().to_str().unwrap().to_string(),
+            RaftEngineConfig::default(),
+        )
+        .await
+        .unwrap();
+        let entries =
+            collect_entries(logstore.read(&Namespace::with_id(1), 1).await.unwrap()).await;
+        assert_eq!(
+            vec![1, 2, 3],
+            entries.iter().map(|e| e.id).collect::<Vec<_>>()
+        );
+    }
+
     async fn wal_dir_usage(path: impl AsRef<str>) -> usize {
         let mut size: usize = 0;
         let mut read_dir = tokio::fs::read_dir(path.as_ref()).await.unwrap();
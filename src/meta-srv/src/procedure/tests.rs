@@ -29,6 +29,7 @@ use common_meta::datanode_manager::DatanodeManagerRef;
 use common_meta::ddl::alter_table::AlterTableProcedure;
 use common_meta::ddl::create_table::*;
 use common_meta::ddl::drop_table::DropTableProcedure;
+use common_meta::ddl::table_meta::{PLACEMENT_HINTS_KEY, PLACEMENT_HINT_KEY};
 use common_meta::key::table_info::TableInfoValue;
 use common_meta::key::table_route::TableRouteValue;
 use common_meta::key::DeserializedValueWithBytes;
@@ -230,6 +231,61 @@ async fn test_on_datanode_create_regions() {
     assert!(expected_created_regions.lock().unwrap().is_empty());
 }
 
+#[tokio::test]
+async fn test_on_datanode_create_regions_with_placement_hints() {
+    let (region_server, mut rx) = EchoRegionServer::new();
+    let mut region_routes = test_data::new_region_routes();
+    // Region 1 and 2 are placed by hints, while region 3 is not.
+    for route in region_routes.iter_mut().take(2) {
+        let leader = route.leader_peer.as_ref().unwrap().id;
+        let _ = route
+            .region
+            .attrs
+            .insert(PLACEMENT_HINT_KEY.to_string(), leader.to_string());
+    }
+    let datanode_manager = new_datanode_manager(&region_server, &region_routes).await;
+
+    let mut task = create_table_task();
+    let _ = task.create_table.table_options.insert(
+        PLACEMENT_HINTS_KEY.to_string(),
+        r#"{"0":3,"1":2}"#.to_string(),
+    );
+    let mut procedure = CreateTableProcedure::new(
+        1,
+        task,
+        TableRouteValue::physical(region_routes),
+        HashMap::default(),
+        test_data::new_ddl_context(datanode_manager),
+    );
+
+    let handle = tokio::spawn(async move {
+        let mut hints = HashMap::new();
+        while let Some(PbRegionRequest::Create(request)) = rx.recv().await {
+            let region_id = RegionId::from_u64(request.region_id);
+            // The placement hints of all partitions are not passed to the region.
+            assert!(!request.options.contains_key(PLACEMENT_HINTS_KEY));
+            let _ = hints.insert(region_id, request.options.get(PLACEMENT_HINT_KEY).cloned());
+            if hints.len() == 3 {
+                break;
+            }
+        }
+        hints
+    });
+
+    let status = procedure.on_datanode_create_regions().await.unwrap();
+    assert!(matches!(status, Status::Executing { persist: false }));
+
+    let hints = handle.await.unwrap();
+    assert_eq!(
+        hints,
+        HashMap::from([
+            (RegionId::new(42, 1), Some("3".to_string())),
+            (RegionId::new(42, 2), Some("2".to_string())),
+            (RegionId::new(42, 3), None),
+        ])
+    );
+}
+
 #[tokio::test]
 async fn test_on_datanode_drop_regions() {
     let drop_table_task = DropTableTask {
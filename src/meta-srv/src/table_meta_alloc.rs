@@ -18,10 +18,12 @@ use common_meta::ddl::table_meta::PeerAllocator;
 use common_meta::ddl::TableMetadataAllocatorContext;
 use common_meta::error::{ExternalSnafu, Result as MetaResult};
 use common_meta::peer::Peer;
+use common_meta::DatanodeId;
 use snafu::{ensure, ResultExt};
 use store_api::storage::MAX_REGION_SEQ;
 
 use crate::error::{self, Result, TooManyPartitionsSnafu};
+use crate::lease;
 use crate::metasrv::{SelectorContext, SelectorRef};
 use crate::selector::SelectorOptions;
 
@@ -66,6 +68,20 @@ impl MetasrvPeerAllocator {
 
         Ok(peers)
     }
+
+    async fn find(
+        &self,
+        ctx: &TableMetadataAllocatorContext,
+        datanode_id: DatanodeId,
+    ) -> Result<Option<Peer>> {
+        lease::lookup_alive_datanode_peer(
+            ctx.cluster_id,
+            datanode_id,
+            &self.ctx.meta_peer_client,
+            self.ctx.datanode_lease_secs,
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -80,4 +96,15 @@ impl PeerAllocator for MetasrvPeerAllocator {
             .map_err(BoxedError::new)
             .context(ExternalSnafu)
     }
+
+    async fn find(
+        &self,
+        ctx: &TableMetadataAllocatorContext,
+        datanode_id: DatanodeId,
+    ) -> MetaResult<Option<Peer>> {
+        self.find(ctx, datanode_id)
+            .await
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)
+    }
 }
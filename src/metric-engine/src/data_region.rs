@@ -20,6 +20,7 @@ use store_api::metadata::ColumnMetadata;
 use store_api::region_engine::RegionEngine;
 use store_api::region_request::{
     AddColumn, AffectedRows, AlterKind, RegionAlterRequest, RegionPutRequest, RegionRequest,
+    RegionSyncRequest,
 };
 use store_api::storage::consts::ReservedColumnId;
 use store_api::storage::RegionId;
@@ -144,6 +145,15 @@ impl DataRegion {
             .context(MitoWriteOperationSnafu)
     }
 
+    /// Syncs the WAL of the data region.
+    pub async fn sync_data(&self, region_id: RegionId) -> Result<AffectedRows> {
+        let region_id = utils::to_data_region_id(region_id);
+        self.mito
+            .handle_request(region_id, RegionRequest::Sync(RegionSyncRequest::default()))
+            .await
+            .context(MitoWriteOperationSnafu)
+    }
+
     pub async fn physical_columns(
         &self,
         physical_region_id: RegionId,
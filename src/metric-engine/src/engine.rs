@@ -21,6 +21,7 @@ mod put;
 mod read;
 mod region_metadata;
 mod state;
+mod sync;
 
 use std::any::Any;
 use std::sync::{Arc, RwLock};
@@ -39,7 +40,10 @@ use store_api::storage::{RegionId, ScanRequest};
 
 use self::state::MetricEngineState;
 use crate::data_region::DataRegion;
-use crate::error::Result;
+use crate::error::{
+    ForbiddenLogicalFreezeSnafu, ForbiddenLogicalPauseCompactionSnafu, Result,
+    UnsupportedRegionRequestSnafu,
+};
 use crate::metadata_region::MetadataRegion;
 use crate::utils;
 
@@ -133,6 +137,11 @@ impl RegionEngine for MetricEngine {
             RegionRequest::Flush(_) => todo!(),
             RegionRequest::Compact(_) => todo!(),
             RegionRequest::Truncate(_) => todo!(),
+            RegionRequest::DeleteRange(_) => UnsupportedRegionRequestSnafu {
+                request: "DeleteRange",
+            }
+            .fail(),
+            RegionRequest::Sync(_) => self.inner.sync_region(region_id).await,
             /// It always Ok(0), all data is latest.
             RegionRequest::Catchup(_) => Ok(0),
         };
@@ -200,6 +209,48 @@ impl RegionEngine for MetricEngine {
         self.inner.mito.set_readonly_gracefully(region_id).await
     }
 
+    /// Freezes the physical region, including its data region and metadata region.
+    ///
+    /// Logical regions share the data region of their physical region, so they can't
+    /// be frozen individually.
+    async fn set_frozen(&self, region_id: RegionId, frozen: bool) -> Result<(), BoxedError> {
+        if !self.inner.is_physical_region(region_id) {
+            return ForbiddenLogicalFreezeSnafu { region_id }
+                .fail()
+                .map_err(BoxedError::new);
+        }
+
+        for x in [
+            utils::to_metadata_region_id(region_id),
+            utils::to_data_region_id(region_id),
+        ] {
+            self.inner.mito.set_frozen(x, frozen).await?;
+        }
+        Ok(())
+    }
+
+    /// Pauses or resumes compactions of the physical region, including its data region
+    /// and metadata region.
+    async fn set_compaction_paused(
+        &self,
+        region_id: RegionId,
+        paused: bool,
+    ) -> Result<(), BoxedError> {
+        if !self.inner.is_physical_region(region_id) {
+            return ForbiddenLogicalPauseCompactionSnafu { region_id }
+                .fail()
+                .map_err(BoxedError::new);
+        }
+
+        for x in [
+            utils::to_metadata_region_id(region_id),
+            utils::to_data_region_id(region_id),
+        ] {
+            self.inner.mito.set_compaction_paused(x, paused).await?;
+        }
+        Ok(())
+    }
+
     /// Returns the physical region role.
     ///
     /// Note: Returns `None` if it's a logical region.
@@ -249,8 +300,11 @@ struct MetricEngineInner {
 mod test {
     use std::collections::HashMap;
 
+    use common_time::Timestamp;
     use store_api::metric_engine_consts::PHYSICAL_TABLE_METADATA_KEY;
-    use store_api::region_request::{RegionCloseRequest, RegionOpenRequest};
+    use store_api::region_request::{
+        RegionCloseRequest, RegionDeleteRangeRequest, RegionOpenRequest,
+    };
 
     use super::*;
     use crate::test_util::TestEnv;
@@ -312,6 +366,30 @@ mod test {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_delete_range_unsupported() {
+        let env = TestEnv::new().await;
+        env.init_metric_region().await;
+
+        for region_id in [
+            env.default_physical_region_id(),
+            env.default_logical_region_id(),
+        ] {
+            let err = env
+                .metric()
+                .handle_request(
+                    region_id,
+                    RegionRequest::DeleteRange(RegionDeleteRangeRequest {
+                        start: Timestamp::new_millisecond(0),
+                        end: Timestamp::new_millisecond(1000),
+                    }),
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(StatusCode::Unsupported, err.status_code());
+        }
+    }
+
     #[tokio::test]
     async fn test_role() {
         let env = TestEnv::new().await;
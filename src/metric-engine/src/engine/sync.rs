@@ -0,0 +1,41 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::OptionExt;
+use store_api::region_request::AffectedRows;
+use store_api::storage::RegionId;
+
+use crate::engine::MetricEngineInner;
+use crate::error::{LogicalRegionNotFoundSnafu, Result};
+
+impl MetricEngineInner {
+    /// Syncs the WAL of the data region the logical or physical region writes to.
+    ///
+    /// The metadata region shares the WAL with the data region so it is synced too.
+    pub async fn sync_region(&self, region_id: RegionId) -> Result<AffectedRows> {
+        let physical_region_id = {
+            let state = self.state.read().unwrap();
+            if state.physical_regions().contains_key(&region_id) {
+                region_id
+            } else {
+                *state
+                    .logical_regions()
+                    .get(&region_id)
+                    .context(LogicalRegionNotFoundSnafu { region_id })?
+            }
+        };
+
+        self.data_region.sync_data(physical_region_id).await
+    }
+}
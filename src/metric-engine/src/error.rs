@@ -141,6 +141,27 @@ pub enum Error {
     #[snafu(display("Alter request to physical region is forbidden"))]
     ForbiddenPhysicalAlter { location: Location },
 
+    #[snafu(display("Unsupported region request: {}", request))]
+    UnsupportedRegionRequest { request: String, location: Location },
+
+    #[snafu(display(
+        "Freezing logical region {} is forbidden, logical regions are frozen with their physical region",
+        region_id
+    ))]
+    ForbiddenLogicalFreeze {
+        region_id: RegionId,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Pausing compaction of logical region {} is forbidden, logical regions are compacted with their physical region",
+        region_id
+    ))]
+    ForbiddenLogicalPauseCompaction {
+        region_id: RegionId,
+        location: Location,
+    },
+
     #[snafu(display("Invalid region metadata"))]
     InvalidMetadata {
         source: store_api::metadata::MetadataError,
@@ -170,7 +191,10 @@ impl ErrorExt for Error {
             | ColumnTypeMismatch { .. }
             | PhysicalRegionBusy { .. } => StatusCode::InvalidArguments,
 
-            ForbiddenPhysicalAlter { .. } => StatusCode::Unsupported,
+            ForbiddenPhysicalAlter { .. }
+            | ForbiddenLogicalFreeze { .. }
+            | ForbiddenLogicalPauseCompaction { .. }
+            | UnsupportedRegionRequest { .. } => StatusCode::Unsupported,
 
             MissingInternalColumn { .. }
             | DeserializeSemanticType { .. }
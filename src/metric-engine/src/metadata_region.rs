@@ -351,6 +351,10 @@ impl MetadataRegion {
             filters: vec![],
             output_ordering: None,
             limit: None,
+            sample: None,
+            index_hint: None,
+            source_order: None,
+            follow: false,
         };
         let record_batch_stream = self
             .mito
@@ -408,6 +412,10 @@ impl MetadataRegion {
             filters: vec![filter_expr.into()],
             output_ordering: None,
             limit: None,
+            sample: None,
+            index_hint: None,
+            source_order: None,
+            follow: false,
         }
     }
 
@@ -568,6 +576,10 @@ mod test {
             filters: vec![expected_filter_expr.into()],
             output_ordering: None,
             limit: None,
+            sample: None,
+            index_hint: None,
+            source_order: None,
+            follow: false,
         };
         let actual_scan_request = MetadataRegion::build_read_request(key);
         assert_eq!(actual_scan_request, expected_scan_request);
@@ -12,50 +12,202 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{HashSet, VecDeque};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
-use object_store::services::Fs;
-use object_store::util::{join_dir, with_instrument_layers};
+use async_trait::async_trait;
+use common_base::readable_size::ReadableSize;
+use common_telemetry::{debug, warn};
+use object_store::layers::ConcurrentLimitLayer;
+use object_store::services::{Fs, Memory};
+use object_store::util::{join_dir, lock_dir, with_instrument_layers};
 use object_store::ObjectStore;
-use snafu::ResultExt;
+use smallvec::SmallVec;
+use snafu::{OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
+use store_api::storage::RegionId;
+use tokio::sync::mpsc::Sender;
 
-use crate::cache::write_cache::SstUploadRequest;
+use crate::cache::file_cache::{FileType, IndexKey};
+use crate::cache::write_cache::{SstUploadRequest, WriteCacheRef};
 use crate::cache::CacheManagerRef;
-use crate::error::{CleanDirSnafu, DeleteIndexSnafu, DeleteSstSnafu, OpenDalSnafu, Result};
-use crate::read::Source;
-use crate::sst::file::{FileHandle, FileId, FileMeta};
-use crate::sst::location;
+use crate::error::{
+    AtomicWriteDirLockedSnafu, CleanDirSnafu, CopyFileSnafu, DeleteIndexSnafu, DeleteSstSnafu,
+    Error, OpenDalSnafu, Result, SourceNotReplayableSnafu,
+};
+use crate::metrics::{SST_MIRROR_FAILURES_TOTAL, WRITE_CACHE_BYPASS_TOTAL};
+use crate::read::{BatchReader, SharedBatchReader, Source};
+use crate::request::WorkerRequest;
+use crate::sst::file::{FileHandle, FileId, FileMeta, IndexType};
+use crate::sst::index::creator::SstIndexCreator;
+use crate::sst::index::limiter::IndexBuildLimiterRef;
+use crate::sst::index::rebuilder::IndexRebuildContext;
+use crate::sst::index::store::RetryPolicy;
+use crate::sst::location::PathStrategyRef;
 use crate::sst::parquet::reader::ParquetReaderBuilder;
-use crate::sst::parquet::writer::ParquetWriter;
-use crate::sst::parquet::{SstInfo, WriteOptions};
+use crate::sst::parquet::writer::{ParquetWriter, SstFile, SstFileProvider};
+use crate::sst::parquet::{ColumnKeyValues, SstInfo, WriteOptions};
+use crate::sst::retry::{maybe_retry, RetryConfig, DELETE_OPERATION};
+use crate::sst::DEFAULT_WRITE_BUFFER_SIZE;
 
 pub type AccessLayerRef = Arc<AccessLayer>;
 
+/// Memory threshold to spill intermediate data while creating an inverted index.
+pub(crate) const INDEX_CREATE_MEM_THRESHOLD: ReadableSize = ReadableSize::mb(64);
+
 /// A layer to access SST files under the same directory.
+#[derive(Clone)]
 pub struct AccessLayer {
     region_dir: String,
+    /// Strategy to derive paths of SSTs under the region directory.
+    path_strategy: PathStrategyRef,
     /// Target object store.
     object_store: ObjectStore,
+    /// Store to mirror SSTs written by the layer, e.g. for a standby node.
+    mirror_store: Option<ObjectStore>,
+    /// Buffer size to write intermediate files while creating indexes.
+    index_intermediate_write_buffer_size: ReadableSize,
+    /// Store of intermediate files while creating indexes, `None` means using the
+    /// target object store.
+    intermediate_store: Option<ObjectStore>,
+    /// Limiter of concurrent index builds, `None` means unlimited.
+    index_build_limiter: Option<IndexBuildLimiterRef>,
+    /// Timeout and retries of operations on intermediate files while creating indexes.
+    index_intermediate_retry_policy: RetryPolicy,
+    /// Number of entries in a page to list intermediate files, 0 means using the
+    /// default page size of the store.
+    index_intermediate_list_page_size: usize,
+    /// Max bytes of intermediate files of an index creation, 0 means no limit.
+    index_intermediate_max_bytes: ReadableSize,
+    /// Number of intermediate files to open concurrently, 0 means the default concurrency.
+    index_intermediate_open_concurrency: usize,
+    /// Backoff to retry deletes and reads of SSTs, `None` means no retry.
+    retry_config: Option<RetryConfig>,
+    /// Sender of requests to the worker of the region, readers only rebuild missing
+    /// or corrupt indexes if it isn't `None`.
+    request_sender: Option<Sender<WorkerRequest>>,
 }
 
 impl std::fmt::Debug for AccessLayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AccessLayer")
             .field("region_dir", &self.region_dir)
+            .field("path_strategy", &self.path_strategy)
             .finish()
     }
 }
 
 impl AccessLayer {
-    /// Returns a new [AccessLayer] for specific `region_dir`.
-    pub fn new(region_dir: impl Into<String>, object_store: ObjectStore) -> AccessLayer {
+    /// Returns a new [AccessLayer] for specific `region_dir`, paths of SSTs under the
+    /// directory are derived by the `path_strategy`.
+    ///
+    /// SSTs written by the layer are also mirrored to the `mirror_store` under the same
+    /// paths if it isn't `None`. Failures to mirror SSTs don't fail the write as the
+    /// target store already has the SSTs.
+    pub fn new(
+        region_dir: impl Into<String>,
+        object_store: ObjectStore,
+        mirror_store: Option<ObjectStore>,
+        path_strategy: PathStrategyRef,
+    ) -> AccessLayer {
         AccessLayer {
             region_dir: region_dir.into(),
+            path_strategy,
             object_store,
+            mirror_store,
+            index_intermediate_write_buffer_size: ReadableSize(0),
+            intermediate_store: None,
+            index_build_limiter: None,
+            index_intermediate_retry_policy: RetryPolicy::default(),
+            index_intermediate_list_page_size: 0,
+            index_intermediate_max_bytes: ReadableSize(0),
+            index_intermediate_open_concurrency: 0,
+            retry_config: None,
+            request_sender: None,
         }
     }
 
+    /// Sets the buffer size to write intermediate files while creating indexes.
+    pub(crate) fn with_index_intermediate_write_buffer_size(
+        mut self,
+        buffer_size: ReadableSize,
+    ) -> AccessLayer {
+        self.index_intermediate_write_buffer_size = buffer_size;
+        self
+    }
+
+    /// Sets the store of intermediate files while creating indexes.
+    pub(crate) fn with_intermediate_store(
+        mut self,
+        intermediate_store: Option<ObjectStore>,
+    ) -> AccessLayer {
+        self.intermediate_store = intermediate_store;
+        self
+    }
+
+    /// Sets the limiter of concurrent index builds.
+    pub(crate) fn with_index_build_limiter(
+        mut self,
+        limiter: Option<IndexBuildLimiterRef>,
+    ) -> AccessLayer {
+        self.index_build_limiter = limiter;
+        self
+    }
+
+    /// Sets the sender of requests to the worker of the region to commit indexes rebuilt
+    /// by readers.
+    pub(crate) fn with_request_sender(
+        mut self,
+        request_sender: Option<Sender<WorkerRequest>>,
+    ) -> AccessLayer {
+        self.request_sender = request_sender;
+        self
+    }
+
+    /// Sets the timeout and retries of operations on intermediate files while creating indexes.
+    pub(crate) fn with_index_intermediate_retry_policy(
+        mut self,
+        retry_policy: RetryPolicy,
+    ) -> AccessLayer {
+        self.index_intermediate_retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the number of entries in a page to list intermediate files while creating indexes.
+    pub(crate) fn with_index_intermediate_list_page_size(
+        mut self,
+        page_size: usize,
+    ) -> AccessLayer {
+        self.index_intermediate_list_page_size = page_size;
+        self
+    }
+
+    /// Sets the max bytes of intermediate files of an index creation.
+    pub(crate) fn with_index_intermediate_max_bytes(
+        mut self,
+        max_bytes: ReadableSize,
+    ) -> AccessLayer {
+        self.index_intermediate_max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets the number of intermediate files to open concurrently while creating indexes.
+    pub(crate) fn with_index_intermediate_open_concurrency(
+        mut self,
+        concurrency: usize,
+    ) -> AccessLayer {
+        self.index_intermediate_open_concurrency = concurrency;
+        self
+    }
+
+    /// Retries deletes and reads of SSTs that fail with temporary errors by the
+    /// `retry_config`, see [RetryConfig] for operations to retry.
+    pub fn with_retry_config(mut self, retry_config: Option<RetryConfig>) -> AccessLayer {
+        self.retry_config = retry_config;
+        self
+    }
+
     /// Returns the directory of the region.
     pub fn region_dir(&self) -> &str {
         &self.region_dir
@@ -66,81 +218,673 @@ impl AccessLayer {
         &self.object_store
     }
 
+    /// Returns the strategy to derive paths of SSTs.
+    pub fn path_strategy(&self) -> &PathStrategyRef {
+        &self.path_strategy
+    }
+
+    /// Returns a new id for the SST to create, the id contains the time it is created
+    /// if the path strategy lays out SSTs by time.
+    pub fn new_file_id(&self) -> FileId {
+        self.path_strategy.new_file_id()
+    }
+
+    /// Returns the directory of the SST with `file_id`, its index file and intermediate
+    /// files to create the index are also under the directory.
+    pub fn sst_dir(&self, file_id: FileId) -> String {
+        self.path_strategy.sst_dir(&self.region_dir, file_id)
+    }
+
+    /// Returns the path of the SST file with `file_id`.
+    pub fn sst_file_path(&self, file_id: FileId) -> String {
+        self.path_strategy.sst_file_path(&self.region_dir, file_id)
+    }
+
+    /// Returns the path of the index file with `index_file_id` of the SST with `file_id`.
+    pub fn index_file_path(&self, file_id: FileId, index_file_id: FileId) -> String {
+        self.path_strategy
+            .index_file_path(&self.region_dir, file_id, index_file_id)
+    }
+
     /// Deletes a SST file (and its index file if it has one) with given file id.
     pub(crate) async fn delete_sst(&self, file_meta: &FileMeta) -> Result<()> {
-        let path = location::sst_file_path(&self.region_dir, file_meta.file_id);
-        self.object_store
-            .delete(&path)
-            .await
-            .context(DeleteSstSnafu {
-                file_id: file_meta.file_id,
-            })?;
+        let mut failures = self.delete_ssts(std::slice::from_ref(file_meta)).await?;
+        match failures.pop() {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
+    }
 
+    /// Deletes SST files (and their index files) of `file_metas` in a batch, and returns
+    /// ids of files that fail to delete with their errors.
+    ///
+    /// All files are removed by one batch delete of the object store. If the batch fails,
+    /// files are deleted one by one concurrently to find out which files fail. Failing to
+    /// delete a file doesn't stop deleting other files.
+    pub(crate) async fn delete_ssts(
+        &self,
+        file_metas: &[FileMeta],
+    ) -> Result<Vec<(FileId, Error)>> {
+        if file_metas.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let paths: Vec<_> = file_metas
+            .iter()
+            .flat_map(|file_meta| self.file_paths(file_meta))
+            .collect();
+        let result = maybe_retry(
+            self.retry_config.as_ref(),
+            DELETE_OPERATION,
+            &self.region_dir,
+            || self.object_store.remove(paths.clone()),
+        )
+        .await;
+        match result {
+            Ok(()) => return Ok(Vec::new()),
+            Err(e) => warn!(
+                e; "Failed to delete {} SSTs in a batch, delete them one by one, region_dir: {}",
+                file_metas.len(), self.region_dir
+            ),
+        }
+
+        let results = futures::future::join_all(
+            file_metas
+                .iter()
+                .map(|file_meta| self.delete_sst_files(file_meta)),
+        )
+        .await;
+        Ok(file_metas
+            .iter()
+            .zip(results)
+            .filter_map(|(file_meta, result)| result.err().map(|e| (file_meta.file_id, e)))
+            .collect())
+    }
+
+    /// Returns paths of the SST file and its index file if it has one.
+    fn file_paths(&self, file_meta: &FileMeta) -> SmallVec<[String; 2]> {
+        let mut paths = SmallVec::new();
+        paths.push(self.sst_file_path(file_meta.file_id));
         if file_meta.inverted_index_available() {
-            let path = location::index_file_path(&self.region_dir, file_meta.file_id);
-            self.object_store
-                .delete(&path)
-                .await
-                .context(DeleteIndexSnafu {
-                    file_id: file_meta.file_id,
-                })?;
+            paths.push(self.index_file_path(file_meta.file_id, file_meta.index_file_id()));
+        }
+        paths
+    }
+
+    /// Deletes the SST file and then its index file if it has one.
+    async fn delete_sst_files(&self, file_meta: &FileMeta) -> Result<()> {
+        let path = self.sst_file_path(file_meta.file_id);
+        self.delete_file(&path).await.context(DeleteSstSnafu {
+            file_id: file_meta.file_id,
+        })?;
+
+        if file_meta.inverted_index_available() {
+            self.delete_index(file_meta).await?;
         }
 
         Ok(())
     }
 
+    /// Deletes the file at `path`, retrying the delete by the retry config.
+    async fn delete_file(&self, path: &str) -> object_store::Result<()> {
+        maybe_retry(self.retry_config.as_ref(), DELETE_OPERATION, path, || {
+            self.object_store.delete(path)
+        })
+        .await
+    }
+
+    /// Copies a SST file (and its index file if it has one) to the `target` layer,
+    /// files in the target have the same file id.
+    ///
+    /// Copies files on the server side if both layers are on the same store that supports
+    /// copying, otherwise streams files from this layer to the target. Files already
+    /// copied to the target are removed if it fails to copy.
+    #[allow(dead_code)]
+    pub(crate) async fn copy_sst(&self, file_meta: &FileMeta, target: &AccessLayer) -> Result<()> {
+        let file_id = file_meta.file_id;
+        let mut files = vec![(
+            FileType::Parquet,
+            self.sst_file_path(file_id),
+            target.sst_file_path(file_id),
+        )];
+        if file_meta.inverted_index_available() {
+            files.push((
+                FileType::Puffin,
+                self.index_file_path(file_id, file_meta.index_file_id()),
+                target.index_file_path(file_id, file_meta.index_file_id()),
+            ));
+        }
+
+        let mut copied = Vec::with_capacity(files.len());
+        for (file_type, source_path, target_path) in files {
+            let result = self
+                .copy_file(file_meta, file_type, &source_path, target, &target_path)
+                .await;
+            // The target file might be partially written on failure.
+            copied.push(target_path);
+            if let Err(e) = result {
+                for path in &copied {
+                    if let Err(delete_err) = target.object_store.delete(path).await {
+                        warn!(
+                            delete_err; "Failed to remove partially copied file {}, region_id: {}",
+                            path, file_meta.region_id
+                        );
+                    }
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies a file from `source_path` of this layer to `target_path` of the `target` layer.
+    async fn copy_file(
+        &self,
+        file_meta: &FileMeta,
+        file_type: FileType,
+        source_path: &str,
+        target: &AccessLayer,
+        target_path: &str,
+    ) -> Result<()> {
+        if self.can_copy_on_server(target) {
+            return self
+                .object_store
+                .copy(source_path, target_path)
+                .await
+                .context(OpenDalSnafu);
+        }
+
+        let reader = self
+            .object_store
+            .reader(source_path)
+            .await
+            .context(OpenDalSnafu)?;
+        let mut writer = target
+            .object_store
+            .writer_with(target_path)
+            .buffer(DEFAULT_WRITE_BUFFER_SIZE.as_bytes() as usize)
+            .await
+            .context(OpenDalSnafu)?;
+        futures::io::copy(reader, &mut writer)
+            .await
+            .context(CopyFileSnafu {
+                region_id: file_meta.region_id,
+                file_id: file_meta.file_id,
+                file_type,
+            })?;
+        // Must close to write all data.
+        writer.close().await.context(OpenDalSnafu)
+    }
+
+    /// Returns true if the `target` layer is on the same store as this layer and the
+    /// store can copy files on the server side.
+    fn can_copy_on_server(&self, target: &AccessLayer) -> bool {
+        let info = self.object_store.info();
+        let target_info = target.object_store.info();
+        info.scheme() == target_info.scheme()
+            && info.name() == target_info.name()
+            && info.root() == target_info.root()
+            && info.full_capability().copy
+    }
+
     /// Returns a reader builder for specific `file`.
     pub(crate) fn read_sst(&self, file: FileHandle) -> ParquetReaderBuilder {
-        ParquetReaderBuilder::new(self.region_dir.clone(), file, self.object_store.clone())
+        ParquetReaderBuilder::new(
+            self.sst_dir(file.file_id()),
+            file,
+            self.object_store.clone(),
+        )
+        .intermediate_store(self.intermediate_store.clone())
+        .index_rebuild(
+            self.request_sender
+                .clone()
+                .map(|request_sender| IndexRebuildContext {
+                    request_sender,
+                    limiter: self.index_build_limiter.clone(),
+                }),
+        )
+        .retry_config(self.retry_config)
     }
 
-    /// Writes a SST with specific `file_id` and `metadata` to the layer.
+    /// Writes SSTs with specific `file_id` and `metadata` to the layer. It rolls over
+    /// to SSTs with new file ids once a SST reaches [WriteOptions::max_file_size].
     ///
-    /// Returns the info of the SST. If no data written, returns None.
+    /// Returns the info of written SSTs in order, the first SST has the `file_id` of
+    /// the request. If no data written, returns an empty vec.
     pub(crate) async fn write_sst(
         &self,
         request: SstWriteRequest,
         write_opts: &WriteOptions,
-    ) -> Result<Option<SstInfo>> {
-        let file_path = location::sst_file_path(&self.region_dir, request.file_id);
-        let index_file_path = location::index_file_path(&self.region_dir, request.file_id);
+    ) -> Result<Vec<SstInfo>> {
         let region_id = request.metadata.region_id;
+        let cache_manager = request.cache_manager.clone();
 
-        let sst_info = if let Some(write_cache) = request.cache_manager.write_cache() {
+        let write_cache = cache_manager.write_cache().filter(|write_cache| {
+            let bypass = write_cache.should_bypass(request.source_size);
+            if bypass {
+                WRITE_CACHE_BYPASS_TOTAL.inc();
+                debug!(
+                    "Write SST directly to the object store, region_id: {}, file_id: {}, source_size: {:?}",
+                    region_id, request.file_id, request.source_size
+                );
+            }
+            !bypass
+        });
+        let sst_infos = if let Some(write_cache) = write_cache {
             // Write to the write cache.
-            write_cache
-                .write_and_upload_sst(
-                    SstUploadRequest {
-                        file_id: request.file_id,
-                        metadata: request.metadata,
-                        source: request.source,
-                        storage: request.storage,
-                        upload_path: file_path,
-                        index_upload_path: index_file_path,
-                        remote_store: self.object_store.clone(),
-                    },
-                    write_opts,
-                )
+            self.write_sst_to_cache(write_cache, request, write_opts)
                 .await?
         } else {
-            // Write cache is disabled.
-            let mut writer =
-                ParquetWriter::new(file_path, request.metadata, self.object_store.clone());
-            writer.write_all(request.source, write_opts).await?
+            // Write cache is disabled or bypassed.
+            let file_path = self.sst_file_path(request.file_id);
+            let index_creator = self
+                .new_index_creator(
+                    request.file_id,
+                    &request.metadata,
+                    request.index_columns.as_deref(),
+                    write_opts,
+                )
+                .await;
+            let mut writer = ParquetWriter::new(
+                request.file_id,
+                file_path,
+                request.metadata.clone(),
+                self.object_store.clone(),
+            )
+            .with_column_metadata(request.column_metadata);
+            if let Some(index_creator) = index_creator {
+                writer = writer.with_index_creator(index_creator);
+            }
+            if write_opts.max_file_size.is_some() {
+                writer = writer.with_file_provider(Box::new(LayerFileProvider {
+                    layer: self.clone(),
+                    metadata: request.metadata,
+                    index_columns: request.index_columns,
+                    write_opts: write_opts.clone(),
+                }));
+            }
+            let sst_infos = writer.write_all(request.source, write_opts).await?;
+
+            if let Some(mirror_store) = &self.mirror_store {
+                for sst_info in &sst_infos {
+                    let file_path = self.sst_file_path(sst_info.file_id);
+                    let mut files = vec![(&self.object_store, file_path.clone(), file_path)];
+                    if sst_info.inverted_index_available {
+                        let index_file_path =
+                            self.index_file_path(sst_info.file_id, sst_info.file_id);
+                        files.push((&self.object_store, index_file_path.clone(), index_file_path));
+                    }
+                    mirror_files(region_id, sst_info.file_id, mirror_store, &files).await;
+                }
+            }
+            sst_infos
         };
 
         // Put parquet metadata to cache manager.
-        if let Some(sst_info) = &sst_info {
+        for sst_info in &sst_infos {
             if let Some(parquet_metadata) = &sst_info.file_metadata {
-                request.cache_manager.put_parquet_meta_data(
+                cache_manager.put_parquet_meta_data(
                     region_id,
-                    request.file_id,
+                    sst_info.file_id,
                     parquet_metadata.clone(),
                 )
             }
         }
 
-        Ok(sst_info)
+        Ok(sst_infos)
+    }
+
+    /// Writes SSTs of the `request` to the `write_cache`, which uploads SSTs to the layer.
+    ///
+    /// The write cache writes one SST at a time, so the layer rolls over by writing SSTs
+    /// of at most [WriteOptions::max_file_size] until the source is exhausted or SSTs
+    /// reach the target of `write_opts`. SSTs already uploaded are removed on failure.
+    async fn write_sst_to_cache(
+        &self,
+        write_cache: &WriteCacheRef,
+        mut request: SstWriteRequest,
+        write_opts: &WriteOptions,
+    ) -> Result<Vec<SstInfo>> {
+        // Takes the source out of the request so SSTs can share it.
+        let source = std::mem::replace(&mut request.source, Source::Batches(VecDeque::new()));
+        let Some(max_file_size) = write_opts.max_file_size else {
+            let upload_request = self.new_upload_request(request.file_id, &request, source);
+            let sst_info = write_cache
+                .write_and_upload_sst(upload_request, write_opts)
+                .await?;
+            return Ok(sst_info.into_iter().collect());
+        };
+
+        let max_file_size = max_file_size as u64;
+        let file_opts = WriteOptions {
+            target_file_size: Some(ReadableSize(
+                write_opts
+                    .target_file_size
+                    .map_or(max_file_size, |size| size.as_bytes().min(max_file_size)),
+            )),
+            ..write_opts.clone()
+        };
+        let reader = SharedBatchReader::new(source);
+        let mut sst_infos: Vec<SstInfo> = Vec::new();
+        let mut file_id = request.file_id;
+        loop {
+            let upload_request = self.new_upload_request(
+                file_id,
+                &request,
+                Source::Reader(Box::new(reader.clone())),
+            );
+            let sst_info = match write_cache
+                .write_and_upload_sst(upload_request, &file_opts)
+                .await
+            {
+                Ok(sst_info) => sst_info,
+                Err(e) => {
+                    for sst_info in &sst_infos {
+                        self.remove_partial_sst(sst_info.file_id).await;
+                    }
+                    return Err(e);
+                }
+            };
+            let Some(sst_info) = sst_info else {
+                break;
+            };
+            sst_infos.push(sst_info);
+
+            let file_size: u64 = sst_infos.iter().map(|info| info.file_size).sum();
+            let num_rows: usize = sst_infos.iter().map(|info| info.num_rows).sum();
+            if write_opts
+                .target_file_size
+                .is_some_and(|size| file_size >= size.as_bytes())
+                || write_opts
+                    .target_num_rows
+                    .is_some_and(|target| num_rows >= target)
+            {
+                // Leaves the remaining batches to the next writer.
+                break;
+            }
+            file_id = self.new_file_id();
+        }
+
+        Ok(sst_infos)
+    }
+
+    /// Returns a request to write the SST with `file_id` from the `source` to the write
+    /// cache and upload it to the layer.
+    fn new_upload_request(
+        &self,
+        file_id: FileId,
+        request: &SstWriteRequest,
+        source: Source,
+    ) -> SstUploadRequest {
+        SstUploadRequest {
+            file_id,
+            metadata: request.metadata.clone(),
+            source,
+            storage: request.storage.clone(),
+            column_metadata: request.column_metadata.clone(),
+            upload_path: self.sst_file_path(file_id),
+            index_upload_path: self.index_file_path(file_id, file_id),
+            remote_store: self.object_store.clone(),
+            mirror_store: self.mirror_store.clone(),
+        }
+    }
+
+    /// Removes the SST with `file_id` and its index file after failing to write other
+    /// SSTs of the same request.
+    async fn remove_partial_sst(&self, file_id: FileId) {
+        for path in [
+            self.sst_file_path(file_id),
+            self.index_file_path(file_id, file_id),
+        ] {
+            if let Err(e) = self.delete_file(&path).await {
+                warn!(e; "Failed to remove SST file {}", path);
+            }
+        }
+    }
+
+    /// Estimates the size of the SST the `request` writes, without writing
+    /// anything to the layer.
+    ///
+    /// The estimate encodes rows in the source with the same [ParquetWriter]
+    /// options as [AccessLayer::write_sst()] but writes the SST to memory. It
+    /// ignores the target size and number of rows in `write_opts` and doesn't
+    /// build indexes. Returns 0 if the source has no data.
+    ///
+    /// Only replayable sources (see [Source::try_clone()]) are encoded so the request
+    /// can still write the SST after the estimate. Other sources, e.g. streams of
+    /// memtables or SSTs, are estimated by the `source_size` of the request, which is
+    /// cheap to get. Returns an error if the source can't be replayed and the request
+    /// has no source size.
+    pub(crate) async fn estimate_sst_size(
+        &self,
+        request: &SstWriteRequest,
+        write_opts: &WriteOptions,
+    ) -> Result<usize> {
+        let Some(source) = request.source.try_clone() else {
+            return request.source_size.map(|size| size as usize).context(
+                SourceNotReplayableSnafu {
+                    region_id: request.metadata.region_id,
+                    file_id: request.file_id,
+                },
+            );
+        };
+        let write_opts = WriteOptions {
+            target_file_size: None,
+            target_num_rows: None,
+            ..write_opts.clone()
+        };
+        let mut writer = ParquetWriter::new(
+            request.file_id,
+            self.sst_file_path(request.file_id),
+            request.metadata.clone(),
+            ObjectStore::new(Memory::default())
+                .context(OpenDalSnafu)?
+                .finish(),
+        )
+        .with_column_metadata(request.column_metadata.clone());
+        let sst_infos = writer.write_all(source, &write_opts).await?;
+
+        Ok(sst_infos.iter().map(|info| info.file_size as usize).sum())
+    }
+
+    /// Returns a creator to create the inverted index of the SST with `file_id`, or
+    /// `None` if `index_columns` is `None` or there is no tag column to index.
+    ///
+    /// Only indexes tag columns in `index_columns`, columns not in the region anymore
+    /// are ignored.
+    ///
+    /// Waits for a permit of the index build limiter, the creator holds the permit.
+    async fn new_index_creator(
+        &self,
+        file_id: FileId,
+        metadata: &RegionMetadataRef,
+        index_columns: Option<&[String]>,
+        write_opts: &WriteOptions,
+    ) -> Option<SstIndexCreator> {
+        let index_columns = index_columns?;
+        // Segments of the index are row groups of the SST.
+        let segment_row_count = NonZeroUsize::new(write_opts.row_group_size)?;
+
+        let creator =
+            self.index_creator(file_id, metadata, segment_row_count, Some(index_columns))?;
+        match &self.index_build_limiter {
+            Some(limiter) => Some(creator.with_build_permit(limiter.acquire().await)),
+            None => Some(creator),
+        }
+    }
+
+    /// Returns a creator to create the inverted index of the SST with `file_id`, or
+    /// `None` if there is no tag column to index.
+    fn index_creator(
+        &self,
+        file_id: FileId,
+        metadata: &RegionMetadataRef,
+        segment_row_count: NonZeroUsize,
+        index_columns: Option<&[String]>,
+    ) -> Option<SstIndexCreator> {
+        if metadata.primary_key.is_empty() {
+            return None;
+        }
+
+        let indexed_columns = match index_columns {
+            Some(names) => {
+                let column_ids: HashSet<_> = metadata
+                    .primary_key_columns()
+                    .filter(|column| names.iter().any(|name| *name == column.column_schema.name))
+                    .map(|column| column.column_id)
+                    .collect();
+                if column_ids.is_empty() {
+                    return None;
+                }
+                Some(column_ids)
+            }
+            None => None,
+        };
+
+        let mut creator = SstIndexCreator::new(
+            self.sst_dir(file_id),
+            file_id,
+            metadata,
+            self.object_store.clone(),
+            self.intermediate_store
+                .clone()
+                .unwrap_or_else(|| self.object_store.clone()),
+            Some(INDEX_CREATE_MEM_THRESHOLD.as_bytes() as usize),
+            segment_row_count,
+            Some(self.index_intermediate_write_buffer_size.as_bytes() as usize),
+            self.index_intermediate_retry_policy,
+            Some(self.index_intermediate_list_page_size),
+            Some(self.index_intermediate_max_bytes.as_bytes() as usize),
+            Some(self.index_intermediate_open_concurrency),
+        );
+        if let Some(column_ids) = indexed_columns {
+            creator = creator.with_indexed_columns(column_ids);
+        }
+        Some(creator)
+    }
+
+    /// Builds the inverted index of an existing SST from rows in the SST, the SST
+    /// itself isn't rewritten. The index is written to a new index file, the existing
+    /// index file of the SST isn't touched until the new meta is committed.
+    ///
+    /// Returns the meta of the SST with the new index, or `None` if the SST has
+    /// no column to index. Indexes all tag columns if `index_columns` is `None`.
+    pub(crate) async fn build_index(
+        &self,
+        file: FileHandle,
+        index_columns: Option<&[String]>,
+        cache_manager: Option<CacheManagerRef>,
+    ) -> Result<Option<FileMeta>> {
+        // Reads all rows without using the index.
+        let mut reader = self
+            .read_sst(file.clone())
+            .cache(cache_manager.clone())
+            .build()
+            .await?;
+        // Segments of the index are row groups, all row groups except the last one
+        // have the same number of rows.
+        let Some(segment_row_count) = reader
+            .parquet_metadata()
+            .row_groups()
+            .first()
+            .and_then(|row_group| NonZeroUsize::new(row_group.num_rows() as usize))
+        else {
+            return Ok(None);
+        };
+        // Uses the metadata of the SST as columns of the region might be altered.
+        let metadata = reader.metadata().clone();
+        let index_file_id = FileId::random();
+        let Some(mut creator) = self
+            .index_creator(file.file_id(), &metadata, segment_row_count, index_columns)
+            .map(|creator| creator.with_index_file_id(index_file_id))
+        else {
+            return Ok(None);
+        };
+        // Building indexes of existing SSTs waits for a permit.
+        if let Some(limiter) = &self.index_build_limiter {
+            creator = creator.with_build_permit(limiter.acquire().await);
+        }
+
+        loop {
+            match reader.next_batch().await {
+                Ok(Some(batch)) => creator.update(&batch).await?,
+                Ok(None) => break,
+                Err(e) => {
+                    if let Err(abort_err) = creator.abort().await {
+                        warn!(
+                            abort_err; "Failed to abort index creator, region_id: {}, file_id: {}",
+                            file.region_id(), file.file_id()
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let (_, byte_count) = creator.finish().await?;
+        if byte_count == 0 {
+            return Ok(None);
+        }
+
+        // Removes the stale index from the local cache.
+        if let Some(write_cache) = cache_manager.as_ref().and_then(|cache| cache.write_cache()) {
+            write_cache
+                .file_cache()
+                .remove(IndexKey::new(
+                    file.region_id(),
+                    file.index_file_id(),
+                    FileType::Puffin,
+                ))
+                .await;
+        }
+
+        let mut file_meta = file.meta();
+        file_meta.available_indexes = SmallVec::from_iter([IndexType::InvertedIndex]);
+        file_meta.index_file_id = Some(index_file_id);
+        file_meta.index_file_size = byte_count as u64;
+        file_meta.index_stats = creator.column_stats().to_vec();
+        Ok(Some(file_meta))
+    }
+
+    /// Deletes the index file of the SST with `file_meta`.
+    pub(crate) async fn delete_index(&self, file_meta: &FileMeta) -> Result<()> {
+        let path = self.index_file_path(file_meta.file_id, file_meta.index_file_id());
+        self.delete_file(&path).await.context(DeleteIndexSnafu {
+            file_id: file_meta.file_id,
+        })
+    }
+}
+
+/// Provides SSTs with new file ids under the layer for a [ParquetWriter] to roll
+/// over to.
+struct LayerFileProvider {
+    layer: AccessLayer,
+    metadata: RegionMetadataRef,
+    index_columns: Option<Vec<String>>,
+    write_opts: WriteOptions,
+}
+
+#[async_trait]
+impl SstFileProvider for LayerFileProvider {
+    async fn next_file(&mut self) -> SstFile {
+        let file_id = self.layer.new_file_id();
+        SstFile {
+            file_id,
+            file_path: self.layer.sst_file_path(file_id),
+            index_creator: self
+                .layer
+                .new_index_creator(
+                    file_id,
+                    &self.metadata,
+                    self.index_columns.as_deref(),
+                    &self.write_opts,
+                )
+                .await,
+        }
+    }
+
+    async fn remove_file(&mut self, file_id: FileId) {
+        self.layer.remove_partial_sst(file_id).await;
     }
 }
 
@@ -151,22 +895,116 @@ pub(crate) struct SstWriteRequest {
     pub(crate) source: Source,
     pub(crate) cache_manager: CacheManagerRef,
     pub(crate) storage: Option<String>,
+    /// Names of tag columns to index while writing the SST, no index is created if
+    /// it is `None`.
+    pub(crate) index_columns: Option<Vec<String>>,
+    /// Custom key-value metadata of columns, e.g. the lineage of data in the columns.
+    pub(crate) column_metadata: ColumnKeyValues,
+    /// Estimated size of data in the source, `None` if unknown.
+    pub(crate) source_size: Option<u64>,
+}
+
+/// Copies `files` from their source stores to the same paths in the `mirror_store`.
+///
+/// Logs the failure and stops mirroring remaining files if it fails to copy a file.
+pub(crate) async fn mirror_files(
+    region_id: RegionId,
+    file_id: FileId,
+    mirror_store: &ObjectStore,
+    files: &[(&ObjectStore, String, String)],
+) {
+    for (source_store, source_path, target_path) in files {
+        if let Err(e) = copy_object(source_store, source_path, mirror_store, target_path).await {
+            SST_MIRROR_FAILURES_TOTAL.inc();
+            warn!(
+                e; "Failed to mirror file {}, region_id: {}, file_id: {}",
+                target_path, region_id, file_id
+            );
+            return;
+        }
+    }
+}
+
+/// Copies an object between object stores.
+async fn copy_object(
+    source_store: &ObjectStore,
+    source_path: &str,
+    target_store: &ObjectStore,
+    target_path: &str,
+) -> std::io::Result<()> {
+    let reader = source_store.reader(source_path).await?;
+    let mut writer = target_store
+        .writer_with(target_path)
+        .buffer(DEFAULT_WRITE_BUFFER_SIZE.as_bytes() as usize)
+        .await?;
+    futures::io::copy(reader, &mut writer).await?;
+    // Must close to write all data.
+    writer.close().await?;
+    Ok(())
+}
+
+/// Default name of the atomic write dir under the root of a fs object store.
+const DEFAULT_ATOMIC_WRITE_DIR_NAME: &str = ".tmp";
+
+/// Config of a local fs object store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FsStoreConfig {
+    /// Root dir of the store.
+    pub(crate) root: String,
+    /// Name of the dir under the root to write files atomically, it's cleaned when the
+    /// store is created unless another process locks the dir.
+    pub(crate) atomic_write_dir_name: String,
+    /// Max number of concurrent operations on the store, unlimited if it's `None`.
+    pub(crate) concurrency: Option<usize>,
+}
+
+impl FsStoreConfig {
+    /// Returns the config of a store under `root` with default options.
+    pub(crate) fn new(root: &str) -> Self {
+        Self {
+            root: root.to_string(),
+            atomic_write_dir_name: DEFAULT_ATOMIC_WRITE_DIR_NAME.to_string(),
+            concurrency: None,
+        }
+    }
+
+    /// Limits the number of concurrent operations on the store, unlimited if
+    /// `concurrency` is 0.
+    pub(crate) fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = (concurrency > 0).then_some(concurrency);
+        self
+    }
 }
 
 /// Creates a fs object store with atomic write dir.
-pub(crate) async fn new_fs_object_store(root: &str) -> Result<ObjectStore> {
-    let atomic_write_dir = join_dir(root, ".tmp/");
-    clean_dir(&atomic_write_dir).await?;
+pub(crate) async fn new_fs_object_store(config: &FsStoreConfig) -> Result<ObjectStore> {
+    let root = &config.root;
+    let atomic_write_dir = join_dir(root, &config.atomic_write_dir_name);
+    clean_atomic_write_dir(&atomic_write_dir).await?;
 
     let mut builder = Fs::default();
     builder.root(root).atomic_write_dir(&atomic_write_dir);
-    let object_store = ObjectStore::new(builder).context(OpenDalSnafu)?.finish();
+    let mut object_store = ObjectStore::new(builder).context(OpenDalSnafu)?.finish();
+    if let Some(concurrency) = config.concurrency {
+        object_store = object_store.layer(ConcurrentLimitLayer::new(concurrency));
+    }
 
     // Add layers.
     let object_store = with_instrument_layers(object_store);
     Ok(object_store)
 }
 
+/// Locks the atomic write dir for the lifetime of the process and cleans it.
+///
+/// Returns an error instead of cleaning the dir if another process locks it, e.g.
+/// processes share the same data home by mistake, as cleaning the dir deletes files
+/// the other process is writing.
+async fn clean_atomic_write_dir(dir: &str) -> Result<()> {
+    lock_dir(dir).context(AtomicWriteDirLockedSnafu { dir })?;
+
+    clean_dir(dir).await
+}
+
 /// Clean the directory.
 async fn clean_dir(dir: &str) -> Result<()> {
     if tokio::fs::try_exists(dir)
@@ -180,3 +1018,555 @@ async fn clean_dir(dir: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+    use common_test_util::temp_dir::create_temp_dir;
+    use object_store::manager::ObjectStoreManager;
+
+    use super::*;
+    use crate::cache::test_util::new_fs_store;
+    use crate::cache::write_cache::WriteCache;
+    use crate::cache::CacheManager;
+    use crate::config::WriteCacheEvictionPolicy;
+    use crate::sst::index::limiter::IndexBuildLimiter;
+    use crate::sst::location::{self, DatePartitionedPath, FlatPath};
+    use crate::test_util::check_reader_result;
+    use crate::test_util::sst_util::{
+        new_batch_by_range, new_source, sst_file_handle, sst_region_metadata,
+    };
+
+    fn new_memory_layer(region_dir: &str) -> AccessLayer {
+        AccessLayer::new(
+            region_dir,
+            ObjectStore::new(Memory::default()).unwrap().finish(),
+            None,
+            Arc::new(FlatPath),
+        )
+    }
+
+    /// Writes a SST with 60 rows to the `layer`.
+    async fn write_sst(layer: &AccessLayer, file: &FileHandle) {
+        let mut writer = ParquetWriter::new(
+            file.file_id(),
+            file.file_path(layer.region_dir()),
+            Arc::new(sst_region_metadata()),
+            layer.object_store().clone(),
+        );
+        writer
+            .write_all(
+                new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]),
+                &WriteOptions::default(),
+            )
+            .await
+            .unwrap()
+            .remove(0);
+    }
+
+    #[tokio::test]
+    async fn test_copy_sst() {
+        let source = new_memory_layer("source/");
+        let file = sst_file_handle(0, 1000);
+        write_sst(&source, &file).await;
+        let mut file_meta = file.meta();
+        file_meta.available_indexes = SmallVec::from_iter([IndexType::InvertedIndex]);
+        let index_path = source.index_file_path(file_meta.file_id, file_meta.index_file_id());
+        source
+            .object_store()
+            .write(&index_path, b"index".to_vec())
+            .await
+            .unwrap();
+
+        // Copies between stores and between directories of the same store.
+        let targets = [
+            new_memory_layer("target/"),
+            AccessLayer::new(
+                "target/",
+                source.object_store().clone(),
+                None,
+                Arc::new(FlatPath),
+            ),
+        ];
+        for target in &targets {
+            source.copy_sst(&file_meta, target).await.unwrap();
+
+            let index_path = target.index_file_path(file_meta.file_id, file_meta.index_file_id());
+            let index = target.object_store().read(&index_path).await.unwrap();
+            assert_eq!(b"index", index.as_slice());
+            let mut reader = target.read_sst(file.clone()).build().await.unwrap();
+            check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_sst_clean_up_on_failure() {
+        let source = new_memory_layer("source/");
+        let file = sst_file_handle(0, 1000);
+        write_sst(&source, &file).await;
+        // The index file doesn't exist.
+        let mut file_meta = file.meta();
+        file_meta.available_indexes = SmallVec::from_iter([IndexType::InvertedIndex]);
+
+        let target = new_memory_layer("target/");
+        source.copy_sst(&file_meta, &target).await.unwrap_err();
+        // The SST already copied is removed.
+        let sst_path = target.sst_file_path(file_meta.file_id);
+        assert!(!target.object_store().is_exist(&sst_path).await.unwrap());
+    }
+
+    fn new_write_request(file_id: FileId) -> SstWriteRequest {
+        SstWriteRequest {
+            file_id,
+            metadata: Arc::new(sst_region_metadata()),
+            source: new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]),
+            cache_manager: Arc::new(CacheManager::default()),
+            storage: None,
+            index_columns: None,
+            column_metadata: ColumnKeyValues::new(),
+            source_size: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_sst_date_partitioned() {
+        let layer = AccessLayer::new(
+            "region/",
+            ObjectStore::new(Memory::default()).unwrap().finish(),
+            None,
+            Arc::new(DatePartitionedPath),
+        );
+        let file = sst_file_handle(0, 1000);
+        let request = SstWriteRequest {
+            index_columns: Some(vec!["tag_0".to_string()]),
+            ..new_write_request(file.file_id())
+        };
+        let sst_info = layer
+            .write_sst(request, &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+        assert!(sst_info.inverted_index_available);
+
+        // The SST and its index are under the directory of the date.
+        let date =
+            chrono::NaiveDateTime::from_timestamp_opt(file.file_id().created_secs().unwrap(), 0)
+                .unwrap()
+                .format("%Y/%m/%d")
+                .to_string();
+        let sst_dir = layer.sst_dir(file.file_id());
+        assert_eq!(format!("region/{date}/"), sst_dir);
+        for path in [
+            location::sst_file_path(&sst_dir, file.file_id()),
+            location::index_file_path(&sst_dir, file.file_id()),
+        ] {
+            assert!(layer.object_store().is_exist(&path).await.unwrap());
+        }
+        let mut reader = layer.read_sst(file.clone()).build().await.unwrap();
+        check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+    }
+
+    #[tokio::test]
+    async fn test_write_sst_wait_for_index_build_permit() {
+        let limiter = Arc::new(IndexBuildLimiter::new(1));
+        let layer =
+            Arc::new(new_memory_layer("region/").with_index_build_limiter(Some(limiter.clone())));
+        let permit = limiter.acquire().await;
+
+        let file = sst_file_handle(0, 1000);
+        let request = SstWriteRequest {
+            index_columns: Some(vec!["tag_0".to_string()]),
+            ..new_write_request(file.file_id())
+        };
+        let handle = tokio::spawn({
+            let layer = layer.clone();
+            async move { layer.write_sst(request, &WriteOptions::default()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // The write waits for a permit instead of skipping the index.
+        assert!(!handle.is_finished());
+
+        drop(permit);
+        let sst_info = handle.await.unwrap().unwrap().remove(0);
+        assert!(sst_info.inverted_index_available);
+    }
+
+    #[tokio::test]
+    async fn test_write_sst_to_mirror() {
+        let mirror_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let layer = AccessLayer::new(
+            "region/",
+            ObjectStore::new(Memory::default()).unwrap().finish(),
+            Some(mirror_store.clone()),
+            Arc::new(FlatPath),
+        );
+        let file = sst_file_handle(0, 1000);
+        layer
+            .write_sst(new_write_request(file.file_id()), &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+
+        let sst_path = file.file_path(layer.region_dir());
+        assert_eq!(
+            layer.object_store().read(&sst_path).await.unwrap(),
+            mirror_store.read(&sst_path).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_sst_mirror_failure() {
+        // The root of the mirror store is a file so it fails to write.
+        let dir = create_temp_dir("");
+        let root = dir.path().join("file");
+        std::fs::write(&root, b"file").unwrap();
+        let mut builder = Fs::default();
+        builder.root(root.to_str().unwrap());
+        let mirror_store = ObjectStore::new(builder).unwrap().finish();
+
+        let layer = AccessLayer::new(
+            "region/",
+            ObjectStore::new(Memory::default()).unwrap().finish(),
+            Some(mirror_store),
+            Arc::new(FlatPath),
+        );
+        let file = sst_file_handle(0, 1000);
+        let failures = SST_MIRROR_FAILURES_TOTAL.get();
+        // The write still succeeds.
+        layer
+            .write_sst(new_write_request(file.file_id()), &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+        assert!(SST_MIRROR_FAILURES_TOTAL.get() > failures);
+
+        let mut reader = layer.read_sst(file).build().await.unwrap();
+        check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+    }
+
+    #[tokio::test]
+    async fn test_write_sst_bypass_write_cache() {
+        let layer = new_memory_layer("region/");
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let object_store_manager = Arc::new(ObjectStoreManager::new(
+            "default",
+            layer.object_store().clone(),
+        ));
+        let write_cache = WriteCache::new(
+            local_store.clone(),
+            object_store_manager,
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        )
+        .await
+        .unwrap()
+        .with_bypass_size(Some(ReadableSize(100)));
+        let write_cache = Arc::new(write_cache);
+        let cache_manager = Arc::new(
+            CacheManager::builder()
+                .sst_meta_cache_size(ReadableSize::mb(1).as_bytes())
+                .write_cache(Some(write_cache.clone()))
+                .build(),
+        );
+
+        // (source size, bypass)
+        for (source_size, bypass) in [(None, false), (Some(100), false), (Some(10000), true)] {
+            let file = sst_file_handle(0, 1000);
+            let region_id = file.region_id();
+            let request = SstWriteRequest {
+                cache_manager: cache_manager.clone(),
+                source_size,
+                ..new_write_request(file.file_id())
+            };
+            let bypassed = WRITE_CACHE_BYPASS_TOTAL.get();
+            layer
+                .write_sst(request, &WriteOptions::default())
+                .await
+                .unwrap()
+                .remove(0);
+
+            // Other tests might bypass the write cache concurrently.
+            assert!(!bypass || WRITE_CACHE_BYPASS_TOTAL.get() > bypassed);
+            let key = IndexKey::new(region_id, file.file_id(), FileType::Parquet);
+            assert_eq!(
+                !bypass,
+                write_cache.file_cache().contains_key(&key),
+                "source_size: {source_size:?}"
+            );
+            // The metadata is cached either way.
+            assert!(cache_manager
+                .get_parquet_meta_data(region_id, file.file_id())
+                .is_some());
+            let mut reader = layer.read_sst(file).build().await.unwrap();
+            check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_sst_roll_over() {
+        let layer = new_memory_layer("region/");
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let object_store_manager = Arc::new(ObjectStoreManager::new(
+            "default",
+            layer.object_store().clone(),
+        ));
+        let write_cache = WriteCache::new(
+            local_store,
+            object_store_manager,
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        )
+        .await
+        .unwrap();
+        let batches = [
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+            new_batch_by_range(&["b", "h"], 100, 200),
+        ];
+        // Each batch fills row groups so SSTs roll over after each batch.
+        let write_opts = WriteOptions {
+            row_group_size: 10,
+            max_file_size: Some(1),
+            ..Default::default()
+        };
+
+        // Writes SSTs directly and through the write cache.
+        for write_cache in [None, Some(Arc::new(write_cache))] {
+            let cache_manager = Arc::new(CacheManager::builder().write_cache(write_cache).build());
+            let file = sst_file_handle(0, 1000);
+            let request = SstWriteRequest {
+                source: new_source(&batches),
+                cache_manager,
+                ..new_write_request(file.file_id())
+            };
+            let sst_infos = layer.write_sst(request, &write_opts).await.unwrap();
+
+            assert_eq!(batches.len(), sst_infos.len());
+            assert_eq!(file.file_id(), sst_infos[0].file_id);
+            for (sst_info, batch) in sst_infos.iter().zip(&batches) {
+                assert_eq!(batch.num_rows(), sst_info.num_rows);
+                assert_eq!(
+                    (
+                        batch.first_timestamp().unwrap(),
+                        batch.last_timestamp().unwrap()
+                    ),
+                    sst_info.time_range
+                );
+                let sst_path = layer.sst_file_path(sst_info.file_id);
+                assert!(layer.object_store().is_exist(&sst_path).await.unwrap());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_fs_object_store() {
+        let dir = create_temp_dir("");
+        let root = dir.path().to_str().unwrap();
+        let default_config = FsStoreConfig::new(root);
+        assert_eq!(".tmp", default_config.atomic_write_dir_name);
+        assert_eq!(None, default_config.concurrency);
+        assert_eq!(None, default_config.clone().with_concurrency(0).concurrency);
+
+        // Leftover files in the atomic write dir.
+        let atomic_write_dir = dir.path().join("atomic");
+        tokio::fs::create_dir_all(&atomic_write_dir).await.unwrap();
+        tokio::fs::write(atomic_write_dir.join("leftover"), b"leftover")
+            .await
+            .unwrap();
+
+        let config = FsStoreConfig {
+            atomic_write_dir_name: "atomic".to_string(),
+            ..default_config.with_concurrency(2)
+        };
+        let object_store = new_fs_object_store(&config).await.unwrap();
+        assert!(!tokio::fs::try_exists(atomic_write_dir.join("leftover"))
+            .await
+            .unwrap());
+
+        object_store
+            .write("data/a", b"hello".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            b"hello".to_vec(),
+            object_store.read("data/a").await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write_dir_lock() {
+        let dir = create_temp_dir("");
+        let root = dir.path().to_str().unwrap();
+        let config = FsStoreConfig::new(root);
+        let atomic_write_dir = dir.path().join(&config.atomic_write_dir_name);
+
+        new_fs_object_store(&config).await.unwrap();
+        tokio::fs::create_dir_all(&atomic_write_dir).await.unwrap();
+        tokio::fs::write(atomic_write_dir.join("writing"), b"writing")
+            .await
+            .unwrap();
+        // The process holds the lock so it can create the store again.
+        new_fs_object_store(&config).await.unwrap();
+        assert!(!tokio::fs::try_exists(atomic_write_dir.join("writing"))
+            .await
+            .unwrap());
+        let lock_file = dir
+            .path()
+            .join(format!("{}.lock", config.atomic_write_dir_name));
+        assert!(tokio::fs::try_exists(lock_file).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_estimate_sst_size() {
+        let layer = new_memory_layer("region/");
+        let file = sst_file_handle(0, 1000);
+        let batches = [
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+        ];
+        let request = SstWriteRequest {
+            source: Source::Batches(VecDeque::from(batches.to_vec())),
+            ..new_write_request(file.file_id())
+        };
+        let write_opts = WriteOptions::default();
+        let estimated = layer
+            .estimate_sst_size(&request, &write_opts)
+            .await
+            .unwrap();
+        // Nothing is written to the layer.
+        let sst_path = file.file_path(layer.region_dir());
+        assert!(!layer.object_store().is_exist(&sst_path).await.unwrap());
+
+        // The source is still complete after the estimate.
+        let sst_info = layer
+            .write_sst(request, &write_opts)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(sst_info.file_size as usize, estimated);
+        let mut reader = layer.read_sst(file).build().await.unwrap();
+        check_reader_result(&mut reader, &batches).await;
+
+        // Empty source.
+        let request = SstWriteRequest {
+            source: Source::Batches(VecDeque::new()),
+            ..new_write_request(FileId::random())
+        };
+        assert_eq!(
+            0,
+            layer
+                .estimate_sst_size(&request, &write_opts)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_sst_size_not_replayable() {
+        let layer = new_memory_layer("region/");
+        let request = new_write_request(FileId::random());
+        let err = layer
+            .estimate_sst_size(&request, &WriteOptions::default())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::Unsupported, err.status_code());
+
+        // Uses the source size of the request.
+        let request = SstWriteRequest {
+            source_size: Some(100),
+            ..new_write_request(FileId::random())
+        };
+        assert_eq!(
+            100,
+            layer
+                .estimate_sst_size(&request, &WriteOptions::default())
+                .await
+                .unwrap()
+        );
+    }
+
+    /// Writes a SST and its index to the `layer` and returns the meta of the SST.
+    async fn write_sst_with_index(layer: &AccessLayer) -> FileMeta {
+        let file = sst_file_handle(0, 1000);
+        write_sst(layer, &file).await;
+        let mut file_meta = file.meta();
+        file_meta.available_indexes = SmallVec::from_iter([IndexType::InvertedIndex]);
+        let index_path = location::index_file_path(layer.region_dir(), file_meta.file_id);
+        layer
+            .object_store()
+            .write(&index_path, b"index".to_vec())
+            .await
+            .unwrap();
+        file_meta
+    }
+
+    #[tokio::test]
+    async fn test_delete_ssts() {
+        let layer = new_memory_layer("region/");
+        let mut file_metas = Vec::new();
+        for _ in 0..3 {
+            file_metas.push(write_sst_with_index(&layer).await);
+        }
+        // A SST without index.
+        let file = sst_file_handle(0, 1000);
+        write_sst(&layer, &file).await;
+        file_metas.push(file.meta());
+
+        let failures = layer.delete_ssts(&file_metas).await.unwrap();
+        assert!(failures.is_empty(), "unexpected failures: {failures:?}");
+        let entries = layer.object_store().list("region/").await.unwrap();
+        assert!(
+            entries.iter().all(|entry| entry.metadata().is_dir()),
+            "unexpected entries: {entries:?}"
+        );
+
+        // Deleting files again is fine.
+        assert!(layer.delete_ssts(&file_metas).await.unwrap().is_empty());
+        layer.delete_sst(&file_metas[0]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_ssts_partial_failure() {
+        let dir = create_temp_dir("");
+        let layer = AccessLayer::new(
+            "region/",
+            new_fs_store(dir.path().to_str().unwrap()),
+            None,
+            Arc::new(FlatPath),
+        );
+        let deleted = write_sst_with_index(&layer).await;
+        // The SST path of the file is a non-empty directory so it fails to delete.
+        let failed = sst_file_handle(0, 1000).meta();
+        let sst_path = location::sst_file_path(layer.region_dir(), failed.file_id);
+        layer
+            .object_store()
+            .write(&format!("{sst_path}/file"), b"file".to_vec())
+            .await
+            .unwrap();
+
+        let failures = layer
+            .delete_ssts(&[deleted.clone(), failed.clone()])
+            .await
+            .unwrap();
+        assert_eq!(1, failures.len(), "unexpected failures: {failures:?}");
+        assert_eq!(failed.file_id, failures[0].0);
+        assert!(matches!(failures[0].1, Error::DeleteSst { .. }));
+        for path in [
+            location::sst_file_path(layer.region_dir(), deleted.file_id),
+            location::index_file_path(layer.region_dir(), deleted.file_id),
+        ] {
+            assert!(!layer.object_store().is_exist(&path).await.unwrap());
+        }
+
+        let err = layer.delete_sst(&failed).await.unwrap_err();
+        assert!(
+            matches!(err, Error::DeleteSst { .. }),
+            "unexpected error: {err:?}"
+        );
+    }
+}
@@ -18,6 +18,7 @@ mod cache_size;
 // TODO(yingwen): Remove this after the write cache is ready.
 #[allow(unused)]
 pub(crate) mod file_cache;
+pub(crate) mod reader_cache;
 #[cfg(test)]
 pub(crate) mod test_util;
 #[allow(unused)]
@@ -34,6 +35,7 @@ use parquet::file::metadata::ParquetMetaData;
 use store_api::storage::RegionId;
 
 use crate::cache::cache_size::parquet_meta_size;
+use crate::cache::reader_cache::{SstReaderCache, SstReaderRef};
 use crate::cache::write_cache::WriteCacheRef;
 use crate::metrics::{CACHE_BYTES, CACHE_HIT, CACHE_MISS};
 use crate::sst::file::FileId;
@@ -46,6 +48,10 @@ const VECTOR_TYPE: &str = "vector";
 const PAGE_TYPE: &str = "page";
 // Metrics type key for files on the local store.
 const FILE_TYPE: &str = "file";
+// Metrics type key for open SST readers.
+const SST_READER_TYPE: &str = "sst_reader";
+// Metrics type key for row groups read by queries.
+const ROW_GROUP_HINT_TYPE: &str = "row_group_hint";
 
 /// Manages cached data for the engine.
 ///
@@ -60,6 +66,10 @@ pub struct CacheManager {
     page_cache: Option<PageCache>,
     /// A Cache for writing files to object stores.
     write_cache: Option<WriteCacheRef>,
+    /// Cache for open SST readers.
+    sst_reader_cache: Option<SstReaderCache>,
+    /// Cache for row groups of SSTs read by queries.
+    row_group_hint_cache: Option<RowGroupHintCache>,
 }
 
 pub type CacheManagerRef = Arc<CacheManager>;
@@ -141,6 +151,71 @@ impl CacheManager {
         }
     }
 
+    /// Returns true if the cache keeps open readers of SSTs.
+    pub(crate) fn has_sst_reader_cache(&self) -> bool {
+        self.sst_reader_cache.is_some()
+    }
+
+    /// Gets the open reader of the SST.
+    pub(crate) fn get_sst_reader(
+        &self,
+        region_id: RegionId,
+        file_id: FileId,
+    ) -> Option<SstReaderRef> {
+        self.sst_reader_cache.as_ref().and_then(|cache| {
+            let value = cache.get(region_id, file_id);
+            update_hit_miss(value, SST_READER_TYPE)
+        })
+    }
+
+    /// Puts the open reader of the SST into the cache and returns the reader to use,
+    /// which is the reader already in the cache if there is one.
+    pub(crate) fn put_sst_reader(
+        &self,
+        region_id: RegionId,
+        file_id: FileId,
+        reader: SstReaderRef,
+    ) -> SstReaderRef {
+        match &self.sst_reader_cache {
+            Some(cache) => cache.put(region_id, file_id, reader),
+            None => reader,
+        }
+    }
+
+    /// Removes the open reader of the SST from the cache.
+    pub fn remove_sst_reader(&self, region_id: RegionId, file_id: FileId) {
+        if let Some(cache) = &self.sst_reader_cache {
+            cache.remove(region_id, file_id);
+        }
+    }
+
+    /// Gets row groups of the SST read by the same query before.
+    pub(crate) fn get_row_group_hint(&self, key: &RowGroupHintKey) -> Option<Arc<Vec<usize>>> {
+        self.row_group_hint_cache.as_ref().and_then(|cache| {
+            let value = cache.get(key);
+            update_hit_miss(value, ROW_GROUP_HINT_TYPE)
+        })
+    }
+
+    /// Puts row groups of the SST read by the query into the cache.
+    pub(crate) fn put_row_group_hint(&self, key: RowGroupHintKey, row_groups: Arc<Vec<usize>>) {
+        if let Some(cache) = &self.row_group_hint_cache {
+            cache.insert(key, row_groups);
+        }
+    }
+
+    /// Removes row groups of the SST read by all queries, e.g. the SST is deleted.
+    pub fn remove_row_group_hints(&self, region_id: RegionId, file_id: FileId) {
+        if let Some(cache) = &self.row_group_hint_cache {
+            // Safety: the cache supports invalidation closures.
+            let _ = cache
+                .invalidate_entries_if(move |key, _| {
+                    key.region_id == region_id && key.file_id == file_id
+                })
+                .unwrap();
+        }
+    }
+
     /// Gets the the write cache.
     pub(crate) fn write_cache(&self) -> Option<&WriteCacheRef> {
         self.write_cache.as_ref()
@@ -154,6 +229,8 @@ pub struct CacheManagerBuilder {
     vector_cache_size: u64,
     page_cache_size: u64,
     write_cache: Option<WriteCacheRef>,
+    sst_reader_cache_size: usize,
+    row_group_hint_cache_size: u64,
 }
 
 impl CacheManagerBuilder {
@@ -181,6 +258,18 @@ impl CacheManagerBuilder {
         self
     }
 
+    /// Sets max number of open SST readers to cache.
+    pub fn sst_reader_cache_size(mut self, num_readers: usize) -> Self {
+        self.sst_reader_cache_size = num_readers;
+        self
+    }
+
+    /// Sets max number of (query, SST) pairs whose row groups read are cached.
+    pub fn row_group_hint_cache_size(mut self, num_entries: u64) -> Self {
+        self.row_group_hint_cache_size = num_entries;
+        self
+    }
+
     /// Builds the [CacheManager].
     pub fn build(self) -> CacheManager {
         let sst_meta_cache = (self.sst_meta_cache_size != 0).then(|| {
@@ -218,11 +307,23 @@ impl CacheManagerBuilder {
                 .build()
         });
 
+        let sst_reader_cache = (self.sst_reader_cache_size != 0)
+            .then(|| SstReaderCache::new(self.sst_reader_cache_size));
+
+        let row_group_hint_cache = (self.row_group_hint_cache_size != 0).then(|| {
+            Cache::builder()
+                .max_capacity(self.row_group_hint_cache_size)
+                .support_invalidation_closures()
+                .build()
+        });
+
         CacheManager {
             sst_meta_cache,
             vector_cache,
             page_cache,
             write_cache: self.write_cache,
+            sst_reader_cache,
+            row_group_hint_cache,
         }
     }
 }
@@ -282,6 +383,15 @@ impl PageKey {
     }
 }
 
+/// Cache key for row groups of a SST read by a query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RowGroupHintKey {
+    pub(crate) region_id: RegionId,
+    pub(crate) file_id: FileId,
+    /// Fingerprint of the query.
+    pub(crate) query: u64,
+}
+
 /// Cached row group pages for a column.
 pub struct PageValue {
     /// All pages of the column in the row group.
@@ -309,6 +419,8 @@ type SstMetaCache = Cache<SstMetaKey, Arc<ParquetMetaData>>;
 type VectorCache = Cache<Value, VectorRef>;
 /// Maps (region, file, row group, column) to [PageValue].
 type PageCache = Cache<PageKey, Arc<PageValue>>;
+/// Maps (region, file, query) to indices of row groups read by the query.
+type RowGroupHintCache = Cache<RowGroupHintKey, Arc<Vec<usize>>>;
 
 #[cfg(test)]
 mod tests {
@@ -388,4 +500,25 @@ mod tests {
         cache.put_pages(key.clone(), pages);
         assert!(cache.get_pages(&key).is_some());
     }
+
+    #[test]
+    fn test_row_group_hint_cache() {
+        let cache = CacheManager::builder()
+            .row_group_hint_cache_size(10)
+            .build();
+        let region_id = RegionId::new(1, 1);
+        let file_id = FileId::random();
+        let key = RowGroupHintKey {
+            region_id,
+            file_id,
+            query: 1,
+        };
+        assert!(cache.get_row_group_hint(&key).is_none());
+        cache.put_row_group_hint(key.clone(), Arc::new(vec![0, 2]));
+        assert_eq!(vec![0, 2], *cache.get_row_group_hint(&key).unwrap());
+
+        // Removes hints of the file.
+        cache.remove_row_group_hints(region_id, file_id);
+        assert!(cache.get_row_group_hint(&key).is_none());
+    }
 }
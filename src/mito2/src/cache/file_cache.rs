@@ -14,8 +14,9 @@
 
 //! A cache for files.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::{Range, RangeBounds};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use bytes::Bytes;
@@ -30,6 +31,7 @@ use snafu::ResultExt;
 use store_api::storage::RegionId;
 
 use crate::cache::FILE_TYPE;
+use crate::config::WriteCacheEvictionPolicy;
 use crate::error::{OpenDalSnafu, Result};
 use crate::metrics::{CACHE_BYTES, CACHE_HIT, CACHE_MISS};
 use crate::sst::file::FileId;
@@ -48,20 +50,32 @@ pub(crate) struct FileCache {
     ///
     /// File id is enough to identity a file uniquely.
     memory_index: Cache<IndexKey, IndexValue>,
+    /// Max size of files in the cache.
+    capacity: u64,
+    eviction_policy: WriteCacheEvictionPolicy,
+    /// Order to evict files in the index.
+    eviction_order: Mutex<EvictionOrder>,
 }
 
 pub(crate) type FileCacheRef = Arc<FileCache>;
 
 impl FileCache {
-    /// Creates a new file cache.
-    pub(crate) fn new(local_store: ObjectStore, capacity: ReadableSize) -> FileCache {
+    /// Creates a new file cache that evicts files by the `eviction_policy` once the
+    /// size of files exceeds the `capacity`.
+    ///
+    /// The cache only evicts files in its index so files staged but not uploaded
+    /// are never evicted.
+    pub(crate) fn new(
+        local_store: ObjectStore,
+        capacity: ReadableSize,
+        eviction_policy: WriteCacheEvictionPolicy,
+    ) -> FileCache {
         let cache_store = local_store.clone();
         let memory_index = Cache::builder()
             .weigher(|_key, value: &IndexValue| -> u32 {
                 // We only measure space on local store.
                 value.file_size
             })
-            .max_capacity(capacity.as_bytes())
             .async_eviction_listener(move |key, value, cause| {
                 let store = cache_store.clone();
                 // Stores files under FILE_DIR.
@@ -90,6 +104,9 @@ impl FileCache {
         FileCache {
             local_store,
             memory_index,
+            capacity: capacity.as_bytes(),
+            eviction_policy,
+            eviction_order: Mutex::new(EvictionOrder::default()),
         }
     }
 
@@ -100,7 +117,37 @@ impl FileCache {
         CACHE_BYTES
             .with_label_values(&[FILE_TYPE])
             .add(value.file_size.into());
+        let file_size = value.file_size;
         self.memory_index.insert(key, value).await;
+        self.eviction_order.lock().unwrap().push(key, file_size);
+
+        self.evict().await;
+    }
+
+    /// Evicts files until the size of files doesn't exceed the capacity. The eviction
+    /// listener removes evicted files and updates the metrics.
+    async fn evict(&self) {
+        let victims = self
+            .eviction_order
+            .lock()
+            .unwrap()
+            .pop_victims(self.capacity);
+        for key in victims {
+            self.memory_index.invalidate(&key).await;
+        }
+    }
+
+    /// Records an access of the file.
+    fn touch(&self, key: &IndexKey) {
+        if self.eviction_policy == WriteCacheEvictionPolicy::Lru {
+            self.eviction_order.lock().unwrap().touch(key);
+        }
+    }
+
+    /// Removes the file from the index.
+    async fn remove_index(&self, key: &IndexKey) {
+        self.memory_index.remove(key).await;
+        self.eviction_order.lock().unwrap().remove(key);
     }
 
     /// Reads a file from the cache.
@@ -116,6 +163,7 @@ impl FileCache {
         match self.get_reader(&file_path).await {
             Ok(Some(reader)) => {
                 CACHE_HIT.with_label_values(&[FILE_TYPE]).inc();
+                self.touch(&key);
                 return Some(reader);
             }
             Err(e) => {
@@ -127,7 +175,7 @@ impl FileCache {
         }
 
         // We removes the file from the index.
-        self.memory_index.remove(&key).await;
+        self.remove_index(&key).await;
         CACHE_MISS.with_label_values(&[FILE_TYPE]).inc();
         None
     }
@@ -150,6 +198,7 @@ impl FileCache {
         match bytes_result {
             Ok(bytes) => {
                 CACHE_HIT.with_label_values(&[FILE_TYPE]).inc();
+                self.touch(&key);
                 Some(bytes)
             }
             Err(e) => {
@@ -158,7 +207,7 @@ impl FileCache {
                 }
 
                 // We removes the file from the index.
-                self.memory_index.remove(&key).await;
+                self.remove_index(&key).await;
                 CACHE_MISS.with_label_values(&[FILE_TYPE]).inc();
                 None
             }
@@ -168,14 +217,15 @@ impl FileCache {
     /// Removes a file from the cache explicitly.
     pub(crate) async fn remove(&self, key: IndexKey) {
         let file_path = self.cache_file_path(key);
-        self.memory_index.remove(&key).await;
+        self.remove_index(&key).await;
         if let Err(e) = self.local_store.delete(&file_path).await {
             warn!(e; "Failed to delete a cached file {}", file_path);
         }
     }
 
-    /// Recovers the index from local store.
-    pub(crate) async fn recover(&self) -> Result<()> {
+    /// Recovers the index from local store, skipping files in `excludes`, e.g. files
+    /// still pending upload.
+    pub(crate) async fn recover(&self, excludes: &HashSet<IndexKey>) -> Result<()> {
         let now = Instant::now();
 
         let mut lister = self
@@ -193,10 +243,14 @@ impl FileCache {
             let Some(key) = parse_index_key(entry.name()) else {
                 continue;
             };
+            if excludes.contains(&key) {
+                continue;
+            }
             let file_size = meta.content_length() as u32;
             self.memory_index
                 .insert(key, IndexValue { file_size })
                 .await;
+            self.eviction_order.lock().unwrap().push(key, file_size);
             total_size += file_size;
             total_keys += 1;
         }
@@ -211,6 +265,8 @@ impl FileCache {
             total_size,
             now.elapsed()
         );
+        // The capacity might be smaller than before.
+        self.evict().await;
 
         Ok(())
     }
@@ -240,6 +296,69 @@ impl FileCache {
     }
 }
 
+/// Order to evict files, files with smaller sequences are evicted first.
+#[derive(Debug, Default)]
+struct EvictionOrder {
+    /// Sequence and size of each file.
+    files: HashMap<IndexKey, (u64, u32)>,
+    /// Files ordered by sequences.
+    sequences: BTreeMap<u64, IndexKey>,
+    next_sequence: u64,
+    /// Total size of files.
+    total_size: u64,
+}
+
+impl EvictionOrder {
+    /// Pushes a file to the end of the order, replacing the file with the same key.
+    fn push(&mut self, key: IndexKey, file_size: u32) {
+        self.remove(&key);
+        let sequence = self.allocate_sequence();
+        self.files.insert(key, (sequence, file_size));
+        self.sequences.insert(sequence, key);
+        self.total_size += u64::from(file_size);
+    }
+
+    /// Moves the file to the end of the order.
+    fn touch(&mut self, key: &IndexKey) {
+        let next_sequence = self.next_sequence;
+        let Some((sequence, _)) = self.files.get_mut(key) else {
+            return;
+        };
+        self.sequences.remove(sequence);
+        *sequence = next_sequence;
+        self.sequences.insert(next_sequence, *key);
+        self.next_sequence += 1;
+    }
+
+    fn remove(&mut self, key: &IndexKey) {
+        if let Some((sequence, file_size)) = self.files.remove(key) {
+            self.sequences.remove(&sequence);
+            self.total_size -= u64::from(file_size);
+        }
+    }
+
+    /// Pops files to evict until the total size doesn't exceed the `capacity`.
+    fn pop_victims(&mut self, capacity: u64) -> Vec<IndexKey> {
+        let mut victims = Vec::new();
+        while self.total_size > capacity {
+            let Some((_, key)) = self.sequences.pop_first() else {
+                break;
+            };
+            if let Some((_, file_size)) = self.files.remove(&key) {
+                self.total_size -= u64::from(file_size);
+            }
+            victims.push(key);
+        }
+        victims
+    }
+
+    fn allocate_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+}
+
 /// Key of file cache index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) struct IndexKey {
@@ -343,7 +462,11 @@ mod tests {
         let dir = create_temp_dir("");
         let local_store = new_fs_store(dir.path().to_str().unwrap());
 
-        let cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+        let cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        );
         let region_id = RegionId::new(2000, 0);
         let file_id = FileId::random();
         let key = IndexKey::new(region_id, file_id, FileType::Parquet);
@@ -392,7 +515,11 @@ mod tests {
         let dir = create_temp_dir("");
         let local_store = new_fs_store(dir.path().to_str().unwrap());
 
-        let cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+        let cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        );
         let region_id = RegionId::new(2000, 0);
         let file_id = FileId::random();
         let key = IndexKey::new(region_id, file_id, FileType::Parquet);
@@ -424,7 +551,11 @@ mod tests {
     async fn test_file_cache_recover() {
         let dir = create_temp_dir("");
         let local_store = new_fs_store(dir.path().to_str().unwrap());
-        let cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+        let cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        );
 
         let region_id = RegionId::new(2000, 0);
         let file_type = FileType::Parquet;
@@ -450,13 +581,17 @@ mod tests {
         }
 
         // Recover the cache.
-        let cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+        let cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        );
         // No entry before recovery.
         assert!(cache
             .reader(IndexKey::new(region_id, file_ids[0], file_type))
             .await
             .is_none());
-        cache.recover().await.unwrap();
+        cache.recover(&HashSet::new()).await.unwrap();
 
         // Check size.
         cache.memory_index.run_pending_tasks().await;
@@ -475,7 +610,11 @@ mod tests {
     async fn test_file_cache_read_ranges() {
         let dir = create_temp_dir("");
         let local_store = new_fs_store(dir.path().to_str().unwrap());
-        let file_cache = FileCache::new(local_store.clone(), ReadableSize::mb(10));
+        let file_cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        );
         let region_id = RegionId::new(2000, 0);
         let file_id = FileId::random();
         let key = IndexKey::new(region_id, file_id, FileType::Parquet);
@@ -542,4 +681,79 @@ mod tests {
         )
         .is_none());
     }
+
+    /// Puts a file of `file_size` bytes into the cache.
+    async fn put_file(cache: &FileCache, local_store: &ObjectStore, file_size: u32) -> IndexKey {
+        let key = IndexKey::new(RegionId::new(2000, 0), FileId::random(), FileType::Parquet);
+        local_store
+            .write(&cache.cache_file_path(key), vec![0; file_size as usize])
+            .await
+            .unwrap();
+        cache.put(key, IndexValue { file_size }).await;
+        key
+    }
+
+    #[tokio::test]
+    async fn test_file_cache_evict() {
+        for (policy, expect_evicted) in [
+            // The first file is accessed so the second file is evicted.
+            (WriteCacheEvictionPolicy::Lru, 1),
+            (WriteCacheEvictionPolicy::Fifo, 0),
+        ] {
+            let dir = create_temp_dir("");
+            let local_store = new_fs_store(dir.path().to_str().unwrap());
+            let cache = FileCache::new(local_store.clone(), ReadableSize(10), policy);
+
+            let mut keys = Vec::new();
+            keys.push(put_file(&cache, &local_store, 4).await);
+            keys.push(put_file(&cache, &local_store, 4).await);
+            assert!(cache.reader(keys[0]).await.is_some());
+            // Exceeds the capacity.
+            keys.push(put_file(&cache, &local_store, 4).await);
+
+            cache.memory_index.run_pending_tasks().await;
+            assert_eq!(8, cache.memory_index.weighted_size(), "{policy:?}");
+            for (i, key) in keys.iter().enumerate() {
+                let exists = local_store
+                    .is_exist(&cache.cache_file_path(*key))
+                    .await
+                    .unwrap();
+                assert_eq!(i != expect_evicted, exists, "{policy:?}, file {i}");
+                assert_eq!(
+                    i != expect_evicted,
+                    cache.contains_key(key),
+                    "{policy:?}, file {i}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_cache_recover_excludes() {
+        let dir = create_temp_dir("");
+        let local_store = new_fs_store(dir.path().to_str().unwrap());
+        let cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Fifo,
+        );
+        let excluded = put_file(&cache, &local_store, 8).await;
+        let key = put_file(&cache, &local_store, 8).await;
+        drop(cache);
+
+        let cache = FileCache::new(
+            local_store.clone(),
+            ReadableSize(10),
+            WriteCacheEvictionPolicy::Fifo,
+        );
+        cache.recover(&HashSet::from([excluded])).await.unwrap();
+        cache.memory_index.run_pending_tasks().await;
+        // The excluded file is neither recovered nor evicted.
+        assert!(!cache.contains_key(&excluded));
+        assert!(local_store
+            .is_exist(&cache.cache_file_path(excluded))
+            .await
+            .unwrap());
+        assert!(cache.contains_key(&key));
+    }
 }
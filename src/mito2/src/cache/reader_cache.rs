@@ -0,0 +1,208 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A cache for open readers of SSTs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use object_store::Reader;
+use store_api::storage::RegionId;
+
+use crate::sst::file::FileId;
+
+/// An open reader of a SST shared by readers of the SST.
+pub(crate) type SstReaderRef = Arc<tokio::sync::Mutex<Reader>>;
+
+/// Caches open readers of SSTs so queries reuse them instead of opening the
+/// SSTs again, and closes the least recently used readers once there are more
+/// readers than the capacity.
+///
+/// Readers in use are never closed by the cache, so the cache may hold more
+/// readers than the capacity if all of them are in use.
+pub(crate) struct SstReaderCache {
+    /// Max number of readers in the cache.
+    capacity: usize,
+    readers: Mutex<ReaderOrder>,
+}
+
+impl SstReaderCache {
+    /// Creates a new cache that holds at most `capacity` readers.
+    pub(crate) fn new(capacity: usize) -> SstReaderCache {
+        SstReaderCache {
+            capacity,
+            readers: Mutex::new(ReaderOrder::default()),
+        }
+    }
+
+    /// Gets the reader of the SST and marks it as the most recently used one.
+    pub(crate) fn get(&self, region_id: RegionId, file_id: FileId) -> Option<SstReaderRef> {
+        self.readers.lock().unwrap().touch(&(region_id, file_id))
+    }
+
+    /// Puts the reader of the SST into the cache and returns the reader in the cache.
+    ///
+    /// Keeps the reader in the cache if another reader of the same SST is put concurrently.
+    pub(crate) fn put(
+        &self,
+        region_id: RegionId,
+        file_id: FileId,
+        reader: SstReaderRef,
+    ) -> SstReaderRef {
+        let mut readers = self.readers.lock().unwrap();
+        let key = (region_id, file_id);
+        if let Some(cached) = readers.touch(&key) {
+            return cached;
+        }
+        readers.push(key, reader.clone());
+        readers.evict(self.capacity);
+        reader
+    }
+
+    /// Removes the reader of the SST, e.g. the SST is deleted.
+    ///
+    /// The reader is closed once it isn't in use.
+    pub(crate) fn remove(&self, region_id: RegionId, file_id: FileId) {
+        self.readers.lock().unwrap().remove(&(region_id, file_id));
+    }
+
+    /// Returns the number of readers in the cache.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.readers.lock().unwrap().readers.len()
+    }
+}
+
+/// Key (region id, file id) of a reader.
+type ReaderKey = (RegionId, FileId);
+
+/// Readers ordered by the time they are used.
+#[derive(Default)]
+struct ReaderOrder {
+    /// Sequence of each reader.
+    readers: HashMap<ReaderKey, (u64, SstReaderRef)>,
+    /// Readers ordered by sequences.
+    sequences: BTreeMap<u64, ReaderKey>,
+    next_sequence: u64,
+}
+
+impl ReaderOrder {
+    /// Pushes a reader to the end of the order.
+    fn push(&mut self, key: ReaderKey, reader: SstReaderRef) {
+        self.remove(&key);
+        let sequence = self.allocate_sequence();
+        self.readers.insert(key, (sequence, reader));
+        self.sequences.insert(sequence, key);
+    }
+
+    /// Moves the reader to the end of the order and returns the reader.
+    fn touch(&mut self, key: &ReaderKey) -> Option<SstReaderRef> {
+        let next_sequence = self.next_sequence;
+        let (sequence, reader) = self.readers.get_mut(key)?;
+        self.sequences.remove(sequence);
+        *sequence = next_sequence;
+        self.sequences.insert(next_sequence, *key);
+        self.next_sequence += 1;
+        Some(reader.clone())
+    }
+
+    fn remove(&mut self, key: &ReaderKey) {
+        if let Some((sequence, _)) = self.readers.remove(key) {
+            self.sequences.remove(&sequence);
+        }
+    }
+
+    /// Removes the least recently used readers not in use until there are at most
+    /// `capacity` readers.
+    fn evict(&mut self, capacity: usize) {
+        while self.readers.len() > capacity {
+            // Only the cache holds readers not in use.
+            let victim = self
+                .sequences
+                .values()
+                .find(|key| Arc::strong_count(&self.readers[*key].1) == 1)
+                .copied();
+            let Some(key) = victim else {
+                // All readers are in use.
+                return;
+            };
+            self.remove(&key);
+        }
+    }
+
+    fn allocate_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_test_util::temp_dir::create_temp_dir;
+    use object_store::services::Fs;
+    use object_store::ObjectStore;
+
+    use super::*;
+
+    async fn new_reader(store: &ObjectStore, file_id: FileId) -> SstReaderRef {
+        let path = file_id.as_parquet();
+        store.write(&path, b"sst".to_vec()).await.unwrap();
+        Arc::new(tokio::sync::Mutex::new(store.reader(&path).await.unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_sst_reader_cache() {
+        let dir = create_temp_dir("");
+        let mut builder = Fs::default();
+        builder.root(dir.path().to_str().unwrap());
+        let store = ObjectStore::new(builder).unwrap().finish();
+        let region_id = RegionId::new(1, 1);
+        let cache = SstReaderCache::new(2);
+
+        // Opens more files than the capacity.
+        let file_ids: Vec<_> = (0..3).map(|_| FileId::random()).collect();
+        let mut readers = Vec::new();
+        for file_id in &file_ids {
+            let reader = new_reader(&store, *file_id).await;
+            readers.push(Arc::downgrade(&cache.put(region_id, *file_id, reader)));
+        }
+        // The oldest reader is closed.
+        assert_eq!(2, cache.len());
+        assert!(readers[0].upgrade().is_none());
+        assert!(cache.get(region_id, file_ids[0]).is_none());
+        assert!(readers[1].upgrade().is_some());
+        assert!(readers[2].upgrade().is_some());
+
+        // Readers in use are not closed.
+        let reader1 = cache.get(region_id, file_ids[1]).unwrap();
+        let reader = new_reader(&store, file_ids[0]).await;
+        let reader0 = cache.put(region_id, file_ids[0], reader);
+        // Closes the least recently used reader not in use.
+        assert_eq!(2, cache.len());
+        assert!(readers[2].upgrade().is_none());
+        let reader = new_reader(&store, file_ids[2]).await;
+        let _ = cache.put(region_id, file_ids[2], reader);
+        // All readers are in use.
+        assert_eq!(3, cache.len());
+        drop(reader1);
+
+        // Removes readers of deleted files.
+        let weak = Arc::downgrade(&reader0);
+        drop(reader0);
+        cache.remove(region_id, file_ids[0]);
+        assert!(cache.get(region_id, file_ids[0]).is_none());
+        assert!(weak.upgrade().is_none());
+    }
+}
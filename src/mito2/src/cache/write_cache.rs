@@ -14,27 +14,37 @@
 
 //! A write-through cache for remote object stores.
 
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use api::v1::region;
 use bytes::Bytes;
 use common_base::readable_size::ReadableSize;
-use common_telemetry::{debug, info};
+use common_datasource::file_format::parquet::BufferedWriter;
+use common_telemetry::{debug, info, warn};
+use futures::TryStreamExt;
 use object_store::manager::ObjectStoreManagerRef;
-use object_store::ObjectStore;
+use object_store::util::join_path;
+use object_store::{ErrorKind, ObjectStore};
+use parquet::arrow::ParquetRecordBatchStreamBuilder;
+use parquet::file::metadata::ParquetMetaData;
+use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use store_api::metadata::RegionMetadataRef;
 use store_api::storage::RegionId;
+use tokio::io::BufReader;
 
-use crate::access_layer::new_fs_object_store;
+use crate::access_layer::{self, new_fs_object_store, FsStoreConfig};
 use crate::cache::file_cache::{FileCache, FileCacheRef, FileType, IndexKey, IndexValue};
+use crate::config::WriteCacheEvictionPolicy;
 use crate::error::{self, Result};
 use crate::metrics::{FLUSH_ELAPSED, UPLOAD_BYTES_TOTAL};
 use crate::read::Source;
 use crate::sst::file::FileId;
+use crate::sst::parquet::helper::{footer_checksum, metadata_checksum, parse_parquet_metadata};
 use crate::sst::parquet::writer::ParquetWriter;
-use crate::sst::parquet::{SstInfo, WriteOptions};
+use crate::sst::parquet::{ColumnKeyValues, Compression, SstInfo, WriteOptions};
 use crate::sst::DEFAULT_WRITE_BUFFER_SIZE;
 
 /// A cache for uploading files to remote object stores.
@@ -45,6 +55,17 @@ pub struct WriteCache {
     file_cache: FileCacheRef,
     /// Object store manager.
     object_store_manager: ObjectStoreManagerRef,
+    /// Compression of SSTs staged in the local disk before uploading, SSTs are staged
+    /// with their own compression if it is `None`.
+    staging_compression: Option<Compression>,
+    /// SSTs larger than the size are written directly to the remote object store.
+    bypass_size: Option<ReadableSize>,
+    /// Size of the write buffer to stage SSTs in the local disk, SSTs are staged with
+    /// the buffer size in their write options if it is `None`.
+    local_write_buffer_size: Option<ReadableSize>,
+    /// Uploads interrupted by the last crash, grouped by regions. They are resolved
+    /// once their regions are opened.
+    pending_uploads: Mutex<HashMap<RegionId, Vec<PendingUpload>>>,
 }
 
 pub type WriteCacheRef = Arc<WriteCache>;
@@ -56,26 +77,81 @@ impl WriteCache {
         local_store: ObjectStore,
         object_store_manager: ObjectStoreManagerRef,
         cache_capacity: ReadableSize,
+        eviction_policy: WriteCacheEvictionPolicy,
     ) -> Result<Self> {
-        let file_cache = FileCache::new(local_store, cache_capacity);
-        file_cache.recover().await?;
+        let file_cache = FileCache::new(local_store, cache_capacity, eviction_policy);
 
-        Ok(Self {
+        let cache = Self {
             file_cache: Arc::new(file_cache),
             object_store_manager,
-        })
+            staging_compression: None,
+            bypass_size: None,
+            local_write_buffer_size: None,
+            pending_uploads: Mutex::new(HashMap::new()),
+        };
+        // Loads pending uploads before recovering the file cache so the file cache never
+        // evicts files still pending upload. Uploads are resolved in background after
+        // their regions are opened so they don't block the startup.
+        let pending = cache.load_pending_uploads().await?;
+        cache.file_cache.recover(&pending).await?;
+
+        Ok(cache)
+    }
+
+    /// Stages SSTs with the `compression` to save the local disk.
+    ///
+    /// SSTs staged with another compression are rewritten with their own compression
+    /// while uploading, so remote SSTs are always the same as SSTs written without the
+    /// cache. These SSTs are removed from the local disk after uploading as they are
+    /// different from remote SSTs.
+    pub fn with_staging_compression(mut self, compression: Option<Compression>) -> Self {
+        self.staging_compression = compression;
+        self
+    }
+
+    /// Bypasses the cache to write SSTs larger than the `bypass_size`, which avoids
+    /// wasting local disk IO on huge flushes.
+    pub fn with_bypass_size(mut self, bypass_size: Option<ReadableSize>) -> Self {
+        self.bypass_size = bypass_size;
+        self
+    }
+
+    /// Stages SSTs with the `write_buffer_size` instead of the buffer size in their
+    /// write options.
+    pub fn with_local_write_buffer_size(mut self, write_buffer_size: Option<ReadableSize>) -> Self {
+        self.local_write_buffer_size = write_buffer_size;
+        self
+    }
+
+    /// Returns true if a SST of `source_size` bytes should be written directly to
+    /// the remote object store. The cache never bypasses SSTs of unknown size.
+    pub(crate) fn should_bypass(&self, source_size: Option<u64>) -> bool {
+        match (self.bypass_size, source_size) {
+            (Some(bypass_size), Some(source_size)) => source_size > bypass_size.as_bytes(),
+            _ => false,
+        }
     }
 
     /// Creates a write cache based on local fs.
-    pub async fn new_fs(
-        cache_dir: &str,
+    pub(crate) async fn new_fs(
+        store_config: &FsStoreConfig,
         object_store_manager: ObjectStoreManagerRef,
         cache_capacity: ReadableSize,
+        eviction_policy: WriteCacheEvictionPolicy,
     ) -> Result<Self> {
-        info!("Init write cache on {cache_dir}, capacity: {cache_capacity}");
+        info!(
+            "Init write cache on {}, capacity: {}, eviction_policy: {:?}",
+            store_config.root, cache_capacity, eviction_policy
+        );
 
-        let local_store = new_fs_object_store(cache_dir).await?;
-        Self::new(local_store, object_store_manager, cache_capacity).await
+        let local_store = new_fs_object_store(store_config).await?;
+        Self::new(
+            local_store,
+            object_store_manager,
+            cache_capacity,
+            eviction_policy,
+        )
+        .await
     }
 
     /// Returns the file cache of the write cache.
@@ -99,41 +175,469 @@ impl WriteCache {
 
         // Write to FileCache.
         let mut writer = ParquetWriter::new(
+            file_id,
             self.file_cache.cache_file_path(parquet_key),
             request.metadata,
             self.file_cache.local_store(),
-        );
+        )
+        .with_column_metadata(request.column_metadata);
 
-        let sst_info = writer.write_all(request.source, write_opts).await?;
+        let staging_opts = self
+            .staging_compression
+            .filter(|compression| *compression != write_opts.compression)
+            .map(|compression| WriteOptions {
+                compression,
+                ..write_opts.clone()
+            });
+        let mut local_opts = staging_opts.clone().unwrap_or_else(|| write_opts.clone());
+        if let Some(write_buffer_size) = self.local_write_buffer_size {
+            local_opts.write_buffer_size = write_buffer_size;
+        }
+        // The writer has no file provider so it writes at most one SST.
+        let sst_info = writer.write_all(request.source, &local_opts).await?.pop();
 
         timer.stop_and_record();
 
         // Upload sst file to remote object store.
-        let Some(sst_info) = sst_info else {
+        let Some(mut sst_info) = sst_info else {
             // No data need to upload.
             return Ok(None);
         };
 
         let parquet_path = &request.upload_path;
         let remote_store = &request.remote_store;
-        self.upload(parquet_key, parquet_path, remote_store).await?;
+        // Persists the upload so it can resume after restart.
+        let pending = PendingUpload {
+            region_id,
+            file_id,
+            storage: request.storage.clone(),
+            upload_path: parquet_path.clone(),
+            index_upload_path: request.index_upload_path.clone(),
+            upload_index: sst_info.inverted_index_available,
+            file_size: sst_info.file_size,
+            rewrite: staging_opts.is_some(),
+        };
+        self.put_pending_upload(&pending).await?;
+
+        let local_store = self.file_cache.local_store();
+        // Uploaded files to put into the file cache and files to mirror.
+        let mut uploaded = Vec::with_capacity(2);
+        let mut mirror_files = Vec::with_capacity(2);
+        if staging_opts.is_some() {
+            let result = self
+                .rewrite_and_upload(parquet_key, &writer, parquet_path, remote_store, write_opts)
+                .await;
+            self.remove_staging_file(parquet_key).await;
+            let (file_metadata, file_size, footer_checksum) = result?;
+            sst_info.file_metadata = Some(Arc::new(file_metadata));
+            sst_info.file_size = file_size;
+            // The remote SST has another footer than the staged SST.
+            sst_info.footer_checksum = footer_checksum;
+            // The staging file is different from the remote file.
+            mirror_files.push((remote_store, parquet_path.clone(), parquet_path.clone()));
+        } else {
+            let file_size = self
+                .upload_file(parquet_key, parquet_path, remote_store)
+                .await?;
+            uploaded.push((parquet_key, file_size));
+            mirror_files.push((
+                &local_store,
+                self.file_cache.cache_file_path(parquet_key),
+                parquet_path.clone(),
+            ));
+        }
 
         if sst_info.inverted_index_available {
             let puffin_key = IndexKey::new(region_id, file_id, FileType::Puffin);
             let puffin_path = &request.index_upload_path;
-            self.upload(puffin_key, puffin_path, remote_store).await?;
+            let file_size = self
+                .upload_file(puffin_key, puffin_path, remote_store)
+                .await?;
+            uploaded.push((puffin_key, file_size));
+            mirror_files.push((
+                &local_store,
+                self.file_cache.cache_file_path(puffin_key),
+                puffin_path.clone(),
+            ));
+        }
+
+        self.remove_pending_upload(region_id, file_id).await;
+
+        // Mirrors files before putting them into the file cache as the cache might
+        // evict them.
+        if let Some(mirror_store) = &request.mirror_store {
+            access_layer::mirror_files(region_id, file_id, mirror_store, &mirror_files).await;
+        }
+        for (index_key, file_size) in uploaded {
+            let index_value = IndexValue {
+                file_size: file_size as _,
+            };
+            // Register to file cache
+            self.file_cache.put(index_key, index_value).await;
         }
 
         Ok(Some(sst_info))
     }
 
-    /// Uploads a Parquet file or a Puffin file to the remote object store.
-    async fn upload(
+    /// Loads records of uploads interrupted by a crash and returns files still pending
+    /// upload. Corrupt records are discarded.
+    ///
+    /// Uploads are resolved once their regions are opened, see
+    /// [WriteCache::resolve_pending_uploads()].
+    async fn load_pending_uploads(&self) -> Result<HashSet<IndexKey>> {
+        let local_store = self.file_cache.local_store();
+        let mut lister = local_store
+            .lister_with(PENDING_DIR)
+            .await
+            .context(error::OpenDalSnafu)?;
+        let mut record_paths = Vec::new();
+        while let Some(entry) = lister.try_next().await.context(error::OpenDalSnafu)? {
+            if entry.metadata().is_file() {
+                record_paths.push(entry.path().to_string());
+            }
+        }
+
+        let mut pending_files = HashSet::new();
+        let mut pending_uploads: HashMap<_, Vec<_>> = HashMap::new();
+        for record_path in &record_paths {
+            let data = local_store
+                .read(record_path)
+                .await
+                .context(error::OpenDalSnafu)?;
+            match serde_json::from_slice::<PendingUpload>(&data) {
+                Ok(pending) => {
+                    for file_type in [FileType::Parquet, FileType::Puffin] {
+                        pending_files.insert(IndexKey::new(
+                            pending.region_id,
+                            pending.file_id,
+                            file_type,
+                        ));
+                    }
+                    pending_uploads
+                        .entry(pending.region_id)
+                        .or_default()
+                        .push(pending);
+                }
+                Err(e) => {
+                    warn!(e; "Discard corrupt pending upload {}", record_path);
+                    if let Err(e) = local_store.delete(record_path).await {
+                        warn!(e; "Failed to remove pending upload {}", record_path);
+                    }
+                }
+            }
+        }
+
+        if !record_paths.is_empty() {
+            info!(
+                "Loaded pending uploads of write cache, num_records: {}, num_regions: {}",
+                record_paths.len(),
+                pending_uploads.len()
+            );
+        }
+        *self.pending_uploads.lock().unwrap() = pending_uploads;
+
+        Ok(pending_files)
+    }
+
+    /// Resolves uploads of the region interrupted by a crash once the region is opened.
+    ///
+    /// The region only commits SSTs to its manifest after uploading them, so SSTs in
+    /// `committed_files` are completely uploaded and the remaining uploads only finish
+    /// leftovers, e.g. the index file. SSTs not in `committed_files` are never visible,
+    /// their remote files are removed so they don't leak in the object store, rows of
+    /// these SSTs are replayed from the WAL.
+    ///
+    /// Uploads failing to resolve are retried after next restart.
+    pub(crate) async fn resolve_pending_uploads(
+        &self,
+        region_id: RegionId,
+        committed_files: &HashSet<FileId>,
+    ) {
+        let Some(pending_uploads) = self.pending_uploads.lock().unwrap().remove(&region_id) else {
+            return;
+        };
+
+        let (mut num_resumed, mut num_removed) = (0, 0);
+        for pending in &pending_uploads {
+            let result = if committed_files.contains(&pending.file_id) {
+                num_resumed += 1;
+                self.resume_upload(pending).await
+            } else {
+                num_removed += 1;
+                self.remove_upload(pending).await
+            };
+            match result {
+                Ok(()) => self.remove_pending_upload(region_id, pending.file_id).await,
+                Err(e) => warn!(
+                    e; "Failed to resolve pending upload of region {}, file {}",
+                    region_id, pending.file_id
+                ),
+            }
+        }
+
+        info!(
+            "Resolved pending uploads of region {}, num_resumed: {}, num_removed: {}",
+            region_id, num_resumed, num_removed
+        );
+    }
+
+    /// Finishes the `pending` upload of a committed SST.
+    ///
+    /// Files already uploaded are skipped. Corrupt staging files and files staged with
+    /// another compression are discarded.
+    async fn resume_upload(&self, pending: &PendingUpload) -> Result<()> {
+        let parquet_key = IndexKey::new(pending.region_id, pending.file_id, FileType::Parquet);
+        let puffin_key = IndexKey::new(pending.region_id, pending.file_id, FileType::Puffin);
+
+        if pending.rewrite {
+            // The staging file differs from the remote SST so we can't cache it.
+            warn!(
+                "Discard staging file of region {}, file {} as it needs a rewrite",
+                pending.region_id, pending.file_id
+            );
+            self.discard_staging_files(parquet_key, puffin_key).await;
+            return Ok(());
+        }
+        if !self
+            .is_valid_staging_file(parquet_key, pending.file_size)
+            .await
+        {
+            warn!(
+                "Discard corrupt staging file of region {}, file {}",
+                pending.region_id, pending.file_id
+            );
+            self.discard_staging_files(parquet_key, puffin_key).await;
+            return Ok(());
+        }
+        let Some(remote_store) = self.remote_store(pending) else {
+            self.discard_staging_files(parquet_key, puffin_key).await;
+            return Ok(());
+        };
+
+        let mut keys = vec![(parquet_key, pending.upload_path.as_str())];
+        if pending.upload_index {
+            keys.push((puffin_key, pending.index_upload_path.as_str()));
+        }
+        for (index_key, upload_path) in keys {
+            let file_size = if self
+                .is_uploaded(index_key, upload_path, remote_store)
+                .await?
+            {
+                let cache_path = self.file_cache.cache_file_path(index_key);
+                self.file_cache
+                    .local_store()
+                    .stat(&cache_path)
+                    .await
+                    .context(error::OpenDalSnafu)?
+                    .content_length()
+            } else {
+                self.upload_file(index_key, upload_path, remote_store)
+                    .await?
+            };
+            // The file cache skips files pending upload while recovering.
+            let index_value = IndexValue {
+                file_size: file_size as _,
+            };
+            self.file_cache.put(index_key, index_value).await;
+        }
+
+        Ok(())
+    }
+
+    /// Removes remote files and staging files of the `pending` upload of a SST never
+    /// committed.
+    async fn remove_upload(&self, pending: &PendingUpload) -> Result<()> {
+        let parquet_key = IndexKey::new(pending.region_id, pending.file_id, FileType::Parquet);
+        let puffin_key = IndexKey::new(pending.region_id, pending.file_id, FileType::Puffin);
+
+        if let Some(remote_store) = self.remote_store(pending) {
+            // Deleting a file not found is ok.
+            for path in [&pending.upload_path, &pending.index_upload_path] {
+                remote_store
+                    .delete(path)
+                    .await
+                    .context(error::OpenDalSnafu)?;
+            }
+        }
+        self.discard_staging_files(parquet_key, puffin_key).await;
+
+        Ok(())
+    }
+
+    /// Returns the remote object store of the `pending` upload.
+    fn remote_store(&self, pending: &PendingUpload) -> Option<&ObjectStore> {
+        let remote_store = match &pending.storage {
+            Some(name) => self.object_store_manager.find(name),
+            None => Some(self.object_store_manager.default_object_store()),
+        };
+        if remote_store.is_none() {
+            warn!(
+                "Object store {:?} of the pending upload of region {}, file {} not found",
+                pending.storage, pending.region_id, pending.file_id
+            );
+        }
+        remote_store
+    }
+
+    /// Returns true if the staging file has the expected size and a valid footer.
+    async fn is_valid_staging_file(&self, index_key: IndexKey, file_size: u64) -> bool {
+        let cache_path = self.file_cache.cache_file_path(index_key);
+        let local_store = self.file_cache.local_store();
+        match local_store.stat(&cache_path).await {
+            Ok(meta) if meta.content_length() == file_size => (),
+            _ => return false,
+        }
+        let Ok(reader) = local_store.reader(&cache_path).await else {
+            return false;
+        };
+        ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+            .await
+            .is_ok()
+    }
+
+    /// Returns true if the remote file has the same size and checksum as the staging
+    /// file.
+    async fn is_uploaded(
+        &self,
+        index_key: IndexKey,
+        upload_path: &str,
+        remote_store: &ObjectStore,
+    ) -> Result<bool> {
+        let cache_path = self.file_cache.cache_file_path(index_key);
+        let local_store = self.file_cache.local_store();
+        let file_size = local_store
+            .stat(&cache_path)
+            .await
+            .context(error::OpenDalSnafu)?
+            .content_length();
+        match remote_store.stat(upload_path).await {
+            Ok(remote_meta) if remote_meta.content_length() == file_size => (),
+            Ok(_) => return Ok(false),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e).context(error::OpenDalSnafu),
+        }
+
+        let local_checksum =
+            file_checksum(index_key.file_type, &cache_path, &local_store, file_size).await?;
+        // Uploads the file again if the remote file is partially written.
+        let remote_checksum =
+            file_checksum(index_key.file_type, upload_path, remote_store, file_size).await;
+        Ok(remote_checksum.ok() == Some(local_checksum))
+    }
+
+    /// Removes staging files from the local disk and the file cache.
+    async fn discard_staging_files(&self, parquet_key: IndexKey, puffin_key: IndexKey) {
+        // The file cache might recover the staging files.
+        self.file_cache.remove(parquet_key).await;
+        self.file_cache.remove(puffin_key).await;
+    }
+
+    /// Persists the pending upload to the local store.
+    async fn put_pending_upload(&self, pending: &PendingUpload) -> Result<()> {
+        let data = serde_json::to_vec(pending).context(error::SerdeJsonSnafu)?;
+        self.file_cache
+            .local_store()
+            .write(
+                &pending_upload_path(pending.region_id, pending.file_id),
+                data,
+            )
+            .await
+            .context(error::OpenDalSnafu)
+    }
+
+    /// Removes the record of a finished upload.
+    async fn remove_pending_upload(&self, region_id: RegionId, file_id: FileId) {
+        let record_path = pending_upload_path(region_id, file_id);
+        if let Err(e) = self.file_cache.local_store().delete(&record_path).await {
+            warn!(e; "Failed to remove pending upload {}", record_path);
+        }
+    }
+
+    /// Rewrites the staged SST with `write_opts` of the `writer` to the remote object
+    /// store, returns the metadata, the size and the footer checksum of the remote SST.
+    async fn rewrite_and_upload(
         &self,
         index_key: IndexKey,
+        writer: &ParquetWriter,
         upload_path: &str,
         remote_store: &ObjectStore,
-    ) -> Result<()> {
+        write_opts: &WriteOptions,
+    ) -> Result<(ParquetMetaData, u64, u32)> {
+        let timer = FLUSH_ELAPSED
+            .with_label_values(&["upload_parquet"])
+            .start_timer();
+
+        let cache_path = self.file_cache.cache_file_path(index_key);
+        let reader = self
+            .file_cache
+            .local_store()
+            .reader(&cache_path)
+            .await
+            .context(error::OpenDalSnafu)?;
+        let builder = ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+            .await
+            .context(error::ReadParquetSnafu { path: &cache_path })?;
+        // The staged SST has the same arrow schema, including metadata of columns.
+        let arrow_schema = builder.schema().clone();
+        let mut stream = builder
+            .build()
+            .context(error::ReadParquetSnafu { path: &cache_path })?;
+
+        let mut buffered_writer = BufferedWriter::try_new(
+            upload_path.to_string(),
+            remote_store.clone(),
+            arrow_schema,
+            Some(writer.writer_props(write_opts)?),
+            write_opts.write_buffer_size.as_bytes() as usize,
+        )
+        .await
+        .context(error::WriteBufferSnafu)?;
+        while let Some(batch) = stream
+            .try_next()
+            .await
+            .context(error::ReadParquetSnafu { path: &cache_path })?
+        {
+            buffered_writer
+                .write(&batch)
+                .await
+                .context(error::WriteBufferSnafu)?;
+        }
+        let (file_meta, file_size) = buffered_writer
+            .close()
+            .await
+            .context(error::WriteBufferSnafu)?;
+        let footer_checksum = metadata_checksum(upload_path, &file_meta)?;
+        let parquet_metadata = parse_parquet_metadata(file_meta)?;
+
+        UPLOAD_BYTES_TOTAL.inc_by(file_size);
+
+        debug!(
+            "Successfully rewrite file to remote, region: {}, file: {}, upload_path: {}, cost: {:?}s",
+            index_key.region_id,
+            index_key.file_id,
+            upload_path,
+            timer.stop_and_record()
+        );
+
+        Ok((parquet_metadata, file_size, footer_checksum))
+    }
+
+    /// Removes the staged file that isn't put into the file cache.
+    async fn remove_staging_file(&self, index_key: IndexKey) {
+        let cache_path = self.file_cache.cache_file_path(index_key);
+        if let Err(e) = self.file_cache.local_store().delete(&cache_path).await {
+            warn!(e; "Failed to remove staging file {}", cache_path);
+        }
+    }
+
+    /// Uploads a Parquet file or a Puffin file in the local store to the remote object
+    /// store, returns the number of bytes uploaded.
+    async fn upload_file(
+        &self,
+        index_key: IndexKey,
+        upload_path: &str,
+        remote_store: &ObjectStore,
+    ) -> Result<u64> {
         let region_id = index_key.region_id;
         let file_id = index_key.file_id;
         let file_type = index_key.file_type;
@@ -181,28 +685,73 @@ impl WriteCache {
             timer.stop_and_record()
         );
 
-        let index_value = IndexValue {
-            file_size: bytes_written as _,
-        };
-        // Register to file cache
-        self.file_cache.put(index_key, index_value).await;
+        Ok(bytes_written)
+    }
+}
 
-        Ok(())
+/// Returns the checksum of the file of `file_size` bytes at `path`.
+///
+/// The checksum of a SST only covers its footer as the footer contains offsets and
+/// statistics of all pages, the checksum of an index file covers the whole file.
+async fn file_checksum(
+    file_type: FileType,
+    path: &str,
+    object_store: &ObjectStore,
+    file_size: u64,
+) -> Result<u32> {
+    match file_type {
+        FileType::Parquet => footer_checksum(path, object_store, file_size, None).await,
+        FileType::Puffin => {
+            let data = object_store.read(path).await.context(error::OpenDalSnafu)?;
+            Ok(crc32c::crc32c(&data))
+        }
     }
 }
 
+/// Subdirectory of records of pending uploads.
+const PENDING_DIR: &str = "pending/";
+
+/// Record of a staged SST that isn't uploaded yet.
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingUpload {
+    region_id: RegionId,
+    file_id: FileId,
+    /// Name of the remote object store, `None` for the default store.
+    storage: Option<String>,
+    upload_path: String,
+    index_upload_path: String,
+    /// Whether the SST has an index file to upload.
+    upload_index: bool,
+    /// Size of the staging SST file.
+    file_size: u64,
+    /// Whether the staging SST is rewritten while uploading.
+    rewrite: bool,
+}
+
+/// Returns the path of the pending upload record of the SST.
+fn pending_upload_path(region_id: RegionId, file_id: FileId) -> String {
+    join_path(
+        PENDING_DIR,
+        &format!("{}.{}.json", region_id.as_u64(), file_id),
+    )
+}
+
 /// Request to write and upload a SST.
 pub struct SstUploadRequest {
     pub file_id: FileId,
     pub metadata: RegionMetadataRef,
     pub source: Source,
     pub storage: Option<String>,
+    /// Custom key-value metadata of columns.
+    pub column_metadata: ColumnKeyValues,
     /// Path to upload the file.
     pub upload_path: String,
     /// Path to upload the index file.
     pub index_upload_path: String,
     /// Remote object store to upload.
     pub remote_store: ObjectStore,
+    /// Store to mirror uploaded files to the same paths.
+    pub mirror_store: Option<ObjectStore>,
 }
 
 #[cfg(test)]
@@ -211,7 +760,7 @@ mod tests {
     use common_base::readable_size::ReadableSize;
     use common_test_util::temp_dir::create_temp_dir;
     use object_store::manager::ObjectStoreManager;
-    use object_store::services::Fs;
+    use object_store::services::{Fs, Memory};
     use object_store::ObjectStore;
     use store_api::storage::RegionId;
 
@@ -220,10 +769,14 @@ mod tests {
     use crate::cache::test_util::new_fs_store;
     use crate::sst::file::FileId;
     use crate::sst::location::{index_file_path, sst_file_path};
+    use crate::sst::parquet::helper::footer_checksum;
+    use crate::sst::parquet::reader::ParquetReaderBuilder;
     use crate::test_util::sst_util::{
         new_batch_by_range, new_source, sst_file_handle, sst_region_metadata,
     };
-    use crate::test_util::{build_rows, new_batch_builder, CreateRequestBuilder, TestEnv};
+    use crate::test_util::{
+        build_rows, check_reader_result, new_batch_builder, CreateRequestBuilder, TestEnv,
+    };
 
     #[tokio::test]
     async fn test_write_and_upload_sst() {
@@ -243,6 +796,7 @@ mod tests {
             local_store.clone(),
             object_store_manager,
             ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
         )
         .await
         .unwrap();
@@ -256,14 +810,17 @@ mod tests {
             new_batch_by_range(&["b", "h"], 100, 200),
         ]);
 
+        let mirror_store = ObjectStore::new(Memory::default()).unwrap().finish();
         let request = SstUploadRequest {
             file_id,
             metadata,
             source,
             storage: None,
+            column_metadata: ColumnKeyValues::new(),
             upload_path: upload_path.clone(),
             index_upload_path,
             remote_store: mock_store.clone(),
+            mirror_store: Some(mirror_store.clone()),
         };
 
         let write_opts = WriteOptions {
@@ -289,5 +846,307 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(remote_data, cache_data);
+        // The mirror store also has the file.
+        assert_eq!(remote_data, mirror_store.read(&upload_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_write_and_upload_sst_with_staging_compression() {
+        let mut env = TestEnv::new();
+        let mock_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_id = handle.file_id();
+        let upload_path = sst_file_path("test", file_id);
+
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let object_store_manager = env.get_object_store_manager().unwrap();
+        let write_cache = WriteCache::new(
+            local_store.clone(),
+            object_store_manager,
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        )
+        .await
+        .unwrap()
+        .with_staging_compression(Some(Compression::None));
+
+        let metadata = Arc::new(sst_region_metadata());
+        let region_id = metadata.region_id;
+        let source = new_source(&[
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+        ]);
+        let request = SstUploadRequest {
+            file_id,
+            metadata,
+            source,
+            storage: None,
+            column_metadata: ColumnKeyValues::new(),
+            upload_path: upload_path.clone(),
+            index_upload_path: index_file_path("test", file_id),
+            remote_store: mock_store.clone(),
+            mirror_store: None,
+        };
+        let write_opts = WriteOptions {
+            row_group_size: 50,
+            compression: Compression::Snappy,
+            ..Default::default()
+        };
+        let sst_info = write_cache
+            .write_and_upload_sst(request, &write_opts)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The staging file is removed and not cached.
+        let key = IndexKey::new(region_id, file_id, FileType::Parquet);
+        assert!(!write_cache.file_cache.contains_key(&key));
+        assert!(!local_store
+            .is_exist(&write_cache.file_cache.cache_file_path(key))
+            .await
+            .unwrap());
+
+        // The remote SST has the compression of the write options.
+        let remote_data = mock_store.read(&upload_path).await.unwrap();
+        assert_eq!(remote_data.len() as u64, sst_info.file_size);
+        assert_eq!(
+            footer_checksum(&upload_path, &mock_store, sst_info.file_size, None)
+                .await
+                .unwrap(),
+            sst_info.footer_checksum
+        );
+        let builder = ParquetReaderBuilder::new("test".to_string(), handle, mock_store);
+        let mut reader = builder.build().await.unwrap();
+        let parquet_meta = reader.parquet_metadata();
+        assert_eq!(2, parquet_meta.num_row_groups());
+        assert_eq!(
+            sst_info.file_metadata.unwrap().row_groups(),
+            parquet_meta.row_groups()
+        );
+        for row_group in parquet_meta.row_groups() {
+            for column in row_group.columns() {
+                assert_eq!(parquet::basic::Compression::SNAPPY, column.compression());
+            }
+        }
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch_by_range(&["a", "d"], 0, 50),
+                new_batch_by_range(&["a", "d"], 50, 60),
+                new_batch_by_range(&["b", "f"], 0, 40),
+            ],
+        )
+        .await;
+    }
+
+    /// Stages a SST and persists its pending upload, like a crash before uploading.
+    async fn stage_sst(write_cache: &WriteCache, file_id: FileId) -> PendingUpload {
+        let metadata = Arc::new(sst_region_metadata());
+        let region_id = metadata.region_id;
+        let key = IndexKey::new(region_id, file_id, FileType::Parquet);
+        let mut writer = ParquetWriter::new(
+            file_id,
+            write_cache.file_cache.cache_file_path(key),
+            metadata,
+            write_cache.file_cache.local_store(),
+        );
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+        let sst_info = writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+        let pending = PendingUpload {
+            region_id,
+            file_id,
+            storage: None,
+            upload_path: sst_file_path("test", file_id),
+            index_upload_path: index_file_path("test", file_id),
+            upload_index: false,
+            file_size: sst_info.file_size,
+            rewrite: false,
+        };
+        write_cache.put_pending_upload(&pending).await.unwrap();
+        pending
+    }
+
+    #[tokio::test]
+    async fn test_resolve_pending_uploads() {
+        let mut env = TestEnv::new();
+        let mock_store = env.init_object_store_manager();
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let object_store_manager = env.get_object_store_manager().unwrap();
+        let write_cache = WriteCache::new(
+            local_store.clone(),
+            object_store_manager.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        )
+        .await
+        .unwrap();
+
+        // Not uploaded yet.
+        let pending = stage_sst(&write_cache, FileId::random()).await;
+        // Partially uploaded, the remote file has the same size but different content.
+        let partial = stage_sst(&write_cache, FileId::random()).await;
+        let partial_key = IndexKey::new(partial.region_id, partial.file_id, FileType::Parquet);
+        let mut remote_data = local_store
+            .read(&write_cache.file_cache.cache_file_path(partial_key))
+            .await
+            .unwrap();
+        remote_data.reverse();
+        mock_store
+            .write(&partial.upload_path, remote_data)
+            .await
+            .unwrap();
+        // The staging file is corrupt.
+        let corrupt = stage_sst(&write_cache, FileId::random()).await;
+        let corrupt_key = IndexKey::new(corrupt.region_id, corrupt.file_id, FileType::Parquet);
+        let corrupt_path = write_cache.file_cache.cache_file_path(corrupt_key);
+        let data = local_store.read(&corrupt_path).await.unwrap();
+        local_store
+            .write(&corrupt_path, data[..data.len() / 2].to_vec())
+            .await
+            .unwrap();
+        // Uploaded but never committed.
+        let orphan = stage_sst(&write_cache, FileId::random()).await;
+        let orphan_key = IndexKey::new(orphan.region_id, orphan.file_id, FileType::Parquet);
+        let orphan_path = write_cache.file_cache.cache_file_path(orphan_key);
+        let data = local_store.read(&orphan_path).await.unwrap();
+        mock_store.write(&orphan.upload_path, data).await.unwrap();
+        // The record is corrupt.
+        let corrupt_record = pending_upload_path(RegionId::new(1, 1), FileId::random());
+        local_store
+            .write(&corrupt_record, "invalid".as_bytes().to_vec())
+            .await
+            .unwrap();
+        drop(write_cache);
+
+        // Restarts the write cache.
+        let write_cache = WriteCache::new(
+            local_store.clone(),
+            object_store_manager,
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Lru,
+        )
+        .await
+        .unwrap();
+        // Doesn't upload anything before the region is opened.
+        assert!(!mock_store.is_exist(&pending.upload_path).await.unwrap());
+        assert!(!local_store.is_exist(&corrupt_record).await.unwrap());
+
+        let committed = [pending.file_id, partial.file_id, corrupt.file_id]
+            .into_iter()
+            .collect();
+        write_cache
+            .resolve_pending_uploads(pending.region_id, &committed)
+            .await;
+
+        // Resumes uploads of committed SSTs.
+        for upload in [&pending, &partial] {
+            let key = IndexKey::new(upload.region_id, upload.file_id, FileType::Parquet);
+            assert!(write_cache.file_cache.contains_key(&key));
+            let local_data = local_store
+                .read(&write_cache.file_cache.cache_file_path(key))
+                .await
+                .unwrap();
+            assert_eq!(
+                local_data,
+                mock_store.read(&upload.upload_path).await.unwrap()
+            );
+        }
+        // Discards the corrupt staging file.
+        assert!(!write_cache.file_cache.contains_key(&corrupt_key));
+        assert!(!local_store.is_exist(&corrupt_path).await.unwrap());
+        assert!(!mock_store.is_exist(&corrupt.upload_path).await.unwrap());
+        // Removes files of the SST never committed.
+        assert!(!write_cache.file_cache.contains_key(&orphan_key));
+        assert!(!local_store.is_exist(&orphan_path).await.unwrap());
+        assert!(!mock_store.is_exist(&orphan.upload_path).await.unwrap());
+        // All records are removed.
+        for upload in [&pending, &partial, &corrupt, &orphan] {
+            let path = pending_upload_path(upload.region_id, upload.file_id);
+            assert!(!local_store.is_exist(&path).await.unwrap(), "{path}");
+        }
+        assert!(write_cache.pending_uploads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_evict_uploaded_files() {
+        let mut env = TestEnv::new();
+        let mock_store = env.init_object_store_manager();
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let object_store_manager = env.get_object_store_manager().unwrap();
+
+        // Gets the size of a SST.
+        let write_cache = WriteCache::new(
+            local_store.clone(),
+            object_store_manager.clone(),
+            ReadableSize::mb(10),
+            WriteCacheEvictionPolicy::Fifo,
+        )
+        .await
+        .unwrap();
+        let pending = stage_sst(&write_cache, FileId::random()).await;
+        drop(write_cache);
+
+        // The cache can only hold 2 SSTs.
+        let write_cache = WriteCache {
+            file_cache: Arc::new(FileCache::new(
+                local_store.clone(),
+                ReadableSize(pending.file_size * 2),
+                WriteCacheEvictionPolicy::Fifo,
+            )),
+            object_store_manager,
+            staging_compression: None,
+            bypass_size: None,
+            local_write_buffer_size: None,
+            pending_uploads: Mutex::new(HashMap::new()),
+        };
+        let mut keys = Vec::new();
+        for _ in 0..3 {
+            let file_id = FileId::random();
+            let request = SstUploadRequest {
+                file_id,
+                metadata: Arc::new(sst_region_metadata()),
+                source: new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]),
+                storage: None,
+                column_metadata: ColumnKeyValues::new(),
+                upload_path: sst_file_path("test", file_id),
+                index_upload_path: index_file_path("test", file_id),
+                remote_store: mock_store.clone(),
+                mirror_store: None,
+            };
+            let sst_info = write_cache
+                .write_and_upload_sst(request, &WriteOptions::default())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(pending.file_size, sst_info.file_size);
+            keys.push(IndexKey::new(pending.region_id, file_id, FileType::Parquet));
+        }
+
+        // The earliest uploaded file is evicted.
+        for (i, key) in keys.iter().enumerate() {
+            let exists = local_store
+                .is_exist(&write_cache.file_cache.cache_file_path(*key))
+                .await
+                .unwrap();
+            assert_eq!(i != 0, exists, "file {i}");
+            assert_eq!(i != 0, write_cache.file_cache.contains_key(key), "file {i}");
+        }
+        // The pending file stays.
+        let pending_key = IndexKey::new(pending.region_id, pending.file_id, FileType::Parquet);
+        assert!(local_store
+            .is_exist(&write_cache.file_cache.cache_file_path(pending_key))
+            .await
+            .unwrap());
+        assert!(local_store
+            .is_exist(&pending_upload_path(pending.region_id, pending.file_id))
+            .await
+            .unwrap());
     }
 }
@@ -12,17 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod leveled;
+pub(crate) mod limiter;
 mod picker;
+mod rollup;
 #[cfg(test)]
 mod test_util;
 mod twcs;
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 use common_base::readable_size::ReadableSize;
-use common_telemetry::{debug, error};
+use common_telemetry::{debug, error, info};
+use common_time::clock::ClockRef;
+use common_time::Timestamp;
 pub use picker::CompactionPickerRef;
 use snafu::ResultExt;
 use store_api::storage::RegionId;
@@ -30,17 +36,22 @@ use tokio::sync::mpsc::{self, Sender};
 
 use crate::access_layer::AccessLayerRef;
 use crate::cache::CacheManagerRef;
+use crate::compaction::leveled::LeveledPicker;
+use crate::compaction::limiter::{CompactionLimiterRef, CompactionPriority};
+use crate::compaction::rollup::RollupPicker;
 use crate::compaction::twcs::TwcsPicker;
 use crate::config::MitoConfig;
 use crate::error::{
-    CompactRegionSnafu, Error, RegionClosedSnafu, RegionDroppedSnafu, RegionTruncatedSnafu, Result,
+    CompactRegionSnafu, CompactionPausedSnafu, Error, RegionClosedSnafu, RegionDroppedSnafu,
+    RegionFrozenSnafu, RegionTruncatedSnafu, Result,
 };
 use crate::metrics::COMPACTION_STAGE_ELAPSED;
 use crate::region::options::CompactionOptions;
 use crate::region::version::{VersionControlRef, VersionRef};
-use crate::request::{OptionOutputTx, OutputTx, WorkerRequest};
+use crate::request::{BackgroundNotify, CompactionFailed, OptionOutputTx, OutputTx, WorkerRequest};
 use crate::schedule::scheduler::SchedulerRef;
 use crate::sst::file_purger::FilePurgerRef;
+use crate::sst::parquet::{ColumnKeyValues, WriteOptions};
 
 /// Region compaction request.
 pub struct CompactionRequest {
@@ -53,9 +64,15 @@ pub struct CompactionRequest {
     pub(crate) file_purger: FilePurgerRef,
     /// Start time of compaction task.
     pub(crate) start_time: Instant,
-    /// Buffering threshold while writing SST files.
-    pub(crate) sst_write_buffer_size: ReadableSize,
+    /// Options to write SST files.
+    pub(crate) sst_write_opts: WriteOptions,
+    /// Custom key-value metadata of columns of SST files.
+    pub(crate) column_metadata: ColumnKeyValues,
+    /// Target size of output SST files, `None` to output one file for each output.
+    pub(crate) target_file_size: Option<ReadableSize>,
     pub(crate) cache_manager: CacheManagerRef,
+    /// Wall-clock time of the request to find expired SSTs.
+    pub(crate) current_time: Timestamp,
 }
 
 impl CompactionRequest {
@@ -79,6 +96,57 @@ pub fn compaction_options_to_picker(strategy: &CompactionOptions) -> CompactionP
             twcs_opts.max_inactive_window_files,
             twcs_opts.time_window_seconds(),
         )) as Arc<_>,
+        CompactionOptions::Leveled(leveled_opts) => Arc::new(LeveledPicker::new(
+            leveled_opts.max_level0_files,
+            leveled_opts.base_level_size,
+            leveled_opts.fanout,
+        )) as Arc<_>,
+    }
+}
+
+pub(crate) type CompactionPauserRef = Arc<CompactionPauser>;
+
+/// Pauses compactions globally or for specific regions.
+///
+/// Pausing doesn't stop running compactions, they are allowed to finish.
+#[derive(Debug, Default)]
+pub(crate) struct CompactionPauser {
+    /// Whether compactions of all regions are paused.
+    pause_all: AtomicBool,
+    /// Regions whose compactions are paused.
+    paused_regions: RwLock<HashSet<RegionId>>,
+}
+
+impl CompactionPauser {
+    /// Pauses compactions of the region, or all regions if `region_id` is `None`.
+    pub(crate) fn pause(&self, region_id: Option<RegionId>) {
+        match region_id {
+            Some(region_id) => {
+                self.paused_regions.write().unwrap().insert(region_id);
+            }
+            None => self.pause_all.store(true, Ordering::Relaxed),
+        }
+    }
+
+    /// Resumes compactions of the region, or all regions if `region_id` is `None`.
+    ///
+    /// Resuming all regions also clears pauses of specific regions.
+    pub(crate) fn resume(&self, region_id: Option<RegionId>) {
+        match region_id {
+            Some(region_id) => {
+                self.paused_regions.write().unwrap().remove(&region_id);
+            }
+            None => {
+                self.pause_all.store(false, Ordering::Relaxed);
+                self.paused_regions.write().unwrap().clear();
+            }
+        }
+    }
+
+    /// Returns true if compactions of the region are paused.
+    pub(crate) fn is_paused(&self, region_id: RegionId) -> bool {
+        self.pause_all.load(Ordering::Relaxed)
+            || self.paused_regions.read().unwrap().contains(&region_id)
     }
 }
 
@@ -90,6 +158,10 @@ pub(crate) struct CompactionScheduler {
     /// Request sender of the worker that this scheduler belongs to.
     request_sender: Sender<WorkerRequest>,
     cache_manager: CacheManagerRef,
+    /// Limiter of compactions running in the node.
+    limiter: CompactionLimiterRef,
+    pauser: CompactionPauserRef,
+    clock: ClockRef,
 }
 
 impl CompactionScheduler {
@@ -97,12 +169,18 @@ impl CompactionScheduler {
         scheduler: SchedulerRef,
         request_sender: Sender<WorkerRequest>,
         cache_manager: CacheManagerRef,
+        limiter: CompactionLimiterRef,
+        pauser: CompactionPauserRef,
+        clock: ClockRef,
     ) -> Self {
         Self {
             scheduler,
             region_status: HashMap::new(),
             request_sender,
             cache_manager,
+            limiter,
+            pauser,
+            clock,
         }
     }
 
@@ -134,6 +212,7 @@ impl CompactionScheduler {
             waiter,
             engine_config,
             self.cache_manager.clone(),
+            self.clock.now(),
         );
         self.region_status.insert(region_id, status);
         self.schedule_compaction_request(request)
@@ -154,6 +233,7 @@ impl CompactionScheduler {
             OptionOutputTx::none(),
             engine_config,
             self.cache_manager.clone(),
+            self.clock.now(),
         );
         // Try to schedule next compaction task for this region.
         if let Err(e) = self.schedule_compaction_request(request) {
@@ -186,6 +266,11 @@ impl CompactionScheduler {
         self.remove_region_on_failure(region_id, Arc::new(RegionClosedSnafu { region_id }.build()));
     }
 
+    /// Notifies the scheduler that the region is frozen.
+    pub(crate) fn on_region_frozen(&mut self, region_id: RegionId) {
+        self.remove_region_on_failure(region_id, Arc::new(RegionFrozenSnafu { region_id }.build()));
+    }
+
     /// Notifies the scheduler that the region is truncated.
     pub(crate) fn on_region_truncated(&mut self, region_id: RegionId) {
         self.remove_region_on_failure(
@@ -198,13 +283,34 @@ impl CompactionScheduler {
     ///
     /// If the region has nothing to compact, it removes the region from the status map.
     fn schedule_compaction_request(&mut self, request: CompactionRequest) -> Result<()> {
-        let picker = compaction_options_to_picker(&request.current_version.options.compaction);
         let region_id = request.region_id();
+        if self.pauser.is_paused(region_id) {
+            info!("Compaction of region {} is paused", region_id);
+            // Compaction is scheduled again once the region is resumed.
+            self.region_status.remove(&region_id);
+            for waiter in request.waiters {
+                waiter.send(CompactionPausedSnafu { region_id }.fail());
+            }
+            return Ok(());
+        }
+
+        let options = &request.current_version.options;
+        let mut picker = compaction_options_to_picker(&options.compaction);
+        if options.rollup.rollup_after().is_some() {
+            picker = Arc::new(RollupPicker::new(options.rollup.clone(), picker));
+        }
         debug!(
             "Pick compaction strategy {:?} for region: {}",
             picker, region_id
         );
 
+        // Someone is waiting for compactions requested explicitly.
+        let priority = if request.waiters.is_empty() {
+            CompactionPriority::Normal
+        } else {
+            CompactionPriority::High
+        };
+
         let pick_timer = COMPACTION_STAGE_ELAPSED
             .with_label_values(&["pick"])
             .start_timer();
@@ -215,10 +321,43 @@ impl CompactionScheduler {
         };
         drop(pick_timer);
 
+        let Some(permit) = self.limiter.try_acquire() else {
+            debug!(
+                "Too many running compactions, region {} waits for a permit",
+                region_id
+            );
+            // Waits for the permit in background so queued tasks don't occupy the
+            // background job scheduler.
+            let limiter = self.limiter.clone();
+            let scheduler = self.scheduler.clone();
+            let request_sender = self.request_sender.clone();
+            common_runtime::spawn_bg(async move {
+                let permit = limiter.acquire(priority).await;
+                let job = Box::pin(async move {
+                    task.run().await;
+                    drop(permit);
+                });
+                if let Err(e) = scheduler.schedule(job) {
+                    error!(e; "Failed to submit compaction request for region {}", region_id);
+                    // Notifies the worker to remove the region from the scheduler.
+                    let notify = WorkerRequest::Background {
+                        region_id,
+                        notify: BackgroundNotify::CompactionFailed(CompactionFailed {
+                            region_id,
+                            err: Arc::new(e),
+                        }),
+                    };
+                    let _ = request_sender.send(notify).await;
+                }
+            });
+            return Ok(());
+        };
+
         // Submit the compaction task.
         self.scheduler
             .schedule(Box::pin(async move {
                 task.run().await;
+                drop(permit);
             }))
             .map_err(|e| {
                 error!(e; "Failed to submit compaction request for region {}", region_id);
@@ -327,9 +466,12 @@ impl CompactionStatus {
         waiter: OptionOutputTx,
         engine_config: Arc<MitoConfig>,
         cache_manager: CacheManagerRef,
+        current_time: Timestamp,
     ) -> CompactionRequest {
         let current_version = self.version_control.current().version;
         let start_time = Instant::now();
+        let sst_write_opts = engine_config.sst_write_options(&current_version.metadata);
+        let column_metadata = engine_config.sst_column_metadata(&current_version.metadata);
         let mut req = CompactionRequest {
             current_version,
             access_layer: self.access_layer.clone(),
@@ -337,8 +479,12 @@ impl CompactionStatus {
             waiters: Vec::new(),
             file_purger: self.file_purger.clone(),
             start_time,
-            sst_write_buffer_size: engine_config.sst_write_buffer_size,
+            sst_write_opts,
+            column_metadata,
+            target_file_size: (engine_config.compaction_target_file_size.as_bytes() > 0)
+                .then_some(engine_config.compaction_target_file_size),
             cache_manager,
+            current_time,
         };
 
         if let Some(pending) = self.pending_compaction.take() {
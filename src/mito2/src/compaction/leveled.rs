@@ -0,0 +1,369 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::{debug, info};
+use common_time::Timestamp;
+
+use crate::compaction::picker::{CompactionTask, Picker};
+use crate::compaction::twcs::{get_expired_ssts, CompactionOutput, TwcsCompactionTask};
+use crate::compaction::CompactionRequest;
+use crate::sst::file::{FileHandle, Level, MAX_LEVEL};
+use crate::sst::version::LevelMeta;
+
+/// `LeveledPicker` bounds the total size of files in each level. The bound of level 1 is
+/// `base_level_size` and the bound of each following level is `fanout` times the bound of
+/// the previous level. The last level is unbounded.
+///
+/// Level 0 is bounded by the number of files instead, as files flushed to level 0 may
+/// overlap with each other.
+pub struct LeveledPicker {
+    max_level0_files: usize,
+    base_level_size: u64,
+    fanout: u64,
+}
+
+impl Debug for LeveledPicker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeveledPicker")
+            .field("max_level0_files", &self.max_level0_files)
+            .field("base_level_size", &self.base_level_size)
+            .field("fanout", &self.fanout)
+            .finish()
+    }
+}
+
+impl LeveledPicker {
+    pub fn new(max_level0_files: usize, base_level_size: ReadableSize, fanout: usize) -> Self {
+        Self {
+            max_level0_files,
+            base_level_size: base_level_size.as_bytes(),
+            fanout: fanout as u64,
+        }
+    }
+
+    /// Returns the max total size of files in `level`, or `None` if the size of the level
+    /// is unbounded.
+    fn level_size_limit(&self, level: Level) -> Option<u64> {
+        if level == 0 || level + 1 >= MAX_LEVEL {
+            return None;
+        }
+        let ratio = self.fanout.saturating_pow(u32::from(level) - 1);
+        Some(self.base_level_size.saturating_mul(ratio))
+    }
+
+    /// Builds the output of the next compaction, returns `None` if all levels are in bound.
+    fn build_output(&self, levels: &[LevelMeta]) -> Option<CompactionOutput> {
+        // Files in level 0 may overlap with each other, so we always compact all of them
+        // together to keep files in the next level disjoint.
+        let level0_files: Vec<_> = levels[0]
+            .files()
+            .filter(|file| !file.compacting())
+            .cloned()
+            .collect();
+        if level0_files.len() > self.max_level0_files {
+            return Some(merge_into_level(level0_files, &levels[1]));
+        }
+        debug!(
+            "No enough files in level 0, current: {}, max_level0_files: {}",
+            level0_files.len(),
+            self.max_level0_files
+        );
+
+        for level in 1..MAX_LEVEL {
+            let Some(limit) = self.level_size_limit(level) else {
+                continue;
+            };
+            // Levels are added to the version on demand.
+            let Some(level_meta) = levels.get(level as usize) else {
+                break;
+            };
+            let level_size: u64 = level_meta.files().map(FileHandle::size).sum();
+            if level_size <= limit {
+                continue;
+            }
+
+            // Moves the oldest file to the next level first as it is less likely to be
+            // overlapped by new data.
+            let Some(oldest) = level_meta
+                .files()
+                .filter(|file| !file.compacting())
+                .min_by_key(|file| file.time_range().0)
+            else {
+                continue;
+            };
+            debug!(
+                "Level {} exceeds size limit, current: {}, limit: {}, picked file: {}",
+                level,
+                level_size,
+                limit,
+                oldest.file_id()
+            );
+            let empty;
+            let next_level = match levels.get(level as usize + 1) {
+                Some(next_level) => next_level,
+                None => {
+                    empty = LevelMeta::new(level + 1);
+                    &empty
+                }
+            };
+            return Some(merge_into_level(vec![oldest.clone()], next_level));
+        }
+
+        None
+    }
+}
+
+/// Builds an output that merges `inputs` with files they overlap in `next_level`.
+fn merge_into_level(mut inputs: Vec<FileHandle>, next_level: &LevelMeta) -> CompactionOutput {
+    let start = inputs.iter().map(|file| file.time_range().0).min();
+    let end = inputs.iter().map(|file| file.time_range().1).max();
+    if let (Some(start), Some(end)) = (start, end) {
+        // Compacting files in the next level are expired files, which are removed by
+        // the same task so we don't need to merge them.
+        inputs.extend(
+            next_level
+                .files()
+                .filter(|file| !file.compacting() && overlaps(file, start, end))
+                .cloned(),
+        );
+    }
+
+    CompactionOutput {
+        output_level: next_level.level,
+        inputs,
+        rollup: None,
+    }
+}
+
+/// Returns true if the time range of the `file` overlaps with `[start, end]`.
+fn overlaps(file: &FileHandle, start: Timestamp, end: Timestamp) -> bool {
+    let (file_start, file_end) = file.time_range();
+    file_start <= end && start <= file_end
+}
+
+impl Picker for LeveledPicker {
+    fn pick(&self, req: CompactionRequest) -> Option<Box<dyn CompactionTask>> {
+        let CompactionRequest {
+            current_version,
+            access_layer,
+            request_sender,
+            waiters,
+            file_purger,
+            start_time,
+            sst_write_opts,
+            column_metadata,
+            target_file_size,
+            cache_manager,
+            current_time,
+        } = req;
+
+        let region_metadata = current_version.metadata.clone();
+        let region_id = region_metadata.region_id;
+
+        let levels = current_version.ssts.levels();
+        let ttl = current_version.options.ttl;
+        let expired_ssts = get_expired_ssts(levels, ttl, current_time);
+        if !expired_ssts.is_empty() {
+            info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
+            // here we mark expired SSTs as compacting to avoid them being picked.
+            expired_ssts.iter().for_each(|f| f.set_compacting(true));
+        }
+
+        // Picks one output each time, the scheduler picks again after the task finishes
+        // until all levels are in bound.
+        let outputs: Vec<_> = self.build_output(levels).into_iter().collect();
+        if outputs.is_empty() && expired_ssts.is_empty() {
+            // Nothing to compact, we are done. Notifies all waiters as we consume the compaction request.
+            for waiter in waiters {
+                waiter.send(Ok(0));
+            }
+            return None;
+        }
+        let task = TwcsCompactionTask {
+            region_id,
+            metadata: region_metadata,
+            sst_layer: access_layer,
+            outputs,
+            expired_ssts,
+            sst_write_opts,
+            column_metadata,
+            target_file_size,
+            compaction_time_window: None,
+            request_sender,
+            waiters,
+            file_purger,
+            start_time,
+            cache_manager,
+            storage: current_version.options.storage.clone(),
+            index_columns: current_version.options.index_columns.clone(),
+            duplicate_mode: current_version.options.duplicate_mode,
+        };
+        Some(Box::new(task))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compaction::test_util::new_file_handle_with_size;
+    use crate::sst::file::{FileId, FileMeta};
+    use crate::sst::version::SstVersion;
+    use crate::test_util::new_noop_file_purger;
+
+    fn file_ids(output: &CompactionOutput) -> Vec<FileId> {
+        let mut ids: Vec<_> = output.inputs.iter().map(FileHandle::file_id).collect();
+        ids.sort();
+        ids
+    }
+
+    fn new_levels(files: &[FileHandle]) -> Vec<LevelMeta> {
+        let mut levels: Vec<_> = (0..MAX_LEVEL).map(LevelMeta::new).collect();
+        for file in files {
+            levels[file.meta().level as usize]
+                .files
+                .insert(file.file_id(), file.clone());
+        }
+        levels
+    }
+
+    #[test]
+    fn test_level_size_limit() {
+        let picker = LeveledPicker::new(4, ReadableSize(100), 10);
+        assert_eq!(None, picker.level_size_limit(0));
+        assert_eq!(Some(100), picker.level_size_limit(1));
+        assert_eq!(Some(1000), picker.level_size_limit(2));
+        assert_eq!(None, picker.level_size_limit(MAX_LEVEL - 1));
+    }
+
+    #[test]
+    fn test_build_output_empty_levels() {
+        let picker = LeveledPicker::new(0, ReadableSize(100), 10);
+        assert!(picker.build_output(&new_levels(&[])).is_none());
+    }
+
+    #[test]
+    fn test_build_output_level0_overlap() {
+        let picker = LeveledPicker::new(2, ReadableSize(100), 10);
+        // Files in level 0 overlap with each other.
+        let level0 = [
+            new_file_handle_with_size(FileId::random(), 0, 1000, 0, 10),
+            new_file_handle_with_size(FileId::random(), 500, 1500, 0, 10),
+            new_file_handle_with_size(FileId::random(), 1200, 2000, 0, 10),
+        ];
+        let overlapped = new_file_handle_with_size(FileId::random(), 1800, 2500, 1, 10);
+        let disjoint = new_file_handle_with_size(FileId::random(), 3000, 4000, 1, 10);
+        let mut files = level0.to_vec();
+        files.push(overlapped.clone());
+        files.push(disjoint);
+        let levels = new_levels(&files);
+
+        let output = picker.build_output(&levels).unwrap();
+        assert_eq!(1, output.output_level);
+        let mut expect: Vec<_> = level0.iter().map(FileHandle::file_id).collect();
+        expect.push(overlapped.file_id());
+        expect.sort();
+        assert_eq!(expect, file_ids(&output));
+
+        // Files under compaction are not picked.
+        level0[0].set_compacting(true);
+        assert!(picker.build_output(&levels).is_none());
+    }
+
+    #[test]
+    fn test_build_output_level_overflow() {
+        let picker = LeveledPicker::new(4, ReadableSize(100), 10);
+        let oldest = new_file_handle_with_size(FileId::random(), 0, 1000, 1, 60);
+        let newer = new_file_handle_with_size(FileId::random(), 1001, 2000, 1, 60);
+        let overlapped = new_file_handle_with_size(FileId::random(), 500, 1500, 2, 60);
+        let levels = new_levels(&[oldest.clone(), newer, overlapped.clone()]);
+
+        let output = picker.build_output(&levels).unwrap();
+        assert_eq!(2, output.output_level);
+        let mut expect = vec![oldest.file_id(), overlapped.file_id()];
+        expect.sort();
+        assert_eq!(expect, file_ids(&output));
+    }
+
+    /// Applies the `output` to the `version` as if the compaction finished, the output
+    /// file is as large as all its inputs.
+    fn apply_output(version: &mut SstVersion, output: CompactionOutput) {
+        let inputs: Vec<_> = output.inputs.iter().map(FileHandle::meta).collect();
+        let output_file = FileMeta {
+            region_id: 0.into(),
+            file_id: FileId::random(),
+            time_range: (
+                inputs.iter().map(|f| f.time_range.0).min().unwrap(),
+                inputs.iter().map(|f| f.time_range.1).max().unwrap(),
+            ),
+            level: output.output_level,
+            file_size: inputs.iter().map(|f| f.file_size).sum(),
+            available_indexes: Default::default(),
+            index_file_size: 0,
+            rolled_up: false,
+            index_stats: Vec::new(),
+            footer_checksum: None,
+            index_file_id: None,
+        };
+        version.remove_files(inputs.into_iter());
+        version.add_files(new_noop_file_purger(), std::iter::once(output_file));
+    }
+
+    fn level_size(level: &LevelMeta) -> u64 {
+        level.files().map(FileHandle::size).sum()
+    }
+
+    #[test]
+    fn test_simulate_writes() {
+        let max_level0_files = 4;
+        let file_size = 10;
+        let num_flushes = 200;
+        let picker = LeveledPicker::new(max_level0_files, ReadableSize(64), 4);
+        let mut version = SstVersion::new();
+        // Levels are added on demand.
+        assert_eq!(2, version.levels().len());
+
+        for i in 0..num_flushes {
+            // Flushes a file to level 0.
+            let flushed =
+                new_file_handle_with_size(FileId::random(), i * 10, i * 10 + 9, 0, file_size);
+            version.add_files(new_noop_file_purger(), std::iter::once(flushed.meta()));
+
+            while let Some(output) = picker.build_output(version.levels()) {
+                apply_output(&mut version, output);
+            }
+
+            let levels = version.levels();
+            assert!(levels[0].files.len() <= max_level0_files);
+            for level in 1..MAX_LEVEL {
+                if let Some(limit) = picker.level_size_limit(level) {
+                    let size = levels.get(level as usize).map(level_size).unwrap_or(0);
+                    assert!(
+                        size <= limit,
+                        "level: {level}, size: {size}, limit: {limit}"
+                    );
+                }
+            }
+        }
+
+        let levels = version.levels();
+        let total_size: u64 = levels.iter().map(level_size).sum();
+        assert_eq!(file_size * num_flushes as u64, total_size);
+        // Data overflows to the last level.
+        assert_eq!(MAX_LEVEL as usize, levels.len());
+        assert!(!levels[MAX_LEVEL as usize - 1].files.is_empty());
+    }
+}
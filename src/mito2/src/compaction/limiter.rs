@@ -0,0 +1,227 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limiter of concurrent compactions in a node.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use crate::metrics::{COMPACTION_ACTIVE, COMPACTION_QUEUED};
+
+/// Priority of a compaction waiting for a permit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompactionPriority {
+    /// Compactions triggered in background, e.g. after flush.
+    Normal,
+    /// Compactions requested explicitly, they are granted before all normal ones.
+    High,
+}
+
+pub(crate) type CompactionLimiterRef = Arc<CompactionLimiter>;
+
+/// Limits the number of compactions running concurrently.
+///
+/// The limiter is shared by all region workers of an engine. Permits are granted in
+/// the order compactions are queued, except high priority compactions jump the queue.
+pub(crate) struct CompactionLimiter {
+    max_running: usize,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    /// Number of permits granted.
+    running: usize,
+    high: VecDeque<oneshot::Sender<CompactionPermit>>,
+    normal: VecDeque<oneshot::Sender<CompactionPermit>>,
+}
+
+impl LimiterState {
+    fn pop_waiter(&mut self) -> Option<oneshot::Sender<CompactionPermit>> {
+        self.high.pop_front().or_else(|| self.normal.pop_front())
+    }
+
+    fn num_queued(&self) -> usize {
+        self.high.len() + self.normal.len()
+    }
+}
+
+impl CompactionLimiter {
+    /// Returns a limiter that allows `max_running` compactions to run concurrently.
+    pub(crate) fn new(max_running: usize) -> CompactionLimiter {
+        CompactionLimiter {
+            max_running,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// Returns a permit if there are free permits.
+    pub(crate) fn try_acquire(self: &Arc<Self>) -> Option<CompactionPermit> {
+        let mut state = self.state.lock().unwrap();
+        if state.running >= self.max_running {
+            return None;
+        }
+
+        state.running += 1;
+        COMPACTION_ACTIVE.inc();
+        Some(CompactionPermit::new(self.clone()))
+    }
+
+    /// Waits until a permit is granted.
+    pub(crate) async fn acquire(
+        self: &Arc<Self>,
+        priority: CompactionPriority,
+    ) -> CompactionPermit {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if state.running < self.max_running {
+                state.running += 1;
+                COMPACTION_ACTIVE.inc();
+                return CompactionPermit::new(self.clone());
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            match priority {
+                CompactionPriority::Normal => state.normal.push_back(sender),
+                CompactionPriority::High => state.high.push_back(sender),
+            }
+            COMPACTION_QUEUED.inc();
+            receiver
+        };
+
+        // The limiter holds the sender until it grants the permit.
+        receiver.await.unwrap()
+    }
+
+    /// Returns the number of permits granted.
+    #[cfg(test)]
+    pub(crate) fn num_running(&self) -> usize {
+        self.state.lock().unwrap().running
+    }
+
+    /// Returns the number of compactions waiting for permits.
+    #[cfg(test)]
+    pub(crate) fn num_queued(&self) -> usize {
+        self.state.lock().unwrap().num_queued()
+    }
+
+    /// Releases a permit, hands it over to the next waiter if there is any.
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        while let Some(waiter) = state.pop_waiter() {
+            COMPACTION_QUEUED.dec();
+            if let Err(mut permit) = waiter.send(CompactionPermit::new(self.clone())) {
+                // The waiter is gone, the permit goes to the next waiter.
+                permit.limiter = None;
+                continue;
+            }
+            return;
+        }
+
+        state.running -= 1;
+        COMPACTION_ACTIVE.dec();
+    }
+}
+
+/// Permit to run a compaction, released on drop.
+pub(crate) struct CompactionPermit {
+    limiter: Option<CompactionLimiterRef>,
+}
+
+impl CompactionPermit {
+    fn new(limiter: CompactionLimiterRef) -> CompactionPermit {
+        CompactionPermit {
+            limiter: Some(limiter),
+        }
+    }
+}
+
+impl Drop for CompactionPermit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limit_concurrency() {
+        let limiter = Arc::new(CompactionLimiter::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let running = running.clone();
+                let max_running = max_running.clone();
+                tokio::spawn(async move {
+                    let _permit = limiter.acquire(CompactionPriority::Normal).await;
+                    let current = running.fetch_add(1, Ordering::Relaxed) + 1;
+                    max_running.fetch_max(current, Ordering::Relaxed);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::Relaxed);
+                })
+            })
+            .collect();
+        futures::future::join_all(handles).await;
+
+        assert_eq!(2, max_running.load(Ordering::Relaxed));
+        assert_eq!(0, limiter.num_running());
+        assert_eq!(0, limiter.num_queued());
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_jump_queue() {
+        let limiter = Arc::new(CompactionLimiter::new(1));
+        let permit = limiter.try_acquire().unwrap();
+        assert!(limiter.try_acquire().is_none());
+
+        let normal = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(CompactionPriority::Normal).await }
+        });
+        while limiter.num_queued() < 1 {
+            tokio::task::yield_now().await;
+        }
+        let high = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(CompactionPriority::High).await }
+        });
+        while limiter.num_queued() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // The high priority compaction gets the permit first.
+        drop(permit);
+        let high_permit = high.await.unwrap();
+        assert_eq!(1, limiter.num_queued());
+        assert!(!normal.is_finished());
+
+        drop(high_permit);
+        let normal_permit = normal.await.unwrap();
+        assert_eq!(1, limiter.num_running());
+        drop(normal_permit);
+        assert_eq!(0, limiter.num_running());
+    }
+}
@@ -0,0 +1,601 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rolls up aged SSTs into downsampled rows.
+
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::v1::OpType;
+use common_telemetry::{error, info};
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use datatypes::prelude::{ConcreteDataType, DataType, MutableVector, Vector, VectorRef};
+use datatypes::value::{OrderedF64, Value};
+use datatypes::vectors::{UInt64Vector, UInt8Vector};
+use snafu::ResultExt;
+use store_api::metadata::RegionMetadata;
+
+use crate::compaction::picker::{CompactionPickerRef, CompactionTask, Picker};
+use crate::compaction::twcs::{CompactionOutput, TwcsCompactionTask};
+use crate::compaction::CompactionRequest;
+use crate::error::{ComputeVectorSnafu, Result};
+use crate::read::{Batch, BatchColumn, BatchReader, BoxedBatchReader};
+use crate::region::options::{RollupAggregation, RollupOptions};
+use crate::sst::file::FileHandle;
+use crate::sst::version::LevelMeta;
+
+/// Picks raw SSTs older than the rollup age to roll up, and delegates to the `inner`
+/// picker if there is nothing to roll up.
+///
+/// Rolling up an SST replaces it with a rolled up SST in the same compaction, so a
+/// crash before the compaction finishes leaves the raw SST untouched.
+pub(crate) struct RollupPicker {
+    options: RollupOptions,
+    inner: CompactionPickerRef,
+}
+
+impl Debug for RollupPicker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RollupPicker")
+            .field("options", &self.options)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl RollupPicker {
+    pub(crate) fn new(options: RollupOptions, inner: CompactionPickerRef) -> Self {
+        Self { options, inner }
+    }
+}
+
+impl Picker for RollupPicker {
+    fn pick(&self, req: CompactionRequest) -> Option<Box<dyn CompactionTask>> {
+        let Some(after) = self.options.rollup_after() else {
+            return self.inner.pick(req);
+        };
+        let levels = req.current_version.ssts.levels();
+        let inputs = find_rollup_inputs(levels, after, self.options.interval, req.current_time);
+        if inputs.is_empty() {
+            return self.inner.pick(req);
+        }
+
+        let CompactionRequest {
+            current_version,
+            access_layer,
+            request_sender,
+            waiters,
+            file_purger,
+            start_time,
+            sst_write_opts,
+            column_metadata,
+            target_file_size,
+            cache_manager,
+            current_time: _,
+        } = req;
+        let region_id = current_version.metadata.region_id;
+        info!(
+            "Roll up SSTs in region {} older than {:?}: {:?}",
+            region_id, after, inputs
+        );
+
+        let output = CompactionOutput {
+            output_level: inputs
+                .iter()
+                .map(|file| file.meta().level)
+                .max()
+                .unwrap_or_default(),
+            inputs,
+            rollup: Some(self.options.clone()),
+        };
+        // Rolls up files first, the inner picker compacts the region after all aged
+        // files are rolled up.
+        let task = TwcsCompactionTask {
+            region_id,
+            metadata: current_version.metadata.clone(),
+            sst_layer: access_layer,
+            outputs: vec![output],
+            expired_ssts: Vec::new(),
+            sst_write_opts,
+            column_metadata,
+            target_file_size,
+            compaction_time_window: None,
+            request_sender,
+            waiters,
+            file_purger,
+            start_time,
+            cache_manager,
+            storage: current_version.options.storage.clone(),
+            index_columns: current_version.options.index_columns.clone(),
+            duplicate_mode: current_version.options.duplicate_mode,
+        };
+        Some(Box::new(task))
+    }
+}
+
+/// Finds raw SSTs whose rows are all in intervals older than `after`, and rolled up
+/// SSTs overlapping with intervals of these raw SSTs.
+///
+/// The threshold is aligned down to the start of an interval, so an interval is never
+/// rolled up before all its rows are aged. Rows of an interval may still be rolled up
+/// in several steps, e.g. rows written late, so we roll up the rolled up rows of the
+/// interval again with its raw rows.
+fn find_rollup_inputs(
+    levels: &[LevelMeta],
+    after: Duration,
+    interval: Duration,
+    now: Timestamp,
+) -> Vec<FileHandle> {
+    let threshold = match now.sub_duration(after) {
+        Ok(threshold) => align_to_interval(threshold, interval),
+        Err(e) => {
+            error!(e; "Failed to calculate rollup threshold");
+            return Vec::new();
+        }
+    };
+
+    let files = || {
+        levels
+            .iter()
+            .flat_map(LevelMeta::files)
+            .filter(|file| !file.compacting())
+    };
+    let mut inputs: Vec<_> = files()
+        .filter(|file| !file.rolled_up() && file.time_range().1 < threshold)
+        .cloned()
+        .collect();
+    let Some(start) = inputs.iter().map(|file| file.time_range().0).min() else {
+        return inputs;
+    };
+    let start = align_to_interval(start, interval);
+    // Safety: inputs are not empty.
+    let end = inputs.iter().map(|file| file.time_range().1).max().unwrap();
+    inputs.extend(
+        files()
+            .filter(|file| {
+                let (file_start, file_end) = file.time_range();
+                file.rolled_up() && file_start <= end && file_end >= start
+            })
+            .cloned(),
+    );
+    inputs
+}
+
+/// Returns the start of the interval `ts` belongs to.
+fn align_to_interval(ts: Timestamp, interval: Duration) -> Timestamp {
+    let interval = interval_in_unit(interval, ts.unit());
+    Timestamp::new(align_down(ts.value(), interval), ts.unit())
+}
+
+/// Returns the `interval` in the time `unit`, which is at least 1.
+fn interval_in_unit(interval: Duration, unit: TimeUnit) -> i64 {
+    let interval = interval.as_nanos() / u128::from(unit.factor());
+    i64::try_from(interval).unwrap_or(i64::MAX).max(1)
+}
+
+/// Aligns `value` down to a multiple of `interval`.
+fn align_down(value: i64, interval: i64) -> i64 {
+    value.saturating_sub(value.rem_euclid(interval))
+}
+
+/// A [BatchReader] that aggregates rows of each time series in the same interval into
+/// one row, whose timestamp is the start of the interval.
+///
+/// Each input reader must return rows sorted by primary key and timestamp without
+/// duplications and deletions, e.g. a merge reader. Rows of different readers are
+/// aggregated together even if they have the same timestamp, so rolled up rows can be
+/// rolled up again with raw rows of the same interval.
+pub(crate) struct RollupReader {
+    sources: Vec<RollupSource>,
+    /// Interval in the unit of the time index.
+    interval: i64,
+    aggregation: RollupAggregation,
+}
+
+impl RollupReader {
+    pub(crate) fn new(
+        readers: Vec<BoxedBatchReader>,
+        options: &RollupOptions,
+        metadata: &RegionMetadata,
+    ) -> RollupReader {
+        // Safety: the time index is always a timestamp.
+        let unit = metadata
+            .time_index_column()
+            .column_schema
+            .data_type
+            .as_timestamp()
+            .unwrap()
+            .unit();
+        RollupReader {
+            sources: readers.into_iter().map(RollupSource::new).collect(),
+            interval: interval_in_unit(options.interval, unit),
+            aggregation: options.aggregation,
+        }
+    }
+
+    /// Rolls up batches of a time series.
+    fn roll_up(&self, series: Vec<Batch>) -> Result<Batch> {
+        let batch = Batch::concat(series)?;
+        // Safety: the batch isn't empty, Batch::concat() ensures it.
+        let timestamps = batch.timestamps_native().unwrap();
+        // Rows of the series from different sources aren't sorted, so we sort rows by
+        // their intervals.
+        let mut rows: Vec<_> = timestamps
+            .iter()
+            .enumerate()
+            .map(|(i, ts)| (align_down(*ts, self.interval), i))
+            .collect();
+        rows.sort_by_key(|(bucket, _)| *bucket);
+        let mut ranges: Vec<(i64, Vec<usize>)> = Vec::new();
+        for (bucket, i) in rows {
+            match ranges.last_mut() {
+                Some((last, indices)) if *last == bucket => indices.push(i),
+                _ => ranges.push((bucket, vec![i])),
+            }
+        }
+
+        let ts_type = batch.timestamps().data_type();
+        // Safety: the time index is always a timestamp.
+        let unit = ts_type.as_timestamp().unwrap().unit();
+        let mut ts_builder = ts_type.create_mutable_vector(ranges.len());
+        for (bucket, _) in &ranges {
+            ts_builder
+                .try_push_value_ref(Value::Timestamp(Timestamp::new(*bucket, unit)).as_value_ref())
+                .context(ComputeVectorSnafu)?;
+        }
+        // Keeps the max sequence of rows in each interval.
+        let sequences: Vec<_> = ranges
+            .iter()
+            .map(|(_, indices)| {
+                indices
+                    .iter()
+                    .map(|i| batch.get_sequence(*i))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let op_types = vec![OpType::Put as u8; ranges.len()];
+        let fields = batch
+            .fields()
+            .iter()
+            .map(|field| {
+                let data = self.aggregate(&field.data, &ranges)?;
+                Ok(BatchColumn {
+                    column_id: field.column_id,
+                    data,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Batch::new(
+            batch.primary_key().to_vec(),
+            ts_builder.to_vector(),
+            Arc::new(UInt64Vector::from_vec(sequences)),
+            Arc::new(UInt8Vector::from_vec(op_types)),
+            fields,
+        )
+    }
+
+    /// Aggregates values of the column `data` in each range.
+    fn aggregate(&self, data: &VectorRef, ranges: &[(i64, Vec<usize>)]) -> Result<VectorRef> {
+        let data_type = data.data_type();
+        let mut builder = data_type.create_mutable_vector(ranges.len());
+        for (_, indices) in ranges {
+            let values = indices
+                .iter()
+                .map(|i| data.get(*i))
+                .filter(|v| !v.is_null());
+            let value = aggregate_values(self.aggregation, &data_type, values);
+            builder
+                .try_push_value_ref(value.as_value_ref())
+                .context(ComputeVectorSnafu)?;
+        }
+        Ok(builder.to_vector())
+    }
+}
+
+/// Aggregates non-null `values` of `data_type`, returns null if there is no value.
+///
+/// Computes the sum of numeric values in f64 and casts the result back to the
+/// `data_type`. Uses the last value as the sum of non-numeric types.
+fn aggregate_values(
+    aggregation: RollupAggregation,
+    data_type: &ConcreteDataType,
+    values: impl Iterator<Item = Value>,
+) -> Value {
+    let value = match aggregation {
+        RollupAggregation::Min => values.min(),
+        RollupAggregation::Max => values.max(),
+        RollupAggregation::Sum if !data_type.is_numeric() => values.last(),
+        RollupAggregation::Sum => {
+            let float64 = ConcreteDataType::float64_datatype();
+            let (sum, count) = values
+                .filter_map(|value| match float64.try_cast(value) {
+                    Some(Value::Float64(v)) => Some(v.0),
+                    _ => None,
+                })
+                .fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+            (count > 0)
+                .then_some(sum)
+                .and_then(|v| data_type.try_cast(Value::Float64(OrderedF64::from(v))))
+        }
+    };
+
+    value.unwrap_or(Value::Null)
+}
+
+/// An input reader of the [RollupReader].
+struct RollupSource {
+    reader: BoxedBatchReader,
+    /// Next non-empty batch to roll up.
+    batch: Option<Batch>,
+    /// Whether the reader is exhausted.
+    eof: bool,
+}
+
+impl RollupSource {
+    fn new(reader: BoxedBatchReader) -> RollupSource {
+        RollupSource {
+            reader,
+            batch: None,
+            eof: false,
+        }
+    }
+
+    /// Fetches the next non-empty batch if the source doesn't have a batch.
+    async fn fetch(&mut self) -> Result<()> {
+        while self.batch.is_none() && !self.eof {
+            match self.reader.next_batch().await? {
+                Some(batch) if batch.is_empty() => continue,
+                Some(batch) => self.batch = Some(batch),
+                None => self.eof = true,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl BatchReader for RollupReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        for source in &mut self.sources {
+            source.fetch().await?;
+        }
+        // Rolls up the time series with the min primary key in all sources.
+        let Some(primary_key) = self
+            .sources
+            .iter()
+            .filter_map(|source| source.batch.as_ref())
+            .map(Batch::primary_key)
+            .min()
+            .map(<[u8]>::to_vec)
+        else {
+            return Ok(None);
+        };
+
+        let mut series = Vec::new();
+        for source in &mut self.sources {
+            while source
+                .batch
+                .as_ref()
+                .is_some_and(|batch| batch.primary_key() == primary_key)
+            {
+                series.extend(source.batch.take());
+                source.fetch().await?;
+            }
+        }
+        self.roll_up(series).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::compaction::test_util::new_file_handle;
+    use crate::sst::file::{FileId, MAX_LEVEL};
+    use crate::test_util::sst_util::sst_region_metadata;
+    use crate::test_util::{check_reader_result, new_batch, new_noop_file_purger, VecBatchReader};
+
+    fn new_reader(sources: &[&[Batch]], aggregation: RollupAggregation) -> RollupReader {
+        let options = RollupOptions {
+            after: Some(Duration::from_secs(3600)),
+            interval: Duration::from_secs(10),
+            aggregation,
+        };
+        let readers = sources
+            .iter()
+            .map(|batches| Box::new(VecBatchReader::new(batches)) as _)
+            .collect();
+        RollupReader::new(readers, &options, &sst_region_metadata())
+    }
+
+    async fn read_all(reader: &mut RollupReader) -> Vec<Batch> {
+        let mut batches = Vec::new();
+        while let Some(batch) = reader.next_batch().await.unwrap() {
+            batches.push(batch);
+        }
+        batches
+    }
+
+    fn new_inputs() -> Vec<Batch> {
+        vec![
+            new_batch(
+                b"k1",
+                &[1000, 5000, 9999],
+                &[11, 12, 13],
+                &[OpType::Put, OpType::Put, OpType::Put],
+                &[1, 2, 6],
+            ),
+            // The time series spans multiple batches.
+            new_batch(
+                b"k1",
+                &[10000, 25000],
+                &[14, 15],
+                &[OpType::Put, OpType::Put],
+                &[4, 5],
+            ),
+            new_batch(
+                b"k2",
+                &[-1000, 2000],
+                &[16, 17],
+                &[OpType::Put, OpType::Put],
+                &[7, 8],
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_rollup_reader() {
+        let inputs = new_inputs();
+        for (aggregation, k1_fields, k2_fields) in [
+            (RollupAggregation::Sum, [9, 4, 5], [7, 8]),
+            (RollupAggregation::Min, [1, 4, 5], [7, 8]),
+            (RollupAggregation::Max, [6, 5, 5], [7, 8]),
+        ] {
+            let mut reader = new_reader(&[&inputs], aggregation);
+            let expect = [
+                new_batch(
+                    b"k1",
+                    &[0, 10000, 20000],
+                    &[13, 14, 15],
+                    &[OpType::Put, OpType::Put, OpType::Put],
+                    &k1_fields,
+                ),
+                new_batch(
+                    b"k2",
+                    &[-10000, 0],
+                    &[16, 17],
+                    &[OpType::Put, OpType::Put],
+                    &k2_fields,
+                ),
+            ];
+            check_reader_result(&mut reader, &expect).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rollup_idempotent() {
+        for aggregation in [
+            RollupAggregation::Sum,
+            RollupAggregation::Min,
+            RollupAggregation::Max,
+        ] {
+            let rolled_up = read_all(&mut new_reader(&[&new_inputs()], aggregation)).await;
+
+            // Rolling up the rolled up rows again returns the same rows.
+            let mut reader = new_reader(&[&rolled_up], aggregation);
+            check_reader_result(&mut reader, &rolled_up).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rollup_in_two_steps() {
+        let first = [
+            new_batch(
+                b"k1",
+                &[1000, 5000],
+                &[11, 12],
+                &[OpType::Put, OpType::Put],
+                &[1, 2],
+            ),
+            new_batch(b"k2", &[-1000], &[16], &[OpType::Put], &[7]),
+        ];
+        // Rows written after the first rollup, in the same intervals as rolled up rows.
+        // The row at 0 has the same timestamp as the rolled up row of k1.
+        let second = [
+            new_batch(
+                b"k1",
+                &[0, 9999, 10000, 25000],
+                &[18, 13, 14, 15],
+                &[OpType::Put, OpType::Put, OpType::Put, OpType::Put],
+                &[3, 6, 4, 5],
+            ),
+            new_batch(b"k2", &[2000], &[17], &[OpType::Put], &[8]),
+        ];
+        for (aggregation, k1_fields) in [
+            (RollupAggregation::Sum, [12, 4, 5]),
+            (RollupAggregation::Min, [1, 4, 5]),
+            (RollupAggregation::Max, [6, 5, 5]),
+        ] {
+            let rolled_up = read_all(&mut new_reader(&[&first], aggregation)).await;
+
+            let mut reader = new_reader(&[&second, &rolled_up], aggregation);
+            let expect = [
+                new_batch(
+                    b"k1",
+                    &[0, 10000, 20000],
+                    &[18, 14, 15],
+                    &[OpType::Put, OpType::Put, OpType::Put],
+                    &k1_fields,
+                ),
+                new_batch(
+                    b"k2",
+                    &[-10000, 0],
+                    &[16, 17],
+                    &[OpType::Put, OpType::Put],
+                    &[7, 8],
+                ),
+            ];
+            check_reader_result(&mut reader, &expect).await;
+        }
+    }
+
+    fn new_rolled_up_file(start_ts_millis: i64, end_ts_millis: i64) -> FileHandle {
+        let mut meta = new_file_handle(FileId::random(), start_ts_millis, end_ts_millis, 1).meta();
+        meta.rolled_up = true;
+        FileHandle::new(meta, new_noop_file_purger())
+    }
+
+    #[test]
+    fn test_find_rollup_inputs() {
+        let mut levels: Vec<_> = (0..MAX_LEVEL).map(LevelMeta::new).collect();
+        let old = new_file_handle(FileId::random(), 1000, 3000, 0);
+        let compacting = new_file_handle(FileId::random(), 0, 1000, 0);
+        compacting.set_compacting(true);
+        // Older than the threshold, but its last interval isn't aged.
+        let partial = new_file_handle(FileId::random(), 5000, 7000, 0);
+        let recent = new_file_handle(FileId::random(), 0, 9000, 1);
+        let rolled_up = new_rolled_up_file(0, 0);
+        let rolled_up_before = new_rolled_up_file(-10000, -5000);
+        for file in [
+            &old,
+            &compacting,
+            &partial,
+            &recent,
+            &rolled_up,
+            &rolled_up_before,
+        ] {
+            levels[file.meta().level as usize]
+                .files
+                .insert(file.file_id(), file.clone());
+        }
+
+        // The threshold 8000 is aligned to 5000.
+        let now = Timestamp::new_millisecond(10000);
+        let inputs =
+            find_rollup_inputs(&levels, Duration::from_secs(2), Duration::from_secs(5), now);
+        let ids: HashSet<_> = inputs.iter().map(FileHandle::file_id).collect();
+        assert_eq!(HashSet::from([old.file_id(), rolled_up.file_id()]), ids);
+
+        // Rolled up files are not rolled up again without raw files.
+        let now = Timestamp::new_millisecond(4000);
+        let inputs =
+            find_rollup_inputs(&levels, Duration::from_secs(2), Duration::from_secs(5), now);
+        assert!(inputs.is_empty());
+    }
+}
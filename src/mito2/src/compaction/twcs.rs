@@ -31,18 +31,20 @@ use tokio::sync::mpsc;
 use crate::access_layer::{AccessLayerRef, SstWriteRequest};
 use crate::cache::CacheManagerRef;
 use crate::compaction::picker::{CompactionTask, Picker};
+use crate::compaction::rollup::RollupReader;
 use crate::compaction::CompactionRequest;
 use crate::error::{self, CompactRegionSnafu};
 use crate::metrics::{COMPACTION_FAILURE_COUNT, COMPACTION_STAGE_ELAPSED};
 use crate::read::projection::ProjectionMapper;
 use crate::read::seq_scan::SeqScan;
-use crate::read::{BoxedBatchReader, Source};
+use crate::read::{BoxedBatchReader, SharedBatchReader, Source};
+use crate::region::options::{DuplicateMode, RollupOptions};
 use crate::request::{
     BackgroundNotify, CompactionFailed, CompactionFinished, OutputTx, WorkerRequest,
 };
-use crate::sst::file::{FileHandle, FileId, FileMeta, IndexType, Level};
+use crate::sst::file::{FileHandle, FileMeta, IndexType, Level};
 use crate::sst::file_purger::FilePurgerRef;
-use crate::sst::parquet::WriteOptions;
+use crate::sst::parquet::{ColumnKeyValues, WriteOptions};
 use crate::sst::version::LevelMeta;
 
 const MAX_PARALLEL_COMPACTION: usize = 8;
@@ -92,9 +94,9 @@ impl TwcsPicker {
             {
                 if files.len() > self.max_active_window_files {
                     output.push(CompactionOutput {
-                        output_file_id: FileId::random(),
                         output_level: 1, // we only have two levels and always compact to l1
                         inputs: files.clone(),
+                        rollup: None,
                     });
                 } else {
                     debug!("Active window not present or no enough files in active window {:?}, window: {}", active_window, *window);
@@ -103,9 +105,9 @@ impl TwcsPicker {
                 // not active writing window
                 if files.len() > self.max_inactive_window_files {
                     output.push(CompactionOutput {
-                        output_file_id: FileId::random(),
                         output_level: 1,
                         inputs: files.clone(),
+                        rollup: None,
                     });
                 } else {
                     debug!(
@@ -129,8 +131,11 @@ impl Picker for TwcsPicker {
             waiters,
             file_purger,
             start_time,
-            sst_write_buffer_size,
+            sst_write_opts,
+            column_metadata,
+            target_file_size,
             cache_manager,
+            current_time,
         } = req;
 
         let region_metadata = current_version.metadata.clone();
@@ -138,7 +143,7 @@ impl Picker for TwcsPicker {
 
         let levels = current_version.ssts.levels();
         let ttl = current_version.options.ttl;
-        let expired_ssts = get_expired_ssts(levels, ttl, Timestamp::current_millis());
+        let expired_ssts = get_expired_ssts(levels, ttl, current_time);
         if !expired_ssts.is_empty() {
             info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
             // here we mark expired SSTs as compacting to avoid them being picked.
@@ -178,7 +183,9 @@ impl Picker for TwcsPicker {
             sst_layer: access_layer,
             outputs,
             expired_ssts,
-            sst_write_buffer_size,
+            sst_write_opts,
+            column_metadata,
+            target_file_size,
             compaction_time_window: Some(time_window_size),
             request_sender,
             waiters,
@@ -186,6 +193,8 @@ impl Picker for TwcsPicker {
             start_time,
             cache_manager,
             storage: current_version.options.storage.clone(),
+            index_columns: current_version.options.index_columns.clone(),
+            duplicate_mode: current_version.options.duplicate_mode,
         };
         Some(Box::new(task))
     }
@@ -239,7 +248,12 @@ pub(crate) struct TwcsCompactionTask {
     pub sst_layer: AccessLayerRef,
     pub outputs: Vec<CompactionOutput>,
     pub expired_ssts: Vec<FileHandle>,
-    pub sst_write_buffer_size: ReadableSize,
+    /// Options to write output files.
+    pub sst_write_opts: WriteOptions,
+    /// Custom key-value metadata of columns of output files.
+    pub column_metadata: ColumnKeyValues,
+    /// Target size of output files.
+    pub target_file_size: Option<ReadableSize>,
     pub compaction_time_window: Option<i64>,
     pub file_purger: FilePurgerRef,
     /// Request sender to notify the worker.
@@ -251,6 +265,10 @@ pub(crate) struct TwcsCompactionTask {
     pub(crate) cache_manager: CacheManagerRef,
     /// Target storage of the region.
     pub(crate) storage: Option<String>,
+    /// Names of tag columns to index.
+    pub(crate) index_columns: Option<Vec<String>>,
+    /// How to select a row among duplicate rows of inputs.
+    pub(crate) duplicate_mode: DuplicateMode,
 }
 
 impl Debug for TwcsCompactionTask {
@@ -289,7 +307,7 @@ impl TwcsCompactionTask {
             compacted_inputs.extend(output.inputs.iter().map(FileHandle::meta));
 
             info!(
-                "Compaction region {} output [{}]-> {}",
+                "Compaction region {} output [{}]-> level {}",
                 self.region_id,
                 output
                     .inputs
@@ -297,47 +315,96 @@ impl TwcsCompactionTask {
                     .map(|f| f.file_id().to_string())
                     .collect::<Vec<_>>()
                     .join(","),
-                output.output_file_id
+                output.output_level
             );
 
             let write_opts = WriteOptions {
-                write_buffer_size: self.sst_write_buffer_size,
-                ..Default::default()
+                target_file_size: self.target_file_size,
+                ..self.sst_write_opts.clone()
             };
             let metadata = self.metadata.clone();
             let sst_layer = self.sst_layer.clone();
             let region_id = self.region_id;
-            let file_id = output.output_file_id;
             let cache_manager = self.cache_manager.clone();
             let storage = self.storage.clone();
+            let index_columns = self.index_columns.clone();
+            let column_metadata = self.column_metadata.clone();
+            let duplicate_mode = self.duplicate_mode;
             futs.push(async move {
-                let reader =
-                    build_sst_reader(metadata.clone(), sst_layer.clone(), &output.inputs).await?;
-                let file_meta_opt = sst_layer
-                    .write_sst(
-                        SstWriteRequest {
-                            file_id,
-                            metadata,
-                            source: Source::Reader(reader),
-                            cache_manager,
-                            storage,
-                        },
-                        &write_opts,
-                    )
-                    .await?
-                    .map(|sst_info| FileMeta {
-                        region_id,
+                let reader = match &output.rollup {
+                    Some(rollup) => {
+                        build_rollup_reader(
+                            metadata.clone(),
+                            sst_layer.clone(),
+                            &output.inputs,
+                            duplicate_mode,
+                            rollup,
+                        )
+                        .await?
+                    }
+                    None => {
+                        build_sst_reader(
+                            metadata.clone(),
+                            sst_layer.clone(),
+                            &output.inputs,
+                            duplicate_mode,
+                        )
+                        .await?
+                    }
+                };
+                let rolled_up = output.rolled_up();
+                let reader = SharedBatchReader::new(Source::Reader(reader));
+                let mut file_id = sst_layer.new_file_id();
+                let mut file_metas = Vec::new();
+                // Bytes of inputs not written yet, it's a cheap estimate of the output.
+                let mut remaining_bytes: u64 = output.inputs.iter().map(|f| f.size()).sum();
+                // Each writer stops at the target size, rolls over to a new file until the
+                // reader is exhausted.
+                loop {
+                    let request = SstWriteRequest {
                         file_id,
-                        time_range: sst_info.time_range,
-                        level: output.output_level,
-                        file_size: sst_info.file_size,
-                        available_indexes: sst_info
-                            .inverted_index_available
-                            .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
-                            .unwrap_or_default(),
-                        index_file_size: sst_info.index_file_size,
-                    });
-                Ok(file_meta_opt)
+                        metadata: metadata.clone(),
+                        source: Source::Reader(Box::new(reader.clone())),
+                        cache_manager: cache_manager.clone(),
+                        storage: storage.clone(),
+                        index_columns: index_columns.clone(),
+                        column_metadata: column_metadata.clone(),
+                        source_size: Some(remaining_bytes),
+                    };
+                    let estimated_size = sst_layer.estimate_sst_size(&request, &write_opts).await?;
+                    debug!(
+                        "Compaction output of region {} is estimated to {} bytes, file_id: {}, target_file_size: {:?}",
+                        region_id, estimated_size, file_id, write_opts.target_file_size
+                    );
+                    let sst_infos = sst_layer.write_sst(request, &write_opts).await?;
+                    if sst_infos.is_empty() {
+                        break;
+                    }
+                    for sst_info in sst_infos {
+                        remaining_bytes = remaining_bytes.saturating_sub(sst_info.file_size);
+                        file_metas.push(FileMeta {
+                            region_id,
+                            file_id: sst_info.file_id,
+                            time_range: sst_info.time_range,
+                            level: output.output_level,
+                            file_size: sst_info.file_size,
+                            available_indexes: sst_info
+                                .inverted_index_available
+                                .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
+                                .unwrap_or_default(),
+                            index_file_size: sst_info.index_file_size,
+                            rolled_up,
+                            index_stats: sst_info.index_stats,
+                            footer_checksum: Some(sst_info.footer_checksum),
+                            index_file_id: None,
+                        });
+                    }
+                    if !write_opts.has_target() {
+                        break;
+                    }
+                    file_id = sst_layer.new_file_id();
+                }
+                Ok(file_metas)
             });
         }
 
@@ -502,7 +569,7 @@ pub(crate) const TIME_BUCKETS: TimeBuckets = TimeBuckets([
 ]);
 
 /// Finds all expired SSTs across levels.
-fn get_expired_ssts(
+pub(crate) fn get_expired_ssts(
     levels: &[LevelMeta],
     ttl: Option<Duration>,
     now: Timestamp,
@@ -527,11 +594,19 @@ fn get_expired_ssts(
 
 #[derive(Debug)]
 pub(crate) struct CompactionOutput {
-    pub output_file_id: FileId,
     /// Compaction output file level.
     pub output_level: Level,
     /// Compaction input files.
     pub inputs: Vec<FileHandle>,
+    /// Rolls up rows of the inputs if set.
+    pub rollup: Option<RollupOptions>,
+}
+
+impl CompactionOutput {
+    /// Returns true if rows in the output are rolled up.
+    fn rolled_up(&self) -> bool {
+        self.rollup.is_some() || self.inputs.iter().all(FileHandle::rolled_up)
+    }
 }
 
 /// Builds [BoxedBatchReader] that reads all SST files and yields batches in primary key order.
@@ -539,22 +614,52 @@ async fn build_sst_reader(
     metadata: RegionMetadataRef,
     sst_layer: AccessLayerRef,
     inputs: &[FileHandle],
+    duplicate_mode: DuplicateMode,
 ) -> error::Result<BoxedBatchReader> {
     SeqScan::new(sst_layer, ProjectionMapper::all(&metadata)?)
         .with_files(inputs.to_vec())
+        .with_duplicate_mode(duplicate_mode)
         // We ignore file not found error during compaction.
         .with_ignore_file_not_found(true)
         .build_reader()
         .await
 }
 
+/// Builds a reader to roll up `inputs`.
+///
+/// Each rolled up SST has its own reader, as its rows may have the same timestamps as
+/// rows of other inputs in the same interval, which must be aggregated instead of
+/// deduplicated.
+async fn build_rollup_reader(
+    metadata: RegionMetadataRef,
+    sst_layer: AccessLayerRef,
+    inputs: &[FileHandle],
+    duplicate_mode: DuplicateMode,
+    rollup: &RollupOptions,
+) -> error::Result<BoxedBatchReader> {
+    let (rolled_up, raw): (Vec<_>, Vec<_>) =
+        inputs.iter().cloned().partition(FileHandle::rolled_up);
+    let mut readers = Vec::with_capacity(rolled_up.len() + 1);
+    if !raw.is_empty() {
+        readers.push(
+            build_sst_reader(metadata.clone(), sst_layer.clone(), &raw, duplicate_mode).await?,
+        );
+    }
+    for file in rolled_up {
+        readers.push(
+            build_sst_reader(metadata.clone(), sst_layer.clone(), &[file], duplicate_mode).await?,
+        );
+    }
+    Ok(Box::new(RollupReader::new(readers, rollup, &metadata)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
 
     use super::*;
     use crate::compaction::test_util::new_file_handle;
-    use crate::sst::file::Level;
+    use crate::sst::file::{FileId, Level};
 
     #[test]
     fn test_get_latest_window_in_seconds() {
@@ -14,21 +14,36 @@
 
 //! Configurations.
 
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
+use api::v1::SemanticType;
 use common_base::readable_size::ReadableSize;
 use common_telemetry::warn;
+use parquet::basic::Encoding;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
+use store_api::metadata::RegionMetadata;
+use store_api::storage::ColumnId;
 
 use crate::error::{InvalidConfigSnafu, Result};
+use crate::sst::location::{DatePartitionedPath, FlatPath, PathStrategyRef};
+use crate::sst::parquet::writer::requires_parquet_v2;
+use crate::sst::parquet::{ColumnKeyValues, Compression, ParquetVersion, WriteOptions};
+use crate::sst::retry::RetryConfig;
 
 /// Default max running background job.
 const DEFAULT_MAX_BG_JOB: usize = 4;
 
 const MULTIPART_UPLOAD_MINIMUM_SIZE: ReadableSize = ReadableSize::mb(5);
+/// Default level of the zstd codec of SSTs, the default level of the parquet writer.
+const DEFAULT_SST_ZSTD_LEVEL: i32 = 1;
 /// Default channel size for parallel scan task.
 const DEFAULT_SCAN_CHANNEL_SIZE: usize = 32;
+/// Max percentage of the default flush threshold adaptive flush scales to, which
+/// reaches the global write buffer size.
+const MAX_ADAPTIVE_FLUSH_PERCENT: u32 = 200;
 
 /// Configuration for [MitoEngine](crate::engine::MitoEngine).
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -42,6 +57,17 @@ pub struct MitoConfig {
     pub worker_channel_size: usize,
     /// Max batch size for a worker to handle requests (default 64).
     pub worker_request_batch_size: usize,
+    /// Time window for a worker to wait for more write requests to write them in one
+    /// batch (default 0). It bounds the latency the worker adds to each write request.
+    /// Setting it to 0 to disable coalescing.
+    #[serde(with = "humantime_serde")]
+    pub write_coalesce_window: Duration,
+    /// Max number of rows to coalesce in one batch (default 16384).
+    pub write_coalesce_max_rows: usize,
+    /// Max number of open regions in the node (default 0). The least recently used
+    /// regions are flushed and closed in background once more regions are open, and
+    /// reopened on the next access. Setting it to 0 to disable the limit.
+    pub max_open_regions: usize,
 
     // Manifest configs:
     /// Number of meta action updated to trigger a new checkpoint
@@ -49,10 +75,18 @@ pub struct MitoConfig {
     pub manifest_checkpoint_distance: u64,
     /// Whether to compress manifest and checkpoint file by gzip (default false).
     pub compress_manifest: bool,
+    /// Number of entries in a page to list manifests and intermediate files of indexes
+    /// (default 0). A larger page lists many files in fewer requests. Setting it to 0 uses
+    /// the default page size of the store, and the page size is capped to the max page size
+    /// of object stores.
+    pub list_page_size: usize,
 
     // Background job configs:
     /// Max number of running background jobs (default 4).
     pub max_background_jobs: usize,
+    /// Max number of compactions running concurrently in the node (default: 1/4 of cpu cores).
+    /// Sets to 0 to use the default value.
+    pub max_concurrent_compactions: usize,
 
     // Flush configs:
     /// Interval to auto flush a region if it has not flushed yet (default 30 min).
@@ -62,6 +96,20 @@ pub struct MitoConfig {
     pub global_write_buffer_size: ReadableSize,
     /// Global write buffer size threshold to reject write requests (default 2G).
     pub global_write_buffer_reject_size: ReadableSize,
+    /// Whether to scale the flush threshold of mutable memtables by the recent write
+    /// rate (default false). Higher write rates lower the threshold to flush bursts earlier,
+    /// and lower write rates raise it to flush fewer but larger SSTs.
+    pub adaptive_flush: bool,
+    /// Window to measure the recent write rate for adaptive flush (default 10s).
+    #[serde(with = "humantime_serde")]
+    pub adaptive_flush_window: Duration,
+    /// Write rate per second at which adaptive flush keeps the default threshold (default 8MB).
+    pub adaptive_flush_base_rate: ReadableSize,
+    /// Min percentage of the default threshold adaptive flush scales to (default 25).
+    pub adaptive_flush_min_percent: u32,
+    /// Max percentage of the default threshold adaptive flush scales to (default 150).
+    /// It must not exceed 200 as the default threshold is half of the global write buffer size.
+    pub adaptive_flush_max_percent: u32,
 
     // Cache configs:
     /// Cache size for SST metadata (default 128MB). Setting it to 0 to disable the cache.
@@ -70,16 +118,93 @@ pub struct MitoConfig {
     pub vector_cache_size: ReadableSize,
     /// Cache size for pages of SST row groups (default 512MB). Setting it to 0 to disable the cache.
     pub page_cache_size: ReadableSize,
+    /// Max number of open SST readers to cache (default 0), queries reuse cached readers
+    /// to read metadata and row groups instead of opening SSTs again. Reads of the same SST
+    /// by a cached reader are serialized. Setting it to 0 to disable the cache.
+    pub sst_reader_cache_size: usize,
+    /// Max number of (query, SST) pairs to remember row groups read by the query (default 0),
+    /// so the same query fetches these row groups in advance. Setting it to 0 to disable the cache.
+    pub row_group_hint_cache_size: usize,
     /// Whether to enable the experimental write cache.
     pub enable_experimental_write_cache: bool,
     /// Path for write cache.
     pub experimental_write_cache_path: String,
     /// Capacity for write cache.
     pub experimental_write_cache_size: ReadableSize,
+    /// Policy to evict uploaded files once the write cache exceeds its capacity (default lru).
+    pub experimental_write_cache_eviction_policy: WriteCacheEvictionPolicy,
+    /// Writes SSTs directly to the object store if the data to write is larger than the
+    /// threshold (default 0). Setting it to 0 to always write SSTs through the write cache.
+    pub experimental_write_cache_bypass_size: ReadableSize,
+    /// Compression of SSTs staged in the write cache (default sst). Staging SSTs with
+    /// another compression saves the local disk, but these SSTs are rewritten with their
+    /// own compression while uploading.
+    pub experimental_write_cache_staging_compression: StagingCompression,
+    /// Buffer size to stage SSTs in the write cache (default 0). Setting it to 0 to stage
+    /// SSTs with `sst_write_buffer_size`.
+    pub experimental_write_cache_write_buffer_size: ReadableSize,
+    /// Max number of concurrent operations on the local disk of the write cache (default 0).
+    /// Setting it to 0 to disable the limit.
+    pub experimental_write_cache_concurrency: usize,
 
     // Other configs:
     /// Buffer size for SST writing.
     pub sst_write_buffer_size: ReadableSize,
+    /// Version of the parquet format of SSTs (default default). `v1` only uses encodings
+    /// readers of parquet 1.0 support and `v2` writes data pages of version 2. `default`
+    /// writes parquet 1.0 but uses delta encodings of 2.0 for the time index and sequence.
+    pub sst_parquet_version: SstParquetVersion,
+    /// Compression codec of SSTs, "zstd", "snappy", "lz4" or "none" (default zstd).
+    pub sst_compression: SstCompression,
+    /// Level of the zstd codec of SSTs in `[1, 22]` (default 1). A higher level trades
+    /// CPU for smaller SSTs.
+    pub sst_zstd_level: i32,
+    /// Custom key-value metadata to write to fields and the time index of SSTs (default
+    /// empty), e.g. the source system of the data. Keys are stored with a `custom:` prefix.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub sst_column_metadata: HashMap<String, String>,
+    /// Encodings of fields and the time index of SSTs by column names (default empty),
+    /// overriding the default encodings. Tags are encoded into the primary key so their
+    /// encodings are ignored.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub sst_column_encodings: HashMap<String, SstEncoding>,
+    /// Layout of SSTs under the directory of new regions (default flat). The layout is
+    /// persisted in the region manifest so regions keep the layout they are created with.
+    pub sst_path_layout: SstPathLayout,
+    /// Name of the storage to mirror SSTs written by regions to (default empty), e.g. for
+    /// a standby node. Setting it to empty to disable mirroring.
+    pub sst_mirror_storage: String,
+    /// Whether to verify checksums of SST footers before reading SSTs (default false).
+    /// Footers cached in memory are not verified again.
+    pub verify_sst_checksum: bool,
+    /// Number of row groups of a SST to fetch in background while scanning the current
+    /// row group (default 0). Row groups are not fetched in advance from the local file
+    /// system. Setting it to 0 to disable prefetching.
+    pub sst_prefetch_row_groups: usize,
+    /// Target size of SSTs output by compaction (default 0). Compaction rolls over to a new
+    /// SST once the output reaches the target. Setting it to 0 to disable the target.
+    pub compaction_target_file_size: ReadableSize,
+    /// Target size of SSTs output by flush (default 0). Flush rolls over to a new SST once
+    /// the output reaches the target. Setting it to 0 to disable the target.
+    pub flush_target_file_size: ReadableSize,
+    /// Target number of rows of SSTs output by flush (default 0). Flush rolls over to a new
+    /// SST once the output has the target number of rows. Setting it to 0 to disable the target.
+    pub flush_target_file_rows: usize,
+    /// Max number of retries of deletes and reads of SSTs failing with temporary errors
+    /// (default 0), e.g. throttling of the object store. Setting it to 0 to disable retries.
+    ///
+    /// These retries stack on retries of the object store, e.g. the `RetryLayer` of remote
+    /// object stores in the datanode, see [RetryConfig].
+    pub sst_retry_max_retries: usize,
+    /// Delay before the first retry of a SST operation (default 100ms), the delay
+    /// doubles on each retry.
+    #[serde(with = "humantime_serde")]
+    pub sst_retry_base_delay: Duration,
+    /// Max delay between retries of a SST operation (default 10s).
+    #[serde(with = "humantime_serde")]
+    pub sst_retry_max_delay: Duration,
+    /// Whether to randomize delays between retries of SST operations (default true).
+    pub sst_retry_jitter: bool,
     /// Parallelism to scan a region (default: 1/4 of cpu cores).
     /// - 0: using the default value (1/4 of cpu cores).
     /// - 1: scan in current thread.
@@ -89,6 +214,176 @@ pub struct MitoConfig {
     pub parallel_scan_channel_size: usize,
     /// Whether to allow stale entries read during replay.
     pub allow_stale_entries: bool,
+
+    // Index configs:
+    /// Buffer size to write intermediate files while creating indexes (default 8KiB).
+    /// A larger buffer flushes files less often. Setting it to 0 disables the buffer.
+    pub index_intermediate_write_buffer_size: ReadableSize,
+    /// Where to store intermediate files while creating indexes (default object_store).
+    pub index_intermediate_backend: IntermediateBackend,
+    /// Local directory of intermediate files if the backend is `local_fs`.
+    pub index_intermediate_path: String,
+    /// Max number of concurrent operations on the local directory of intermediate files
+    /// if the backend is `local_fs` (default 0). Setting it to 0 disables the limit.
+    pub index_intermediate_concurrency: usize,
+    /// Timeout of each attempt to open, read, write, list or remove intermediate files
+    /// (default 30s). Setting it to 0 disables the timeout.
+    #[serde(with = "humantime_serde")]
+    pub index_intermediate_op_timeout: Duration,
+    /// Max number of retries of an intermediate file operation that timed out or
+    /// failed temporarily (default 3). Writes of an opened file are not retried.
+    pub index_intermediate_op_max_retries: usize,
+    /// Max bytes of intermediate files of an index creation (default 0). The index creation
+    /// fails if its intermediate files exceed the limit. Setting it to 0 disables the limit.
+    pub index_intermediate_max_bytes: ReadableSize,
+    /// Number of intermediate files to open concurrently while merging them (default 8).
+    /// Sets to 0 to use the default value.
+    pub index_intermediate_open_concurrency: usize,
+    /// Max number of index builds running concurrently in the node (default: 1/4 of cpu cores).
+    /// Sets to 0 to use the default value.
+    pub max_concurrent_index_builds: usize,
+}
+
+/// Storage of intermediate files while creating indexes.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntermediateBackend {
+    /// The object store of the region.
+    #[default]
+    ObjectStore,
+    /// A local directory, e.g. on a tmpfs.
+    LocalFs,
+}
+
+/// Policy to evict files from the write cache.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteCacheEvictionPolicy {
+    /// Evicts the least recently accessed file first.
+    #[default]
+    Lru,
+    /// Evicts the earliest uploaded file first, accesses don't change the order.
+    Fifo,
+}
+
+/// Compression of SSTs staged in the write cache.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StagingCompression {
+    /// Stages SSTs with their own compression.
+    #[default]
+    Sst,
+    /// Zstd with the default level.
+    Zstd,
+    Snappy,
+    Lz4,
+    /// Doesn't compress staged SSTs.
+    None,
+}
+
+impl StagingCompression {
+    /// Returns the compression to stage SSTs, `None` if SSTs are staged with their
+    /// own compression.
+    pub(crate) fn compression(&self) -> Option<Compression> {
+        match self {
+            StagingCompression::Sst => None,
+            StagingCompression::Zstd => Some(Compression::default()),
+            StagingCompression::Snappy => Some(Compression::Snappy),
+            StagingCompression::Lz4 => Some(Compression::Lz4),
+            StagingCompression::None => Some(Compression::None),
+        }
+    }
+}
+
+/// Compression codec of SSTs.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SstCompression {
+    /// Zstd with the level of `sst_zstd_level`.
+    #[default]
+    Zstd,
+    Snappy,
+    Lz4,
+    /// Doesn't compress SSTs.
+    None,
+}
+
+/// Encoding of a column of SSTs.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SstEncoding {
+    Plain,
+    /// Dictionary encoding, enables the dictionary of the column.
+    Dictionary,
+    Rle,
+    /// Delta encodings and the byte stream split encoding are defined in the parquet
+    /// format version 2.0, they can't be used with the version `v1`.
+    DeltaBinaryPacked,
+    DeltaLengthByteArray,
+    DeltaByteArray,
+    ByteStreamSplit,
+}
+
+impl SstEncoding {
+    /// Returns the encoding of the parquet writer.
+    pub(crate) fn encoding(&self) -> Encoding {
+        match self {
+            SstEncoding::Plain => Encoding::PLAIN,
+            SstEncoding::Dictionary => Encoding::RLE_DICTIONARY,
+            SstEncoding::Rle => Encoding::RLE,
+            SstEncoding::DeltaBinaryPacked => Encoding::DELTA_BINARY_PACKED,
+            SstEncoding::DeltaLengthByteArray => Encoding::DELTA_LENGTH_BYTE_ARRAY,
+            SstEncoding::DeltaByteArray => Encoding::DELTA_BYTE_ARRAY,
+            SstEncoding::ByteStreamSplit => Encoding::BYTE_STREAM_SPLIT,
+        }
+    }
+}
+
+/// Version of the parquet format of SSTs.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SstParquetVersion {
+    /// Parquet 1.0 with delta encodings of 2.0 for the time index and sequence.
+    #[default]
+    Default,
+    /// Parquet 1.0.
+    V1,
+    /// Parquet 2.0.
+    V2,
+}
+
+impl SstParquetVersion {
+    /// Returns the version of the parquet writer, `None` to use the default version.
+    pub(crate) fn parquet_version(&self) -> Option<ParquetVersion> {
+        match self {
+            SstParquetVersion::Default => None,
+            SstParquetVersion::V1 => Some(ParquetVersion::V1),
+            SstParquetVersion::V2 => Some(ParquetVersion::V2),
+        }
+    }
+}
+
+/// Layout of SSTs under the region directory.
+///
+/// New variants must not be removed or renamed as the layout is persisted in the manifest.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SstPathLayout {
+    /// Puts all SSTs directly under the region directory.
+    #[default]
+    Flat,
+    /// Groups SSTs by the date they are created, e.g. `{region_dir}/2024/01/31/`.
+    Date,
+}
+
+impl SstPathLayout {
+    /// Returns the strategy to derive paths of SSTs in this layout.
+    pub(crate) fn path_strategy(&self) -> PathStrategyRef {
+        match self {
+            SstPathLayout::Flat => Arc::new(FlatPath),
+            SstPathLayout::Date => Arc::new(DatePartitionedPath),
+        }
+    }
 }
 
 impl Default for MitoConfig {
@@ -97,27 +392,141 @@ impl Default for MitoConfig {
             num_workers: divide_num_cpus(2),
             worker_channel_size: 128,
             worker_request_batch_size: 64,
+            write_coalesce_window: Duration::ZERO,
+            write_coalesce_max_rows: 16384,
+            max_open_regions: 0,
             manifest_checkpoint_distance: 10,
             compress_manifest: false,
+            list_page_size: 0,
             max_background_jobs: DEFAULT_MAX_BG_JOB,
+            max_concurrent_compactions: divide_num_cpus(4),
             auto_flush_interval: Duration::from_secs(30 * 60),
             global_write_buffer_size: ReadableSize::gb(1),
             global_write_buffer_reject_size: ReadableSize::gb(2),
+            adaptive_flush: false,
+            adaptive_flush_window: Duration::from_secs(10),
+            adaptive_flush_base_rate: ReadableSize::mb(8),
+            adaptive_flush_min_percent: 25,
+            adaptive_flush_max_percent: 150,
             sst_meta_cache_size: ReadableSize::mb(128),
             vector_cache_size: ReadableSize::mb(512),
             page_cache_size: ReadableSize::mb(512),
+            sst_reader_cache_size: 0,
+            row_group_hint_cache_size: 0,
             enable_experimental_write_cache: false,
             experimental_write_cache_path: String::new(),
             experimental_write_cache_size: ReadableSize::mb(512),
+            experimental_write_cache_eviction_policy: WriteCacheEvictionPolicy::default(),
+            experimental_write_cache_bypass_size: ReadableSize(0),
+            experimental_write_cache_staging_compression: StagingCompression::default(),
+            experimental_write_cache_write_buffer_size: ReadableSize(0),
+            experimental_write_cache_concurrency: 0,
             sst_write_buffer_size: ReadableSize::mb(8),
+            sst_parquet_version: SstParquetVersion::default(),
+            sst_compression: SstCompression::default(),
+            sst_zstd_level: DEFAULT_SST_ZSTD_LEVEL,
+            sst_column_metadata: HashMap::new(),
+            sst_column_encodings: HashMap::new(),
+            sst_path_layout: SstPathLayout::default(),
+            sst_mirror_storage: String::new(),
+            verify_sst_checksum: false,
+            sst_prefetch_row_groups: 0,
+            compaction_target_file_size: ReadableSize(0),
+            flush_target_file_size: ReadableSize(0),
+            flush_target_file_rows: 0,
+            sst_retry_max_retries: 0,
+            sst_retry_base_delay: Duration::from_millis(100),
+            sst_retry_max_delay: Duration::from_secs(10),
+            sst_retry_jitter: true,
             scan_parallelism: divide_num_cpus(4),
             parallel_scan_channel_size: DEFAULT_SCAN_CHANNEL_SIZE,
             allow_stale_entries: false,
+            index_intermediate_write_buffer_size: ReadableSize::kb(8),
+            index_intermediate_backend: IntermediateBackend::ObjectStore,
+            index_intermediate_path: String::new(),
+            index_intermediate_concurrency: 0,
+            index_intermediate_op_timeout: Duration::from_secs(30),
+            index_intermediate_op_max_retries: 3,
+            index_intermediate_max_bytes: ReadableSize(0),
+            index_intermediate_open_concurrency: 8,
+            max_concurrent_index_builds: divide_num_cpus(4),
         }
     }
 }
 
 impl MitoConfig {
+    /// Returns the backoff to retry operations on SSTs, `None` if retries are disabled.
+    pub(crate) fn sst_retry_config(&self) -> Option<RetryConfig> {
+        (self.sst_retry_max_retries > 0).then_some(RetryConfig {
+            max_retries: self.sst_retry_max_retries,
+            base_delay: self.sst_retry_base_delay,
+            max_delay: self.sst_retry_max_delay,
+            jitter: self.sst_retry_jitter,
+        })
+    }
+
+    /// Returns options to write SSTs of the region with `metadata`, callers set targets
+    /// of their outputs.
+    pub(crate) fn sst_write_options(&self, metadata: &RegionMetadata) -> WriteOptions {
+        WriteOptions {
+            write_buffer_size: self.sst_write_buffer_size,
+            parquet_version: self.sst_parquet_version.parquet_version(),
+            compression: self.sst_compression(),
+            column_encodings: self.sst_column_encodings(metadata),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the compression of SSTs, the zstd level is validated by [MitoConfig::sanitize()].
+    fn sst_compression(&self) -> Compression {
+        match self.sst_compression {
+            SstCompression::Zstd => Compression::zstd(self.sst_zstd_level).unwrap_or_default(),
+            SstCompression::Snappy => Compression::Snappy,
+            SstCompression::Lz4 => Compression::Lz4,
+            SstCompression::None => Compression::None,
+        }
+    }
+
+    /// Returns encodings of columns of SSTs of the region with `metadata`, columns not in
+    /// the region are ignored.
+    fn sst_column_encodings(&self, metadata: &RegionMetadata) -> HashMap<ColumnId, Encoding> {
+        if self.sst_column_encodings.is_empty() {
+            return HashMap::new();
+        }
+
+        // Tags are encoded into the primary key so they can't have encodings.
+        metadata
+            .column_metadatas
+            .iter()
+            .filter(|column| column.semantic_type != SemanticType::Tag)
+            .filter_map(|column| {
+                self.sst_column_encodings
+                    .get(&column.column_schema.name)
+                    .map(|encoding| (column.column_id, encoding.encoding()))
+            })
+            .collect()
+    }
+
+    /// Returns custom metadata of columns to write to SSTs of the region with `metadata`.
+    pub(crate) fn sst_column_metadata(&self, metadata: &RegionMetadata) -> ColumnKeyValues {
+        if self.sst_column_metadata.is_empty() {
+            return ColumnKeyValues::new();
+        }
+
+        // Tags are encoded into the primary key so they can't have metadata.
+        metadata
+            .column_metadatas
+            .iter()
+            .filter(|column| column.semantic_type != SemanticType::Tag)
+            .map(|column| {
+                (
+                    column.column_schema.name.clone(),
+                    self.sst_column_metadata.clone(),
+                )
+            })
+            .collect()
+    }
+
     /// Sanitize incorrect configurations.
     ///
     /// Returns an error if there is a configuration that unable to sanitize.
@@ -138,6 +547,16 @@ impl MitoConfig {
             self.max_background_jobs = DEFAULT_MAX_BG_JOB;
         }
 
+        // Use default value if `max_concurrent_compactions` is 0.
+        if self.max_concurrent_compactions == 0 {
+            self.max_concurrent_compactions = divide_num_cpus(4);
+        }
+
+        // Use default value if `max_concurrent_index_builds` is 0.
+        if self.max_concurrent_index_builds == 0 {
+            self.max_concurrent_index_builds = divide_num_cpus(4);
+        }
+
         if self.global_write_buffer_reject_size <= self.global_write_buffer_size {
             self.global_write_buffer_reject_size = self.global_write_buffer_size * 2;
             warn!(
@@ -146,6 +565,39 @@ impl MitoConfig {
             );
         }
 
+        if self.adaptive_flush_window.is_zero() {
+            self.adaptive_flush_window = Duration::from_secs(10);
+            warn!(
+                "Sanitize adaptive flush window to {:?}",
+                self.adaptive_flush_window
+            );
+        }
+        if self.adaptive_flush_max_percent > MAX_ADAPTIVE_FLUSH_PERCENT {
+            self.adaptive_flush_max_percent = MAX_ADAPTIVE_FLUSH_PERCENT;
+            warn!(
+                "Sanitize adaptive flush max percent to {}",
+                self.adaptive_flush_max_percent
+            );
+        }
+        if self.adaptive_flush {
+            ensure!(
+                self.adaptive_flush_base_rate.as_bytes() > 0,
+                InvalidConfigSnafu {
+                    reason: "adaptive_flush_base_rate should be greater than 0",
+                }
+            );
+            ensure!(
+                self.adaptive_flush_min_percent > 0
+                    && self.adaptive_flush_min_percent <= self.adaptive_flush_max_percent,
+                InvalidConfigSnafu {
+                    reason: format!(
+                        "adaptive_flush_min_percent should be in [1, {}], given: {}",
+                        self.adaptive_flush_max_percent, self.adaptive_flush_min_percent,
+                    ),
+                }
+            );
+        }
+
         if self.sst_write_buffer_size < MULTIPART_UPLOAD_MINIMUM_SIZE {
             self.sst_write_buffer_size = MULTIPART_UPLOAD_MINIMUM_SIZE;
             warn!(
@@ -154,6 +606,33 @@ impl MitoConfig {
             );
         }
 
+        if self.sst_compression == SstCompression::Zstd {
+            ensure!(
+                Compression::zstd(self.sst_zstd_level).is_ok(),
+                InvalidConfigSnafu {
+                    reason: format!(
+                        "sst_zstd_level should be in [1, 22], given: {}",
+                        self.sst_zstd_level
+                    ),
+                }
+            );
+        }
+
+        if self.sst_parquet_version == SstParquetVersion::V1 {
+            if let Some((name, encoding)) = self
+                .sst_column_encodings
+                .iter()
+                .find(|(_, encoding)| requires_parquet_v2(encoding.encoding()))
+            {
+                return InvalidConfigSnafu {
+                    reason: format!(
+                        "sst_column_encodings of column {name} can't be {encoding:?} with sst_parquet_version v1"
+                    ),
+                }
+                .fail();
+            }
+        }
+
         // Use default value if `scan_parallelism` is 0.
         if self.scan_parallelism == 0 {
             self.scan_parallelism = divide_num_cpus(4);
@@ -176,6 +655,15 @@ impl MitoConfig {
             );
         }
 
+        if self.index_intermediate_backend == IntermediateBackend::LocalFs {
+            ensure!(
+                !self.index_intermediate_path.is_empty(),
+                InvalidConfigSnafu {
+                    reason: "index_intermediate_path should not be empty",
+                }
+            );
+        }
+
         Ok(())
     }
 }
@@ -27,12 +27,24 @@ mod compaction_test;
 #[cfg(test)]
 mod create_test;
 #[cfg(test)]
+mod delete_range_test;
+#[cfg(test)]
 mod drop_test;
 #[cfg(test)]
+mod duplicate_test;
+#[cfg(test)]
 mod flush_test;
+#[cfg(test)]
+mod follow_test;
+#[cfg(test)]
+mod freeze_test;
+#[cfg(test)]
+mod index_test;
 #[cfg(any(test, feature = "test"))]
 pub mod listener;
 #[cfg(test)]
+mod open_limit_test;
+#[cfg(test)]
 mod open_test;
 #[cfg(test)]
 mod parallel_test;
@@ -41,30 +53,63 @@ mod projection_test;
 #[cfg(test)]
 mod prune_test;
 #[cfg(test)]
+mod sample_test;
+#[cfg(test)]
+mod series_limit_test;
+#[cfg(test)]
 mod set_readonly_test;
 #[cfg(test)]
+mod sync_test;
+#[cfg(test)]
 mod truncate_test;
+#[cfg(test)]
+mod ttl_test;
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use api::helper::{pb_value_to_value_ref, value_to_grpc_value};
+use api::v1::{ColumnSchema, OpType, Row, Rows};
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_recordbatch::SendableRecordBatchStream;
+use common_telemetry::{info, warn};
+use common_time::Timestamp;
+use datafusion_expr::{col, lit};
+use datatypes::value::{timestamp_to_scalar_value, Value, ValueRef};
+use futures::TryStreamExt;
 use object_store::manager::ObjectStoreManagerRef;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::index_statistics::{self, IndexStatistics, IndexStatisticsProvider};
 use store_api::logstore::LogStore;
-use store_api::metadata::RegionMetadataRef;
+use store_api::metadata::{ColumnMetadata, RegionMetadata, RegionMetadataRef};
 use store_api::region_engine::{RegionEngine, RegionRole, SetReadonlyResponse};
-use store_api::region_request::{AffectedRows, RegionRequest};
+use store_api::region_request::{
+    AffectedRows, RegionCloseRequest, RegionDeleteRangeRequest, RegionDeleteRequest,
+    RegionFlushRequest, RegionPutRequest, RegionRequest,
+};
 use store_api::storage::{RegionId, ScanRequest};
+use tokio::sync::{oneshot, OwnedRwLockReadGuard};
 
 use crate::config::MitoConfig;
-use crate::error::{RecvSnafu, RegionNotFoundSnafu, Result};
-use crate::metrics::HANDLE_REQUEST_ELAPSED;
+use crate::error::{
+    DuplicateRowsSnafu, InvalidRequestSnafu, RecvSnafu, RegionNotFoundSnafu, RegionReadonlySnafu,
+    Result, ScanDeleteRangeSnafu, ScanDuplicateRowsSnafu,
+};
+use crate::manifest::action::{RegionFreeze, RegionMetaAction, RegionMetaActionList};
+use crate::metrics::{DELETE_ROWS_REWRITTEN_TOTAL, HANDLE_REQUEST_ELAPSED};
+use crate::read::follow::FollowScan;
 use crate::read::scan_region::{ScanParallism, ScanRegion, Scanner};
-use crate::region::RegionUsage;
-use crate::request::WorkerRequest;
+use crate::region::open_limiter::{EvictedRegion, OpenRegionLimiter, RegionOpenOptions};
+use crate::region::options::DuplicateMode;
+use crate::region::{MitoRegionRef, RegionUsage};
+use crate::request::{
+    column_to_schema, BackgroundNotify, IndexBuildFinished, WorkerRequest, WriteRequest,
+};
+use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
+use crate::sst::index::explain::FileIndexExplain;
+use crate::wal::EntryId;
 use crate::worker::WorkerGroup;
 
 pub const MITO_ENGINE_NAME: &str = "mito";
@@ -84,9 +129,9 @@ impl MitoEngine {
     ) -> Result<MitoEngine> {
         config.sanitize()?;
 
-        Ok(MitoEngine {
-            inner: Arc::new(EngineInner::new(config, log_store, object_store_manager).await?),
-        })
+        let inner = Arc::new(EngineInner::new(config, log_store, object_store_manager).await?);
+        index_statistics::register_provider(Arc::downgrade(&inner) as _);
+        Ok(MitoEngine { inner })
     }
 
     /// Returns true if the specific region exists.
@@ -105,15 +150,140 @@ impl MitoEngine {
         Ok(region.region_usage().await)
     }
 
+    /// Returns the manifest of the region in json, including its SSTs and the recent edits.
+    pub async fn region_manifest_json(&self, region_id: RegionId) -> Result<String> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+
+        region.manifest_manager.snapshot().await?.to_json()
+    }
+
+    /// Pauses compactions of the region, or all regions if `region_id` is `None`.
+    ///
+    /// Running compactions are allowed to finish.
+    pub fn pause_compaction(&self, region_id: Option<RegionId>) {
+        self.inner.workers.pause_compaction(region_id);
+    }
+
+    /// Resumes compactions of the region, or all regions if `region_id` is `None`.
+    ///
+    /// Resumed regions are scheduled to compact in background.
+    pub async fn resume_compaction(&self, region_id: Option<RegionId>) -> Result<()> {
+        self.inner.workers.resume_compaction(region_id).await
+    }
+
+    /// Explains how indexes of SSTs in the region prune row groups for the `request`.
+    ///
+    /// SSTs without indexes are fully scanned.
+    pub async fn explain_index(
+        &self,
+        region_id: RegionId,
+        request: ScanRequest,
+    ) -> Result<Vec<FileIndexExplain>> {
+        self.scanner(region_id, request)?.explain_index().await
+    }
+
+    /// Counts rows of the region for the `request` from row counts in the metadata of
+    /// SSTs and rows in memtables, without reading data of SSTs, e.g. `SELECT COUNT(*)`.
+    ///
+    /// Only filters on the time index are supported. Returns `None` if rows must be
+    /// scanned to count them, e.g. the request has other filters or SSTs may have
+    /// duplicate or deleted rows.
+    pub async fn count_rows(
+        &self,
+        region_id: RegionId,
+        request: ScanRequest,
+    ) -> Result<Option<u64>> {
+        self.scanner(region_id, request)?
+            .count_rows_by_metadata()
+            .await
+    }
+
+    /// Returns the min and max values of the time index or a field `column` of the region
+    /// for the `request` from statistics of SSTs and rows in memtables, without reading
+    /// data of SSTs, e.g. `SELECT max(ts)`. Both values are null if all values are null.
+    ///
+    /// Only filters on the time index are supported. Returns `None` if rows must be
+    /// scanned to find the values, e.g. the request has other filters or SSTs may have
+    /// overwritten or deleted rows.
+    pub async fn min_max(
+        &self,
+        region_id: RegionId,
+        column: &str,
+        request: ScanRequest,
+    ) -> Result<Option<(Value, Value)>> {
+        self.scanner(region_id, request)?
+            .min_max_by_metadata(column)
+            .await
+    }
+
     /// Returns a scanner to scan for `request`.
     fn scanner(&self, region_id: RegionId, request: ScanRequest) -> Result<Scanner> {
         self.inner.handle_query(region_id, request)
     }
 
+    /// Sets the max number of series in the region, `None` removes the limit.
+    ///
+    /// Existing series of the region still accept writes if they exceed the new limit.
+    pub fn set_series_limit(&self, region_id: RegionId, limit: Option<usize>) -> Result<()> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        region.series_limiter.set_limit(limit);
+        Ok(())
+    }
+
+    /// Builds indexes of existing SSTs in the region from their rows without rewriting
+    /// the SSTs. Returns the number of SSTs with new indexes.
+    ///
+    /// SSTs that already have indexes are skipped unless `overwrite` is true, so building
+    /// indexes of a region again only builds the missing ones.
+    pub async fn build_index(&self, region_id: RegionId, overwrite: bool) -> Result<usize> {
+        self.inner.build_index(region_id, overwrite).await
+    }
+
+    /// Forces the WAL to be persisted and returns the id of the last durable entry
+    /// of the region. Returns 0 if nothing has been written to the region.
+    ///
+    /// Syncing a region again without new writes returns the same entry id.
+    pub async fn sync_region(&self, region_id: RegionId) -> Result<EntryId> {
+        self.inner.sync_region(region_id).await
+    }
+
+    /// Closes the least recently used regions in background if more regions than the
+    /// limit are open.
+    fn close_idle_regions(&self) {
+        if !self.inner.open_limiter.start_evicting() {
+            return;
+        }
+
+        let inner = self.inner.clone();
+        common_runtime::spawn_bg(async move {
+            loop {
+                let num_closed = inner.close_idle_regions().await;
+                inner.open_limiter.finish_evicting();
+                // Regions opened while closing may exceed the limit again.
+                if num_closed == 0 || !inner.open_limiter.start_evicting() {
+                    break;
+                }
+            }
+        });
+    }
+
     #[cfg(test)]
     pub(crate) fn get_region(&self, id: RegionId) -> Option<crate::region::MitoRegionRef> {
         self.inner.workers.get_region(id)
     }
+
+    #[cfg(test)]
+    pub(crate) fn is_region_evicted(&self, id: RegionId) -> bool {
+        self.inner.open_limiter.evicted(id).is_some()
+    }
 }
 
 /// Inner struct of [MitoEngine].
@@ -122,6 +292,8 @@ struct EngineInner {
     workers: WorkerGroup,
     /// Config of the engine.
     config: Arc<MitoConfig>,
+    /// Limiter of open regions in the node.
+    open_limiter: OpenRegionLimiter,
 }
 
 impl EngineInner {
@@ -134,6 +306,7 @@ impl EngineInner {
         let config = Arc::new(config);
         Ok(EngineInner {
             workers: WorkerGroup::start(config.clone(), log_store, object_store_manager).await?,
+            open_limiter: OpenRegionLimiter::new(config.max_open_regions),
             config,
         })
     }
@@ -165,12 +338,494 @@ impl EngineInner {
             .with_label_values(&[request.type_name()])
             .start_timer();
 
+        let open_options = match &request {
+            RegionRequest::Create(request) => Some(RegionOpenOptions {
+                region_dir: request.region_dir.clone(),
+                options: request.options.clone(),
+            }),
+            RegionRequest::Open(request) => Some(RegionOpenOptions {
+                region_dir: request.region_dir.clone(),
+                options: request.options.clone(),
+            }),
+            _ => None,
+        };
+        let is_close = matches!(request, RegionRequest::Close(_) | RegionRequest::Drop(_));
+        let _guard = match &request {
+            RegionRequest::Create(_) | RegionRequest::Open(_) => None,
+            RegionRequest::Close(_) if self.forget_evicted_region(region_id).await => {
+                // The limiter has already closed the region.
+                return Ok(0);
+            }
+            _ => self.access_region(region_id).await?,
+        };
+        let rows = match request {
+            RegionRequest::DeleteRange(request) => self.delete_range(region_id, request).await?,
+            RegionRequest::Put(request) if self.rejects_duplicates(region_id) => {
+                self.put_rejecting_duplicates(region_id, request).await?
+            }
+            RegionRequest::Sync(_) => {
+                self.sync_region(region_id).await?;
+                0
+            }
+            request => self.submit_request(region_id, request).await?,
+        };
+        if is_close {
+            self.open_limiter.on_closed(region_id);
+        } else if let Some(open_options) = open_options {
+            self.open_limiter.on_opened(region_id, open_options);
+        }
+        Ok(rows)
+    }
+
+    /// Reopens the region if the open region limiter closed it and marks the region
+    /// as just accessed.
+    ///
+    /// Returns a guard that prevents the limiter from closing the region until it's
+    /// dropped, or `None` if the limiter doesn't track the region.
+    async fn access_region(&self, region_id: RegionId) -> Result<Option<OwnedRwLockReadGuard<()>>> {
+        loop {
+            let Some(lock) = self.open_limiter.region_lock(region_id) else {
+                return Ok(None);
+            };
+            let guard = lock.clone().read_owned().await;
+            if self.open_limiter.evicted(region_id).is_none() {
+                self.open_limiter.touch(region_id);
+                return Ok(Some(guard));
+            }
+            drop(guard);
+
+            let _guard = lock.write_owned().await;
+            // Others may reopen or close the region while we are waiting for the lock.
+            if let Some((open_options, evicted)) = self.open_limiter.evicted_options(region_id) {
+                self.reopen_region(region_id, open_options, evicted).await?;
+            }
+        }
+    }
+
+    /// Opens the region closed by the limiter again, replaying its WAL.
+    async fn reopen_region(
+        &self,
+        region_id: RegionId,
+        open_options: RegionOpenOptions,
+        evicted: EvictedRegion,
+    ) -> Result<()> {
+        self.submit_request(
+            region_id,
+            RegionRequest::Open(open_options.to_open_request()),
+        )
+        .await?;
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        region.set_writable(evicted.writable);
+        self.open_limiter.on_reopened(region_id);
+
+        info!(
+            "Reopened region {} closed by the open region limit, writable: {}",
+            region_id, evicted.writable
+        );
+        Ok(())
+    }
+
+    /// Forgets the region if the limiter has closed it, returns false if the region
+    /// is open.
+    async fn forget_evicted_region(&self, region_id: RegionId) -> bool {
+        let Some(lock) = self.open_limiter.region_lock(region_id) else {
+            return false;
+        };
+        let _guard = lock.write_owned().await;
+        if self.open_limiter.evicted(region_id).is_none() {
+            return false;
+        }
+        self.open_limiter.on_closed(region_id);
+        true
+    }
+
+    /// Closes the least recently used regions until open regions don't exceed the
+    /// limit. Regions being accessed are skipped.
+    ///
+    /// Closed regions are still reported with their roles, so they keep their leases
+    /// until they are reopened on the next access. Returns the number of closed regions.
+    async fn close_idle_regions(&self) -> usize {
+        let mut num_to_close = self.open_limiter.num_exceeded();
+        let mut num_closed = 0;
+        for (region_id, tick) in self.open_limiter.idle_regions() {
+            if num_to_close == 0 {
+                break;
+            }
+            let Some(lock) = self.open_limiter.region_lock(region_id) else {
+                continue;
+            };
+            let Ok(_guard) = lock.try_write_owned() else {
+                continue;
+            };
+            // The region may be accessed after we picked it.
+            if !self.open_limiter.is_idle_since(region_id, tick) {
+                continue;
+            }
+            let Some(region) = self.workers.get_region(region_id) else {
+                // The region is closed by others.
+                self.open_limiter.on_closed(region_id);
+                num_to_close -= 1;
+                continue;
+            };
+
+            match self.close_idle_region(&region).await {
+                Ok(()) => {
+                    num_to_close -= 1;
+                    num_closed += 1;
+                }
+                Err(e) => {
+                    warn!(e; "Failed to close idle region {}", region_id);
+                }
+            }
+        }
+        num_closed
+    }
+
+    /// Flushes the memtables of the region and closes it.
+    ///
+    /// Followers can't flush their memtables, their rows are replayed from the WAL after
+    /// reopening.
+    async fn close_idle_region(&self, region: &MitoRegionRef) -> Result<()> {
+        let region_id = region.region_id;
+        let writable = region.is_writable();
+        if writable {
+            self.submit_request(
+                region_id,
+                RegionRequest::Flush(RegionFlushRequest {
+                    row_group_size: None,
+                }),
+            )
+            .await?;
+        }
+        let disk_usage = region.region_usage().await.disk_usage();
+        // Marks the region as evicted before closing it so the region is always reported.
+        self.open_limiter.on_evicted(
+            region_id,
+            EvictedRegion {
+                writable,
+                disk_usage,
+            },
+        );
+        if let Err(e) = self
+            .submit_request(region_id, RegionRequest::Close(RegionCloseRequest {}))
+            .await
+        {
+            self.open_limiter.on_reopened(region_id);
+            return Err(e);
+        }
+
+        info!("Region {} closed by the open region limit", region_id);
+        Ok(())
+    }
+
+    /// Submits the [RegionRequest] to the region worker and waits for the result.
+    async fn submit_request(
+        &self,
+        region_id: RegionId,
+        request: RegionRequest,
+    ) -> Result<AffectedRows> {
         let (request, receiver) = WorkerRequest::try_from_region_request(region_id, request)?;
         self.workers.submit_to_worker(region_id, request).await?;
 
         receiver.await.context(RecvSnafu)?
     }
 
+    /// Syncs the WAL and returns the id of the last durable entry of the region.
+    ///
+    /// The region worker only reads the last entry id of the region, the WAL is synced
+    /// off the worker loop.
+    async fn sync_region(&self, region_id: RegionId) -> Result<EntryId> {
+        let (request, receiver) = WorkerRequest::new_sync(region_id);
+        self.workers.submit_to_worker(region_id, request).await?;
+
+        receiver.await.context(RecvSnafu)?
+    }
+
+    /// Builds indexes of SSTs in the region and submits metas of the SSTs to the
+    /// region worker.
+    async fn build_index(&self, region_id: RegionId, overwrite: bool) -> Result<usize> {
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        ensure!(region.is_writable(), RegionReadonlySnafu { region_id });
+
+        let version = region.version();
+        let cache_manager = Some(self.workers.cache_manager());
+        // Indexes are built under new names, we remove the new index files on failure.
+        let mut file_metas = Vec::new();
+        let mut result = Ok(());
+        for file in version.ssts.levels().iter().flat_map(|level| level.files()) {
+            let has_index = file.meta().inverted_index_available();
+            if has_index && !overwrite {
+                continue;
+            }
+            match region
+                .access_layer
+                .build_index(
+                    file.clone(),
+                    version.options.index_columns.as_deref(),
+                    cache_manager.clone(),
+                )
+                .await
+            {
+                Ok(Some(file_meta)) => file_metas.push(file_meta),
+                Ok(None) => (),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        let result = match result {
+            Ok(()) if file_metas.is_empty() => return Ok(0),
+            Ok(()) => {
+                let (sender, receiver) = oneshot::channel();
+                let request = WorkerRequest::Background {
+                    region_id,
+                    notify: BackgroundNotify::IndexBuildFinished(IndexBuildFinished {
+                        region_id,
+                        file_metas: file_metas.clone(),
+                        sender: sender.into(),
+                    }),
+                };
+                self.workers
+                    .submit_to_worker(region_id, request)
+                    .await
+                    .map(|()| receiver)
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            // The worker removes new index files it doesn't commit.
+            Ok(receiver) => receiver.await.context(RecvSnafu)?,
+            Err(e) => {
+                for file_meta in &file_metas {
+                    if let Err(e) = region.access_layer.delete_index(file_meta).await {
+                        warn!(
+                            e; "Failed to remove index file, region_id: {}, file_id: {}",
+                            region_id, file_meta.file_id
+                        );
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Handles the [RegionDeleteRangeRequest].
+    ///
+    /// The region worker removes SSTs fully covered by the range first, then the remaining
+    /// rows in the range are deleted row by row. Returns the number of rows deleted row by
+    /// row as the SSTs don't track their row numbers.
+    async fn delete_range(
+        &self,
+        region_id: RegionId,
+        request: RegionDeleteRangeRequest,
+    ) -> Result<AffectedRows> {
+        let (start, end) = (request.start, request.end);
+        ensure!(
+            start < end,
+            InvalidRequestSnafu {
+                region_id,
+                reason: format!(
+                    "invalid delete range [{}, {})",
+                    start.to_iso8601_string(),
+                    end.to_iso8601_string()
+                ),
+            }
+        );
+
+        // Fast path: drops the SSTs in the range.
+        self.submit_request(region_id, RegionRequest::DeleteRange(request))
+            .await?;
+
+        // Slow path: reads keys of the remaining rows in the range and deletes them in
+        // chunks of at most `write_coalesce_max_rows` rows while streaming the scan, so
+        // the memory usage doesn't grow with the number of rows in the range.
+        let metadata = self.get_metadata(region_id)?;
+        let key_columns = key_columns(&metadata);
+        let schema: Vec<_> = key_columns
+            .iter()
+            .map(|column| column_to_schema(column))
+            .collect::<Result<_>>()?;
+        let chunk_size = self.config.write_coalesce_max_rows.max(1);
+        // We still need to check the timestamp of each row.
+        let mut stream = self.scan_keys(&metadata, start, end).await?;
+
+        let ts_index = key_columns.len() - 1;
+        let mut rows = Vec::new();
+        let mut num_deleted = 0;
+        while let Some(batch) = stream
+            .try_next()
+            .await
+            .context(ScanDeleteRangeSnafu { region_id })?
+        {
+            let ts_column = batch.column(ts_index);
+            for row_index in 0..batch.num_rows() {
+                let Value::Timestamp(ts) = ts_column.get(row_index) else {
+                    continue;
+                };
+                if ts < start || ts >= end {
+                    continue;
+                }
+                let values = batch
+                    .columns()
+                    .iter()
+                    .map(|column| value_to_grpc_value(column.get(row_index)))
+                    .collect();
+                rows.push(Row { values });
+                if rows.len() >= chunk_size {
+                    num_deleted += self
+                        .delete_rows(region_id, schema.clone(), std::mem::take(&mut rows))
+                        .await?;
+                }
+            }
+        }
+        if !rows.is_empty() {
+            num_deleted += self.delete_rows(region_id, schema, rows).await?;
+        }
+
+        Ok(num_deleted)
+    }
+
+    /// Deletes `rows` of primary keys and timestamps in the `schema` from the region.
+    async fn delete_rows(
+        &self,
+        region_id: RegionId,
+        schema: Vec<ColumnSchema>,
+        rows: Vec<Row>,
+    ) -> Result<AffectedRows> {
+        let num_rows = rows.len();
+        let request = RegionDeleteRequest {
+            rows: Rows { schema, rows },
+        };
+        self.submit_request(region_id, RegionRequest::Delete(request))
+            .await?;
+        DELETE_ROWS_REWRITTEN_TOTAL.inc_by(num_rows as u64);
+
+        Ok(num_rows)
+    }
+
+    /// Returns true if the region rejects duplicate rows.
+    fn rejects_duplicates(&self, region_id: RegionId) -> bool {
+        self.workers.get_region(region_id).is_some_and(|region| {
+            region.version().options.duplicate_mode == DuplicateMode::ErrorOnDuplicate
+        })
+    }
+
+    /// Handles the put `request` of a region rejecting duplicate rows, see
+    /// [DuplicateMode::ErrorOnDuplicate].
+    ///
+    /// Rejects the put if its rows have the same primary key and timestamp as each other
+    /// or as rows in memtables and SSTs of the region.
+    async fn put_rejecting_duplicates(
+        &self,
+        region_id: RegionId,
+        request: RegionPutRequest,
+    ) -> Result<AffectedRows> {
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        // Serializes puts of the region, so no other put writes rows after the check
+        // and before this put.
+        let _guard = region.put_lock.lock().await;
+
+        let metadata = region.metadata();
+        let mut request = WriteRequest::new(region_id, OpType::Put, request.rows)?;
+        request.maybe_fill_missing_columns(&metadata)?;
+        let key_columns = key_columns(&metadata);
+        let keys = request.encode_columns(&key_columns)?;
+        let mut distinct_keys = HashSet::with_capacity(keys.len());
+        let mut num_duplicates = keys
+            .iter()
+            .filter(|key| !distinct_keys.insert(key.as_slice()))
+            .count();
+
+        if let Some((start, end)) = put_time_range(&request, &metadata) {
+            let codec = McmpRowCodec::new(
+                key_columns
+                    .iter()
+                    .map(|column| SortField::new(column.column_schema.data_type.clone()))
+                    .collect(),
+            );
+            let mut stream = self.scan_keys(&metadata, start, end).await?;
+            while let Some(batch) = stream
+                .try_next()
+                .await
+                .context(ScanDuplicateRowsSnafu { region_id })?
+            {
+                for row_index in 0..batch.num_rows() {
+                    let key = codec.encode(
+                        batch
+                            .columns()
+                            .iter()
+                            .map(|column| column.get_ref(row_index)),
+                    )?;
+                    if distinct_keys.contains(key.as_slice()) {
+                        num_duplicates += 1;
+                    }
+                }
+            }
+        }
+        ensure!(
+            num_duplicates == 0,
+            DuplicateRowsSnafu {
+                region_id,
+                num_duplicates,
+            }
+        );
+
+        let request = RegionPutRequest { rows: request.rows };
+        self.submit_request(region_id, RegionRequest::Put(request))
+            .await
+    }
+
+    /// Scans primary keys and timestamps of rows in `[start, end)` of the region, the
+    /// columns are ordered like [key_columns()].
+    ///
+    /// The range is only used to prune memtables and SSTs, so the stream may return rows
+    /// out of the range.
+    async fn scan_keys(
+        &self,
+        metadata: &RegionMetadata,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<SendableRecordBatchStream> {
+        let projection = key_columns(metadata)
+            .iter()
+            .map(|column| metadata.column_index_by_id(column.column_id).unwrap())
+            .collect();
+        let ts_name = &metadata.time_index_column().column_schema.name;
+        let filters = vec![
+            col(ts_name)
+                .gt_eq(lit(timestamp_to_scalar_value(
+                    start.unit(),
+                    Some(start.value()),
+                )))
+                .into(),
+            col(ts_name)
+                .lt(lit(timestamp_to_scalar_value(
+                    end.unit(),
+                    Some(end.value()),
+                )))
+                .into(),
+        ];
+        let scan_request = ScanRequest {
+            projection: Some(projection),
+            filters,
+            ..Default::default()
+        };
+        self.handle_query(metadata.region_id, scan_request)?
+            .scan()
+            .await
+    }
+
     /// Handles the scan `request` and returns a [Scanner] for the `request`.
     fn handle_query(&self, region_id: RegionId, request: ScanRequest) -> Result<Scanner> {
         // Reading a region doesn't need to go through the region worker thread.
@@ -192,13 +847,40 @@ impl EngineInner {
             request,
             Some(cache_manager),
         )
-        .with_parallelism(scan_parallelism);
+        .with_parallelism(scan_parallelism)
+        .with_verify_checksum(self.config.verify_sst_checksum)
+        .with_prefetch(self.config.sst_prefetch_row_groups);
 
         scan_region.scanner()
     }
 
+    /// Handles the scan `request` in follow mode, the returned stream keeps returning rows
+    /// written to the region after the scan.
+    async fn follow_query(
+        &self,
+        region_id: RegionId,
+        request: ScanRequest,
+    ) -> Result<SendableRecordBatchStream> {
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        // Subscribes before getting the version to scan so we won't miss any write.
+        let receiver = region.write_subscribers.subscribe();
+        let committed_sequence = region.version_control.current().committed_sequence;
+        let Scanner::Seq(seq_scan) = self.handle_query(region_id, request)?;
+
+        FollowScan::new(seq_scan, receiver, committed_sequence)
+            .build_stream()
+            .await
+    }
+
     /// Set writable mode for a region.
     fn set_writable(&self, region_id: RegionId, writable: bool) -> Result<()> {
+        if self.open_limiter.set_evicted_writable(region_id, writable) {
+            // Applies to the region after reopening.
+            return Ok(());
+        }
         let region = self
             .workers
             .get_region(region_id)
@@ -208,6 +890,26 @@ impl EngineInner {
         Ok(())
     }
 
+    /// Freezes or unfreezes the region and persists the state in the manifest.
+    ///
+    /// Writes the region worker already accepted before freezing the region still
+    /// complete, later writes fail. Running compactions are allowed to finish.
+    async fn set_frozen(&self, region_id: RegionId, frozen: bool) -> Result<()> {
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        // Only the leader updates the manifest.
+        ensure!(region.is_writable(), RegionReadonlySnafu { region_id });
+
+        let action_list =
+            RegionMetaActionList::with_action(RegionMetaAction::Freeze(RegionFreeze { frozen }));
+        region.manifest_manager.update(action_list).await?;
+        region.set_frozen(frozen);
+        info!("Set region {} frozen to {}", region_id, frozen);
+        Ok(())
+    }
+
     /// Sets read-only for a region and ensures no more writes in the region after it returns.
     async fn set_readonly_gracefully(&self, region_id: RegionId) -> Result<SetReadonlyResponse> {
         // Notes: It acquires the mutable ownership to ensure no other threads,
@@ -219,13 +921,79 @@ impl EngineInner {
     }
 
     fn role(&self, region_id: RegionId) -> Option<RegionRole> {
-        self.workers.get_region(region_id).map(|region| {
-            if region.is_writable() {
-                RegionRole::Leader
-            } else {
-                RegionRole::Follower
+        let writable = match self.workers.get_region(region_id) {
+            Some(region) => region.is_writable(),
+            None => self.open_limiter.evicted(region_id)?.writable,
+        };
+        if writable {
+            Some(RegionRole::Leader)
+        } else {
+            Some(RegionRole::Follower)
+        }
+    }
+}
+
+/// Returns primary key columns and the time index column of the region.
+fn key_columns(metadata: &RegionMetadata) -> Vec<&ColumnMetadata> {
+    metadata
+        .primary_key_columns()
+        .chain([metadata.time_index_column()])
+        .collect()
+}
+
+/// Returns the time range `[start, end)` of rows in the put `request`, the request must
+/// contain all columns of the region.
+fn put_time_range(
+    request: &WriteRequest,
+    metadata: &RegionMetadata,
+) -> Option<(Timestamp, Timestamp)> {
+    let index = request.column_index_by_name(&metadata.time_index_column().column_schema.name)?;
+    let datatype_extension = &request.rows.schema[index].datatype_extension;
+    let (min, max) = request
+        .rows
+        .rows
+        .iter()
+        .filter_map(
+            |row| match pb_value_to_value_ref(&row.values[index], datatype_extension) {
+                ValueRef::Timestamp(ts) => Some(ts),
+                _ => None,
+            },
+        )
+        .fold(None, |range, ts| match range {
+            Some((min, max)) => Some((std::cmp::min(min, ts), std::cmp::max(max, ts))),
+            None => Some((ts, ts)),
+        })?;
+    Some((
+        min,
+        Timestamp::new(max.value().saturating_add(1), max.unit()),
+    ))
+}
+
+impl IndexStatisticsProvider for EngineInner {
+    fn index_statistics(&self) -> Vec<IndexStatistics> {
+        let mut statistics = Vec::new();
+        for region in self.workers.list_regions() {
+            let version = region.version();
+            for file in version.ssts.levels().iter().flat_map(|level| level.files()) {
+                let meta = file.meta();
+                // Skipped columns have no index.
+                for stats in meta.index_stats.iter().filter(|stats| !stats.skipped) {
+                    let Some(column) = version.metadata.column_by_id(stats.column_id) else {
+                        continue;
+                    };
+                    statistics.push(IndexStatistics {
+                        region_id: region.region_id,
+                        file_id: meta.file_id.to_string(),
+                        column_id: stats.column_id,
+                        column_name: column.column_schema.name.clone(),
+                        distinct_count: stats.distinct_count,
+                        index_size: stats.index_size,
+                        avg_postings_len: stats.avg_postings_len(),
+                    });
+                }
             }
-        })
+        }
+        statistics
     }
 }
 
@@ -240,10 +1008,13 @@ impl RegionEngine for MitoEngine {
         region_id: RegionId,
         request: RegionRequest,
     ) -> Result<AffectedRows, BoxedError> {
-        self.inner
+        let rows = self
+            .inner
             .handle_request(region_id, request)
             .await
-            .map_err(BoxedError::new)
+            .map_err(BoxedError::new)?;
+        self.close_idle_regions();
+        Ok(rows)
     }
 
     /// Handle substrait query and return a stream of record batches
@@ -252,6 +1023,20 @@ impl RegionEngine for MitoEngine {
         region_id: RegionId,
         request: ScanRequest,
     ) -> std::result::Result<SendableRecordBatchStream, BoxedError> {
+        let _guard = self
+            .inner
+            .access_region(region_id)
+            .await
+            .map_err(BoxedError::new)?;
+        self.close_idle_regions();
+        if request.follow {
+            return self
+                .inner
+                .follow_query(region_id, request)
+                .await
+                .map_err(BoxedError::new);
+        }
+
         self.scanner(region_id, request)
             .map_err(BoxedError::new)?
             .scan()
@@ -259,11 +1044,47 @@ impl RegionEngine for MitoEngine {
             .map_err(BoxedError::new)
     }
 
+    async fn count_rows(
+        &self,
+        region_id: RegionId,
+        request: ScanRequest,
+    ) -> std::result::Result<Option<u64>, BoxedError> {
+        let _guard = self
+            .inner
+            .access_region(region_id)
+            .await
+            .map_err(BoxedError::new)?;
+        MitoEngine::count_rows(self, region_id, request)
+            .await
+            .map_err(BoxedError::new)
+    }
+
+    async fn min_max(
+        &self,
+        region_id: RegionId,
+        column: &str,
+        request: ScanRequest,
+    ) -> std::result::Result<Option<(Value, Value)>, BoxedError> {
+        let _guard = self
+            .inner
+            .access_region(region_id)
+            .await
+            .map_err(BoxedError::new)?;
+        MitoEngine::min_max(self, region_id, column, request)
+            .await
+            .map_err(BoxedError::new)
+    }
+
     /// Retrieve region's metadata.
     async fn get_metadata(
         &self,
         region_id: RegionId,
     ) -> std::result::Result<RegionMetadataRef, BoxedError> {
+        let _guard = self
+            .inner
+            .access_region(region_id)
+            .await
+            .map_err(BoxedError::new)?;
         self.inner.get_metadata(region_id).map_err(BoxedError::new)
     }
 
@@ -277,6 +1098,10 @@ impl RegionEngine for MitoEngine {
     }
 
     async fn region_disk_usage(&self, region_id: RegionId) -> Option<i64> {
+        if let Some(evicted) = self.inner.open_limiter.evicted(region_id) {
+            // Reports the usage of the closed region without opening it.
+            return evicted.disk_usage.try_into().ok();
+        }
         let size = self
             .get_region_usage(region_id)
             .await
@@ -295,12 +1120,55 @@ impl RegionEngine for MitoEngine {
         &self,
         region_id: RegionId,
     ) -> Result<SetReadonlyResponse, BoxedError> {
+        let _guard = self
+            .inner
+            .access_region(region_id)
+            .await
+            .map_err(BoxedError::new)?;
         self.inner
             .set_readonly_gracefully(region_id)
             .await
             .map_err(BoxedError::new)
     }
 
+    /// Freezes or unfreezes the region. A frozen region rejects puts, deletes and
+    /// compactions but still serves reads, e.g. to protect old data from accidental
+    /// writes. The state is persisted so the region is still frozen after reopening.
+    async fn set_frozen(&self, region_id: RegionId, frozen: bool) -> Result<(), BoxedError> {
+        let _guard = self
+            .inner
+            .access_region(region_id)
+            .await
+            .map_err(BoxedError::new)?;
+        self.inner
+            .set_frozen(region_id, frozen)
+            .await
+            .map_err(BoxedError::new)
+    }
+
+    /// Pauses or resumes compactions of the region, resuming the region schedules a
+    /// compaction for it.
+    async fn set_compaction_paused(
+        &self,
+        region_id: RegionId,
+        paused: bool,
+    ) -> Result<(), BoxedError> {
+        let _region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })
+            .map_err(BoxedError::new)?;
+        if paused {
+            self.pause_compaction(Some(region_id));
+            Ok(())
+        } else {
+            self.resume_compaction(Some(region_id))
+                .await
+                .map_err(BoxedError::new)
+        }
+    }
+
     fn role(&self, region_id: RegionId) -> Option<RegionRole> {
         self.inner.role(region_id)
     }
@@ -320,22 +1188,25 @@ impl MitoEngine {
         object_store_manager: ObjectStoreManagerRef,
         write_buffer_manager: Option<crate::flush::WriteBufferManagerRef>,
         listener: Option<crate::engine::listener::EventListenerRef>,
+        clock: Option<common_time::clock::ClockRef>,
     ) -> Result<MitoEngine> {
         config.sanitize()?;
 
         let config = Arc::new(config);
-        Ok(MitoEngine {
-            inner: Arc::new(EngineInner {
-                workers: WorkerGroup::start_for_test(
-                    config.clone(),
-                    log_store,
-                    object_store_manager,
-                    write_buffer_manager,
-                    listener,
-                )
-                .await?,
-                config,
-            }),
-        })
+        let inner = Arc::new(EngineInner {
+            workers: WorkerGroup::start_for_test(
+                config.clone(),
+                log_store,
+                object_store_manager,
+                write_buffer_manager,
+                listener,
+                clock,
+            )
+            .await?,
+            open_limiter: OpenRegionLimiter::new(config.max_open_regions),
+            config,
+        });
+        index_statistics::register_provider(Arc::downgrade(&inner) as _);
+        Ok(MitoEngine { inner })
     }
 }
@@ -15,17 +15,25 @@
 //! Basic tests for mito engine.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use api::v1::value::ValueData;
 use api::v1::Rows;
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
-use common_recordbatch::RecordBatches;
+use common_query::prelude::Expr;
+use common_recordbatch::{RecordBatchStream, RecordBatches};
+use common_time::Timestamp;
+use datafusion_common::ScalarValue;
+use datafusion_expr::{col, lit};
 use datatypes::prelude::ConcreteDataType;
+use futures::StreamExt;
 use store_api::region_request::{RegionOpenRequest, RegionPutRequest};
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, SourceOrder};
 
 use super::*;
+use crate::engine::listener::WriteBatchListener;
+use crate::metrics::READ_SST_FETCHED_BYTES_TOTAL;
 use crate::region::version::VersionControlData;
 use crate::test_util::{
     build_delete_rows_for_key, build_rows, build_rows_for_key, delete_rows, delete_rows_schema,
@@ -522,7 +530,7 @@ async fn test_region_usage() {
     // region is empty now, check manifest size
     let region = engine.get_region(region_id).unwrap();
     let region_stat = region.region_usage().await;
-    assert_eq!(region_stat.manifest_usage, 686);
+    assert_eq!(region_stat.manifest_usage, 711);
 
     // put some rows
     let rows = Rows {
@@ -553,5 +561,473 @@ async fn test_region_usage() {
     assert_eq!(region_stat.sst_usage, 2742);
 
     // region total usage
-    assert_eq!(region_stat.disk_usage(), 3791);
+    assert_eq!(region_stat.disk_usage(), 3816);
+}
+
+#[tokio::test]
+async fn test_region_manifest_json() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes two SSTs.
+    for (start, end) in [(0, 3), (10, 15)] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(start, end),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+
+    let json = engine.region_manifest_json(region_id).await.unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let files = value["manifest"]["files"].as_object().unwrap();
+    assert_eq!(2, files.len());
+    let region = engine.get_region(region_id).unwrap();
+    for file in region.version().ssts.levels()[0].files() {
+        let file_json = &files[&file.file_id().to_string()];
+        assert_eq!(
+            serde_json::to_value(file.time_range()).unwrap(),
+            file_json["time_range"]
+        );
+    }
+
+    // Each flush writes an edit.
+    let recent_actions = value["recent_actions"].as_array().unwrap();
+    let num_edits = recent_actions
+        .iter()
+        .flat_map(|list| list["action_list"]["actions"].as_array().unwrap())
+        .filter(|action| action.get("Edit").is_some())
+        .count();
+    assert_eq!(2, num_edits);
+}
+
+#[tokio::test]
+async fn test_scan_fetched_bytes() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes two SSTs.
+    for (start, end) in [(0, 3), (10, 15)] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(start, end),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+    let region = engine.get_region(region_id).unwrap();
+    let file_sizes: HashMap<_, _> = region.version().ssts.levels()[0]
+        .files()
+        .map(|file| (file.file_id(), file.size()))
+        .collect();
+
+    let fetched_before = READ_SST_FETCHED_BYTES_TOTAL.get();
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    let mut stream = scanner.scan().await.unwrap();
+    while let Some(batch) = stream.next().await {
+        batch.unwrap();
+    }
+    let total = stream.metrics().unwrap().fetched_bytes;
+    assert!(total > 0);
+    // Other tests may also read SSTs.
+    assert!(READ_SST_FETCHED_BYTES_TOTAL.get() - fetched_before >= total);
+
+    let per_file = scanner.fetched_bytes().per_file();
+    assert_eq!(2, per_file.len());
+    for (file_id, bytes) in &per_file {
+        assert!(*bytes > 0 && *bytes <= file_sizes[file_id]);
+    }
+    assert_eq!(total, per_file.values().sum::<u64>());
+
+    // Pages are in the cache now.
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    let mut stream = scanner.scan().await.unwrap();
+    while let Some(batch) = stream.next().await {
+        batch.unwrap();
+    }
+    assert_eq!(0, stream.metrics().unwrap().fetched_bytes);
+}
+
+#[tokio::test]
+async fn test_count_rows_by_metadata() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    let delete_schema = delete_rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes two SSTs and puts rows to the memtable, rows in the memtable are
+    // overwritten once.
+    for (start, end) in [(0, 3), (10, 15)] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(start, end),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+    for _ in 0..2 {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(20, 24),
+        };
+        put_rows(&engine, region_id, rows).await;
+    }
+
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(Some(12), scanner.count_rows_by_metadata().await.unwrap());
+    // Doesn't fetch any row group.
+    assert_eq!(0, scanner.fetched_bytes().total());
+
+    // Filters on the time index that contain whole SSTs.
+    let ts = |secs: i64| lit(ScalarValue::TimestampMillisecond(Some(secs * 1000), None));
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("ts").gt_eq(ts(10)))],
+        ..Default::default()
+    };
+    assert_eq!(
+        Some(9),
+        engine.count_rows(region_id, request).await.unwrap()
+    );
+    // Filters that select part of a SST.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("ts").gt_eq(ts(12)))],
+        ..Default::default()
+    };
+    assert_eq!(None, engine.count_rows(region_id, request).await.unwrap());
+    // Filters on other columns.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("0")))],
+        ..Default::default()
+    };
+    assert_eq!(None, engine.count_rows(region_id, request).await.unwrap());
+
+    // Deletes in memtables are counted.
+    let rows = Rows {
+        schema: delete_schema.clone(),
+        rows: build_delete_rows_for_key("20", 20, 21),
+    };
+    delete_rows(&engine, region_id, rows).await;
+    assert_eq!(
+        Some(11),
+        engine
+            .count_rows(region_id, ScanRequest::default())
+            .await
+            .unwrap()
+    );
+    // SSTs with deleted rows need scanning.
+    flush_region(&engine, region_id, None).await;
+    assert_eq!(
+        None,
+        engine
+            .count_rows(region_id, ScanRequest::default())
+            .await
+            .unwrap()
+    );
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("ts").lt(ts(20)))],
+        ..Default::default()
+    };
+    assert_eq!(
+        Some(8),
+        engine.count_rows(region_id, request).await.unwrap()
+    );
+
+    // SSTs overlapping in time may have the same rows.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(2, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("ts").lt(ts(20)))],
+        ..Default::default()
+    };
+    assert_eq!(None, engine.count_rows(region_id, request).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_min_max_by_metadata() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes two SSTs and puts rows to the memtable.
+    for (start, end) in [(0, 3), (10, 15)] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(start, end),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(20, 24),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let ts = |secs: i64| Value::Timestamp(Timestamp::new_millisecond(secs * 1000));
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(
+        Some((ts(0), ts(23))),
+        scanner.min_max_by_metadata("ts").await.unwrap()
+    );
+    assert_eq!(
+        Some((Value::from(0.0), Value::from(23.0))),
+        scanner.min_max_by_metadata("field_0").await.unwrap()
+    );
+    // Doesn't fetch any row group.
+    assert_eq!(0, scanner.fetched_bytes().total());
+    // Tags are encoded in primary keys.
+    assert_eq!(None, scanner.min_max_by_metadata("tag_0").await.unwrap());
+    assert!(scanner.min_max_by_metadata("unknown").await.is_err());
+
+    // Filters on the time index that contain whole SSTs.
+    let ts_lit = |secs: i64| lit(ScalarValue::TimestampMillisecond(Some(secs * 1000), None));
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("ts").lt(ts_lit(20)))],
+        ..Default::default()
+    };
+    assert_eq!(
+        Some((ts(0), ts(14))),
+        engine.min_max(region_id, "ts", request).await.unwrap()
+    );
+    // Filters that select part of a SST.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("ts").lt(ts_lit(12)))],
+        ..Default::default()
+    };
+    assert_eq!(
+        None,
+        engine.min_max(region_id, "ts", request).await.unwrap()
+    );
+
+    // Deletes a row of the SST, the deleted row is still in the SST.
+    let delete_schema = delete_rows_schema(&CreateRequestBuilder::new().build());
+    let rows = Rows {
+        schema: delete_schema,
+        rows: build_delete_rows_for_key("0", 0, 1),
+    };
+    delete_rows(&engine, region_id, rows).await;
+    for column in ["ts", "field_0"] {
+        assert_eq!(
+            None,
+            engine
+                .min_max(region_id, column, ScanRequest::default())
+                .await
+                .unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_min_max_all_null_by_metadata() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let mut rows = build_rows(0, 5);
+    for row in &mut rows {
+        row.values[1].value_data = None;
+    }
+    let rows = Rows {
+        schema: column_schemas,
+        rows,
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    assert_eq!(
+        Some((Value::Null, Value::Null)),
+        engine
+            .min_max(region_id, "field_0", ScanRequest::default())
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_scan_source_order() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes an old SST and a new SST, then overwrites rows of the old SST in the memtable.
+    for (start, end, value_start) in [(0, 3, 0), (3, 6, 3)] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows_for_key("a", start, end, value_start),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 0, 2, 100),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let mut files: Vec<_> = region.version().ssts.levels()[0]
+        .files()
+        .map(|file| (file.time_range().0, file.file_id()))
+        .collect();
+    files.sort_unstable();
+    let (old_file, new_file) = (files[0].1, files[1].1);
+
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 100.0   | 1970-01-01T00:00:00 |
+| a     | 101.0   | 1970-01-01T00:00:01 |
++-------+---------+---------------------+";
+    for (source_order, read_order) in [
+        (
+            SourceOrder::NewestFirst,
+            vec![None, Some(new_file), Some(old_file)],
+        ),
+        (
+            SourceOrder::OldestFirst,
+            vec![Some(old_file), Some(new_file), None],
+        ),
+    ] {
+        // The scan stops once it returns enough rows.
+        let request = ScanRequest {
+            limit: Some(2),
+            source_order: Some(source_order),
+            ..Default::default()
+        };
+        let scanner = engine.scanner(region_id, request).unwrap();
+        assert_eq!(read_order, scanner.read_order());
+
+        // Rows in the memtable always win regardless of the order.
+        let stream = scanner.scan().await.unwrap();
+        let batches = RecordBatches::try_collect(stream).await.unwrap();
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
+
+    // The output ordering overrides the source order.
+    let request = ScanRequest {
+        output_ordering: Some(Vec::new()),
+        source_order: Some(SourceOrder::OldestFirst),
+        ..Default::default()
+    };
+    let scanner = engine.scanner(region_id, request).unwrap();
+    assert_eq!(Some(None), scanner.read_order().first().copied());
+
+    // The scan can't stop at the limit if it has filters to evaluate.
+    let request = ScanRequest {
+        limit: Some(2),
+        filters: vec![Expr::from(col("tag_0").eq(lit("a")))],
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(
+        6,
+        batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+    );
+}
+
+#[tokio::test]
+async fn test_coalesce_write_requests() {
+    let mut env = TestEnv::new();
+    let listener = Arc::new(WriteBatchListener::default());
+    let engine = env
+        .create_engine_with(
+            MitoConfig {
+                num_workers: 1,
+                write_coalesce_window: Duration::from_millis(500),
+                ..Default::default()
+            },
+            None,
+            Some(listener.clone()),
+        )
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Sends small inserts one by one in a short period.
+    let handles: Vec<_> = (0..10)
+        .map(|i| {
+            let engine = engine.clone();
+            let rows = Rows {
+                schema: column_schemas.clone(),
+                rows: build_rows(i, i + 1),
+            };
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(5 * i as u64)).await;
+                put_rows(&engine, region_id, rows).await;
+            })
+        })
+        .collect();
+    futures::future::join_all(handles).await;
+
+    assert_eq!(10, listener.num_requests());
+    assert!(
+        listener.num_batches() < 10,
+        "num_batches: {}",
+        listener.num_batches()
+    );
+
+    // The flush drains all coalesced rows.
+    flush_region(&engine, region_id, None).await;
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(10, batches.iter().map(|b| b.num_rows()).sum::<usize>());
 }
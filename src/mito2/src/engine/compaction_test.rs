@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::assert_matches::assert_matches;
 use std::ops::Range;
 
-use api::v1::{ColumnSchema, Rows};
+use api::v1::value::ValueData;
+use api::v1::{ColumnSchema, Row, Rows};
+use common_base::readable_size::ReadableSize;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 use datatypes::prelude::ScalarVector;
-use datatypes::vectors::TimestampMillisecondVector;
+use datatypes::vectors::{Float64Vector, TimestampMillisecondVector};
 use store_api::region_engine::RegionEngine;
 use store_api::region_request::{
     RegionCompactRequest, RegionDeleteRequest, RegionFlushRequest, RegionRequest,
@@ -26,8 +31,10 @@ use store_api::storage::{RegionId, ScanRequest};
 
 use crate::config::MitoConfig;
 use crate::engine::MitoEngine;
+use crate::error::Error;
 use crate::test_util::{
-    build_rows_for_key, column_metadata_to_column_schema, put_rows, CreateRequestBuilder, TestEnv,
+    build_rows_for_key, column_metadata_to_column_schema, flush_region, put_rows,
+    CreateRequestBuilder, TestEnv,
 };
 
 async fn put_and_flush(
@@ -145,3 +152,341 @@ async fn test_compaction_region() {
     let vec = collect_stream_ts(stream).await;
     assert_eq!((0..25).map(|v| v * 1000).collect::<Vec<_>>(), vec);
 }
+
+#[tokio::test]
+async fn test_compaction_target_file_size() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let target_file_size = ReadableSize::kb(1);
+    let engine = env
+        .create_engine(MitoConfig {
+            compaction_target_file_size: target_file_size,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Flush 5 SSTs in the same time window, the output has more rows than a row group.
+    let num_rows = 250_000;
+    for start in (0..num_rows).step_by(50_000) {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows_millis("a", start, start + 50_000),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+
+    // The target is smaller than a row group so each file contains one row group
+    // except the remaining rows.
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let mut files: Vec<_> = version.ssts.levels()[1].files().cloned().collect();
+    assert!(version.ssts.levels()[0].files().next().is_none());
+    assert_eq!(3, files.len(), "unexpected files: {:?}", files);
+    files.sort_unstable_by_key(|file| file.time_range().0);
+    for file in &files[..files.len() - 1] {
+        assert!(file.meta().file_size >= target_file_size.as_bytes());
+    }
+
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    let stream = scanner.scan().await.unwrap();
+    let vec = collect_stream_ts(stream).await;
+    assert_eq!((0..num_rows as i64).collect::<Vec<_>>(), vec);
+}
+
+/// Builds rows for the `key` whose timestamps are `start..end` in millis.
+fn build_rows_millis(key: &str, start: usize, end: usize) -> Vec<Row> {
+    (start..end)
+        .map(|ts| Row {
+            values: vec![
+                api::v1::Value {
+                    value_data: Some(ValueData::StringValue(key.to_string())),
+                },
+                api::v1::Value {
+                    value_data: Some(ValueData::F64Value(ts as f64)),
+                },
+                api::v1::Value {
+                    value_data: Some(ValueData::TimestampMillisecondValue(ts as i64)),
+                },
+            ],
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_pause_resume_compaction() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    engine.pause_compaction(Some(region_id));
+    // Flush 5 SSTs, which is enough to trigger compaction.
+    for i in 0..5 {
+        put_and_flush(&engine, region_id, &column_schemas, i * 10..(i + 1) * 10).await;
+    }
+    // Explicit compactions are rejected during pause.
+    let err = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap_err();
+    let err = err.as_any().downcast_ref::<Error>().unwrap();
+    assert_matches!(err, Error::CompactionPaused { .. });
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(5, scanner.num_files());
+
+    // Writes continue during pause.
+    put_and_flush(&engine, region_id, &column_schemas, 50..60).await;
+
+    engine.resume_compaction(Some(region_id)).await.unwrap();
+    // Waits for the compaction scheduled by resuming.
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(
+        1,
+        scanner.num_files(),
+        "unexpected files: {:?}",
+        scanner.file_ids()
+    );
+    let stream = scanner.scan().await.unwrap();
+    let vec = collect_stream_ts(stream).await;
+    assert_eq!((0..60).map(|v| v * 1000).collect::<Vec<_>>(), vec);
+}
+
+#[tokio::test]
+async fn test_pause_resume_compaction_globally() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            num_workers: 2,
+            ..Default::default()
+        })
+        .await;
+
+    let region_ids = [RegionId::new(1, 1), RegionId::new(1, 2)];
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    for region_id in region_ids {
+        engine
+            .handle_request(region_id, RegionRequest::Create(request.clone()))
+            .await
+            .unwrap();
+    }
+
+    // The pause is shared by all workers of the group.
+    engine.pause_compaction(None);
+    for region_id in region_ids {
+        for i in 0..5 {
+            put_and_flush(&engine, region_id, &column_schemas, i * 10..(i + 1) * 10).await;
+        }
+        let err = engine
+            .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+            .await
+            .unwrap_err();
+        let err = err.as_any().downcast_ref::<Error>().unwrap();
+        assert_matches!(err, Error::CompactionPaused { .. });
+        let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+        assert_eq!(5, scanner.num_files());
+    }
+
+    engine.resume_compaction(None).await.unwrap();
+    for region_id in region_ids {
+        let output = engine
+            .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+            .await
+            .unwrap();
+        assert_eq!(output, 0);
+        let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+        assert_eq!(
+            1,
+            scanner.num_files(),
+            "unexpected files: {:?}",
+            scanner.file_ids()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_set_compaction_paused() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let err = engine
+        .set_compaction_paused(region_id, true)
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionNotFound, err.status_code());
+
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+
+    engine.set_compaction_paused(region_id, true).await.unwrap();
+    let err = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap_err();
+    let err = err.as_any().downcast_ref::<Error>().unwrap();
+    assert_matches!(err, Error::CompactionPaused { .. });
+
+    engine
+        .set_compaction_paused(region_id, false)
+        .await
+        .unwrap();
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+}
+
+/// Collects timestamps and values of `field_0` in the `stream`.
+async fn collect_stream_ts_and_fields(stream: SendableRecordBatchStream) -> Vec<(i64, f64)> {
+    let mut res = Vec::new();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    for batch in batches {
+        let ts_col = batch
+            .column_by_name("ts")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMillisecondVector>()
+            .unwrap();
+        let field_col = batch
+            .column_by_name("field_0")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Vector>()
+            .unwrap();
+        res.extend(
+            ts_col
+                .iter_data()
+                .zip(field_col.iter_data())
+                .map(|(t, v)| (t.unwrap().0.value(), v.unwrap())),
+        );
+    }
+    res
+}
+
+#[tokio::test]
+async fn test_compaction_rollup() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("rollup.after", "1h")
+        .insert_option("rollup.interval", "1m")
+        .insert_option("rollup.aggregation", "sum")
+        .build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Rows of the first 3 minutes are aged, their values are the same as their seconds.
+    // The interval [60s, 120s) is rolled up in two steps.
+    let now_secs = (common_time::util::current_time_millis() / 1000) as usize;
+    for ranges in [vec![0..90], vec![90..180, now_secs..now_secs + 2]] {
+        for range in ranges {
+            let rows = Rows {
+                schema: column_schemas.clone(),
+                rows: build_rows_for_key("a", range.start, range.end, range.start),
+            };
+            put_rows(&engine, region_id, rows).await;
+            flush_region(&engine, region_id, None).await;
+        }
+        let output = engine
+            .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+            .await
+            .unwrap();
+        assert_eq!(output, 0);
+    }
+
+    let now_millis = now_secs as i64 * 1000;
+    let expect = vec![
+        (0, 1770.0),
+        (60000, 5370.0),
+        (120000, 8970.0),
+        (now_millis, now_secs as f64),
+        (now_millis + 1000, (now_secs + 1) as f64),
+    ];
+    // Rolling up the region again doesn't change the rows.
+    for _ in 0..2 {
+        let output = engine
+            .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+            .await
+            .unwrap();
+        assert_eq!(output, 0);
+
+        let stream = engine
+            .handle_query(region_id, ScanRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(expect, collect_stream_ts_and_fields(stream).await);
+    }
+
+    // Aged rows are all in rolled up files.
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let rolled_up = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files())
+        .filter(|file| file.time_range().1.value() < 180000)
+        .all(|file| file.rolled_up());
+    assert!(rolled_up);
+}
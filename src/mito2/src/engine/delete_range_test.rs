@@ -0,0 +1,159 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use common_time::Timestamp;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionDeleteRangeRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::metrics::{DELETE_FILES_DROPPED_TOTAL, DELETE_ROWS_REWRITTEN_TOTAL};
+use crate::test_util::{
+    build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+async fn delete_range(engine: &MitoEngine, region_id: RegionId, start: i64, end: i64) -> usize {
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::DeleteRange(RegionDeleteRangeRequest {
+                start: Timestamp::new_millisecond(start),
+                end: Timestamp::new_millisecond(end),
+            }),
+        )
+        .await
+        .unwrap()
+}
+
+fn num_files(engine: &MitoEngine, region_id: RegionId) -> usize {
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    version
+        .ssts
+        .levels()
+        .iter()
+        .map(|level| level.files.len())
+        .sum()
+}
+
+#[tokio::test]
+async fn test_engine_delete_range() {
+    let mut env = TestEnv::with_prefix("delete-range");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes two files: [0s, 2s] and [3s, 5s].
+    for (start, end) in [(0, 3), (3, 6)] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(start, end),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+    assert_eq!(2, num_files(&engine, region_id));
+
+    let files_dropped = DELETE_FILES_DROPPED_TOTAL.get();
+    let rows_rewritten = DELETE_ROWS_REWRITTEN_TOTAL.get();
+
+    // Deletes a range without data.
+    assert_eq!(0, delete_range(&engine, region_id, 10000, 20000).await);
+    assert_eq!(2, num_files(&engine, region_id));
+    assert_eq!(files_dropped, DELETE_FILES_DROPPED_TOTAL.get());
+    assert_eq!(rows_rewritten, DELETE_ROWS_REWRITTEN_TOTAL.get());
+
+    // Covers the first file and the first row of the second file.
+    assert_eq!(1, delete_range(&engine, region_id, 0, 4000).await);
+    assert_eq!(1, num_files(&engine, region_id));
+    assert_eq!(files_dropped + 1, DELETE_FILES_DROPPED_TOTAL.get());
+    assert_eq!(rows_rewritten + 1, DELETE_ROWS_REWRITTEN_TOTAL.get());
+
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 4     | 4.0     | 1970-01-01T00:00:04 |
+| 5     | 5.0     | 1970-01-01T00:00:05 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_engine_delete_range_in_chunks() {
+    let mut env = TestEnv::with_prefix("delete-range-chunks");
+    // Deletes at most 2 rows at a time.
+    let engine = env
+        .create_engine(MitoConfig {
+            write_coalesce_max_rows: 2,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // The file [0s, 9s] is only partially covered by the range so rows are deleted row
+    // by row.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 10),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    // Rows in the memtable are deleted too.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(10, 12),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let rows_rewritten = DELETE_ROWS_REWRITTEN_TOTAL.get();
+    assert_eq!(9, delete_range(&engine, region_id, 2000, 11000).await);
+    assert_eq!(rows_rewritten + 9, DELETE_ROWS_REWRITTEN_TOTAL.get());
+
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 11    | 11.0    | 1970-01-01T00:00:11 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
@@ -0,0 +1,227 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for handling rows with duplicate timestamps.
+
+use api::v1::{ColumnSchema, Rows};
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_recordbatch::RecordBatches;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionPutRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_delete_rows_for_key, build_rows_for_key, delete_rows, delete_rows_schema, flush_region,
+    put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+/// Rows of key `a` that contain each timestamp in `[0, 2)` twice.
+fn duplicate_rows(column_schemas: &[ColumnSchema]) -> Rows {
+    let mut rows = build_rows_for_key("a", 0, 2, 0);
+    rows.extend(build_rows_for_key("a", 0, 2, 10));
+    Rows {
+        schema: column_schemas.to_vec(),
+        rows,
+    }
+}
+
+async fn create_region(engine: &MitoEngine, region_id: RegionId, mode: &str) -> Vec<ColumnSchema> {
+    let request = CreateRequestBuilder::new()
+        .insert_option("duplicate_mode", mode)
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    column_schemas
+}
+
+async fn scan_region(engine: &MitoEngine, region_id: RegionId) -> String {
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+#[tokio::test]
+async fn test_duplicate_keep_last() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let column_schemas = create_region(&engine, region_id, "keep_last").await;
+
+    put_rows(&engine, region_id, duplicate_rows(&column_schemas)).await;
+    flush_region(&engine, region_id, None).await;
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("a", 1, 3, 20),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 10.0    | 1970-01-01T00:00:00 |
+| a     | 20.0    | 1970-01-01T00:00:01 |
+| a     | 21.0    | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_region(&engine, region_id).await);
+}
+
+#[tokio::test]
+async fn test_duplicate_keep_first() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let column_schemas = create_region(&engine, region_id, "keep_first").await;
+
+    put_rows(&engine, region_id, duplicate_rows(&column_schemas)).await;
+    flush_region(&engine, region_id, None).await;
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("a", 1, 3, 20),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 0.0     | 1970-01-01T00:00:00 |
+| a     | 1.0     | 1970-01-01T00:00:01 |
+| a     | 21.0    | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_region(&engine, region_id).await);
+}
+
+#[tokio::test]
+async fn test_duplicate_keep_first_with_delete() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("duplicate_mode", "keep_first")
+        .build();
+    let column_schemas = rows_schema(&request);
+    let delete_schema = delete_rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 0, 2, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    let rows = Rows {
+        schema: delete_schema,
+        rows: build_delete_rows_for_key("a", 0, 2),
+    };
+    delete_rows(&engine, region_id, rows).await;
+    // Puts after the delete are kept, the deleted rows in the SST are not visible.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("a", 0, 1, 20),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 20.0    | 1970-01-01T00:00:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_region(&engine, region_id).await);
+
+    // The delete markers are also kept in the flushed SST.
+    flush_region(&engine, region_id, None).await;
+    assert_eq!(expected, scan_region(&engine, region_id).await);
+}
+
+#[tokio::test]
+async fn test_duplicate_error_on_duplicate() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let column_schemas = create_region(&engine, region_id, "error_on_duplicate").await;
+
+    // Rejects the whole request if it contains duplicate rows.
+    let rows = duplicate_rows(&column_schemas);
+    let err = engine
+        .handle_request(region_id, RegionRequest::Put(RegionPutRequest { rows }))
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 0, 2, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    // Rejects rows duplicate with rows in the memtable.
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 1, 3, 20),
+    };
+    let err = engine
+        .handle_request(region_id, RegionRequest::Put(RegionPutRequest { rows }))
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+    // Rejects rows duplicate with rows in SSTs.
+    flush_region(&engine, region_id, None).await;
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 1, 3, 20),
+    };
+    let err = engine
+        .handle_request(region_id, RegionRequest::Put(RegionPutRequest { rows }))
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+    // Rows of other keys and timestamps are accepted.
+    let mut rows = build_rows_for_key("a", 2, 3, 20);
+    rows.extend(build_rows_for_key("b", 0, 1, 30));
+    let rows = Rows {
+        schema: column_schemas,
+        rows,
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 0.0     | 1970-01-01T00:00:00 |
+| a     | 1.0     | 1970-01-01T00:00:01 |
+| a     | 20.0    | 1970-01-01T00:00:02 |
+| b     | 30.0    | 1970-01-01T00:00:00 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_region(&engine, region_id).await);
+}
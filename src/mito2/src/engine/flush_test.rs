@@ -15,15 +15,19 @@
 //! Flush tests for mito engine.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::Rows;
+use common_base::readable_size::ReadableSize;
 use common_recordbatch::RecordBatches;
+use common_time::Timestamp;
 use store_api::region_engine::RegionEngine;
 use store_api::region_request::RegionRequest;
 use store_api::storage::{RegionId, ScanRequest};
 
 use crate::config::MitoConfig;
 use crate::engine::listener::{FlushListener, StallListener};
+use crate::metrics::{MEMTABLE_BYTES, MEMTABLE_OLDEST_ENTRY_AGE};
 use crate::test_util::{
     build_rows, build_rows_for_key, flush_region, put_rows, reopen_region, rows_schema,
     CreateRequestBuilder, MockWriteBufferManager, TestEnv,
@@ -68,6 +72,117 @@ async fn test_manual_flush() {
     assert_eq!(expected, batches.pretty_print().unwrap());
 }
 
+#[tokio::test]
+async fn test_flush_target_file_rows() {
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            flush_target_file_rows: 100,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Tags have the same length so rows sorted by tags are also sorted by timestamps.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(1000, 2050),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    flush_region(&engine, region_id, None).await;
+
+    // Each file has 100 rows except the last one.
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let mut files: Vec<_> = version.ssts.levels()[0].files().cloned().collect();
+    assert_eq!(11, files.len(), "unexpected files: {:?}", files);
+    files.sort_unstable_by_key(|file| file.time_range().0);
+    for (i, file) in files.iter().enumerate() {
+        let start = 1000 + i as i64 * 100;
+        let end = (start + 99).min(2049);
+        assert_eq!(
+            (
+                Timestamp::new_millisecond(start * 1000),
+                Timestamp::new_millisecond(end * 1000)
+            ),
+            file.time_range()
+        );
+    }
+
+    let request = ScanRequest::default();
+    let scanner = engine.scanner(region_id, request).unwrap();
+    assert_eq!(11, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(1050, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+}
+
+#[tokio::test]
+async fn test_flush_target_file_size() {
+    let mut env = TestEnv::new();
+    let target_file_size = ReadableSize::kb(1);
+    let engine = env
+        .create_engine(MitoConfig {
+            flush_target_file_size: target_file_size,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Tags have the same length so rows sorted by tags are also sorted by timestamps.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(1000, 2050),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    flush_region(&engine, region_id, Some(100)).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let mut files: Vec<_> = version.ssts.levels()[0].files().cloned().collect();
+    assert!(files.len() > 1, "unexpected files: {:?}", files);
+    files.sort_unstable_by_key(|file| file.time_range().0);
+    let mut next_start = 1000;
+    for (i, file) in files.iter().enumerate() {
+        let (start, end) = file.time_range();
+        let (start, end) = (start.value() / 1000, end.value() / 1000);
+        // Time ranges of files only contain their own rows.
+        assert_eq!(next_start, start);
+        next_start = end + 1;
+        if i + 1 < files.len() {
+            // Files only roll over after whole row groups.
+            assert!(file.meta().file_size >= target_file_size.as_bytes());
+            assert_eq!(0, (end - start + 1) % 100, "unexpected file: {:?}", file);
+        }
+    }
+    assert_eq!(2050, next_start);
+
+    let request = ScanRequest::default();
+    let scanner = engine.scanner(region_id, request).unwrap();
+    assert_eq!(files.len(), scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(1050, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+}
+
 #[tokio::test]
 async fn test_flush_engine() {
     let mut env = TestEnv::new();
@@ -272,3 +387,53 @@ async fn test_flush_reopen_region() {
     assert_eq!(2, version_data.last_entry_id);
     assert_eq!(5, version_data.committed_sequence);
 }
+
+#[tokio::test]
+async fn test_memtable_metrics() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    // Metrics are global so we use a region id no other test uses.
+    let region_id = RegionId::new(2048, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let label = region_id.to_string();
+    let bytes = || MEMTABLE_BYTES.with_label_values(&[&label]).get();
+    let age = || MEMTABLE_OLDEST_ENTRY_AGE.with_label_values(&[&label]).get();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let bytes_after_first_write = bytes();
+    assert!(bytes_after_first_write > 0);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(3, 6),
+    };
+    put_rows(&engine, region_id, rows).await;
+    assert!(bytes() > bytes_after_first_write);
+    // The age is the age of the first write.
+    let age_before_flush = age();
+    assert!(age_before_flush >= 0.1, "age: {age_before_flush}");
+
+    flush_region(&engine, region_id, None).await;
+    assert_eq!(0, bytes());
+    assert_eq!(0.0, age());
+
+    // The new oldest entry is in the new memtable.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(6, 9),
+    };
+    put_rows(&engine, region_id, rows).await;
+    assert!(bytes() > 0);
+    assert!(age() < age_before_flush, "age: {}", age());
+}
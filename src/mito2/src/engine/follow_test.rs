@@ -0,0 +1,104 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use api::v1::Rows;
+use common_recordbatch::{RecordBatch, RecordBatches, SendableRecordBatchStream};
+use futures::StreamExt;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::RegionRequest;
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::test_util::{build_rows, put_rows, rows_schema, CreateRequestBuilder, TestEnv};
+
+/// Reads batches from the `stream` until it returns `num_rows` rows.
+async fn next_rows(stream: &mut SendableRecordBatchStream, num_rows: usize) -> Vec<RecordBatch> {
+    let mut batches = Vec::new();
+    let mut rows = 0;
+    while rows < num_rows {
+        let batch = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        rows += batch.num_rows();
+        batches.push(batch);
+    }
+    assert_eq!(num_rows, rows);
+    batches
+}
+
+#[tokio::test]
+async fn test_follow_region() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let request = ScanRequest {
+        follow: true,
+        ..Default::default()
+    };
+    let mut stream = engine.handle_query(region_id, request).await.unwrap();
+    let schema = stream.schema();
+    let region = engine.get_region(region_id).unwrap();
+    assert_eq!(1, region.write_subscribers.num_subscribers());
+
+    // Returns existing rows first.
+    let batches = next_rows(&mut stream, 3).await;
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    let batches = RecordBatches::try_new(schema.clone(), batches).unwrap();
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // Then returns rows inserted later.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    put_rows(&engine, region_id, rows).await;
+    let batches = next_rows(&mut stream, 2).await;
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 3     | 3.0     | 1970-01-01T00:00:03 |
+| 4     | 4.0     | 1970-01-01T00:00:04 |
++-------+---------+---------------------+";
+    let batches = RecordBatches::try_new(schema, batches).unwrap();
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // Dropping the stream removes the subscriber.
+    drop(stream);
+    assert_eq!(0, region.write_subscribers.num_subscribers());
+}
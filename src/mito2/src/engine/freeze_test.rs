@@ -0,0 +1,233 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::Rows;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_recordbatch::RecordBatches;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{
+    RegionCompactRequest, RegionDeleteRequest, RegionOpenRequest, RegionPutRequest, RegionRequest,
+};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_delete_rows_for_key, build_rows, delete_rows_schema, flush_region, put_rows, rows_schema,
+    CreateRequestBuilder, TestEnv,
+};
+
+async fn scan_rows(engine: &MitoEngine, region_id: RegionId) -> String {
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+#[tokio::test]
+async fn test_freeze_region() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    let delete_schema = delete_rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(0, 2),
+        },
+    )
+    .await;
+    flush_region(&engine, region_id, None).await;
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(2, 3),
+        },
+    )
+    .await;
+
+    engine.set_frozen(region_id, true).await.unwrap();
+
+    // Rejects puts, deletes and compactions.
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows: Rows {
+                    schema: column_schemas.clone(),
+                    rows: build_rows(3, 5),
+                },
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionFrozen, err.status_code());
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Delete(RegionDeleteRequest {
+                rows: Rows {
+                    schema: delete_schema,
+                    rows: build_delete_rows_for_key("0", 0, 1),
+                },
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionFrozen, err.status_code());
+    let err = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionFrozen, err.status_code());
+
+    // Still serves reads and flushes data written before.
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_rows(&engine, region_id).await);
+    flush_region(&engine, region_id, None).await;
+    let region = engine.get_region(region_id).unwrap();
+    assert_eq!(2, region.version().ssts.levels()[0].files().count());
+    assert_eq!(expected, scan_rows(&engine, region_id).await);
+
+    // Unfreezing restores writes and compactions.
+    engine.set_frozen(region_id, false).await.unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(3, 4),
+        },
+    )
+    .await;
+    engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
+| 3     | 3.0     | 1970-01-01T00:00:03 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_rows(&engine, region_id).await);
+}
+
+#[tokio::test]
+async fn test_freeze_region_not_found() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let err = engine
+        .set_frozen(RegionId::new(1, 1), true)
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionNotFound, err.status_code());
+}
+
+#[tokio::test]
+async fn test_freeze_region_persisted() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    engine.set_frozen(region_id, true).await.unwrap();
+
+    let open_region = |engine: MitoEngine| {
+        let region_dir = region_dir.clone();
+        async move {
+            engine
+                .handle_request(
+                    region_id,
+                    RegionRequest::Open(RegionOpenRequest {
+                        engine: String::new(),
+                        region_dir,
+                        options: HashMap::default(),
+                        skip_wal_replay: false,
+                    }),
+                )
+                .await
+                .unwrap();
+            engine.set_writable(region_id, true).unwrap();
+            engine
+        }
+    };
+
+    // The reopened region is still frozen.
+    let engine = open_region(env.reopen_engine(engine, MitoConfig::default()).await).await;
+    let err = engine
+        .handle_request(
+            region_id,
+            RegionRequest::Put(RegionPutRequest {
+                rows: Rows {
+                    schema: column_schemas.clone(),
+                    rows: build_rows(0, 1),
+                },
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::RegionFrozen, err.status_code());
+
+    // Unfreezing is persisted too.
+    engine.set_frozen(region_id, false).await.unwrap();
+    let engine = open_region(env.reopen_engine(engine, MitoConfig::default()).await).await;
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 1),
+        },
+    )
+    .await;
+
+    // Followers can't change the state.
+    engine.set_writable(region_id, false).unwrap();
+    let err = engine.set_frozen(region_id, true).await.unwrap_err();
+    assert_eq!(StatusCode::RegionReadonly, err.status_code());
+}
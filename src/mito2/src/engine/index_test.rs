@@ -0,0 +1,640 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+
+use api::v1::value::ValueData;
+use api::v1::Rows;
+use common_query::prelude::Expr;
+use common_recordbatch::{RecordBatchStream, RecordBatches};
+use datafusion_expr::{col, lit};
+use futures::StreamExt;
+use store_api::index_statistics;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionOpenRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::sst::file::{FileId, FileMeta};
+use crate::sst::location;
+use crate::test_util::{
+    build_rows, build_rows_for_key, flush_region, put_rows, rows_schema, CreateRequestBuilder,
+    TestEnv,
+};
+
+#[tokio::test]
+async fn test_index_stats() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("index.inverted_index.columns", "tag_0")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 15),
+        },
+    )
+    .await;
+    // Each row group (segment) has 5 rows.
+    flush_region(&engine, region_id, Some(5)).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let files: Vec<_> = version.ssts.levels()[0].files().collect();
+    assert_eq!(1, files.len());
+    let meta = files[0].meta();
+    assert!(meta.inverted_index_available());
+
+    // Each value of tag_0 is in one segment.
+    let tag_0 = version.metadata.column_by_name("tag_0").unwrap().column_id;
+    assert_eq!(1, meta.index_stats.len());
+    let stats = &meta.index_stats[0];
+    assert_eq!(tag_0, stats.column_id);
+    assert_eq!(15, stats.distinct_count);
+    assert_eq!(15, stats.postings_len);
+    assert_eq!(1.0, stats.avg_postings_len());
+    // The index file also contains metadata of the index.
+    assert!(stats.index_size > 0);
+    assert!(stats.index_size < meta.index_file_size);
+    let index_path = location::index_file_path(region.region_dir(), meta.file_id);
+    let object_store = env.get_object_store().unwrap();
+    let index_file = object_store.stat(&index_path).await.unwrap();
+    assert_eq!(meta.index_file_size, index_file.content_length());
+
+    // The engine provides the statistics to the system table.
+    let file_id = meta.file_id.to_string();
+    let statistics: Vec<_> = index_statistics::index_statistics()
+        .into_iter()
+        .filter(|stats| stats.region_id == region_id && stats.file_id == file_id)
+        .collect();
+    assert_eq!(1, statistics.len());
+    assert_eq!("tag_0", statistics[0].column_name);
+    assert_eq!(15, statistics[0].distinct_count);
+    assert_eq!(stats.index_size, statistics[0].index_size);
+    assert_eq!(1.0, statistics[0].avg_postings_len);
+
+    // Queries the index.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("5")))],
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 5     | 5.0     | 1970-01-01T00:00:05 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_rebuild_missing_index() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("index.inverted_index.columns", "tag_0")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 15),
+        },
+    )
+    .await;
+    flush_region(&engine, region_id, Some(5)).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let file = region.version().ssts.levels()[0]
+        .files()
+        .next()
+        .unwrap()
+        .clone();
+    let index_path = location::index_file_path(region.region_dir(), file.file_id());
+    let object_store = env.get_object_store().unwrap();
+    object_store.delete(&index_path).await.unwrap();
+
+    let new_request = || ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("5")))],
+        ..Default::default()
+    };
+    // Falls back to scan all row groups.
+    let applier = engine
+        .scanner(region_id, new_request())
+        .unwrap()
+        .index_applier()
+        .cloned()
+        .unwrap();
+    assert!(applier
+        .apply(file.file_id(), file.index_file_id())
+        .await
+        .is_err());
+    let stream = engine.handle_query(region_id, new_request()).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 5     | 5.0     | 1970-01-01T00:00:05 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // The query triggers rebuilding the index, the rebuilt index is committed under
+    // a new name.
+    let rebuilt = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let rebuilt = region.version().ssts.levels()[0]
+                .files()
+                .next()
+                .unwrap()
+                .clone();
+            if !file.rebuilding_index() && rebuilt.index_file_id() != file.index_file_id() {
+                return rebuilt;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(file.file_id(), rebuilt.file_id());
+    let new_index_path = location::index_file_path(region.region_dir(), rebuilt.index_file_id());
+    let index_file = object_store.stat(&new_index_path).await.unwrap();
+    assert_eq!(rebuilt.meta().index_file_size, index_file.content_length());
+
+    // Only the second row group contains the value.
+    let row_groups = applier
+        .apply(rebuilt.file_id(), rebuilt.index_file_id())
+        .await
+        .unwrap();
+    assert_eq!(BTreeSet::from([1]), row_groups);
+    let stream = engine.handle_query(region_id, new_request()).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_index_selected_columns() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .tag_num(2)
+        .insert_option("index.inverted_index.columns", "tag_1")
+        .build();
+    // tag_0, tag_1, field_0, ts
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let rows = (0..10)
+        .map(|i| api::v1::Row {
+            values: vec![
+                api::v1::Value {
+                    value_data: Some(ValueData::StringValue(format!("a{i}"))),
+                },
+                api::v1::Value {
+                    value_data: Some(ValueData::StringValue(format!("b{}", i % 2))),
+                },
+                api::v1::Value {
+                    value_data: Some(ValueData::F64Value(i as f64)),
+                },
+                api::v1::Value {
+                    value_data: Some(ValueData::TimestampMillisecondValue(i as i64 * 1000)),
+                },
+            ],
+        })
+        .collect();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows,
+        },
+    )
+    .await;
+    flush_region(&engine, region_id, None).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let files: Vec<_> = version.ssts.levels()[0].files().collect();
+    assert_eq!(1, files.len());
+    let meta = files[0].meta();
+    assert!(meta.inverted_index_available());
+
+    // Only tag_1 has an index.
+    let tag_1 = version.metadata.column_by_name("tag_1").unwrap().column_id;
+    assert_eq!(1, meta.index_stats.len());
+    assert_eq!(tag_1, meta.index_stats[0].column_id);
+    assert_eq!(2, meta.index_stats[0].distinct_count);
+
+    // Queries the column without index.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("a3")))],
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+-------+---------+---------------------+
+| tag_0 | tag_1 | field_0 | ts                  |
++-------+-------+---------+---------------------+
+| a3    | b1    | 3.0     | 1970-01-01T00:00:03 |
++-------+-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_skip_index_of_high_cardinality_column() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("index.inverted_index.columns", "tag_0")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Each row has a distinct tag_0.
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 5000),
+        },
+    )
+    .await;
+    flush_region(&engine, region_id, None).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let files: Vec<_> = version.ssts.levels()[0].files().collect();
+    assert_eq!(1, files.len());
+    let meta = files[0].meta();
+    let tag_0 = version.metadata.column_by_name("tag_0").unwrap().column_id;
+    assert_eq!(1, meta.index_stats.len());
+    assert_eq!(tag_0, meta.index_stats[0].column_id);
+    assert!(meta.index_stats[0].skipped);
+    assert!(!meta.column_inverted_index_available(tag_0));
+
+    // Queries still return rows of the column.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("4321")))],
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 4321  | 4321.0  | 1970-01-01T01:12:01 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_apply_index_of_multiple_files() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("index.inverted_index.columns", "tag_0")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Each file has 10 other keys and 1 row of key "x".
+    for i in 0..3 {
+        let mut rows = build_rows(i * 10, i * 10 + 10);
+        rows.extend(build_rows_for_key("x", 100 + i, 101 + i, i));
+        put_rows(
+            &engine,
+            region_id,
+            Rows {
+                schema: column_schemas.clone(),
+                rows,
+            },
+        )
+        .await;
+        flush_region(&engine, region_id, Some(5)).await;
+    }
+    let region = engine.get_region(region_id).unwrap();
+    let files: Vec<_> = region.version().ssts.levels()[0].files().cloned().collect();
+    assert_eq!(3, files.len());
+
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("x")))],
+        ..Default::default()
+    };
+    // The index selects one row group of each file.
+    let applier = engine
+        .scanner(region_id, request.clone())
+        .unwrap()
+        .index_applier()
+        .cloned()
+        .unwrap();
+    let file_ids: Vec<_> = files
+        .iter()
+        .map(|file| (file.file_id(), file.index_file_id()))
+        .collect();
+    let row_groups = applier.apply_files(&file_ids, 2).await;
+    assert_eq!(3, row_groups.len());
+    assert!(row_groups
+        .values()
+        .all(|output| output.row_groups.len() == 1));
+
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| x     | 0.0     | 1970-01-01T00:01:40 |
+| x     | 1.0     | 1970-01-01T00:01:41 |
+| x     | 2.0     | 1970-01-01T00:01:42 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
+#[tokio::test]
+async fn test_explain_index() {
+    let mut env = TestEnv::new();
+    let config = MitoConfig::default();
+    let engine = env.create_engine(config.clone()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("index.inverted_index.columns", "tag_0")
+        .build();
+    let region_dir = request.region_dir.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let new_file_id = |engine: &MitoEngine, known: &[FileId]| {
+        let region = engine.get_region(region_id).unwrap();
+        let file_ids: Vec<_> = region.version().ssts.levels()[0]
+            .files()
+            .map(|file| file.file_id())
+            .filter(|file_id| !known.contains(file_id))
+            .collect();
+        assert_eq!(1, file_ids.len());
+        file_ids[0]
+    };
+
+    // The first file contains the value and the second doesn't.
+    let mut file_ids = Vec::new();
+    for (start, end) in [(0, 15), (20, 30)] {
+        put_rows(
+            &engine,
+            region_id,
+            Rows {
+                schema: column_schemas.clone(),
+                rows: build_rows(start, end),
+            },
+        )
+        .await;
+        flush_region(&engine, region_id, Some(5)).await;
+        file_ids.push(new_file_id(&engine, &file_ids));
+    }
+
+    // The third file has no index as the region is reopened without index options.
+    let engine = env.reopen_engine(engine, config).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Open(RegionOpenRequest {
+                engine: String::new(),
+                region_dir,
+                options: HashMap::default(),
+                skip_wal_replay: false,
+            }),
+        )
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(30, 35),
+        },
+    )
+    .await;
+    flush_region(&engine, region_id, Some(5)).await;
+    file_ids.push(new_file_id(&engine, &file_ids));
+
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("5")))],
+        ..Default::default()
+    };
+    let explains: HashMap<_, _> = engine
+        .explain_index(region_id, request.clone())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|explain| (explain.file_id, explain))
+        .collect();
+    assert_eq!(3, explains.len());
+
+    let explain = &explains[&file_ids[0]];
+    assert_eq!(Some(1), explain.selected_row_groups);
+    assert_eq!(3, explain.total_row_groups);
+    // Only the posting of the value is read.
+    assert_eq!(1, explain.num_postings);
+    assert_eq!(
+        format!(
+            "file_id: {}, index: applied, row_groups: 1/3, postings: 1",
+            file_ids[0]
+        ),
+        explain.to_string()
+    );
+
+    let explain = &explains[&file_ids[1]];
+    assert!(explain.is_pruned());
+    assert_eq!(0, explain.num_postings);
+    assert_eq!(
+        format!(
+            "file_id: {}, index: pruned, row_groups: 0/2, postings: 0",
+            file_ids[1]
+        ),
+        explain.to_string()
+    );
+
+    let explain = &explains[&file_ids[2]];
+    assert!(!explain.index_available);
+    assert!(explain.is_full_scan());
+    assert_eq!(
+        format!(
+            "file_id: {}, index: full scan (no index), row_groups: 1/1",
+            file_ids[2]
+        ),
+        explain.to_string()
+    );
+
+    // The scan reports how it applies indexes in the same way.
+    let mut stream = engine.handle_query(region_id, request).await.unwrap();
+    while let Some(batch) = stream.next().await {
+        batch.unwrap();
+    }
+    let metrics = stream.metrics().unwrap();
+    assert_eq!(2, metrics.index_applied_files);
+    assert_eq!(1, metrics.index_selected_row_groups);
+    assert_eq!(1, metrics.index_postings);
+}
+
+#[tokio::test]
+async fn test_build_index_of_existing_sst() {
+    let mut env = TestEnv::new();
+    let config = MitoConfig::default();
+    let engine = env.create_engine(config.clone()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 15),
+        },
+    )
+    .await;
+    // The SST has no index.
+    flush_region(&engine, region_id, Some(5)).await;
+    let file_meta = |engine: &MitoEngine| {
+        let region = engine.get_region(region_id).unwrap();
+        let mut files: Vec<_> = region.version().ssts.levels()[0]
+            .files()
+            .map(|file| file.meta())
+            .collect();
+        assert_eq!(1, files.len());
+        files.pop().unwrap()
+    };
+    let old_meta = file_meta(&engine);
+    assert!(!old_meta.inverted_index_available());
+
+    assert_eq!(1, engine.build_index(region_id, false).await.unwrap());
+    let meta = file_meta(&engine);
+    assert_eq!(old_meta.file_id, meta.file_id);
+    assert_eq!(old_meta.file_size, meta.file_size);
+    assert!(meta.inverted_index_available());
+    assert_eq!(1, meta.index_stats.len());
+    // The index is built under a new name.
+    assert_ne!(meta.file_id, meta.index_file_id());
+    let index_path = location::index_file_path(&region_dir, meta.index_file_id());
+    let object_store = env.get_object_store().unwrap();
+    let index_file = object_store.stat(&index_path).await.unwrap();
+    assert_eq!(meta.index_file_size, index_file.content_length());
+
+    // Queries use the new index.
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("5")))],
+        ..Default::default()
+    };
+    let explains = engine
+        .explain_index(region_id, request.clone())
+        .await
+        .unwrap();
+    assert_eq!(1, explains.len());
+    assert!(explains[0].index_available);
+    assert_eq!(Some(1), explains[0].selected_row_groups);
+    assert_eq!(3, explains[0].total_row_groups);
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 5     | 5.0     | 1970-01-01T00:00:05 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // Skips the SST with an index, unless we overwrite the index.
+    assert_eq!(0, engine.build_index(region_id, false).await.unwrap());
+    assert_eq!(1, engine.build_index(region_id, true).await.unwrap());
+    let new_meta = file_meta(&engine);
+    assert_ne!(meta.index_file_id(), new_meta.index_file_id());
+    assert_eq!(
+        meta,
+        FileMeta {
+            index_file_id: meta.index_file_id,
+            ..new_meta.clone()
+        }
+    );
+    // The replaced index file is removed in background.
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while object_store.is_exist(&index_path).await.unwrap() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let meta = new_meta;
+
+    // The new meta is persisted in the manifest.
+    let engine = env.reopen_engine(engine, config).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Open(RegionOpenRequest {
+                engine: String::new(),
+                region_dir,
+                options: HashMap::default(),
+                skip_wal_replay: false,
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(meta, file_meta(&engine));
+}
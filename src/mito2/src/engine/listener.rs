@@ -14,6 +14,7 @@
 
 //! Engine event listener for tests.
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -31,6 +32,11 @@ pub trait EventListener: Send + Sync {
     /// Notifies the listener that the engine is stalled.
     fn on_write_stall(&self);
 
+    /// Notifies the listener that a worker writes a batch of `num_requests` write requests.
+    fn on_write_batch(&self, num_requests: usize) {
+        let _ = num_requests;
+    }
+
     /// Notifies the listener that the region starts to do flush.
     async fn on_flush_begin(&self, region_id: RegionId);
 
@@ -185,3 +191,36 @@ impl EventListener for DropListener {
         self.notify.notify_one();
     }
 }
+
+/// Listener to count write batches.
+#[derive(Default)]
+pub struct WriteBatchListener {
+    num_batches: AtomicUsize,
+    num_requests: AtomicUsize,
+}
+
+impl WriteBatchListener {
+    /// Returns the number of write batches.
+    pub fn num_batches(&self) -> usize {
+        self.num_batches.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of write requests in all batches.
+    pub fn num_requests(&self) -> usize {
+        self.num_requests.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl EventListener for WriteBatchListener {
+    fn on_flush_success(&self, _region_id: RegionId) {}
+
+    fn on_write_stall(&self) {}
+
+    async fn on_flush_begin(&self, _region_id: RegionId) {}
+
+    fn on_write_batch(&self, num_requests: usize) {
+        self.num_batches.fetch_add(1, Ordering::Relaxed);
+        self.num_requests.fetch_add(num_requests, Ordering::Relaxed);
+    }
+}
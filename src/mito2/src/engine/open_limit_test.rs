@@ -0,0 +1,156 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use store_api::region_engine::{RegionEngine, RegionRole};
+use store_api::region_request::{RegionCloseRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{build_rows, put_rows, rows_schema, CreateRequestBuilder, TestEnv};
+
+async fn create_region_with_rows(engine: &MitoEngine, region_id: RegionId, num_rows: usize) {
+    let request = CreateRequestBuilder::new()
+        .region_dir(&format!("test-{}", region_id.region_number()))
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(0, num_rows),
+    };
+    put_rows(engine, region_id, rows).await;
+}
+
+async fn scan_num_rows(engine: &MitoEngine, region_id: RegionId) -> usize {
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.iter().map(|b| b.num_rows()).sum()
+}
+
+/// Waits until the background task closes the region.
+async fn wait_evicted(engine: &MitoEngine, region_id: RegionId) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !engine.is_region_evicted(region_id) || engine.get_region(region_id).is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_close_least_recently_used_region() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            max_open_regions: 2,
+            ..Default::default()
+        })
+        .await;
+
+    let region_ids: Vec<_> = (1..=3).map(|i| RegionId::new(1, i)).collect();
+    create_region_with_rows(&engine, region_ids[0], 10).await;
+    create_region_with_rows(&engine, region_ids[1], 20).await;
+    // Accesses the first region so the second one is the least recently used.
+    assert_eq!(10, scan_num_rows(&engine, region_ids[0]).await);
+
+    create_region_with_rows(&engine, region_ids[2], 30).await;
+    wait_evicted(&engine, region_ids[1]).await;
+    assert!(!engine.is_region_evicted(region_ids[0]));
+    assert!(!engine.is_region_evicted(region_ids[2]));
+
+    // The closed region is still reported with its role and disk usage.
+    assert_eq!(Some(RegionRole::Leader), engine.role(region_ids[1]));
+    assert!(engine.region_disk_usage(region_ids[1]).await.is_some());
+
+    // Reading the closed region reopens it and closes the first region.
+    assert_eq!(20, scan_num_rows(&engine, region_ids[1]).await);
+    assert!(!engine.is_region_evicted(region_ids[1]));
+    // Memtables are flushed before closing the region.
+    let region = engine.get_region(region_ids[1]).unwrap();
+    assert!(region.is_writable());
+    let version = region.version();
+    assert_eq!(1, version.ssts.levels()[0].files().count());
+    assert!(version.memtables.mutable.is_empty());
+    wait_evicted(&engine, region_ids[0]).await;
+
+    // Writing to the closed region also reopens it.
+    let request = CreateRequestBuilder::new().build();
+    let rows = Rows {
+        schema: rows_schema(&request),
+        rows: build_rows(10, 15),
+    };
+    put_rows(&engine, region_ids[0], rows).await;
+    assert!(!engine.is_region_evicted(region_ids[0]));
+    wait_evicted(&engine, region_ids[2]).await;
+    assert_eq!(15, scan_num_rows(&engine, region_ids[0]).await);
+
+    // Closing the closed region only forgets it.
+    engine
+        .handle_request(region_ids[2], RegionRequest::Close(RegionCloseRequest {}))
+        .await
+        .unwrap();
+    assert!(!engine.is_region_evicted(region_ids[2]));
+    assert_eq!(None, engine.role(region_ids[2]));
+    assert!(engine
+        .handle_query(region_ids[2], ScanRequest::default())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_reopen_region_replays_wal() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            max_open_regions: 1,
+            ..Default::default()
+        })
+        .await;
+
+    let region_ids: Vec<_> = (1..=2).map(|i| RegionId::new(1, i)).collect();
+    create_region_with_rows(&engine, region_ids[0], 10).await;
+    // Followers can't flush, so the region is closed with rows in its memtable.
+    engine.set_writable(region_ids[0], false).unwrap();
+    create_region_with_rows(&engine, region_ids[1], 20).await;
+    wait_evicted(&engine, region_ids[0]).await;
+    assert_eq!(Some(RegionRole::Follower), engine.role(region_ids[0]));
+
+    // Reopening the region replays rows from the WAL and keeps its role.
+    assert_eq!(10, scan_num_rows(&engine, region_ids[0]).await);
+    let region = engine.get_region(region_ids[0]).unwrap();
+    assert!(!region.is_writable());
+    let version = region.version();
+    assert_eq!(0, version.ssts.levels()[0].files().count());
+    assert!(!version.memtables.mutable.is_empty());
+
+    // The second region is flushed before it is closed, nothing to replay.
+    wait_evicted(&engine, region_ids[1]).await;
+    assert_eq!(20, scan_num_rows(&engine, region_ids[1]).await);
+    assert_eq!(Some(RegionRole::Leader), engine.role(region_ids[1]));
+}
@@ -78,6 +78,10 @@ async fn test_scan_projection() {
         filters: Vec::new(),
         output_ordering: None,
         limit: None,
+        sample: None,
+        index_hint: None,
+        source_order: None,
+        follow: false,
     };
     let stream = engine.handle_query(region_id, request).await.unwrap();
     let batches = RecordBatches::try_collect(stream).await.unwrap();
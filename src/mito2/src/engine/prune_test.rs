@@ -20,7 +20,7 @@ use datafusion_common::ScalarValue;
 use datafusion_expr::{col, lit};
 use store_api::region_engine::RegionEngine;
 use store_api::region_request::RegionRequest;
-use store_api::storage::{RegionId, ScanRequest};
+use store_api::storage::{IndexHint, RegionId, ScanRequest};
 
 use crate::config::MitoConfig;
 use crate::test_util::{
@@ -266,3 +266,50 @@ async fn test_prune_memtable_complex_expr() {
 +-------+---------+---------------------+";
     assert_eq!(expected, batches.pretty_print().unwrap());
 }
+
+#[tokio::test]
+async fn test_index_hint() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let has_index_applier = |index_hint: Option<IndexHint>| {
+        let request = ScanRequest {
+            filters: vec![Expr::from(col("tag_0").eq(lit("5")))],
+            index_hint,
+            ..Default::default()
+        };
+        let scanner = engine.scanner(region_id, request).unwrap();
+        scanner.index_applier().is_some()
+    };
+
+    assert!(has_index_applier(None));
+    assert!(!has_index_applier(Some(IndexHint::NoIndex)));
+    assert!(has_index_applier(Some(IndexHint::UseIndex(vec![
+        "tag_0".to_string()
+    ]))));
+    // Only a field column, the hint is ignored.
+    assert!(has_index_applier(Some(IndexHint::UseIndex(vec![
+        "field_0".to_string()
+    ]))));
+    // The index of tag_0 is not in the hint.
+    let request = CreateRequestBuilder::new().tag_num(2).build();
+    let region_id = RegionId::new(1, 2);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit("5")))],
+        index_hint: Some(IndexHint::UseIndex(vec!["tag_1".to_string()])),
+        ..Default::default()
+    };
+    let scanner = engine.scanner(region_id, request).unwrap();
+    assert!(scanner.index_applier().is_none());
+}
@@ -0,0 +1,122 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use datatypes::prelude::ScalarVector;
+use datatypes::value::OrderedF64;
+use datatypes::vectors::TimestampMillisecondVector;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::RegionRequest;
+use store_api::storage::{RegionId, SampleMethod, ScanRequest, TableSample};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+/// Scans the region with the sample and returns timestamps of sampled rows.
+async fn scan_sample(
+    engine: &MitoEngine,
+    region_id: RegionId,
+    method: SampleMethod,
+    percentage: f64,
+    seed: u64,
+) -> Vec<i64> {
+    let request = ScanRequest {
+        sample: Some(TableSample {
+            method,
+            percentage: OrderedF64::from(percentage),
+            seed: Some(seed),
+        }),
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let mut res = Vec::new();
+    for batch in batches {
+        let ts_col = batch
+            .column_by_name("ts")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMillisecondVector>()
+            .unwrap();
+        res.extend(ts_col.iter_data().map(|t| t.unwrap().0.value()));
+    }
+    res
+}
+
+#[tokio::test]
+async fn test_scan_sample() {
+    let mut env = TestEnv::with_prefix("scan-sample");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Flushes 3 files and leaves the last 200 rows in the memtable.
+    for start in [0, 200, 400, 600] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows(start, start + 200),
+        };
+        put_rows(&engine, region_id, rows).await;
+        if start < 600 {
+            flush_region(&engine, region_id, None).await;
+        }
+    }
+
+    for method in [SampleMethod::Bernoulli, SampleMethod::System] {
+        assert!(scan_sample(&engine, region_id, method, 0.0, 42)
+            .await
+            .is_empty());
+        assert_eq!(
+            800,
+            scan_sample(&engine, region_id, method, 100.0, 42)
+                .await
+                .len()
+        );
+
+        // The same seed samples the same rows.
+        let sampled = scan_sample(&engine, region_id, method, 10.0, 42).await;
+        assert_eq!(
+            sampled,
+            scan_sample(&engine, region_id, method, 10.0, 42).await
+        );
+    }
+
+    let sampled = scan_sample(&engine, region_id, SampleMethod::Bernoulli, 10.0, 42).await;
+    assert!(
+        (40..=120).contains(&sampled.len()),
+        "sampled rows: {}",
+        sampled.len()
+    );
+
+    // Percentage out of range.
+    let request = ScanRequest {
+        sample: Some(TableSample {
+            method: SampleMethod::Bernoulli,
+            percentage: OrderedF64::from(101.0),
+            seed: None,
+        }),
+        ..Default::default()
+    };
+    assert!(engine.handle_query(region_id, request).await.is_err());
+}
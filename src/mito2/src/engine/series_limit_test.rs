@@ -0,0 +1,190 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::{ColumnSchema, Rows};
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{
+    RegionCloseRequest, RegionOpenRequest, RegionPutRequest, RegionRequest,
+};
+use store_api::storage::RegionId;
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows_for_key, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+fn rows_for_keys(column_schemas: &[ColumnSchema], keys: &[&str], ts: usize) -> Rows {
+    Rows {
+        schema: column_schemas.to_vec(),
+        rows: keys
+            .iter()
+            .flat_map(|key| build_rows_for_key(key, ts, ts + 1, 0))
+            .collect(),
+    }
+}
+
+async fn put_rows_status(engine: &MitoEngine, region_id: RegionId, rows: Rows) -> StatusCode {
+    engine
+        .handle_request(region_id, RegionRequest::Put(RegionPutRequest { rows }))
+        .await
+        .unwrap_err()
+        .status_code()
+}
+
+#[tokio::test]
+async fn test_series_limit() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("series_limit", "2")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Writes up to the limit.
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["a"], 0),
+    )
+    .await;
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["a", "b"], 1),
+    )
+    .await;
+    let region = engine.get_region(region_id).unwrap();
+    assert_eq!(2, region.series_limiter.num_series());
+
+    // Rejects the whole request if it creates a new series.
+    let rows = rows_for_keys(&column_schemas, &["a", "c"], 2);
+    assert_eq!(
+        StatusCode::RateLimited,
+        put_rows_status(&engine, region_id, rows).await
+    );
+    assert_eq!(2, region.series_limiter.num_series());
+    // Existing series still accept writes.
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["b", "a"], 3),
+    )
+    .await;
+
+    // Adjusts the limit at runtime.
+    engine.set_series_limit(region_id, Some(3)).unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["c"], 4),
+    )
+    .await;
+    let rows = rows_for_keys(&column_schemas, &["d"], 5);
+    assert_eq!(
+        StatusCode::RateLimited,
+        put_rows_status(&engine, region_id, rows).await
+    );
+    // Lowering the limit keeps existing series writable.
+    engine.set_series_limit(region_id, Some(1)).unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["a", "b", "c"], 6),
+    )
+    .await;
+
+    engine.set_series_limit(region_id, None).unwrap();
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["d", "e"], 7),
+    )
+    .await;
+    assert_eq!(0, region.series_limiter.num_series());
+}
+
+#[tokio::test]
+async fn test_load_series_on_open() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("series_limit", "2")
+        .build();
+    let region_dir = request.region_dir.clone();
+    let options = request.options.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // One series in the SST and the other one in the memtable.
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["a"], 0),
+    )
+    .await;
+    flush_region(&engine, region_id, None).await;
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["b"], 1),
+    )
+    .await;
+
+    engine
+        .handle_request(region_id, RegionRequest::Close(RegionCloseRequest {}))
+        .await
+        .unwrap();
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Open(RegionOpenRequest {
+                engine: String::new(),
+                region_dir,
+                options,
+                skip_wal_replay: false,
+            }),
+        )
+        .await
+        .unwrap();
+    engine.set_writable(region_id, true).unwrap();
+
+    // Series written before opening the region count towards the limit.
+    let region = engine.get_region(region_id).unwrap();
+    assert_eq!(2, region.series_limiter.num_series());
+    let rows = rows_for_keys(&column_schemas, &["c"], 2);
+    assert_eq!(
+        StatusCode::RateLimited,
+        put_rows_status(&engine, region_id, rows).await
+    );
+    put_rows(
+        &engine,
+        region_id,
+        rows_for_keys(&column_schemas, &["a", "b"], 3),
+    )
+    .await;
+}
@@ -0,0 +1,97 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::Rows;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_recordbatch::RecordBatches;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionOpenRequest, RegionRequest, RegionSyncRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::test_util::{build_rows, put_rows, rows_schema, CreateRequestBuilder, TestEnv};
+
+#[tokio::test]
+async fn test_sync_region() {
+    let mut env = TestEnv::with_prefix("sync-region");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Nothing written yet.
+    assert_eq!(0, engine.sync_region(region_id).await.unwrap());
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    assert_eq!(1, engine.sync_region(region_id).await.unwrap());
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    put_rows(&engine, region_id, rows).await;
+    assert_eq!(2, engine.sync_region(region_id).await.unwrap());
+    // Syncing again without new writes returns the same entry id.
+    assert_eq!(2, engine.sync_region(region_id).await.unwrap());
+    // Syncs by the region request.
+    engine
+        .handle_request(region_id, RegionRequest::Sync(RegionSyncRequest::default()))
+        .await
+        .unwrap();
+
+    let engine = env.reopen_engine(engine, MitoConfig::default()).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Open(RegionOpenRequest {
+                engine: String::new(),
+                region_dir,
+                options: HashMap::default(),
+                skip_wal_replay: false,
+            }),
+        )
+        .await
+        .unwrap();
+
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(5, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    assert_eq!(2, engine.sync_region(region_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_sync_region_not_found() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let err = engine.sync_region(RegionId::new(1, 1)).await.unwrap_err();
+    assert_eq!(StatusCode::RegionNotFound, err.status_code());
+}
@@ -0,0 +1,77 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::v1::Rows;
+use common_time::clock::MockClock;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionCompactRequest, RegionRequest};
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows_for_key, column_metadata_to_column_schema, flush_region, put_rows,
+    CreateRequestBuilder, TestEnv,
+};
+
+async fn compact_and_count_files(engine: &MitoEngine, region_id: RegionId) -> usize {
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    scanner.num_files()
+}
+
+#[tokio::test]
+async fn test_compaction_removes_expired_files() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let clock = Arc::new(MockClock::new(0));
+    let engine = env
+        .create_engine_with_clock(MitoConfig::default(), clock.clone())
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("ttl", "1h")
+        .build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("a", 0, 10, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    // Rows are written in the first 10 seconds so they are still alive.
+    assert_eq!(1, compact_and_count_files(&engine, region_id).await);
+
+    clock.advance(Duration::from_secs(2 * 3600));
+    assert_eq!(0, compact_and_count_files(&engine, region_id).await);
+}
@@ -14,6 +14,7 @@
 
 use std::any::Any;
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_datasource::compression::CompressionType;
 use common_error::ext::{BoxedError, ErrorExt};
@@ -26,7 +27,7 @@ use object_store::ErrorKind;
 use prost::{DecodeError, EncodeError};
 use snafu::{Location, Snafu};
 use store_api::manifest::ManifestVersion;
-use store_api::storage::RegionId;
+use store_api::storage::{ColumnId, RegionId};
 
 use crate::cache::file_cache::FileType;
 use crate::sst::file::FileId;
@@ -141,6 +142,23 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Failed to open SST {}, path: {}, region_dir: {}, scheme: {}",
+        file_id,
+        path,
+        region_dir,
+        scheme
+    ))]
+    OpenSst {
+        file_id: FileId,
+        path: String,
+        region_dir: String,
+        scheme: String,
+        #[snafu(source)]
+        error: object_store::Error,
+        location: Location,
+    },
+
     #[snafu(display("Region {} not found", region_id))]
     RegionNotFound {
         region_id: RegionId,
@@ -210,6 +228,12 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to sync WAL"))]
+    SyncWal {
+        location: Location,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to read WAL, region_id: {}", region_id))]
     ReadWal {
         region_id: RegionId,
@@ -364,6 +388,35 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Region {} has too many series, rejecting writes that create new series, limit: {}",
+        region_id,
+        limit
+    ))]
+    SeriesLimitExceeded {
+        region_id: RegionId,
+        limit: usize,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Region {} rejects duplicate rows, {} rows have the same primary key and timestamp as other rows in the request or the region",
+        region_id,
+        num_duplicates
+    ))]
+    DuplicateRows {
+        region_id: RegionId,
+        num_duplicates: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to sync WAL of region {}", region_id))]
+    SyncRegion {
+        region_id: RegionId,
+        source: Arc<Error>,
+        location: Location,
+    },
+
     #[snafu(display("Failed to compact region {}", region_id))]
     CompactRegion {
         region_id: RegionId,
@@ -394,6 +447,18 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Region {} is frozen", region_id))]
+    RegionFrozen {
+        region_id: RegionId,
+        location: Location,
+    },
+
+    #[snafu(display("Compaction of region {} is paused", region_id))]
+    CompactionPaused {
+        region_id: RegionId,
+        location: Location,
+    },
+
     #[snafu(display("Invalid options"))]
     JsonOptions {
         #[snafu(source)]
@@ -507,6 +572,16 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Failed to lock atomic write dir {dir}, check whether processes share the same data home"
+    ))]
+    AtomicWriteDirLocked {
+        dir: String,
+        #[snafu(source)]
+        error: std::io::Error,
+        location: Location,
+    },
+
     #[snafu(display("Invalid config, {reason}"))]
     InvalidConfig { reason: String, location: Location },
 
@@ -536,6 +611,119 @@ pub enum Error {
         error: std::io::Error,
         location: Location,
     },
+
+    #[snafu(display(
+        "Failed to copy file, region_id: {}, file_id: {}, file_type: {:?}",
+        region_id,
+        file_id,
+        file_type,
+    ))]
+    CopyFile {
+        region_id: RegionId,
+        file_id: FileId,
+        file_type: FileType,
+        #[snafu(source)]
+        error: std::io::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to scan rows to delete in region {}", region_id))]
+    ScanDeleteRange {
+        region_id: RegionId,
+        source: common_recordbatch::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to scan rows to check duplicates in region {}", region_id))]
+    ScanDuplicateRows {
+        region_id: RegionId,
+        source: common_recordbatch::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Subscriber of region {} lagged behind and missed {} writes",
+        region_id,
+        num_missed
+    ))]
+    SubscriberLagged {
+        region_id: RegionId,
+        num_missed: u64,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Index IO operation {} timed out after {:?}, path: {}",
+        op,
+        timeout,
+        path
+    ))]
+    IndexIoTimeout {
+        op: String,
+        path: String,
+        timeout: Duration,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Intermediate files of index creation exceed the limit of {} bytes, used: {}, to write: {}",
+        limit,
+        used,
+        len
+    ))]
+    IntermediateBytesExceeded {
+        limit: usize,
+        used: usize,
+        len: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid metadata of column {} in SST, reason: {}", column, reason))]
+    InvalidColumnMetadata {
+        column: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid encoding of column {}, reason: {}", column_id, reason))]
+    InvalidColumnEncoding {
+        column_id: ColumnId,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid {} compression level {}", codec, level))]
+    InvalidCompressionLevel {
+        codec: String,
+        level: i32,
+        #[snafu(source)]
+        error: parquet::errors::ParquetError,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Source of SST {} in region {} can't be replayed to estimate its size",
+        file_id,
+        region_id
+    ))]
+    SourceNotReplayable {
+        region_id: RegionId,
+        file_id: FileId,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "SST {} is corrupted, expect checksum {}, actual checksum {}",
+        file_id,
+        expected,
+        actual
+    ))]
+    SstCorrupted {
+        file_id: FileId,
+        expected: u32,
+        actual: u32,
+        location: Location,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -549,10 +737,36 @@ impl Error {
     /// Returns true if the file is not found on the object store.
     pub(crate) fn is_object_not_found(&self) -> bool {
         match self {
-            Error::OpenDal { error, .. } => error.kind() == ErrorKind::NotFound,
+            Error::OpenDal { error, .. } | Error::OpenSst { error, .. } => {
+                error.kind() == ErrorKind::NotFound
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the index IO operation failed temporarily and can be retried.
+    pub(crate) fn is_index_io_retryable(&self) -> bool {
+        match self {
+            Error::IndexIoTimeout { .. } => true,
+            Error::OpenDal { error, .. } => error.is_temporary(),
             _ => false,
         }
     }
+
+    /// Returns true if the error indicates the index file is missing or corrupt.
+    ///
+    /// Only the index file not found and errors to decode the index count, other
+    /// errors, e.g. timeouts, might be temporary so the index isn't rebuilt.
+    pub(crate) fn is_index_missing_or_corrupt(&self) -> bool {
+        match self {
+            Error::ApplyIndex { source, .. } => source.is_decode_error(),
+            Error::PuffinReadMetadata { source, .. } | Error::PuffinReadBlob { source, .. } => {
+                source.is_decode_error()
+            }
+            Error::PuffinBlobTypeNotFound { .. } => true,
+            _ => self.is_object_not_found(),
+        }
+    }
 }
 
 impl ErrorExt for Error {
@@ -561,8 +775,10 @@ impl ErrorExt for Error {
 
         match self {
             OpenDal { .. }
+            | OpenSst { .. }
             | ReadParquet { .. }
             | WriteWal { .. }
+            | SyncWal { .. }
             | ReadWal { .. }
             | DeleteWal { .. } => StatusCode::StorageUnavailable,
             CompressObject { .. }
@@ -613,10 +829,15 @@ impl ErrorExt for Error {
             RegionClosed { .. } => StatusCode::Cancelled,
             RegionTruncated { .. } => StatusCode::Cancelled,
             RejectWrite { .. } => StatusCode::StorageUnavailable,
+            SeriesLimitExceeded { .. } => StatusCode::RateLimited,
+            DuplicateRows { .. } => StatusCode::InvalidArguments,
             CompactRegion { source, .. } => source.status_code(),
+            SyncRegion { source, .. } => source.status_code(),
             CompatReader { .. } => StatusCode::Unexpected,
             InvalidRegionRequest { source, .. } => source.status_code(),
             RegionReadonly { .. } => StatusCode::RegionReadonly,
+            RegionFrozen { .. } => StatusCode::RegionFrozen,
+            CompactionPaused { .. } => StatusCode::Cancelled,
             JsonOptions { .. } => StatusCode::InvalidArguments,
             EmptyRegionDir { .. } | EmptyManifestDir { .. } => StatusCode::RegionNotFound,
             ArrowReader { .. } => StatusCode::StorageUnavailable,
@@ -630,9 +851,21 @@ impl ErrorExt for Error {
             | PuffinFinish { source, .. }
             | PuffinAddBlob { source, .. } => source.status_code(),
             CleanDir { .. } => StatusCode::Unexpected,
+            AtomicWriteDirLocked { .. } => StatusCode::Unexpected,
             InvalidConfig { .. } => StatusCode::InvalidArguments,
             StaleLogEntry { .. } => StatusCode::Unexpected,
             Upload { .. } => StatusCode::StorageUnavailable,
+            CopyFile { .. } => StatusCode::StorageUnavailable,
+            ScanDeleteRange { source, .. } => source.status_code(),
+            ScanDuplicateRows { source, .. } => source.status_code(),
+            SubscriberLagged { .. } => StatusCode::Cancelled,
+            IndexIoTimeout { .. } => StatusCode::StorageUnavailable,
+            IntermediateBytesExceeded { .. } => StatusCode::RuntimeResourcesExhausted,
+            InvalidColumnMetadata { .. } => StatusCode::InvalidArguments,
+            InvalidCompressionLevel { .. } => StatusCode::InvalidArguments,
+            InvalidColumnEncoding { .. } => StatusCode::InvalidArguments,
+            SourceNotReplayable { .. } => StatusCode::Unsupported,
+            SstCorrupted { .. } => StatusCode::Unexpected,
         }
     }
 
@@ -16,7 +16,8 @@
 
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use common_telemetry::{error, info};
 use smallvec::SmallVec;
@@ -33,14 +34,14 @@ use crate::error::{
 };
 use crate::memtable::MemtableBuilderRef;
 use crate::metrics::{FLUSH_BYTES_TOTAL, FLUSH_ELAPSED, FLUSH_ERRORS_TOTAL, FLUSH_REQUESTS_TOTAL};
-use crate::read::Source;
+use crate::read::{SharedBatchReader, Source};
 use crate::region::version::{VersionControlData, VersionControlRef, VersionRef};
 use crate::request::{
     BackgroundNotify, FlushFailed, FlushFinished, OptionOutputTx, OutputTx, SenderDdlRequest,
     SenderWriteRequest, WorkerRequest,
 };
 use crate::schedule::scheduler::{Job, SchedulerRef};
-use crate::sst::file::{FileId, FileMeta, IndexType};
+use crate::sst::file::{FileMeta, IndexType};
 use crate::sst::file_purger::FilePurgerRef;
 use crate::sst::parquet::WriteOptions;
 use crate::worker::WorkerListener;
@@ -87,6 +88,8 @@ pub struct WriteBufferManagerImpl {
     memory_used: AtomicUsize,
     /// Memory that hasn't been scheduled to free (e.g. used by mutable memtables).
     memory_active: AtomicUsize,
+    /// Policy to scale `mutable_limit` by the write rate.
+    adaptive_flush: Option<AdaptiveFlushPolicy>,
 }
 
 impl WriteBufferManagerImpl {
@@ -97,9 +100,17 @@ impl WriteBufferManagerImpl {
             mutable_limit: Self::get_mutable_limit(global_write_buffer_size),
             memory_used: AtomicUsize::new(0),
             memory_active: AtomicUsize::new(0),
+            adaptive_flush: None,
         }
     }
 
+    /// Scales the flush threshold of mutable memtables by the `policy`.
+    #[must_use]
+    pub fn with_adaptive_flush(mut self, policy: AdaptiveFlushPolicy) -> Self {
+        self.adaptive_flush = Some(policy);
+        self
+    }
+
     /// Returns memory usage of mutable memtables.
     pub fn mutable_usage(&self) -> usize {
         self.memory_active.load(Ordering::Relaxed)
@@ -110,15 +121,24 @@ impl WriteBufferManagerImpl {
         // Reserves half of the write buffer for mutable memtable.
         global_write_buffer_size / 2
     }
+
+    /// Returns the current size limit for mutable memtables.
+    fn current_mutable_limit(&self) -> usize {
+        match &self.adaptive_flush {
+            Some(policy) => policy.flush_limit(self.mutable_limit, Instant::now()),
+            None => self.mutable_limit,
+        }
+    }
 }
 
 impl WriteBufferManager for WriteBufferManagerImpl {
     fn should_flush_engine(&self) -> bool {
         let mutable_memtable_memory_usage = self.memory_active.load(Ordering::Relaxed);
-        if mutable_memtable_memory_usage > self.mutable_limit {
+        let mutable_limit = self.current_mutable_limit();
+        if mutable_memtable_memory_usage > mutable_limit {
             info!(
                 "Engine should flush (over mutable limit), mutable_usage: {}, memory_usage: {}, mutable_limit: {}, global_limit: {}",
-                mutable_memtable_memory_usage, self.memory_usage(), self.mutable_limit, self.global_write_buffer_size,
+                mutable_memtable_memory_usage, self.memory_usage(), mutable_limit, self.global_write_buffer_size,
             );
             return true;
         }
@@ -150,6 +170,9 @@ impl WriteBufferManager for WriteBufferManagerImpl {
     fn reserve_mem(&self, mem: usize) {
         self.memory_used.fetch_add(mem, Ordering::Relaxed);
         self.memory_active.fetch_add(mem, Ordering::Relaxed);
+        if let Some(policy) = &self.adaptive_flush {
+            policy.record_write(mem, Instant::now());
+        }
     }
 
     fn schedule_free_mem(&self, mem: usize) {
@@ -165,6 +188,116 @@ impl WriteBufferManager for WriteBufferManagerImpl {
     }
 }
 
+/// Policy to scale the flush threshold by the recent write rate.
+///
+/// The threshold is lower under write rates higher than the base rate, so memtables
+/// of write bursts are flushed before they grow too large. It is higher under lower
+/// write rates, so steady writes are flushed into fewer but larger SSTs.
+#[derive(Debug)]
+pub struct AdaptiveFlushPolicy {
+    /// Write rate in bytes per second to keep the base threshold.
+    base_rate: f64,
+    /// Min percentage of the base threshold.
+    min_percent: f64,
+    /// Max percentage of the base threshold.
+    max_percent: f64,
+    meter: Mutex<WriteRateMeter>,
+}
+
+impl AdaptiveFlushPolicy {
+    /// Returns a new policy with adaptive flush options in the `config`.
+    pub fn new(config: &MitoConfig) -> Self {
+        Self {
+            base_rate: config.adaptive_flush_base_rate.as_bytes() as f64,
+            min_percent: config.adaptive_flush_min_percent as f64,
+            max_percent: config.adaptive_flush_max_percent as f64,
+            meter: Mutex::new(WriteRateMeter::new(config.adaptive_flush_window)),
+        }
+    }
+
+    /// Records `bytes` written at `now`.
+    fn record_write(&self, bytes: usize, now: Instant) {
+        self.meter.lock().unwrap().record(bytes, now);
+    }
+
+    /// Returns the threshold scaled from the `base_limit` by the write rate at `now`.
+    fn flush_limit(&self, base_limit: usize, now: Instant) -> usize {
+        let rate = self.meter.lock().unwrap().rate(now);
+        let percent = if rate > 0.0 {
+            self.base_rate / rate * 100.0
+        } else {
+            self.max_percent
+        };
+        let percent = percent.clamp(self.min_percent, self.max_percent);
+        (base_limit as f64 * percent / 100.0) as usize
+    }
+}
+
+/// Measures the write rate in a sliding window.
+///
+/// It counts bytes in fixed windows and estimates bytes in the sliding window by
+/// weighting the previous window by its overlap with the sliding window.
+#[derive(Debug)]
+struct WriteRateMeter {
+    window: Duration,
+    /// Start of the current fixed window.
+    window_start: Option<Instant>,
+    /// Bytes written in the current fixed window.
+    current_bytes: usize,
+    /// Bytes written in the previous fixed window.
+    previous_bytes: usize,
+}
+
+impl WriteRateMeter {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            window_start: None,
+            current_bytes: 0,
+            previous_bytes: 0,
+        }
+    }
+
+    fn record(&mut self, bytes: usize, now: Instant) {
+        self.advance(now);
+        self.current_bytes += bytes;
+    }
+
+    /// Returns the write rate in bytes per second at `now`.
+    fn rate(&mut self, now: Instant) -> f64 {
+        self.advance(now);
+        let Some(window_start) = self.window_start else {
+            return 0.0;
+        };
+        let window = self.window.as_secs_f64();
+        let elapsed = now.saturating_duration_since(window_start).as_secs_f64();
+        let previous_weight = (1.0 - elapsed / window).max(0.0);
+        (self.previous_bytes as f64 * previous_weight + self.current_bytes as f64) / window
+    }
+
+    /// Moves the current window forward to contain `now`.
+    fn advance(&mut self, now: Instant) {
+        let Some(window_start) = self.window_start else {
+            self.window_start = Some(now);
+            return;
+        };
+        let elapsed = now.saturating_duration_since(window_start);
+        if elapsed < self.window {
+            return;
+        }
+
+        if elapsed < self.window * 2 {
+            self.previous_bytes = self.current_bytes;
+            self.window_start = Some(window_start + self.window);
+        } else {
+            // Nothing is written in the previous window.
+            self.previous_bytes = 0;
+            self.window_start = Some(now);
+        }
+        self.current_bytes = 0;
+    }
+}
+
 /// Reason of a flush task.
 #[derive(Debug, IntoStaticStr)]
 pub enum FlushReason {
@@ -295,9 +428,13 @@ impl RegionFlushTask {
             .with_label_values(&["flush_memtables"])
             .start_timer();
 
+        let engine_config = &self.engine_config;
         let mut write_opts = WriteOptions {
-            write_buffer_size: self.engine_config.sst_write_buffer_size,
-            ..Default::default()
+            max_file_size: (engine_config.flush_target_file_size.as_bytes() > 0)
+                .then_some(engine_config.flush_target_file_size.as_bytes() as usize),
+            target_num_rows: (engine_config.flush_target_file_rows > 0)
+                .then_some(engine_config.flush_target_file_rows),
+            ..engine_config.sst_write_options(&version.metadata)
         };
         if let Some(row_group_size) = self.row_group_size {
             write_opts.row_group_size = row_group_size;
@@ -312,41 +449,59 @@ impl RegionFlushTask {
                 continue;
             }
 
-            let file_id = FileId::random();
             let iter = mem.iter(None, None);
-            let source = Source::Iter(iter);
-
-            // Flush to level 0.
-            let write_request = SstWriteRequest {
-                file_id,
-                metadata: version.metadata.clone(),
-                source,
-                cache_manager: self.cache_manager.clone(),
-                storage: version.options.storage.clone(),
-            };
-            let Some(sst_info) = self
-                .access_layer
-                .write_sst(write_request, &write_opts)
-                .await?
-            else {
-                // No data written.
-                continue;
-            };
+            let reader = SharedBatchReader::new(Source::Iter(iter));
+
+            // The writer rolls over to new files once a file reaches the max size. Each
+            // writer stops at the target number of rows, the next writer writes remaining
+            // rows until the memtable is exhausted. Rows are still sorted across files as
+            // they are written in order.
+            loop {
+                let file_id = self.access_layer.new_file_id();
+                // Flush to level 0.
+                let write_request = SstWriteRequest {
+                    file_id,
+                    metadata: version.metadata.clone(),
+                    source: Source::Reader(Box::new(reader.clone())),
+                    cache_manager: self.cache_manager.clone(),
+                    storage: version.options.storage.clone(),
+                    index_columns: version.options.index_columns.clone(),
+                    column_metadata: self.engine_config.sst_column_metadata(&version.metadata),
+                    source_size: Some(mem.stats().bytes_allocated() as u64),
+                };
+                let sst_infos = self
+                    .access_layer
+                    .write_sst(write_request, &write_opts)
+                    .await?;
+                if sst_infos.is_empty() {
+                    // No data written.
+                    break;
+                }
 
-            flushed_bytes += sst_info.file_size;
-            let file_meta = FileMeta {
-                region_id: self.region_id,
-                file_id,
-                time_range: sst_info.time_range,
-                level: 0,
-                file_size: sst_info.file_size,
-                available_indexes: sst_info
-                    .inverted_index_available
-                    .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
-                    .unwrap_or_default(),
-                index_file_size: sst_info.index_file_size,
-            };
-            file_metas.push(file_meta);
+                for sst_info in sst_infos {
+                    flushed_bytes += sst_info.file_size;
+                    let file_meta = FileMeta {
+                        region_id: self.region_id,
+                        file_id: sst_info.file_id,
+                        time_range: sst_info.time_range,
+                        level: 0,
+                        file_size: sst_info.file_size,
+                        available_indexes: sst_info
+                            .inverted_index_available
+                            .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
+                            .unwrap_or_default(),
+                        index_file_size: sst_info.index_file_size,
+                        rolled_up: false,
+                        index_stats: sst_info.index_stats,
+                        footer_checksum: Some(sst_info.footer_checksum),
+                        index_file_id: None,
+                    };
+                    file_metas.push(file_meta);
+                }
+                if !write_opts.has_target() {
+                    break;
+                }
+            }
         }
 
         if !file_metas.is_empty() {
@@ -670,6 +825,7 @@ impl FlushStatus {
 
 #[cfg(test)]
 mod tests {
+    use common_base::readable_size::ReadableSize;
     use tokio::sync::oneshot;
 
     use super::*;
@@ -730,6 +886,98 @@ mod tests {
         assert!(manager.should_flush_engine());
     }
 
+    /// Writes `bytes` every `interval` for `num_writes` times, flushes all memory once it
+    /// exceeds the limit of the `policy` and returns the number of flushes.
+    fn simulate_flushes(
+        policy: &AdaptiveFlushPolicy,
+        start: Instant,
+        bytes: usize,
+        interval: Duration,
+        num_writes: usize,
+    ) -> usize {
+        let mut usage = 0;
+        let mut num_flushes = 0;
+        for i in 0..num_writes {
+            let now = start + interval * i as u32;
+            policy.record_write(bytes, now);
+            usage += bytes;
+            if usage > policy.flush_limit(1000, now) {
+                num_flushes += 1;
+                usage = 0;
+            }
+        }
+        num_flushes
+    }
+
+    fn new_adaptive_flush_policy() -> AdaptiveFlushPolicy {
+        AdaptiveFlushPolicy::new(&MitoConfig {
+            adaptive_flush: true,
+            adaptive_flush_window: Duration::from_secs(10),
+            adaptive_flush_base_rate: ReadableSize(100),
+            adaptive_flush_min_percent: 25,
+            adaptive_flush_max_percent: 150,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_adaptive_flush_limit() {
+        let policy = new_adaptive_flush_policy();
+        let start = Instant::now();
+        // Uses the max limit without writes.
+        assert_eq!(1500, policy.flush_limit(1000, start));
+
+        // 1000 bytes in the window, which is the base rate.
+        policy.record_write(1000, start);
+        assert_eq!(1000, policy.flush_limit(1000, start));
+        // 10000 bytes in the window.
+        policy.record_write(9000, start);
+        assert_eq!(250, policy.flush_limit(1000, start));
+
+        // 20% of the previous window is in the sliding window.
+        assert_eq!(
+            500,
+            policy.flush_limit(1000, start + Duration::from_secs(18))
+        );
+        // Back to the max limit after idle.
+        assert_eq!(
+            1500,
+            policy.flush_limit(1000, start + Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_adaptive_flush_frequency() {
+        // A burst writes 2000 bytes in 200ms.
+        let policy = new_adaptive_flush_policy();
+        let start = Instant::now();
+        let burst_flushes = simulate_flushes(&policy, start, 100, Duration::from_millis(10), 20);
+        // Steady writes of the same bytes in 200s.
+        let policy = new_adaptive_flush_policy();
+        let steady_flushes = simulate_flushes(&policy, start, 100, Duration::from_secs(10), 20);
+        assert_eq!(2, burst_flushes);
+        assert_eq!(1, steady_flushes);
+    }
+
+    #[test]
+    fn test_adaptive_flush_burst_then_idle() {
+        // A burst of 1200 bytes, which is under the limit of steady writes.
+        let policy = new_adaptive_flush_policy();
+        let start = Instant::now();
+        assert_eq!(
+            1,
+            simulate_flushes(&policy, start, 100, Duration::from_millis(10), 12)
+        );
+
+        // The threshold is raised after idle.
+        let now = start + Duration::from_secs(60);
+        assert_eq!(1500, policy.flush_limit(1000, now));
+        assert_eq!(
+            0,
+            simulate_flushes(&policy, now, 100, Duration::from_secs(10), 12)
+        );
+    }
+
     #[tokio::test]
     async fn test_schedule_empty() {
         let env = SchedulerEnv::new();
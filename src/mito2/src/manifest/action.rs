@@ -23,6 +23,7 @@ use store_api::manifest::ManifestVersion;
 use store_api::metadata::RegionMetadataRef;
 use store_api::storage::{RegionId, SequenceNumber};
 
+use crate::config::SstPathLayout;
 use crate::error::{RegionMetadataNotFoundSnafu, Result, SerdeJsonSnafu, Utf8Snafu};
 use crate::sst::file::{FileId, FileMeta};
 use crate::wal::EntryId;
@@ -38,12 +39,17 @@ pub enum RegionMetaAction {
     Remove(RegionRemove),
     /// Truncate the region.
     Truncate(RegionTruncate),
+    /// Freeze or unfreeze the region.
+    Freeze(RegionFreeze),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RegionChange {
     /// The metadata after changed.
     pub metadata: RegionMetadataRef,
+    /// Layout of SSTs in the region, only set when the region is created.
+    #[serde(default)]
+    pub sst_path_layout: Option<SstPathLayout>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -71,6 +77,13 @@ pub struct RegionTruncate {
     pub truncated_sequence: SequenceNumber,
 }
 
+/// Frozen state of the region.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionFreeze {
+    /// Whether the region rejects writes and compactions.
+    pub frozen: bool,
+}
+
 /// The region manifest data.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RegionManifest {
@@ -89,6 +102,13 @@ pub struct RegionManifest {
     /// Inferred compaction time window.
     #[serde(with = "humantime_serde")]
     pub compaction_time_window: Option<Duration>,
+    /// Layout of SSTs in the region, regions created before the layout is persisted
+    /// are flat.
+    #[serde(default)]
+    pub sst_path_layout: SstPathLayout,
+    /// Whether the region is frozen. It's omitted if the region is not frozen.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub frozen: bool,
 }
 
 #[derive(Debug, Default)]
@@ -100,6 +120,8 @@ pub struct RegionManifestBuilder {
     manifest_version: ManifestVersion,
     truncated_entry_id: Option<EntryId>,
     compaction_time_window: Option<Duration>,
+    sst_path_layout: SstPathLayout,
+    frozen: bool,
 }
 
 impl RegionManifestBuilder {
@@ -114,6 +136,8 @@ impl RegionManifestBuilder {
                 flushed_sequence: s.flushed_sequence,
                 truncated_entry_id: s.truncated_entry_id,
                 compaction_time_window: s.compaction_time_window,
+                sst_path_layout: s.sst_path_layout,
+                frozen: s.frozen,
             }
         } else {
             Default::default()
@@ -122,6 +146,9 @@ impl RegionManifestBuilder {
 
     pub fn apply_change(&mut self, manifest_version: ManifestVersion, change: RegionChange) {
         self.metadata = Some(change.metadata);
+        if let Some(sst_path_layout) = change.sst_path_layout {
+            self.sst_path_layout = sst_path_layout;
+        }
         self.manifest_version = manifest_version;
     }
 
@@ -152,6 +179,11 @@ impl RegionManifestBuilder {
         self.files.clear();
     }
 
+    pub fn apply_freeze(&mut self, manifest_version: ManifestVersion, freeze: RegionFreeze) {
+        self.manifest_version = manifest_version;
+        self.frozen = freeze.frozen;
+    }
+
     /// Check if the builder keeps a [RegionMetadata](store_api::metadata::RegionMetadata).
     pub fn contains_metadata(&self) -> bool {
         self.metadata.is_some()
@@ -167,6 +199,8 @@ impl RegionManifestBuilder {
             manifest_version: self.manifest_version,
             truncated_entry_id: self.truncated_entry_id,
             compaction_time_window: self.compaction_time_window,
+            sst_path_layout: self.sst_path_layout,
+            frozen: self.frozen,
         })
     }
 }
@@ -232,10 +266,35 @@ impl RegionMetaActionList {
     }
 }
 
+/// A consistent view of the region manifest for inspection.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RegionManifestSnapshot {
+    /// Current state of the manifest.
+    pub manifest: RegionManifest,
+    /// Action lists not compacted into the checkpoint yet, sorted by version.
+    pub recent_actions: Vec<VersionedActionList>,
+}
+
+impl RegionManifestSnapshot {
+    /// Encodes the snapshot into pretty printed json.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self).context(SerdeJsonSnafu)
+    }
+}
+
+/// A [RegionMetaActionList] with its manifest version.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct VersionedActionList {
+    pub version: ManifestVersion,
+    pub action_list: RegionMetaActionList,
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
 
     use super::*;
+    use crate::manifest::tests::utils::basic_region_metadata;
 
     #[test]
     fn test_encode_decode_action_list() {
@@ -293,6 +352,61 @@ mod tests {
         // TODO(ruihang): port this test case
     }
 
+    #[test]
+    fn test_sst_path_layout() {
+        let metadata = Arc::new(basic_region_metadata());
+        let mut builder = RegionManifestBuilder::default();
+        builder.apply_change(
+            0,
+            RegionChange {
+                metadata: metadata.clone(),
+                sst_path_layout: Some(SstPathLayout::Date),
+            },
+        );
+        // Altering the region keeps the layout.
+        builder.apply_change(
+            1,
+            RegionChange {
+                metadata,
+                sst_path_layout: None,
+            },
+        );
+        let manifest = builder.try_build().unwrap();
+        assert_eq!(SstPathLayout::Date, manifest.sst_path_layout);
+
+        // Regions created before the layout is persisted are flat.
+        let mut json = serde_json::to_value(&manifest).unwrap();
+        json.as_object_mut().unwrap().remove("sst_path_layout");
+        let manifest: RegionManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(SstPathLayout::Flat, manifest.sst_path_layout);
+    }
+
+    #[test]
+    fn test_region_freeze() {
+        let mut builder = RegionManifestBuilder::default();
+        builder.apply_change(
+            0,
+            RegionChange {
+                metadata: Arc::new(basic_region_metadata()),
+                sst_path_layout: None,
+            },
+        );
+        let manifest = builder.try_build().unwrap();
+        assert!(!manifest.frozen);
+        // Manifests of regions never frozen stay the same.
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert!(!json.as_object().unwrap().contains_key("frozen"));
+
+        let mut builder = RegionManifestBuilder::with_checkpoint(Some(manifest));
+        builder.apply_freeze(1, RegionFreeze { frozen: true });
+        let manifest = builder.try_build().unwrap();
+        assert!(manifest.frozen);
+        assert_eq!(1, manifest.manifest_version);
+        let json = serde_json::to_string(&manifest).unwrap();
+        let decoded: RegionManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(manifest, decoded);
+    }
+
     #[test]
     fn test_encode_decode_region_checkpoint() {
         // TODO(ruihang): port this test case
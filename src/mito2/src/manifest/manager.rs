@@ -23,10 +23,11 @@ use store_api::manifest::{ManifestVersion, MAX_VERSION, MIN_VERSION};
 use store_api::metadata::RegionMetadataRef;
 use tokio::sync::RwLock;
 
+use crate::config::SstPathLayout;
 use crate::error::{self, Result};
 use crate::manifest::action::{
-    RegionChange, RegionCheckpoint, RegionManifest, RegionManifestBuilder, RegionMetaAction,
-    RegionMetaActionList,
+    RegionChange, RegionCheckpoint, RegionManifest, RegionManifestBuilder, RegionManifestSnapshot,
+    RegionMetaAction, RegionMetaActionList, VersionedActionList,
 };
 use crate::manifest::storage::{file_version, is_delta_file, ManifestObjectStore};
 
@@ -40,6 +41,9 @@ pub struct RegionManifestOptions {
     /// Interval of version ([ManifestVersion](store_api::manifest::ManifestVersion)) between two checkpoints.
     /// Set to 0 to disable checkpoint.
     pub checkpoint_distance: u64,
+    /// Number of entries in a page to list manifest files, 0 means using the default
+    /// page size of the store.
+    pub list_page_size: usize,
 }
 
 // rewrite note:
@@ -68,6 +72,7 @@ pub struct RegionManifestOptions {
 /// }
 /// class RegionChange {
 ///     -RegionMetadataRef metadata
+///     -Option~SstPathLayout~ sst_path_layout
 /// }
 /// class RegionEdit {
 ///     -VersionNumber regoin_version
@@ -114,9 +119,13 @@ pub struct RegionManifestManager {
 }
 
 impl RegionManifestManager {
-    /// Construct a region's manifest and persist it.
-    pub async fn new(metadata: RegionMetadataRef, options: RegionManifestOptions) -> Result<Self> {
-        let inner = RegionManifestManagerInner::new(metadata, options).await?;
+    /// Construct a region's manifest with the layout of its SSTs and persist it.
+    pub async fn new(
+        metadata: RegionMetadataRef,
+        sst_path_layout: SstPathLayout,
+        options: RegionManifestOptions,
+    ) -> Result<Self> {
+        let inner = RegionManifestManagerInner::new(metadata, sst_path_layout, options).await?;
         Ok(Self {
             inner: RwLock::new(inner),
         })
@@ -151,6 +160,14 @@ impl RegionManifestManager {
         inner.manifest.clone()
     }
 
+    /// Retrieve the current [RegionManifest] with the actions since the last checkpoint.
+    pub async fn snapshot(&self) -> Result<RegionManifestSnapshot> {
+        // Updates hold the write lock until the new manifest is applied, so we never
+        // observe a half applied edit.
+        let inner = self.inner.read().await;
+        inner.snapshot().await
+    }
+
     #[cfg(test)]
     pub async fn store(&self) -> ManifestObjectStore {
         let inner = self.inner.read().await;
@@ -198,13 +215,18 @@ pub(crate) struct RegionManifestManagerInner {
 
 impl RegionManifestManagerInner {
     /// Creates a new manifest.
-    async fn new(metadata: RegionMetadataRef, options: RegionManifestOptions) -> Result<Self> {
+    async fn new(
+        metadata: RegionMetadataRef,
+        sst_path_layout: SstPathLayout,
+        options: RegionManifestOptions,
+    ) -> Result<Self> {
         // construct storage
         let mut store = ManifestObjectStore::new(
             &options.manifest_dir,
             options.object_store.clone(),
             options.compress_type,
-        );
+        )
+        .with_list_page_size(options.list_page_size);
 
         info!(
             "Creating region manifest in {} with metadata {:?}",
@@ -214,12 +236,11 @@ impl RegionManifestManagerInner {
         let version = MIN_VERSION;
         let mut manifest_builder = RegionManifestBuilder::default();
         // set the initial metadata.
-        manifest_builder.apply_change(
-            version,
-            RegionChange {
-                metadata: metadata.clone(),
-            },
-        );
+        let change = RegionChange {
+            metadata,
+            sst_path_layout: Some(sst_path_layout),
+        };
+        manifest_builder.apply_change(version, change.clone());
         let manifest = manifest_builder.try_build()?;
 
         debug!(
@@ -228,8 +249,7 @@ impl RegionManifestManagerInner {
         );
 
         // Persist region change.
-        let action_list =
-            RegionMetaActionList::with_action(RegionMetaAction::Change(RegionChange { metadata }));
+        let action_list = RegionMetaActionList::with_action(RegionMetaAction::Change(change));
         store.save(version, &action_list.encode()?).await?;
 
         Ok(Self {
@@ -250,7 +270,8 @@ impl RegionManifestManagerInner {
             &options.manifest_dir,
             options.object_store.clone(),
             options.compress_type,
-        );
+        )
+        .with_list_page_size(options.list_page_size);
 
         // recover from storage
         // construct manifest builder
@@ -301,6 +322,9 @@ impl RegionManifestManagerInner {
                     RegionMetaAction::Truncate(action) => {
                         manifest_builder.apply_truncate(manifest_version, action);
                     }
+                    RegionMetaAction::Freeze(action) => {
+                        manifest_builder.apply_freeze(manifest_version, action);
+                    }
                 }
             }
         }
@@ -355,6 +379,9 @@ impl RegionManifestManagerInner {
                 RegionMetaAction::Truncate(action) => {
                     manifest_builder.apply_truncate(version, action);
                 }
+                RegionMetaAction::Freeze(action) => {
+                    manifest_builder.apply_freeze(version, action);
+                }
             }
         }
         let new_manifest = manifest_builder.try_build()?;
@@ -364,6 +391,31 @@ impl RegionManifestManagerInner {
         Ok(version)
     }
 
+    /// Returns the current manifest with the actions since the last checkpoint.
+    async fn snapshot(&self) -> Result<RegionManifestSnapshot> {
+        let entries = self
+            .store
+            .scan(self.last_checkpoint_version + 1, self.last_version + 1)
+            .await?;
+        let recent_actions = self
+            .store
+            .fetch_manifests(&entries)
+            .await?
+            .into_iter()
+            .map(|(version, bytes)| {
+                Ok(VersionedActionList {
+                    version,
+                    action_list: RegionMetaActionList::decode(&bytes)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(RegionManifestSnapshot {
+            manifest: self.manifest.as_ref().clone(),
+            recent_actions,
+        })
+    }
+
     /// Returns total manifest size.
     pub(crate) fn total_manifest_size(&self) -> u64 {
         self.store.total_manifest_size()
@@ -437,6 +489,9 @@ impl RegionManifestManagerInner {
                     RegionMetaAction::Truncate(action) => {
                         manifest_builder.apply_truncate(version, action);
                     }
+                    RegionMetaAction::Freeze(action) => {
+                        manifest_builder.apply_freeze(version, action);
+                    }
                 }
             }
             last_version = version;
@@ -592,6 +647,7 @@ mod test {
         let action_list =
             RegionMetaActionList::with_action(RegionMetaAction::Change(RegionChange {
                 metadata: new_metadata.clone(),
+                sst_path_layout: None,
             }));
 
         let current_version = manager.update(action_list).await.unwrap();
@@ -654,6 +710,7 @@ mod test {
         let action_list =
             RegionMetaActionList::with_action(RegionMetaAction::Change(RegionChange {
                 metadata: new_metadata.clone(),
+                sst_path_layout: None,
             }));
 
         let current_version = manager.update(action_list).await.unwrap();
@@ -696,6 +753,6 @@ mod test {
 
         // get manifest size again
         let manifest_size = manager.manifest_usage().await;
-        assert_eq!(manifest_size, 1312);
+        assert_eq!(manifest_size, 1337);
     }
 }
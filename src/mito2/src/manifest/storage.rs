@@ -120,6 +120,9 @@ pub struct ManifestObjectStore {
     path: String,
     /// Stores the size of each manifest file.
     manifest_size_map: HashMap<FileKey, u64>,
+    /// Number of entries in a page to list manifest files, `None` means using the
+    /// default page size of the store.
+    list_page_size: Option<usize>,
 }
 
 impl ManifestObjectStore {
@@ -129,9 +132,17 @@ impl ManifestObjectStore {
             compress_type,
             path: util::normalize_dir(path),
             manifest_size_map: HashMap::new(),
+            list_page_size: None,
         }
     }
 
+    /// Sets the number of entries in a page to list manifest files, 0 means using the
+    /// default page size of the store.
+    pub fn with_list_page_size(mut self, list_page_size: usize) -> Self {
+        self.list_page_size = util::list_page_size(list_page_size);
+        self
+    }
+
     /// Returns the delta file path under the **current** compression algorithm
     fn delta_file_path(&self, version: ManifestVersion) -> String {
         gen_path(&self.path, &delta_file(version), self.compress_type)
@@ -155,7 +166,12 @@ impl ManifestObjectStore {
 
     /// Returns a iterator of manifests.
     pub(crate) async fn manifest_lister(&self) -> Result<Option<Lister>> {
-        match self.object_store.lister_with(&self.path).await {
+        let lister = self.object_store.lister_with(&self.path);
+        let lister = match self.list_page_size {
+            Some(page_size) => lister.limit(page_size),
+            None => lister,
+        };
+        match lister.await {
             Ok(streamer) => Ok(Some(streamer)),
             Err(e) if e.kind() == ErrorKind::NotFound => {
                 debug!("Manifest directory does not exists: {}", self.path);
@@ -530,7 +546,8 @@ impl CheckpointMetadata {
 #[cfg(test)]
 mod tests {
     use common_test_util::temp_dir::create_temp_dir;
-    use object_store::services::Fs;
+    use object_store::services::{Fs, Memory};
+    use object_store::test_util::ListPageLayer;
     use object_store::ObjectStore;
 
     use super::*;
@@ -781,4 +798,37 @@ mod tests {
 
         assert_eq!(log_store.total_manifest_size(), 0);
     }
+
+    #[tokio::test]
+    async fn test_manifest_list_page_size() {
+        let page_layer = ListPageLayer::new(10);
+        let object_store = ObjectStore::new(Memory::default())
+            .unwrap()
+            .finish()
+            .layer(page_layer.clone());
+        let mut log_store =
+            ManifestObjectStore::new("/", object_store.clone(), CompressionType::Uncompressed);
+        for v in 0..50 {
+            log_store.save(v, b"hello").await.unwrap();
+        }
+
+        // Returns the number of manifests and pages to list the manifest directory.
+        let scan = |page_size| {
+            let log_store =
+                ManifestObjectStore::new("/", object_store.clone(), CompressionType::Uncompressed)
+                    .with_list_page_size(page_size);
+            let page_layer = page_layer.clone();
+            async move {
+                let pages = page_layer.num_pages();
+                let manifests = log_store.scan(0, 50).await.unwrap();
+                (manifests.len(), page_layer.num_pages() - pages)
+            }
+        };
+
+        let (num_manifests, default_pages) = scan(0).await;
+        assert_eq!(50, num_manifests);
+        let (num_manifests, large_pages) = scan(25).await;
+        assert_eq!(50, num_manifests);
+        assert!(large_pages < default_pages, "{large_pages} {default_pages}");
+    }
 }
@@ -173,6 +173,10 @@ async fn checkpoint_with_different_compression_types() {
             file_size: 1024000,
             available_indexes: Default::default(),
             index_file_size: 0,
+            rolled_up: false,
+            index_stats: Vec::new(),
+            footer_checksum: None,
+            index_file_id: None,
         };
         let action = RegionMetaActionList::new(vec![RegionMetaAction::Edit(RegionEdit {
             files_to_add: vec![file_meta],
@@ -33,6 +33,7 @@ use crate::flush::WriteBufferManagerRef;
 pub use crate::memtable::key_values::KeyValues;
 use crate::metrics::WRITE_BUFFER_BYTES;
 use crate::read::Batch;
+use crate::region::options::DuplicateMode;
 
 /// Id for memtables.
 ///
@@ -45,6 +46,8 @@ pub struct MemtableStats {
     estimated_bytes: usize,
     /// The time range that this memtable contains.
     time_range: Option<(Timestamp, Timestamp)>,
+    /// Wall clock time in millis of the first write to this memtable.
+    first_write_millis: Option<i64>,
 }
 
 impl MemtableStats {
@@ -57,6 +60,11 @@ impl MemtableStats {
     pub fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
         self.time_range
     }
+
+    /// Returns the wall clock time in millis of the first write to the memtable.
+    pub fn first_write_millis(&self) -> Option<i64> {
+        self.first_write_millis
+    }
 }
 
 pub type BoxedBatchIterator = Box<dyn Iterator<Item = Result<Batch>> + Send + Sync>;
@@ -92,8 +100,8 @@ pub type MemtableRef = Arc<dyn Memtable>;
 
 /// Builder to build a new [Memtable].
 pub trait MemtableBuilder: Send + Sync + fmt::Debug {
-    /// Builds a new memtable instance.
-    fn build(&self, metadata: &RegionMetadataRef) -> MemtableRef;
+    /// Builds a new memtable instance that dedups rows by the `duplicate_mode`.
+    fn build(&self, metadata: &RegionMetadataRef, duplicate_mode: DuplicateMode) -> MemtableRef;
 }
 
 pub type MemtableBuilderRef = Arc<dyn MemtableBuilder>;
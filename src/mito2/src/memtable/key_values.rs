@@ -63,6 +63,16 @@ impl KeyValues {
         // Safety: rows is not None.
         self.mutation.rows.as_ref().unwrap().rows.len()
     }
+
+    /// Returns the op type of the mutation.
+    pub fn op_type(&self) -> OpType {
+        OpType::try_from(self.mutation.op_type).unwrap()
+    }
+
+    /// Returns the sequence of the first row.
+    pub fn sequence(&self) -> SequenceNumber {
+        self.mutation.sequence
+    }
 }
 
 /// Key value view of a row.
@@ -21,6 +21,7 @@ use std::time::{Duration, Instant};
 
 use api::v1::OpType;
 use common_telemetry::{debug, error, trace};
+use common_time::clock::{ClockRef, SystemClock};
 use common_time::Timestamp;
 use datafusion::physical_plan::PhysicalExpr;
 use datafusion_common::ScalarValue;
@@ -50,16 +51,24 @@ use crate::memtable::{
 };
 use crate::metrics::{READ_ROWS_TOTAL, READ_STAGE_ELAPSED};
 use crate::read::{Batch, BatchBuilder, BatchColumn};
+use crate::region::options::DuplicateMode;
 use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
 
 /// Initial vector builder capacity.
 const INITIAL_BUILDER_CAPACITY: usize = 32;
 
 /// Builder to build [TimeSeriesMemtable].
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TimeSeriesMemtableBuilder {
     id: AtomicU32,
     write_buffer_manager: Option<WriteBufferManagerRef>,
+    clock: ClockRef,
+}
+
+impl Default for TimeSeriesMemtableBuilder {
+    fn default() -> Self {
+        Self::new(None)
+    }
 }
 
 impl TimeSeriesMemtableBuilder {
@@ -68,18 +77,25 @@ impl TimeSeriesMemtableBuilder {
         Self {
             id: AtomicU32::new(0),
             write_buffer_manager,
+            clock: Arc::new(SystemClock),
         }
     }
+
+    /// Sets the clock to read the time of the first write of memtables.
+    pub fn with_clock(mut self, clock: ClockRef) -> Self {
+        self.clock = clock;
+        self
+    }
 }
 
 impl MemtableBuilder for TimeSeriesMemtableBuilder {
-    fn build(&self, metadata: &RegionMetadataRef) -> MemtableRef {
+    fn build(&self, metadata: &RegionMetadataRef, duplicate_mode: DuplicateMode) -> MemtableRef {
         let id = self.id.fetch_add(1, Ordering::Relaxed);
-        Arc::new(TimeSeriesMemtable::new(
-            metadata.clone(),
-            id,
-            self.write_buffer_manager.clone(),
-        ))
+        Arc::new(
+            TimeSeriesMemtable::new(metadata.clone(), id, self.write_buffer_manager.clone())
+                .with_duplicate_mode(duplicate_mode)
+                .with_clock(self.clock.clone()),
+        )
     }
 }
 
@@ -92,6 +108,12 @@ pub struct TimeSeriesMemtable {
     alloc_tracker: AllocTracker,
     max_timestamp: AtomicI64,
     min_timestamp: AtomicI64,
+    /// Wall clock time in millis of the first write, 0 if nothing is written.
+    first_write_millis: AtomicI64,
+    /// How to select a row among duplicate rows.
+    duplicate_mode: DuplicateMode,
+    /// Clock to read the time of the first write.
+    clock: ClockRef,
 }
 
 impl TimeSeriesMemtable {
@@ -115,13 +137,37 @@ impl TimeSeriesMemtable {
             alloc_tracker: AllocTracker::new(write_buffer_manager),
             max_timestamp: AtomicI64::new(i64::MIN),
             min_timestamp: AtomicI64::new(i64::MAX),
+            first_write_millis: AtomicI64::new(0),
+            duplicate_mode: DuplicateMode::default(),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Sets how to select a row among duplicate rows, keeps the latest row by default.
+    pub fn with_duplicate_mode(mut self, duplicate_mode: DuplicateMode) -> Self {
+        self.duplicate_mode = duplicate_mode;
+        self
+    }
+
+    /// Sets the clock to read the time of the first write.
+    pub fn with_clock(mut self, clock: ClockRef) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Updates memtable stats.
     fn update_stats(&self, request_size: usize, min: i64, max: i64) {
         self.alloc_tracker.on_allocation(request_size);
 
+        if self.first_write_millis.load(Ordering::Relaxed) == 0 {
+            let _ = self.first_write_millis.compare_exchange(
+                0,
+                self.clock.now_millis(),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+
         loop {
             let current_min = self.min_timestamp.load(Ordering::Relaxed);
             if min >= current_min {
@@ -227,7 +273,10 @@ impl Memtable for TimeSeriesMemtable {
                 .collect()
         };
 
-        Box::new(self.series_set.iter_series(projection, filters))
+        Box::new(
+            self.series_set
+                .iter_series(projection, filters, self.duplicate_mode),
+        )
     }
 
     fn is_empty(&self) -> bool {
@@ -246,6 +295,7 @@ impl Memtable for TimeSeriesMemtable {
             return MemtableStats {
                 estimated_bytes,
                 time_range: None,
+                first_write_millis: None,
             };
         }
         let ts_type = self
@@ -261,6 +311,7 @@ impl Memtable for TimeSeriesMemtable {
         MemtableStats {
             estimated_bytes,
             time_range: Some((min_timestamp, max_timestamp)),
+            first_write_millis: Some(self.first_write_millis.load(Ordering::Relaxed)),
         }
     }
 }
@@ -304,7 +355,12 @@ impl SeriesSet {
     }
 
     /// Iterates all series in [SeriesSet].
-    fn iter_series(&self, projection: HashSet<ColumnId>, predicate: Option<Predicate>) -> Iter {
+    fn iter_series(
+        &self,
+        projection: HashSet<ColumnId>,
+        predicate: Option<Predicate>,
+        duplicate_mode: DuplicateMode,
+    ) -> Iter {
         let (primary_key_builders, primary_key_schema) =
             primary_key_builders(&self.region_metadata, 1);
 
@@ -321,6 +377,7 @@ impl SeriesSet {
             pk_schema: primary_key_schema,
             primary_key_builders,
             codec: self.codec.clone(),
+            duplicate_mode,
             metrics: Metrics::default(),
         }
     }
@@ -373,6 +430,7 @@ struct Iter {
     pk_schema: arrow::datatypes::SchemaRef,
     primary_key_builders: Vec<Box<dyn MutableVector>>,
     codec: Arc<McmpRowCodec>,
+    duplicate_mode: DuplicateMode,
     metrics: Metrics,
 }
 
@@ -427,8 +485,14 @@ impl Iterator for Iter {
             self.last_key = Some(primary_key.clone());
 
             let values = series.compact(&self.metadata);
-            let batch =
-                values.and_then(|v| v.to_batch(primary_key, &self.metadata, &self.projection));
+            let batch = values.and_then(|v| {
+                v.to_batch(
+                    primary_key,
+                    &self.metadata,
+                    &self.projection,
+                    self.duplicate_mode,
+                )
+            });
 
             // Update metrics.
             self.metrics.num_batches += 1;
@@ -665,12 +729,13 @@ struct Values {
 
 impl Values {
     /// Converts [Values] to `Batch`, sorts the batch according to `timestamp, sequence` desc and
-    /// keeps only the latest row for the same timestamp.
+    /// keeps only one row for the same timestamp by the `duplicate_mode`.
     pub fn to_batch(
         &self,
         primary_key: &[u8],
         metadata: &RegionMetadataRef,
         projection: &HashSet<ColumnId>,
+        duplicate_mode: DuplicateMode,
     ) -> Result<Batch> {
         let builder = BatchBuilder::with_required_columns(
             primary_key.to_vec(),
@@ -691,7 +756,7 @@ impl Values {
             .collect();
 
         let mut batch = builder.with_fields(fields).build()?;
-        batch.sort_and_dedup()?;
+        batch.sort_and_dedup(duplicate_mode)?;
         Ok(batch)
     }
 
@@ -759,6 +824,7 @@ mod tests {
     use api::helper::ColumnDataTypeWrapper;
     use api::v1::value::ValueData;
     use api::v1::{Row, Rows, SemanticType};
+    use common_time::clock::MockClock;
     use common_time::Timestamp;
     use datatypes::prelude::{ConcreteDataType, ScalarVector};
     use datatypes::schema::ColumnSchema;
@@ -906,7 +972,12 @@ mod tests {
         };
 
         let batch = values
-            .to_batch(b"test", &schema, &[0, 1, 2, 3, 4].into_iter().collect())
+            .to_batch(
+                b"test",
+                &schema,
+                &[0, 1, 2, 3, 4].into_iter().collect(),
+                DuplicateMode::KeepLast,
+            )
             .unwrap();
         check_value(
             &batch,
@@ -1108,6 +1179,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_memtable_first_write_by_clock() {
+        let schema = schema_for_test();
+        let clock = Arc::new(MockClock::new(1_000));
+        let memtable = TimeSeriesMemtable::new(schema.clone(), 42, None).with_clock(clock.clone());
+        assert_eq!(None, memtable.stats().first_write_millis());
+
+        let kvs = build_key_values(&schema, "hello".to_string(), 42, 10);
+        memtable.write(&kvs).unwrap();
+        clock.advance(std::time::Duration::from_secs(1));
+        memtable.write(&kvs).unwrap();
+        // Only the first write is recorded.
+        assert_eq!(Some(1_000), memtable.stats().first_write_millis());
+    }
+
     #[test]
     fn test_memtable_projection() {
         common_telemetry::init_default_ut_logging();
@@ -19,6 +19,7 @@ use std::sync::Arc;
 use smallvec::SmallVec;
 
 use crate::memtable::{MemtableId, MemtableRef};
+use crate::metrics::MemtableMetrics;
 
 /// A version of current memtables in a region.
 #[derive(Debug, Clone)]
@@ -44,6 +45,25 @@ impl MemtableVersion {
         }
     }
 
+    /// Updates metrics of memtables in the region at `now_millis`.
+    pub(crate) fn update_metrics(&self, metrics: &MemtableMetrics, now_millis: i64) {
+        let mut bytes = 0;
+        let mut first_write_millis: Option<i64> = None;
+        for memtable in self.immutables.iter().chain(std::iter::once(&self.mutable)) {
+            let stats = memtable.stats();
+            bytes += stats.bytes_allocated();
+            if let Some(millis) = stats.first_write_millis() {
+                first_write_millis = Some(first_write_millis.map_or(millis, |v| v.min(millis)));
+            }
+        }
+        let age_millis = first_write_millis
+            .map(|millis| (now_millis - millis).max(0))
+            .unwrap_or(0);
+
+        metrics.bytes.set(bytes as i64);
+        metrics.oldest_entry_age.set(age_millis as f64 / 1000.0);
+    }
+
     /// Immutable memtables.
     pub(crate) fn immutables(&self) -> &[MemtableRef] {
         &self.immutables
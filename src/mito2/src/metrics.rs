@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use lazy_static::lazy_static;
 use prometheus::*;
+use store_api::storage::RegionId;
 
 /// Stage label.
 pub const STAGE_LABEL: &str = "stage";
@@ -23,6 +26,12 @@ pub const TYPE_LABEL: &str = "type";
 pub const FLUSH_REASON: &str = "reason";
 /// File type label.
 pub const FILE_TYPE_LABEL: &str = "file_type";
+/// Region label.
+pub const REGION_LABEL: &str = "region";
+/// Label to attribute index IO operations to, e.g. a region or a tenant.
+pub const ATTRIBUTION_LABEL: &str = "attribution";
+/// Object store operation label.
+pub const OPERATION_LABEL: &str = "operation";
 
 lazy_static! {
     /// Global write buffer size in bytes.
@@ -31,6 +40,27 @@ lazy_static! {
     /// Gauge for open regions
     pub static ref REGION_COUNT: IntGauge =
         register_int_gauge!("greptime_mito_region_count", "mito region count").unwrap();
+    /// Bytes of memtables in each region.
+    pub static ref MEMTABLE_BYTES: IntGaugeVec = register_int_gauge_vec!(
+            "greptime_mito_memtable_bytes",
+            "mito memtable bytes",
+            &[REGION_LABEL]
+        )
+        .unwrap();
+    /// Age of the oldest entry in memtables of each region.
+    pub static ref MEMTABLE_OLDEST_ENTRY_AGE: GaugeVec = register_gauge_vec!(
+            "greptime_mito_memtable_oldest_entry_age_seconds",
+            "mito memtable oldest entry age in seconds",
+            &[REGION_LABEL]
+        )
+        .unwrap();
+    /// Number of series tracked by the series limiter of each region.
+    pub static ref SERIES_COUNT: IntGaugeVec = register_int_gauge_vec!(
+            "greptime_mito_series_count",
+            "mito series count",
+            &[REGION_LABEL]
+        )
+        .unwrap();
     /// Elapsed time to handle requests.
     pub static ref HANDLE_REQUEST_ELAPSED: HistogramVec = register_histogram_vec!(
             "greptime_mito_handle_request_elapsed",
@@ -66,6 +96,24 @@ lazy_static! {
 
     // ------ Write related metrics
     /// Counter of stalled write requests.
+    /// Number of SSTs written directly to the object store, bypassing the write cache.
+    pub static ref WRITE_CACHE_BYPASS_TOTAL: IntCounter = register_int_counter!(
+        "greptime_mito_write_cache_bypass_total",
+        "mito write cache bypass total"
+    )
+    .unwrap();
+    pub static ref SST_MIRROR_FAILURES_TOTAL: IntCounter = register_int_counter!(
+        "greptime_mito_sst_mirror_failures_total",
+        "mito sst mirror failures total"
+    )
+    .unwrap();
+    /// Counter of retries of object store operations on SSTs.
+    pub static ref OBJECT_STORE_RETRY_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "greptime_mito_object_store_retry_total",
+        "mito object store retry total",
+        &[OPERATION_LABEL]
+    )
+    .unwrap();
     pub static ref WRITE_STALL_TOTAL: IntCounter =
         register_int_counter!("greptime_mito_write_stall_total", "mito write stall total").unwrap();
     /// Counter of rejected write requests.
@@ -85,6 +133,18 @@ lazy_static! {
         &[TYPE_LABEL]
     )
     .unwrap();
+    /// Counter of SST files dropped as a whole by range deletes.
+    pub static ref DELETE_FILES_DROPPED_TOTAL: IntCounter = register_int_counter!(
+        "mito_delete_files_dropped_total",
+        "mito delete files dropped total",
+    )
+    .unwrap();
+    /// Counter of rows deleted row by row by range deletes.
+    pub static ref DELETE_ROWS_REWRITTEN_TOTAL: IntCounter = register_int_counter!(
+        "mito_delete_rows_rewritten_total",
+        "mito delete rows rewritten total",
+    )
+    .unwrap();
     // ------ End of write related metrics
 
 
@@ -105,6 +165,12 @@ lazy_static! {
     /// Counter of failed compaction task.
     pub static ref COMPACTION_FAILURE_COUNT: IntCounter =
         register_int_counter!("greptime_mito_compaction_failure_total", "mito compaction failure total").unwrap();
+    /// Number of compactions running.
+    pub static ref COMPACTION_ACTIVE: IntGauge =
+        register_int_gauge!("greptime_mito_compaction_active", "mito compaction active").unwrap();
+    /// Number of compactions waiting for a permit to run.
+    pub static ref COMPACTION_QUEUED: IntGauge =
+        register_int_gauge!("greptime_mito_compaction_queued", "mito compaction queued").unwrap();
     // ------- End of compaction metrics.
 
     // Query metrics.
@@ -115,6 +181,12 @@ lazy_static! {
         &[STAGE_LABEL]
     )
     .unwrap();
+    /// Bytes of SSTs fetched from the object store by SST readers.
+    pub static ref READ_SST_FETCHED_BYTES_TOTAL: IntCounter = register_int_counter!(
+        "greptime_mito_read_sst_fetched_bytes_total",
+        "mito read sst fetched bytes total"
+    )
+    .unwrap();
     /// Counter of rows read.
     pub static ref READ_ROWS_TOTAL: IntCounterVec =
         register_int_counter_vec!("greptime_mito_read_rows_total", "mito read rows total", &[TYPE_LABEL]).unwrap();
@@ -240,5 +312,89 @@ lazy_static! {
     /// Counter of flush operations on intermediate files.
     pub static ref INDEX_INTERMEDIATE_FLUSH_OP_TOTAL: IntCounter = INDEX_IO_OP_TOTAL
         .with_label_values(&["flush", "intermediate"]);
+    /// Counter of bytes of index related IO operations, partitioned by the label
+    /// passed to the instrumented store.
+    pub static ref INDEX_IO_LABELED_BYTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "greptime_index_io_labeled_bytes_total",
+        "index io labeled bytes total",
+        &[TYPE_LABEL, ATTRIBUTION_LABEL]
+    )
+    .unwrap();
+    /// Counter of index related IO operations, partitioned by the label passed to
+    /// the instrumented store.
+    pub static ref INDEX_IO_LABELED_OP_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "greptime_index_io_labeled_op_total",
+        "index io labeled op total",
+        &[TYPE_LABEL, ATTRIBUTION_LABEL]
+    )
+    .unwrap();
+    /// Counter of index related IO operations timed out.
+    pub static ref INDEX_IO_TIMEOUT_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "greptime_index_io_timeout_total",
+        "index io timeout total",
+        &[TYPE_LABEL]
+    )
+    .unwrap();
+    /// Number of intermediate files of index creations in progress.
+    pub static ref INDEX_INTERMEDIATE_FILES: IntGauge = register_int_gauge!(
+        "greptime_index_intermediate_files",
+        "index intermediate files",
+    )
+    .unwrap();
+    /// Counter of bytes spilled to intermediate files by index creations.
+    pub static ref INDEX_INTERMEDIATE_SPILL_BYTES_TOTAL: IntCounter = register_int_counter!(
+        "greptime_index_intermediate_spill_bytes_total",
+        "index intermediate spill bytes total",
+    )
+    .unwrap();
+    /// Bytes of intermediate files not removed yet of index creations in progress.
+    pub static ref INDEX_INTERMEDIATE_TEMP_BYTES: IntGauge = register_int_gauge!(
+        "greptime_index_intermediate_temp_bytes",
+        "index intermediate temp bytes",
+    )
+    .unwrap();
+    /// Counter of orphaned intermediate files reclaimed, e.g. files left by index
+    /// creations interrupted by crashes.
+    pub static ref INDEX_INTERMEDIATE_ORPHAN_RECLAIMED_TOTAL: IntCounter = register_int_counter!(
+        "greptime_index_intermediate_orphan_reclaimed_total",
+        "index intermediate orphan reclaimed total",
+    )
+    .unwrap();
+    /// Number of index builds running.
+    pub static ref INDEX_BUILD_ACTIVE: IntGauge =
+        register_int_gauge!("greptime_index_build_active", "index build active").unwrap();
+    /// Number of index builds waiting for a permit to run.
+    pub static ref INDEX_BUILD_QUEUED: IntGauge =
+        register_int_gauge!("greptime_index_build_queued", "index build queued").unwrap();
     // ------- End of index metrics.
 }
+
+/// Memtable metrics of a region, so writes don't resolve the region label each time.
+#[derive(Debug)]
+pub(crate) struct MemtableMetrics {
+    /// Bytes of memtables in the region.
+    pub(crate) bytes: IntGauge,
+    /// Age of the oldest entry in memtables of the region.
+    pub(crate) oldest_entry_age: Gauge,
+}
+
+pub(crate) type MemtableMetricsRef = Arc<MemtableMetrics>;
+
+impl MemtableMetrics {
+    /// Returns metrics of the region.
+    pub(crate) fn new(region_id: RegionId) -> MemtableMetrics {
+        let label = region_id.to_string();
+        MemtableMetrics {
+            bytes: MEMTABLE_BYTES.with_label_values(&[&label]),
+            oldest_entry_age: MEMTABLE_OLDEST_ENTRY_AGE.with_label_values(&[&label]),
+        }
+    }
+}
+
+/// Removes metrics labeled by the region.
+pub(crate) fn remove_region_metrics(region_id: RegionId) {
+    let label = region_id.to_string();
+    let _ = MEMTABLE_BYTES.remove_label_values(&[&label]);
+    let _ = MEMTABLE_OLDEST_ENTRY_AGE.remove_label_values(&[&label]);
+    let _ = SERIES_COUNT.remove_label_values(&[&label]);
+}
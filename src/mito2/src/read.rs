@@ -15,13 +15,17 @@
 //! Common structs and utilities for reading data.
 
 pub mod compat;
+pub(crate) mod follow;
 pub mod merge;
 pub mod projection;
+pub(crate) mod sample;
 pub(crate) mod scan_region;
 pub(crate) mod seq_scan;
 
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 use api::v1::OpType;
 use async_trait::async_trait;
@@ -49,6 +53,8 @@ use crate::error::{
     ComputeArrowSnafu, ComputeVectorSnafu, ConvertVectorSnafu, InvalidBatchSnafu, Result,
 };
 use crate::memtable::BoxedBatchIterator;
+use crate::region::options::DuplicateMode;
+use crate::sst::file::FileId;
 
 /// Storage internal representation of a batch of rows for a primary key (time series).
 ///
@@ -317,10 +323,10 @@ impl Batch {
 
     /// Sorts and dedup rows in the batch.
     ///
-    /// It orders rows by timestamp, sequence desc and only keep the latest
-    /// row for the same timestamp. It doesn't consider op type as sequence
-    /// should already provide uniqueness for a row.
-    pub fn sort_and_dedup(&mut self) -> Result<()> {
+    /// It orders rows by timestamp, sequence desc and only keeps rows for the same
+    /// timestamp selected by [select_duplicates] under the `mode`. There may be a put
+    /// and a delete of the same timestamp if the `mode` keeps the first row.
+    pub fn sort_and_dedup(&mut self, mode: DuplicateMode) -> Result<()> {
         // If building a converter each time is costly, we may allow passing a
         // converter.
         let converter = RowConverter::new(vec![
@@ -344,15 +350,24 @@ impl Batch {
         to_sort.sort_unstable_by(|left, right| left.1.cmp(&right.1));
 
         // Dedup by timestamps.
-        to_sort.dedup_by(|left, right| {
-            debug_assert_eq!(18, left.1.as_ref().len());
-            debug_assert_eq!(18, right.1.as_ref().len());
-            let (left_key, right_key) = (left.1.as_ref(), right.1.as_ref());
+        let op_types = self.op_types.as_arrow().values();
+        let mut indices = Vec::with_capacity(to_sort.len());
+        let mut start = 0;
+        while start < to_sort.len() {
+            debug_assert_eq!(18, to_sort[start].1.as_ref().len());
             // We only compare the timestamp part and ignore sequence.
-            left_key[..TIMESTAMP_KEY_LEN] == right_key[..TIMESTAMP_KEY_LEN]
-        });
+            let ts_key = &to_sort[start].1.as_ref()[..TIMESTAMP_KEY_LEN];
+            let num_duplicates = to_sort[start..]
+                .iter()
+                .take_while(|(_, row)| &row.as_ref()[..TIMESTAMP_KEY_LEN] == ts_key)
+                .count();
+            let duplicates = &to_sort[start..start + num_duplicates];
+            let selected = select_duplicates(mode, duplicates.iter().map(|(i, _)| op_types[*i]));
+            indices.extend(duplicates[selected].iter().map(|(i, _)| *i as u32));
+            start += num_duplicates;
+        }
 
-        let indices = UInt32Vector::from_iter_values(to_sort.iter().map(|v| v.0 as u32));
+        let indices = UInt32Vector::from_vec(indices);
         self.take_in_place(&indices)
     }
 
@@ -415,6 +430,17 @@ impl Batch {
         Some(values)
     }
 
+    /// Returns the number of leading rows with the same timestamp as the first row.
+    pub(crate) fn num_rows_of_first_timestamp(&self) -> usize {
+        let Some(timestamps) = self.timestamps_native() else {
+            return 0;
+        };
+        timestamps
+            .iter()
+            .take_while(|ts| **ts == timestamps[0])
+            .count()
+    }
+
     /// Takes the batch in place.
     fn take_in_place(&mut self, indices: &UInt32Vector) -> Result<()> {
         self.timestamps = self.timestamps.take(indices).context(ComputeVectorSnafu)?;
@@ -472,6 +498,33 @@ fn is_put_only(op_types: &UInt8Vector) -> bool {
         .all(|v| *v == OpType::Put as u8)
 }
 
+/// Returns the range of rows to keep among rows with the same primary key and
+/// timestamp, given their `op_types` ordered by sequence desc.
+///
+/// Keeps the latest row unless the `mode` keeps the first row, then keeps the earliest
+/// put after the latest delete and the latest delete itself. The delete is kept so it
+/// still deletes rows of the timestamp in older sources, e.g. SSTs flushed before.
+/// The row is deleted if the latest row is a delete.
+///
+/// The first row in the range is the row to output.
+pub(crate) fn select_duplicates(
+    mode: DuplicateMode,
+    op_types: impl Iterator<Item = u8>,
+) -> Range<usize> {
+    if !mode.keeps_first() {
+        return 0..1;
+    }
+
+    let mut num_puts = 0;
+    for op_type in op_types {
+        if op_type != OpType::Put as u8 {
+            return num_puts.saturating_sub(1)..num_puts + 1;
+        }
+        num_puts += 1;
+    }
+    num_puts.saturating_sub(1)..num_puts
+}
+
 /// Len of timestamp in arrow row format.
 const TIMESTAMP_KEY_LEN: usize = 9;
 
@@ -662,6 +715,71 @@ impl BatchBuilder {
     }
 }
 
+/// Bytes fetched from the object store by SST readers of a scan, grouped by files.
+///
+/// Bytes served by caches are not counted.
+#[derive(Debug, Default)]
+pub struct FetchedBytes {
+    files: Mutex<HashMap<FileId, u64>>,
+}
+
+pub type FetchedBytesRef = Arc<FetchedBytes>;
+
+impl FetchedBytes {
+    /// Adds `bytes` fetched from the file.
+    pub(crate) fn add(&self, file_id: FileId, bytes: u64) {
+        *self.files.lock().unwrap().entry(file_id).or_default() += bytes;
+    }
+
+    /// Returns total bytes fetched from all files.
+    pub fn total(&self) -> u64 {
+        self.files.lock().unwrap().values().sum()
+    }
+
+    /// Returns bytes fetched from each file.
+    pub fn per_file(&self) -> HashMap<FileId, u64> {
+        self.files.lock().unwrap().clone()
+    }
+}
+
+/// Metrics of applying indexes of SSTs by a scan.
+#[derive(Debug, Default)]
+pub struct IndexMetrics {
+    /// Number of SSTs whose indexes are applied.
+    applied_files: AtomicU64,
+    /// Number of row groups selected by indexes.
+    selected_row_groups: AtomicU64,
+    /// Number of postings read from indexes.
+    postings: AtomicU64,
+}
+
+pub type IndexMetricsRef = Arc<IndexMetrics>;
+
+impl IndexMetrics {
+    /// Adds the result of applying the index of a SST.
+    pub(crate) fn add(&self, selected_row_groups: usize, postings: usize) {
+        self.applied_files.fetch_add(1, Ordering::Relaxed);
+        self.selected_row_groups
+            .fetch_add(selected_row_groups as u64, Ordering::Relaxed);
+        self.postings.fetch_add(postings as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the number of SSTs whose indexes are applied.
+    pub fn applied_files(&self) -> u64 {
+        self.applied_files.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of row groups selected by indexes.
+    pub fn selected_row_groups(&self) -> u64 {
+        self.selected_row_groups.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of postings read from indexes.
+    pub fn postings(&self) -> u64 {
+        self.postings.load(Ordering::Relaxed)
+    }
+}
+
 /// Async [Batch] reader and iterator wrapper.
 ///
 /// This is the data source for SST writers or internal readers.
@@ -672,6 +790,8 @@ pub enum Source {
     Iter(BoxedBatchIterator),
     /// Source from a [BoxedBatchStream].
     Stream(BoxedBatchStream),
+    /// Source from [Batch]es in memory.
+    Batches(VecDeque<Batch>),
 }
 
 impl Source {
@@ -681,10 +801,45 @@ impl Source {
             Source::Reader(reader) => reader.next_batch().await,
             Source::Iter(iter) => iter.next().transpose(),
             Source::Stream(stream) => stream.try_next().await,
+            Source::Batches(batches) => Ok(batches.pop_front()),
+        }
+    }
+
+    /// Returns a source that yields the same remaining batches as this source
+    /// without consuming this source, or `None` if the source can't be replayed.
+    ///
+    /// Only sources of batches in memory can be replayed.
+    pub(crate) fn try_clone(&self) -> Option<Source> {
+        match self {
+            Source::Batches(batches) => Some(Source::Batches(batches.clone())),
+            Source::Reader(_) | Source::Iter(_) | Source::Stream(_) => None,
+        }
+    }
+}
+
+/// A [BatchReader] over a [Source] shared by writers of SSTs, each writer
+/// continues reading from where the previous writer stops.
+#[derive(Clone)]
+pub(crate) struct SharedBatchReader {
+    inner: Arc<tokio::sync::Mutex<Source>>,
+}
+
+impl SharedBatchReader {
+    /// Creates a new [SharedBatchReader] from the `source`.
+    pub(crate) fn new(source: Source) -> SharedBatchReader {
+        SharedBatchReader {
+            inner: Arc::new(tokio::sync::Mutex::new(source)),
         }
     }
 }
 
+#[async_trait]
+impl BatchReader for SharedBatchReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.inner.lock().await.next_batch().await
+    }
+}
+
 /// Async batch reader.
 ///
 /// The reader must guarantee [Batch]es returned by it have the same schema.
@@ -976,7 +1131,7 @@ mod tests {
             ],
             &[21, 22, 23, 24, 25, 26],
         );
-        batch.sort_and_dedup().unwrap();
+        batch.sort_and_dedup(DuplicateMode::KeepLast).unwrap();
         // It should only keep one timestamp 2.
         let expect = new_batch(
             &[1, 2, 3, 4, 5],
@@ -998,8 +1153,57 @@ mod tests {
             &[OpType::Delete, OpType::Put, OpType::Put],
             &[21, 22, 23],
         );
-        batch.sort_and_dedup().unwrap();
+        batch.sort_and_dedup(DuplicateMode::KeepLast).unwrap();
         let expect = new_batch(&[1, 2], &[1, 6], &[OpType::Put, OpType::Put], &[23, 22]);
         assert_eq!(expect, batch);
     }
+
+    #[test]
+    fn test_sort_and_dedup_keep_first() {
+        let mut batch = new_batch(
+            &[2, 3, 1, 2, 3, 2],
+            &[1, 2, 3, 4, 5, 6],
+            &[
+                OpType::Put,
+                OpType::Put,
+                OpType::Put,
+                OpType::Put,
+                OpType::Put,
+                OpType::Put,
+            ],
+            &[21, 22, 23, 24, 25, 26],
+        );
+        batch.sort_and_dedup(DuplicateMode::KeepFirst).unwrap();
+        let expect = new_batch(
+            &[1, 2, 3],
+            &[3, 1, 2],
+            &[OpType::Put, OpType::Put, OpType::Put],
+            &[23, 21, 22],
+        );
+        assert_eq!(expect, batch);
+
+        // Keeps the first put after the latest delete.
+        let mut batch = new_batch(
+            &[2, 2, 2, 2, 1, 1],
+            &[1, 2, 3, 4, 5, 6],
+            &[
+                OpType::Put,
+                OpType::Delete,
+                OpType::Put,
+                OpType::Put,
+                OpType::Put,
+                OpType::Delete,
+            ],
+            &[21, 22, 23, 24, 25, 26],
+        );
+        batch.sort_and_dedup(DuplicateMode::KeepFirst).unwrap();
+        // Also keeps the latest delete to delete the row in older sources.
+        let expect = new_batch(
+            &[1, 2, 2],
+            &[6, 3, 2],
+            &[OpType::Delete, OpType::Put, OpType::Delete],
+            &[26, 23, 22],
+        );
+        assert_eq!(expect, batch);
+    }
 }
@@ -0,0 +1,140 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Follow scan, which returns rows written to a region after scanning it.
+
+use std::sync::Arc;
+
+use async_stream::try_stream;
+use common_error::ext::BoxedError;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{RecordBatchStreamWrapper, SendableRecordBatchStream};
+use futures::StreamExt;
+use snafu::ResultExt;
+use store_api::storage::{RegionId, SequenceNumber};
+use table::predicate::Predicate;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+
+use crate::error::{Result, SubscriberLaggedSnafu};
+use crate::memtable::time_series::TimeSeriesMemtable;
+use crate::memtable::{KeyValues, Memtable};
+use crate::read::projection::ProjectionMapper;
+use crate::read::seq_scan::SeqScan;
+use crate::read::Batch;
+
+/// Scans a region and then keeps returning rows written to the region until the
+/// stream is dropped or the region is gone.
+///
+/// Rows written while scanning the region may be returned twice.
+pub(crate) struct FollowScan {
+    /// Scans rows already in the region.
+    seq_scan: SeqScan,
+    /// Receives rows written to the region, it must subscribe before scanning the
+    /// region so no write is missed.
+    receiver: Receiver<Arc<KeyValues>>,
+    /// Committed sequence of the region when subscribed. Writes not after it are
+    /// returned by the scan.
+    committed_sequence: SequenceNumber,
+}
+
+impl FollowScan {
+    /// Creates a new [FollowScan].
+    pub(crate) fn new(
+        seq_scan: SeqScan,
+        receiver: Receiver<Arc<KeyValues>>,
+        committed_sequence: SequenceNumber,
+    ) -> FollowScan {
+        FollowScan {
+            seq_scan,
+            receiver,
+            committed_sequence,
+        }
+    }
+
+    /// Builds a stream that returns rows of the scan first and then rows written later.
+    ///
+    /// The stream returns an error if it is consumed so slowly that it misses some writes.
+    pub(crate) async fn build_stream(self) -> Result<SendableRecordBatchStream> {
+        let mut scan_stream = self.seq_scan.build_stream().await?;
+        let mapper = self.seq_scan.mapper().clone();
+        let predicate = self.seq_scan.predicate().cloned();
+        let FollowScan {
+            mut receiver,
+            committed_sequence,
+            ..
+        } = self;
+
+        let schema = mapper.output_schema();
+        let stream = try_stream! {
+            while let Some(batch) = scan_stream.next().await {
+                yield batch?;
+            }
+
+            let region_id = mapper.metadata().region_id;
+            while let Some(kvs) = recv_key_values(&mut receiver, region_id)
+                .await
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?
+            {
+                if kvs.sequence() <= committed_sequence {
+                    continue;
+                }
+                let batches = key_values_to_batches(&kvs, &mapper, predicate.clone())
+                    .map_err(BoxedError::new)
+                    .context(ExternalSnafu)?;
+                for batch in batches {
+                    yield mapper.convert(&batch, None)?;
+                }
+            }
+        };
+
+        Ok(Box::pin(RecordBatchStreamWrapper::new(
+            schema,
+            Box::pin(stream),
+        )))
+    }
+}
+
+/// Receives the next write, returns `None` if the region is gone.
+async fn recv_key_values(
+    receiver: &mut Receiver<Arc<KeyValues>>,
+    region_id: RegionId,
+) -> Result<Option<Arc<KeyValues>>> {
+    match receiver.recv().await {
+        Ok(kvs) => Ok(Some(kvs)),
+        Err(RecvError::Closed) => Ok(None),
+        Err(RecvError::Lagged(num_missed)) => SubscriberLaggedSnafu {
+            region_id,
+            num_missed,
+        }
+        .fail(),
+    }
+}
+
+/// Converts the `kvs` into batches of the projection of the `mapper`.
+///
+/// Sorts rows by writing them to a temporary memtable, the `predicate` is used to
+/// filter series of the memtable.
+fn key_values_to_batches(
+    kvs: &KeyValues,
+    mapper: &ProjectionMapper,
+    predicate: Option<Predicate>,
+) -> Result<Vec<Batch>> {
+    let memtable = TimeSeriesMemtable::new(mapper.metadata().clone(), 0, None);
+    memtable.write(kvs)?;
+    memtable
+        .iter(Some(mapper.column_ids()), predicate)
+        .collect()
+}
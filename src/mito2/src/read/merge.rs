@@ -14,7 +14,7 @@
 
 //! Merge reader implementation.
 
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::BinaryHeap;
 use std::mem;
 use std::time::{Duration, Instant};
@@ -26,7 +26,8 @@ use common_time::Timestamp;
 use crate::error::Result;
 use crate::memtable::BoxedBatchIterator;
 use crate::metrics::{MERGE_FILTER_ROWS_TOTAL, READ_STAGE_ELAPSED};
-use crate::read::{Batch, BatchReader, BoxedBatchReader, Source};
+use crate::read::{select_duplicates, Batch, BatchReader, BoxedBatchReader, Source};
+use crate::region::options::DuplicateMode;
 
 /// Reader to merge sorted batches.
 ///
@@ -48,6 +49,8 @@ pub struct MergeReader {
     cold: BinaryHeap<Node>,
     /// Batch to output.
     output_batch: Option<Batch>,
+    /// How to select a row among duplicate rows.
+    duplicate_mode: DuplicateMode,
     /// Local metrics.
     metrics: Metrics,
 }
@@ -98,7 +101,7 @@ impl Drop for MergeReader {
 
 impl MergeReader {
     /// Creates and initializes a new [MergeReader].
-    pub async fn new(sources: Vec<Source>) -> Result<MergeReader> {
+    pub async fn new(sources: Vec<Source>, duplicate_mode: DuplicateMode) -> Result<MergeReader> {
         let start = Instant::now();
         let mut metrics = Metrics::default();
 
@@ -116,6 +119,7 @@ impl MergeReader {
             hot,
             cold,
             output_batch: None,
+            duplicate_mode,
             metrics,
         };
         // Initializes the reader.
@@ -173,43 +177,30 @@ impl MergeReader {
 
         // Safety: Batches in the heap is not empty, so we can use unwrap here.
         let timestamps = top.timestamps_native().unwrap();
-        // Binary searches the timestamp in the top batch.
+        // Searches the first timestamp not less than `next_min_ts` in the top batch. The batch
+        // may contain a put and a delete of the same timestamp, so we can't binary search it.
         // Safety: Batches should have the same timestamp resolution so we can compare the native
         // value directly.
-        match timestamps.binary_search(&next_min_ts.value()) {
-            Ok(pos) => {
-                // They have duplicate timestamps. Outputs timestamps before the duplicated timestamp.
-                // Batch itself doesn't contain duplicate timestamps so timestamps before `pos`
-                // must be less than `next_min_ts`.
-                Self::maybe_output_batch(
-                    top.slice(0, pos),
-                    &mut self.output_batch,
-                    &mut self.metrics,
-                )?;
-                // This keep the duplicate timestamp in the node.
-                top_node.skip_rows(pos, &mut self.metrics).await?;
-                // The merge window should contain this timestamp so only nodes in the hot heap
-                // have this timestamp.
-                self.filter_first_duplicate_timestamp_in_hot(top_node, next_min_ts)
-                    .await?;
-            }
-            Err(pos) => {
-                // No duplicate timestamp. Outputs timestamp before `pos`.
-                Self::maybe_output_batch(
-                    top.slice(0, pos),
-                    &mut self.output_batch,
-                    &mut self.metrics,
-                )?;
-                top_node.skip_rows(pos, &mut self.metrics).await?;
-                self.reheap(top_node)?;
-            }
+        let pos = timestamps.partition_point(|ts| *ts < next_min_ts.value());
+        let is_duplicate = timestamps.get(pos) == Some(&next_min_ts.value());
+        // Outputs timestamps before `pos`, which must be less than `next_min_ts`.
+        Self::maybe_output_batch(top.slice(0, pos), &mut self.output_batch, &mut self.metrics)?;
+        // This keep the duplicate timestamp in the node.
+        top_node.skip_rows(pos, &mut self.metrics).await?;
+        if is_duplicate {
+            // The merge window should contain this timestamp so only nodes in the hot heap
+            // have this timestamp.
+            self.filter_first_duplicate_timestamp_in_hot(top_node, next_min_ts)
+                .await?;
+        } else {
+            self.reheap(top_node)?;
         }
 
         Ok(())
     }
 
     /// Filters the first duplicate `timestamp` in `top_node` and `hot` heap. Only keeps the timestamp
-    /// with the maximum sequence.
+    /// selected by [select_duplicates] under the duplicate mode of the reader.
     async fn filter_first_duplicate_timestamp_in_hot(
         &mut self,
         top_node: Node,
@@ -220,40 +211,62 @@ impl MergeReader {
             timestamp
         );
 
-        // The node with maximum sequence.
-        let mut max_seq_node = top_node;
-        let mut max_seq = max_seq_node.current_batch().first_sequence().unwrap();
-        while let Some(mut next_node) = self.hot.pop() {
+        // Nodes whose first rows are duplicate.
+        let mut nodes = vec![top_node];
+        while let Some(next_node) = self.hot.pop() {
             // Safety: Batches in the heap is not empty.
             let next_first_ts = next_node.current_batch().first_timestamp().unwrap();
-            let next_first_seq = next_node.current_batch().first_sequence().unwrap();
-
             if next_first_ts != timestamp {
-                // We are done, push the node with max seq.
+                // We are done.
                 self.cold.push(next_node);
                 break;
             }
+            nodes.push(next_node);
+        }
 
-            if max_seq < next_first_seq {
-                // The next node has larger seq.
-                max_seq_node.skip_rows(1, &mut self.metrics).await?;
-                self.metrics.num_duplicate_rows += 1;
-                if !max_seq_node.is_eof() {
-                    self.cold.push(max_seq_node);
-                }
-                max_seq_node = next_node;
-                max_seq = next_first_seq;
-            } else {
-                next_node.skip_rows(1, &mut self.metrics).await?;
-                self.metrics.num_duplicate_rows += 1;
-                if !next_node.is_eof() {
-                    // If the next node has smaller seq, skip that row.
-                    self.cold.push(next_node);
-                }
+        // A node may have more than one row of the timestamp, e.g. a put and the delete
+        // kept by `DuplicateMode::KeepFirst`. Collects (sequence, op type, node index) of
+        // all these rows.
+        let mut rows = Vec::with_capacity(nodes.len());
+        let mut num_duplicates = Vec::with_capacity(nodes.len());
+        for (i, node) in nodes.iter_mut().enumerate() {
+            node.fetch_rows_of_timestamp(timestamp, &mut self.metrics)
+                .await?;
+            let batch = node.current_batch();
+            let num_rows = batch.num_rows_of_first_timestamp();
+            rows.extend((0..num_rows).map(|row| {
+                (
+                    batch.sequences().get_data(row).unwrap(),
+                    batch.op_types().get_data(row).unwrap(),
+                    i,
+                )
+            }));
+            num_duplicates.push(num_rows);
+        }
+        // Orders duplicate rows by sequence desc to select the row to keep.
+        rows.sort_unstable_by_key(|(sequence, _, _)| Reverse(*sequence));
+        let selected = select_duplicates(
+            self.duplicate_mode,
+            rows.iter().map(|(_, op_type, _)| *op_type),
+        );
+        // The row to output is always the first row of its node, as rows of the same node
+        // are ordered by sequence desc and only a delete can follow a put. We keep its node
+        // unchanged and skip rows of the timestamp in other nodes. Deletes are removed from
+        // the output so we don't need to keep the selected delete.
+        let selected_node = rows[selected.start].2;
+        for (i, mut node) in nodes.into_iter().enumerate() {
+            if i == selected_node {
+                debug_assert!(!node.is_eof());
+                self.cold.push(node);
+                continue;
+            }
+            // Skips the duplicate rows.
+            node.skip_rows(num_duplicates[i], &mut self.metrics).await?;
+            self.metrics.num_duplicate_rows += num_duplicates[i];
+            if !node.is_eof() {
+                self.cold.push(node);
             }
         }
-        debug_assert!(!max_seq_node.is_eof());
-        self.cold.push(max_seq_node);
 
         // The merge window is updated, we need to refill the hot heap.
         self.refill_hot();
@@ -321,6 +334,8 @@ pub struct MergeReaderBuilder {
     ///
     /// All source must yield batches with the same schema.
     sources: Vec<Source>,
+    /// How to select a row among duplicate rows.
+    duplicate_mode: DuplicateMode,
 }
 
 impl MergeReaderBuilder {
@@ -331,7 +346,16 @@ impl MergeReaderBuilder {
 
     /// Creates a builder from sources.
     pub fn from_sources(sources: Vec<Source>) -> MergeReaderBuilder {
-        MergeReaderBuilder { sources }
+        MergeReaderBuilder {
+            sources,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how to select a row among duplicate rows, keeps the latest row by default.
+    pub fn with_duplicate_mode(&mut self, duplicate_mode: DuplicateMode) -> &mut Self {
+        self.duplicate_mode = duplicate_mode;
+        self
     }
 
     /// Pushes a batch reader to sources.
@@ -349,7 +373,7 @@ impl MergeReaderBuilder {
     /// Builds and initializes the reader, then resets the builder.
     pub async fn build(&mut self) -> Result<MergeReader> {
         let sources = mem::take(&mut self.sources);
-        MergeReader::new(sources).await
+        MergeReader::new(sources, self.duplicate_mode).await
     }
 }
 
@@ -382,6 +406,8 @@ struct Node {
     ///
     /// `None` means the `source` has reached EOF.
     current_batch: Option<CompareFirst>,
+    /// Batch fetched from the `source` but not read yet.
+    pending_batch: Option<Batch>,
 }
 
 impl Node {
@@ -398,6 +424,7 @@ impl Node {
         Ok(Node {
             source,
             current_batch,
+            pending_batch: None,
         })
     }
 
@@ -429,6 +456,11 @@ impl Node {
     /// Panics if the node has reached EOF.
     async fn fetch_batch(&mut self, metrics: &mut Metrics) -> Result<Batch> {
         let current = self.current_batch.take().unwrap();
+        if let Some(pending) = self.pending_batch.take() {
+            // Rows of the pending batch are already counted.
+            self.current_batch = Some(CompareFirst(pending));
+            return Ok(current.0);
+        }
         let start = Instant::now();
         // Ensures batch is not empty.
         self.current_batch = self.source.next_batch().await?.map(CompareFirst);
@@ -460,6 +492,38 @@ impl Node {
         }) == Ordering::Greater
     }
 
+    /// Concatenates next batches of the same primary key to current batch while current batch
+    /// ends with the `timestamp`, so current batch contains all rows of the timestamp in the node.
+    ///
+    /// # Panics
+    /// Panics if the node is EOF.
+    async fn fetch_rows_of_timestamp(
+        &mut self,
+        timestamp: Timestamp,
+        metrics: &mut Metrics,
+    ) -> Result<()> {
+        while self.pending_batch.is_none()
+            && self.current_batch().last_timestamp() == Some(timestamp)
+        {
+            let start = Instant::now();
+            let next = self.source.next_batch().await?;
+            metrics.fetch_cost += start.elapsed();
+            let Some(next) = next else {
+                break;
+            };
+            metrics.num_input_rows += next.num_rows();
+            if next.primary_key() != self.primary_key() {
+                self.pending_batch = Some(next);
+                break;
+            }
+
+            let current = self.current_batch.take().unwrap().0;
+            self.current_batch = Some(CompareFirst(Batch::concat(vec![current, next])?));
+        }
+
+        Ok(())
+    }
+
     /// Skips first `num_to_skip` rows from node's current batch. If current batch is empty it fetches
     /// next batch from the node.
     ///
@@ -766,6 +830,112 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_merge_keep_first() {
+        let reader1 = VecBatchReader::new(&[new_batch(
+            b"k1",
+            &[1, 2],
+            &[11, 15],
+            &[OpType::Put, OpType::Put],
+            &[21, 25],
+        )]);
+        let reader2 = VecBatchReader::new(&[new_batch(
+            b"k1",
+            &[1, 2],
+            &[12, 16],
+            &[OpType::Delete, OpType::Put],
+            &[22, 26],
+        )]);
+        let reader3 = VecBatchReader::new(&[new_batch(b"k1", &[1], &[13], &[OpType::Put], &[23])]);
+        let reader4 = VecBatchReader::new(&[new_batch(b"k1", &[1], &[14], &[OpType::Put], &[24])]);
+        let mut reader = MergeReaderBuilder::new()
+            .push_batch_reader(Box::new(reader1))
+            .push_batch_reader(Box::new(reader2))
+            .push_batch_reader(Box::new(reader3))
+            .push_batch_reader(Box::new(reader4))
+            .with_duplicate_mode(DuplicateMode::KeepFirst)
+            .build()
+            .await
+            .unwrap();
+        // Keeps the first put after the delete.
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k1", &[1], &[13], &[OpType::Put], &[23]),
+                new_batch(b"k1", &[2], &[15], &[OpType::Put], &[25]),
+            ],
+        )
+        .await;
+        assert_eq!(4, reader.metrics.num_duplicate_rows);
+    }
+
+    #[tokio::test]
+    async fn test_merge_keep_first_with_delete() {
+        // A put after a delete in the memtable.
+        let reader1 = VecBatchReader::new(&[new_batch(
+            b"k1",
+            &[1, 1, 2],
+            &[13, 12, 14],
+            &[OpType::Put, OpType::Delete, OpType::Put],
+            &[23, 22, 24],
+        )]);
+        // Rows in the SST before the delete.
+        let reader2 = VecBatchReader::new(&[new_batch(
+            b"k1",
+            &[1, 2],
+            &[11, 11],
+            &[OpType::Put, OpType::Put],
+            &[21, 21],
+        )]);
+        let mut reader = MergeReaderBuilder::new()
+            .push_batch_reader(Box::new(reader1))
+            .push_batch_reader(Box::new(reader2))
+            .with_duplicate_mode(DuplicateMode::KeepFirst)
+            .build()
+            .await
+            .unwrap();
+        // The delete hides the put in the SST.
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k1", &[1], &[13], &[OpType::Put], &[23]),
+                new_batch(b"k1", &[2], &[11], &[OpType::Put], &[21]),
+            ],
+        )
+        .await;
+
+        // Rows of the same timestamp are split into two batches.
+        let reader1 = VecBatchReader::new(&[
+            new_batch(b"k1", &[1], &[13], &[OpType::Put], &[23]),
+            new_batch(
+                b"k1",
+                &[1, 2],
+                &[12, 14],
+                &[OpType::Delete, OpType::Put],
+                &[22, 24],
+            ),
+        ]);
+        let reader2 = VecBatchReader::new(&[new_batch(b"k1", &[1], &[11], &[OpType::Put], &[21])]);
+        let mut reader = MergeReaderBuilder::new()
+            .push_batch_reader(Box::new(reader1))
+            .push_batch_reader(Box::new(reader2))
+            .with_duplicate_mode(DuplicateMode::KeepFirst)
+            .build()
+            .await
+            .unwrap();
+        check_reader_result(
+            &mut reader,
+            &[new_batch(
+                b"k1",
+                &[1, 2],
+                &[13, 14],
+                &[OpType::Put, OpType::Put],
+                &[23, 24],
+            )],
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn test_merge_next_node_empty() {
         let reader1 = VecBatchReader::new(&[new_batch(
@@ -0,0 +1,70 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sampler for scans with `TABLESAMPLE`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use datatypes::vectors::BooleanVector;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use store_api::storage::TableSample;
+
+use crate::error::Result;
+use crate::read::Batch;
+
+/// Decides whether to keep each row or block of rows.
+pub(crate) struct Sampler {
+    probability: f64,
+    rng: StdRng,
+}
+
+impl Sampler {
+    /// Creates a sampler for `sample`.
+    ///
+    /// Samplers of different sources (SSTs, memtables) should use different `salt`s so
+    /// they don't make the same decisions. The sampler is seeded by the seed of the
+    /// sample and the `salt`, or randomly if the sample has no seed.
+    ///
+    /// The percentage of the `sample` must be valid.
+    pub(crate) fn new(sample: &TableSample, salt: impl Hash) -> Sampler {
+        let rng = match sample.seed {
+            Some(seed) => {
+                // DefaultHasher::new() always uses the same keys so the result is stable.
+                let mut hasher = DefaultHasher::new();
+                seed.hash(&mut hasher);
+                salt.hash(&mut hasher);
+                StdRng::seed_from_u64(hasher.finish())
+            }
+            None => StdRng::from_entropy(),
+        };
+
+        Sampler {
+            probability: sample.probability(),
+            rng,
+        }
+    }
+
+    /// Returns true if the next row or block is sampled.
+    pub(crate) fn sample(&mut self) -> bool {
+        self.rng.gen_bool(self.probability)
+    }
+
+    /// Removes unsampled rows from the `batch`.
+    pub(crate) fn sample_rows(&mut self, batch: &mut Batch) -> Result<()> {
+        let mask = BooleanVector::from_iter((0..batch.num_rows()).map(|_| Some(self.sample())));
+        batch.filter(&mask)
+    }
+}
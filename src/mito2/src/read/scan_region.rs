@@ -14,24 +14,32 @@
 
 //! Scans a region according to the scan request.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+use api::v1::SemanticType;
 use common_recordbatch::SendableRecordBatchStream;
 use common_telemetry::{debug, warn};
 use common_time::range::TimestampRange;
-use store_api::storage::ScanRequest;
+use datafusion_expr::{Between, BinaryExpr, Expr as DfExpr, Operator};
+use datatypes::value::Value;
+use snafu::ensure;
+use store_api::storage::{ColumnId, IndexHint, ScanRequest};
 use table::predicate::{Predicate, TimeRangePredicateBuilder};
 
 use crate::access_layer::AccessLayerRef;
 use crate::cache::file_cache::FileCacheRef;
 use crate::cache::CacheManagerRef;
-use crate::error::Result;
+use crate::error::{InvalidRequestSnafu, Result};
 use crate::read::projection::ProjectionMapper;
 use crate::read::seq_scan::SeqScan;
 use crate::region::version::VersionRef;
 use crate::sst::file::FileHandle;
 use crate::sst::index::applier::builder::SstIndexApplierBuilder;
 use crate::sst::index::applier::SstIndexApplierRef;
+use crate::sst::index::explain::FileIndexExplain;
 
 /// A scanner scans a region and returns a [SendableRecordBatchStream].
 pub(crate) enum Scanner {
@@ -47,6 +55,29 @@ impl Scanner {
             Scanner::Seq(seq_scan) => seq_scan.build_stream().await,
         }
     }
+
+    /// Explains how indexes of SSTs to scan prune row groups.
+    pub(crate) async fn explain_index(&self) -> Result<Vec<FileIndexExplain>> {
+        match self {
+            Scanner::Seq(seq_scan) => seq_scan.explain_index().await,
+        }
+    }
+
+    /// Counts rows to scan without reading row groups of SSTs, returns `None` if
+    /// rows must be scanned to count them.
+    pub(crate) async fn count_rows_by_metadata(&self) -> Result<Option<u64>> {
+        match self {
+            Scanner::Seq(seq_scan) => seq_scan.count_rows_by_metadata().await,
+        }
+    }
+
+    /// Returns the min and max values of the `column` to scan without reading row
+    /// groups of SSTs, returns `None` if rows must be scanned to find them.
+    pub(crate) async fn min_max_by_metadata(&self, column: &str) -> Result<Option<(Value, Value)>> {
+        match self {
+            Scanner::Seq(seq_scan) => seq_scan.min_max_by_metadata(column).await,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -71,6 +102,27 @@ impl Scanner {
             Scanner::Seq(seq_scan) => seq_scan.file_ids(),
         }
     }
+
+    /// Returns bytes fetched from the object store by the scanner.
+    pub(crate) fn fetched_bytes(&self) -> &crate::read::FetchedBytesRef {
+        match self {
+            Scanner::Seq(seq_scan) => seq_scan.fetched_bytes(),
+        }
+    }
+
+    /// Returns the index applier of the scanner.
+    pub(crate) fn index_applier(&self) -> Option<&SstIndexApplierRef> {
+        match self {
+            Scanner::Seq(seq_scan) => seq_scan.index_applier(),
+        }
+    }
+
+    /// Returns the order to read sources, `None` for memtables and file ids for SSTs.
+    pub(crate) fn read_order(&self) -> Vec<Option<crate::sst::file::FileId>> {
+        match self {
+            Scanner::Seq(seq_scan) => seq_scan.read_order(),
+        }
+    }
 }
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
@@ -122,6 +174,10 @@ pub(crate) struct ScanRegion {
     cache_manager: Option<CacheManagerRef>,
     /// Parallelism to scan.
     parallelism: ScanParallism,
+    /// Whether to verify checksums of SST footers.
+    verify_checksum: bool,
+    /// Number of row groups of a SST to fetch in advance.
+    prefetch: usize,
 }
 
 impl ScanRegion {
@@ -138,6 +194,8 @@ impl ScanRegion {
             request,
             cache_manager,
             parallelism: ScanParallism::default(),
+            verify_checksum: false,
+            prefetch: 0,
         }
     }
 
@@ -148,6 +206,20 @@ impl ScanRegion {
         self
     }
 
+    /// Sets whether to verify checksums of SST footers.
+    #[must_use]
+    pub(crate) fn with_verify_checksum(mut self, verify_checksum: bool) -> Self {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Sets the number of row groups of a SST to fetch in advance.
+    #[must_use]
+    pub(crate) fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
     /// Returns a [Scanner] to scan the region.
     pub(crate) fn scanner(self) -> Result<Scanner> {
         self.seq_scan().map(Scanner::Seq)
@@ -155,6 +227,18 @@ impl ScanRegion {
 
     /// Scan sequentially.
     pub(crate) fn seq_scan(self) -> Result<SeqScan> {
+        if let Some(sample) = &self.request.sample {
+            ensure!(
+                sample.is_valid(),
+                InvalidRequestSnafu {
+                    region_id: self.version.metadata.region_id,
+                    reason: format!(
+                        "sample percentage {} is not in range [0, 100]",
+                        sample.percentage
+                    ),
+                }
+            );
+        }
         let time_range = self.build_time_range_predicate();
 
         let ssts = &self.version.ssts;
@@ -207,6 +291,20 @@ impl ScanRegion {
             None => ProjectionMapper::all(&self.version.metadata)?,
         };
 
+        // The required output ordering overrides the preferred source order.
+        let source_order = if self.request.output_ordering.is_some() {
+            None
+        } else {
+            self.request.source_order
+        };
+        // Filters are applied inexactly so the scan can only stop at the limit if
+        // there is no filter.
+        let limit = if self.request.filters.is_empty() {
+            self.request.limit
+        } else {
+            None
+        };
+
         let seq_scan = SeqScan::new(self.access_layer.clone(), mapper)
             .with_time_range(Some(time_range))
             .with_predicate(Some(predicate))
@@ -214,7 +312,15 @@ impl ScanRegion {
             .with_files(files)
             .with_cache(self.cache_manager)
             .with_index_applier(index_applier)
-            .with_parallelism(self.parallelism);
+            .with_parallelism(self.parallelism)
+            .with_sample(self.request.sample.clone())
+            .with_source_order(source_order)
+            .with_limit(limit)
+            .with_query_fingerprint(Some(query_fingerprint(&self.request)))
+            .with_exact_time_range(self.is_exact_time_range())
+            .with_duplicate_mode(self.version.options.duplicate_mode)
+            .with_verify_checksum(self.verify_checksum)
+            .with_prefetch(self.prefetch);
 
         Ok(seq_scan)
     }
@@ -232,8 +338,24 @@ impl ScanRegion {
             .build()
     }
 
+    /// Returns true if filters only compare the time index with literals, so the
+    /// time range built from them selects rows exactly.
+    fn is_exact_time_range(&self) -> bool {
+        let time_index = &self.version.metadata.time_index_column().column_schema.name;
+        self.request
+            .filters
+            .iter()
+            .all(|filter| is_exact_time_filter(filter.df_expr(), time_index))
+    }
+
     /// Use the latest schema to build the index applier.
     fn build_index_applier(&self) -> Option<SstIndexApplierRef> {
+        let columns = match &self.request.index_hint {
+            Some(IndexHint::NoIndex) => return None,
+            Some(IndexHint::UseIndex(names)) => self.hinted_index_columns(names),
+            None => None,
+        };
+
         let file_cache = || -> Option<FileCacheRef> {
             let cache_manager = self.cache_manager.as_ref()?;
             let write_cache = cache_manager.write_cache()?;
@@ -247,12 +369,35 @@ impl ScanRegion {
             file_cache,
             self.version.metadata.as_ref(),
         )
+        .path_strategy(self.access_layer.path_strategy().clone())
+        .columns(columns)
         .build(&self.request.filters)
         .inspect_err(|err| warn!(err; "Failed to build index applier"))
         .ok()
         .flatten()
         .map(Arc::new)
     }
+
+    /// Returns ids of indexed columns in the `use_index` hint, or `None` to ignore the
+    /// hint if no column in the hint is indexed.
+    fn hinted_index_columns(&self, names: &[String]) -> Option<HashSet<ColumnId>> {
+        let metadata = &self.version.metadata;
+        let mut columns = HashSet::with_capacity(names.len());
+        for name in names {
+            // Only tag columns have inverted indexes.
+            match metadata.column_by_name(name) {
+                Some(column) if column.semantic_type == SemanticType::Tag => {
+                    columns.insert(column.column_id);
+                }
+                _ => warn!(
+                    "Ignore index hint on column {} of region {}, the column is not indexed",
+                    name, metadata.region_id
+                ),
+            }
+        }
+
+        (!columns.is_empty()).then_some(columns)
+    }
 }
 
 /// Config for parallel scan.
@@ -281,3 +426,47 @@ fn file_in_range(file: &FileHandle, predicate: &TimestampRange) -> bool {
     let file_ts_range = TimestampRange::new_inclusive(Some(start), Some(end));
     file_ts_range.intersects(predicate)
 }
+
+/// Returns true if the `expr` only compares the `time_index` column with literals by
+/// range operators. Equality is excluded as literals more precise than the time index
+/// are truncated while building the time range.
+fn is_exact_time_filter(expr: &DfExpr, time_index: &str) -> bool {
+    let is_time_index =
+        |expr: &DfExpr| matches!(expr, DfExpr::Column(column) if column.name == time_index);
+    let is_literal = |expr: &DfExpr| matches!(expr, DfExpr::Literal(_));
+    match expr {
+        DfExpr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => is_exact_time_filter(left, time_index) && is_exact_time_filter(right, time_index),
+        DfExpr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq,
+            right,
+        }) => {
+            (is_time_index(left) && is_literal(right)) || (is_literal(left) && is_time_index(right))
+        }
+        DfExpr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) => is_time_index(expr) && is_literal(low) && is_literal(high),
+        _ => false,
+    }
+}
+
+/// Returns the fingerprint of the `request`. Requests with the same fingerprint read
+/// the same row groups of a SST.
+fn query_fingerprint(request: &ScanRequest) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request.projection.hash(&mut hasher);
+    for filter in &request.filters {
+        filter.df_expr().hash(&mut hasher);
+    }
+    // Samples and index hints also decide row groups to read.
+    format!("{:?}", request.sample).hash(&mut hasher);
+    format!("{:?}", request.index_hint).hash(&mut hasher);
+    hasher.finish()
+}
@@ -14,32 +14,73 @@
 
 //! Sequential scan.
 
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
 
+use api::v1::SemanticType;
 use async_stream::try_stream;
 use common_error::ext::BoxedError;
 use common_recordbatch::error::ExternalSnafu;
-use common_recordbatch::{RecordBatch, RecordBatchStreamWrapper, SendableRecordBatchStream};
+use common_recordbatch::{
+    OrderOption, RecordBatch, RecordBatchMetrics, RecordBatchStream, RecordBatchStreamWrapper,
+    SendableRecordBatchStream,
+};
 use common_telemetry::{debug, error};
 use common_time::range::TimestampRange;
-use snafu::ResultExt;
+use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use futures::{Stream, StreamExt};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{ColumnId, SampleMethod, SourceOrder, TableSample};
 use table::predicate::Predicate;
 use tokio::sync::{mpsc, Semaphore};
 use tokio_stream::wrappers::ReceiverStream;
 
 use crate::access_layer::AccessLayerRef;
 use crate::cache::{CacheManager, CacheManagerRef};
-use crate::error::Result;
+use crate::error::{ColumnNotFoundSnafu, Result};
 use crate::memtable::MemtableRef;
 use crate::metrics::READ_STAGE_ELAPSED;
 use crate::read::compat::{self, CompatReader};
-use crate::read::merge::MergeReaderBuilder;
+use crate::read::merge::{MergeReader, MergeReaderBuilder};
 use crate::read::projection::ProjectionMapper;
+use crate::read::sample::Sampler;
 use crate::read::scan_region::ScanParallism;
-use crate::read::{BatchReader, BoxedBatchReader, BoxedBatchStream, Source};
-use crate::sst::file::FileHandle;
-use crate::sst::index::applier::SstIndexApplierRef;
+use crate::read::{
+    BatchReader, BoxedBatchReader, BoxedBatchStream, FetchedBytes, FetchedBytesRef, IndexMetrics,
+    IndexMetricsRef, Source,
+};
+use crate::region::options::DuplicateMode;
+use crate::sst::file::{FileHandle, FileId, FileTimeRange};
+use crate::sst::index::applier::{IndexApplyOutput, SstIndexApplierRef};
+use crate::sst::index::explain::FileIndexExplain;
+
+/// Max number of SST files to apply the index concurrently.
+const MAX_CONCURRENT_INDEX_APPLY: usize = 8;
+
+/// Returns true if any two of the inclusive time `ranges` overlap.
+fn ranges_overlap(ranges: &[FileTimeRange]) -> bool {
+    let mut ranges = ranges.to_vec();
+    ranges.sort_unstable_by_key(|(start, _)| *start);
+    ranges.windows(2).any(|pair| pair[0].1 >= pair[1].0)
+}
+
+/// Merges the non-null `value` into the min and max values.
+fn merge_min_max(min_max: &mut (Value, Value), value: Value) {
+    if value.is_null() {
+        return;
+    }
+    if min_max.0.is_null() || value < min_max.0 {
+        min_max.0 = value.clone();
+    }
+    if min_max.1.is_null() || value > min_max.1 {
+        min_max.1 = value;
+    }
+}
 
 /// Scans a region and returns rows in a sorted sequence.
 ///
@@ -65,6 +106,27 @@ pub struct SeqScan {
     parallelism: ScanParallism,
     /// Index applier.
     index_applier: Option<SstIndexApplierRef>,
+    /// Bytes fetched from the object store by the scan.
+    fetched_bytes: FetchedBytesRef,
+    /// Metrics of applying indexes of SSTs by the scan.
+    index_metrics: IndexMetricsRef,
+    /// Returns only a sample of rows if set.
+    sample: Option<TableSample>,
+    /// Order to read memtables and SSTs.
+    source_order: Option<SourceOrder>,
+    /// Max number of rows to return, the scan stops once it returns enough rows.
+    limit: Option<usize>,
+    /// Fingerprint of the query to scan.
+    query_fingerprint: Option<u64>,
+    /// Whether the time range is the only filter of the scan and it selects rows
+    /// exactly, so rows can be counted without evaluating filters.
+    exact_time_range: bool,
+    /// How to select a row among duplicate rows.
+    duplicate_mode: DuplicateMode,
+    /// Whether to verify checksums of SST footers.
+    verify_checksum: bool,
+    /// Number of row groups of a SST to fetch in advance.
+    prefetch: usize,
 }
 
 impl SeqScan {
@@ -82,6 +144,16 @@ impl SeqScan {
             ignore_file_not_found: false,
             parallelism: ScanParallism::default(),
             index_applier: None,
+            fetched_bytes: Arc::new(FetchedBytes::default()),
+            index_metrics: Arc::new(IndexMetrics::default()),
+            sample: None,
+            source_order: None,
+            limit: None,
+            query_fingerprint: None,
+            exact_time_range: false,
+            duplicate_mode: DuplicateMode::default(),
+            verify_checksum: false,
+            prefetch: 0,
         }
     }
 
@@ -141,6 +213,78 @@ impl SeqScan {
         self
     }
 
+    /// Sets the sample of the scan.
+    #[must_use]
+    pub(crate) fn with_sample(mut self, sample: Option<TableSample>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    /// Sets the order to read memtables and SSTs, `None` to read memtables first and
+    /// SSTs in ascending level.
+    #[must_use]
+    pub(crate) fn with_source_order(mut self, source_order: Option<SourceOrder>) -> Self {
+        self.source_order = source_order;
+        self
+    }
+
+    /// Sets the max number of rows to return.
+    ///
+    /// The limit must only be set if the scan selects rows exactly, i.e. there are
+    /// no filters to evaluate on the rows returned.
+    #[must_use]
+    pub(crate) fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets the fingerprint of the query, so readers of SSTs fetch row groups read by
+    /// the same query before in advance.
+    #[must_use]
+    pub(crate) fn with_query_fingerprint(mut self, query_fingerprint: Option<u64>) -> Self {
+        self.query_fingerprint = query_fingerprint;
+        self
+    }
+
+    /// Sets whether the time range is the only filter of the scan and it selects
+    /// rows exactly.
+    #[must_use]
+    pub(crate) fn with_exact_time_range(mut self, exact: bool) -> Self {
+        self.exact_time_range = exact;
+        self
+    }
+
+    /// Sets how to select a row among duplicate rows.
+    #[must_use]
+    pub(crate) fn with_duplicate_mode(mut self, duplicate_mode: DuplicateMode) -> Self {
+        self.duplicate_mode = duplicate_mode;
+        self
+    }
+
+    /// Sets whether to verify checksums of SST footers.
+    #[must_use]
+    pub(crate) fn with_verify_checksum(mut self, verify_checksum: bool) -> Self {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Sets the number of row groups of a SST to fetch in advance.
+    #[must_use]
+    pub(crate) fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Returns the mapper to convert batches into record batches.
+    pub(crate) fn mapper(&self) -> &Arc<ProjectionMapper> {
+        &self.mapper
+    }
+
+    /// Returns the predicate to push down.
+    pub(crate) fn predicate(&self) -> Option<&Predicate> {
+        self.predicate.as_ref()
+    }
+
     /// Builds a stream for the query.
     pub async fn build_stream(&self) -> Result<SendableRecordBatchStream> {
         let start = Instant::now();
@@ -160,17 +304,36 @@ impl SeqScan {
         let mapper = self.mapper.clone();
         let cache_manager = self.cache_manager.clone();
         let parallelism = self.parallelism.parallelism;
+        let fetched_bytes = self.fetched_bytes.clone();
+        // Samples rows after merging so each row is sampled once.
+        let mut sampler = self
+            .sample
+            .as_ref()
+            .filter(|sample| sample.method == SampleMethod::Bernoulli)
+            .map(|sample| Sampler::new(sample, mapper.metadata().region_id));
+        let mut remaining = self.limit.unwrap_or(usize::MAX);
         let stream = try_stream! {
             let cache = cache_manager.as_ref().map(|cache| cache.as_ref());
-            while let Some(batch) =
-                Self::fetch_record_batch(&mut reader, &mapper, cache, &mut metrics).await?
-            {
+            // Stops reading sources once the limit is reached.
+            while remaining > 0 {
+                let Some(batch) =
+                    Self::fetch_record_batch(&mut reader, &mapper, cache, &mut sampler, &mut metrics)
+                        .await?
+                else {
+                    break;
+                };
+                let batch = if batch.num_rows() > remaining {
+                    batch.slice(0, remaining)
+                } else {
+                    batch
+                };
+                remaining -= batch.num_rows();
                 yield batch;
             }
 
             debug!(
-                "Seq scan finished, region_id: {:?}, metrics: {:?}, use_parallel: {}, parallelism: {}",
-                mapper.metadata().region_id, metrics, use_parallel, parallelism,
+                "Seq scan finished, region_id: {:?}, metrics: {:?}, use_parallel: {}, parallelism: {}, fetched bytes: {:?}",
+                mapper.metadata().region_id, metrics, use_parallel, parallelism, fetched_bytes.per_file(),
             );
             // Update metrics.
             READ_STAGE_ELAPSED.with_label_values(&["total"]).observe(metrics.scan_cost.as_secs_f64());
@@ -180,7 +343,11 @@ impl SeqScan {
             Box::pin(stream),
         ));
 
-        Ok(stream)
+        Ok(Box::pin(SeqScanStream {
+            stream,
+            fetched_bytes: self.fetched_bytes.clone(),
+            index_metrics: self.index_metrics.clone(),
+        }))
     }
 
     /// Builds a [BoxedBatchReader] from sequential scan.
@@ -188,6 +355,7 @@ impl SeqScan {
         // Scans all memtables and SSTs. Builds a merge reader to merge results.
         let sources = self.build_sources().await?;
         let mut builder = MergeReaderBuilder::from_sources(sources);
+        builder.with_duplicate_mode(self.duplicate_mode);
         Ok(Box::new(builder.build().await?))
     }
 
@@ -206,17 +374,35 @@ impl SeqScan {
             })
             .collect();
         let mut builder = MergeReaderBuilder::from_sources(sources);
+        builder.with_duplicate_mode(self.duplicate_mode);
         Ok(Box::new(builder.build().await?))
     }
 
     /// Builds and returns sources to read.
     async fn build_sources(&self) -> Result<Vec<Source>> {
-        let mut sources = Vec::with_capacity(self.memtables.len() + self.files.len());
-        for mem in &self.memtables {
-            let iter = mem.iter(Some(self.mapper.column_ids()), self.predicate.clone());
-            sources.push(Source::Iter(iter));
+        // Samples blocks of each source before merging.
+        let block_sample = self
+            .sample
+            .clone()
+            .filter(|sample| sample.method == SampleMethod::System);
+        let (memtables, files) = self.memtables_and_files_in_order();
+        let mut mem_sources = Vec::with_capacity(memtables.len());
+        for mem in memtables {
+            let mut iter = mem.iter(Some(self.mapper.column_ids()), self.predicate.clone());
+            if let Some(sample) = &block_sample {
+                // Batches of the memtable are blocks to sample.
+                let mut sampler = Sampler::new(sample, mem.id());
+                iter = Box::new(iter.filter(move |batch| batch.is_err() || sampler.sample()));
+            }
+            mem_sources.push(Source::Iter(iter));
         }
-        for file in &self.files {
+        let mut index_outputs = self.apply_index(&files).await;
+        for output in index_outputs.values() {
+            self.index_metrics
+                .add(output.row_groups.len(), output.num_postings);
+        }
+        let mut file_sources = Vec::with_capacity(files.len());
+        for file in files {
             let maybe_reader = self
                 .access_layer
                 .read_sst(file.clone())
@@ -225,6 +411,16 @@ impl SeqScan {
                 .projection(Some(self.mapper.column_ids().to_vec()))
                 .cache(self.cache_manager.clone())
                 .index_applier(self.index_applier.clone())
+                .index_row_groups(
+                    index_outputs
+                        .remove(&file.file_id())
+                        .map(|output| output.row_groups),
+                )
+                .fetched_bytes(Some(self.fetched_bytes.clone()))
+                .sample(block_sample.clone())
+                .query_fingerprint(self.query_fingerprint)
+                .verify_checksum(self.verify_checksum)
+                .prefetch(self.prefetch)
                 .build()
                 .await;
             let reader = match maybe_reader {
@@ -239,17 +435,246 @@ impl SeqScan {
                 }
             };
             if compat::has_same_columns(self.mapper.metadata(), reader.metadata()) {
-                sources.push(Source::Reader(Box::new(reader)));
+                file_sources.push(Source::Reader(Box::new(reader)));
             } else {
                 // They have different schema. We need to adapt the batch first so the
                 // mapper can convert it.
                 let compat_reader =
                     CompatReader::new(&self.mapper, reader.metadata().clone(), reader)?;
-                sources.push(Source::Reader(Box::new(compat_reader)));
+                file_sources.push(Source::Reader(Box::new(compat_reader)));
+            }
+        }
+
+        if self.memtables_first() {
+            mem_sources.extend(file_sources);
+            Ok(mem_sources)
+        } else {
+            file_sources.extend(mem_sources);
+            Ok(file_sources)
+        }
+    }
+
+    /// Applies the index of all `files` in advance as applying them one by one while
+    /// building readers is slow.
+    ///
+    /// Files without index or failed to apply are absent in the result.
+    async fn apply_index(&self, files: &[&FileHandle]) -> HashMap<FileId, IndexApplyOutput> {
+        let Some(index_applier) = &self.index_applier else {
+            return HashMap::new();
+        };
+        let file_ids: Vec<_> = files
+            .iter()
+            .filter(|file| file.meta().inverted_index_available())
+            .map(|file| (file.file_id(), file.index_file_id()))
+            .collect();
+        index_applier
+            .apply_files(&file_ids, MAX_CONCURRENT_INDEX_APPLY)
+            .await
+    }
+
+    /// Explains how indexes of SSTs to scan prune row groups, in the order to read SSTs.
+    ///
+    /// Indexes are applied in the same way as the scan applies them.
+    pub(crate) async fn explain_index(&self) -> Result<Vec<FileIndexExplain>> {
+        let (_, files) = self.memtables_and_files_in_order();
+        let mut index_outputs = self.apply_index(&files).await;
+        let mut explains = Vec::with_capacity(files.len());
+        for file in files {
+            let num_row_groups = self
+                .access_layer
+                .read_sst(file.clone())
+                .cache(self.cache_manager.clone())
+                .read_num_row_groups()
+                .await;
+            match num_row_groups {
+                Ok(total_row_groups) => {
+                    let output = index_outputs.remove(&file.file_id());
+                    explains.push(FileIndexExplain {
+                        file_id: file.file_id(),
+                        index_available: file.meta().inverted_index_available(),
+                        total_row_groups,
+                        selected_row_groups: output.as_ref().map(|output| output.row_groups.len()),
+                        num_postings: output.map(|output| output.num_postings).unwrap_or(0),
+                    });
+                }
+                Err(e) if e.is_object_not_found() && self.ignore_file_not_found => {
+                    error!(e; "File to scan does not exist, region_id: {}, file: {}", file.region_id(), file.file_id());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(explains)
+    }
+
+    /// Counts rows to scan from row counts in the metadata of SSTs and rows in
+    /// memtables, without reading row groups of SSTs.
+    ///
+    /// Returns `None` if rows must be scanned to count them, i.e. filters other than
+    /// the time range, sources partially in the time range, SSTs overlapping with other
+    /// sources in time that may have the same rows, or SSTs that may have deleted rows.
+    pub(crate) async fn count_rows_by_metadata(&self) -> Result<Option<u64>> {
+        match self.source_time_ranges() {
+            Some(ranges) if !ranges_overlap(&ranges) => (),
+            _ => return Ok(None),
+        }
+
+        let mut num_rows = 0;
+        for file in &self.files {
+            let put_rows = self
+                .access_layer
+                .read_sst(file.clone())
+                .cache(self.cache_manager.clone())
+                .read_put_rows()
+                .await;
+            match put_rows {
+                Ok(Some(rows)) => num_rows += rows,
+                Ok(None) => return Ok(None),
+                Err(e) if e.is_object_not_found() && self.ignore_file_not_found => {
+                    error!(e; "File to scan does not exist, region_id: {}, file: {}", file.region_id(), file.file_id());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        // Only reads the primary key and the time index of memtables.
+        let mut reader = self.build_memtable_reader(&[]).await?;
+        while let Some(batch) = reader.next_batch().await? {
+            num_rows += batch.num_rows() as u64;
+        }
+
+        Ok(Some(num_rows))
+    }
+
+    /// Returns the min and max values of the time index or a field `column` to scan
+    /// from statistics in the metadata of SSTs and rows in memtables, without reading
+    /// row groups of SSTs. Both values are null if all values of the column are null.
+    ///
+    /// Returns `None` if rows must be scanned to find the values, i.e. the column is a
+    /// tag, filters other than the time range, sources partially in the time range,
+    /// SSTs overlapping with other sources in time that may have overwritten or deleted
+    /// rows of the SST, or SSTs without statistics of the column or with deleted rows.
+    pub(crate) async fn min_max_by_metadata(&self, column: &str) -> Result<Option<(Value, Value)>> {
+        let metadata = self.mapper.metadata();
+        let column = metadata
+            .column_by_name(column)
+            .context(ColumnNotFoundSnafu {
+                column: column.to_string(),
+            })?;
+        let column_id = column.column_id;
+        let is_field = match column.semantic_type {
+            SemanticType::Field => true,
+            SemanticType::Timestamp => false,
+            // Tags are encoded in primary keys.
+            SemanticType::Tag => return Ok(None),
+        };
+        match self.source_time_ranges() {
+            // Statistics of SSTs still contain rows deleted by other sources.
+            Some(ranges) if !ranges_overlap(&ranges) => (),
+            _ => return Ok(None),
+        }
+
+        let mut min_max = (Value::Null, Value::Null);
+        for file in &self.files {
+            let file_min_max = self
+                .access_layer
+                .read_sst(file.clone())
+                .cache(self.cache_manager.clone())
+                .read_min_max(column_id)
+                .await;
+            match file_min_max {
+                Ok(Some((min, max))) => {
+                    merge_min_max(&mut min_max, min);
+                    merge_min_max(&mut min_max, max);
+                }
+                Ok(None) => return Ok(None),
+                Err(e) if e.is_object_not_found() && self.ignore_file_not_found => {
+                    error!(e; "File to scan does not exist, region_id: {}, file: {}", file.region_id(), file.file_id());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        let projection = if is_field { vec![column_id] } else { vec![] };
+        let mut reader = self.build_memtable_reader(&projection).await?;
+        while let Some(batch) = reader.next_batch().await? {
+            let values = if is_field {
+                // Safety: the field is projected.
+                let field = batch.fields().iter().find(|f| f.column_id == column_id);
+                &field.unwrap().data
+            } else {
+                batch.timestamps()
+            };
+            for i in 0..values.len() {
+                merge_min_max(&mut min_max, values.get(i));
+            }
+        }
+
+        Ok(Some(min_max))
+    }
+
+    /// Returns time ranges of SSTs and memtables to scan if all their rows match
+    /// filters of the scan, i.e. the time range is the only filter and sources are
+    /// entirely in the time range. Memtables are merged as one source.
+    fn source_time_ranges(&self) -> Option<Vec<FileTimeRange>> {
+        if !self.exact_time_range || self.sample.is_some() {
+            return None;
+        }
+        let time_range = self.time_range.unwrap_or_else(TimestampRange::min_to_max);
+
+        let mut ranges: Vec<_> = self.files.iter().map(|file| file.time_range()).collect();
+        let mut memtable_range: Option<FileTimeRange> = None;
+        for mem in &self.memtables {
+            let (start, end) = mem.stats().time_range()?;
+            memtable_range = Some(match memtable_range {
+                Some((min, max)) => (min.min(start), max.max(end)),
+                None => (start, end),
+            });
+        }
+        ranges.extend(memtable_range);
+        // Time ranges are inclusive.
+        ranges
+            .iter()
+            .all(|(start, end)| time_range.contains(start) && time_range.contains(end))
+            .then_some(ranges)
+    }
+
+    /// Builds a reader to merge rows of memtables to scan, which removes duplicate and
+    /// deleted rows. Only reads fields in the `projection`.
+    async fn build_memtable_reader(&self, projection: &[ColumnId]) -> Result<MergeReader> {
+        let sources = self
+            .memtables
+            .iter()
+            .map(|mem| Source::Iter(mem.iter(Some(projection), None)))
+            .collect();
+        MergeReaderBuilder::from_sources(sources)
+            .with_duplicate_mode(self.duplicate_mode)
+            .build()
+            .await
+    }
+
+    /// Returns memtables and SSTs to read, sorted by the source order.
+    ///
+    /// The merge reader dedups rows by sequence so the order doesn't affect the result,
+    /// it only decides which sources to read first.
+    fn memtables_and_files_in_order(&self) -> (Vec<&MemtableRef>, Vec<&FileHandle>) {
+        let mut memtables: Vec<_> = self.memtables.iter().collect();
+        let mut files: Vec<_> = self.files.iter().collect();
+        match self.source_order {
+            // Newer memtables have larger ids.
+            Some(SourceOrder::NewestFirst) => {
+                memtables.sort_unstable_by_key(|mem| Reverse(mem.id()));
+                files.sort_by_key(|file| Reverse(file.time_range().1));
+            }
+            Some(SourceOrder::OldestFirst) => {
+                memtables.sort_unstable_by_key(|mem| mem.id());
+                files.sort_by_key(|file| file.time_range().0);
             }
+            None => (),
         }
+        (memtables, files)
+    }
 
-        Ok(sources)
+    /// Returns true if memtables are read before SSTs.
+    fn memtables_first(&self) -> bool {
+        self.source_order != Some(SourceOrder::OldestFirst)
     }
 
     /// Returns whether to use a parallel reader.
@@ -286,23 +711,38 @@ impl SeqScan {
     }
 
     /// Fetch a batch from the reader and convert it into a record batch.
+    ///
+    /// Skips batches that have no rows sampled by the `sampler`.
     async fn fetch_record_batch(
         reader: &mut dyn BatchReader,
         mapper: &ProjectionMapper,
         cache: Option<&CacheManager>,
+        sampler: &mut Option<Sampler>,
         metrics: &mut Metrics,
     ) -> common_recordbatch::error::Result<Option<RecordBatch>> {
         let start = Instant::now();
 
-        let Some(batch) = reader
-            .next_batch()
-            .await
-            .map_err(BoxedError::new)
-            .context(ExternalSnafu)?
-        else {
-            metrics.scan_cost += start.elapsed();
+        let batch = loop {
+            let Some(mut batch) = reader
+                .next_batch()
+                .await
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?
+            else {
+                metrics.scan_cost += start.elapsed();
 
-            return Ok(None);
+                return Ok(None);
+            };
+            let Some(sampler) = sampler else {
+                break batch;
+            };
+            sampler
+                .sample_rows(&mut batch)
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+            if !batch.is_empty() {
+                break batch;
+            }
         };
 
         let convert_start = Instant::now();
@@ -314,6 +754,42 @@ impl SeqScan {
     }
 }
 
+/// Stream of [SeqScan] that reports bytes fetched and indexes applied by the scan in
+/// its metrics.
+struct SeqScanStream {
+    stream: SendableRecordBatchStream,
+    fetched_bytes: FetchedBytesRef,
+    index_metrics: IndexMetricsRef,
+}
+
+impl Stream for SeqScanStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl RecordBatchStream for SeqScanStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn output_ordering(&self) -> Option<&[OrderOption]> {
+        self.stream.output_ordering()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        Some(RecordBatchMetrics {
+            fetched_bytes: self.fetched_bytes.total(),
+            index_applied_files: self.index_metrics.applied_files(),
+            index_selected_row_groups: self.index_metrics.selected_row_groups(),
+            index_postings: self.index_metrics.postings(),
+            ..Default::default()
+        })
+    }
+}
+
 /// Metrics for [SeqScan].
 #[derive(Debug, Default)]
 struct Metrics {
@@ -341,4 +817,26 @@ impl SeqScan {
     pub(crate) fn file_ids(&self) -> Vec<crate::sst::file::FileId> {
         self.files.iter().map(|file| file.file_id()).collect()
     }
+
+    /// Returns bytes fetched from the object store by the scan.
+    pub(crate) fn fetched_bytes(&self) -> &FetchedBytesRef {
+        &self.fetched_bytes
+    }
+
+    /// Returns the index applier of the scan.
+    pub(crate) fn index_applier(&self) -> Option<&SstIndexApplierRef> {
+        self.index_applier.as_ref()
+    }
+
+    /// Returns the order to read sources, `None` for memtables and file ids for SSTs.
+    pub(crate) fn read_order(&self) -> Vec<Option<crate::sst::file::FileId>> {
+        let (memtables, files) = self.memtables_and_files_in_order();
+        let memtables = memtables.into_iter().map(|_| None);
+        let files = files.into_iter().map(|file| Some(file.file_id()));
+        if self.memtables_first() {
+            memtables.chain(files).collect()
+        } else {
+            files.chain(memtables).collect()
+        }
+    }
 }
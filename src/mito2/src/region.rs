@@ -14,8 +14,11 @@
 
 //! Mito region.
 
+pub(crate) mod open_limiter;
 pub(crate) mod opener;
 pub mod options;
+pub(crate) mod series_limiter;
+pub(crate) mod subscriber;
 pub(crate) mod version;
 
 use std::collections::HashMap;
@@ -24,14 +27,16 @@ use std::sync::{Arc, RwLock};
 
 use common_config::wal::WalOptions;
 use common_telemetry::info;
-use common_time::util::current_time_millis;
 use snafu::{ensure, OptionExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::storage::RegionId;
 
 use crate::access_layer::AccessLayerRef;
-use crate::error::{RegionNotFoundSnafu, RegionReadonlySnafu, Result};
+use crate::error::{RegionFrozenSnafu, RegionNotFoundSnafu, RegionReadonlySnafu, Result};
 use crate::manifest::manager::RegionManifestManager;
+use crate::metrics::MemtableMetricsRef;
+use crate::region::series_limiter::SeriesLimiterRef;
+use crate::region::subscriber::WriteSubscribersRef;
 use crate::region::version::{VersionControlRef, VersionRef};
 use crate::request::OnFailure;
 use crate::sst::file_purger::FilePurgerRef;
@@ -81,6 +86,17 @@ pub(crate) struct MitoRegion {
     last_flush_millis: AtomicI64,
     /// Whether the region is writable.
     writable: AtomicBool,
+    /// Whether the region is frozen, a frozen region rejects writes and compactions
+    /// but still serves reads.
+    frozen: AtomicBool,
+    /// Subscribers of rows written to the region.
+    pub(crate) write_subscribers: WriteSubscribersRef,
+    /// Limiter of series in the region.
+    pub(crate) series_limiter: SeriesLimiterRef,
+    /// Memtable metrics of the region.
+    pub(crate) memtable_metrics: MemtableMetricsRef,
+    /// Serializes puts checking duplicate rows in the region.
+    pub(crate) put_lock: tokio::sync::Mutex<()>,
 }
 
 pub(crate) type MitoRegionRef = Arc<MitoRegion>;
@@ -115,9 +131,8 @@ impl MitoRegion {
         self.last_flush_millis.load(Ordering::Relaxed)
     }
 
-    /// Update flush time to current time.
-    pub(crate) fn update_flush_millis(&self) {
-        let now = current_time_millis();
+    /// Update flush time to `now`.
+    pub(crate) fn update_flush_millis(&self, now: i64) {
         self.last_flush_millis.store(now, Ordering::Relaxed);
     }
 
@@ -136,6 +151,16 @@ impl MitoRegion {
         self.writable.store(writable, Ordering::Relaxed);
     }
 
+    /// Returns whether the region is frozen.
+    pub(crate) fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::Relaxed)
+    }
+
+    /// Sets the frozen flag.
+    pub(crate) fn set_frozen(&self, frozen: bool) {
+        self.frozen.store(frozen, Ordering::Relaxed);
+    }
+
     /// Returns the region usage in bytes.
     pub(crate) async fn region_usage(&self) -> RegionUsage {
         let region_id = self.region_id;
@@ -201,6 +226,32 @@ impl RegionMap {
         Ok(region)
     }
 
+    /// Gets writable region that accepts writes by region id.
+    ///
+    /// Returns error if the region does not exist, is readonly or frozen.
+    pub(crate) fn mutable_region(&self, region_id: RegionId) -> Result<MitoRegionRef> {
+        let region = self.writable_region(region_id)?;
+        ensure!(!region.is_frozen(), RegionFrozenSnafu { region_id });
+        Ok(region)
+    }
+
+    /// Gets writable region that accepts writes by region id.
+    ///
+    /// Calls the callback if the region does not exist, is readonly or frozen.
+    pub(crate) fn mutable_region_or<F: OnFailure>(
+        &self,
+        region_id: RegionId,
+        cb: &mut F,
+    ) -> Option<MitoRegionRef> {
+        match self.mutable_region(region_id) {
+            Ok(region) => Some(region),
+            Err(e) => {
+                cb.on_failure(e);
+                None
+            }
+        }
+    }
+
     /// Gets writable region by region id.
     ///
     /// Calls the callback if the region does not exist or is readonly.
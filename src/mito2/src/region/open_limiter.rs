@@ -0,0 +1,398 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limits the number of regions open in the node.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use store_api::region_request::RegionOpenRequest;
+use store_api::storage::RegionId;
+use tokio::sync::RwLock;
+
+use crate::engine::MITO_ENGINE_NAME;
+
+/// Limit value that means the number of open regions is unlimited.
+const UNLIMITED: usize = 0;
+
+/// Lock to access a region tracked by the limiter.
+///
+/// Requests hold the read lock while accessing the region, the limiter closes or
+/// reopens the region under the write lock.
+pub(crate) type RegionLockRef = Arc<RwLock<()>>;
+
+/// Location and options to open a region again.
+#[derive(Debug, Clone)]
+pub(crate) struct RegionOpenOptions {
+    pub(crate) region_dir: String,
+    pub(crate) options: HashMap<String, String>,
+}
+
+impl RegionOpenOptions {
+    /// Returns the request to reopen the region, replaying the WAL.
+    pub(crate) fn to_open_request(&self) -> RegionOpenRequest {
+        RegionOpenRequest {
+            engine: MITO_ENGINE_NAME.to_string(),
+            region_dir: self.region_dir.clone(),
+            options: self.options.clone(),
+            skip_wal_replay: false,
+        }
+    }
+}
+
+/// State of a region closed by the limiter.
+///
+/// The engine still reports the region with this state, so the region keeps its
+/// role and lease until it is reopened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EvictedRegion {
+    /// Whether the region is writable, it's restored after reopening.
+    pub(crate) writable: bool,
+    /// Disk usage of the region when it's closed.
+    pub(crate) disk_usage: u64,
+}
+
+#[derive(Debug)]
+struct TrackedRegion {
+    /// Tick of the last access.
+    last_access: u64,
+    open_options: RegionOpenOptions,
+    lock: RegionLockRef,
+    /// State of the region if it's closed by the limiter.
+    evicted: Option<EvictedRegion>,
+}
+
+#[derive(Debug, Default)]
+struct OpenRegions {
+    /// Increases on each access to order accesses of regions.
+    tick: u64,
+    /// Open regions and regions closed by the limiter.
+    regions: HashMap<RegionId, TrackedRegion>,
+}
+
+/// Tracks the regions open in the node and picks the least recently used ones to
+/// close once there are more open regions than the limit.
+///
+/// The limiter only tracks regions opened through the engine. Regions it closes are
+/// still tracked and reopened on the next access with the options they were opened
+/// with.
+#[derive(Debug)]
+pub(crate) struct OpenRegionLimiter {
+    /// Max number of open regions.
+    limit: usize,
+    regions: Mutex<OpenRegions>,
+    /// Whether a background task is closing regions.
+    evicting: AtomicBool,
+}
+
+impl OpenRegionLimiter {
+    /// Creates a new limiter, the number of open regions is unlimited if `limit` is 0.
+    pub(crate) fn new(limit: usize) -> OpenRegionLimiter {
+        OpenRegionLimiter {
+            limit,
+            regions: Mutex::new(OpenRegions::default()),
+            evicting: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns true if the number of open regions is limited.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.limit != UNLIMITED
+    }
+
+    /// Marks the region as opened and just accessed.
+    pub(crate) fn on_opened(&self, region_id: RegionId, open_options: RegionOpenOptions) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut regions = self.regions.lock().unwrap();
+        regions.tick += 1;
+        let tick = regions.tick;
+        let lock = regions
+            .regions
+            .get(&region_id)
+            .map(|region| region.lock.clone())
+            .unwrap_or_default();
+        regions.regions.insert(
+            region_id,
+            TrackedRegion {
+                last_access: tick,
+                open_options,
+                lock,
+                evicted: None,
+            },
+        );
+    }
+
+    /// Forgets the region closed or dropped by the user.
+    pub(crate) fn on_closed(&self, region_id: RegionId) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.regions.lock().unwrap().regions.remove(&region_id);
+    }
+
+    /// Marks the region as closed by the limiter so it is reopened on the next access.
+    pub(crate) fn on_evicted(&self, region_id: RegionId, evicted: EvictedRegion) {
+        if let Some(region) = self.regions.lock().unwrap().regions.get_mut(&region_id) {
+            region.evicted = Some(evicted);
+        }
+    }
+
+    /// Marks the region closed by the limiter as opened again and just accessed.
+    pub(crate) fn on_reopened(&self, region_id: RegionId) {
+        let mut regions = self.regions.lock().unwrap();
+        regions.tick += 1;
+        let tick = regions.tick;
+        if let Some(region) = regions.regions.get_mut(&region_id) {
+            region.last_access = tick;
+            region.evicted = None;
+        }
+    }
+
+    /// Marks the region as just accessed if it is open.
+    pub(crate) fn touch(&self, region_id: RegionId) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut regions = self.regions.lock().unwrap();
+        regions.tick += 1;
+        let tick = regions.tick;
+        if let Some(region) = regions.regions.get_mut(&region_id) {
+            region.last_access = tick;
+        }
+    }
+
+    /// Returns the lock of the region, or `None` if the limiter doesn't track it.
+    pub(crate) fn region_lock(&self, region_id: RegionId) -> Option<RegionLockRef> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let regions = self.regions.lock().unwrap();
+        regions
+            .regions
+            .get(&region_id)
+            .map(|region| region.lock.clone())
+    }
+
+    /// Returns the state of the region if it is closed by the limiter.
+    pub(crate) fn evicted(&self, region_id: RegionId) -> Option<EvictedRegion> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let regions = self.regions.lock().unwrap();
+        regions
+            .regions
+            .get(&region_id)
+            .and_then(|region| region.evicted)
+    }
+
+    /// Returns the options to reopen the region if it is closed by the limiter.
+    pub(crate) fn evicted_options(
+        &self,
+        region_id: RegionId,
+    ) -> Option<(RegionOpenOptions, EvictedRegion)> {
+        let regions = self.regions.lock().unwrap();
+        let region = regions.regions.get(&region_id)?;
+        region
+            .evicted
+            .map(|evicted| (region.open_options.clone(), evicted))
+    }
+
+    /// Sets whether the region closed by the limiter is writable after reopening.
+    /// Returns false if the region is not closed by the limiter.
+    pub(crate) fn set_evicted_writable(&self, region_id: RegionId, writable: bool) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let mut regions = self.regions.lock().unwrap();
+        match regions
+            .regions
+            .get_mut(&region_id)
+            .and_then(|region| region.evicted.as_mut())
+        {
+            Some(evicted) => {
+                evicted.writable = writable;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if the open region is not accessed since `tick`.
+    pub(crate) fn is_idle_since(&self, region_id: RegionId, tick: u64) -> bool {
+        let regions = self.regions.lock().unwrap();
+        regions
+            .regions
+            .get(&region_id)
+            .is_some_and(|region| region.evicted.is_none() && region.last_access == tick)
+    }
+
+    /// Returns the number of open regions exceeding the limit.
+    pub(crate) fn num_exceeded(&self) -> usize {
+        if !self.is_enabled() {
+            return 0;
+        }
+
+        self.num_open().saturating_sub(self.limit)
+    }
+
+    /// Returns open regions with their last access ticks, from the least recently used
+    /// to the most recently used.
+    pub(crate) fn idle_regions(&self) -> Vec<(RegionId, u64)> {
+        let regions = self.regions.lock().unwrap();
+        let mut idle: Vec<_> = regions
+            .regions
+            .iter()
+            .filter(|(_, region)| region.evicted.is_none())
+            .map(|(id, region)| (region.last_access, *id))
+            .collect();
+        idle.sort_unstable();
+        idle.into_iter().map(|(tick, id)| (id, tick)).collect()
+    }
+
+    /// Returns true if the caller should start closing regions, only one caller
+    /// closes regions at a time.
+    ///
+    /// The caller must call [OpenRegionLimiter::finish_evicting()] after closing.
+    pub(crate) fn start_evicting(&self) -> bool {
+        self.num_exceeded() > 0
+            && self
+                .evicting
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+    }
+
+    /// Allows other callers to close regions.
+    pub(crate) fn finish_evicting(&self) {
+        self.evicting.store(false, Ordering::Release);
+    }
+
+    /// Returns the number of open regions tracked.
+    fn num_open(&self) -> usize {
+        let regions = self.regions.lock().unwrap();
+        regions
+            .regions
+            .values()
+            .filter(|region| region.evicted.is_none())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_options(region_dir: &str) -> RegionOpenOptions {
+        RegionOpenOptions {
+            region_dir: region_dir.to_string(),
+            options: HashMap::new(),
+        }
+    }
+
+    const EVICTED: EvictedRegion = EvictedRegion {
+        writable: true,
+        disk_usage: 10,
+    };
+
+    #[test]
+    fn test_unlimited() {
+        let limiter = OpenRegionLimiter::new(0);
+        assert!(!limiter.is_enabled());
+        for i in 0..4 {
+            limiter.on_opened(RegionId::new(1, i), new_options("test"));
+        }
+        assert_eq!(0, limiter.num_open());
+        assert_eq!(0, limiter.num_exceeded());
+        assert!(limiter.region_lock(RegionId::new(1, 0)).is_none());
+        assert!(!limiter.start_evicting());
+    }
+
+    #[test]
+    fn test_idle_regions() {
+        let limiter = OpenRegionLimiter::new(2);
+        let regions: Vec<_> = (0..3).map(|i| RegionId::new(1, i)).collect();
+        for region_id in &regions {
+            limiter.on_opened(*region_id, new_options("test"));
+        }
+        assert_eq!(1, limiter.num_exceeded());
+        let idle: Vec<_> = limiter
+            .idle_regions()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(regions, idle);
+
+        limiter.touch(regions[0]);
+        let idle = limiter.idle_regions();
+        assert_eq!(regions[1], idle[0].0);
+        assert!(limiter.is_idle_since(idle[0].0, idle[0].1));
+
+        assert!(limiter.start_evicting());
+        // Only one caller closes regions at a time.
+        assert!(!limiter.start_evicting());
+        limiter.on_evicted(regions[1], EVICTED);
+        limiter.finish_evicting();
+        assert_eq!(Some(EVICTED), limiter.evicted(regions[1]));
+        assert_eq!(0, limiter.num_exceeded());
+        assert!(!limiter.start_evicting());
+        assert!(!limiter.is_idle_since(idle[0].0, idle[0].1));
+
+        // The evicted region keeps its options and state.
+        assert!(limiter.set_evicted_writable(regions[1], false));
+        let (options, evicted) = limiter.evicted_options(regions[1]).unwrap();
+        assert_eq!("test", options.region_dir);
+        assert!(!evicted.writable);
+        assert!(limiter.evicted_options(regions[0]).is_none());
+        assert!(!limiter.set_evicted_writable(regions[0], false));
+
+        limiter.on_reopened(regions[1]);
+        assert!(limiter.evicted(regions[1]).is_none());
+        assert_eq!(1, limiter.num_exceeded());
+        // Opening the region again keeps its lock.
+        let lock = limiter.region_lock(regions[1]).unwrap();
+        limiter.on_opened(regions[1], new_options("test"));
+        assert!(Arc::ptr_eq(
+            &lock,
+            &limiter.region_lock(regions[1]).unwrap()
+        ));
+
+        limiter.on_closed(regions[1]);
+        assert_eq!(2, limiter.num_open());
+        assert!(limiter.region_lock(regions[1]).is_none());
+        // Unknown regions are ignored.
+        limiter.touch(regions[1]);
+        assert_eq!(2, limiter.num_open());
+    }
+
+    #[test]
+    fn test_access_while_evicting() {
+        let limiter = OpenRegionLimiter::new(1);
+        let regions: Vec<_> = (0..2).map(|i| RegionId::new(1, i)).collect();
+        for region_id in &regions {
+            limiter.on_opened(*region_id, new_options("test"));
+        }
+        let (region_id, tick) = limiter.idle_regions()[0];
+        // The region is accessed after it is picked to close.
+        limiter.touch(region_id);
+        assert!(!limiter.is_idle_since(region_id, tick));
+    }
+}
@@ -20,14 +20,16 @@ use std::sync::Arc;
 
 use common_config::wal::WalOptions;
 use common_telemetry::{debug, error, info, warn};
-use common_time::util::current_time_millis;
+use common_time::clock::{ClockRef, SystemClock};
 use futures::StreamExt;
 use object_store::manager::ObjectStoreManagerRef;
 use object_store::util::{join_dir, normalize_dir};
+use object_store::ObjectStore;
 use snafu::{ensure, OptionExt};
 use store_api::logstore::LogStore;
 use store_api::metadata::{ColumnMetadata, RegionMetadata};
 use store_api::storage::{ColumnId, RegionId};
+use tokio::sync::mpsc::Sender;
 
 use crate::access_layer::AccessLayer;
 use crate::cache::CacheManagerRef;
@@ -38,13 +40,17 @@ use crate::error::{
 use crate::manifest::manager::{RegionManifestManager, RegionManifestOptions};
 use crate::manifest::storage::manifest_compress_type;
 use crate::memtable::MemtableBuilderRef;
+use crate::metrics::MemtableMetrics;
 use crate::region::options::RegionOptions;
+use crate::region::series_limiter::SeriesLimiter;
 use crate::region::version::{VersionBuilder, VersionControl, VersionControlRef};
 use crate::region::MitoRegion;
 use crate::region_write_ctx::RegionWriteCtx;
-use crate::request::OptionOutputTx;
+use crate::request::{OptionOutputTx, WorkerRequest};
 use crate::schedule::scheduler::SchedulerRef;
 use crate::sst::file_purger::LocalFilePurger;
+use crate::sst::index::limiter::IndexBuildLimiterRef;
+use crate::sst::index::store::RetryPolicy;
 use crate::wal::{EntryId, Wal};
 
 /// Builder to create a new [MitoRegion] or open an existing one.
@@ -57,7 +63,11 @@ pub(crate) struct RegionOpener {
     scheduler: SchedulerRef,
     options: Option<RegionOptions>,
     cache_manager: Option<CacheManagerRef>,
+    intermediate_store: Option<ObjectStore>,
+    index_build_limiter: Option<IndexBuildLimiterRef>,
+    request_sender: Option<Sender<WorkerRequest>>,
     skip_wal_replay: bool,
+    clock: ClockRef,
 }
 
 impl RegionOpener {
@@ -78,7 +88,11 @@ impl RegionOpener {
             scheduler,
             options: None,
             cache_manager: None,
+            intermediate_store: None,
+            index_build_limiter: None,
+            request_sender: None,
             skip_wal_replay: false,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -106,6 +120,33 @@ impl RegionOpener {
         self
     }
 
+    /// Sets the store of index intermediate files, `None` means using the object
+    /// store of the region.
+    pub(crate) fn intermediate_store(mut self, intermediate_store: Option<ObjectStore>) -> Self {
+        self.intermediate_store = intermediate_store;
+        self
+    }
+
+    /// Sets the clock to initialize the last flush time of the region.
+    pub(crate) fn clock(mut self, clock: ClockRef) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the limiter of concurrent index builds, `None` means unlimited.
+    pub(crate) fn index_build_limiter(mut self, limiter: Option<IndexBuildLimiterRef>) -> Self {
+        self.index_build_limiter = limiter;
+        self
+    }
+
+    /// Sets the sender of requests to the worker of the region, indexes of SSTs are
+    /// only rebuilt on reads if it isn't `None` as rebuilt indexes are committed by
+    /// the worker.
+    pub(crate) fn request_sender(mut self, request_sender: Option<Sender<WorkerRequest>>) -> Self {
+        self.request_sender = request_sender;
+        self
+    }
+
     /// Sets the `skip_wal_replay`.
     pub(crate) fn skip_wal_replay(mut self, skip: bool) -> Self {
         self.skip_wal_replay = skip;
@@ -156,21 +197,47 @@ impl RegionOpener {
         }
         let options = self.options.take().unwrap();
         let wal_options = options.wal_options.clone();
+        let series_limit = options.series_limit;
         let object_store = self.object_store(&options.storage)?.clone();
 
         // Create a manifest manager for this region and writes regions to the manifest file.
         let region_manifest_options = self.manifest_options(config, &options)?;
         let metadata = Arc::new(self.metadata.unwrap());
-        let manifest_manager =
-            RegionManifestManager::new(metadata.clone(), region_manifest_options).await?;
+        let manifest_manager = RegionManifestManager::new(
+            metadata.clone(),
+            config.sst_path_layout,
+            region_manifest_options,
+        )
+        .await?;
 
-        let mutable = self.memtable_builder.build(&metadata);
+        let mutable = self
+            .memtable_builder
+            .build(&metadata, options.duplicate_mode);
 
         let version = VersionBuilder::new(metadata, mutable)
             .options(options)
             .build();
         let version_control = Arc::new(VersionControl::new(version));
-        let access_layer = Arc::new(AccessLayer::new(self.region_dir, object_store));
+        let access_layer = Arc::new(
+            AccessLayer::new(
+                self.region_dir,
+                object_store,
+                self.mirror_store(config)?,
+                config.sst_path_layout.path_strategy(),
+            )
+            .with_index_intermediate_write_buffer_size(config.index_intermediate_write_buffer_size)
+            .with_intermediate_store(self.intermediate_store.clone())
+            .with_index_build_limiter(self.index_build_limiter.clone())
+            .with_request_sender(self.request_sender.clone())
+            .with_index_intermediate_retry_policy(RetryPolicy::new(
+                config.index_intermediate_op_timeout,
+                config.index_intermediate_op_max_retries,
+            ))
+            .with_index_intermediate_list_page_size(config.list_page_size)
+            .with_index_intermediate_max_bytes(config.index_intermediate_max_bytes)
+            .with_index_intermediate_open_concurrency(config.index_intermediate_open_concurrency)
+            .with_retry_config(config.sst_retry_config()),
+        );
 
         Ok(MitoRegion {
             region_id,
@@ -183,9 +250,14 @@ impl RegionOpener {
                 self.cache_manager,
             )),
             wal_options,
-            last_flush_millis: AtomicI64::new(current_time_millis()),
+            last_flush_millis: AtomicI64::new(self.clock.now_millis()),
             // Region is writable after it is created.
             writable: AtomicBool::new(true),
+            frozen: AtomicBool::new(false),
+            write_subscribers: Arc::default(),
+            series_limiter: Arc::new(SeriesLimiter::new(region_id, series_limit)),
+            memtable_metrics: Arc::new(MemtableMetrics::new(region_id)),
+            put_lock: tokio::sync::Mutex::new(()),
         })
     }
 
@@ -228,6 +300,7 @@ impl RegionOpener {
     ) -> Result<Option<MitoRegion>> {
         let region_options = self.options.as_ref().unwrap().clone();
         let wal_options = region_options.wal_options.clone();
+        let series_limit = region_options.series_limit;
 
         let region_manifest_options = self.manifest_options(config, &region_options)?;
         let Some(manifest_manager) = RegionManifestManager::open(region_manifest_options).await?
@@ -240,13 +313,35 @@ impl RegionOpener {
 
         let region_id = self.region_id;
         let object_store = self.object_store(&region_options.storage)?.clone();
-        let access_layer = Arc::new(AccessLayer::new(self.region_dir.clone(), object_store));
+        let access_layer = Arc::new(
+            AccessLayer::new(
+                self.region_dir.clone(),
+                object_store,
+                self.mirror_store(config)?,
+                // Regions keep the layout they are created with.
+                manifest.sst_path_layout.path_strategy(),
+            )
+            .with_index_intermediate_write_buffer_size(config.index_intermediate_write_buffer_size)
+            .with_intermediate_store(self.intermediate_store.clone())
+            .with_index_build_limiter(self.index_build_limiter.clone())
+            .with_request_sender(self.request_sender.clone())
+            .with_index_intermediate_retry_policy(RetryPolicy::new(
+                config.index_intermediate_op_timeout,
+                config.index_intermediate_op_max_retries,
+            ))
+            .with_index_intermediate_list_page_size(config.list_page_size)
+            .with_index_intermediate_max_bytes(config.index_intermediate_max_bytes)
+            .with_index_intermediate_open_concurrency(config.index_intermediate_open_concurrency)
+            .with_retry_config(config.sst_retry_config()),
+        );
         let file_purger = Arc::new(LocalFilePurger::new(
             self.scheduler.clone(),
             access_layer.clone(),
             self.cache_manager.clone(),
         ));
-        let mutable = self.memtable_builder.build(&metadata);
+        let mutable = self
+            .memtable_builder
+            .build(&metadata, region_options.duplicate_mode);
         let version = VersionBuilder::new(metadata, mutable)
             .add_files(file_purger.clone(), manifest.files.values().cloned())
             .flushed_entry_id(manifest.flushed_entry_id)
@@ -270,12 +365,24 @@ impl RegionOpener {
                 flushed_entry_id,
                 &version_control,
                 config.allow_stale_entries,
+                &self.clock,
             )
             .await?;
         } else {
             info!("Skip the WAL replay for region: {}", region_id);
         }
 
+        let series_limiter = Arc::new(SeriesLimiter::new(region_id, series_limit));
+        if let Err(e) = series_limiter
+            .load_series(&version_control.current().version, &access_layer)
+            .await
+        {
+            warn!(
+                e; "Failed to load series of region {}, only counts series put after opening",
+                region_id
+            );
+        }
+
         let region = MitoRegion {
             region_id: self.region_id,
             version_control,
@@ -283,9 +390,14 @@ impl RegionOpener {
             manifest_manager,
             file_purger,
             wal_options,
-            last_flush_millis: AtomicI64::new(current_time_millis()),
+            last_flush_millis: AtomicI64::new(self.clock.now_millis()),
             // Region is always opened in read only mode.
             writable: AtomicBool::new(false),
+            frozen: AtomicBool::new(manifest.frozen),
+            write_subscribers: Arc::default(),
+            series_limiter,
+            memtable_metrics: Arc::new(MemtableMetrics::new(self.region_id)),
+            put_lock: tokio::sync::Mutex::new(()),
         };
         Ok(Some(region))
     }
@@ -304,10 +416,20 @@ impl RegionOpener {
             // Currently, the manifest storage doesn't have good support for changing compression algorithms.
             compress_type: manifest_compress_type(config.compress_manifest),
             checkpoint_distance: config.manifest_checkpoint_distance,
+            list_page_size: config.list_page_size,
         })
     }
 
     /// Returns an object store corresponding to `name`. If `name` is `None`, this method returns the default object store.
+    /// Returns the store to mirror SSTs to, `None` if SSTs aren't mirrored.
+    fn mirror_store(&self, config: &MitoConfig) -> Result<Option<object_store::ObjectStore>> {
+        if config.sst_mirror_storage.is_empty() {
+            return Ok(None);
+        }
+        let name = Some(config.sst_mirror_storage.clone());
+        self.object_store(&name).map(|store| Some(store.clone()))
+    }
+
     fn object_store(&self, name: &Option<String>) -> Result<&object_store::ObjectStore> {
         if let Some(name) = name {
             Ok(self
@@ -379,6 +501,7 @@ pub(crate) async fn replay_memtable<S: LogStore>(
     flushed_entry_id: EntryId,
     version_control: &VersionControlRef,
     allow_stale_entries: bool,
+    clock: &ClockRef,
 ) -> Result<EntryId> {
     let mut rows_replayed = 0;
     // Last entry id should start from flushed entry id since there might be no
@@ -417,7 +540,7 @@ pub(crate) async fn replay_memtable<S: LogStore>(
 
     // set next_entry_id and write to memtable.
     region_write_ctx.set_next_entry_id(last_entry_id + 1);
-    region_write_ctx.write_memtable();
+    region_write_ctx.write_memtable(clock.now_millis());
 
     if allow_stale_entries && stale_entry_found {
         wal.obsolete(region_id, flushed_entry_id, wal_options)
@@ -17,6 +17,7 @@
 use std::collections::HashMap;
 use std::time::Duration;
 
+use common_base::readable_size::ReadableSize;
 use common_config::wal::WalOptions;
 use common_config::WAL_OPTIONS_KEY;
 use serde::Deserialize;
@@ -41,6 +42,15 @@ pub struct RegionOptions {
     pub storage: Option<String>,
     /// Wal options.
     pub wal_options: WalOptions,
+    /// Options to roll up aged rows.
+    pub rollup: RollupOptions,
+    /// Max number of series (distinct primary keys) in the region, unlimited if it is `None`.
+    pub series_limit: Option<usize>,
+    /// Names of tag columns to create inverted indexes while writing SSTs, no index
+    /// is created if it is `None`.
+    pub index_columns: Option<Vec<String>>,
+    /// How to handle rows with the same primary key and timestamp.
+    pub duplicate_mode: DuplicateMode,
 }
 
 impl TryFrom<&HashMap<String, String>> for RegionOptions {
@@ -56,6 +66,7 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
         let options: RegionOptionsWithoutEnum =
             serde_json::from_str(&json).context(JsonOptionsSnafu)?;
         let compaction: CompactionOptions = serde_json::from_str(&json).unwrap_or_default();
+        let rollup: RollupOptions = serde_json::from_str(&json).context(JsonOptionsSnafu)?;
 
         // Tries to decode the wal options from the map or sets to the default if there's none wal options in the map.
         let wal_options = options_map.get(WAL_OPTIONS_KEY).map_or_else(
@@ -70,6 +81,17 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
             compaction,
             storage: options.storage,
             wal_options,
+            rollup,
+            series_limit: options.series_limit,
+            index_columns: options.index_columns.map(|columns| {
+                columns
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }),
+            duplicate_mode: options.duplicate_mode,
         })
     }
 }
@@ -82,6 +104,9 @@ pub enum CompactionOptions {
     /// Time window compaction strategy.
     #[serde(with = "prefix_twcs")]
     Twcs(TwcsOptions),
+    /// Leveled compaction strategy.
+    #[serde(with = "prefix_leveled")]
+    Leveled(LeveledOptions),
 }
 
 impl Default for CompactionOptions {
@@ -132,8 +157,117 @@ impl Default for TwcsOptions {
     }
 }
 
+/// Leveled compaction options.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LeveledOptions {
+    /// Max num of files that can be kept in level 0.
+    #[serde_as(as = "DisplayFromStr")]
+    pub max_level0_files: usize,
+    /// Max total size of files in level 1.
+    pub base_level_size: ReadableSize,
+    /// Size ratio between two adjacent levels.
+    #[serde_as(as = "DisplayFromStr")]
+    pub fanout: usize,
+}
+
+with_prefix!(prefix_leveled "compaction.leveled.");
+
+impl Default for LeveledOptions {
+    fn default() -> Self {
+        Self {
+            max_level0_files: 4,
+            base_level_size: ReadableSize::mb(256),
+            fanout: 10,
+        }
+    }
+}
+
+/// Options to roll up aged rows into downsampled rows.
+///
+/// Rows of a time series older than `after` are aggregated into one row for each
+/// `interval`, whose timestamp is the start of the interval. For example, we can keep
+/// raw rows for 7 days and 1 minute rollups afterwards until the TTL of the region.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct RollupOptions {
+    /// Age of rows to roll up, `None` to disable rollup.
+    #[serde(rename = "rollup.after", with = "humantime_serde")]
+    pub after: Option<Duration>,
+    /// Interval to aggregate rows into.
+    #[serde(rename = "rollup.interval", with = "humantime_serde")]
+    pub interval: Duration,
+    /// Function to aggregate fields of rows in the same interval.
+    #[serde(rename = "rollup.aggregation")]
+    pub aggregation: RollupAggregation,
+}
+
+impl RollupOptions {
+    /// Returns the age of rows to roll up, or `None` if rollup is disabled.
+    pub fn rollup_after(&self) -> Option<Duration> {
+        self.after.filter(|_| !self.interval.is_zero())
+    }
+}
+
+impl Default for RollupOptions {
+    fn default() -> Self {
+        Self {
+            after: None,
+            interval: Duration::from_secs(60),
+            aggregation: RollupAggregation::default(),
+        }
+    }
+}
+
+/// Function to aggregate fields of rolled up rows.
+///
+/// We only support functions that can aggregate rolled up rows with raw rows of the same
+/// interval, so an interval can be rolled up in several steps and rolling up a rolled
+/// up row again returns the same row. Functions like `avg` are not supported as they
+/// can't be computed from rolled up rows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RollupAggregation {
+    /// Sum of numeric fields.
+    Sum,
+    Min,
+    #[default]
+    Max,
+}
+
+/// How to handle rows with the same primary key and timestamp.
+///
+/// Every row written gets a larger sequence than rows written before, so rows are
+/// resolved by their sequences whether they are in the same request, the same memtable
+/// or different SSTs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMode {
+    /// Keeps the row written last.
+    #[default]
+    KeepLast,
+    /// Keeps the row written first, rows written later are ignored until the row is
+    /// deleted.
+    KeepFirst,
+    /// Rejects puts containing rows with the same primary key and timestamp as other
+    /// rows in the put or rows already in the region, so the region never has duplicates.
+    ErrorOnDuplicate,
+}
+
+impl DuplicateMode {
+    /// Returns true if the row written first is kept.
+    pub fn keeps_first(&self) -> bool {
+        matches!(
+            self,
+            DuplicateMode::KeepFirst | DuplicateMode::ErrorOnDuplicate
+        )
+    }
+}
+
 /// We need to define a new struct without enum fields as `#[serde(default)]` does not
 /// support external tagging.
+#[serde_as]
 #[derive(Debug, Deserialize)]
 #[serde(default)]
 struct RegionOptionsWithoutEnum {
@@ -141,6 +275,12 @@ struct RegionOptionsWithoutEnum {
     #[serde(with = "humantime_serde")]
     ttl: Option<Duration>,
     storage: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    series_limit: Option<usize>,
+    /// Comma separated names of columns to index.
+    #[serde(rename = "index.inverted_index.columns")]
+    index_columns: Option<String>,
+    duplicate_mode: DuplicateMode,
 }
 
 impl Default for RegionOptionsWithoutEnum {
@@ -149,18 +289,32 @@ impl Default for RegionOptionsWithoutEnum {
         RegionOptionsWithoutEnum {
             ttl: options.ttl,
             storage: options.storage,
+            series_limit: options.series_limit,
+            index_columns: None,
+            duplicate_mode: options.duplicate_mode,
         }
     }
 }
 
+/// Key of names of columns to index.
+const INDEX_COLUMNS_KEY: &str = "index.inverted_index.columns";
+/// Keys of options whose values are case sensitive, e.g. column names.
+const CASE_SENSITIVE_KEYS: &[&str] = &[INDEX_COLUMNS_KEY];
+
 /// Converts the `options` map to a json object.
 ///
-/// Converts all key-values to lowercase and replaces "null" strings by `null` json values.
+/// Converts all keys and values except values of [CASE_SENSITIVE_KEYS] to lowercase
+/// and replaces "null" strings by `null` json values.
 fn options_map_to_value(options: &HashMap<String, String>) -> Value {
     let map = options
         .iter()
         .map(|(key, value)| {
-            let (key, value) = (key.to_lowercase(), value.to_lowercase());
+            let key = key.to_lowercase();
+            let value = if CASE_SENSITIVE_KEYS.contains(&key.as_str()) {
+                value.clone()
+            } else {
+                value.to_lowercase()
+            };
 
             if value == "null" {
                 (key, Value::Null)
@@ -248,6 +402,25 @@ mod tests {
         assert_eq!(expect, options);
     }
 
+    #[test]
+    fn test_with_leveled_compaction() {
+        let map = make_map(&[
+            ("compaction.leveled.max_level0_files", "8"),
+            ("compaction.leveled.base_level_size", "64MB"),
+            ("compaction.type", "leveled"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            compaction: CompactionOptions::Leveled(LeveledOptions {
+                max_level0_files: 8,
+                base_level_size: ReadableSize::mb(64),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+    }
+
     fn test_with_wal_options(wal_options: &WalOptions) -> bool {
         let encoded_wal_options = serde_json::to_string(&wal_options).unwrap();
         let map = make_map(&[(WAL_OPTIONS_KEY, &encoded_wal_options)]);
@@ -283,6 +456,8 @@ mod tests {
             ("compaction.twcs.time_window", "2h"),
             ("compaction.type", "twcs"),
             ("storage", "S3"),
+            ("series_limit", "1000"),
+            ("duplicate_mode", "keep_first"),
             (
                 WAL_OPTIONS_KEY,
                 &serde_json::to_string(&wal_options).unwrap(),
@@ -298,7 +473,78 @@ mod tests {
             }),
             storage: Some("s3".to_string()),
             wal_options,
+            rollup: RollupOptions::default(),
+            series_limit: Some(1000),
+            index_columns: None,
+            duplicate_mode: DuplicateMode::KeepFirst,
         };
         assert_eq!(expect, options);
     }
+
+    #[test]
+    fn test_with_index_columns() {
+        let map = make_map(&[("index.inverted_index.columns", "tag_0, Tag_1,")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            index_columns: Some(vec!["tag_0".to_string(), "Tag_1".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+
+        let map = make_map(&[("index.inverted_index.columns", "")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(Some(Vec::new()), options.index_columns);
+    }
+
+    #[test]
+    fn test_with_rollup() {
+        let map = make_map(&[
+            ("rollup.after", "7d"),
+            ("rollup.interval", "5m"),
+            ("rollup.aggregation", "MAX"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            rollup: RollupOptions {
+                after: Some(Duration::from_secs(3600 * 24 * 7)),
+                interval: Duration::from_secs(300),
+                aggregation: RollupAggregation::Max,
+            },
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+        assert_eq!(
+            Some(Duration::from_secs(3600 * 24 * 7)),
+            options.rollup.rollup_after()
+        );
+
+        let map = make_map(&[("rollup.after", "7d"), ("rollup.interval", "0s")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(None, options.rollup.rollup_after());
+
+        let map = make_map(&[("rollup.aggregation", "count")]);
+        assert!(RegionOptions::try_from(&map).is_err());
+        let map = make_map(&[("rollup.aggregation", "avg")]);
+        assert!(RegionOptions::try_from(&map).is_err());
+    }
+
+    #[test]
+    fn test_with_duplicate_mode() {
+        let map = make_map(&[("duplicate_mode", "KEEP_FIRST")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(DuplicateMode::KeepFirst, options.duplicate_mode);
+        assert!(options.duplicate_mode.keeps_first());
+
+        let map = make_map(&[("duplicate_mode", "error_on_duplicate")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(DuplicateMode::ErrorOnDuplicate, options.duplicate_mode);
+        assert!(options.duplicate_mode.keeps_first());
+
+        let options = RegionOptions::try_from(&HashMap::new()).unwrap();
+        assert_eq!(DuplicateMode::KeepLast, options.duplicate_mode);
+        assert!(!options.duplicate_mode.keeps_first());
+
+        let map = make_map(&[("duplicate_mode", "keep_none")]);
+        assert!(RegionOptions::try_from(&map).is_err());
+    }
 }
@@ -0,0 +1,177 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limits the number of series in a region.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use snafu::ensure;
+use store_api::metadata::RegionMetadata;
+use store_api::storage::RegionId;
+
+use crate::access_layer::AccessLayer;
+use crate::error::{Result, SeriesLimitExceededSnafu};
+use crate::metrics::SERIES_COUNT;
+use crate::read::BatchReader;
+use crate::region::version::Version;
+use crate::request::WriteRequest;
+
+/// Limit value that means the number of series is unlimited.
+const UNLIMITED: usize = 0;
+
+pub(crate) type SeriesLimiterRef = Arc<SeriesLimiter>;
+
+/// Rejects puts that create new series (distinct primary keys) once a region has
+/// too many series. Puts to existing series are always accepted.
+///
+/// Series already in the region are loaded from memtables and SSTs when the region is
+/// opened with a limit. Otherwise the limiter only tracks the series put while the
+/// limit is set.
+#[derive(Debug)]
+pub(crate) struct SeriesLimiter {
+    region_id: RegionId,
+    /// Max number of series in the region.
+    limit: AtomicUsize,
+    /// Encoded primary keys of series in the region.
+    series: Mutex<HashSet<Vec<u8>>>,
+}
+
+impl SeriesLimiter {
+    /// Creates a new limiter, the number of series is unlimited if `limit` is `None`.
+    pub(crate) fn new(region_id: RegionId, limit: Option<usize>) -> SeriesLimiter {
+        SeriesLimiter {
+            region_id,
+            limit: AtomicUsize::new(limit.unwrap_or(UNLIMITED)),
+            series: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Returns the max number of series in the region.
+    pub(crate) fn limit(&self) -> Option<usize> {
+        let limit = self.limit.load(Ordering::Relaxed);
+        (limit != UNLIMITED).then_some(limit)
+    }
+
+    /// Updates the limit, removing the limit also forgets all series tracked.
+    ///
+    /// Series already in the region are never rejected even if there are more series
+    /// than the new limit.
+    pub(crate) fn set_limit(&self, limit: Option<usize>) {
+        self.limit
+            .store(limit.unwrap_or(UNLIMITED), Ordering::Relaxed);
+        if limit.is_none() {
+            self.clear();
+        }
+    }
+
+    /// Returns the number of series tracked.
+    #[cfg(test)]
+    pub(crate) fn num_series(&self) -> usize {
+        self.series.lock().unwrap().len()
+    }
+
+    /// Forgets all series tracked, e.g. after the region is truncated.
+    pub(crate) fn clear(&self) {
+        let mut series = self.series.lock().unwrap();
+        series.clear();
+        self.update_metrics(series.len());
+    }
+
+    /// Tracks series in memtables and SSTs of the `version`, so series written before
+    /// the region is opened count towards the limit. Does nothing if the number of
+    /// series is unlimited.
+    ///
+    /// Only primary keys are read from SSTs. Keys of SSTs written before adding tag
+    /// columns aren't converted to the current schema.
+    pub(crate) async fn load_series(
+        &self,
+        version: &Version,
+        access_layer: &AccessLayer,
+    ) -> Result<()> {
+        if self.limit().is_none() {
+            return Ok(());
+        }
+
+        // Reads no field.
+        let projection = version.metadata.primary_key.clone();
+        let mut keys = HashSet::new();
+        for memtable in version.memtables.list_memtables() {
+            for batch in memtable.iter(Some(&projection), None) {
+                keys.insert(batch?.primary_key().to_vec());
+            }
+        }
+        for file in version.ssts.levels().iter().flat_map(|level| level.files()) {
+            let mut reader = access_layer
+                .read_sst(file.clone())
+                .projection(Some(projection.clone()))
+                .build()
+                .await?;
+            while let Some(batch) = reader.next_batch().await? {
+                // Rows of the same series are sorted together.
+                if !keys.contains(batch.primary_key()) {
+                    keys.insert(batch.primary_key().to_vec());
+                }
+            }
+        }
+
+        let mut series = self.series.lock().unwrap();
+        series.extend(keys);
+        self.update_metrics(series.len());
+        Ok(())
+    }
+
+    /// Checks whether the put `request` can create its new series. Tracks the new series
+    /// if the request is accepted.
+    ///
+    /// Rejects the whole request if its new series exceed the limit.
+    pub(crate) fn check_put(
+        &self,
+        request: &WriteRequest,
+        metadata: &RegionMetadata,
+    ) -> Result<()> {
+        let Some(limit) = self.limit() else {
+            return Ok(());
+        };
+
+        // The request is already filled with missing columns.
+        let keys = request.encode_columns(&metadata.primary_key_columns().collect::<Vec<_>>())?;
+        let mut series = self.series.lock().unwrap();
+        let new_series: HashSet<_> = keys
+            .into_iter()
+            .filter(|key| !series.contains(key))
+            .collect();
+        if new_series.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            series.len() + new_series.len() <= limit,
+            SeriesLimitExceededSnafu {
+                region_id: self.region_id,
+                limit,
+            }
+        );
+
+        series.extend(new_series);
+        self.update_metrics(series.len());
+        Ok(())
+    }
+
+    fn update_metrics(&self, num_series: usize) {
+        SERIES_COUNT
+            .with_label_values(&[&self.region_id.to_string()])
+            .set(num_series as i64);
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subscribers of rows written to a region.
+
+use std::sync::Arc;
+
+use api::v1::OpType;
+use tokio::sync::broadcast;
+
+use crate::memtable::KeyValues;
+
+/// Number of writes buffered for each subscriber.
+///
+/// Writers never wait for subscribers, a subscriber that doesn't consume the writes in
+/// time lags behind and misses the oldest writes.
+const SUBSCRIBER_CHANNEL_SIZE: usize = 1024;
+
+pub(crate) type WriteSubscribersRef = Arc<WriteSubscribers>;
+
+/// Publishes rows written to the memtable of a region to its subscribers.
+///
+/// A subscriber is removed once its receiver is dropped.
+#[derive(Debug)]
+pub(crate) struct WriteSubscribers {
+    sender: broadcast::Sender<Arc<KeyValues>>,
+}
+
+impl Default for WriteSubscribers {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(SUBSCRIBER_CHANNEL_SIZE);
+        Self { sender }
+    }
+}
+
+impl WriteSubscribers {
+    /// Subscribes rows written after this call.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Arc<KeyValues>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the number of subscribers.
+    pub(crate) fn num_subscribers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Publishes rows put to the memtable, deletions are not published.
+    pub(crate) fn publish(&self, kvs: KeyValues) {
+        if self.num_subscribers() == 0 || kvs.op_type() != OpType::Put {
+            return;
+        }
+        // The send only fails if all subscribers are gone.
+        let _ = self.sender.send(Arc::new(kvs));
+    }
+}
@@ -81,7 +81,7 @@ impl VersionControl {
         if version.memtables.mutable.is_empty() {
             return;
         }
-        let new_mutable = builder.build(&version.metadata);
+        let new_mutable = builder.build(&version.metadata, version.options.duplicate_mode);
         // Safety: Immutable memtable is None.
         let new_memtables = version.memtables.freeze_mutable(new_mutable).unwrap();
         // Create a new version with memtable switched.
@@ -114,10 +114,23 @@ impl VersionControl {
         version_data.version = new_version;
     }
 
+    /// Replaces metas of SSTs in current version, SSTs not in the version are ignored.
+    pub(crate) fn update_files(&self, files: Vec<FileMeta>, purger: FilePurgerRef) {
+        let version = self.current().version;
+        let new_version = Arc::new(
+            VersionBuilder::from_version(version)
+                .update_files(purger, files.into_iter())
+                .build(),
+        );
+
+        let mut version_data = self.data.write().unwrap();
+        version_data.version = new_version;
+    }
+
     /// Mark all opened files as deleted and set the delete marker in [VersionControlData]
     pub(crate) fn mark_dropped(&self, memtable_builder: &MemtableBuilderRef) {
         let version = self.current().version;
-        let new_mutable = memtable_builder.build(&version.metadata);
+        let new_mutable = memtable_builder.build(&version.metadata, version.options.duplicate_mode);
 
         let mut data = self.data.write().unwrap();
         data.is_dropped = true;
@@ -133,8 +146,8 @@ impl VersionControl {
     /// It replaces existing mutable memtable with a memtable that uses the
     /// new schema. Memtables of the version must be empty.
     pub(crate) fn alter_schema(&self, metadata: RegionMetadataRef, builder: &MemtableBuilderRef) {
-        let new_mutable = builder.build(&metadata);
         let version = self.current().version;
+        let new_mutable = builder.build(&metadata, version.options.duplicate_mode);
         debug_assert!(version.memtables.mutable.is_empty());
         debug_assert!(version.memtables.immutables().is_empty());
         let new_version = Arc::new(
@@ -157,7 +170,7 @@ impl VersionControl {
     ) {
         let version = self.current().version;
 
-        let new_mutable = memtable_builder.build(&version.metadata);
+        let new_mutable = memtable_builder.build(&version.metadata, version.options.duplicate_mode);
         let new_version = Arc::new(
             VersionBuilder::new(version.metadata.clone(), new_mutable)
                 .flushed_entry_id(truncated_entry_id)
@@ -348,6 +361,19 @@ impl VersionBuilder {
         self
     }
 
+    /// Replaces metas of files in the builder.
+    pub(crate) fn update_files(
+        mut self,
+        file_purger: FilePurgerRef,
+        files: impl Iterator<Item = FileMeta>,
+    ) -> Self {
+        let mut ssts = (*self.ssts).clone();
+        ssts.update_files(file_purger, files);
+        self.ssts = Arc::new(ssts);
+
+        self
+    }
+
     /// Builds a new [Version] from the builder.
     pub(crate) fn build(self) -> Version {
         Version {
@@ -23,8 +23,11 @@ use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::error::{Error, Result, WriteGroupSnafu};
 use crate::memtable::KeyValues;
+use crate::metrics::MemtableMetricsRef;
+use crate::region::series_limiter::SeriesLimiterRef;
+use crate::region::subscriber::WriteSubscribersRef;
 use crate::region::version::{VersionControlData, VersionControlRef, VersionRef};
-use crate::request::OptionOutputTx;
+use crate::request::{OptionOutputTx, WriteRequest};
 use crate::wal::{EntryId, WalWriter};
 
 /// Notifier to notify write result on drop.
@@ -93,6 +96,12 @@ pub(crate) struct RegionWriteCtx {
     notifiers: Vec<WriteNotify>,
     /// The write operation is failed and we should not write to the mutable memtable.
     failed: bool,
+    /// Subscribers to publish rows written to the memtable.
+    write_subscribers: Option<WriteSubscribersRef>,
+    /// Limiter to reject puts creating too many series.
+    series_limiter: Option<SeriesLimiterRef>,
+    /// Memtable metrics of the region to update after writing.
+    memtable_metrics: Option<MemtableMetricsRef>,
 
     // Metrics:
     /// Rows to put.
@@ -125,11 +134,51 @@ impl RegionWriteCtx {
             wal_options,
             notifiers: Vec::new(),
             failed: false,
+            write_subscribers: None,
+            series_limiter: None,
+            memtable_metrics: None,
             put_num: 0,
             delete_num: 0,
         }
     }
 
+    /// Publishes rows written to the memtable to `write_subscribers`.
+    pub(crate) fn with_write_subscribers(
+        mut self,
+        write_subscribers: WriteSubscribersRef,
+    ) -> RegionWriteCtx {
+        self.write_subscribers = Some(write_subscribers);
+        self
+    }
+
+    /// Checks puts to the region by the `series_limiter`.
+    pub(crate) fn with_series_limiter(
+        mut self,
+        series_limiter: SeriesLimiterRef,
+    ) -> RegionWriteCtx {
+        self.series_limiter = Some(series_limiter);
+        self
+    }
+
+    /// Updates `memtable_metrics` after writing to the memtable.
+    pub(crate) fn with_memtable_metrics(
+        mut self,
+        memtable_metrics: MemtableMetricsRef,
+    ) -> RegionWriteCtx {
+        self.memtable_metrics = Some(memtable_metrics);
+        self
+    }
+
+    /// Returns an error if the put `request` creates more series than the limit.
+    pub(crate) fn check_series_limit(&self, request: &WriteRequest) -> Result<()> {
+        match &self.series_limiter {
+            Some(limiter) if request.op_type == OpType::Put => {
+                limiter.check_put(request, &self.version.metadata)
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Push mutation to the context.
     pub(crate) fn push_mutation(&mut self, op_type: i32, rows: Option<Rows>, tx: OptionOutputTx) {
         let num_rows = rows.as_ref().map(|rows| rows.rows.len()).unwrap_or(0);
@@ -190,7 +239,7 @@ impl RegionWriteCtx {
     }
 
     /// Consumes mutations and writes them into mutable memtable.
-    pub(crate) fn write_memtable(&mut self) {
+    pub(crate) fn write_memtable(&mut self, now_millis: i64) {
         debug_assert_eq!(self.notifiers.len(), self.wal_entry.mutations.len());
 
         if self.failed {
@@ -207,6 +256,8 @@ impl RegionWriteCtx {
             };
             if let Err(e) = mutable.write(&kvs) {
                 notify.err = Some(Arc::new(e));
+            } else if let Some(write_subscribers) = &self.write_subscribers {
+                write_subscribers.publish(kvs);
             }
         }
 
@@ -214,5 +265,9 @@ impl RegionWriteCtx {
         // to decrease `next_sequence` and `next_entry_id` by 1.
         self.version_control
             .set_sequence_and_entry_id(self.next_sequence - 1, self.next_entry_id - 1);
+
+        if let Some(metrics) = &self.memtable_metrics {
+            self.version.memtables.update_metrics(metrics, now_millis);
+        }
     }
 }
@@ -19,12 +19,13 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use api::helper::{
-    is_column_type_value_eq, is_semantic_type_eq, proto_value_type, to_proto_value,
-    ColumnDataTypeWrapper,
+    is_column_type_value_eq, is_semantic_type_eq, pb_value_to_value_ref, proto_value_type,
+    to_proto_value, ColumnDataTypeWrapper,
 };
 use api::v1::{ColumnDataType, ColumnSchema, OpType, Rows, SemanticType, Value};
 use common_telemetry::{info, warn};
 use datatypes::prelude::DataType;
+use datatypes::value::ValueRef;
 use prometheus::HistogramTimer;
 use prost::Message;
 use smallvec::SmallVec;
@@ -33,8 +34,8 @@ use store_api::metadata::{ColumnMetadata, RegionMetadata};
 use store_api::region_engine::SetReadonlyResponse;
 use store_api::region_request::{
     AffectedRows, RegionAlterRequest, RegionCatchupRequest, RegionCloseRequest,
-    RegionCompactRequest, RegionCreateRequest, RegionDropRequest, RegionFlushRequest,
-    RegionOpenRequest, RegionRequest, RegionTruncateRequest,
+    RegionCompactRequest, RegionCreateRequest, RegionDeleteRangeRequest, RegionDropRequest,
+    RegionFlushRequest, RegionOpenRequest, RegionRequest, RegionTruncateRequest,
 };
 use store_api::storage::{RegionId, SequenceNumber};
 use tokio::sync::oneshot::{self, Receiver, Sender};
@@ -45,6 +46,7 @@ use crate::error::{
 };
 use crate::memtable::MemtableId;
 use crate::metrics::COMPACTION_ELAPSED_TOTAL;
+use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
 use crate::sst::file::FileMeta;
 use crate::sst::file_purger::{FilePurgerRef, PurgeRequest};
 use crate::wal::EntryId;
@@ -130,6 +132,37 @@ impl WriteRequest {
         self.name_to_index.get(name).copied()
     }
 
+    /// Encodes values of `columns` in each row in a memcomparable form, like the
+    /// memtable encodes primary keys. Missing columns are encoded as null.
+    pub(crate) fn encode_columns(&self, columns: &[&ColumnMetadata]) -> Result<Vec<Vec<u8>>> {
+        let codec = McmpRowCodec::new(
+            columns
+                .iter()
+                .map(|c| SortField::new(c.column_schema.data_type.clone()))
+                .collect(),
+        );
+        let schema = &self.rows.schema;
+        let indices: Vec<_> = columns
+            .iter()
+            .map(|c| self.column_index_by_name(&c.column_schema.name))
+            .collect();
+
+        self.rows
+            .rows
+            .iter()
+            .map(|row| {
+                let values = indices.iter().map(|index| match index {
+                    Some(index) => pb_value_to_value_ref(
+                        &row.values[*index],
+                        &schema[*index].datatype_extension,
+                    ),
+                    None => ValueRef::Null,
+                });
+                codec.encode(values)
+            })
+            .collect()
+    }
+
     /// Checks schema of rows is compatible with schema of the region.
     ///
     /// If column with default value is missing, it returns a special [FillDefault](crate::error::Error::FillDefault)
@@ -225,6 +258,22 @@ impl WriteRequest {
         Ok(())
     }
 
+    /// Checks the schema and fill missing columns.
+    pub(crate) fn maybe_fill_missing_columns(&mut self, metadata: &RegionMetadata) -> Result<()> {
+        if let Err(e) = self.check_schema(metadata) {
+            if e.is_fill_default() {
+                // TODO(yingwen): Add metrics for this case.
+                // We need to fill default value. The write request may be a request
+                // sent before changing the schema.
+                self.fill_missing_columns(metadata)?;
+            } else {
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Tries to fill missing columns.
     ///
     /// Currently, our protobuf format might be inefficient when we need to fill lots of null
@@ -252,21 +301,7 @@ impl WriteRequest {
         }
 
         // Insert column schema.
-        let (datatype, datatype_ext) =
-            ColumnDataTypeWrapper::try_from(column.column_schema.data_type.clone())
-                .with_context(|_| ConvertColumnDataTypeSnafu {
-                    reason: format!(
-                        "no protobuf type for column {} ({:?})",
-                        column.column_schema.name, column.column_schema.data_type
-                    ),
-                })?
-                .to_parts();
-        self.rows.schema.push(ColumnSchema {
-            column_name: column.column_schema.name.clone(),
-            datatype: datatype as i32,
-            semantic_type: column.semantic_type as i32,
-            datatype_extension: datatype_ext,
-        });
+        self.rows.schema.push(column_to_schema(column)?);
 
         Ok(())
     }
@@ -356,6 +391,25 @@ impl WriteRequest {
     }
 }
 
+/// Returns the protobuf [ColumnSchema] of the `column`.
+pub(crate) fn column_to_schema(column: &ColumnMetadata) -> Result<ColumnSchema> {
+    let (datatype, datatype_ext) =
+        ColumnDataTypeWrapper::try_from(column.column_schema.data_type.clone())
+            .with_context(|_| ConvertColumnDataTypeSnafu {
+                reason: format!(
+                    "no protobuf type for column {} ({:?})",
+                    column.column_schema.name, column.column_schema.data_type
+                ),
+            })?
+            .to_parts();
+    Ok(ColumnSchema {
+        column_name: column.column_schema.name.clone(),
+        datatype: datatype as i32,
+        semantic_type: column.semantic_type as i32,
+        datatype_extension: datatype_ext,
+    })
+}
+
 /// Validate proto value schema.
 pub(crate) fn validate_proto_value(
     region_id: RegionId,
@@ -484,6 +538,14 @@ pub(crate) enum WorkerRequest {
         sender: Sender<SetReadonlyResponse>,
     },
 
+    /// Syncs the WAL of a region.
+    Sync {
+        /// Id of the region to sync.
+        region_id: RegionId,
+        /// The sender of the last durable entry id of the region.
+        sender: Sender<Result<EntryId>>,
+    },
+
     /// Notify a worker to stop.
     Stop,
 }
@@ -555,6 +617,18 @@ impl WorkerRequest {
                 sender: sender.into(),
                 request: DdlRequest::Catchup(v),
             }),
+            RegionRequest::DeleteRange(v) => WorkerRequest::Ddl(SenderDdlRequest {
+                region_id,
+                sender: sender.into(),
+                request: DdlRequest::DeleteRange(v),
+            }),
+            RegionRequest::Sync(_) => {
+                return InvalidRequestSnafu {
+                    region_id,
+                    reason: "sync request must be converted by WorkerRequest::new_sync",
+                }
+                .fail();
+            }
         };
 
         Ok((worker_request, receiver))
@@ -570,6 +644,12 @@ impl WorkerRequest {
             receiver,
         )
     }
+
+    pub(crate) fn new_sync(region_id: RegionId) -> (WorkerRequest, Receiver<Result<EntryId>>) {
+        let (sender, receiver) = oneshot::channel();
+
+        (WorkerRequest::Sync { region_id, sender }, receiver)
+    }
 }
 
 /// DDL request to a region.
@@ -584,6 +664,7 @@ pub(crate) enum DdlRequest {
     Compact(RegionCompactRequest),
     Truncate(RegionTruncateRequest),
     Catchup(RegionCatchupRequest),
+    DeleteRange(RegionDeleteRangeRequest),
 }
 
 /// Sender and Ddl request.
@@ -608,6 +689,8 @@ pub(crate) enum BackgroundNotify {
     CompactionFinished(CompactionFinished),
     /// Compaction has failed.
     CompactionFailed(CompactionFailed),
+    /// Indexes of SSTs have been built.
+    IndexBuildFinished(IndexBuildFinished),
 }
 
 /// Notifies a flush job is finished.
@@ -652,6 +735,7 @@ impl OnFailure for FlushFinished {
         for file in &self.file_metas {
             self.file_purger.send_request(PurgeRequest {
                 file_meta: file.clone(),
+                index_only: false,
             });
         }
     }
@@ -712,6 +796,7 @@ impl OnFailure for CompactionFinished {
             );
             self.file_purger.send_request(PurgeRequest {
                 file_meta: file.clone(),
+                index_only: false,
             });
         }
     }
@@ -725,6 +810,23 @@ pub(crate) struct CompactionFailed {
     pub(crate) err: Arc<Error>,
 }
 
+/// Notifies indexes of existing SSTs are built.
+#[derive(Debug)]
+pub(crate) struct IndexBuildFinished {
+    /// Region id.
+    pub(crate) region_id: RegionId,
+    /// Metas of the SSTs with new indexes.
+    pub(crate) file_metas: Vec<FileMeta>,
+    /// Sender of the number of updated SSTs.
+    pub(crate) sender: OptionOutputTx,
+}
+
+impl OnFailure for IndexBuildFinished {
+    fn on_failure(&mut self, err: Error) {
+        self.sender.send_mut(Err(err));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use api::v1::value::ValueData;
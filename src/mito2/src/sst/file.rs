@@ -23,7 +23,7 @@ use common_time::Timestamp;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use snafu::{ResultExt, Snafu};
-use store_api::storage::RegionId;
+use store_api::storage::{ColumnId, RegionId};
 use uuid::Uuid;
 
 use crate::sst::file_purger::{FilePurgerRef, PurgeRequest};
@@ -32,7 +32,7 @@ use crate::sst::location;
 /// Type to store SST level.
 pub type Level = u8;
 /// Maximum level of SSTs.
-pub const MAX_LEVEL: Level = 2;
+pub const MAX_LEVEL: Level = 4;
 
 #[derive(Debug, Snafu, PartialEq)]
 pub struct ParseIdError {
@@ -49,6 +49,17 @@ impl FileId {
         FileId(Uuid::new_v4())
     }
 
+    /// Returns a new unique [FileId] that contains the time it is created.
+    pub fn time_ordered() -> FileId {
+        FileId(Uuid::now_v7())
+    }
+
+    /// Returns the unix timestamp in seconds the id is created, or `None` if the id
+    /// doesn't contain the time, e.g. ids created by [FileId::random()].
+    pub fn created_secs(&self) -> Option<i64> {
+        self.0.get_timestamp().map(|ts| ts.to_unix().0 as i64)
+    }
+
     /// Parses id from string.
     pub fn parse_str(input: &str) -> std::result::Result<FileId, ParseIdError> {
         Uuid::parse_str(input).map(FileId).context(ParseIdSnafu)
@@ -100,6 +111,45 @@ pub struct FileMeta {
     pub available_indexes: SmallVec<[IndexType; 4]>,
     /// Size of the index file.
     pub index_file_size: u64,
+    /// Whether rows in the file are rolled up, they are never rolled up again.
+    pub rolled_up: bool,
+    /// Statistics of the inverted index of each column.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub index_stats: Vec<ColumnIndexStats>,
+    /// Crc32c checksum of the footer metadata of the file, `None` if the file is
+    /// written without the checksum.
+    pub footer_checksum: Option<u32>,
+    /// Id of the index file if the index is rebuilt under a new name, `None` means
+    /// the index file has the same id as the SST file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_file_id: Option<FileId>,
+}
+
+/// Statistics of the inverted index of a column, recorded while building the index.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ColumnIndexStats {
+    /// Id of the indexed column.
+    pub column_id: ColumnId,
+    /// Number of distinct non-null values of the column.
+    pub distinct_count: u64,
+    /// Size of the index of the column in bytes.
+    pub index_size: u64,
+    /// Total length of the postings of all values, in segments.
+    pub postings_len: u64,
+    /// Whether the index of the column is skipped as the column has too many
+    /// distinct values. Other statistics are zero if it's skipped.
+    pub skipped: bool,
+}
+
+impl ColumnIndexStats {
+    /// Returns the average length of postings of each value.
+    pub fn avg_postings_len(&self) -> f64 {
+        if self.distinct_count == 0 {
+            return 0.0;
+        }
+        self.postings_len as f64 / self.distinct_count as f64
+    }
 }
 
 /// Type of index.
@@ -110,9 +160,29 @@ pub enum IndexType {
 }
 
 impl FileMeta {
+    /// Returns the id of the index file.
+    pub fn index_file_id(&self) -> FileId {
+        self.index_file_id.unwrap_or(self.file_id)
+    }
+
     pub fn inverted_index_available(&self) -> bool {
         self.available_indexes.contains(&IndexType::InvertedIndex)
     }
+
+    /// Returns true if the inverted index of the column is available.
+    ///
+    /// Files without index statistics index all tag columns.
+    pub fn column_inverted_index_available(&self, column_id: ColumnId) -> bool {
+        if !self.inverted_index_available() {
+            return false;
+        }
+        if self.index_stats.is_empty() {
+            return true;
+        }
+        self.index_stats
+            .iter()
+            .any(|stats| stats.column_id == column_id && !stats.skipped)
+    }
 }
 
 /// Handle to a SST file.
@@ -152,16 +222,36 @@ impl FileHandle {
         self.inner.meta.file_id
     }
 
+    /// Returns the id of the index file.
+    pub fn index_file_id(&self) -> FileId {
+        self.inner.meta.index_file_id()
+    }
+
     /// Returns the complete file path of the file.
     pub fn file_path(&self, file_dir: &str) -> String {
         location::sst_file_path(file_dir, self.file_id())
     }
 
+    /// Returns the size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.inner.meta.file_size
+    }
+
     /// Returns the time range of the file.
     pub fn time_range(&self) -> FileTimeRange {
         self.inner.meta.time_range
     }
 
+    /// Returns true if rows in the file are rolled up.
+    pub fn rolled_up(&self) -> bool {
+        self.inner.meta.rolled_up
+    }
+
+    /// Returns the checksum of the footer metadata of the file.
+    pub fn footer_checksum(&self) -> Option<u32> {
+        self.inner.meta.footer_checksum
+    }
+
     /// Mark the file as deleted and will delete it on drop asynchronously
     pub fn mark_deleted(&self) {
         self.inner.deleted.store(true, Ordering::Relaxed);
@@ -178,6 +268,32 @@ impl FileHandle {
     pub fn meta(&self) -> FileMeta {
         self.inner.meta.clone()
     }
+
+    /// Marks the index file of the file as replaced by a new index file, the old index
+    /// file is deleted on drop asynchronously unless the file is deleted.
+    pub(crate) fn mark_index_replaced(&self) {
+        self.inner.index_replaced.store(true, Ordering::Relaxed);
+    }
+
+    /// Marks the index of the file as being rebuilt, returns false if it's already
+    /// being rebuilt.
+    pub(crate) fn start_rebuilding_index(&self) -> bool {
+        self.inner
+            .rebuilding_index
+            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Marks the rebuild of the index as finished.
+    pub(crate) fn finish_rebuilding_index(&self) {
+        self.inner.rebuilding_index.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns true if the index of the file is being rebuilt.
+    #[cfg(test)]
+    pub(crate) fn rebuilding_index(&self) -> bool {
+        self.inner.rebuilding_index.load(Ordering::Relaxed)
+    }
 }
 
 /// Inner data of [FileHandle].
@@ -187,6 +303,10 @@ struct FileHandleInner {
     meta: FileMeta,
     compacting: AtomicBool,
     deleted: AtomicBool,
+    /// Whether the index of the file is being rebuilt.
+    rebuilding_index: AtomicBool,
+    /// Whether the index file is replaced by a new index file.
+    index_replaced: AtomicBool,
     file_purger: FilePurgerRef,
 }
 
@@ -195,6 +315,12 @@ impl Drop for FileHandleInner {
         if self.deleted.load(Ordering::Relaxed) {
             self.file_purger.send_request(PurgeRequest {
                 file_meta: self.meta.clone(),
+                index_only: false,
+            });
+        } else if self.index_replaced.load(Ordering::Relaxed) {
+            self.file_purger.send_request(PurgeRequest {
+                file_meta: self.meta.clone(),
+                index_only: true,
             });
         }
     }
@@ -206,6 +332,8 @@ impl FileHandleInner {
             meta,
             compacting: AtomicBool::new(false),
             deleted: AtomicBool::new(false),
+            rebuilding_index: AtomicBool::new(false),
+            index_replaced: AtomicBool::new(false),
             file_purger,
         }
     }
@@ -255,6 +383,10 @@ mod tests {
             file_size: 0,
             available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
             index_file_size: 0,
+            rolled_up: false,
+            index_stats: Vec::new(),
+            footer_checksum: None,
+            index_file_id: None,
         }
     }
 
@@ -27,6 +27,8 @@ use crate::sst::file::FileMeta;
 pub struct PurgeRequest {
     /// File meta.
     pub file_meta: FileMeta,
+    /// Only removes the index file of the file, e.g. the index is replaced.
+    pub index_only: bool,
 }
 
 /// A worker to delete files in background.
@@ -72,9 +74,28 @@ impl FilePurger for LocalFilePurger {
         let file_meta = request.file_meta;
         let sst_layer = self.sst_layer.clone();
 
+        if request.index_only {
+            if let Err(e) = self.scheduler.schedule(Box::pin(async move {
+                if let Err(e) = sst_layer.delete_index(&file_meta).await {
+                    error!(e; "Failed to delete index file, file_id: {}, region: {}",
+                        file_meta.file_id, file_meta.region_id);
+                } else {
+                    info!(
+                        "Successfully deleted index file, file_id: {}, index_file_id: {}, region: {}",
+                        file_meta.file_id, file_meta.index_file_id(), file_meta.region_id
+                    );
+                }
+            })) {
+                error!(e; "Failed to schedule the index file purge request");
+            }
+            return;
+        }
+
         // Remove meta of the file from cache.
         if let Some(cache) = &self.cache_manager {
             cache.remove_parquet_meta_data(file_meta.region_id, file_meta.file_id);
+            cache.remove_sst_reader(file_meta.region_id, file_meta.file_id);
+            cache.remove_row_group_hints(file_meta.region_id, file_meta.file_id);
         }
 
         if let Err(e) = self.scheduler.schedule(Box::pin(async move {
@@ -104,7 +125,7 @@ mod tests {
     use crate::access_layer::AccessLayer;
     use crate::schedule::scheduler::{LocalScheduler, Scheduler};
     use crate::sst::file::{FileHandle, FileId, FileMeta, FileTimeRange, IndexType};
-    use crate::sst::location;
+    use crate::sst::location::{self, FlatPath};
 
     #[tokio::test]
     async fn test_file_purge() {
@@ -121,7 +142,12 @@ mod tests {
         object_store.write(&path, vec![0; 4096]).await.unwrap();
 
         let scheduler = Arc::new(LocalScheduler::new(3));
-        let layer = Arc::new(AccessLayer::new(sst_dir, object_store.clone()));
+        let layer = Arc::new(AccessLayer::new(
+            sst_dir,
+            object_store.clone(),
+            None,
+            Arc::new(FlatPath),
+        ));
 
         let file_purger = Arc::new(LocalFilePurger::new(scheduler.clone(), layer, None));
 
@@ -135,6 +161,10 @@ mod tests {
                     file_size: 4096,
                     available_indexes: Default::default(),
                     index_file_size: 0,
+                    rolled_up: false,
+                    index_stats: Vec::new(),
+                    footer_checksum: None,
+                    index_file_id: None,
                 },
                 file_purger,
             );
@@ -168,7 +198,12 @@ mod tests {
             .unwrap();
 
         let scheduler = Arc::new(LocalScheduler::new(3));
-        let layer = Arc::new(AccessLayer::new(sst_dir, object_store.clone()));
+        let layer = Arc::new(AccessLayer::new(
+            sst_dir,
+            object_store.clone(),
+            None,
+            Arc::new(FlatPath),
+        ));
 
         let file_purger = Arc::new(LocalFilePurger::new(scheduler.clone(), layer, None));
 
@@ -182,6 +217,10 @@ mod tests {
                     file_size: 4096,
                     available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
                     index_file_size: 4096,
+                    rolled_up: false,
+                    index_stats: Vec::new(),
+                    footer_checksum: None,
+                    index_file_id: None,
                 },
                 file_purger,
             );
@@ -194,4 +233,55 @@ mod tests {
         assert!(!object_store.is_exist(&path).await.unwrap());
         assert!(!object_store.is_exist(&index_path).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_file_purge_replaced_index() {
+        common_telemetry::init_default_ut_logging();
+
+        let dir = create_temp_dir("file-purge");
+        let mut builder = Fs::default();
+        builder.root(dir.path().to_str().unwrap());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+        let sst_file_id = FileId::random();
+        let sst_dir = "table1";
+
+        let path = location::sst_file_path(sst_dir, sst_file_id);
+        object_store.write(&path, vec![0; 4096]).await.unwrap();
+        let index_path = location::index_file_path(sst_dir, sst_file_id);
+        object_store
+            .write(&index_path, vec![0; 4096])
+            .await
+            .unwrap();
+
+        let scheduler = Arc::new(LocalScheduler::new(3));
+        let layer = Arc::new(AccessLayer::new(
+            sst_dir,
+            object_store.clone(),
+            None,
+            Arc::new(FlatPath),
+        ));
+        let file_purger = Arc::new(LocalFilePurger::new(scheduler.clone(), layer, None));
+
+        {
+            let handle = FileHandle::new(
+                FileMeta {
+                    region_id: 0.into(),
+                    file_id: sst_file_id,
+                    level: 0,
+                    file_size: 4096,
+                    available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
+                    index_file_size: 4096,
+                    ..Default::default()
+                },
+                file_purger,
+            );
+            // The index is rebuilt under a new name, only the old index file is deleted.
+            handle.mark_index_replaced();
+        }
+
+        scheduler.stop(true).await.unwrap();
+
+        assert!(object_store.is_exist(&path).await.unwrap());
+        assert!(!object_store.is_exist(&index_path).await.unwrap());
+    }
 }
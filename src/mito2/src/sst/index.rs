@@ -17,7 +17,12 @@
 pub mod applier;
 mod codec;
 pub mod creator;
-mod store;
+pub mod explain;
+pub(crate) mod limiter;
+pub(crate) mod rebuilder;
+pub(crate) mod store;
+
+use std::time::Duration;
 
 const INDEX_BLOB_TYPE: &str = "greptime-inverted-index-v1";
 
@@ -25,5 +30,20 @@ const INDEX_BLOB_TYPE: &str = "greptime-inverted-index-v1";
 /// The minimum memory usage threshold for a column to qualify for external sorting during index creation.
 const MIN_MEMORY_USAGE_THRESHOLD: usize = 8192;
 
+/// The max number of sorted runs of a column while creating the index, runs are merged
+/// when there are more runs.
+const MAX_SORTED_RUNS_PER_COLUMN: usize = 64;
+
+/// The number of rows of a column to sample to estimate its cardinality.
+const CARDINALITY_SAMPLE_ROWS: usize = 4096;
+
+/// Skips the index of a column if the ratio of its distinct values to rows sampled
+/// exceeds this threshold, as the index of a near-unique column is mostly wasted.
+const MAX_DISTINCT_RATIO_TO_INDEX: f64 = 0.9;
+
 /// The buffer size for the pipe used to send index data to the puffin blob.
 const PIPE_BUFFER_SIZE_FOR_SENDING_BLOB: usize = 8192;
+
+/// Intermediate files not modified in this duration are treated as leftovers of
+/// index creations that didn't finish.
+const ORPHAN_INTERMEDIATE_FILE_TTL: Duration = Duration::from_secs(60 * 60);
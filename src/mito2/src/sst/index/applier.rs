@@ -14,14 +14,20 @@
 
 pub mod builder;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
-use futures::{AsyncRead, AsyncSeek};
-use index::inverted_index::format::reader::InvertedIndexBlobReader;
+use api::greptime_proto::v1::index::{InvertedIndexMeta, InvertedIndexMetas};
+use async_trait::async_trait;
+use common_base::BitVec;
+use common_telemetry::debug;
+use futures::{AsyncRead, AsyncSeek, StreamExt};
+use index::inverted_index::error::Result as IndexResult;
+use index::inverted_index::format::reader::{InvertedIndexBlobReader, InvertedIndexReader};
 use index::inverted_index::search::index_apply::{
     IndexApplier, IndexNotFoundStrategy, SearchContext,
 };
+use index::inverted_index::FstMap;
 use object_store::ObjectStore;
 use puffin::file_format::reader::{PuffinAsyncReader, PuffinFileReader};
 use snafu::{OptionExt, ResultExt};
@@ -39,7 +45,7 @@ use crate::metrics::{
 use crate::sst::file::FileId;
 use crate::sst::index::store::InstrumentedStore;
 use crate::sst::index::INDEX_BLOB_TYPE;
-use crate::sst::location;
+use crate::sst::location::{FlatPath, PathStrategyRef};
 
 /// The [`SstIndexApplier`] is responsible for applying predicates to the provided SST files
 /// and returning the relevant row group ids for further scan.
@@ -47,6 +53,9 @@ pub(crate) struct SstIndexApplier {
     /// The root directory of the region.
     region_dir: String,
 
+    /// Strategy to derive paths of index files under the region directory.
+    path_strategy: PathStrategyRef,
+
     /// Region ID.
     region_id: RegionId,
 
@@ -63,6 +72,16 @@ pub(crate) struct SstIndexApplier {
 
 pub(crate) type SstIndexApplierRef = Arc<SstIndexApplier>;
 
+/// Output of applying predicates to the index of a SST file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct IndexApplyOutput {
+    /// Ids of row groups matched by predicates.
+    pub(crate) row_groups: BTreeSet<usize>,
+    /// Number of postings read from the index, i.e. postings of values matched
+    /// by predicates.
+    pub(crate) num_postings: usize,
+}
+
 impl SstIndexApplier {
     /// Creates a new [`SstIndexApplier`].
     pub fn new(
@@ -76,6 +95,7 @@ impl SstIndexApplier {
 
         Self {
             region_dir,
+            path_strategy: Arc::new(FlatPath),
             region_id,
             store: InstrumentedStore::new(object_store),
             file_cache,
@@ -83,32 +103,59 @@ impl SstIndexApplier {
         }
     }
 
-    /// Applies predicates to the provided SST file id and returns the relevant row group ids
-    pub async fn apply(&self, file_id: FileId) -> Result<BTreeSet<usize>> {
+    /// Sets the strategy to derive paths of index files, index files are under the
+    /// region directory by default.
+    pub fn with_path_strategy(mut self, path_strategy: PathStrategyRef) -> Self {
+        self.path_strategy = path_strategy;
+        self
+    }
+
+    /// Applies predicates to the index file with `index_file_id` of the SST with `file_id`
+    /// and returns the relevant row group ids
+    pub async fn apply(&self, file_id: FileId, index_file_id: FileId) -> Result<BTreeSet<usize>> {
+        self.apply_index(file_id, index_file_id)
+            .await
+            .map(|output| output.row_groups)
+    }
+
+    /// Applies predicates to the index file with `index_file_id` of the SST with `file_id`
+    /// and returns the relevant row group ids with the number of postings read.
+    async fn apply_index(
+        &self,
+        file_id: FileId,
+        index_file_id: FileId,
+    ) -> Result<IndexApplyOutput> {
         let _timer = INDEX_APPLY_ELAPSED.start_timer();
 
         let context = SearchContext {
-            // Encountering a non-existing column indicates that it doesn't match predicates.
-            index_not_found_strategy: IndexNotFoundStrategy::ReturnEmpty,
+            // A column may have no index as it isn't selected to index, so we can't prune
+            // row groups by predicates of non-existing columns.
+            index_not_found_strategy: IndexNotFoundStrategy::Ignore,
         };
 
-        match self.cached_puffin_reader(file_id).await? {
+        match self.cached_puffin_reader(index_file_id).await? {
             Some(mut puffin_reader) => {
                 let blob_reader = Self::index_blob_reader(&mut puffin_reader).await?;
-                let mut index_reader = InvertedIndexBlobReader::new(blob_reader);
-                self.index_applier
+                let mut index_reader =
+                    PostingsCounter::new(InvertedIndexBlobReader::new(blob_reader));
+                let row_groups = self
+                    .index_applier
                     .apply(context, &mut index_reader)
                     .await
-                    .context(ApplyIndexSnafu)
+                    .context(ApplyIndexSnafu)?;
+                Ok(index_reader.into_output(row_groups))
             }
             None => {
-                let mut puffin_reader = self.remote_puffin_reader(file_id).await?;
+                let mut puffin_reader = self.remote_puffin_reader(file_id, index_file_id).await?;
                 let blob_reader = Self::index_blob_reader(&mut puffin_reader).await?;
-                let mut index_reader = InvertedIndexBlobReader::new(blob_reader);
-                self.index_applier
+                let mut index_reader =
+                    PostingsCounter::new(InvertedIndexBlobReader::new(blob_reader));
+                let row_groups = self
+                    .index_applier
                     .apply(context, &mut index_reader)
                     .await
-                    .context(ApplyIndexSnafu)
+                    .context(ApplyIndexSnafu)?;
+                Ok(index_reader.into_output(row_groups))
             }
         }
     }
@@ -116,14 +163,18 @@ impl SstIndexApplier {
     /// Helper function to create a [`PuffinFileReader`] from the cached index file.
     async fn cached_puffin_reader(
         &self,
-        file_id: FileId,
+        index_file_id: FileId,
     ) -> Result<Option<PuffinFileReader<impl AsyncRead + AsyncSeek>>> {
         let Some(file_cache) = &self.file_cache else {
             return Ok(None);
         };
 
         Ok(file_cache
-            .reader(IndexKey::new(self.region_id, file_id, FileType::Puffin))
+            .reader(IndexKey::new(
+                self.region_id,
+                index_file_id,
+                FileType::Puffin,
+            ))
             .await
             .map(PuffinFileReader::new))
     }
@@ -132,8 +183,11 @@ impl SstIndexApplier {
     async fn remote_puffin_reader(
         &self,
         file_id: FileId,
+        index_file_id: FileId,
     ) -> Result<PuffinFileReader<impl AsyncRead + AsyncSeek>> {
-        let file_path = location::index_file_path(&self.region_dir, file_id);
+        let file_path =
+            self.path_strategy
+                .index_file_path(&self.region_dir, file_id, index_file_id);
         let file_reader = self
             .store
             .reader(
@@ -141,11 +195,45 @@ impl SstIndexApplier {
                 &INDEX_PUFFIN_READ_BYTES_TOTAL,
                 &INDEX_PUFFIN_READ_OP_TOTAL,
                 &INDEX_PUFFIN_SEEK_OP_TOTAL,
+                None,
             )
             .await?;
         Ok(PuffinFileReader::new(file_reader))
     }
 
+    /// Applies predicates to SST files concurrently and returns the relevant row group
+    /// ids of each file. `files` are pairs of the SST file id and its index file id, the
+    /// result is keyed by the SST file id.
+    ///
+    /// At most `concurrency` files are applied at the same time to bound the memory
+    /// to read indexes. Files failed to apply are absent in the result, so readers
+    /// of these files can handle the failure, e.g. read all row groups.
+    pub async fn apply_files(
+        &self,
+        files: &[(FileId, FileId)],
+        concurrency: usize,
+    ) -> HashMap<FileId, IndexApplyOutput> {
+        futures::stream::iter(files)
+            .map(|(file_id, index_file_id)| async move {
+                (*file_id, self.apply_index(*file_id, *index_file_id).await)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .filter_map(|(file_id, result)| async move {
+                match result {
+                    Ok(output) => Some((file_id, output)),
+                    Err(e) => {
+                        debug!(
+                            "Failed to apply index, region_id: {}, file_id: {}, error: {}",
+                            self.region_id, file_id, e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await
+    }
+
     /// Helper function to create a [`PuffinBlobReader`] for the index blob of the provided index file reader.
     async fn index_blob_reader(
         puffin_reader: &mut PuffinFileReader<impl AsyncRead + AsyncSeek + Unpin + Send>,
@@ -167,6 +255,49 @@ impl SstIndexApplier {
     }
 }
 
+/// [InvertedIndexReader] that counts postings read from the index.
+struct PostingsCounter<R> {
+    reader: R,
+    num_postings: usize,
+}
+
+impl<R> PostingsCounter<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            num_postings: 0,
+        }
+    }
+
+    fn into_output(self, row_groups: BTreeSet<usize>) -> IndexApplyOutput {
+        IndexApplyOutput {
+            row_groups,
+            num_postings: self.num_postings,
+        }
+    }
+}
+
+#[async_trait]
+impl<R: InvertedIndexReader> InvertedIndexReader for PostingsCounter<R> {
+    async fn metadata(&mut self) -> IndexResult<InvertedIndexMetas> {
+        self.reader.metadata().await
+    }
+
+    async fn fst(&mut self, meta: &InvertedIndexMeta) -> IndexResult<FstMap> {
+        self.reader.fst(meta).await
+    }
+
+    async fn bitmap(
+        &mut self,
+        meta: &InvertedIndexMeta,
+        relative_offset: u32,
+        size: u32,
+    ) -> IndexResult<BitVec> {
+        self.num_postings += 1;
+        self.reader.bitmap(meta, relative_offset, size).await
+    }
+}
+
 impl Drop for SstIndexApplier {
     fn drop(&mut self) {
         INDEX_APPLY_MEMORY_USAGE.sub(self.index_applier.memory_usage() as i64);
@@ -182,6 +313,7 @@ mod tests {
 
     use super::*;
     use crate::error::Error;
+    use crate::sst::location;
 
     #[tokio::test]
     async fn test_index_applier_apply_basic() {
@@ -214,10 +346,59 @@ mod tests {
             None,
             Box::new(mock_index_applier),
         );
-        let ids = sst_index_applier.apply(file_id).await.unwrap();
+        let ids = sst_index_applier.apply(file_id, file_id).await.unwrap();
         assert_eq!(ids, BTreeSet::from_iter([1, 2, 3]));
     }
 
+    #[tokio::test]
+    async fn test_index_applier_apply_files() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let region_dir = "region_dir".to_string();
+        let file_ids: Vec<_> = (0..4).map(|_| FileId::random()).collect();
+        // The last file has no index.
+        for file_id in &file_ids[..3] {
+            let path = location::index_file_path(&region_dir, *file_id);
+            let mut puffin_writer =
+                PuffinFileWriter::new(object_store.writer(&path).await.unwrap());
+            puffin_writer
+                .add_blob(Blob {
+                    blob_type: INDEX_BLOB_TYPE.to_string(),
+                    data: Cursor::new(vec![]),
+                    properties: Default::default(),
+                })
+                .await
+                .unwrap();
+            puffin_writer.finish().await.unwrap();
+        }
+
+        let mut mock_index_applier = MockIndexApplier::new();
+        mock_index_applier.expect_memory_usage().returning(|| 100);
+        mock_index_applier
+            .expect_apply()
+            .times(3)
+            .returning(|_, _| Ok(BTreeSet::from_iter([0, 2])));
+
+        let sst_index_applier = SstIndexApplier::new(
+            region_dir.clone(),
+            RegionId::new(0, 0),
+            object_store,
+            None,
+            Box::new(mock_index_applier),
+        );
+        let files: Vec<_> = file_ids
+            .iter()
+            .map(|file_id| (*file_id, *file_id))
+            .collect();
+        let row_groups = sst_index_applier.apply_files(&files, 2).await;
+        assert_eq!(3, row_groups.len());
+        for file_id in &file_ids[..3] {
+            assert_eq!(BTreeSet::from_iter([0, 2]), row_groups[file_id].row_groups);
+            // The mock applier reads no postings.
+            assert_eq!(0, row_groups[file_id].num_postings);
+        }
+        assert!(!row_groups.contains_key(&file_ids[3]));
+    }
+
     #[tokio::test]
     async fn test_index_applier_apply_invalid_blob_type() {
         let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
@@ -247,7 +428,7 @@ mod tests {
             None,
             Box::new(mock_index_applier),
         );
-        let res = sst_index_applier.apply(file_id).await;
+        let res = sst_index_applier.apply(file_id, file_id).await;
         assert!(matches!(res, Err(Error::PuffinBlobTypeNotFound { .. })));
     }
 }
@@ -18,7 +18,7 @@ mod eq_list;
 mod in_list;
 mod regex_match;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use api::v1::SemanticType;
 use common_query::logical_plan::Expr;
@@ -39,12 +39,17 @@ use crate::error::{BuildIndexApplierSnafu, ColumnNotFoundSnafu, ConvertValueSnaf
 use crate::row_converter::SortField;
 use crate::sst::index::applier::SstIndexApplier;
 use crate::sst::index::codec::IndexValueCodec;
+use crate::sst::location::PathStrategyRef;
 
 /// Constructs an [`SstIndexApplier`] which applies predicates to SST files during scan.
 pub(crate) struct SstIndexApplierBuilder<'a> {
     /// Directory of the region, required argument for constructing [`SstIndexApplier`].
     region_dir: String,
 
+    /// Strategy to derive paths of index files, index files are under the region
+    /// directory if it is `None`.
+    path_strategy: Option<PathStrategyRef>,
+
     /// Object store, required argument for constructing [`SstIndexApplier`].
     object_store: ObjectStore,
 
@@ -56,6 +61,9 @@ pub(crate) struct SstIndexApplierBuilder<'a> {
 
     /// Stores predicates during traversal on the Expr tree.
     output: HashMap<ColumnId, Vec<Predicate>>,
+
+    /// Only applies indexes of these columns if set.
+    columns: Option<HashSet<ColumnId>>,
 }
 
 impl<'a> SstIndexApplierBuilder<'a> {
@@ -68,19 +76,37 @@ impl<'a> SstIndexApplierBuilder<'a> {
     ) -> Self {
         Self {
             region_dir,
+            path_strategy: None,
             object_store,
             file_cache,
             metadata,
             output: HashMap::default(),
+            columns: None,
         }
     }
 
+    /// Sets the strategy to derive paths of index files under the region directory.
+    pub fn path_strategy(mut self, path_strategy: PathStrategyRef) -> Self {
+        self.path_strategy = Some(path_strategy);
+        self
+    }
+
+    /// Only applies indexes of `columns`, `None` to apply indexes of all columns.
+    pub fn columns(mut self, columns: Option<HashSet<ColumnId>>) -> Self {
+        self.columns = columns;
+        self
+    }
+
     /// Consumes the builder to construct an [`SstIndexApplier`], optionally returned based on
     /// the expressions provided. If no predicates match, returns `None`.
     pub fn build(mut self, exprs: &[Expr]) -> Result<Option<SstIndexApplier>> {
         for expr in exprs {
             self.traverse_and_collect(expr.df_expr());
         }
+        if let Some(columns) = &self.columns {
+            self.output
+                .retain(|column_id, _| columns.contains(column_id));
+        }
 
         if self.output.is_empty() {
             return Ok(None);
@@ -92,13 +118,17 @@ impl<'a> SstIndexApplierBuilder<'a> {
             .map(|(column_id, predicates)| (column_id.to_string(), predicates))
             .collect();
         let applier = PredicatesIndexApplier::try_from(predicates);
-        Ok(Some(SstIndexApplier::new(
+        let applier = SstIndexApplier::new(
             self.region_dir,
             self.metadata.region_id,
             self.object_store,
             self.file_cache,
             Box::new(applier.context(BuildIndexApplierSnafu)?),
-        )))
+        );
+        match self.path_strategy {
+            Some(path_strategy) => Ok(Some(applier.with_path_strategy(path_strategy))),
+            None => Ok(Some(applier)),
+        }
     }
 
     /// Recursively traverses expressions to collect predicates.
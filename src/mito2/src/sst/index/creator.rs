@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod sampler;
 mod statistics;
 mod temp_provider;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
-use common_telemetry::warn;
+use common_telemetry::{debug, warn};
+use common_time::clock::Clock;
 use index::inverted_index::create::sort::external_sort::ExternalSorter;
 use index::inverted_index::create::sort_create::SortIndexCreator;
 use index::inverted_index::create::InvertedIndexCreator;
@@ -28,6 +30,7 @@ use object_store::ObjectStore;
 use puffin::file_format::writer::{Blob, PuffinAsyncWriter, PuffinFileWriter};
 use snafu::{ensure, ResultExt};
 use store_api::metadata::RegionMetadataRef;
+use store_api::storage::ColumnId;
 use tokio::io::duplex;
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
@@ -39,25 +42,55 @@ use crate::metrics::{
     INDEX_PUFFIN_FLUSH_OP_TOTAL, INDEX_PUFFIN_WRITE_BYTES_TOTAL, INDEX_PUFFIN_WRITE_OP_TOTAL,
 };
 use crate::read::Batch;
-use crate::sst::file::FileId;
+use crate::sst::file::{ColumnIndexStats, FileId};
 use crate::sst::index::codec::{IndexValueCodec, IndexValuesCodec};
+use crate::sst::index::creator::sampler::CardinalitySampler;
 use crate::sst::index::creator::statistics::Statistics;
 use crate::sst::index::creator::temp_provider::TempFileProvider;
-use crate::sst::index::store::InstrumentedStore;
+use crate::sst::index::limiter::IndexBuildPermit;
+use crate::sst::index::store::{InstrumentedStore, RetryPolicy};
 use crate::sst::index::{
-    INDEX_BLOB_TYPE, MIN_MEMORY_USAGE_THRESHOLD, PIPE_BUFFER_SIZE_FOR_SENDING_BLOB,
+    CARDINALITY_SAMPLE_ROWS, INDEX_BLOB_TYPE, MAX_DISTINCT_RATIO_TO_INDEX,
+    MAX_SORTED_RUNS_PER_COLUMN, MIN_MEMORY_USAGE_THRESHOLD, ORPHAN_INTERMEDIATE_FILE_TTL,
+    PIPE_BUFFER_SIZE_FOR_SENDING_BLOB,
 };
-use crate::sst::location::{self, IntermediateLocation};
+use crate::sst::location::{self, IntermediateLocation, PathStrategy};
 
 type ByteCount = usize;
 type RowCount = usize;
 
+/// State of the index of a column.
+enum ColumnState {
+    /// Sampling values to decide whether to index the column, values are pushed to
+    /// the index creator once the column is decided to index.
+    Sampling(CardinalitySampler),
+    /// Pushing values to the index creator.
+    Indexing,
+    /// The index is skipped as the column has too many distinct values.
+    Skipped,
+}
+
+impl ColumnState {
+    /// Starts indexing the column, returns the sampler if the column is sampling.
+    fn start_indexing(&mut self) -> Option<CardinalitySampler> {
+        match std::mem::replace(self, ColumnState::Indexing) {
+            ColumnState::Sampling(sampler) => Some(sampler),
+            state => {
+                *self = state;
+                None
+            }
+        }
+    }
+}
+
 /// Creates SST index.
 pub struct SstIndexCreator {
-    /// Directory of the region.
-    region_dir: String,
+    /// Directory of the SST, the index file and intermediate files are under the directory.
+    sst_dir: String,
     /// ID of the SST file.
     sst_file_id: FileId,
+    /// ID of the index file, the same as the SST file unless the index is rebuilt.
+    index_file_id: FileId,
 
     /// The store to write index files.
     store: InstrumentedStore,
@@ -73,38 +106,73 @@ pub struct SstIndexCreator {
 
     /// Statistics of index creation.
     stats: Statistics,
+    /// Statistics of the index of each column, available after the index is finished.
+    column_stats: Vec<ColumnIndexStats>,
     /// Whether the index creation is aborted.
     aborted: bool,
+    /// Permit to run the index creation, released when the creator is dropped.
+    build_permit: Option<IndexBuildPermit>,
+    /// Ids of tag columns to index in the form of index names, indexes all tag columns
+    /// if it is `None`.
+    indexed_columns: Option<HashSet<String>>,
+    /// States of the index of each column, keyed by index names.
+    column_states: HashMap<String, ColumnState>,
 }
 
 impl SstIndexCreator {
     /// Creates a new `SstIndexCreator`.
     /// Should ensure that the number of tag columns is greater than 0.
+    ///
+    /// Intermediate files are written with a buffer of `intermediate_write_buffer_size`
+    /// bytes, `None` or 0 means no buffer. Operations on intermediate files are retried
+    /// by the `intermediate_retry_policy`. Intermediate files are listed in pages of
+    /// `intermediate_list_page_size` entries, `None` or 0 means the default page size.
+    /// Intermediate files take at most `intermediate_max_bytes` bytes, `None` or 0 means
+    /// no limit. At most `intermediate_open_concurrency` intermediate files are opened
+    /// concurrently, `None` or 0 means the default concurrency.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        region_dir: String,
+        sst_dir: String,
         sst_file_id: FileId,
         metadata: &RegionMetadataRef,
         index_store: ObjectStore,
         intermediate_store: ObjectStore, // prefer to use local store
         memory_usage_threshold: Option<usize>,
         row_group_size: NonZeroUsize,
+        intermediate_write_buffer_size: Option<usize>,
+        intermediate_retry_policy: RetryPolicy,
+        intermediate_list_page_size: Option<usize>,
+        intermediate_max_bytes: Option<usize>,
+        intermediate_open_concurrency: Option<usize>,
     ) -> Self {
         // `memory_usage_threshold` is the total memory usage threshold of the index creation,
         // so we need to divide it by the number of columns
         let memory_threshold = memory_usage_threshold.map(|threshold| {
             (threshold / metadata.primary_key.len()).max(MIN_MEMORY_USAGE_THRESHOLD)
         });
-        let temp_file_provider = Arc::new(TempFileProvider::new(
-            IntermediateLocation::new(&region_dir, &sst_file_id),
-            InstrumentedStore::new(intermediate_store),
-        ));
-        let sorter = ExternalSorter::factory(temp_file_provider.clone() as _, memory_threshold);
+        let temp_file_provider = Arc::new(
+            TempFileProvider::new(
+                IntermediateLocation::new(&sst_dir, &sst_file_id),
+                InstrumentedStore::new(intermediate_store)
+                    .with_write_buffer_size(intermediate_write_buffer_size)
+                    .with_retry_policy(intermediate_retry_policy)
+                    .with_list_page_size(intermediate_list_page_size),
+                intermediate_max_bytes,
+            )
+            .with_open_concurrency(intermediate_open_concurrency),
+        );
+        let sorter = ExternalSorter::factory(
+            temp_file_provider.clone() as _,
+            memory_threshold,
+            Some(MAX_SORTED_RUNS_PER_COLUMN),
+        );
         let index_creator = Box::new(SortIndexCreator::new(sorter, row_group_size));
 
         let codec = IndexValuesCodec::from_tag_columns(metadata.primary_key_columns());
         Self {
-            region_dir,
+            sst_dir,
             sst_file_id,
+            index_file_id: sst_file_id,
             store: InstrumentedStore::new(index_store),
             codec,
             index_creator,
@@ -113,10 +181,34 @@ impl SstIndexCreator {
             value_buf: vec![],
 
             stats: Statistics::default(),
+            column_stats: Vec::new(),
             aborted: false,
+            build_permit: None,
+            indexed_columns: None,
+            column_states: HashMap::new(),
         }
     }
 
+    /// Only indexes tag columns in `column_ids`.
+    pub(crate) fn with_indexed_columns(mut self, column_ids: HashSet<ColumnId>) -> Self {
+        let names = column_ids.iter().map(|id| id.to_string()).collect();
+        self.indexed_columns = Some(names);
+        self
+    }
+
+    /// Writes the index to the index file with `index_file_id` instead of the id of
+    /// the SST file.
+    pub(crate) fn with_index_file_id(mut self, index_file_id: FileId) -> Self {
+        self.index_file_id = index_file_id;
+        self
+    }
+
+    /// Holds the `permit` until the creator is dropped.
+    pub(crate) fn with_build_permit(mut self, permit: IndexBuildPermit) -> Self {
+        self.build_permit = Some(permit);
+        self
+    }
+
     /// Updates index with a batch of rows.
     /// Garbage will be cleaned up if failed to update.
     pub async fn update(&mut self, batch: &Batch) -> Result<()> {
@@ -130,8 +222,8 @@ impl SstIndexCreator {
             // clean up garbage if failed to update
             if let Err(err) = self.do_cleanup().await {
                 warn!(
-                    err; "Failed to clean up index creator, region_dir: {}, sst_file_id: {}",
-                    self.region_dir, self.sst_file_id,
+                    err; "Failed to clean up index creator, sst_dir: {}, sst_file_id: {}",
+                    self.sst_dir, self.sst_file_id,
                 );
             }
             return Err(update_err);
@@ -154,14 +246,21 @@ impl SstIndexCreator {
         // clean up garbage no matter finish successfully or not
         if let Err(err) = self.do_cleanup().await {
             warn!(
-                err; "Failed to clean up index creator, region_dir: {}, sst_file_id: {}",
-                self.region_dir, self.sst_file_id,
+                err; "Failed to clean up index creator, sst_dir: {}, sst_file_id: {}",
+                self.sst_dir, self.sst_file_id,
             );
         }
 
         finish_res.map(|_| (self.stats.row_count(), self.stats.byte_count()))
     }
 
+    /// Returns statistics of the index of each column, sorted by column ids.
+    ///
+    /// It's empty until the index is finished.
+    pub fn column_stats(&self) -> &[ColumnIndexStats] {
+        &self.column_stats
+    }
+
     /// Aborts index creation and clean up garbage.
     pub async fn abort(&mut self) -> Result<()> {
         if self.aborted {
@@ -179,6 +278,10 @@ impl SstIndexCreator {
         guard.inc_row_count(n);
 
         for (column_id, field, value) in self.codec.decode(batch.primary_key())? {
+            if !self.is_indexed(column_id) {
+                continue;
+            }
+
             if let Some(value) = value.as_ref() {
                 self.value_buf.clear();
                 IndexValueCodec::encode_value(value.as_value_ref(), field, &mut self.value_buf)?;
@@ -186,15 +289,53 @@ impl SstIndexCreator {
 
             // non-null value -> Some(encoded_bytes), null value -> None
             let value = value.is_some().then_some(self.value_buf.as_slice());
-            self.index_creator
-                .push_with_name_n(column_id, value, n)
-                .await
-                .context(PushIndexValueSnafu)?;
+            let state = self
+                .column_states
+                .entry(column_id.clone())
+                .or_insert_with(|| {
+                    ColumnState::Sampling(CardinalitySampler::new(CARDINALITY_SAMPLE_ROWS))
+                });
+            match state {
+                ColumnState::Indexing => self
+                    .index_creator
+                    .push_with_name_n(column_id, value, n)
+                    .await
+                    .context(PushIndexValueSnafu)?,
+                ColumnState::Skipped => {}
+                ColumnState::Sampling(sampler) => {
+                    if !sampler.add(value, n) {
+                        continue;
+                    }
+
+                    if sampler.distinct_ratio() > MAX_DISTINCT_RATIO_TO_INDEX {
+                        debug!(
+                            "Skip index of column {}, distinct values: {}, sst_file_id: {}",
+                            column_id,
+                            sampler.num_distinct(),
+                            self.sst_file_id,
+                        );
+                        *state = ColumnState::Skipped;
+                    } else if let Some(sampler) = state.start_indexing() {
+                        push_sampled_values(&mut self.index_creator, column_id, sampler).await?;
+                    }
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Indexes columns still sampling, they don't have enough rows to estimate their
+    /// cardinality.
+    async fn finish_sampling(&mut self) -> Result<()> {
+        for (column_id, state) in &mut self.column_states {
+            if let Some(sampler) = state.start_indexing() {
+                push_sampled_values(&mut self.index_creator, column_id, sampler).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Data flow of finishing index:
     ///
     /// ```text
@@ -216,7 +357,9 @@ impl SstIndexCreator {
     async fn do_finish(&mut self) -> Result<()> {
         let mut guard = self.stats.record_finish();
 
-        let file_path = location::index_file_path(&self.region_dir, self.sst_file_id);
+        self.finish_sampling().await?;
+
+        let file_path = location::index_file_path(&self.sst_dir, self.index_file_id);
         let file_writer = self
             .store
             .writer(
@@ -224,6 +367,7 @@ impl SstIndexCreator {
                 &INDEX_PUFFIN_WRITE_BYTES_TOTAL,
                 &INDEX_PUFFIN_WRITE_OP_TOTAL,
                 &INDEX_PUFFIN_FLUSH_OP_TOTAL,
+                None,
             )
             .await?;
         let mut puffin_writer = PuffinFileWriter::new(file_writer);
@@ -243,21 +387,175 @@ impl SstIndexCreator {
         index_finish.context(IndexFinishSnafu)?;
         puffin_add_blob.context(PuffinAddBlobSnafu)?;
 
+        // Indexes are named by column ids.
+        let mut column_stats: Vec<_> = index_writer
+            .stats()
+            .iter()
+            .filter_map(|(name, stats)| {
+                Some(ColumnIndexStats {
+                    column_id: name.parse().ok()?,
+                    distinct_count: stats.distinct_count,
+                    index_size: stats.index_size,
+                    postings_len: stats.postings_len,
+                    skipped: false,
+                })
+            })
+            .collect();
+        // Records columns whose indexes are skipped.
+        column_stats.extend(
+            self.column_states
+                .iter()
+                .filter_map(|(name, state)| match state {
+                    ColumnState::Skipped => Some(ColumnIndexStats {
+                        column_id: name.parse().ok()?,
+                        skipped: true,
+                        ..Default::default()
+                    }),
+                    _ => None,
+                }),
+        );
+        column_stats.sort_unstable_by_key(|stats| stats.column_id);
+        self.column_stats = column_stats;
+
         let byte_count = puffin_writer.finish().await.context(PuffinFinishSnafu)?;
         guard.inc_byte_count(byte_count);
         Ok(())
     }
 
+    fn is_indexed(&self, column_id: &str) -> bool {
+        self.indexed_columns
+            .as_ref()
+            .map_or(true, |columns| columns.contains(column_id))
+    }
+
     async fn do_cleanup(&mut self) -> Result<()> {
         let _guard = self.stats.record_cleanup();
 
+        debug!(
+            "Clean up intermediate files, files: {}, spill bytes: {}, temp bytes: {}, sst_file_id: {}",
+            self.temp_file_provider.num_files(),
+            self.temp_file_provider.spill_bytes(),
+            self.temp_file_provider.temp_bytes(),
+            self.sst_file_id,
+        );
         self.temp_file_provider.cleanup().await
     }
 }
 
+/// Removes intermediate files under `region_dir` left by index creations that didn't
+/// finish, e.g. the process crashed halfway, and returns the number of files removed.
+///
+/// Only directories of intermediate files given by the `path_strategy` are listed. The
+/// age of intermediate files is read from the `clock`.
+pub(crate) async fn reclaim_orphan_intermediate_files(
+    intermediate_store: ObjectStore,
+    region_dir: &str,
+    path_strategy: &dyn PathStrategy,
+    clock: &dyn Clock,
+) -> Result<usize> {
+    let intermediate_dirs = path_strategy
+        .intermediate_dirs(&intermediate_store, region_dir)
+        .await?;
+    TempFileProvider::reclaim_orphans(
+        &InstrumentedStore::new(intermediate_store),
+        &intermediate_dirs,
+        ORPHAN_INTERMEDIATE_FILE_TTL,
+        clock,
+    )
+    .await
+}
+
+/// Pushes values sampled by the `sampler` to the index of the column.
+async fn push_sampled_values(
+    index_creator: &mut Box<dyn InvertedIndexCreator>,
+    column_id: &str,
+    sampler: CardinalitySampler,
+) -> Result<()> {
+    for (value, n) in sampler.into_values() {
+        index_creator
+            .push_with_name_n(column_id, value.as_deref(), n)
+            .await
+            .context(PushIndexValueSnafu)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     // TODO(zhongzc): This PR has grown quite large, and the SstIndexCreator deserves
     // a significant number of unit tests. These unit tests are substantial enough to
     // make up a large PR on their own. I will bring them in with the next PR.
+
+    use std::path::Path;
+
+    use common_test_util::temp_dir::create_temp_dir;
+    use object_store::services::Memory;
+
+    use super::*;
+    use crate::access_layer::{new_fs_object_store, FsStoreConfig};
+    use crate::test_util::sst_util::{new_batch_by_range, sst_region_metadata};
+
+    /// Creates an index of many distinct tags with the given intermediate store
+    /// and returns the index file.
+    async fn create_index(intermediate_store: ObjectStore) -> Vec<u8> {
+        let metadata = Arc::new(sst_region_metadata());
+        let index_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let file_id = FileId::random();
+        let mut creator = SstIndexCreator::new(
+            "region_dir".to_string(),
+            file_id,
+            &metadata,
+            index_store.clone(),
+            intermediate_store,
+            // Spills to intermediate files as soon as possible.
+            Some(0),
+            NonZeroUsize::new(10).unwrap(),
+            None,
+            RetryPolicy::default(),
+            None,
+            None,
+            None,
+        );
+        for i in 0..3000 {
+            let batch = new_batch_by_range(&[&format!("{i:04}"), "b"], i, i + 1);
+            creator.update(&batch).await.unwrap();
+        }
+        let (row_count, _) = creator.finish().await.unwrap();
+        assert_eq!(3000, row_count);
+
+        index_store
+            .read(&location::index_file_path("region_dir", file_id))
+            .await
+            .unwrap()
+    }
+
+    fn count_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    count_files(&path)
+                } else {
+                    1
+                }
+            })
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_index_creator_intermediate_backends() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let expected = create_index(object_store).await;
+
+        let dir = create_temp_dir("intermediate");
+        let local_store = new_fs_object_store(&FsStoreConfig::new(dir.path().to_str().unwrap()))
+            .await
+            .unwrap();
+        let index = create_index(local_store).await;
+        assert_eq!(expected, index);
+
+        // Intermediate files on the local fs are removed.
+        assert_eq!(0, count_files(dir.path()));
+    }
 }
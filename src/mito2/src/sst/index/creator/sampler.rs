@@ -0,0 +1,111 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Samples values of a column to estimate its cardinality.
+
+use std::collections::HashSet;
+
+/// Samples the first rows of a column to estimate the ratio of distinct values.
+///
+/// The sampler keeps the sampled values so they can be indexed later if the column
+/// isn't skipped.
+pub(crate) struct CardinalitySampler {
+    /// Number of rows to sample.
+    max_rows: usize,
+    /// Number of rows sampled.
+    num_rows: usize,
+    /// Distinct non-null values sampled.
+    distinct_values: HashSet<Vec<u8>>,
+    /// Sampled values and their number of rows, in the order they are added.
+    values: Vec<(Option<Vec<u8>>, usize)>,
+}
+
+impl CardinalitySampler {
+    /// Creates a sampler that samples `max_rows` rows.
+    pub(crate) fn new(max_rows: usize) -> CardinalitySampler {
+        CardinalitySampler {
+            max_rows,
+            num_rows: 0,
+            distinct_values: HashSet::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Adds `n` rows of the `value`, returns true if enough rows are sampled.
+    pub(crate) fn add(&mut self, value: Option<&[u8]>, n: usize) -> bool {
+        let value = value.map(|value| value.to_vec());
+        if let Some(value) = &value {
+            if !self.distinct_values.contains(value) {
+                self.distinct_values.insert(value.clone());
+            }
+        }
+        self.values.push((value, n));
+        self.num_rows += n;
+
+        self.is_full()
+    }
+
+    /// Returns true if enough rows are sampled.
+    pub(crate) fn is_full(&self) -> bool {
+        self.num_rows >= self.max_rows
+    }
+
+    /// Returns the ratio of distinct non-null values to rows sampled.
+    pub(crate) fn distinct_ratio(&self) -> f64 {
+        if self.num_rows == 0 {
+            return 0.0;
+        }
+        self.distinct_values.len() as f64 / self.num_rows as f64
+    }
+
+    /// Returns the number of distinct non-null values sampled.
+    pub(crate) fn num_distinct(&self) -> usize {
+        self.distinct_values.len()
+    }
+
+    /// Returns sampled values and their number of rows.
+    pub(crate) fn into_values(self) -> Vec<(Option<Vec<u8>>, usize)> {
+        self.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinality_sampler() {
+        let mut sampler = CardinalitySampler::new(10);
+        assert_eq!(0.0, sampler.distinct_ratio());
+
+        assert!(!sampler.add(Some(b"a"), 2));
+        assert!(!sampler.add(None, 2));
+        assert!(!sampler.add(Some(b"b"), 3));
+        assert!(sampler.add(Some(b"a"), 3));
+        assert!(sampler.is_full());
+        assert_eq!(2, sampler.num_distinct());
+        assert_eq!(0.2, sampler.distinct_ratio());
+
+        let values = sampler.into_values();
+        assert_eq!(
+            vec![
+                (Some(b"a".to_vec()), 2),
+                (None, 2),
+                (Some(b"b".to_vec()), 3),
+                (Some(b"a".to_vec()), 3),
+            ],
+            values
+        );
+    }
+}
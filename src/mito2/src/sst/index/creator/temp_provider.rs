@@ -12,31 +12,66 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
-use common_telemetry::warn;
-use futures::{AsyncRead, AsyncWrite};
+use common_telemetry::{info, warn};
+use common_time::clock::Clock;
+use futures::{stream, AsyncRead, AsyncWrite, StreamExt, TryStreamExt};
 use index::inverted_index::create::sort::external_provider::ExternalTempFileProvider;
 use index::inverted_index::error as index_error;
 use index::inverted_index::error::Result as IndexResult;
-use snafu::ResultExt;
+use lazy_static::lazy_static;
+use snafu::{ensure, ResultExt};
 
-use crate::error::Result;
+use crate::error::{IntermediateBytesExceededSnafu, Result};
 use crate::metrics::{
-    INDEX_INTERMEDIATE_FLUSH_OP_TOTAL, INDEX_INTERMEDIATE_READ_BYTES_TOTAL,
+    INDEX_INTERMEDIATE_FILES, INDEX_INTERMEDIATE_FLUSH_OP_TOTAL,
+    INDEX_INTERMEDIATE_ORPHAN_RECLAIMED_TOTAL, INDEX_INTERMEDIATE_READ_BYTES_TOTAL,
     INDEX_INTERMEDIATE_READ_OP_TOTAL, INDEX_INTERMEDIATE_SEEK_OP_TOTAL,
+    INDEX_INTERMEDIATE_SPILL_BYTES_TOTAL, INDEX_INTERMEDIATE_TEMP_BYTES,
     INDEX_INTERMEDIATE_WRITE_BYTES_TOTAL, INDEX_INTERMEDIATE_WRITE_OP_TOTAL,
 };
 use crate::sst::index::store::InstrumentedStore;
-use crate::sst::location::IntermediateLocation;
+use crate::sst::location::{IntermediateLocation, INTERMEDIATE_DIR};
+
+/// Default number of intermediate files to open concurrently.
+const DEFAULT_OPEN_CONCURRENCY: usize = 8;
+
+lazy_static! {
+    /// Root directories of intermediate files of index creations in progress.
+    static ref ACTIVE_ROOTS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
 
 /// `TempFileProvider` implements `ExternalTempFileProvider`.
 /// It uses `InstrumentedStore` to create and read intermediate files.
+///
+/// Intermediate files are removed in background on drop if they are not cleaned up,
+/// e.g. the index creation fails halfway.
+///
+/// Writers of intermediate files fail with [`IntermediateBytesExceeded`] if intermediate
+/// files not removed yet would exceed `max_temp_bytes` bytes, so the index creation fails
+/// instead of filling the disk.
+///
+/// [`IntermediateBytesExceeded`]: crate::error::Error::IntermediateBytesExceeded
 pub(crate) struct TempFileProvider {
     /// Provides the location of intermediate files.
     location: IntermediateLocation,
     /// Provides access to files in the object store.
     store: InstrumentedStore,
+    /// Whether intermediate files are cleaned up.
+    cleaned: AtomicBool,
+    /// Statistics of intermediate files.
+    stats: Arc<SpillStats>,
+    /// Number of intermediate files to open concurrently.
+    open_concurrency: usize,
 }
 
 #[async_trait]
@@ -54,77 +89,399 @@ impl ExternalTempFileProvider for TempFileProvider {
                 &INDEX_INTERMEDIATE_WRITE_BYTES_TOTAL,
                 &INDEX_INTERMEDIATE_WRITE_OP_TOTAL,
                 &INDEX_INTERMEDIATE_FLUSH_OP_TOTAL,
+                None,
             )
             .await
             .map_err(BoxedError::new)
             .context(index_error::ExternalSnafu)?;
-        Ok(Box::new(writer))
+        let written = self.stats.add_file(path);
+        Ok(Box::new(SpillWriter {
+            inner: writer,
+            stats: self.stats.clone(),
+            written,
+        }))
     }
 
+    /// Reads all intermediate files of the column in the order of their file ids.
+    ///
+    /// File ids are zero-padded numbers of rows written before the files, so readers
+    /// are in the order the files were written. Ids of merged files are ids of the
+    /// first and the last file merged joined by `-`, and they are ordered by the first
+    /// id. Files whose ids are not numbers are read last. Files are opened concurrently
+    /// but readers keep the order.
     async fn read_all(
         &self,
         column_id: &str,
     ) -> IndexResult<Vec<Box<dyn AsyncRead + Unpin + Send>>> {
+        let mut entries = self.list_files(column_id).await?;
+        entries.sort_by_cached_key(|entry| {
+            let file_id = entry.name().trim_end_matches(".im");
+            // Safety: split always returns at least one item.
+            let first_id = file_id.split('-').next().unwrap();
+            let row_count = first_id.parse::<u64>().ok();
+            if row_count.is_none() {
+                warn!("Unexpected intermediate file name: {:?}", entry.path());
+            }
+            (row_count.is_none(), row_count, entry.name().to_string())
+        });
+        stream::iter(entries)
+            .map(|entry| self.open_reader(entry))
+            .buffered(self.open_concurrency)
+            .try_collect()
+            .await
+    }
+
+    async fn remove(&self, column_id: &str, file_id: &str) -> IndexResult<()> {
+        let path = self.location.file_path(column_id, file_id);
+        self.store
+            .delete(&path)
+            .await
+            .map_err(BoxedError::new)
+            .context(index_error::ExternalSnafu)?;
+        self.stats.remove_file(&path);
+        Ok(())
+    }
+}
+
+impl TempFileProvider {
+    /// Creates a new `TempFileProvider` whose intermediate files take at most
+    /// `max_temp_bytes` bytes, `None` or 0 means no limit.
+    pub fn new(
+        location: IntermediateLocation,
+        store: InstrumentedStore,
+        max_temp_bytes: Option<usize>,
+    ) -> Self {
+        ACTIVE_ROOTS
+            .lock()
+            .unwrap()
+            .insert(location.root_path().to_string());
+        Self {
+            location,
+            store,
+            cleaned: AtomicBool::new(false),
+            stats: Arc::new(SpillStats::new(max_temp_bytes.filter(|bytes| *bytes > 0))),
+            open_concurrency: DEFAULT_OPEN_CONCURRENCY,
+        }
+    }
+
+    /// Sets the number of intermediate files to open concurrently, `None` or 0 means
+    /// the default concurrency.
+    pub fn with_open_concurrency(mut self, open_concurrency: Option<usize>) -> Self {
+        self.open_concurrency = open_concurrency
+            .filter(|concurrency| *concurrency > 0)
+            .unwrap_or(DEFAULT_OPEN_CONCURRENCY);
+        self
+    }
+
+    /// Returns the number of intermediate files.
+    pub fn num_files(&self) -> usize {
+        self.stats.num_files.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total bytes spilled to intermediate files since the last cleanup,
+    /// including files already merged.
+    pub fn spill_bytes(&self) -> usize {
+        self.stats.spill_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the bytes of intermediate files not removed yet.
+    pub fn temp_bytes(&self) -> usize {
+        self.stats.temp_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Removes all intermediate files.
+    pub async fn cleanup(&self) -> Result<()> {
+        self.store.remove_all(self.location.root_path()).await?;
+        self.cleaned.store(true, Ordering::Relaxed);
+        self.stats.reset();
+        Ok(())
+    }
+
+    /// Removes intermediate files under `intermediate_dirs` left by index creations
+    /// that didn't finish, e.g. the process crashed halfway, and returns the number of
+    /// files removed. `intermediate_dirs` are `{sst_dir}/index/__intermediate/` of
+    /// the region's SST directories, so other files of the region are not listed.
+    ///
+    /// Intermediate files of an index creation are removed only if none of them is
+    /// modified in `older_than` by the `clock` and the creation is not in progress in
+    /// this process. Files whose modified time is unknown are treated as old.
+    pub async fn reclaim_orphans(
+        store: &InstrumentedStore,
+        intermediate_dirs: &[String],
+        older_than: Duration,
+        clock: &dyn Clock,
+    ) -> Result<usize> {
+        let mut entries = Vec::new();
+        for dir in intermediate_dirs {
+            entries.extend(store.list_recursive(dir).await?);
+        }
+
+        let now = clock.now_millis();
+        let mut orphans: HashMap<String, OrphanRoot> = HashMap::new();
+        for entry in entries {
+            let Some(root) = intermediate_root(entry.path()) else {
+                continue;
+            };
+            let meta = entry.metadata();
+            let expired = meta.last_modified().map_or(true, |modified| {
+                // Files modified in the future are not expired.
+                now - modified.timestamp_millis() >= older_than.as_millis() as i64
+            });
+            let orphan = orphans.entry(root.to_string()).or_insert(OrphanRoot {
+                num_files: 0,
+                bytes: 0,
+                expired: true,
+            });
+            orphan.num_files += 1;
+            orphan.bytes += meta.content_length();
+            orphan.expired &= expired;
+        }
+
+        let mut reclaimed = 0;
+        for (root, orphan) in orphans {
+            if !orphan.expired || ACTIVE_ROOTS.lock().unwrap().contains(&root) {
+                continue;
+            }
+            store.remove_all(&root).await?;
+            INDEX_INTERMEDIATE_ORPHAN_RECLAIMED_TOTAL.inc_by(orphan.num_files as u64);
+            info!(
+                "Reclaimed {} orphaned intermediate files, bytes: {}, path: {}",
+                orphan.num_files, orphan.bytes, root
+            );
+            reclaimed += orphan.num_files;
+        }
+        Ok(reclaimed)
+    }
+
+    /// Lists intermediate files of the column.
+    async fn list_files(&self, column_id: &str) -> IndexResult<Vec<object_store::Entry>> {
         let column_path = self.location.column_path(column_id);
         let entries = self
             .store
-            .list(&column_path)
+            .list(&column_path, None)
             .await
             .map_err(BoxedError::new)
             .context(index_error::ExternalSnafu)?;
-        let mut readers = Vec::with_capacity(entries.len());
 
-        for entry in entries {
-            if entry.metadata().is_dir() {
-                warn!("Unexpected entry in index creation dir: {:?}", entry.path());
-                continue;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| {
+                if entry.metadata().is_dir() {
+                    warn!("Unexpected entry in index creation dir: {:?}", entry.path());
+                    return false;
+                }
+                true
+            })
+            .collect())
+    }
+
+    /// Opens the reader of the intermediate file `entry`.
+    async fn open_reader(
+        &self,
+        entry: object_store::Entry,
+    ) -> IndexResult<Box<dyn AsyncRead + Unpin + Send>> {
+        let reader = self
+            .store
+            .reader(
+                entry.path(),
+                &INDEX_INTERMEDIATE_READ_BYTES_TOTAL,
+                &INDEX_INTERMEDIATE_READ_OP_TOTAL,
+                &INDEX_INTERMEDIATE_SEEK_OP_TOTAL,
+                None,
+            )
+            .await
+            .map_err(BoxedError::new)
+            .context(index_error::ExternalSnafu)?;
+        Ok(Box::new(reader))
+    }
+}
+
+impl Drop for TempFileProvider {
+    fn drop(&mut self) {
+        ACTIVE_ROOTS
+            .lock()
+            .unwrap()
+            .remove(self.location.root_path());
+        if self.cleaned.load(Ordering::Relaxed) {
+            return;
+        }
+        self.stats.reset();
+
+        let store = self.store.clone();
+        let root_path = self.location.root_path().to_string();
+        common_runtime::spawn_bg(async move {
+            if let Err(err) = store.remove_all(&root_path).await {
+                warn!(err; "Failed to remove intermediate files on drop, path: {}", root_path);
             }
+        });
+    }
+}
 
-            let reader = self
-                .store
-                .reader(
-                    entry.path(),
-                    &INDEX_INTERMEDIATE_READ_BYTES_TOTAL,
-                    &INDEX_INTERMEDIATE_READ_OP_TOTAL,
-                    &INDEX_INTERMEDIATE_SEEK_OP_TOTAL,
-                )
-                .await
-                .map_err(BoxedError::new)
-                .context(index_error::ExternalSnafu)?;
-            readers.push(Box::new(reader) as _);
+/// Intermediate files of an index creation found by [`TempFileProvider::reclaim_orphans`].
+struct OrphanRoot {
+    /// Number of files.
+    num_files: usize,
+    /// Total bytes of files.
+    bytes: u64,
+    /// Whether all files are older than the threshold.
+    expired: bool,
+}
+
+/// Returns the root directory of intermediate files of an index creation given the
+/// `path` of an intermediate file, see [`IntermediateLocation`]:
+/// `{sst_dir}/index/__intermediate/{sst_file_id}/{uuid}/`
+fn intermediate_root(path: &str) -> Option<&str> {
+    let start = path.find(INTERMEDIATE_DIR)? + INTERMEDIATE_DIR.len();
+    let file_id_len = path[start..].find('/')? + 1;
+    let uuid_len = path[start + file_id_len..].find('/')? + 1;
+    Some(&path[..start + file_id_len + uuid_len])
+}
+
+/// Statistics of intermediate files of an index creation, they are also added to
+/// the global metrics.
+#[derive(Default)]
+struct SpillStats {
+    /// Number of intermediate files.
+    num_files: AtomicUsize,
+    /// Total bytes written to intermediate files.
+    spill_bytes: AtomicUsize,
+    /// Bytes of intermediate files not removed yet.
+    temp_bytes: AtomicUsize,
+    /// Max bytes of intermediate files not removed yet, `None` means no limit.
+    max_temp_bytes: Option<usize>,
+    /// Bytes written to each intermediate file not removed yet, keyed by the path.
+    file_bytes: Mutex<HashMap<String, Arc<AtomicUsize>>>,
+}
+
+impl SpillStats {
+    fn new(max_temp_bytes: Option<usize>) -> Self {
+        Self {
+            max_temp_bytes,
+            ..Default::default()
         }
+    }
 
-        Ok(readers)
+    /// Adds the file of the `path` and returns the counter of bytes written to it.
+    fn add_file(&self, path: String) -> Arc<AtomicUsize> {
+        self.num_files.fetch_add(1, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_FILES.inc();
+        let written = Arc::new(AtomicUsize::new(0));
+        let replaced = self
+            .file_bytes
+            .lock()
+            .unwrap()
+            .insert(path, written.clone());
+        if let Some(replaced) = replaced {
+            // The file is overwritten.
+            self.sub_temp_bytes(replaced.load(Ordering::Relaxed));
+        }
+        written
+    }
+
+    fn remove_file(&self, path: &str) {
+        self.num_files.fetch_sub(1, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_FILES.dec();
+        if let Some(written) = self.file_bytes.lock().unwrap().remove(path) {
+            self.sub_temp_bytes(written.load(Ordering::Relaxed));
+        }
+    }
+
+    /// Returns an error if writing `len` bytes exceeds the max bytes of intermediate files.
+    fn check_bytes(&self, len: usize) -> Result<()> {
+        let Some(limit) = self.max_temp_bytes else {
+            return Ok(());
+        };
+        let used = self.temp_bytes.load(Ordering::Relaxed);
+        ensure!(
+            used + len <= limit,
+            IntermediateBytesExceededSnafu { limit, used, len }
+        );
+        Ok(())
+    }
+
+    fn add_bytes(&self, written: &AtomicUsize, bytes: usize) {
+        written.fetch_add(bytes, Ordering::Relaxed);
+        self.spill_bytes.fetch_add(bytes, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_SPILL_BYTES_TOTAL.inc_by(bytes as u64);
+        self.temp_bytes.fetch_add(bytes, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_TEMP_BYTES.add(bytes as i64);
+    }
+
+    fn sub_temp_bytes(&self, bytes: usize) {
+        self.temp_bytes.fetch_sub(bytes, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_TEMP_BYTES.sub(bytes as i64);
+    }
+
+    /// Resets the statistics after all files are removed.
+    fn reset(&self) {
+        let num_files = self.num_files.swap(0, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_FILES.sub(num_files as i64);
+        self.spill_bytes.store(0, Ordering::Relaxed);
+        self.file_bytes.lock().unwrap().clear();
+        let temp_bytes = self.temp_bytes.swap(0, Ordering::Relaxed);
+        INDEX_INTERMEDIATE_TEMP_BYTES.sub(temp_bytes as i64);
     }
 }
 
-impl TempFileProvider {
-    /// Creates a new `TempFileProvider`.
-    pub fn new(location: IntermediateLocation, store: InstrumentedStore) -> Self {
-        Self { location, store }
+/// Writer of an intermediate file that records bytes written to [`SpillStats`].
+struct SpillWriter<W> {
+    inner: W,
+    stats: Arc<SpillStats>,
+    /// Bytes written to the file.
+    written: Arc<AtomicUsize>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for SpillWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        // Wraps the error so the index creation returns it as an external error.
+        if let Err(e) = self.stats.check_bytes(buf.len()) {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Other,
+                BoxedError::new(e),
+            )));
+        }
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = &poll {
+            self.stats.add_bytes(&self.written, *n);
+        }
+        poll
     }
 
-    /// Removes all intermediate files.
-    pub async fn cleanup(&self) -> Result<()> {
-        self.store.remove_all(self.location.root_path()).await
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+    use common_test_util::temp_dir::create_temp_dir;
+    use common_time::clock::{MockClock, SystemClock};
     use futures::{AsyncReadExt, AsyncWriteExt};
-    use object_store::services::Memory;
+    use object_store::services::{Fs, Memory};
     use object_store::ObjectStore;
 
     use super::*;
     use crate::sst::file::FileId;
+    use crate::sst::location::{DatePartitionedPath, FlatPath, PathStrategy};
 
     #[tokio::test]
     async fn test_temp_file_provider_basic() {
         let location = IntermediateLocation::new("region_dir", &FileId::random());
         let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
         let store = InstrumentedStore::new(object_store);
-        let provider = TempFileProvider::new(location.clone(), store);
+        let provider = TempFileProvider::new(location.clone(), store, None);
 
         let column_name = "tag0";
         let file_id = "0000000010";
@@ -164,9 +521,344 @@ mod tests {
 
         assert!(provider
             .store
-            .list(location.root_path())
+            .list(location.root_path(), None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_read_all_in_order() {
+        let location = IntermediateLocation::new("region_dir", &FileId::random());
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let provider = TempFileProvider::new(location, InstrumentedStore::new(object_store), None);
+
+        let file_ids = [
+            "000000000100",
+            "invalid",
+            "000000000050-000000000090",
+            "000000001000",
+            "000000000010",
+        ];
+        for file_id in file_ids {
+            let mut writer = provider.create("tag0", file_id).await.unwrap();
+            writer.write_all(file_id.as_bytes()).await.unwrap();
+            writer.close().await.unwrap();
+        }
+
+        let readers = provider.read_all("tag0").await.unwrap();
+        let mut contents = Vec::with_capacity(readers.len());
+        for mut reader in readers {
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf).await.unwrap();
+            contents.push(buf);
+        }
+        assert_eq!(
+            vec![
+                "000000000010",
+                "000000000050-000000000090",
+                "000000000100",
+                "000000001000",
+                "invalid"
+            ],
+            contents
+        );
+
+        provider.cleanup().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_read_all_concurrently() {
+        let location = IntermediateLocation::new("region_dir", &FileId::random());
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let provider = TempFileProvider::new(location, InstrumentedStore::new(object_store), None)
+            .with_open_concurrency(Some(3));
+
+        let file_ids: Vec<_> = (0..20).map(|i| format!("{:010}", i * 10)).collect();
+        for file_id in &file_ids {
+            let mut writer = provider.create("tag0", file_id).await.unwrap();
+            writer.write_all(file_id.as_bytes()).await.unwrap();
+            writer.close().await.unwrap();
+        }
+
+        let mut contents = Vec::with_capacity(file_ids.len());
+        for mut reader in provider.read_all("tag0").await.unwrap() {
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf).await.unwrap();
+            contents.push(buf);
+        }
+        assert_eq!(file_ids, contents);
+
+        provider.cleanup().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_cleanup_on_drop() {
+        let location = IntermediateLocation::new("region_dir", &FileId::random());
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let store = InstrumentedStore::new(object_store);
+        let provider = TempFileProvider::new(location.clone(), store.clone(), None);
+
+        let mut writer = provider.create("tag0", "0000000010").await.unwrap();
+        writer.write_all(b"hello").await.unwrap();
+        writer.close().await.unwrap();
+        drop(writer);
+        assert!(!store
+            .list(location.root_path(), None)
             .await
             .unwrap()
             .is_empty());
+
+        // Drops without cleaning up.
+        drop(provider);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !store
+                .list(location.root_path(), None)
+                .await
+                .unwrap()
+                .is_empty()
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_spill_metrics() {
+        let location = IntermediateLocation::new("region_dir", &FileId::random());
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let provider = TempFileProvider::new(location, InstrumentedStore::new(object_store), None);
+
+        let spill_bytes_total = INDEX_INTERMEDIATE_SPILL_BYTES_TOTAL.get();
+        for file_id in ["0000000010", "0000000020", "0000000030"] {
+            let mut writer = provider.create("tag0", file_id).await.unwrap();
+            writer.write_all(b"hello").await.unwrap();
+            writer.close().await.unwrap();
+        }
+        assert_eq!(3, provider.num_files());
+        assert_eq!(15, provider.spill_bytes());
+        // Other tests may write intermediate files concurrently.
+        assert!(INDEX_INTERMEDIATE_FILES.get() >= 3);
+        assert!(INDEX_INTERMEDIATE_SPILL_BYTES_TOTAL.get() >= spill_bytes_total + 15);
+
+        provider.remove("tag0", "0000000010").await.unwrap();
+        assert_eq!(2, provider.num_files());
+        assert_eq!(15, provider.spill_bytes());
+
+        provider.cleanup().await.unwrap();
+        assert_eq!(0, provider.num_files());
+        assert_eq!(0, provider.spill_bytes());
+        // The counter is cumulative.
+        assert!(INDEX_INTERMEDIATE_SPILL_BYTES_TOTAL.get() >= spill_bytes_total + 15);
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_max_temp_bytes() {
+        let location = IntermediateLocation::new("region_dir", &FileId::random());
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let provider =
+            TempFileProvider::new(location, InstrumentedStore::new(object_store), Some(8));
+
+        let mut writer = provider.create("tag0", "0000000010").await.unwrap();
+        writer.write_all(b"hello").await.unwrap();
+        writer.close().await.unwrap();
+        assert_eq!(5, provider.temp_bytes());
+
+        // Exceeds the limit across files.
+        let mut writer = provider.create("tag0", "0000000020").await.unwrap();
+        let err = writer.write_all(b"world").await.unwrap_err();
+        let source = err.into_inner().unwrap().downcast::<BoxedError>().unwrap();
+        assert_eq!(StatusCode::RuntimeResourcesExhausted, source.status_code());
+        assert_eq!(5, provider.temp_bytes());
+
+        // Removing files releases the bytes.
+        provider.remove("tag0", "0000000010").await.unwrap();
+        assert_eq!(0, provider.temp_bytes());
+        let mut writer = provider.create("tag0", "0000000030").await.unwrap();
+        writer.write_all(b"world").await.unwrap();
+        writer.close().await.unwrap();
+        assert_eq!(5, provider.temp_bytes());
+        assert_eq!(10, provider.spill_bytes());
+
+        provider.cleanup().await.unwrap();
+        assert_eq!(0, provider.temp_bytes());
+    }
+
+    #[test]
+    fn test_intermediate_root() {
+        let location = IntermediateLocation::new("region_dir/2024/01/02/", &FileId::random());
+        let path = location.file_path("tag0", "0000000010");
+        assert_eq!(Some(location.root_path()), intermediate_root(&path));
+
+        assert_eq!(None, intermediate_root("region_dir/index/abc.puffin"));
+        assert_eq!(
+            None,
+            intermediate_root("region_dir/index/__intermediate/abc.im")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_reclaim_orphans() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let store = InstrumentedStore::new(object_store.clone());
+
+        // Intermediate files left by an index creation that didn't finish.
+        let orphan = IntermediateLocation::new("region_dir", &FileId::random());
+        for file_id in ["0000000010", "0000000020"] {
+            let mut writer = store
+                .writer(
+                    &orphan.file_path("tag0", file_id),
+                    &INDEX_INTERMEDIATE_WRITE_BYTES_TOTAL,
+                    &INDEX_INTERMEDIATE_WRITE_OP_TOTAL,
+                    &INDEX_INTERMEDIATE_FLUSH_OP_TOTAL,
+                    None,
+                )
+                .await
+                .unwrap();
+            writer.write_all(b"hello").await.unwrap();
+            writer.close().await.unwrap();
+        }
+        // Intermediate files of an SST in a date directory.
+        let file_id = DatePartitionedPath.new_file_id();
+        let dated_orphan = IntermediateLocation::new(
+            &DatePartitionedPath.sst_dir("region_dir", file_id),
+            &file_id,
+        );
+        object_store
+            .write(
+                &dated_orphan.file_path("tag0", "0000000010"),
+                b"hello".to_vec(),
+            )
+            .await
+            .unwrap();
+        // Files that are not intermediate files.
+        let sst_path = "region_dir/0000000000.parquet";
+        object_store.write(sst_path, b"sst".to_vec()).await.unwrap();
+
+        // Index creation in progress.
+        let location = IntermediateLocation::new("region_dir", &FileId::random());
+        let provider = TempFileProvider::new(location.clone(), store.clone(), None);
+        let mut writer = provider.create("tag0", "0000000010").await.unwrap();
+        writer.write_all(b"world").await.unwrap();
+        writer.close().await.unwrap();
+
+        let dirs = DatePartitionedPath
+            .intermediate_dirs(&object_store, "region_dir")
+            .await
+            .unwrap();
+        assert_eq!(2, dirs.len(), "{dirs:?}");
+        let reclaimed_total = INDEX_INTERMEDIATE_ORPHAN_RECLAIMED_TOTAL.get();
+        let reclaimed =
+            TempFileProvider::reclaim_orphans(&store, &dirs, Duration::ZERO, &SystemClock)
+                .await
+                .unwrap();
+        assert_eq!(3, reclaimed);
+        // Other tests may reclaim files concurrently.
+        assert!(INDEX_INTERMEDIATE_ORPHAN_RECLAIMED_TOTAL.get() >= reclaimed_total + 3);
+
+        for root in [orphan.root_path(), dated_orphan.root_path()] {
+            assert!(store.list(root, None).await.unwrap().is_empty());
+        }
+        assert!(object_store.is_exist(sst_path).await.unwrap());
+        assert_eq!(1, provider.read_all("tag0").await.unwrap().len());
+
+        provider.cleanup().await.unwrap();
+        let reclaimed =
+            TempFileProvider::reclaim_orphans(&store, &dirs, Duration::ZERO, &SystemClock)
+                .await
+                .unwrap();
+        assert_eq!(0, reclaimed);
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_reclaim_orphans_by_clock() {
+        let dir = create_temp_dir("");
+        let mut builder = Fs::default();
+        builder.root(dir.path().to_str().unwrap());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+        let store = InstrumentedStore::new(object_store.clone());
+
+        let orphan = IntermediateLocation::new("region_dir", &FileId::random());
+        let mut writer = store
+            .writer(
+                &orphan.file_path("tag0", "0000000010"),
+                &INDEX_INTERMEDIATE_WRITE_BYTES_TOTAL,
+                &INDEX_INTERMEDIATE_WRITE_OP_TOTAL,
+                &INDEX_INTERMEDIATE_FLUSH_OP_TOTAL,
+                None,
+            )
+            .await
+            .unwrap();
+        writer.write_all(b"hello").await.unwrap();
+        writer.close().await.unwrap();
+
+        let dirs = FlatPath
+            .intermediate_dirs(&object_store, "region_dir")
+            .await
+            .unwrap();
+        let older_than = Duration::from_secs(3600);
+        let clock = MockClock::new(SystemClock.now_millis());
+        let reclaimed = TempFileProvider::reclaim_orphans(&store, &dirs, older_than, &clock)
+            .await
+            .unwrap();
+        assert_eq!(0, reclaimed);
+
+        clock.advance(older_than);
+        let reclaimed = TempFileProvider::reclaim_orphans(&store, &dirs, older_than, &clock)
+            .await
+            .unwrap();
+        assert_eq!(1, reclaimed);
+    }
+
+    /// Writes `data` in small chunks to a new file of `column_name` and returns the
+    /// number of flushes of the file.
+    async fn write_in_chunks(provider: &TempFileProvider, column_name: &str, data: &[u8]) -> u64 {
+        let flush_count = INDEX_INTERMEDIATE_FLUSH_OP_TOTAL.get();
+        let mut writer = provider.create(column_name, "0000000010").await.unwrap();
+        for chunk in data.chunks(16) {
+            writer.write_all(chunk).await.unwrap();
+            writer.flush().await.unwrap();
+        }
+        writer.close().await.unwrap();
+        // Counters are updated on drop.
+        drop(writer);
+        INDEX_INTERMEDIATE_FLUSH_OP_TOTAL.get() - flush_count
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_provider_write_buffer() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let data: Vec<_> = (0..4096u32).map(|i| (i % 251) as u8).collect();
+
+        let tiny_provider = TempFileProvider::new(
+            IntermediateLocation::new("region_dir", &FileId::random()),
+            InstrumentedStore::new(object_store.clone()).with_write_buffer_size(Some(32)),
+            None,
+        );
+        let tiny_flushes = write_in_chunks(&tiny_provider, "tag0", &data).await;
+
+        let large_provider = TempFileProvider::new(
+            IntermediateLocation::new("region_dir", &FileId::random()),
+            InstrumentedStore::new(object_store).with_write_buffer_size(Some(1024 * 1024)),
+            None,
+        );
+        let large_flushes = write_in_chunks(&large_provider, "tag0", &data).await;
+        assert!(
+            large_flushes < tiny_flushes,
+            "large: {large_flushes}, tiny: {tiny_flushes}"
+        );
+
+        // Buffered data is written on close.
+        for provider in [&tiny_provider, &large_provider] {
+            let readers = provider.read_all("tag0").await.unwrap();
+            assert_eq!(readers.len(), 1);
+            let mut reader = readers.into_iter().next().unwrap();
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(data, buf);
+            provider.cleanup().await.unwrap();
+        }
     }
 }
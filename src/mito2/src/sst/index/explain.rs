@@ -0,0 +1,78 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Explains how a query uses indexes of SSTs.
+
+use std::fmt;
+
+use crate::sst::file::FileId;
+
+/// Explains how the index of a SST prunes row groups for a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileIndexExplain {
+    /// Id of the SST.
+    pub file_id: FileId,
+    /// Whether the SST has an inverted index.
+    pub index_available: bool,
+    /// Number of row groups in the SST.
+    pub total_row_groups: usize,
+    /// Number of row groups selected by the index, `None` if the index isn't applied
+    /// so the SST is fully scanned.
+    ///
+    /// Segments of the index are row groups, so it's also the number of segments in
+    /// the postings matched by predicates.
+    pub selected_row_groups: Option<usize>,
+    /// Number of postings read from the index, i.e. postings of values matched by
+    /// predicates.
+    pub num_postings: usize,
+}
+
+impl FileIndexExplain {
+    /// Returns true if the index prunes the whole SST.
+    pub fn is_pruned(&self) -> bool {
+        self.selected_row_groups == Some(0)
+    }
+
+    /// Returns true if the SST is scanned without the index.
+    pub fn is_full_scan(&self) -> bool {
+        self.selected_row_groups.is_none()
+    }
+}
+
+impl fmt::Display for FileIndexExplain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.selected_row_groups {
+            Some(0) => write!(
+                f,
+                "file_id: {}, index: pruned, row_groups: 0/{}, postings: {}",
+                self.file_id, self.total_row_groups, self.num_postings
+            ),
+            Some(selected) => write!(
+                f,
+                "file_id: {}, index: applied, row_groups: {}/{}, postings: {}",
+                self.file_id, selected, self.total_row_groups, self.num_postings
+            ),
+            None if self.index_available => write!(
+                f,
+                "file_id: {}, index: full scan, row_groups: {}/{}",
+                self.file_id, self.total_row_groups, self.total_row_groups
+            ),
+            None => write!(
+                f,
+                "file_id: {}, index: full scan (no index), row_groups: {}/{}",
+                self.file_id, self.total_row_groups, self.total_row_groups
+            ),
+        }
+    }
+}
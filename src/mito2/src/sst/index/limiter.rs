@@ -0,0 +1,144 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limiter of concurrent index builds in a node.
+
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::metrics::{INDEX_BUILD_ACTIVE, INDEX_BUILD_QUEUED};
+
+pub(crate) type IndexBuildLimiterRef = Arc<IndexBuildLimiter>;
+
+/// Limits the number of index builds running concurrently.
+///
+/// The limiter is shared by all regions of an engine. Builds wait for permits in
+/// the order they are queued.
+#[derive(Debug)]
+pub(crate) struct IndexBuildLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl IndexBuildLimiter {
+    /// Returns a limiter that allows `max_running` index builds to run concurrently.
+    pub(crate) fn new(max_running: usize) -> IndexBuildLimiter {
+        IndexBuildLimiter {
+            semaphore: Arc::new(Semaphore::new(max_running)),
+        }
+    }
+
+    /// Waits until a permit is granted.
+    pub(crate) async fn acquire(&self) -> IndexBuildPermit {
+        let permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let _queued = QueuedGuard::new();
+                // The semaphore is never closed.
+                self.semaphore.clone().acquire_owned().await.unwrap()
+            }
+        };
+
+        INDEX_BUILD_ACTIVE.inc();
+        IndexBuildPermit { _permit: permit }
+    }
+
+    /// Returns the number of free permits.
+    #[cfg(test)]
+    pub(crate) fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+/// Permit to run an index build, released on drop so a failed or panicked build
+/// never holds it.
+#[derive(Debug)]
+pub(crate) struct IndexBuildPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for IndexBuildPermit {
+    fn drop(&mut self) {
+        INDEX_BUILD_ACTIVE.dec();
+    }
+}
+
+/// Counts a build as queued until it is dropped, e.g. the build gets a permit or
+/// is cancelled.
+struct QueuedGuard;
+
+impl QueuedGuard {
+    fn new() -> QueuedGuard {
+        INDEX_BUILD_QUEUED.inc();
+        QueuedGuard
+    }
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        INDEX_BUILD_QUEUED.dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limit_index_builds() {
+        let limiter = Arc::new(IndexBuildLimiter::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let running = running.clone();
+                let max_running = max_running.clone();
+                tokio::spawn(async move {
+                    let _permit = limiter.acquire().await;
+                    let current = running.fetch_add(1, Ordering::Relaxed) + 1;
+                    max_running.fetch_max(current, Ordering::Relaxed);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::Relaxed);
+                })
+            })
+            .collect();
+        futures::future::join_all(handles).await;
+
+        assert_eq!(2, max_running.load(Ordering::Relaxed));
+        assert_eq!(2, limiter.available_permits());
+    }
+
+    #[tokio::test]
+    async fn test_release_permit_on_panic() {
+        let limiter = Arc::new(IndexBuildLimiter::new(1));
+
+        let handle = tokio::spawn({
+            let limiter = limiter.clone();
+            async move {
+                let _permit = limiter.acquire().await;
+                panic!("index build failed");
+            }
+        });
+        assert!(handle.await.unwrap_err().is_panic());
+
+        assert_eq!(1, limiter.available_permits());
+        let _permit = limiter.acquire().await;
+        assert_eq!(0, limiter.available_permits());
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rebuilds missing or corrupt index files from SSTs.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use common_telemetry::{info, warn};
+use object_store::ObjectStore;
+use smallvec::SmallVec;
+use store_api::metadata::RegionMetadataRef;
+use tokio::sync::mpsc::Sender;
+
+use crate::access_layer::INDEX_CREATE_MEM_THRESHOLD;
+use crate::cache::file_cache::{FileType, IndexKey};
+use crate::cache::CacheManagerRef;
+use crate::error::Result;
+use crate::read::BatchReader;
+use crate::request::{BackgroundNotify, IndexBuildFinished, OptionOutputTx, WorkerRequest};
+use crate::sst::file::{FileHandle, FileId, FileMeta, IndexType};
+use crate::sst::index::creator::SstIndexCreator;
+use crate::sst::index::limiter::IndexBuildLimiterRef;
+use crate::sst::index::store::RetryPolicy;
+use crate::sst::location;
+use crate::sst::parquet::reader::ParquetReaderBuilder;
+
+/// Context to rebuild indexes of SSTs in a region.
+#[derive(Clone)]
+pub(crate) struct IndexRebuildContext {
+    /// Sender of requests to the worker of the region, the worker commits rebuilt
+    /// indexes to the manifest.
+    pub(crate) request_sender: Sender<WorkerRequest>,
+    /// Limiter of concurrent index builds, `None` means unlimited.
+    pub(crate) limiter: Option<IndexBuildLimiterRef>,
+}
+
+/// Rebuilds the index file of a SST from rows in the SST.
+///
+/// The index is written to a new index file, then the worker of the region replaces
+/// the meta of the SST in the manifest so readers switch to the new index file. The
+/// old index file is deleted once no reader uses it.
+pub(crate) struct IndexRebuilder {
+    /// SST directory.
+    file_dir: String,
+    file_handle: FileHandle,
+    object_store: ObjectStore,
+    /// Metadata of the SST.
+    metadata: RegionMetadataRef,
+    /// Number of rows in a segment of the index, which is the row group size of the SST.
+    segment_row_count: NonZeroUsize,
+    cache_manager: Option<CacheManagerRef>,
+    context: IndexRebuildContext,
+    /// Store of intermediate files, `None` means using the object store of the SST.
+    intermediate_store: Option<ObjectStore>,
+}
+
+impl IndexRebuilder {
+    /// Creates a new [IndexRebuilder].
+    pub(crate) fn new(
+        file_dir: String,
+        file_handle: FileHandle,
+        object_store: ObjectStore,
+        metadata: RegionMetadataRef,
+        segment_row_count: NonZeroUsize,
+        cache_manager: Option<CacheManagerRef>,
+        context: IndexRebuildContext,
+    ) -> IndexRebuilder {
+        IndexRebuilder {
+            file_dir,
+            file_handle,
+            object_store,
+            metadata,
+            segment_row_count,
+            cache_manager,
+            context,
+            intermediate_store: None,
+        }
+    }
+
+    /// Writes intermediate files to the `intermediate_store` while sorting values,
+    /// the index file is still uploaded to the object store of the SST.
+    pub(crate) fn with_intermediate_store(
+        mut self,
+        intermediate_store: Option<ObjectStore>,
+    ) -> IndexRebuilder {
+        self.intermediate_store = intermediate_store;
+        self
+    }
+
+    /// Rebuilds the index in background and submits the new meta of the SST to the
+    /// worker of the region, does nothing if the index of the file is already being
+    /// rebuilt.
+    ///
+    /// The task holds the file handle so the file isn't purged while rebuilding.
+    pub(crate) fn rebuild_in_background(self) {
+        if !self.file_handle.start_rebuilding_index() {
+            return;
+        }
+
+        common_runtime::spawn_bg(async move {
+            let region_id = self.file_handle.region_id();
+            let file_id = self.file_handle.file_id();
+            match self.rebuild().await {
+                Ok(Some(file_meta)) => {
+                    info!(
+                        "Rebuilt index, region_id: {}, file_id: {}, index_file_id: {}, size: {}",
+                        region_id,
+                        file_id,
+                        file_meta.index_file_id(),
+                        file_meta.index_file_size
+                    );
+                    self.submit(file_meta).await;
+                }
+                Ok(None) => (),
+                Err(e) => warn!(
+                    e; "Failed to rebuild index, region_id: {}, file_id: {}",
+                    region_id, file_id
+                ),
+            }
+            self.file_handle.finish_rebuilding_index();
+        });
+    }
+
+    /// Rebuilds the index to a new index file and returns the meta of the SST with
+    /// the new index, or `None` if nothing is indexed.
+    ///
+    /// Waits for a permit of the limiter before building the index.
+    pub(crate) async fn rebuild(&self) -> Result<Option<FileMeta>> {
+        let index_file_id = FileId::random();
+        let creator = SstIndexCreator::new(
+            self.file_dir.clone(),
+            self.file_handle.file_id(),
+            &self.metadata,
+            self.object_store.clone(),
+            self.intermediate_store
+                .clone()
+                .unwrap_or_else(|| self.object_store.clone()),
+            Some(INDEX_CREATE_MEM_THRESHOLD.as_bytes() as usize),
+            self.segment_row_count,
+            None,
+            RetryPolicy::default(),
+            None,
+            None,
+            None,
+        )
+        .with_index_file_id(index_file_id);
+        // Only rebuilds indexes of columns in the file.
+        let indexed_columns: HashSet<_> = self
+            .file_handle
+            .meta()
+            .index_stats
+            .iter()
+            .map(|stats| stats.column_id)
+            .collect();
+        let creator = if indexed_columns.is_empty() {
+            creator
+        } else {
+            creator.with_indexed_columns(indexed_columns)
+        };
+        let mut creator = match &self.context.limiter {
+            Some(limiter) => creator.with_build_permit(limiter.acquire().await),
+            None => creator,
+        };
+        // Reads all rows without using the index.
+        let mut reader = ParquetReaderBuilder::new(
+            self.file_dir.clone(),
+            self.file_handle.clone(),
+            self.object_store.clone(),
+        )
+        .cache(self.cache_manager.clone())
+        .build()
+        .await?;
+        loop {
+            match reader.next_batch().await {
+                Ok(Some(batch)) => creator.update(&batch).await?,
+                Ok(None) => break,
+                Err(e) => {
+                    if let Err(abort_err) = creator.abort().await {
+                        warn!(
+                            abort_err; "Failed to abort index creator, region_id: {}, file_id: {}",
+                            self.file_handle.region_id(), self.file_handle.file_id()
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let (_, byte_count) = creator.finish().await?;
+        if byte_count == 0 {
+            return Ok(None);
+        }
+
+        // Removes the broken index from the local cache.
+        if let Some(write_cache) = self
+            .cache_manager
+            .as_ref()
+            .and_then(|cache| cache.write_cache())
+        {
+            write_cache
+                .file_cache()
+                .remove(IndexKey::new(
+                    self.file_handle.region_id(),
+                    self.file_handle.index_file_id(),
+                    FileType::Puffin,
+                ))
+                .await;
+        }
+
+        let mut file_meta = self.file_handle.meta();
+        file_meta.available_indexes = SmallVec::from_iter([IndexType::InvertedIndex]);
+        file_meta.index_file_id = Some(index_file_id);
+        file_meta.index_file_size = byte_count as u64;
+        file_meta.index_stats = creator.column_stats().to_vec();
+        Ok(Some(file_meta))
+    }
+
+    /// Submits the meta of the SST with the rebuilt index to the worker, removes the
+    /// new index file if the worker is stopped.
+    async fn submit(&self, file_meta: FileMeta) {
+        let region_id = file_meta.region_id;
+        let index_path = location::index_file_path(&self.file_dir, file_meta.index_file_id());
+        let request = WorkerRequest::Background {
+            region_id,
+            notify: BackgroundNotify::IndexBuildFinished(IndexBuildFinished {
+                region_id,
+                file_metas: vec![file_meta],
+                sender: OptionOutputTx::none(),
+            }),
+        };
+        if self.context.request_sender.send(request).await.is_err() {
+            warn!(
+                "Failed to submit rebuilt index, worker is stopped, region_id: {}, index_path: {}",
+                region_id, index_path
+            );
+            if let Err(e) = self.object_store.delete(&index_path).await {
+                warn!(e; "Failed to remove index file {}", index_path);
+            }
+        }
+    }
+}
@@ -12,17 +12,52 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::future::Future;
 use std::io;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
 
-use futures::{AsyncRead, AsyncSeek, AsyncWrite};
-use object_store::ObjectStore;
+use common_telemetry::warn;
+use futures::future::BoxFuture;
+use futures::{AsyncRead, AsyncSeek, AsyncSeekExt, AsyncWrite};
+use object_store::{util, Metakey, ObjectStore, Reader, Writer};
 use pin_project::pin_project;
-use prometheus::IntCounter;
+use prometheus::{IntCounter, IntCounterVec};
 use snafu::ResultExt;
+use tokio::time::Sleep;
 
-use crate::error::{OpenDalSnafu, Result};
+use crate::error::{IndexIoTimeoutSnafu, OpenDalSnafu, Result};
+use crate::metrics::{
+    INDEX_IO_LABELED_BYTES_TOTAL, INDEX_IO_LABELED_OP_TOTAL, INDEX_IO_TIMEOUT_TOTAL,
+};
+
+/// Timeout and retries of operations of an [`InstrumentedStore`].
+///
+/// Opening readers and writers, listing and removing files are retried. Reads and
+/// seeks of an opened file are retried by reopening the file at the position of the
+/// reader, see [`RetryReader`]. Writes of an opened file only time out, as a partially
+/// uploaded file can't be written again.
+///
+/// The default policy has no timeout and no retries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RetryPolicy {
+    /// Timeout of each attempt of an operation, `None` means no timeout.
+    pub(crate) timeout: Option<Duration>,
+    /// Max number of retries after the first attempt of an operation fails.
+    pub(crate) max_retries: usize,
+}
+
+impl RetryPolicy {
+    /// Returns a policy with the `timeout` of each attempt and `max_retries`,
+    /// a zero timeout means no timeout.
+    pub(crate) fn new(timeout: Duration, max_retries: usize) -> RetryPolicy {
+        RetryPolicy {
+            timeout: (!timeout.is_zero()).then_some(timeout),
+            max_retries,
+        }
+    }
+}
 
 /// A wrapper around [`ObjectStore`] that adds instrumentation for monitoring
 /// metrics such as bytes read, bytes written, and the number of seek operations.
@@ -30,64 +65,416 @@ use crate::error::{OpenDalSnafu, Result};
 pub(crate) struct InstrumentedStore {
     /// The underlying object store.
     object_store: ObjectStore,
+    /// Size of the buffer of writers, `None` means writers are not buffered.
+    write_buffer_size: Option<usize>,
+    /// Timeout and retries of operations.
+    retry_policy: RetryPolicy,
+    /// Number of entries in a page of list operations, `None` means using the
+    /// default page size of the store.
+    list_page_size: Option<usize>,
 }
 
 impl InstrumentedStore {
     /// Create a new `InstrumentedStore`.
     pub fn new(object_store: ObjectStore) -> Self {
-        Self { object_store }
+        Self {
+            object_store,
+            write_buffer_size: None,
+            retry_policy: RetryPolicy::default(),
+            list_page_size: None,
+        }
+    }
+
+    /// Buffers data written by writers of the store, see [`InstrumentedAsyncWrite`]
+    /// for how the buffer works.
+    ///
+    /// A buffer size of 0 disables the buffer.
+    pub fn with_write_buffer_size(mut self, write_buffer_size: Option<usize>) -> Self {
+        self.write_buffer_size = write_buffer_size.filter(|size| *size > 0);
+        self
+    }
+
+    /// Sets the timeout and retries of operations, see [`RetryPolicy`].
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the number of entries in a page of list operations. A larger page needs
+    /// fewer requests to list a directory with many files.
+    ///
+    /// A page size of 0 uses the default page size of the store, and the page size
+    /// is capped to the max page size of object stores.
+    pub fn with_list_page_size(mut self, list_page_size: Option<usize>) -> Self {
+        self.list_page_size = list_page_size.and_then(util::list_page_size);
+        self
     }
 
     /// Returns an [`InstrumentedAsyncRead`] for the given path.
     /// Metrics like the number of bytes read, read and seek operations
     /// are recorded using the provided `IntCounter`s.
+    ///
+    /// If `label` is set, the metrics are also recorded in series of the label,
+    /// e.g. to attribute the operations to a region or a tenant.
     pub async fn reader<'a>(
         &self,
         path: &str,
         read_byte_count: &'a IntCounter,
         read_count: &'a IntCounter,
         seek_count: &'a IntCounter,
-    ) -> Result<InstrumentedAsyncRead<'a, object_store::Reader>> {
-        let reader = self.object_store.reader(path).await.context(OpenDalSnafu)?;
+        label: Option<&str>,
+    ) -> Result<InstrumentedAsyncRead<'a, RetryReader>> {
+        let reader = self
+            .retry("read", path, || self.object_store.reader(path))
+            .await?;
         Ok(InstrumentedAsyncRead::new(
-            reader,
-            read_byte_count,
-            read_count,
-            seek_count,
+            RetryReader::new(self.object_store.clone(), path, self.retry_policy, reader),
+            CounterGuard::new(
+                read_byte_count,
+                labeled_counter(&INDEX_IO_LABELED_BYTES_TOTAL, "read", label),
+            ),
+            CounterGuard::new(
+                read_count,
+                labeled_counter(&INDEX_IO_LABELED_OP_TOTAL, "read", label),
+            ),
+            CounterGuard::new(
+                seek_count,
+                labeled_counter(&INDEX_IO_LABELED_OP_TOTAL, "seek", label),
+            ),
         ))
     }
 
     /// Returns an [`InstrumentedAsyncWrite`] for the given path.
     /// Metrics like the number of bytes written, write and flush operations
     /// are recorded using the provided `IntCounter`s.
+    ///
+    /// If `label` is set, the metrics are also recorded in series of the label.
     pub async fn writer<'a>(
         &self,
         path: &str,
         write_byte_count: &'a IntCounter,
         write_count: &'a IntCounter,
         flush_count: &'a IntCounter,
-    ) -> Result<InstrumentedAsyncWrite<'a, object_store::Writer>> {
-        let writer = self.object_store.writer(path).await.context(OpenDalSnafu)?;
+        label: Option<&str>,
+    ) -> Result<InstrumentedAsyncWrite<'a, TimeoutWrite<Writer>>> {
+        let writer = self
+            .retry("write", path, || self.object_store.writer(path))
+            .await?;
         Ok(InstrumentedAsyncWrite::new(
-            writer,
-            write_byte_count,
-            write_count,
-            flush_count,
+            TimeoutWrite::new(writer, self.retry_policy.timeout),
+            self.write_buffer_size,
+            CounterGuard::new(
+                write_byte_count,
+                labeled_counter(&INDEX_IO_LABELED_BYTES_TOTAL, "write", label),
+            ),
+            CounterGuard::new(
+                write_count,
+                labeled_counter(&INDEX_IO_LABELED_OP_TOTAL, "write", label),
+            ),
+            CounterGuard::new(
+                flush_count,
+                labeled_counter(&INDEX_IO_LABELED_OP_TOTAL, "flush", label),
+            ),
         ))
     }
 
-    /// Proxies to [`ObjectStore::list`].
-    pub async fn list(&self, path: &str) -> Result<Vec<object_store::Entry>> {
-        let list = self.object_store.list(path).await.context(OpenDalSnafu)?;
+    /// Proxies to [`ObjectStore::list_with`], listing entries page by page with the
+    /// list page size of the store.
+    ///
+    /// List operations are only counted if `label` is set.
+    pub async fn list(&self, path: &str, label: Option<&str>) -> Result<Vec<object_store::Entry>> {
+        let list = self
+            .retry("list", path, || {
+                let list = self.object_store.list_with(path);
+                match self.list_page_size {
+                    Some(page_size) => list.limit(page_size),
+                    None => list,
+                }
+            })
+            .await?;
+        if let Some(counter) = labeled_counter(&INDEX_IO_LABELED_OP_TOTAL, "list", label) {
+            counter.inc();
+        }
         Ok(list)
     }
 
+    /// Lists all files under `path` recursively with their content lengths and last
+    /// modified time.
+    pub async fn list_recursive(&self, path: &str) -> Result<Vec<object_store::Entry>> {
+        let list = self
+            .retry("list", path, || {
+                let list = self
+                    .object_store
+                    .list_with(path)
+                    .recursive(true)
+                    .metakey(Metakey::ContentLength | Metakey::LastModified);
+                match self.list_page_size {
+                    Some(page_size) => list.limit(page_size),
+                    None => list,
+                }
+            })
+            .await?;
+        Ok(list
+            .into_iter()
+            .filter(|entry| entry.metadata().is_file())
+            .collect())
+    }
+
+    /// Proxies to [`ObjectStore::delete`].
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        self.retry("delete", path, || self.object_store.delete(path))
+            .await
+    }
+
     /// Proxies to [`ObjectStore::remove_all`].
     pub async fn remove_all(&self, path: &str) -> Result<()> {
-        self.object_store
-            .remove_all(path)
+        self.retry("remove_all", path, || self.object_store.remove_all(path))
             .await
-            .context(OpenDalSnafu)
+    }
+
+    /// Runs the operation `op` on `path` with the retry policy of the store.
+    ///
+    /// Each attempt fails if it doesn't finish in the timeout, errors that are not
+    /// temporary are returned without retrying.
+    async fn retry<T, F, Fut>(&self, op: &str, path: &str, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = object_store::Result<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            let result = match self.retry_policy.timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, f()).await {
+                    Ok(result) => result.context(OpenDalSnafu),
+                    Err(_) => {
+                        INDEX_IO_TIMEOUT_TOTAL.with_label_values(&[op]).inc();
+                        IndexIoTimeoutSnafu { op, path, timeout }.fail()
+                    }
+                },
+                None => f().await.context(OpenDalSnafu),
+            };
+            match result {
+                Err(e) if retries < self.retry_policy.max_retries && e.is_index_io_retryable() => {
+                    retries += 1;
+                    warn!(
+                        e; "Retrying index IO operation {}, path: {}, retries: {}",
+                        op, path, retries
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Deadline of the pending IO operation on an opened file.
+struct Deadline {
+    /// Timeout of each operation, `None` means no timeout.
+    timeout: Option<Duration>,
+    /// Timer of the pending operation, it starts once the operation is pending.
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl Deadline {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            sleep: None,
+        }
+    }
+
+    /// Checks the `poll` of the operation `op`, returns a [`io::ErrorKind::TimedOut`]
+    /// error if the operation is still pending after the timeout.
+    fn check<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        op: &str,
+        poll: Poll<io::Result<T>>,
+    ) -> Poll<io::Result<T>> {
+        if poll.is_ready() {
+            self.sleep = None;
+            return poll;
+        }
+        let Some(timeout) = self.timeout else {
+            return Poll::Pending;
+        };
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+        ready!(sleep.as_mut().poll(cx));
+        self.sleep = None;
+        INDEX_IO_TIMEOUT_TOTAL.with_label_values(&[op]).inc();
+        Poll::Ready(Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("index IO operation {op} timed out after {timeout:?}"),
+        )))
+    }
+}
+
+/// Returns true if the IO error of an opened file is temporary, e.g. it times out
+/// or the object store throttles requests.
+fn is_io_retryable(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::TimedOut
+        || e.get_ref()
+            .and_then(|inner| inner.downcast_ref::<object_store::Error>())
+            .is_some_and(|inner| inner.is_temporary())
+}
+
+/// Reader of an opened file that applies the [`RetryPolicy`] to its reads and seeks.
+///
+/// A read or seek that times out or fails temporarily reopens the file at the current
+/// position of the reader and then runs again.
+pub(crate) struct RetryReader {
+    store: ObjectStore,
+    path: String,
+    max_retries: usize,
+    reader: Reader,
+    /// Position of the reader in the file.
+    pos: u64,
+    /// Retries of the pending operation.
+    retries: usize,
+    deadline: Deadline,
+    /// Reopens the file to retry the pending operation.
+    reopening: Option<BoxFuture<'static, io::Result<Reader>>>,
+}
+
+impl RetryReader {
+    fn new(store: ObjectStore, path: &str, policy: RetryPolicy, reader: Reader) -> Self {
+        Self {
+            store,
+            path: path.to_string(),
+            max_retries: policy.max_retries,
+            reader,
+            pos: 0,
+            retries: 0,
+            deadline: Deadline::new(policy.timeout),
+            reopening: None,
+        }
+    }
+
+    /// Reopens the file if the pending operation fails temporarily and can be retried,
+    /// otherwise returns the error.
+    fn retry_or_fail(&mut self, e: io::Error) -> io::Result<()> {
+        if self.retries >= self.max_retries || !is_io_retryable(&e) {
+            self.retries = 0;
+            return Err(e);
+        }
+
+        self.retries += 1;
+        warn!(
+            e; "Retrying index IO operation on opened file, path: {}, pos: {}, retries: {}",
+            self.path, self.pos, self.retries
+        );
+        let (store, path, pos) = (self.store.clone(), self.path.clone(), self.pos);
+        self.reopening = Some(Box::pin(async move {
+            let mut reader = store.reader(&path).await.map_err(io::Error::from)?;
+            reader.seek(io::SeekFrom::Start(pos)).await?;
+            Ok(reader)
+        }));
+        Ok(())
+    }
+
+    /// Polls reopening the file until the reader is ready.
+    fn poll_reopen(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while let Some(reopening) = &mut self.reopening {
+            let poll = reopening.as_mut().poll(cx);
+            match self.deadline.check(cx, "open", poll) {
+                Poll::Ready(Ok(reader)) => {
+                    self.reader = reader;
+                    self.reopening = None;
+                }
+                Poll::Ready(Err(e)) => {
+                    self.reopening = None;
+                    self.retry_or_fail(e)?;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for RetryReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_reopen(cx))?;
+            let poll = Pin::new(&mut this.reader).poll_read(cx, buf);
+            match ready!(this.deadline.check(cx, "read", poll)) {
+                Ok(n) => {
+                    this.pos += n as u64;
+                    this.retries = 0;
+                    return Poll::Ready(Ok(n));
+                }
+                Err(e) => this.retry_or_fail(e)?,
+            }
+        }
+    }
+}
+
+impl AsyncSeek for RetryReader {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: io::SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_reopen(cx))?;
+            let poll = Pin::new(&mut this.reader).poll_seek(cx, pos);
+            match ready!(this.deadline.check(cx, "seek", poll)) {
+                Ok(new_pos) => {
+                    this.pos = new_pos;
+                    this.retries = 0;
+                    return Poll::Ready(Ok(new_pos));
+                }
+                Err(e) => this.retry_or_fail(e)?,
+            }
+        }
+    }
+}
+
+/// Writer of an opened file that fails writes, flushes and closes that don't
+/// finish in the timeout.
+pub(crate) struct TimeoutWrite<W> {
+    inner: W,
+    deadline: Deadline,
+}
+
+impl<W> TimeoutWrite<W> {
+    fn new(inner: W, timeout: Option<Duration>) -> Self {
+        Self {
+            inner,
+            deadline: Deadline::new(timeout),
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for TimeoutWrite<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        this.deadline.check(cx, "write", poll)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_flush(cx);
+        this.deadline.check(cx, "flush", poll)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_close(cx);
+        this.deadline.check(cx, "close", poll)
     }
 }
 
@@ -105,15 +492,15 @@ impl<'a, R> InstrumentedAsyncRead<'a, R> {
     /// Create a new `InstrumentedAsyncRead`.
     fn new(
         inner: R,
-        read_byte_count: &'a IntCounter,
-        read_count: &'a IntCounter,
-        seek_count: &'a IntCounter,
+        read_byte_count: CounterGuard<'a>,
+        read_count: CounterGuard<'a>,
+        seek_count: CounterGuard<'a>,
     ) -> Self {
         Self {
             inner,
-            read_byte_count: CounterGuard::new(read_byte_count),
-            read_count: CounterGuard::new(read_count),
-            seek_count: CounterGuard::new(seek_count),
+            read_byte_count,
+            read_count,
+            seek_count,
         }
     }
 }
@@ -148,10 +535,23 @@ impl<'a, R: AsyncSeek + Unpin + Send> AsyncSeek for InstrumentedAsyncRead<'a, R>
 }
 
 /// A wrapper around [`AsyncWrite`] that adds instrumentation for monitoring
+///
+/// If the writer has a buffer, small writes are collected in the buffer, and the
+/// buffer is written and flushed to the inner writer once it's full. Flushes of
+/// the writer are deferred until the buffer is full or the writer is closed,
+/// as files written with a buffer are only read after they are closed.
 #[pin_project]
 pub(crate) struct InstrumentedAsyncWrite<'a, W> {
     #[pin]
     inner: W,
+    /// Buffer of data not written to `inner` yet.
+    buf: Vec<u8>,
+    /// Capacity of `buf`, `None` means the writer is not buffered.
+    buf_capacity: Option<usize>,
+    /// Number of bytes in `buf` already written to `inner`.
+    buf_written: usize,
+    /// Whether `inner` has to be flushed before accepting more data.
+    flush_pending: bool,
     write_byte_count: CounterGuard<'a>,
     write_count: CounterGuard<'a>,
     flush_count: CounterGuard<'a>,
@@ -161,26 +561,27 @@ impl<'a, W> InstrumentedAsyncWrite<'a, W> {
     /// Create a new `InstrumentedAsyncWrite`.
     fn new(
         inner: W,
-        write_byte_count: &'a IntCounter,
-        write_count: &'a IntCounter,
-        flush_count: &'a IntCounter,
+        buf_capacity: Option<usize>,
+        write_byte_count: CounterGuard<'a>,
+        write_count: CounterGuard<'a>,
+        flush_count: CounterGuard<'a>,
     ) -> Self {
         Self {
             inner,
-            write_byte_count: CounterGuard::new(write_byte_count),
-            write_count: CounterGuard::new(write_count),
-            flush_count: CounterGuard::new(flush_count),
+            buf: Vec::with_capacity(buf_capacity.unwrap_or(0)),
+            buf_capacity,
+            buf_written: 0,
+            flush_pending: false,
+            write_byte_count,
+            write_count,
+            flush_count,
         }
     }
 }
 
-impl<'a, W: AsyncWrite + Unpin + Send> AsyncWrite for InstrumentedAsyncWrite<'a, W> {
-    fn poll_write(
-        mut self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &[u8],
-    ) -> Poll<io::Result<usize>> {
-        let poll = self.as_mut().project().inner.poll_write(cx, buf);
+impl<'a, W: AsyncWrite + Unpin + Send> InstrumentedAsyncWrite<'a, W> {
+    fn poll_write_inner(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
         if let Poll::Ready(Ok(n)) = &poll {
             self.write_count.inc_by(1);
             self.write_byte_count.inc_by(*n);
@@ -188,29 +589,104 @@ impl<'a, W: AsyncWrite + Unpin + Send> AsyncWrite for InstrumentedAsyncWrite<'a,
         poll
     }
 
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        let poll = self.as_mut().project().inner.poll_flush(cx);
+    fn poll_flush_inner(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let poll = Pin::new(&mut self.inner).poll_flush(cx);
         if let Poll::Ready(Ok(())) = &poll {
             self.flush_count.inc_by(1);
         }
         poll
     }
 
+    /// Writes all data in the buffer to `inner` and then flushes `inner`.
+    fn poll_flush_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.buf_written < self.buf.len() {
+            let poll = Pin::new(&mut self.inner).poll_write(cx, &self.buf[self.buf_written..]);
+            let n = ready!(poll)?;
+            if n == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write the buffered data",
+                )));
+            }
+            self.write_count.inc_by(1);
+            self.write_byte_count.inc_by(n);
+            self.buf_written += n;
+            self.flush_pending = true;
+        }
+        self.buf.clear();
+        self.buf_written = 0;
+
+        if self.flush_pending {
+            ready!(self.poll_flush_inner(cx))?;
+            self.flush_pending = false;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<'a, W: AsyncWrite + Unpin + Send> AsyncWrite for InstrumentedAsyncWrite<'a, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let Some(capacity) = this.buf_capacity else {
+            return this.poll_write_inner(cx, buf);
+        };
+
+        if this.flush_pending || this.buf.len() + buf.len() > capacity {
+            ready!(this.poll_flush_buf(cx))?;
+        }
+        if buf.len() >= capacity {
+            // The data doesn't fit in the buffer, writes it directly.
+            let n = ready!(this.poll_write_inner(cx, buf))?;
+            this.flush_pending = true;
+            return Poll::Ready(Ok(n));
+        }
+        this.buf.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.buf_capacity.is_some() {
+            // Deferred until the buffer is full or the writer is closed.
+            return Poll::Ready(Ok(()));
+        }
+        this.poll_flush_inner(cx)
+    }
+
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.project().inner.poll_close(cx)
+        let this = self.get_mut();
+        if this.buf_capacity.is_some() {
+            // Makes sure all buffered data is written before closing.
+            ready!(this.poll_flush_buf(cx))?;
+        }
+        Pin::new(&mut this.inner).poll_close(cx)
     }
 }
 
-/// A guard that increments a counter when dropped.
+/// Returns the counter of the operation `op` in series of the `label`, if any.
+fn labeled_counter(vec: &IntCounterVec, op: &str, label: Option<&str>) -> Option<IntCounter> {
+    label.map(|label| vec.with_label_values(&[op, label]))
+}
+
+/// A guard that increments a counter, and the labeled counter if any, when dropped.
 struct CounterGuard<'a> {
     count: usize,
     counter: &'a IntCounter,
+    labeled: Option<IntCounter>,
 }
 
 impl<'a> CounterGuard<'a> {
     /// Create a new `CounterGuard`.
-    fn new(counter: &'a IntCounter) -> Self {
-        Self { count: 0, counter }
+    fn new(counter: &'a IntCounter, labeled: Option<IntCounter>) -> Self {
+        Self {
+            count: 0,
+            counter,
+            labeled,
+        }
     }
 
     /// Increment the counter by `n`.
@@ -223,6 +699,9 @@ impl<'a> Drop for CounterGuard<'a> {
     fn drop(&mut self) {
         if self.count > 0 {
             self.counter.inc_by(self.count as _);
+            if let Some(labeled) = &self.labeled {
+                labeled.inc_by(self.count as _);
+            }
         }
     }
 }
@@ -231,8 +710,11 @@ impl<'a> Drop for CounterGuard<'a> {
 mod tests {
     use futures::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
     use object_store::services::Memory;
+    use object_store::test_util::{DelayLayer, ListPageLayer};
+    use object_store::util::MAX_LIST_PAGE_SIZE;
 
     use super::*;
+    use crate::error::Error;
 
     #[tokio::test]
     async fn test_instrumented_store_read_write() {
@@ -247,7 +729,13 @@ mod tests {
         let flush_count = IntCounter::new("flush_count", "flush_count").unwrap();
 
         let mut writer = instrumented_store
-            .writer("my_file", &write_byte_count, &write_count, &flush_count)
+            .writer(
+                "my_file",
+                &write_byte_count,
+                &write_count,
+                &flush_count,
+                None,
+            )
             .await
             .unwrap();
         writer.write_all(b"hello").await.unwrap();
@@ -256,7 +744,7 @@ mod tests {
         drop(writer);
 
         let mut reader = instrumented_store
-            .reader("my_file", &read_byte_count, &read_count, &seek_count)
+            .reader("my_file", &read_byte_count, &read_count, &seek_count, None)
             .await
             .unwrap();
         let mut buf = vec![0; 5];
@@ -272,4 +760,245 @@ mod tests {
         assert_eq!(write_count.get(), 1);
         assert_eq!(flush_count.get(), 1);
     }
+
+    #[tokio::test]
+    async fn test_instrumented_store_labels() {
+        let instrumented_store =
+            InstrumentedStore::new(ObjectStore::new(Memory::default()).unwrap().finish());
+
+        let read_byte_count = IntCounter::new("read_byte_count", "read_byte_count").unwrap();
+        let read_count = IntCounter::new("read_count", "read_count").unwrap();
+        let seek_count = IntCounter::new("seek_count", "seek_count").unwrap();
+        let write_byte_count = IntCounter::new("write_byte_count", "write_byte_count").unwrap();
+        let write_count = IntCounter::new("write_count", "write_count").unwrap();
+        let flush_count = IntCounter::new("flush_count", "flush_count").unwrap();
+
+        let label = "test_instrumented_store_labels";
+        let labeled_bytes = |op| INDEX_IO_LABELED_BYTES_TOTAL.with_label_values(&[op, label]);
+        let labeled_ops = |op| INDEX_IO_LABELED_OP_TOTAL.with_label_values(&[op, label]);
+
+        // Labeled operations.
+        let mut writer = instrumented_store
+            .writer(
+                "labeled_file",
+                &write_byte_count,
+                &write_count,
+                &flush_count,
+                Some(label),
+            )
+            .await
+            .unwrap();
+        writer.write_all(b"hello").await.unwrap();
+        writer.flush().await.unwrap();
+        writer.close().await.unwrap();
+        drop(writer);
+        let mut reader = instrumented_store
+            .reader(
+                "labeled_file",
+                &read_byte_count,
+                &read_count,
+                &seek_count,
+                Some(label),
+            )
+            .await
+            .unwrap();
+        let mut buf = vec![0; 5];
+        reader.read_exact(&mut buf).await.unwrap();
+        reader.seek(io::SeekFrom::Start(0)).await.unwrap();
+        drop(reader);
+        instrumented_store.list("/", Some(label)).await.unwrap();
+
+        // Unlabeled operations.
+        let mut writer = instrumented_store
+            .writer(
+                "unlabeled_file",
+                &write_byte_count,
+                &write_count,
+                &flush_count,
+                None,
+            )
+            .await
+            .unwrap();
+        writer.write_all(b"world!").await.unwrap();
+        writer.close().await.unwrap();
+        drop(writer);
+        let mut reader = instrumented_store
+            .reader(
+                "unlabeled_file",
+                &read_byte_count,
+                &read_count,
+                &seek_count,
+                None,
+            )
+            .await
+            .unwrap();
+        let mut buf = vec![0; 6];
+        reader.read_exact(&mut buf).await.unwrap();
+        drop(reader);
+        instrumented_store.list("/", None).await.unwrap();
+
+        // Fixed counters record all operations.
+        assert_eq!(write_byte_count.get(), 11);
+        assert_eq!(read_byte_count.get(), 11);
+        assert_eq!(seek_count.get(), 1);
+        // Labeled series only record labeled operations.
+        assert_eq!(labeled_bytes("write").get(), 5);
+        assert_eq!(labeled_ops("write").get(), 1);
+        assert_eq!(labeled_ops("flush").get(), 1);
+        assert_eq!(labeled_bytes("read").get(), 5);
+        assert_eq!(labeled_ops("read").get(), 1);
+        assert_eq!(labeled_ops("seek").get(), 1);
+        assert_eq!(labeled_ops("list").get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_instrumented_store_retry() {
+        let delay_layer = DelayLayer::new(Duration::from_secs(5));
+        let object_store = ObjectStore::new(Memory::default())
+            .unwrap()
+            .finish()
+            .layer(delay_layer.clone());
+        let instrumented_store = InstrumentedStore::new(object_store)
+            .with_retry_policy(RetryPolicy::new(Duration::from_millis(100), 1));
+
+        let byte_count = IntCounter::new("byte_count", "byte_count").unwrap();
+        let op_count = IntCounter::new("op_count", "op_count").unwrap();
+        let other_op_count = IntCounter::new("other_op_count", "other_op_count").unwrap();
+        let timeouts = INDEX_IO_TIMEOUT_TOTAL.with_label_values(&["list"]);
+
+        // The first attempt times out and the retry succeeds.
+        delay_layer.delay_next(1);
+        let mut writer = instrumented_store
+            .writer("my_file", &byte_count, &op_count, &other_op_count, None)
+            .await
+            .unwrap();
+        writer.write_all(b"hello").await.unwrap();
+        writer.close().await.unwrap();
+        drop(writer);
+        assert_eq!(2, delay_layer.num_calls());
+
+        let timeouts_before = timeouts.get();
+        delay_layer.delay_next(1);
+        let entries = instrumented_store.list("/", None).await.unwrap();
+        assert!(entries.iter().any(|entry| entry.path() == "my_file"));
+        assert_eq!(4, delay_layer.num_calls());
+        assert_eq!(timeouts_before + 1, timeouts.get());
+
+        // Fails after all retries time out.
+        delay_layer.delay_next(2);
+        let err = instrumented_store.list("/", None).await.unwrap_err();
+        assert!(
+            matches!(err, Error::IndexIoTimeout { .. }),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(6, delay_layer.num_calls());
+        assert_eq!(timeouts_before + 3, timeouts.get());
+
+        // Errors that are not temporary are not retried.
+        let err = instrumented_store
+            .reader("not_found", &byte_count, &op_count, &other_op_count, None)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.is_object_not_found(), "unexpected error: {err:?}");
+        assert_eq!(7, delay_layer.num_calls());
+    }
+
+    /// Writer that never finishes an operation.
+    struct PendingWrite;
+
+    impl AsyncWrite for PendingWrite {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Pending
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Pending
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Pending
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_write() {
+        let timeouts = INDEX_IO_TIMEOUT_TOTAL.with_label_values(&["write"]);
+        let before = timeouts.get();
+        let mut writer = TimeoutWrite::new(PendingWrite, Some(Duration::from_millis(10)));
+        let err = writer.write_all(b"hello").await.unwrap_err();
+        assert_eq!(io::ErrorKind::TimedOut, err.kind());
+        assert!(is_io_retryable(&err));
+        assert_eq!(before + 1, timeouts.get());
+        let err = writer.close().await.unwrap_err();
+        assert_eq!(io::ErrorKind::TimedOut, err.kind());
+
+        // Operations don't time out without a timeout.
+        let mut writer = TimeoutWrite::new(PendingWrite, None);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), writer.flush())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_is_io_retryable() {
+        let temporary = object_store::Error::new(object_store::ErrorKind::Unexpected, "throttled")
+            .set_temporary();
+        assert!(is_io_retryable(&io::Error::from(temporary)));
+        let not_found = object_store::Error::new(object_store::ErrorKind::NotFound, "not found");
+        assert!(!is_io_retryable(&io::Error::from(not_found)));
+        assert!(!is_io_retryable(&io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "eof"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_instrumented_store_list_page_size() {
+        let page_layer = ListPageLayer::new(100);
+        let object_store = ObjectStore::new(Memory::default())
+            .unwrap()
+            .finish()
+            .layer(page_layer.clone());
+        for i in 0..2500 {
+            object_store
+                .write(&format!("dir/file_{i}"), b"data".to_vec())
+                .await
+                .unwrap();
+        }
+
+        // Returns the number of entries and pages to list the directory.
+        let list = |page_size| {
+            let store = InstrumentedStore::new(object_store.clone()).with_list_page_size(page_size);
+            let page_layer = page_layer.clone();
+            async move {
+                let pages = page_layer.num_pages();
+                let entries = store.list("dir/", None).await.unwrap();
+                (entries.len(), page_layer.num_pages() - pages)
+            }
+        };
+
+        // Uses the default page size of the store.
+        let (num_entries, default_pages) = list(None).await;
+        assert!(num_entries >= 2500);
+        assert_eq!(default_pages, list(Some(0)).await.1);
+        // A larger page size needs fewer pages.
+        let (entries, small_pages) = list(Some(10)).await;
+        assert_eq!(num_entries, entries);
+        let (entries, large_pages) = list(Some(500)).await;
+        assert_eq!(num_entries, entries);
+        assert!(large_pages < default_pages);
+        assert!(small_pages > default_pages);
+        // The page size is capped.
+        let (entries, max_pages) = list(Some(MAX_LIST_PAGE_SIZE)).await;
+        assert_eq!(num_entries, entries);
+        assert_eq!(max_pages, list(Some(100000)).await.1);
+        assert_eq!(3, max_pages);
+    }
 }
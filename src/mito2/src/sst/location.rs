@@ -12,11 +12,135 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use object_store::util;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use object_store::{util, ObjectStore};
+use snafu::ResultExt;
 use uuid::Uuid;
 
+use crate::error::{OpenDalSnafu, Result};
 use crate::sst::file::FileId;
 
+/// Strategy to lay out SSTs under the region directory.
+///
+/// The index file and intermediate files to create the index are put under the
+/// directory of the SST.
+#[async_trait]
+pub trait PathStrategy: Debug + Send + Sync {
+    /// Returns the directory of the SST with `sst_file_id` under `region_dir`.
+    fn sst_dir(&self, region_dir: &str, sst_file_id: FileId) -> String;
+
+    /// Returns a new id for the SST to create.
+    fn new_file_id(&self) -> FileId {
+        FileId::random()
+    }
+
+    /// Returns the path of the SST file in the object store:
+    /// `{sst_dir}/{sst_file_id}.parquet`
+    fn sst_file_path(&self, region_dir: &str, sst_file_id: FileId) -> String {
+        sst_file_path(&self.sst_dir(region_dir, sst_file_id), sst_file_id)
+    }
+
+    /// Returns the path of the index file in the object store:
+    /// `{sst_dir}/index/{index_file_id}.puffin`
+    ///
+    /// The index file is under the directory of the SST with `sst_file_id`.
+    fn index_file_path(
+        &self,
+        region_dir: &str,
+        sst_file_id: FileId,
+        index_file_id: FileId,
+    ) -> String {
+        index_file_path(&self.sst_dir(region_dir, sst_file_id), index_file_id)
+    }
+
+    /// Returns directories of intermediate files under `region_dir` in the `store`,
+    /// which are `{sst_dir}/index/__intermediate/` of all SST directories.
+    async fn intermediate_dirs(
+        &self,
+        _store: &ObjectStore,
+        region_dir: &str,
+    ) -> Result<Vec<String>> {
+        Ok(vec![intermediate_dir(region_dir)])
+    }
+}
+
+pub type PathStrategyRef = Arc<dyn PathStrategy>;
+
+/// Puts all SSTs directly under the region directory.
+#[derive(Debug, Default)]
+pub struct FlatPath;
+
+#[async_trait]
+impl PathStrategy for FlatPath {
+    fn sst_dir(&self, region_dir: &str, _sst_file_id: FileId) -> String {
+        region_dir.to_string()
+    }
+}
+
+/// Groups SSTs by the UTC date they are created: `{region_dir}/{YYYY}/{MM}/{DD}/`, so
+/// lifecycle rules of the object store can expire SSTs by the prefix.
+///
+/// SSTs whose ids don't contain their creation time are put directly under the region
+/// directory like [FlatPath].
+#[derive(Debug, Default)]
+pub struct DatePartitionedPath;
+
+#[async_trait]
+impl PathStrategy for DatePartitionedPath {
+    fn sst_dir(&self, region_dir: &str, sst_file_id: FileId) -> String {
+        let Some(datetime) = sst_file_id
+            .created_secs()
+            .and_then(|secs| NaiveDateTime::from_timestamp_opt(secs, 0))
+        else {
+            return region_dir.to_string();
+        };
+        util::join_dir(region_dir, &datetime.format("%Y/%m/%d/").to_string())
+    }
+
+    fn new_file_id(&self) -> FileId {
+        FileId::time_ordered()
+    }
+
+    /// Lists `{YYYY}/`, `{MM}/` and `{DD}/` directories level by level instead of
+    /// listing all SSTs of the region.
+    async fn intermediate_dirs(
+        &self,
+        store: &ObjectStore,
+        region_dir: &str,
+    ) -> Result<Vec<String>> {
+        let mut dirs = vec![util::normalize_dir(region_dir)];
+        for _ in 0..3 {
+            let mut children = Vec::new();
+            for dir in &dirs {
+                let entries = store.list(dir).await.context(OpenDalSnafu)?;
+                children.extend(
+                    entries
+                        .into_iter()
+                        .filter(|entry| {
+                            let name = entry.name().trim_end_matches('/');
+                            entry.metadata().is_dir()
+                                && entry.path() != dir.as_str()
+                                && !name.is_empty()
+                                && name.bytes().all(|b| b.is_ascii_digit())
+                        })
+                        .map(|entry| entry.path().to_string()),
+                );
+            }
+            dirs = children;
+        }
+
+        // SSTs whose ids don't contain their creation time are under the region directory.
+        Ok(std::iter::once(region_dir)
+            .chain(dirs.iter().map(String::as_str))
+            .map(intermediate_dir)
+            .collect())
+    }
+}
+
 /// Returns the path of the SST file in the object store:
 /// `{region_dir}/{sst_file_id}.parquet`
 pub fn sst_file_path(region_dir: &str, sst_file_id: FileId) -> String {
@@ -30,6 +154,15 @@ pub fn index_file_path(region_dir: &str, sst_file_id: FileId) -> String {
     util::join_path(&dir, &sst_file_id.as_puffin())
 }
 
+/// Directory of intermediate files under the directory of SSTs.
+pub(crate) const INTERMEDIATE_DIR: &str = "index/__intermediate/";
+
+/// Returns the directory of intermediate files under the directory of SSTs:
+/// `{sst_dir}/index/__intermediate/`
+fn intermediate_dir(sst_dir: &str) -> String {
+    util::join_dir(sst_dir, INTERMEDIATE_DIR)
+}
+
 /// `IntermediateLocation` produces paths for intermediate files
 /// during external sorting.
 #[derive(Debug, Clone)]
@@ -39,14 +172,17 @@ pub struct IntermediateLocation {
 
 impl IntermediateLocation {
     /// Create a new `IntermediateLocation`. Set the root directory to
-    /// `{region_dir}/index/__intermediate/{sst_file_id}/{uuid}/`, incorporating
+    /// `{sst_dir}/index/__intermediate/{sst_file_id}/{uuid}/`, incorporating
     /// uuid to differentiate active sorting files from orphaned data due to unexpected
     /// process termination.
-    pub fn new(region_dir: &str, sst_file_id: &FileId) -> Self {
+    ///
+    /// `sst_dir` is the directory of the SST given by the [PathStrategy] of the region,
+    /// so intermediate files are put beside the SST.
+    pub fn new(sst_dir: &str, sst_file_id: &FileId) -> Self {
         let uuid = Uuid::new_v4();
-        let child = format!("index/__intermediate/{sst_file_id}/{uuid}/");
+        let child = format!("{INTERMEDIATE_DIR}{sst_file_id}/{uuid}/");
         Self {
-            root_path: util::join_path(region_dir, &child),
+            root_path: util::join_path(sst_dir, &child),
         }
     }
 
@@ -92,6 +228,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_path_strategy() {
+        let file_id = FlatPath.new_file_id();
+        assert_eq!(None, file_id.created_secs());
+        assert_eq!("region_dir", FlatPath.sst_dir("region_dir", file_id));
+        assert_eq!(
+            format!("region_dir/{file_id}.parquet"),
+            FlatPath.sst_file_path("region_dir", file_id)
+        );
+
+        let file_id = DatePartitionedPath.new_file_id();
+        let date = NaiveDateTime::from_timestamp_opt(file_id.created_secs().unwrap(), 0)
+            .unwrap()
+            .format("%Y/%m/%d")
+            .to_string();
+        assert_eq!(
+            format!("region_dir/{date}/"),
+            DatePartitionedPath.sst_dir("region_dir", file_id)
+        );
+        assert_eq!(
+            format!("region_dir/{date}/{file_id}.parquet"),
+            DatePartitionedPath.sst_file_path("region_dir", file_id)
+        );
+        assert_eq!(
+            format!("region_dir/{date}/index/{file_id}.puffin"),
+            DatePartitionedPath.index_file_path("region_dir", file_id, file_id)
+        );
+        // Rebuilt index files are beside the SST.
+        let index_file_id = FileId::random();
+        assert_eq!(
+            format!("region_dir/{date}/index/{index_file_id}.puffin"),
+            DatePartitionedPath.index_file_path("region_dir", file_id, index_file_id)
+        );
+        // Intermediate files are beside the SST.
+        let location = IntermediateLocation::new(
+            &DatePartitionedPath.sst_dir("region_dir", file_id),
+            &file_id,
+        );
+        assert!(location.root_path().starts_with(&format!(
+            "region_dir/{date}/index/__intermediate/{file_id}/"
+        )));
+
+        // Ids without the creation time.
+        let file_id = FileId::parse_str("a5d5e1e0-a1b3-4c2c-9c86-3bdb0e2851f8").unwrap();
+        assert_eq!(
+            format!("region_dir/{file_id}.parquet"),
+            DatePartitionedPath.sst_file_path("region_dir", file_id)
+        );
+    }
+
     #[test]
     fn test_intermediate_location() {
         let sst_file_id = FileId::random();
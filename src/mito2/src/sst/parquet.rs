@@ -22,16 +22,28 @@ pub mod row_group;
 mod stats;
 pub mod writer;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_base::readable_size::ReadableSize;
+use parquet::basic::{Compression as ParquetCompression, Encoding, ZstdLevel};
 use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::WriterVersion;
+use snafu::ResultExt;
+use store_api::storage::ColumnId;
 
 use super::DEFAULT_WRITE_BUFFER_SIZE;
-use crate::sst::file::FileTimeRange;
+use crate::error::{InvalidCompressionLevelSnafu, Result};
+use crate::sst::file::{ColumnIndexStats, FileId, FileTimeRange};
 
 /// Key of metadata in parquet SST.
 pub const PARQUET_METADATA_KEY: &str = "greptime:metadata";
+/// Prefix of keys of custom column metadata in parquet SST, so custom keys never
+/// clobber keys of GreptimeDB.
+pub const COLUMN_METADATA_KEY_PREFIX: &str = "custom:";
+
+/// Custom key-value metadata of columns, keyed by column name.
+pub type ColumnKeyValues = HashMap<String, HashMap<String, String>>;
 
 /// Default batch size to read parquet files.
 pub(crate) const DEFAULT_READ_BATCH_SIZE: usize = 1024;
@@ -39,12 +51,47 @@ pub(crate) const DEFAULT_READ_BATCH_SIZE: usize = 1024;
 const DEFAULT_ROW_GROUP_SIZE: usize = 100 * DEFAULT_READ_BATCH_SIZE;
 
 /// Parquet write options.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WriteOptions {
     /// Buffer size for async writer.
     pub write_buffer_size: ReadableSize,
     /// Row group size.
     pub row_group_size: usize,
+    /// Stops writing once the encoded row groups reach the target size. The writer
+    /// leaves remaining batches in the source so another writer can consume them.
+    ///
+    /// A SST always contains all rows of a row group so it might exceed the target.
+    pub target_file_size: Option<ReadableSize>,
+    /// Stops writing once the SST has the target number of rows, like the target size.
+    ///
+    /// A SST always contains all rows of a batch so it might exceed the target.
+    pub target_num_rows: Option<usize>,
+    /// Rolls over to a new SST once the encoded row groups reach the size in bytes.
+    /// Unlike [WriteOptions::target_file_size], the writer still writes all rows of
+    /// the source, into several SSTs.
+    ///
+    /// Row groups are never split across SSTs. Only writers with a
+    /// [SstFileProvider](crate::sst::parquet::writer::SstFileProvider) roll over.
+    pub max_file_size: Option<usize>,
+    /// Version of the parquet format to write.
+    ///
+    /// `None` writes the format version 1.0 but still uses delta encodings of 2.0 for
+    /// the time index and sequence columns.
+    pub parquet_version: Option<ParquetVersion>,
+    /// Compression codec of the SST.
+    pub compression: Compression,
+    /// Encodings of columns that override the default encodings. Dictionary encodings
+    /// enable the dictionary of the column, other encodings disable it.
+    ///
+    /// Tag columns can't have encodings as they are encoded into the primary key.
+    pub column_encodings: HashMap<ColumnId, Encoding>,
+}
+
+impl WriteOptions {
+    /// Returns true if the writer might stop before the source is exhausted.
+    pub fn has_target(&self) -> bool {
+        self.target_file_size.is_some() || self.target_num_rows.is_some()
+    }
 }
 
 impl Default for WriteOptions {
@@ -52,12 +99,77 @@ impl Default for WriteOptions {
         WriteOptions {
             write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
             row_group_size: DEFAULT_ROW_GROUP_SIZE,
+            target_file_size: None,
+            target_num_rows: None,
+            max_file_size: None,
+            parquet_version: None,
+            compression: Compression::default(),
+            column_encodings: HashMap::new(),
+        }
+    }
+}
+
+/// Version of the parquet format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetVersion {
+    /// Format version 1.0. The writer only uses encodings readers of 1.0 support.
+    V1,
+    /// Format version 2.0 with data pages of version 2.
+    V2,
+}
+
+impl ParquetVersion {
+    /// Returns the [WriterVersion] of the parquet writer.
+    pub(crate) fn writer_version(&self) -> WriterVersion {
+        match self {
+            ParquetVersion::V1 => WriterVersion::PARQUET_1_0,
+            ParquetVersion::V2 => WriterVersion::PARQUET_2_0,
         }
     }
 }
 
+/// Compression codec of parquet SSTs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Zstd with a level, use [Compression::zstd()] to create it from a level.
+    Zstd(ZstdLevel),
+    Snappy,
+    Lz4,
+    /// Doesn't compress the SST.
+    None,
+}
+
+impl Compression {
+    /// Returns zstd with the `level`, which must be in `1..=22`.
+    pub fn zstd(level: i32) -> Result<Compression> {
+        let level = ZstdLevel::try_new(level).context(InvalidCompressionLevelSnafu {
+            codec: "zstd",
+            level,
+        })?;
+        Ok(Compression::Zstd(level))
+    }
+
+    /// Returns the codec of the parquet writer.
+    pub(crate) fn parquet_compression(&self) -> ParquetCompression {
+        match self {
+            Compression::Zstd(level) => ParquetCompression::ZSTD(*level),
+            Compression::Snappy => ParquetCompression::SNAPPY,
+            Compression::Lz4 => ParquetCompression::LZ4_RAW,
+            Compression::None => ParquetCompression::UNCOMPRESSED,
+        }
+    }
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::Zstd(ZstdLevel::default())
+    }
+}
+
 /// Parquet SST info returned by the writer.
 pub struct SstInfo {
+    /// Id of the SST file.
+    pub file_id: FileId,
     /// Time range of the SST.
     pub time_range: FileTimeRange,
     /// File size in bytes.
@@ -70,22 +182,39 @@ pub struct SstInfo {
     pub inverted_index_available: bool,
     /// Index file size in bytes.
     pub index_file_size: u64,
+    /// Statistics of the inverted index of each column.
+    pub index_stats: Vec<ColumnIndexStats>,
+    /// Crc32c checksum of the footer metadata.
+    pub footer_checksum: u32,
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
     use common_time::Timestamp;
+    use datatypes::arrow::array::Int64Array;
+    use datatypes::arrow::datatypes::{DataType, Field, Schema};
+    use datatypes::arrow::record_batch::RecordBatch;
+    use object_store::services::Memory;
+    use object_store::ObjectStore;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Encoding;
+    use store_api::metadata::RegionMetadata;
 
     use super::*;
     use crate::cache::{CacheManager, PageKey};
+    use crate::error::{Error, InvalidBatchSnafu, Result};
+    use crate::read::{Batch, BatchReader, Source};
+    use crate::sst::file::FileHandle;
+    use crate::sst::location;
+    use crate::sst::parquet::helper::footer_checksum;
     use crate::sst::parquet::reader::ParquetReaderBuilder;
-    use crate::sst::parquet::writer::ParquetWriter;
+    use crate::sst::parquet::writer::{ParquetWriter, SstFile, SstFileProvider};
     use crate::test_util::sst_util::{
         new_batch_by_range, new_source, sst_file_handle, sst_region_metadata,
     };
-    use crate::test_util::{check_reader_result, TestEnv};
+    use crate::test_util::{check_reader_result, new_noop_file_purger, TestEnv};
 
     const FILE_DIR: &str = "/";
 
@@ -107,12 +236,13 @@ mod tests {
             ..Default::default()
         };
 
-        let mut writer = ParquetWriter::new(file_path, metadata, object_store.clone());
+        let mut writer =
+            ParquetWriter::new(handle.file_id(), file_path, metadata, object_store.clone());
         let info = writer
             .write_all(source, &write_opts)
             .await
             .unwrap()
-            .unwrap();
+            .remove(0);
         assert_eq!(200, info.num_rows);
         assert!(info.file_size > 0);
         assert_eq!(
@@ -156,12 +286,17 @@ mod tests {
             ..Default::default()
         };
         // Prepare data.
-        let mut writer = ParquetWriter::new(file_path, metadata.clone(), object_store.clone());
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+        );
         writer
             .write_all(source, &write_opts)
             .await
             .unwrap()
-            .unwrap();
+            .remove(0);
 
         // Enable page cache.
         let cache = Some(Arc::new(
@@ -205,6 +340,193 @@ mod tests {
         assert!(cache.as_ref().unwrap().get_pages(&page_key).is_none());
     }
 
+    #[tokio::test]
+    async fn test_read_with_prefetch() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+            new_batch_by_range(&["b", "h"], 100, 200),
+        ]);
+        // Use a small row group size for test.
+        let write_opts = WriteOptions {
+            row_group_size: 50,
+            ..Default::default()
+        };
+        let mut writer =
+            ParquetWriter::new(handle.file_id(), file_path, metadata, object_store.clone());
+        writer
+            .write_all(source, &write_opts)
+            .await
+            .unwrap()
+            .remove(0);
+
+        let expected = [
+            new_batch_by_range(&["a", "d"], 0, 50),
+            new_batch_by_range(&["a", "d"], 50, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+            new_batch_by_range(&["b", "h"], 100, 150),
+            new_batch_by_range(&["b", "h"], 150, 200),
+        ];
+        let builder =
+            ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store.clone())
+                .prefetch(2);
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(&mut reader, &expected).await;
+        // All row groups except the first one are fetched in advance.
+        assert_eq!(3, reader.num_prefetched_row_groups());
+
+        // Doesn't prefetch from the local file system.
+        let mut env = TestEnv::new();
+        let fs_store = env.init_object_store_manager();
+        let data = object_store
+            .read(&handle.file_path(FILE_DIR))
+            .await
+            .unwrap();
+        fs_store
+            .write(&handle.file_path(FILE_DIR), data)
+            .await
+            .unwrap();
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, fs_store).prefetch(2);
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(&mut reader, &expected).await;
+        assert_eq!(0, reader.num_prefetched_row_groups());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_row_group_hint() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+            new_batch_by_range(&["b", "h"], 100, 200),
+        ]);
+        // Use a small row group size for test.
+        let write_opts = WriteOptions {
+            row_group_size: 50,
+            ..Default::default()
+        };
+        let mut writer =
+            ParquetWriter::new(handle.file_id(), file_path, metadata, object_store.clone());
+        writer
+            .write_all(source, &write_opts)
+            .await
+            .unwrap()
+            .remove(0);
+
+        let cache = Arc::new(
+            CacheManager::builder()
+                .row_group_hint_cache_size(16)
+                .build(),
+        );
+        let read = |query| {
+            let builder = ParquetReaderBuilder::new(
+                FILE_DIR.to_string(),
+                handle.clone(),
+                object_store.clone(),
+            )
+            .cache(Some(cache.clone()))
+            .query_fingerprint(Some(query));
+            async move {
+                let mut reader = builder.build().await.unwrap();
+                while reader.next_batch().await.unwrap().is_some() {}
+                reader.num_prefetched_row_groups()
+            }
+        };
+
+        // The first query records row groups it reads.
+        assert_eq!(0, read(1).await);
+        // Running the same query again fetches all row groups in advance.
+        assert_eq!(4, read(1).await);
+        // Another query doesn't.
+        assert_eq!(0, read(2).await);
+
+        // Hints of deleted files are removed.
+        cache.remove_row_group_hints(handle.region_id(), handle.file_id());
+        assert_eq!(0, read(1).await);
+    }
+
+    #[tokio::test]
+    async fn test_open_missing_file() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let handle = sst_file_handle(0, 1000);
+        let err = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store)
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.is_object_not_found(), "unexpected error: {err:?}");
+        let expected_path = handle.file_path(FILE_DIR);
+        assert!(
+            matches!(
+                &err,
+                Error::OpenSst { file_id, path, region_dir, scheme, .. }
+                    if *file_id == handle.file_id()
+                        && *path == expected_path
+                        && region_dir == FILE_DIR
+                        && scheme == "memory"
+            ),
+            "unexpected error: {err:?}"
+        );
+        assert!(err.to_string().contains(&expected_path));
+    }
+
+    #[tokio::test]
+    async fn test_open_with_cached_metadata() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path.clone(),
+            metadata,
+            object_store.clone(),
+        );
+        let info = writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+        let mut file_meta = handle.meta();
+        file_meta.file_size = info.file_size;
+        file_meta.footer_checksum = Some(info.footer_checksum);
+        let handle = FileHandle::new(file_meta, new_noop_file_purger());
+
+        // The cache is cold, e.g. after restart, the first open populates it.
+        let cache = Arc::new(
+            CacheManager::builder()
+                .sst_meta_cache_size(64 * 1024 * 1024)
+                .build(),
+        );
+        let builder =
+            ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store.clone())
+                .cache(Some(cache.clone()))
+                .verify_checksum(true);
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+        assert!(cache
+            .get_parquet_meta_data(handle.region_id(), handle.file_id())
+            .is_some());
+
+        // Removes the file so any footer read fails.
+        object_store.delete(&file_path).await.unwrap();
+        let builder =
+            ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store.clone());
+        assert!(builder.read_metadata().await.is_err());
+
+        // Opening the file again reads nothing from the object store.
+        let builder = builder.cache(Some(cache)).verify_checksum(true);
+        assert!(builder.read_metadata().await.unwrap().is_some());
+        assert_eq!(Some(60), builder.read_put_rows().await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_parquet_metadata_eq() {
         // create test env
@@ -225,11 +547,17 @@ mod tests {
 
         // write the sst file and get sst info
         // sst info contains the parquet metadata, which is converted from FileMetaData
-        let mut writer = ParquetWriter::new(file_path, metadata.clone(), object_store.clone());
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+        );
         let sst_info = writer
             .write_all(source, &write_opts)
             .await
             .unwrap()
+            .pop()
             .expect("write_all should return sst info");
         let writer_metadata = sst_info.file_metadata.unwrap();
 
@@ -267,4 +595,706 @@ mod tests {
             offset_index,
         );
     }
+
+    #[tokio::test]
+    async fn test_verify_checksum() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path.clone(),
+            metadata,
+            object_store.clone(),
+        );
+        let info = writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+        // The checksum computed by the writer matches the footer in the object store.
+        assert_eq!(
+            footer_checksum(&file_path, &object_store, info.file_size, None)
+                .await
+                .unwrap(),
+            info.footer_checksum
+        );
+        let new_handle = |footer_checksum| {
+            let mut file_meta = handle.meta();
+            file_meta.file_size = info.file_size;
+            file_meta.footer_checksum = footer_checksum;
+            FileHandle::new(file_meta, new_noop_file_purger())
+        };
+
+        let builder = ParquetReaderBuilder::new(
+            FILE_DIR.to_string(),
+            new_handle(Some(info.footer_checksum)),
+            object_store.clone(),
+        )
+        .verify_checksum(true);
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+
+        // The checksum in the file meta mismatches.
+        let corrupted = new_handle(Some(info.footer_checksum.wrapping_add(1)));
+        let builder = ParquetReaderBuilder::new(
+            FILE_DIR.to_string(),
+            corrupted.clone(),
+            object_store.clone(),
+        )
+        .verify_checksum(true);
+        let err = builder.build().await.err().unwrap();
+        assert!(
+            matches!(err, Error::SstCorrupted { file_id, .. } if file_id == corrupted.file_id()),
+            "unexpected error: {err:?}"
+        );
+        // Doesn't verify the checksum by default.
+        let builder =
+            ParquetReaderBuilder::new(FILE_DIR.to_string(), corrupted, object_store.clone());
+        assert!(builder.build().await.is_ok());
+
+        // Corrupts the last byte of the footer metadata in the file.
+        let mut data = object_store.read(&file_path).await.unwrap();
+        let pos = data.len() - parquet::file::FOOTER_SIZE - 1;
+        data[pos] ^= 0xff;
+        object_store.write(&file_path, data).await.unwrap();
+        let builder = ParquetReaderBuilder::new(
+            FILE_DIR.to_string(),
+            new_handle(Some(info.footer_checksum)),
+            object_store.clone(),
+        )
+        .verify_checksum(true);
+        let err = builder.build().await.err().unwrap();
+        assert!(
+            matches!(err, Error::SstCorrupted { .. }),
+            "unexpected error: {err:?}"
+        );
+
+        // Files without the checksum are not verified.
+        let builder =
+            ParquetReaderBuilder::new(FILE_DIR.to_string(), new_handle(None), object_store)
+                .verify_checksum(true);
+        assert!(!matches!(
+            builder.build().await.err(),
+            Some(Error::SstCorrupted { .. })
+        ));
+    }
+
+    /// A reader that generates batches of 1000 rows on demand, and fails after
+    /// `num_batches` batches if `fail` is true.
+    struct GeneratedBatchReader {
+        next: usize,
+        num_batches: usize,
+        fail: bool,
+    }
+
+    impl GeneratedBatchReader {
+        fn new_source(num_batches: usize, fail: bool) -> Source {
+            Source::Reader(Box::new(GeneratedBatchReader {
+                next: 0,
+                num_batches,
+                fail,
+            }))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BatchReader for GeneratedBatchReader {
+        async fn next_batch(&mut self) -> Result<Option<Batch>> {
+            if self.next == self.num_batches {
+                if self.fail {
+                    return InvalidBatchSnafu {
+                        reason: "mock error",
+                    }
+                    .fail();
+                }
+                return Ok(None);
+            }
+            let start = self.next * 1000;
+            self.next += 1;
+            Ok(Some(new_batch_by_range(&["a", "d"], start, start + 1000)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_large_source() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = GeneratedBatchReader::new_source(200, false);
+        let write_opts = WriteOptions {
+            write_buffer_size: ReadableSize::kb(4),
+            row_group_size: 10000,
+            ..Default::default()
+        };
+
+        let mut writer = ParquetWriter::new(handle.file_id(), file_path, metadata, object_store);
+        let info = writer
+            .write_all(source, &write_opts)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(200000, info.num_rows);
+        // Flushes a row group once it is full.
+        assert_eq!(20, info.file_metadata.unwrap().num_row_groups());
+        // Encoded data is written to the store once the buffer is full.
+        assert!(info.file_size > write_opts.write_buffer_size.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_write_empty_source() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path.clone(),
+            metadata,
+            object_store.clone(),
+        );
+        let info = writer
+            .write_all(new_source(&[]), &WriteOptions::default())
+            .await
+            .unwrap();
+        assert!(info.is_empty());
+        assert!(!object_store.is_exist(&file_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_write_source_error() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        // Fails after some data is written to the store.
+        let source = GeneratedBatchReader::new_source(50, true);
+        let write_opts = WriteOptions {
+            write_buffer_size: ReadableSize::kb(4),
+            row_group_size: 1000,
+            ..Default::default()
+        };
+
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path.clone(),
+            metadata,
+            object_store.clone(),
+        );
+        let err = writer.write_all(source, &write_opts).await.unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidBatch { .. }),
+            "unexpected error: {err:?}"
+        );
+        // The partial file is removed.
+        assert!(!object_store.is_exist(&file_path).await.unwrap());
+    }
+
+    /// Provides SSTs under [FILE_DIR] and records ids of provided SSTs.
+    struct TestFileProvider {
+        object_store: ObjectStore,
+        file_ids: Arc<Mutex<Vec<FileId>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SstFileProvider for TestFileProvider {
+        async fn next_file(&mut self) -> SstFile {
+            let file_id = FileId::random();
+            self.file_ids.lock().unwrap().push(file_id);
+            SstFile {
+                file_id,
+                file_path: location::sst_file_path(FILE_DIR, file_id),
+                index_creator: None,
+            }
+        }
+
+        async fn remove_file(&mut self, file_id: FileId) {
+            self.object_store
+                .delete(&location::sst_file_path(FILE_DIR, file_id))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_roll_over() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let metadata = Arc::new(sst_region_metadata());
+        let file_ids = Arc::new(Mutex::new(Vec::new()));
+        // Each batch has 1000 rows and fills 2 row groups, the writer rolls over after
+        // each batch.
+        let source = GeneratedBatchReader::new_source(3, false);
+        let write_opts = WriteOptions {
+            row_group_size: 500,
+            max_file_size: Some(1),
+            ..Default::default()
+        };
+
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            handle.file_path(FILE_DIR),
+            metadata,
+            object_store.clone(),
+        )
+        .with_file_provider(Box::new(TestFileProvider {
+            object_store: object_store.clone(),
+            file_ids: file_ids.clone(),
+        }));
+        let sst_infos = writer.write_all(source, &write_opts).await.unwrap();
+
+        assert_eq!(3, sst_infos.len());
+        let mut expect_ids = vec![handle.file_id()];
+        expect_ids.extend(file_ids.lock().unwrap().iter().copied());
+        for (i, sst_info) in sst_infos.into_iter().enumerate() {
+            assert_eq!(expect_ids[i], sst_info.file_id);
+            assert_eq!(1000, sst_info.num_rows);
+            // Each SST only covers its own rows.
+            let start = i as i64 * 1000;
+            assert_eq!(
+                (
+                    Timestamp::new_millisecond(start),
+                    Timestamp::new_millisecond(start + 999)
+                ),
+                sst_info.time_range
+            );
+            // Row groups are never split across SSTs.
+            let file_metadata = sst_info.file_metadata.unwrap();
+            assert_eq!(2, file_metadata.num_row_groups());
+            for row_group in file_metadata.row_groups() {
+                assert_eq!(500, row_group.num_rows());
+            }
+            let file_path = location::sst_file_path(FILE_DIR, sst_info.file_id);
+            assert!(object_store.is_exist(&file_path).await.unwrap());
+        }
+
+        // The writer never rolls over without a file provider.
+        let handle = sst_file_handle(0, 1000);
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            handle.file_path(FILE_DIR),
+            Arc::new(sst_region_metadata()),
+            object_store.clone(),
+        );
+        let sst_infos = writer
+            .write_all(GeneratedBatchReader::new_source(3, false), &write_opts)
+            .await
+            .unwrap();
+        assert_eq!(1, sst_infos.len());
+        assert_eq!(3000, sst_infos[0].num_rows);
+    }
+
+    #[tokio::test]
+    async fn test_write_roll_over_error() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let metadata = Arc::new(sst_region_metadata());
+        let file_ids = Arc::new(Mutex::new(Vec::new()));
+        // Fails after rolling over to the third SST.
+        let source = GeneratedBatchReader::new_source(2, true);
+        let write_opts = WriteOptions {
+            row_group_size: 500,
+            max_file_size: Some(1),
+            ..Default::default()
+        };
+
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            handle.file_path(FILE_DIR),
+            metadata,
+            object_store.clone(),
+        )
+        .with_file_provider(Box::new(TestFileProvider {
+            object_store: object_store.clone(),
+            file_ids: file_ids.clone(),
+        }));
+        let err = writer.write_all(source, &write_opts).await.unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidBatch { .. }),
+            "unexpected error: {err:?}"
+        );
+
+        // SSTs already written and the partial SST are removed.
+        let mut all_ids = vec![handle.file_id()];
+        all_ids.extend(file_ids.lock().unwrap().iter().copied());
+        assert_eq!(3, all_ids.len());
+        for file_id in all_ids {
+            let file_path = location::sst_file_path(FILE_DIR, file_id);
+            assert!(!object_store.is_exist(&file_path).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_embedded_metadata() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+        );
+        writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, object_store);
+        let embedded = builder.read_metadata().await.unwrap().unwrap();
+        assert_eq!(metadata.region_id, embedded.region_id);
+        assert_eq!(metadata.primary_key, embedded.primary_key);
+        let columns = |metadata: &RegionMetadata| {
+            metadata
+                .column_metadatas
+                .iter()
+                .map(|column| {
+                    (
+                        column.column_id,
+                        column.column_schema.data_type.clone(),
+                        column.semantic_type,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(columns(&metadata), columns(&embedded));
+    }
+
+    #[tokio::test]
+    async fn test_read_metadata_not_embedded() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+
+        // Writes a parquet file without the region metadata.
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let mut buf = Vec::new();
+        let mut arrow_writer = ArrowWriter::try_new(&mut buf, schema, None).unwrap();
+        arrow_writer.write(&batch).unwrap();
+        arrow_writer.close().unwrap();
+        object_store.write(&file_path, buf).await.unwrap();
+
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, object_store);
+        assert!(builder.read_metadata().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_write_parquet_version() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let metadata = Arc::new(sst_region_metadata());
+
+        for (version, expect_version, expect_delta) in [
+            (None, 1, true),
+            (Some(ParquetVersion::V1), 1, false),
+            (Some(ParquetVersion::V2), 2, true),
+        ] {
+            let handle = sst_file_handle(0, 1000);
+            let file_path = handle.file_path(FILE_DIR);
+            let source = new_source(&[
+                new_batch_by_range(&["a", "d"], 0, 60),
+                new_batch_by_range(&["b", "f"], 0, 40),
+            ]);
+            let write_opts = WriteOptions {
+                row_group_size: 50,
+                parquet_version: version,
+                ..Default::default()
+            };
+            let mut writer = ParquetWriter::new(
+                handle.file_id(),
+                file_path,
+                metadata.clone(),
+                object_store.clone(),
+            );
+            writer
+                .write_all(source, &write_opts)
+                .await
+                .unwrap()
+                .remove(0);
+
+            let builder =
+                ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, object_store.clone());
+            let mut reader = builder.build().await.unwrap();
+            let parquet_meta = reader.parquet_metadata();
+            assert_eq!(expect_version, parquet_meta.file_metadata().version());
+            // Delta encodings require the format version 2.0.
+            for row_group in parquet_meta.row_groups() {
+                let ts = row_group
+                    .columns()
+                    .iter()
+                    .find(|column| column.column_path().string() == "ts")
+                    .unwrap();
+                assert_eq!(
+                    expect_delta,
+                    ts.encodings().contains(&Encoding::DELTA_BINARY_PACKED),
+                    "version: {version:?}"
+                );
+            }
+            check_reader_result(
+                &mut reader,
+                &[
+                    new_batch_by_range(&["a", "d"], 0, 50),
+                    new_batch_by_range(&["a", "d"], 50, 60),
+                    new_batch_by_range(&["b", "f"], 0, 40),
+                ],
+            )
+            .await;
+        }
+    }
+
+    #[test]
+    fn test_zstd_level() {
+        assert_eq!(
+            Compression::Zstd(ZstdLevel::try_new(3).unwrap()),
+            Compression::zstd(3).unwrap()
+        );
+        Compression::zstd(1).unwrap();
+        Compression::zstd(22).unwrap();
+        assert!(Compression::zstd(0).is_err());
+        assert!(Compression::zstd(23).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write_compression() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let metadata = Arc::new(sst_region_metadata());
+
+        for (compression, expect) in [
+            (
+                Compression::default(),
+                ParquetCompression::ZSTD(ZstdLevel::default()),
+            ),
+            (
+                Compression::zstd(10).unwrap(),
+                ParquetCompression::ZSTD(ZstdLevel::try_new(10).unwrap()),
+            ),
+            (Compression::Snappy, ParquetCompression::SNAPPY),
+            (Compression::Lz4, ParquetCompression::LZ4_RAW),
+            (Compression::None, ParquetCompression::UNCOMPRESSED),
+        ] {
+            let handle = sst_file_handle(0, 1000);
+            let file_path = handle.file_path(FILE_DIR);
+            let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+            let write_opts = WriteOptions {
+                compression,
+                ..Default::default()
+            };
+            let mut writer = ParquetWriter::new(
+                handle.file_id(),
+                file_path,
+                metadata.clone(),
+                object_store.clone(),
+            );
+            writer
+                .write_all(source, &write_opts)
+                .await
+                .unwrap()
+                .remove(0);
+
+            let builder =
+                ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, object_store.clone());
+            let mut reader = builder.build().await.unwrap();
+            for row_group in reader.parquet_metadata().row_groups() {
+                for column in row_group.columns() {
+                    // The reader only returns the codec without the level.
+                    assert_eq!(
+                        std::mem::discriminant(&expect),
+                        std::mem::discriminant(&column.compression()),
+                        "compression: {compression:?}"
+                    );
+                }
+            }
+            check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_column_encodings() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let metadata = Arc::new(sst_region_metadata());
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+        // field_0 and ts.
+        let write_opts = WriteOptions {
+            column_encodings: HashMap::from([
+                (2, Encoding::DELTA_BINARY_PACKED),
+                (3, Encoding::PLAIN),
+            ]),
+            ..Default::default()
+        };
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+        );
+        writer
+            .write_all(source, &write_opts)
+            .await
+            .unwrap()
+            .remove(0);
+
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, object_store);
+        let mut reader = builder.build().await.unwrap();
+        for row_group in reader.parquet_metadata().row_groups() {
+            let encodings = |name: &str| {
+                row_group
+                    .columns()
+                    .iter()
+                    .find(|column| column.column_path().string() == name)
+                    .unwrap()
+                    .encodings()
+                    .clone()
+            };
+            assert!(encodings("field_0").contains(&Encoding::DELTA_BINARY_PACKED));
+            let ts_encodings = encodings("ts");
+            assert!(ts_encodings.contains(&Encoding::PLAIN));
+            assert!(!ts_encodings.contains(&Encoding::DELTA_BINARY_PACKED));
+        }
+        check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+    }
+
+    #[tokio::test]
+    async fn test_write_invalid_column_encodings() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let metadata = Arc::new(sst_region_metadata());
+
+        // Unknown column, tag column and encodings of parquet 2.0 in parquet 1.0.
+        for (column_id, encoding, parquet_version) in [
+            (100, Encoding::PLAIN, None),
+            (0, Encoding::PLAIN, None),
+            (2, Encoding::DELTA_BINARY_PACKED, Some(ParquetVersion::V1)),
+        ] {
+            let handle = sst_file_handle(0, 1000);
+            let file_path = handle.file_path(FILE_DIR);
+            let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+            let write_opts = WriteOptions {
+                column_encodings: HashMap::from([(column_id, encoding)]),
+                parquet_version,
+                ..Default::default()
+            };
+            let mut writer = ParquetWriter::new(
+                handle.file_id(),
+                file_path.clone(),
+                metadata.clone(),
+                object_store.clone(),
+            );
+            let err = writer.write_all(source, &write_opts).await.unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidColumnEncoding { .. }),
+                "unexpected err: {err}"
+            );
+            assert!(!object_store.is_exist(&file_path).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_read_column_metadata() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+
+        let column_metadata = ColumnKeyValues::from([
+            (
+                "field_0".to_string(),
+                HashMap::from([
+                    ("source".to_string(), "kafka".to_string()),
+                    (
+                        "ingest_time".to_string(),
+                        "2024-01-01T00:00:00Z".to_string(),
+                    ),
+                ]),
+            ),
+            (
+                "ts".to_string(),
+                // Custom keys don't clobber keys of the time index.
+                HashMap::from([("greptime:time_index".to_string(), "false".to_string())]),
+            ),
+        ]);
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+        )
+        .with_column_metadata(column_metadata.clone());
+        writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap()
+            .remove(0);
+
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle, object_store);
+        assert_eq!(
+            column_metadata,
+            builder.read_column_metadata().await.unwrap()
+        );
+        let embedded = builder.read_metadata().await.unwrap().unwrap();
+        assert_eq!(
+            metadata.time_index_column().column_id,
+            embedded.time_index_column().column_id
+        );
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(&mut reader, &[new_batch_by_range(&["a", "d"], 0, 60)]).await;
+    }
+
+    #[tokio::test]
+    async fn test_write_tag_column_metadata() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[new_batch_by_range(&["a", "d"], 0, 60)]);
+
+        // Tags are stored in the primary key so they can't have metadata.
+        let column_metadata = ColumnKeyValues::from([(
+            "tag_0".to_string(),
+            HashMap::from([("source".to_string(), "kafka".to_string())]),
+        )]);
+        let mut writer = ParquetWriter::new(
+            handle.file_id(),
+            file_path.clone(),
+            metadata,
+            object_store.clone(),
+        )
+        .with_column_metadata(column_metadata);
+        let err = writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidColumnMetadata { ref column, .. } if column == "tag_0"),
+            "{err:?}"
+        );
+        assert!(!object_store.is_exist(&file_path).await.unwrap());
+    }
 }
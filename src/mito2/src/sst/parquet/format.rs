@@ -49,10 +49,12 @@ use store_api::storage::consts::{
 use store_api::storage::ColumnId;
 
 use crate::error::{
-    ConvertVectorSnafu, InvalidBatchSnafu, InvalidRecordBatchSnafu, NewRecordBatchSnafu, Result,
+    ConvertVectorSnafu, InvalidBatchSnafu, InvalidColumnMetadataSnafu, InvalidRecordBatchSnafu,
+    NewRecordBatchSnafu, Result,
 };
 use crate::read::{Batch, BatchBuilder, BatchColumn};
 use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
+use crate::sst::parquet::{ColumnKeyValues, COLUMN_METADATA_KEY_PREFIX};
 
 /// Number of columns that have fixed positions.
 ///
@@ -76,6 +78,49 @@ impl WriteFormat {
         }
     }
 
+    /// Adds custom key-value metadata to columns in the arrow schema, keys are stored
+    /// with the [COLUMN_METADATA_KEY_PREFIX].
+    ///
+    /// Returns error if a column isn't stored as a parquet column, e.g. tags are
+    /// stored in the primary key.
+    pub(crate) fn with_column_metadata(
+        mut self,
+        column_metadata: &ColumnKeyValues,
+    ) -> Result<WriteFormat> {
+        if column_metadata.is_empty() {
+            return Ok(self);
+        }
+
+        for column in column_metadata.keys() {
+            ensure!(
+                self.metadata
+                    .column_by_name(column)
+                    .is_some_and(|column| column.semantic_type != SemanticType::Tag),
+                InvalidColumnMetadataSnafu {
+                    column,
+                    reason: "only fields and the time index can have metadata",
+                }
+            );
+        }
+        let fields =
+            Fields::from_iter(self.arrow_schema.fields().iter().map(|field| {
+                let Some(key_values) = column_metadata.get(field.name()) else {
+                    return field.clone();
+                };
+                let mut metadata = field.metadata().clone();
+                metadata.extend(key_values.iter().map(|(key, value)| {
+                    (format!("{COLUMN_METADATA_KEY_PREFIX}{key}"), value.clone())
+                }));
+                Arc::new(field.as_ref().clone().with_metadata(metadata))
+            }));
+        self.arrow_schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            self.arrow_schema.metadata().clone(),
+        ));
+
+        Ok(self)
+    }
+
     /// Gets the arrow schema to store in parquet.
     pub(crate) fn arrow_schema(&self) -> SchemaRef {
         self.arrow_schema.clone()
@@ -18,13 +18,18 @@ use std::sync::Arc;
 use bytes::Bytes;
 use object_store::{ErrorKind, ObjectStore};
 use parquet::basic::ColumnOrder;
+use parquet::file::footer::decode_footer;
 use parquet::file::metadata::{FileMetaData, ParquetMetaData, RowGroupMetaData};
+use parquet::file::FOOTER_SIZE;
 use parquet::format;
 use parquet::schema::types::{from_thrift, SchemaDescriptor};
-use snafu::ResultExt;
+use parquet::thrift::TSerializable;
+use snafu::{ensure, OptionExt, ResultExt};
+use thrift::protocol::TCompactOutputProtocol;
 
 use crate::error;
 use crate::error::Result;
+use crate::sst::retry::{maybe_retry, RetryConfig, READ_FOOTER_OPERATION};
 
 // Refer to https://github.com/apache/arrow-rs/blob/7e134f4d277c0b62c27529fc15a4739de3ad0afd/parquet/src/file/footer.rs#L74-L90
 /// Convert [format::FileMetaData] to [ParquetMetaData]
@@ -88,6 +93,79 @@ fn parse_column_orders(
     }
 }
 
+/// Reads the footer metadata of the parquet file of `file_size` bytes and
+/// returns the crc32c checksum of the encoded metadata.
+///
+/// Reads are retried by the `retry_config` if it isn't `None`.
+pub(crate) async fn footer_checksum(
+    file_path: &str,
+    object_store: &ObjectStore,
+    file_size: u64,
+    retry_config: Option<&RetryConfig>,
+) -> Result<u32> {
+    let footer_size = FOOTER_SIZE as u64;
+    ensure!(
+        file_size >= footer_size,
+        error::InvalidParquetSnafu {
+            file: file_path,
+            reason: format!("file size {} is smaller than the footer", file_size),
+        }
+    );
+    let footer = maybe_retry(retry_config, READ_FOOTER_OPERATION, file_path, || async {
+        object_store
+            .read_with(file_path)
+            .range(file_size - footer_size..file_size)
+            .await
+    })
+    .await
+    .context(error::OpenDalSnafu)?;
+    let footer: [u8; FOOTER_SIZE] = footer.try_into().ok().context(error::InvalidParquetSnafu {
+        file: file_path,
+        reason: "footer is truncated",
+    })?;
+    let metadata_len =
+        decode_footer(&footer).context(error::ReadParquetSnafu { path: file_path })? as u64;
+    ensure!(
+        file_size >= footer_size + metadata_len,
+        error::InvalidParquetSnafu {
+            file: file_path,
+            reason: format!(
+                "metadata length {} exceeds file size {}",
+                metadata_len, file_size
+            ),
+        }
+    );
+
+    let metadata_start = file_size - footer_size - metadata_len;
+    let metadata = maybe_retry(retry_config, READ_FOOTER_OPERATION, file_path, || async {
+        object_store
+            .read_with(file_path)
+            .range(metadata_start..file_size - footer_size)
+            .await
+    })
+    .await
+    .context(error::OpenDalSnafu)?;
+    Ok(crc32c::crc32c(&metadata))
+}
+
+/// Encodes the footer `metadata` of the parquet file the same way as the parquet
+/// writer and returns the crc32c checksum of the encoded metadata.
+///
+/// The checksum equals the one [footer_checksum()] reads from the file written with
+/// the `metadata`, without reading the file back.
+pub(crate) fn metadata_checksum(file_path: &str, metadata: &format::FileMetaData) -> Result<u32> {
+    let mut encoded = Vec::new();
+    let mut protocol = TCompactOutputProtocol::new(&mut encoded);
+    metadata.write_to_out_protocol(&mut protocol).map_err(|e| {
+        error::InvalidParquetSnafu {
+            file: file_path,
+            reason: format!("failed to encode metadata, {}", e),
+        }
+        .build()
+    })?;
+    Ok(crc32c::crc32c(&encoded))
+}
+
 /// Fetches data from object store.
 /// If the object store supports blocking, use sequence blocking read.
 /// Otherwise, use concurrent read.
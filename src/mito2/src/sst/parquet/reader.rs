@@ -14,39 +14,60 @@
 
 //! Parquet reader.
 
-use std::collections::{BTreeSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use api::v1::OpType;
 use async_trait::async_trait;
+use common_base::readable_size::ReadableSize;
 use common_telemetry::{debug, warn};
 use common_time::range::TimestampRange;
+use datatypes::arrow::array::{Array, ArrayRef, UInt64Array};
+use datatypes::arrow::compute;
 use datatypes::arrow::record_batch::RecordBatch;
-use object_store::ObjectStore;
+use datatypes::value::Value;
+use datatypes::vectors::Helper;
+use object_store::{ObjectStore, Scheme};
 use parquet::arrow::arrow_reader::ParquetRecordBatchReader;
 use parquet::arrow::async_reader::AsyncFileReader;
-use parquet::arrow::{parquet_to_arrow_field_levels, FieldLevels, ProjectionMask};
+use parquet::arrow::{
+    parquet_to_arrow_field_levels, parquet_to_arrow_schema, FieldLevels, ProjectionMask,
+};
 use parquet::file::metadata::ParquetMetaData;
+use parquet::file::statistics::Statistics;
 use parquet::format::KeyValue;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::metadata::{RegionMetadata, RegionMetadataRef};
-use store_api::storage::ColumnId;
+use store_api::storage::{ColumnId, TableSample};
 use table::predicate::Predicate;
 use tokio::io::BufReader;
+use tokio::task::JoinHandle;
 
-use crate::cache::CacheManagerRef;
+use crate::cache::reader_cache::SstReaderRef;
+use crate::cache::{CacheManagerRef, RowGroupHintKey};
 use crate::error::{
-    ArrowReaderSnafu, InvalidMetadataSnafu, InvalidParquetSnafu, OpenDalSnafu, ReadParquetSnafu,
-    Result,
+    ArrowReaderSnafu, InvalidMetadataSnafu, InvalidParquetSnafu, OpenSstSnafu, ReadParquetSnafu,
+    Result, SstCorruptedSnafu,
 };
 use crate::metrics::{READ_ROWS_TOTAL, READ_ROW_GROUPS_TOTAL, READ_STAGE_ELAPSED};
-use crate::read::{Batch, BatchReader};
+use crate::read::sample::Sampler;
+use crate::read::{Batch, BatchReader, FetchedBytesRef};
 use crate::sst::file::FileHandle;
 use crate::sst::index::applier::SstIndexApplierRef;
+use crate::sst::index::rebuilder::{IndexRebuildContext, IndexRebuilder};
 use crate::sst::parquet::format::ReadFormat;
-use crate::sst::parquet::row_group::InMemoryRowGroup;
+use crate::sst::parquet::helper::footer_checksum;
+use crate::sst::parquet::row_group::{fetch_ranges, FetchedRanges, InMemoryRowGroup};
 use crate::sst::parquet::stats::RowGroupPruningStats;
-use crate::sst::parquet::{DEFAULT_READ_BATCH_SIZE, PARQUET_METADATA_KEY};
+use crate::sst::parquet::{
+    ColumnKeyValues, COLUMN_METADATA_KEY_PREFIX, DEFAULT_READ_BATCH_SIZE, PARQUET_METADATA_KEY,
+};
+use crate::sst::retry::{maybe_retry, RetryConfig, OPEN_OPERATION};
+
+/// Max bytes of row groups to fetch in advance for each reader.
+const MAX_PREFETCH_BYTES: u64 = ReadableSize::mb(64).as_bytes();
 
 /// Parquet SST reader builder.
 pub(crate) struct ParquetReaderBuilder {
@@ -67,6 +88,28 @@ pub(crate) struct ParquetReaderBuilder {
     cache_manager: Option<CacheManagerRef>,
     /// Index applier.
     index_applier: Option<SstIndexApplierRef>,
+    /// Row groups selected by the index in advance, the reader applies the index by
+    /// the index applier if it is `None`.
+    index_row_groups: Option<BTreeSet<usize>>,
+    /// Collector of bytes fetched from the object store.
+    fetched_bytes: Option<FetchedBytesRef>,
+    /// Samples row groups to read.
+    sample: Option<TableSample>,
+    /// Whether to verify the checksum of the footer metadata before reading.
+    verify_checksum: bool,
+    /// Number of row groups to fetch in advance.
+    prefetch: usize,
+    /// Fingerprint of the query to read the SST.
+    query_fingerprint: Option<u64>,
+    /// Store of intermediate files to rebuild the index of the SST, `None` means
+    /// using the object store of the SST.
+    intermediate_store: Option<ObjectStore>,
+    /// Context to rebuild the missing or corrupt index of the SST, the reader doesn't
+    /// rebuild the index if it is `None`.
+    index_rebuild: Option<IndexRebuildContext>,
+    /// Backoff to retry opening the file and reads from the object store, `None`
+    /// means no retry.
+    retry_config: Option<RetryConfig>,
 }
 
 impl ParquetReaderBuilder {
@@ -85,6 +128,15 @@ impl ParquetReaderBuilder {
             projection: None,
             cache_manager: None,
             index_applier: None,
+            index_row_groups: None,
+            fetched_bytes: None,
+            sample: None,
+            verify_checksum: false,
+            prefetch: 0,
+            query_fingerprint: None,
+            intermediate_store: None,
+            index_rebuild: None,
+            retry_config: None,
         }
     }
 
@@ -121,6 +173,78 @@ impl ParquetReaderBuilder {
         self
     }
 
+    /// Attaches row groups selected by the index of the file, so the reader doesn't
+    /// apply the index again.
+    #[must_use]
+    pub fn index_row_groups(mut self, row_groups: Option<BTreeSet<usize>>) -> Self {
+        self.index_row_groups = row_groups;
+        self
+    }
+
+    /// Attaches the collector of bytes fetched from the object store.
+    #[must_use]
+    pub fn fetched_bytes(mut self, fetched_bytes: Option<FetchedBytesRef>) -> Self {
+        self.fetched_bytes = fetched_bytes;
+        self
+    }
+
+    /// Attaches the sample to the builder, the reader only reads sampled row groups.
+    #[must_use]
+    pub fn sample(mut self, sample: Option<TableSample>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    /// Sets whether to verify the checksum of the footer metadata against the
+    /// checksum in the file meta, files written without the checksum are not verified.
+    #[must_use]
+    pub fn verify_checksum(mut self, verify_checksum: bool) -> Self {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Sets the number of row groups to fetch in background while reading the current
+    /// row group. The reader doesn't fetch row groups in advance from the local file
+    /// system or if `depth` is 0.
+    ///
+    /// Row groups to fetch in advance are bounded by [MAX_PREFETCH_BYTES].
+    #[must_use]
+    pub fn prefetch(mut self, depth: usize) -> Self {
+        self.prefetch = depth;
+        self
+    }
+
+    /// Attaches the fingerprint of the query to read the SST. The reader records row
+    /// groups read by the query in the cache, and fetches these row groups in advance
+    /// when the same query reads the SST again, unless the SST is on the local file system.
+    #[must_use]
+    pub fn query_fingerprint(mut self, query_fingerprint: Option<u64>) -> Self {
+        self.query_fingerprint = query_fingerprint;
+        self
+    }
+
+    /// Sets the store of intermediate files to rebuild the missing or corrupt index
+    /// of the SST, e.g. a local store so sorting values doesn't access the remote store.
+    #[must_use]
+    pub fn intermediate_store(mut self, intermediate_store: Option<ObjectStore>) -> Self {
+        self.intermediate_store = intermediate_store;
+        self
+    }
+
+    /// Attaches the context to rebuild the missing or corrupt index of the SST.
+    #[must_use]
+    pub(crate) fn index_rebuild(mut self, index_rebuild: Option<IndexRebuildContext>) -> Self {
+        self.index_rebuild = index_rebuild;
+        self
+    }
+
+    /// Retries opening the file, reading the footer and reading row groups from the
+    /// object store by the `retry_config`. Reads of an opened file are not retried.
+    pub fn retry_config(mut self, retry_config: Option<RetryConfig>) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
     /// Builds and initializes a [ParquetReader].
     ///
     /// This needs to perform IO operation.
@@ -128,15 +252,13 @@ impl ParquetReaderBuilder {
         let start = Instant::now();
 
         let file_path = self.file_handle.file_path(&self.file_dir);
-        // Now we create a reader to read the whole file.
-        let reader = self
-            .object_store
-            .reader(&file_path)
-            .await
-            .context(OpenDalSnafu)?;
-        let mut reader = BufReader::new(reader);
+        // The cached metadata is verified when it's loaded or written by this node, so
+        // it doesn't need to read the footer again.
+        if self.verify_checksum && self.cached_parquet_metadata().is_none() {
+            self.check_footer_checksum(&file_path).await?;
+        }
         // Loads parquet metadata of the file.
-        let parquet_meta = self.read_parquet_metadata(&mut reader, &file_path).await?;
+        let parquet_meta = self.open_parquet_metadata(&file_path).await?;
         // Decodes region metadata.
         let key_value_meta = parquet_meta.file_metadata().key_value_metadata();
         let region_meta = Self::get_region_metadata(&file_path, key_value_meta)?;
@@ -165,7 +287,9 @@ impl ParquetReaderBuilder {
             .row_groups_to_read(&read_format, &parquet_meta, &mut metrics)
             .await;
 
-        let reader_builder = RowGroupReaderBuilder {
+        // Reading local files is fast enough.
+        let local = self.object_store.info().scheme() == Scheme::Fs;
+        let mut reader_builder = RowGroupReaderBuilder {
             file_handle: self.file_handle.clone(),
             file_path,
             parquet_meta,
@@ -173,7 +297,30 @@ impl ParquetReaderBuilder {
             projection: projection_mask,
             field_levels,
             cache_manager: self.cache_manager.clone(),
+            fetched_bytes: self.fetched_bytes.clone(),
+            retry_config: self.retry_config,
+            prefetch: if local { 0 } else { self.prefetch },
+            prefetching: VecDeque::new(),
+            num_prefetched: 0,
         };
+        let hint_key = self.query_fingerprint.map(|query| RowGroupHintKey {
+            region_id: self.file_handle.region_id(),
+            file_id: self.file_handle.file_id(),
+            query,
+        });
+        // Fetches row groups read by the same query before in advance.
+        if let Some(hint) = hint_key
+            .as_ref()
+            .filter(|_| !local)
+            .zip(self.cache_manager.as_ref())
+            .and_then(|(key, cache)| cache.get_row_group_hint(key))
+        {
+            reader_builder.prefetch_row_groups(
+                hint.iter()
+                    .copied()
+                    .filter(|row_group_idx| row_groups.contains(row_group_idx)),
+            );
+        }
 
         let metrics = Metrics {
             build_cost: start.elapsed(),
@@ -187,9 +334,153 @@ impl ParquetReaderBuilder {
             current_reader: None,
             batches: VecDeque::new(),
             metrics,
+            hint_key,
+            read_row_groups: Vec::new(),
         })
     }
 
+    /// Reads the region metadata embedded in the SST when it was written, without
+    /// the manifest.
+    ///
+    /// Returns `None` if the SST doesn't embed the region metadata, e.g. files not
+    /// written by the region engine.
+    pub async fn read_metadata(&self) -> Result<Option<RegionMetadataRef>> {
+        let file_path = self.file_handle.file_path(&self.file_dir);
+        let parquet_meta = self.open_parquet_metadata(&file_path).await?;
+        let key_value_meta = parquet_meta.file_metadata().key_value_metadata();
+        let embedded = key_value_meta.is_some_and(|key_values| {
+            key_values
+                .iter()
+                .any(|kv| kv.key == PARQUET_METADATA_KEY && kv.value.is_some())
+        });
+        if !embedded {
+            return Ok(None);
+        }
+
+        let region_meta = Self::get_region_metadata(&file_path, key_value_meta)?;
+        Ok(Some(Arc::new(region_meta)))
+    }
+
+    /// Reads custom key-value metadata of columns written to the schema of the SST,
+    /// columns without custom metadata are absent.
+    pub async fn read_column_metadata(&self) -> Result<ColumnKeyValues> {
+        let file_path = self.file_handle.file_path(&self.file_dir);
+        let parquet_meta = self.open_parquet_metadata(&file_path).await?;
+        let file_meta = parquet_meta.file_metadata();
+        let arrow_schema =
+            parquet_to_arrow_schema(file_meta.schema_descr(), file_meta.key_value_metadata())
+                .context(ReadParquetSnafu { path: &file_path })?;
+
+        let column_metadata = arrow_schema
+            .fields()
+            .iter()
+            .filter_map(|field| {
+                let key_values: HashMap<_, _> = field
+                    .metadata()
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        key.strip_prefix(COLUMN_METADATA_KEY_PREFIX)
+                            .map(|key| (key.to_string(), value.clone()))
+                    })
+                    .collect();
+                (!key_values.is_empty()).then(|| (field.name().clone(), key_values))
+            })
+            .collect();
+        Ok(column_metadata)
+    }
+
+    /// Returns the number of rows in the file from its parquet metadata, without reading
+    /// row groups.
+    ///
+    /// Returns `None` if the file may have deleted rows, as the statistics of the op type
+    /// column can't tell how many rows they delete.
+    pub(crate) async fn read_put_rows(&self) -> Result<Option<u64>> {
+        let file_path = self.file_handle.file_path(&self.file_dir);
+        let parquet_meta = self.open_parquet_metadata(&file_path).await?;
+
+        if !is_put_only(&parquet_meta) {
+            return Ok(None);
+        }
+        let num_rows = parquet_meta
+            .row_groups()
+            .iter()
+            .map(|row_group| row_group.num_rows() as u64)
+            .sum();
+        Ok(Some(num_rows))
+    }
+
+    /// Returns the min and max values of the column in the file from statistics in its
+    /// parquet metadata, without reading row groups. Both values are null if all values
+    /// of the column are null.
+    ///
+    /// Returns `None` if statistics can't tell the min and max values, e.g. the file
+    /// may have deleted rows, statistics of the column are absent or the file doesn't
+    /// have the column.
+    pub(crate) async fn read_min_max(&self, column_id: ColumnId) -> Result<Option<(Value, Value)>> {
+        let file_path = self.file_handle.file_path(&self.file_dir);
+        let parquet_meta = self.open_parquet_metadata(&file_path).await?;
+        if !is_put_only(&parquet_meta) {
+            return Ok(None);
+        }
+        let key_value_meta = parquet_meta.file_metadata().key_value_metadata();
+        let region_meta = Self::get_region_metadata(&file_path, key_value_meta)?;
+        let Some(column) = region_meta.column_by_id(column_id) else {
+            return Ok(None);
+        };
+        let data_type = column.column_schema.data_type.clone();
+        let read_format = ReadFormat::new(Arc::new(region_meta));
+
+        let row_groups = parquet_meta.row_groups();
+        let stats = (
+            read_format.min_values(row_groups, column_id),
+            read_format.max_values(row_groups, column_id),
+            read_format.null_counts(row_groups, column_id),
+        );
+        let (Some(min_values), Some(max_values), Some(null_counts)) = stats else {
+            return Ok(None);
+        };
+        // Statistics are in physical types of parquet, e.g. int64 for timestamps.
+        let to_vector = |array: ArrayRef| {
+            compute::cast(&array, &data_type.as_arrow_type())
+                .ok()
+                .and_then(|array| Helper::try_into_vector(array).ok())
+        };
+        let (Some(min_values), Some(max_values)) = (to_vector(min_values), to_vector(max_values))
+        else {
+            return Ok(None);
+        };
+        let Some(null_counts) = null_counts.as_any().downcast_ref::<UInt64Array>() else {
+            return Ok(None);
+        };
+
+        let (mut min, mut max) = (Value::Null, Value::Null);
+        for (i, row_group) in row_groups.iter().enumerate() {
+            let (row_group_min, row_group_max) = (min_values.get(i), max_values.get(i));
+            if row_group_min.is_null() || row_group_max.is_null() {
+                // Row groups whose values are all null have no min and max values.
+                if null_counts.is_valid(i) && null_counts.value(i) == row_group.num_rows() as u64 {
+                    continue;
+                }
+                return Ok(None);
+            }
+            if min.is_null() || row_group_min < min {
+                min = row_group_min;
+            }
+            if max.is_null() || row_group_max > max {
+                max = row_group_max;
+            }
+        }
+        Ok(Some((min, max)))
+    }
+
+    /// Returns the number of row groups in the file from its parquet metadata, without
+    /// reading them.
+    pub(crate) async fn read_num_row_groups(&self) -> Result<usize> {
+        let file_path = self.file_handle.file_path(&self.file_dir);
+        let parquet_meta = self.open_parquet_metadata(&file_path).await?;
+        Ok(parquet_meta.num_row_groups())
+    }
+
     /// Decodes region metadata from key value.
     fn get_region_metadata(
         file_path: &str,
@@ -217,20 +508,89 @@ impl ParquetReaderBuilder {
         RegionMetadata::from_json(json).context(InvalidMetadataSnafu)
     }
 
-    /// Reads parquet metadata of specific file.
+    /// Returns [SstCorrupted](crate::error::Error::SstCorrupted) if the checksum of the
+    /// footer metadata in the file mismatches the checksum in the file meta.
+    async fn check_footer_checksum(&self, file_path: &str) -> Result<()> {
+        let Some(expected) = self.file_handle.footer_checksum() else {
+            return Ok(());
+        };
+        let actual = footer_checksum(
+            file_path,
+            &self.object_store,
+            self.file_handle.size(),
+            self.retry_config.as_ref(),
+        )
+        .await?;
+        ensure!(
+            expected == actual,
+            SstCorruptedSnafu {
+                file_id: self.file_handle.file_id(),
+                expected,
+                actual,
+            }
+        );
+        Ok(())
+    }
+
+    /// Opens the file and reads its parquet metadata, the file isn't opened if the
+    /// metadata is in the cache.
+    async fn open_parquet_metadata(&self, file_path: &str) -> Result<Arc<ParquetMetaData>> {
+        if let Some(metadata) = self.cached_parquet_metadata() {
+            return Ok(metadata);
+        }
+
+        let reader = self.open_reader(file_path).await?;
+        let mut reader = reader.lock().await;
+        let mut reader = BufReader::new(&mut *reader);
+        self.read_parquet_metadata(&mut reader, file_path).await
+    }
+
+    /// Opens a reader to read the whole file, reusing the open reader in the cache
+    /// if possible.
+    async fn open_reader(&self, file_path: &str) -> Result<SstReaderRef> {
+        let region_id = self.file_handle.region_id();
+        let file_id = self.file_handle.file_id();
+        if let Some(reader) = self
+            .cache_manager
+            .as_ref()
+            .and_then(|cache| cache.get_sst_reader(region_id, file_id))
+        {
+            return Ok(reader);
+        }
+
+        let reader = maybe_retry(
+            self.retry_config.as_ref(),
+            OPEN_OPERATION,
+            file_path,
+            || self.object_store.reader(file_path),
+        )
+        .await
+        .context(OpenSstSnafu {
+            file_id,
+            path: file_path,
+            region_dir: &self.file_dir,
+            scheme: self.object_store.info().scheme().to_string(),
+        })?;
+        let reader = Arc::new(tokio::sync::Mutex::new(reader));
+        match &self.cache_manager {
+            Some(cache) => Ok(cache.put_sst_reader(region_id, file_id, reader)),
+            None => Ok(reader),
+        }
+    }
+
+    /// Returns the parquet metadata of the file in the cache.
+    fn cached_parquet_metadata(&self) -> Option<Arc<ParquetMetaData>> {
+        self.cache_manager.as_ref().and_then(|cache| {
+            cache.get_parquet_meta_data(self.file_handle.region_id(), self.file_handle.file_id())
+        })
+    }
+
+    /// Reads parquet metadata of specific file from the reader and puts it in the cache.
     async fn read_parquet_metadata(
         &self,
         reader: &mut impl AsyncFileReader,
         file_path: &str,
     ) -> Result<Arc<ParquetMetaData>> {
-        // Tries to get from global cache.
-        if let Some(metadata) = self.cache_manager.as_ref().and_then(|cache| {
-            cache.get_parquet_meta_data(self.file_handle.region_id(), self.file_handle.file_id())
-        }) {
-            return Ok(metadata);
-        }
-
-        // Cache miss, get from the reader.
         let metadata = reader
             .get_metadata()
             .await
@@ -247,6 +607,34 @@ impl ParquetReaderBuilder {
         Ok(metadata)
     }
 
+    /// Rebuilds the missing or corrupt index of the file in background.
+    fn rebuild_index(&self, read_format: &ReadFormat, parquet_meta: &ParquetMetaData) {
+        let Some(context) = &self.index_rebuild else {
+            return;
+        };
+        // Segments of the index are row groups, all row groups except the last one
+        // have the same number of rows.
+        let Some(segment_row_count) = parquet_meta
+            .row_groups()
+            .first()
+            .and_then(|row_group| NonZeroUsize::new(row_group.num_rows() as usize))
+        else {
+            return;
+        };
+
+        IndexRebuilder::new(
+            self.file_dir.clone(),
+            self.file_handle.clone(),
+            self.object_store.clone(),
+            read_format.metadata().clone(),
+            segment_row_count,
+            self.cache_manager.clone(),
+            context.clone(),
+        )
+        .with_intermediate_store(self.intermediate_store.clone())
+        .rebuild_in_background();
+    }
+
     /// Computes row groups to read.
     async fn row_groups_to_read(
         &self,
@@ -262,13 +650,24 @@ impl ParquetReaderBuilder {
         // TODO(zhongzc): Devise a mechanism to enforce the non-use of indices
         // as an escape route in case of index issues, and it can be used to test
         // the correctness of the index.
-        if let Some(index_applier) = &self.index_applier {
+        if let Some(row_groups) = &self.index_row_groups {
+            row_group_ids = row_groups.clone();
+        } else if let Some(index_applier) = &self.index_applier {
             if self.file_handle.meta().inverted_index_available() {
-                match index_applier.apply(self.file_handle.file_id()).await {
+                match index_applier
+                    .apply(self.file_handle.file_id(), self.file_handle.index_file_id())
+                    .await
+                {
                     Ok(row_groups) => row_group_ids = row_groups,
-                    Err(err) => warn!(
-                        err; "Failed to apply index, region_id: {}, file_id: {}", 
-                        self.file_handle.region_id(), self.file_handle.file_id()),
+                    Err(err) => {
+                        warn!(
+                            err; "Failed to apply index, region_id: {}, file_id: {}",
+                            self.file_handle.region_id(), self.file_handle.file_id());
+                        // Reads all row groups until the index is rebuilt.
+                        if err.is_index_missing_or_corrupt() {
+                            self.rebuild_index(read_format, parquet_meta);
+                        }
+                    }
                 }
             }
         }
@@ -303,6 +702,15 @@ impl ParquetReaderBuilder {
         };
         metrics.num_row_groups_min_max_selected += row_group_ids.len();
 
+        if let Some(sample) = &self.sample {
+            // Samples all row groups so the result doesn't depend on pruning.
+            let mut sampler = Sampler::new(sample, self.file_handle.file_id());
+            let sampled: BTreeSet<_> = (0..parquet_meta.num_row_groups())
+                .filter(|_| sampler.sample())
+                .collect();
+            row_group_ids.retain(|id| sampled.contains(id));
+        }
+
         row_group_ids
     }
 }
@@ -346,6 +754,24 @@ struct RowGroupReaderBuilder {
     field_levels: FieldLevels,
     /// Cache.
     cache_manager: Option<CacheManagerRef>,
+    /// Collector of bytes fetched from the object store.
+    fetched_bytes: Option<FetchedBytesRef>,
+    /// Backoff to retry reads from the object store, `None` means no retry.
+    retry_config: Option<RetryConfig>,
+    /// Number of row groups to fetch in advance.
+    prefetch: usize,
+    /// Tasks fetching row groups in advance, ordered by row group indices.
+    prefetching: VecDeque<PrefetchTask>,
+    /// Number of row groups read from prefetched data.
+    num_prefetched: usize,
+}
+
+/// A task fetching column chunks of a row group in background.
+struct PrefetchTask {
+    row_group_idx: usize,
+    /// Bytes to fetch.
+    size: u64,
+    handle: JoinHandle<parquet::errors::Result<FetchedRanges>>,
 }
 
 impl RowGroupReaderBuilder {
@@ -354,8 +780,126 @@ impl RowGroupReaderBuilder {
         &self.file_path
     }
 
+    /// Starts fetching the first row groups in `row_groups` in background, so at most
+    /// [RowGroupReaderBuilder::prefetch] row groups and [MAX_PREFETCH_BYTES] bytes
+    /// are fetched in advance.
+    ///
+    /// `row_groups` are row groups to read after the current one.
+    fn prefetch(&mut self, row_groups: &BTreeSet<usize>) {
+        let num_tasks = self.prefetch.saturating_sub(self.prefetching.len());
+        if num_tasks == 0 {
+            return;
+        }
+
+        let start = self.next_row_group_to_prefetch();
+        self.prefetch_row_groups(row_groups.range(start..).take(num_tasks).copied());
+    }
+
+    /// Returns the min index of row groups that can be prefetched, tasks must be
+    /// ordered by row group indices.
+    fn next_row_group_to_prefetch(&self) -> usize {
+        self.prefetching
+            .back()
+            .map(|task| task.row_group_idx + 1)
+            .unwrap_or(0)
+    }
+
+    /// Starts fetching `row_groups` in ascending order in background until row groups
+    /// fetched in advance reach [MAX_PREFETCH_BYTES] bytes.
+    fn prefetch_row_groups(&mut self, row_groups: impl Iterator<Item = usize>) {
+        let mut buffered: u64 = self.prefetching.iter().map(|task| task.size).sum();
+        for row_group_idx in row_groups {
+            if row_group_idx < self.next_row_group_to_prefetch() {
+                continue;
+            }
+            let ranges = InMemoryRowGroup::create(
+                self.file_handle.region_id(),
+                self.file_handle.file_id(),
+                &self.parquet_meta,
+                row_group_idx,
+                self.cache_manager.clone(),
+                &self.file_path,
+                self.object_store.clone(),
+            )
+            .ranges_to_fetch(&self.projection);
+            if ranges.is_empty() {
+                // Pages of the row group are cached.
+                continue;
+            }
+            let size: u64 = ranges.iter().map(|range| range.end - range.start).sum();
+            if buffered + size > MAX_PREFETCH_BYTES {
+                break;
+            }
+            buffered += size;
+
+            let region_id = self.file_handle.region_id();
+            let file_id = self.file_handle.file_id();
+            let file_path = self.file_path.clone();
+            let object_store = self.object_store.clone();
+            let cache_manager = self.cache_manager.clone();
+            let retry_config = self.retry_config;
+            let handle = common_runtime::spawn_read(async move {
+                fetch_ranges(
+                    region_id,
+                    file_id,
+                    &file_path,
+                    &object_store,
+                    cache_manager.as_ref(),
+                    retry_config.as_ref(),
+                    &ranges,
+                )
+                .await
+            });
+            self.prefetching.push_back(PrefetchTask {
+                row_group_idx,
+                size,
+                handle,
+            });
+        }
+    }
+
+    /// Takes the data of the row group fetched in advance. Returns `None` if the row
+    /// group isn't fetched in advance or fails to fetch.
+    async fn take_prefetched(&mut self, row_group_idx: usize) -> Option<FetchedRanges> {
+        // Row groups are read in order so we no longer need row groups before.
+        while self
+            .prefetching
+            .front()
+            .is_some_and(|task| task.row_group_idx < row_group_idx)
+        {
+            let task = self.prefetching.pop_front().unwrap();
+            task.handle.abort();
+        }
+        if self.prefetching.front()?.row_group_idx != row_group_idx {
+            return None;
+        }
+
+        let task = self.prefetching.pop_front().unwrap();
+        match task.handle.await {
+            Ok(Ok(fetched)) => {
+                self.num_prefetched += 1;
+                Some(fetched)
+            }
+            Ok(Err(e)) => {
+                warn!(
+                    "Failed to prefetch row group {} of {}, err: {}",
+                    row_group_idx, self.file_path, e
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to join the task to prefetch row group {} of {}, err: {}",
+                    row_group_idx, self.file_path, e
+                );
+                None
+            }
+        }
+    }
+
     /// Builds a [ParquetRecordBatchReader] to read the row group at `row_group_idx`.
     async fn build(&mut self, row_group_idx: usize) -> Result<ParquetRecordBatchReader> {
+        let prefetched = self.take_prefetched(row_group_idx).await;
         let mut row_group = InMemoryRowGroup::create(
             self.file_handle.region_id(),
             self.file_handle.file_id(),
@@ -364,7 +908,9 @@ impl RowGroupReaderBuilder {
             self.cache_manager.clone(),
             &self.file_path,
             self.object_store.clone(),
-        );
+        )
+        .with_prefetched(prefetched)
+        .with_retry_config(self.retry_config);
         // Fetches data into memory.
         row_group
             .fetch(&self.projection, None)
@@ -372,6 +918,9 @@ impl RowGroupReaderBuilder {
             .context(ReadParquetSnafu {
                 path: &self.file_path,
             })?;
+        if let Some(fetched_bytes) = &self.fetched_bytes {
+            fetched_bytes.add(self.file_handle.file_id(), row_group.fetched_bytes());
+        }
 
         // Builds the parquet reader.
         // Now the row selection is None.
@@ -387,6 +936,29 @@ impl RowGroupReaderBuilder {
     }
 }
 
+impl Drop for RowGroupReaderBuilder {
+    fn drop(&mut self) {
+        for task in &self.prefetching {
+            task.handle.abort();
+        }
+    }
+}
+
+/// Returns true if the parquet file only has put rows according to statistics of the
+/// op type column.
+fn is_put_only(parquet_meta: &ParquetMetaData) -> bool {
+    parquet_meta.row_groups().iter().all(|row_group| {
+        // The op type is the last column of the SST, and delete is less than put.
+        let op_type = row_group.column(row_group.num_columns() - 1);
+        match op_type.statistics() {
+            Some(Statistics::Int32(stats)) if stats.has_min_max_set() => {
+                *stats.min() == OpType::Put as i32
+            }
+            _ => false,
+        }
+    })
+}
+
 /// Parquet batch reader to read our SST format.
 pub struct ParquetReader {
     /// Indices of row groups to read.
@@ -406,6 +978,10 @@ pub struct ParquetReader {
     batches: VecDeque<Batch>,
     /// Local metrics.
     metrics: Metrics,
+    /// Key to record row groups read by the query.
+    hint_key: Option<RowGroupHintKey>,
+    /// Indices of row groups read.
+    read_row_groups: Vec<usize>,
 }
 
 #[async_trait]
@@ -448,6 +1024,15 @@ impl Drop for ParquetReader {
             self.metrics
         );
 
+        if let Some((key, cache)) = self
+            .hint_key
+            .take()
+            .zip(self.reader_builder.cache_manager.as_ref())
+        {
+            let read_row_groups = std::mem::take(&mut self.read_row_groups);
+            cache.put_row_group_hint(key, Arc::new(read_row_groups));
+        }
+
         // Report metrics.
         READ_STAGE_ELAPSED
             .with_label_values(&["build_parquet_reader"])
@@ -495,6 +1080,8 @@ impl ParquetReader {
 
         // No more items in current row group, reads next row group.
         while let Some(row_group_idx) = self.row_groups.pop_first() {
+            self.read_row_groups.push(row_group_idx);
+            self.reader_builder.prefetch(&self.row_groups);
             let mut row_group_reader = self.reader_builder.build(row_group_idx).await?;
             let Some(record_batch) =
                 row_group_reader
@@ -519,4 +1106,10 @@ impl ParquetReader {
     pub fn parquet_metadata(&self) -> Arc<ParquetMetaData> {
         self.reader_builder.parquet_meta.clone()
     }
+
+    /// Returns the number of row groups read from data fetched in advance.
+    #[cfg(test)]
+    pub fn num_prefetched_row_groups(&self) -> usize {
+        self.reader_builder.num_prefetched
+    }
 }
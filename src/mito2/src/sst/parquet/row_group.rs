@@ -14,10 +14,13 @@
 
 //! Ports private structs from [parquet crate](https://github.com/apache/arrow-rs/blob/7e134f4d277c0b62c27529fc15a4739de3ad0afd/parquet/src/arrow/async_reader/mod.rs#L644-L650).
 
+use std::io::SeekFrom;
 use std::ops::Range;
 use std::sync::Arc;
 
 use bytes::{Buf, Bytes};
+use common_telemetry::warn;
+use futures::{AsyncReadExt, AsyncSeekExt};
 use object_store::ObjectStore;
 use parquet::arrow::arrow_reader::{RowGroups, RowSelection};
 use parquet::arrow::ProjectionMask;
@@ -30,11 +33,13 @@ use parquet::format::PageLocation;
 use store_api::storage::RegionId;
 
 use crate::cache::file_cache::{FileType, IndexKey};
+use crate::cache::reader_cache::SstReaderRef;
 use crate::cache::{CacheManagerRef, PageKey, PageValue};
-use crate::metrics::READ_STAGE_ELAPSED;
+use crate::metrics::{READ_SST_FETCHED_BYTES_TOTAL, READ_STAGE_ELAPSED};
 use crate::sst::file::FileId;
 use crate::sst::parquet::helper::fetch_byte_ranges;
 use crate::sst::parquet::page_reader::CachedPageReader;
+use crate::sst::retry::{maybe_retry, RetryConfig, READ_RANGES_OPERATION};
 
 /// An in-memory collection of column chunks
 pub struct InMemoryRowGroup<'a> {
@@ -53,6 +58,22 @@ pub struct InMemoryRowGroup<'a> {
     file_path: &'a str,
     /// Object store.
     object_store: ObjectStore,
+    /// Bytes fetched from the object store.
+    fetched_bytes: u64,
+    /// Data of column chunks fetched in advance.
+    prefetched: Option<FetchedRanges>,
+    /// Backoff to retry reads from the object store, `None` means no retry.
+    retry_config: Option<RetryConfig>,
+}
+
+/// Data of byte ranges fetched from a SST.
+pub(crate) struct FetchedRanges {
+    /// Ranges fetched.
+    pub(crate) ranges: Vec<Range<u64>>,
+    /// Data of each range.
+    pub(crate) data: Vec<Bytes>,
+    /// Bytes fetched from the object store, zero if the data is in the write cache.
+    pub(crate) fetched_bytes: u64,
 }
 
 impl<'a> InMemoryRowGroup<'a> {
@@ -89,9 +110,53 @@ impl<'a> InMemoryRowGroup<'a> {
             column_cached_pages: vec![None; metadata.columns().len()],
             file_path,
             object_store,
+            fetched_bytes: 0,
+            prefetched: None,
+            retry_config: None,
         }
     }
 
+    /// Retries reads from the object store by the `retry_config`.
+    #[must_use]
+    pub(crate) fn with_retry_config(mut self, retry_config: Option<RetryConfig>) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Attaches data of column chunks fetched in advance, the row group only uses the
+    /// data if it needs to fetch the same ranges.
+    #[must_use]
+    pub(crate) fn with_prefetched(mut self, prefetched: Option<FetchedRanges>) -> Self {
+        self.prefetched = prefetched;
+        self
+    }
+
+    /// Returns byte ranges of column chunks in the `projection` to fetch, skipping
+    /// columns whose pages are cached.
+    pub(crate) fn ranges_to_fetch(&mut self, projection: &ProjectionMask) -> Vec<Range<u64>> {
+        self.fetch_pages_from_cache(projection);
+
+        self.column_chunks
+            .iter()
+            .zip(&self.column_cached_pages)
+            .enumerate()
+            // Don't need to fetch column data if we already cache the column's pages.
+            .filter(|&(idx, (chunk, cached_pages))| {
+                chunk.is_none() && projection.leaf_included(idx) && cached_pages.is_none()
+            })
+            .map(|(idx, (_chunk, _cached_pages))| {
+                let column = self.metadata.column(idx);
+                let (start, length) = column.byte_range();
+                start..(start + length)
+            })
+            .collect()
+    }
+
+    /// Returns bytes fetched from the object store.
+    pub fn fetched_bytes(&self) -> u64 {
+        self.fetched_bytes
+    }
+
     /// Fetches the necessary column data into memory
     pub async fn fetch(
         &mut self,
@@ -159,23 +224,7 @@ impl<'a> InMemoryRowGroup<'a> {
             }
         } else {
             // Now we only use cache in dense chunk data.
-            self.fetch_pages_from_cache(projection);
-
-            let fetch_ranges = self
-                .column_chunks
-                .iter()
-                .zip(&self.column_cached_pages)
-                .enumerate()
-                // Don't need to fetch column data if we already cache the column's pages.
-                .filter(|&(idx, (chunk, cached_pages))| {
-                    chunk.is_none() && projection.leaf_included(idx) && cached_pages.is_none()
-                })
-                .map(|(idx, (_chunk, _cached_pages))| {
-                    let column = self.metadata.column(idx);
-                    let (start, length) = column.byte_range();
-                    start..(start + length)
-                })
-                .collect::<Vec<_>>();
+            let fetch_ranges = self.ranges_to_fetch(projection);
 
             if fetch_ranges.is_empty() {
                 // Nothing to fetch.
@@ -225,36 +274,28 @@ impl<'a> InMemoryRowGroup<'a> {
             });
     }
 
-    /// Try to fetch data from WriteCache,
-    /// if not in WriteCache, fetch data from object store directly.
-    async fn fetch_bytes(&self, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
-        let key = IndexKey::new(self.region_id, self.file_id, FileType::Parquet);
-        match self.fetch_ranges_from_write_cache(key, ranges).await {
-            Some(data) => Ok(data),
-            None => {
-                // Fetch data from object store.
-                let _timer = READ_STAGE_ELAPSED
-                    .with_label_values(&["cache_miss_read"])
-                    .start_timer();
-                let data = fetch_byte_ranges(self.file_path, self.object_store.clone(), ranges)
-                    .await
-                    .map_err(|e| ParquetError::External(Box::new(e)))?;
-                Ok(data)
+    /// Uses the prefetched data if the row group fetches the same ranges,
+    /// otherwise fetches the ranges.
+    async fn fetch_bytes(&mut self, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        if let Some(prefetched) = self.prefetched.take() {
+            if prefetched.ranges == ranges {
+                self.fetched_bytes += prefetched.fetched_bytes;
+                return Ok(prefetched.data);
             }
         }
-    }
 
-    /// Fetches data from write cache.
-    /// Returns `None` if the data is not in the cache.
-    async fn fetch_ranges_from_write_cache(
-        &self,
-        key: IndexKey,
-        ranges: &[Range<u64>],
-    ) -> Option<Vec<Bytes>> {
-        if let Some(cache) = self.cache_manager.as_ref()?.write_cache() {
-            return cache.file_cache().read_ranges(key, ranges).await;
-        }
-        None
+        let fetched = fetch_ranges(
+            self.region_id,
+            self.file_id,
+            self.file_path,
+            &self.object_store,
+            self.cache_manager.as_ref(),
+            self.retry_config.as_ref(),
+            ranges,
+        )
+        .await?;
+        self.fetched_bytes += fetched.fetched_bytes;
+        Ok(fetched.data)
     }
 
     /// Creates a page reader to read column at `i`.
@@ -302,6 +343,128 @@ impl<'a> InMemoryRowGroup<'a> {
     }
 }
 
+/// Try to fetch data from WriteCache,
+/// if not in WriteCache, fetch data by the open reader of the SST in the cache, or from
+/// object store directly if the cache doesn't keep open readers.
+///
+/// Reads from the object store are retried by the `retry_config` if it isn't `None`.
+pub(crate) async fn fetch_ranges(
+    region_id: RegionId,
+    file_id: FileId,
+    file_path: &str,
+    object_store: &ObjectStore,
+    cache_manager: Option<&CacheManagerRef>,
+    retry_config: Option<&RetryConfig>,
+    ranges: &[Range<u64>],
+) -> Result<FetchedRanges> {
+    let key = IndexKey::new(region_id, file_id, FileType::Parquet);
+    if let Some(data) = fetch_ranges_from_write_cache(cache_manager, key, ranges).await {
+        return Ok(FetchedRanges {
+            ranges: ranges.to_vec(),
+            data,
+            fetched_bytes: 0,
+        });
+    }
+
+    // Fetch data from object store.
+    let _timer = READ_STAGE_ELAPSED
+        .with_label_values(&["cache_miss_read"])
+        .start_timer();
+    let data = match fetch_ranges_from_sst_reader(
+        region_id,
+        file_id,
+        file_path,
+        object_store,
+        cache_manager,
+        ranges,
+    )
+    .await
+    {
+        Some(data) => data,
+        None => maybe_retry(retry_config, READ_RANGES_OPERATION, file_path, || {
+            fetch_byte_ranges(file_path, object_store.clone(), ranges)
+        })
+        .await
+        .map_err(|e| ParquetError::External(Box::new(e)))?,
+    };
+    let bytes = ranges.iter().map(|range| range.end - range.start).sum();
+    READ_SST_FETCHED_BYTES_TOTAL.inc_by(bytes);
+    Ok(FetchedRanges {
+        ranges: ranges.to_vec(),
+        data,
+        fetched_bytes: bytes,
+    })
+}
+
+/// Fetches data by the open reader of the SST in the cache, the reader is opened and put
+/// into the cache if the cache doesn't have it.
+///
+/// Returns `None` if the cache doesn't keep open readers or the reader fails, the failed
+/// reader is removed from the cache so the next read opens the SST again. Reads by the
+/// same reader are serialized.
+async fn fetch_ranges_from_sst_reader(
+    region_id: RegionId,
+    file_id: FileId,
+    file_path: &str,
+    object_store: &ObjectStore,
+    cache_manager: Option<&CacheManagerRef>,
+    ranges: &[Range<u64>],
+) -> Option<Vec<Bytes>> {
+    let cache = cache_manager?;
+    if !cache.has_sst_reader_cache() {
+        return None;
+    }
+    let reader = match cache.get_sst_reader(region_id, file_id) {
+        Some(reader) => reader,
+        None => {
+            let reader = object_store.reader(file_path).await.ok()?;
+            cache.put_sst_reader(
+                region_id,
+                file_id,
+                Arc::new(tokio::sync::Mutex::new(reader)),
+            )
+        }
+    };
+
+    match read_ranges(&reader, ranges).await {
+        Ok(data) => Some(data),
+        Err(e) => {
+            warn!(
+                e; "Failed to read SST by the open reader, region_id: {}, file_id: {}",
+                region_id, file_id
+            );
+            cache.remove_sst_reader(region_id, file_id);
+            None
+        }
+    }
+}
+
+/// Reads `ranges` of the SST by the `reader`.
+async fn read_ranges(reader: &SstReaderRef, ranges: &[Range<u64>]) -> std::io::Result<Vec<Bytes>> {
+    let mut reader = reader.lock().await;
+    let mut data = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        reader.seek(SeekFrom::Start(range.start)).await?;
+        let mut buf = vec![0; (range.end - range.start) as usize];
+        reader.read_exact(&mut buf).await?;
+        data.push(Bytes::from(buf));
+    }
+    Ok(data)
+}
+
+/// Fetches data from write cache.
+/// Returns `None` if the data is not in the cache.
+async fn fetch_ranges_from_write_cache(
+    cache_manager: Option<&CacheManagerRef>,
+    key: IndexKey,
+    ranges: &[Range<u64>],
+) -> Option<Vec<Bytes>> {
+    if let Some(cache) = cache_manager?.write_cache() {
+        return cache.file_cache().read_ranges(key, ranges).await;
+    }
+    None
+}
+
 impl<'a> RowGroups for InMemoryRowGroup<'a> {
     fn num_rows(&self) -> usize {
         self.row_count
@@ -385,3 +548,52 @@ impl Iterator for ColumnChunkIterator {
 }
 
 impl PageIterator for ColumnChunkIterator {}
+
+#[cfg(test)]
+mod tests {
+    use common_test_util::temp_dir::create_temp_dir;
+    use object_store::services::Fs;
+
+    use super::*;
+    use crate::cache::CacheManager;
+
+    #[tokio::test]
+    async fn test_fetch_ranges_by_sst_reader() {
+        let dir = create_temp_dir("");
+        let mut builder = Fs::default();
+        builder.root(dir.path().to_str().unwrap());
+        let store = ObjectStore::new(builder).unwrap().finish();
+        let region_id = RegionId::new(1, 1);
+        let file_id = FileId::random();
+        let file_path = file_id.as_parquet();
+        store.write(&file_path, b"abcdef".to_vec()).await.unwrap();
+
+        let cache = Arc::new(CacheManager::builder().sst_reader_cache_size(1).build());
+        let fetched = fetch_ranges(
+            region_id,
+            file_id,
+            &file_path,
+            &store,
+            Some(&cache),
+            None,
+            &[1..3, 4..6],
+        )
+        .await
+        .unwrap();
+        assert_eq!(vec![Bytes::from("bc"), Bytes::from("ef")], fetched.data);
+        // Later reads reuse the reader put into the cache.
+        assert!(cache.get_sst_reader(region_id, file_id).is_some());
+        let fetched = fetch_ranges(
+            region_id,
+            file_id,
+            &file_path,
+            &store,
+            Some(&cache),
+            None,
+            &[0..2],
+        )
+        .await
+        .unwrap();
+        assert_eq!(vec![Bytes::from("ab")], fetched.data);
+    }
+}
@@ -14,71 +14,199 @@
 
 //! Parquet writer.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use api::v1::SemanticType;
+use async_trait::async_trait;
 use common_datasource::file_format::parquet::BufferedWriter;
-use common_telemetry::debug;
+use common_telemetry::{debug, warn};
 use common_time::Timestamp;
 use object_store::ObjectStore;
-use parquet::basic::{Compression, Encoding, ZstdLevel};
+use parquet::basic::Encoding;
 use parquet::file::metadata::KeyValue;
 use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder};
 use parquet::schema::types::ColumnPath;
-use snafu::ResultExt;
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::storage::consts::SEQUENCE_COLUMN_NAME;
+use store_api::storage::ColumnId;
 
-use super::helper::parse_parquet_metadata;
-use crate::error::{InvalidMetadataSnafu, Result, WriteBufferSnafu};
+use super::helper::{metadata_checksum, parse_parquet_metadata};
+use crate::error::{InvalidColumnEncodingSnafu, InvalidMetadataSnafu, Result, WriteBufferSnafu};
 use crate::read::{Batch, Source};
+use crate::sst::file::{ColumnIndexStats, FileId};
+use crate::sst::index::creator::SstIndexCreator;
 use crate::sst::parquet::format::WriteFormat;
-use crate::sst::parquet::{SstInfo, WriteOptions, PARQUET_METADATA_KEY};
+use crate::sst::parquet::{
+    ColumnKeyValues, ParquetVersion, SstInfo, WriteOptions, PARQUET_METADATA_KEY,
+};
+
+/// A new SST for the [ParquetWriter] to roll over to.
+pub(crate) struct SstFile {
+    pub(crate) file_id: FileId,
+    /// SST output file path.
+    pub(crate) file_path: String,
+    /// Creates the inverted index of the SST while writing it.
+    pub(crate) index_creator: Option<SstIndexCreator>,
+}
+
+/// Provides SSTs for the [ParquetWriter] to roll over to once a SST reaches
+/// [WriteOptions::max_file_size].
+#[async_trait]
+pub(crate) trait SstFileProvider: Send {
+    /// Returns the next SST to write.
+    async fn next_file(&mut self) -> SstFile;
+
+    /// Removes the SST with `file_id` and its index, the writer removes SSTs it
+    /// already wrote if it fails to write the next one.
+    async fn remove_file(&mut self, file_id: FileId);
+}
 
 /// Parquet SST writer.
 pub struct ParquetWriter {
+    /// Id of the SST in progress.
+    file_id: FileId,
     /// SST output file path.
     file_path: String,
     /// Region metadata of the source and the target SST.
     metadata: RegionMetadataRef,
     object_store: ObjectStore,
+    /// Creates the inverted index of the SST while writing it.
+    index_creator: Option<SstIndexCreator>,
+    /// Custom key-value metadata of columns to write to the schema of the SST.
+    column_metadata: ColumnKeyValues,
+    /// Provides SSTs to roll over to, the writer never rolls over if it's `None`.
+    file_provider: Option<Box<dyn SstFileProvider>>,
 }
 
 impl ParquetWriter {
     /// Creates a new parquet SST writer.
     pub fn new(
+        file_id: FileId,
         file_path: String,
         metadata: RegionMetadataRef,
         object_store: ObjectStore,
     ) -> ParquetWriter {
         ParquetWriter {
+            file_id,
             file_path,
             metadata,
             object_store,
+            index_creator: None,
+            column_metadata: ColumnKeyValues::new(),
+            file_provider: None,
         }
     }
 
-    /// Iterates source and writes all rows to Parquet file.
+    /// Writes custom key-value metadata of columns to the schema of the SST.
+    pub(crate) fn with_column_metadata(
+        mut self,
+        column_metadata: ColumnKeyValues,
+    ) -> ParquetWriter {
+        self.column_metadata = column_metadata;
+        self
+    }
+
+    /// Creates the inverted index of the SST by the `index_creator`.
+    pub(crate) fn with_index_creator(mut self, index_creator: SstIndexCreator) -> ParquetWriter {
+        self.index_creator = Some(index_creator);
+        self
+    }
+
+    /// Rolls over to SSTs from the `file_provider` once a SST reaches
+    /// [WriteOptions::max_file_size].
+    pub(crate) fn with_file_provider(
+        mut self,
+        file_provider: Box<dyn SstFileProvider>,
+    ) -> ParquetWriter {
+        self.file_provider = Some(file_provider);
+        self
+    }
+
+    /// Iterates source and writes all rows to Parquet file. It stops earlier if the file
+    /// reaches [WriteOptions::target_file_size] or [WriteOptions::target_num_rows].
+    ///
+    /// The source is consumed batch by batch, a row group is encoded once it has
+    /// [WriteOptions::row_group_size] rows and encoded data is written to the object
+    /// store once it exceeds [WriteOptions::write_buffer_size], so the memory usage
+    /// doesn't grow with the size of the source.
     ///
-    /// Returns the [SstInfo] if the SST is written.
+    /// If the writer has a [SstFileProvider], it rolls over to a new SST from the provider
+    /// once the SST reaches [WriteOptions::max_file_size]. The time range of each SST only
+    /// covers its own rows.
+    ///
+    /// Returns the [SstInfo] of each written SST in order, or an empty vec if the source
+    /// is empty. The partial file and SSTs already written are removed if it fails to
+    /// write a SST.
     pub async fn write_all(
         &mut self,
         mut source: Source,
         opts: &WriteOptions,
-    ) -> Result<Option<SstInfo>> {
-        let json = self.metadata.to_json().context(InvalidMetadataSnafu)?;
-        let key_value_meta = KeyValue::new(PARQUET_METADATA_KEY.to_string(), json);
+    ) -> Result<Vec<SstInfo>> {
+        let writer_props = match self.writer_props(opts) {
+            Ok(writer_props) => writer_props,
+            Err(e) => {
+                self.abort_index().await;
+                return Err(e);
+            }
+        };
 
-        // TODO(yingwen): Find and set proper column encoding for internal columns: op type and tsid.
-        let props_builder = WriterProperties::builder()
-            .set_key_value_metadata(Some(vec![key_value_meta]))
-            .set_compression(Compression::ZSTD(ZstdLevel::default()))
-            .set_encoding(Encoding::PLAIN)
-            .set_max_row_group_size(opts.row_group_size);
+        let write_format = match WriteFormat::new(self.metadata.clone())
+            .with_column_metadata(&self.column_metadata)
+        {
+            Ok(write_format) => write_format,
+            Err(e) => {
+                self.abort_index().await;
+                return Err(e);
+            }
+        };
 
-        let props_builder = Self::customize_column_config(props_builder, &self.metadata);
-        let writer_props = props_builder.build();
+        let mut sst_infos = Vec::new();
+        loop {
+            let (sst_info, rolled_over) = match self
+                .write_sst(&mut source, &write_format, writer_props.clone(), opts)
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    self.remove_ssts(&sst_infos).await;
+                    return Err(e);
+                }
+            };
+            let Some(sst_info) = sst_info else {
+                break;
+            };
+            sst_infos.push(sst_info);
+            if !rolled_over {
+                break;
+            }
 
-        let write_format = WriteFormat::new(self.metadata.clone());
+            // Safety: the writer only rolls over if it has a provider.
+            let next_file = self.file_provider.as_mut().unwrap().next_file().await;
+            debug!(
+                "Roll over from SST {} to SST {}",
+                self.file_path, next_file.file_path
+            );
+            self.file_id = next_file.file_id;
+            self.file_path = next_file.file_path;
+            self.index_creator = next_file.index_creator;
+        }
+
+        Ok(sst_infos)
+    }
+
+    /// Writes batches from the `source` to the SST in progress.
+    ///
+    /// Returns the [SstInfo] of the SST, or `None` if no row is written, and whether the
+    /// remaining batches should be written to the next SST.
+    async fn write_sst(
+        &mut self,
+        source: &mut Source,
+        write_format: &WriteFormat,
+        writer_props: WriterProperties,
+        opts: &WriteOptions,
+    ) -> Result<(Option<SstInfo>, bool)> {
         let mut buffered_writer = BufferedWriter::try_new(
             self.file_path.clone(),
             self.object_store.clone(),
@@ -89,16 +217,17 @@ impl ParquetWriter {
         .await
         .context(WriteBufferSnafu)?;
 
-        let mut stats = SourceStats::default();
-        while let Some(batch) = source.next_batch().await? {
-            stats.update(&batch);
-            let arrow_batch = write_format.convert_batch(&batch)?;
-
-            buffered_writer
-                .write(&arrow_batch)
-                .await
-                .context(WriteBufferSnafu)?;
-        }
+        let (stats, rolled_over) = match self
+            .write_batches(source, write_format, &mut buffered_writer, opts)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                drop(buffered_writer);
+                self.clean_up_on_failure().await;
+                return Err(e);
+            }
+        };
 
         if stats.num_rows == 0 {
             debug!(
@@ -106,47 +235,255 @@ impl ParquetWriter {
                 self.file_path
             );
 
+            // The writer doesn't create the file if no row is written.
             buffered_writer.close().await.context(WriteBufferSnafu)?;
-            return Ok(None);
+            self.abort_index().await;
+            return Ok((None, false));
         }
 
-        let (file_meta, file_size) = buffered_writer.close().await.context(WriteBufferSnafu)?;
+        let (file_meta, file_size) = match buffered_writer.close().await.context(WriteBufferSnafu) {
+            Ok(result) => result,
+            Err(e) => {
+                self.clean_up_on_failure().await;
+                return Err(e);
+            }
+        };
+
+        let footer_checksum = match metadata_checksum(&self.file_path, &file_meta) {
+            Ok(checksum) => checksum,
+            Err(e) => {
+                self.clean_up_on_failure().await;
+                return Err(e);
+            }
+        };
 
         // Safety: num rows > 0 so we must have min/max.
         let time_range = stats.time_range.unwrap();
 
         // convert FileMetaData to ParquetMetaData
         let parquet_metadata = parse_parquet_metadata(file_meta)?;
+        let (index_file_size, index_stats) = self.finish_index().await;
 
         // object_store.write will make sure all bytes are written or an error is raised.
-        Ok(Some(SstInfo {
+        let sst_info = SstInfo {
+            file_id: self.file_id,
             time_range,
             file_size,
             num_rows: stats.num_rows,
             file_metadata: Some(Arc::new(parquet_metadata)),
-            inverted_index_available: false,
-            index_file_size: 0,
-        }))
+            inverted_index_available: index_file_size > 0,
+            index_file_size,
+            index_stats,
+            footer_checksum,
+        };
+        Ok((Some(sst_info), rolled_over))
+    }
+
+    /// Returns properties of the parquet writer to write the SST with `opts`.
+    pub(crate) fn writer_props(&self, opts: &WriteOptions) -> Result<WriterProperties> {
+        let json = self.metadata.to_json().context(InvalidMetadataSnafu)?;
+        let key_value_meta = KeyValue::new(PARQUET_METADATA_KEY.to_string(), json);
+
+        // TODO(yingwen): Find and set proper column encoding for internal columns: op type and tsid.
+        let props_builder = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![key_value_meta]))
+            .set_compression(opts.compression.parquet_compression())
+            .set_encoding(Encoding::PLAIN)
+            .set_max_row_group_size(opts.row_group_size);
+        let props_builder = match opts.parquet_version {
+            Some(version) => props_builder.set_writer_version(version.writer_version()),
+            None => props_builder,
+        };
+
+        let props_builder = self.customize_column_config(props_builder, opts.parquet_version);
+        let props_builder =
+            self.set_column_encodings(props_builder, &opts.column_encodings, opts.parquet_version)?;
+        Ok(props_builder.build())
+    }
+
+    /// Writes batches from the `source` until the source is exhausted or the file
+    /// reaches the target size or number of rows, or the max size to roll over.
+    ///
+    /// Returns statistics of written batches and whether the file reaches the max size.
+    async fn write_batches(
+        &mut self,
+        source: &mut Source,
+        write_format: &WriteFormat,
+        buffered_writer: &mut BufferedWriter,
+        opts: &WriteOptions,
+    ) -> Result<(SourceStats, bool)> {
+        let mut stats = SourceStats::default();
+        while let Some(batch) = source.next_batch().await? {
+            stats.update(&batch);
+            self.update_index(&batch).await;
+            let arrow_batch = write_format.convert_batch(&batch)?;
+
+            buffered_writer
+                .write(&arrow_batch)
+                .await
+                .context(WriteBufferSnafu)?;
+
+            if opts
+                .target_file_size
+                .is_some_and(|size| buffered_writer.encoded_size() >= size.as_bytes())
+                || opts
+                    .target_num_rows
+                    .is_some_and(|num_rows| stats.num_rows >= num_rows)
+            {
+                // Leaves the remaining batches to the next writer.
+                break;
+            }
+            // Only checks the size of encoded row groups so a row group is never split.
+            if self.file_provider.is_some()
+                && opts
+                    .max_file_size
+                    .is_some_and(|size| buffered_writer.encoded_size() >= size as u64)
+            {
+                return Ok((stats, true));
+            }
+        }
+
+        Ok((stats, false))
+    }
+
+    /// Removes SSTs in `sst_infos` the writer already wrote.
+    async fn remove_ssts(&mut self, sst_infos: &[SstInfo]) {
+        let Some(file_provider) = &mut self.file_provider else {
+            return;
+        };
+        for sst_info in sst_infos {
+            file_provider.remove_file(sst_info.file_id).await;
+        }
+    }
+
+    /// Aborts the index and removes the partial SST after failing to write the SST.
+    async fn clean_up_on_failure(&mut self) {
+        self.abort_index().await;
+        if let Err(e) = self.object_store.delete(&self.file_path).await {
+            warn!(e; "Failed to remove partial SST {}", self.file_path);
+        }
+    }
+
+    /// Updates the index by the `batch`, stops creating the index on failure.
+    ///
+    /// Failing to create the index never fails writing the SST.
+    async fn update_index(&mut self, batch: &Batch) {
+        let Some(creator) = &mut self.index_creator else {
+            return;
+        };
+        if let Err(e) = creator.update(batch).await {
+            warn!(e; "Failed to update index, skip creating index for SST {}", self.file_path);
+            // The creator cleans up garbage on failure.
+            self.index_creator = None;
+        }
+    }
+
+    /// Finishes the index, returns the index file size and statistics of the index
+    /// of each column. The file size is 0 if no index is created.
+    async fn finish_index(&mut self) -> (u64, Vec<ColumnIndexStats>) {
+        let Some(mut creator) = self.index_creator.take() else {
+            return (0, Vec::new());
+        };
+        match creator.finish().await {
+            Ok((_, byte_count)) => (byte_count as u64, creator.column_stats().to_vec()),
+            Err(e) => {
+                warn!(e; "Failed to finish index for SST {}", self.file_path);
+                (0, Vec::new())
+            }
+        }
+    }
+
+    async fn abort_index(&mut self) {
+        if let Some(mut creator) = self.index_creator.take() {
+            if let Err(e) = creator.abort().await {
+                warn!(e; "Failed to abort index for SST {}", self.file_path);
+            }
+        }
     }
 
     /// Customizes per-column config according to schema and maybe column cardinality.
     fn customize_column_config(
+        &self,
         builder: WriterPropertiesBuilder,
-        region_metadata: &RegionMetadataRef,
+        version: Option<ParquetVersion>,
     ) -> WriterPropertiesBuilder {
-        let ts_col = ColumnPath::new(vec![region_metadata
+        let ts_col = ColumnPath::new(vec![self
+            .metadata
             .time_index_column()
             .column_schema
             .name
             .clone()]);
         let seq_col = ColumnPath::new(vec![SEQUENCE_COLUMN_NAME.to_string()]);
 
+        if version == Some(ParquetVersion::V1) {
+            // Readers of the format version 1.0 might not support delta encodings.
+            return builder
+                .set_column_dictionary_enabled(seq_col, false)
+                .set_column_dictionary_enabled(ts_col, false);
+        }
+
         builder
             .set_column_encoding(seq_col.clone(), Encoding::DELTA_BINARY_PACKED)
             .set_column_dictionary_enabled(seq_col, false)
             .set_column_encoding(ts_col.clone(), Encoding::DELTA_BINARY_PACKED)
             .set_column_dictionary_enabled(ts_col, false)
     }
+
+    /// Overrides encodings of columns by `column_encodings`.
+    fn set_column_encodings(
+        &self,
+        mut builder: WriterPropertiesBuilder,
+        column_encodings: &HashMap<ColumnId, Encoding>,
+        version: Option<ParquetVersion>,
+    ) -> Result<WriterPropertiesBuilder> {
+        for (column_id, encoding) in column_encodings {
+            let column =
+                self.metadata
+                    .column_by_id(*column_id)
+                    .context(InvalidColumnEncodingSnafu {
+                        column_id: *column_id,
+                        reason: "column not found",
+                    })?;
+            ensure!(
+                column.semantic_type != SemanticType::Tag,
+                InvalidColumnEncodingSnafu {
+                    column_id: *column_id,
+                    reason: "tag columns are encoded into the primary key",
+                }
+            );
+            ensure!(
+                version != Some(ParquetVersion::V1) || !requires_parquet_v2(*encoding),
+                InvalidColumnEncodingSnafu {
+                    column_id: *column_id,
+                    reason: format!("{encoding:?} requires the parquet format version 2.0"),
+                }
+            );
+
+            let col = ColumnPath::new(vec![column.column_schema.name.clone()]);
+            builder = match encoding {
+                // The writer only uses dictionary encodings if the dictionary is enabled.
+                Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY => {
+                    builder.set_column_dictionary_enabled(col, true)
+                }
+                _ => builder
+                    .set_column_encoding(col.clone(), *encoding)
+                    .set_column_dictionary_enabled(col, false),
+            };
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Returns true if the `encoding` is only defined in the parquet format version 2.0.
+pub(crate) fn requires_parquet_v2(encoding: Encoding) -> bool {
+    matches!(
+        encoding,
+        Encoding::DELTA_BINARY_PACKED
+            | Encoding::DELTA_LENGTH_BYTE_ARRAY
+            | Encoding::DELTA_BYTE_ARRAY
+            | Encoding::BYTE_STREAM_SPLIT
+    )
 }
 
 #[derive(Default)]
@@ -0,0 +1,208 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retries of object store operations on SSTs.
+
+use std::future::Future;
+use std::time::Duration;
+
+use common_telemetry::warn;
+use rand::Rng;
+
+use crate::metrics::OBJECT_STORE_RETRY_TOTAL;
+
+/// Operation to delete files.
+pub(crate) const DELETE_OPERATION: &str = "delete";
+/// Operation to open a reader of a file.
+pub(crate) const OPEN_OPERATION: &str = "open";
+/// Operation to read the footer of a parquet file.
+pub(crate) const READ_FOOTER_OPERATION: &str = "read_footer";
+/// Operation to read byte ranges of a file.
+pub(crate) const READ_RANGES_OPERATION: &str = "read_ranges";
+
+/// Backoff to retry idempotent object store operations on SSTs that fail with
+/// temporary errors, e.g. throttling or 5xx errors of S3.
+///
+/// Only deletes and reads are retried. Uploads are never retried as a multipart
+/// upload isn't idempotent on every backend.
+///
+/// The backoff stacks on retries of the object store itself, e.g. the datanode wraps
+/// object stores other than the local file system in a `RetryLayer`. Each retry here
+/// reruns the whole operation after the layer gives up, so the total number of attempts
+/// of a request is the product of both limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Max number of retries after the first attempt of an operation fails.
+    pub max_retries: usize,
+    /// Delay before the first retry, the delay doubles on each retry.
+    pub base_delay: Duration,
+    /// Max delay between two attempts.
+    pub max_delay: Duration,
+    /// Whether to randomize delays, so retries of operations failing at the same time
+    /// don't hit the backend at the same time again.
+    pub jitter: bool,
+}
+
+impl RetryConfig {
+    /// Returns the delay before the retry after `retries` retries.
+    fn delay(&self, retries: usize) -> Duration {
+        let factor = 1u32.checked_shl(retries as u32).unwrap_or(u32::MAX);
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+
+        // Keeps at least half of the delay so the backoff still grows.
+        let half = delay / 2;
+        let jitter = rand::thread_rng().gen_range(0..=half.as_micros() as u64);
+        half + Duration::from_micros(jitter)
+    }
+
+    /// Runs the operation `op` on `path`, retrying it if it fails with a temporary error.
+    pub(crate) async fn retry<T, F, Fut>(
+        &self,
+        op: &'static str,
+        path: &str,
+        mut f: F,
+    ) -> object_store::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = object_store::Result<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            match f().await {
+                Err(e) if retries < self.max_retries && e.is_temporary() => {
+                    let delay = self.delay(retries);
+                    retries += 1;
+                    OBJECT_STORE_RETRY_TOTAL.with_label_values(&[op]).inc();
+                    warn!(
+                        e; "Retrying object store operation {} after {:?}, path: {}, retries: {}",
+                        op, delay, path, retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Runs the operation `op` on `path` with the retry `config`, the operation only runs
+/// once if `config` is `None`.
+pub(crate) async fn maybe_retry<T, F, Fut>(
+    config: Option<&RetryConfig>,
+    op: &'static str,
+    path: &str,
+    mut f: F,
+) -> object_store::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = object_store::Result<T>>,
+{
+    match config {
+        Some(config) => config.retry(op, path, f).await,
+        None => f().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use object_store::ErrorKind;
+
+    use super::*;
+
+    fn new_config(max_retries: usize) -> RetryConfig {
+        RetryConfig {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            jitter: false,
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let config = new_config(5);
+        let delays: Vec<_> = (0..4).map(|retries| config.delay(retries)).collect();
+        assert_eq!(
+            vec![1, 2, 4, 4],
+            delays
+                .iter()
+                .map(|delay| delay.as_millis())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Duration::from_millis(4), config.delay(100));
+
+        let config = RetryConfig {
+            jitter: true,
+            ..config
+        };
+        for retries in 0..4 {
+            let delay = config.delay(retries);
+            let max_delay = new_config(5).delay(retries);
+            assert!(delay >= max_delay / 2 && delay <= max_delay, "{delay:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let config = new_config(2);
+        let attempts = AtomicUsize::new(0);
+        let retry_total = OBJECT_STORE_RETRY_TOTAL.with_label_values(&["test_retry"]);
+        let before = retry_total.get();
+
+        // Succeeds after a retry.
+        let result = config
+            .retry("test_retry", "a", || async {
+                if attempts.fetch_add(1, Ordering::Relaxed) == 0 {
+                    Err(object_store::Error::new(ErrorKind::Unexpected, "throttled")
+                        .set_temporary())
+                } else {
+                    Ok(1)
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(1, result);
+        assert_eq!(2, attempts.load(Ordering::Relaxed));
+        assert_eq!(before + 1, retry_total.get());
+
+        // Gives up after max retries.
+        attempts.store(0, Ordering::Relaxed);
+        let err = config
+            .retry("test_retry", "a", || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(
+                    object_store::Error::new(ErrorKind::Unexpected, "throttled").set_temporary(),
+                )
+            })
+            .await
+            .unwrap_err();
+        assert!(err.is_temporary());
+        assert_eq!(3, attempts.load(Ordering::Relaxed));
+
+        // Permanent errors are not retried.
+        attempts.store(0, Ordering::Relaxed);
+        maybe_retry(Some(&config), "test_retry", "a", || async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err::<(), _>(object_store::Error::new(ErrorKind::NotFound, "not found"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(1, attempts.load(Ordering::Relaxed));
+    }
+}
@@ -22,11 +22,17 @@ use common_time::Timestamp;
 use crate::sst::file::{FileHandle, FileId, FileMeta, Level, MAX_LEVEL};
 use crate::sst::file_purger::FilePurgerRef;
 
+/// Number of levels of a new version, regions not using the leveled compaction only
+/// have these levels.
+const DEFAULT_NUM_LEVELS: Level = 2;
+
 /// A version of all SSTs in a region.
 #[derive(Debug, Clone)]
 pub(crate) struct SstVersion {
     /// SST metadata organized by levels.
-    levels: LevelMetaArray,
+    ///
+    /// Levels are added on demand when adding files to them, up to [MAX_LEVEL] levels.
+    levels: Vec<LevelMeta>,
 }
 
 pub(crate) type SstVersionRef = Arc<SstVersion>;
@@ -35,7 +41,7 @@ impl SstVersion {
     /// Returns a new [SstVersion].
     pub(crate) fn new() -> SstVersion {
         SstVersion {
-            levels: new_level_meta_vec(),
+            levels: (0..DEFAULT_NUM_LEVELS).map(LevelMeta::new).collect(),
         }
     }
 
@@ -47,7 +53,7 @@ impl SstVersion {
     /// Add files to the version.
     ///
     /// # Panics
-    /// Panics if level of [FileMeta] is greater than [MAX_LEVEL].
+    /// Panics if level of [FileMeta] is not less than [MAX_LEVEL].
     pub(crate) fn add_files(
         &mut self,
         file_purger: FilePurgerRef,
@@ -55,6 +61,15 @@ impl SstVersion {
     ) {
         for file in files_to_add {
             let level = file.level;
+            assert!(
+                level < MAX_LEVEL,
+                "Invalid level {level} of file {}",
+                file.file_id
+            );
+            while self.levels.len() <= level as usize {
+                let next = self.levels.len() as Level;
+                self.levels.push(LevelMeta::new(next));
+            }
             let handle = FileHandle::new(file, file_purger.clone());
             let file_id = handle.file_id();
             let old = self.levels[level as usize].files.insert(file_id, handle);
@@ -63,18 +78,44 @@ impl SstVersion {
     }
 
     /// Remove files from the version.
-    ///
-    /// # Panics
-    /// Panics if level of [FileMeta] is greater than [MAX_LEVEL].
     pub(crate) fn remove_files(&mut self, files_to_remove: impl Iterator<Item = FileMeta>) {
         for file in files_to_remove {
-            let level = file.level;
-            if let Some(handle) = self.levels[level as usize].files.remove(&file.file_id) {
+            let Some(level) = self.levels.get_mut(file.level as usize) else {
+                continue;
+            };
+            if let Some(handle) = level.files.remove(&file.file_id) {
                 handle.mark_deleted();
             }
         }
     }
 
+    /// Replaces metas of files in the version, e.g. after building indexes of the files.
+    /// Files not in the version are ignored.
+    ///
+    /// Replaced handles aren't marked as deleted so the files are kept. If the index file
+    /// of a file is replaced, the old index file is deleted once the old handle is dropped.
+    pub(crate) fn update_files(
+        &mut self,
+        file_purger: FilePurgerRef,
+        files_to_update: impl Iterator<Item = FileMeta>,
+    ) {
+        for file in files_to_update {
+            let Some(level) = self.levels.get_mut(file.level as usize) else {
+                continue;
+            };
+            let files = &mut level.files;
+            let Some(old) = files.get(&file.file_id) else {
+                continue;
+            };
+            if old.index_file_id() != file.index_file_id() {
+                old.mark_index_replaced();
+            }
+            let handle = FileHandle::new(file, file_purger.clone());
+            handle.set_compacting(old.compacting());
+            files.insert(handle.file_id(), handle);
+        }
+    }
+
     /// Marks all SSTs in this version as deleted.
     pub(crate) fn mark_all_deleted(&self) {
         for level_meta in &self.levels {
@@ -102,10 +143,6 @@ impl SstVersion {
     }
 }
 
-// We only has fixed number of level, so we use array to hold elements. This implementation
-// detail of LevelMetaArray should not be exposed to users of [LevelMetas].
-type LevelMetaArray = [LevelMeta; MAX_LEVEL as usize];
-
 /// Metadata of files in the same SST level.
 #[derive(Clone)]
 pub struct LevelMeta {
@@ -149,11 +186,3 @@ impl fmt::Debug for LevelMeta {
             .finish()
     }
 }
-
-fn new_level_meta_vec() -> LevelMetaArray {
-    (0u8..MAX_LEVEL)
-        .map(LevelMeta::new)
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap() // safety: LevelMetaArray is a fixed length array with length MAX_LEVEL
-}
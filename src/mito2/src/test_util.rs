@@ -31,6 +31,7 @@ use api::v1::value::ValueData;
 use api::v1::{OpType, Row, Rows, SemanticType};
 use common_datasource::compression::CompressionType;
 use common_test_util::temp_dir::{create_temp_dir, TempDir};
+use common_time::clock::ClockRef;
 use datatypes::arrow::array::{TimestampMillisecondArray, UInt64Array, UInt8Array};
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::ColumnSchema;
@@ -47,7 +48,7 @@ use store_api::region_request::{
 };
 use store_api::storage::{ColumnId, RegionId};
 
-use crate::config::MitoConfig;
+use crate::config::{MitoConfig, SstPathLayout};
 use crate::engine::listener::EventListenerRef;
 use crate::engine::{MitoEngine, MITO_ENGINE_NAME};
 use crate::error::Result;
@@ -164,9 +165,40 @@ impl TestEnv {
         let object_store_manager = Arc::new(object_store_manager);
         self.logstore = Some(logstore.clone());
         self.object_store_manager = Some(object_store_manager.clone());
-        MitoEngine::new_for_test(config, logstore, object_store_manager, manager, listener)
-            .await
-            .unwrap()
+        MitoEngine::new_for_test(
+            config,
+            logstore,
+            object_store_manager,
+            manager,
+            listener,
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Creates a new engine with specific config and clock under this env.
+    pub async fn create_engine_with_clock(
+        &mut self,
+        config: MitoConfig,
+        clock: ClockRef,
+    ) -> MitoEngine {
+        let (log_store, object_store_manager) = self.create_log_and_object_store_manager().await;
+
+        let logstore = Arc::new(log_store);
+        let object_store_manager = Arc::new(object_store_manager);
+        self.logstore = Some(logstore.clone());
+        self.object_store_manager = Some(object_store_manager.clone());
+        MitoEngine::new_for_test(
+            config,
+            logstore,
+            object_store_manager,
+            None,
+            None,
+            Some(clock),
+        )
+        .await
+        .unwrap()
     }
 
     pub async fn create_engine_with_multiple_object_stores(
@@ -195,9 +227,16 @@ impl TestEnv {
         let object_store_manager = Arc::new(object_store_manager);
         self.logstore = Some(logstore.clone());
         self.object_store_manager = Some(object_store_manager.clone());
-        MitoEngine::new_for_test(config, logstore, object_store_manager, manager, listener)
-            .await
-            .unwrap()
+        MitoEngine::new_for_test(
+            config,
+            logstore,
+            object_store_manager,
+            manager,
+            listener,
+            None,
+        )
+        .await
+        .unwrap()
     }
 
     /// Reopen the engine.
@@ -291,10 +330,11 @@ impl TestEnv {
             object_store,
             compress_type,
             checkpoint_distance,
+            list_page_size: 0,
         };
 
         if let Some(metadata) = initial_metadata {
-            RegionManifestManager::new(metadata, manifest_opts)
+            RegionManifestManager::new(metadata, SstPathLayout::default(), manifest_opts)
                 .await
                 .map(Some)
         } else {
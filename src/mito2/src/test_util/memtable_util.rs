@@ -26,6 +26,7 @@ use crate::memtable::{
     BoxedBatchIterator, KeyValues, Memtable, MemtableBuilder, MemtableId, MemtableRef,
     MemtableStats,
 };
+use crate::region::options::DuplicateMode;
 
 /// Empty memtable for test.
 #[derive(Debug, Default)]
@@ -77,7 +78,7 @@ pub(crate) struct EmptyMemtableBuilder {
 }
 
 impl MemtableBuilder for EmptyMemtableBuilder {
-    fn build(&self, _metadata: &RegionMetadataRef) -> MemtableRef {
+    fn build(&self, _metadata: &RegionMetadataRef, _duplicate_mode: DuplicateMode) -> MemtableRef {
         Arc::new(EmptyMemtable::new(
             self.next_id.fetch_add(1, Ordering::Relaxed),
         ))
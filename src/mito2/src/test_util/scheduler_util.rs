@@ -17,16 +17,20 @@
 use std::sync::Arc;
 
 use common_test_util::temp_dir::{create_temp_dir, TempDir};
+use common_time::clock::SystemClock;
 use object_store::services::Fs;
 use object_store::ObjectStore;
 use tokio::sync::mpsc::Sender;
 
 use crate::access_layer::{AccessLayer, AccessLayerRef};
 use crate::cache::CacheManager;
-use crate::compaction::CompactionScheduler;
+use crate::compaction::limiter::CompactionLimiter;
+use crate::compaction::{CompactionPauser, CompactionScheduler};
+use crate::config::MitoConfig;
 use crate::flush::FlushScheduler;
 use crate::request::WorkerRequest;
 use crate::schedule::scheduler::{LocalScheduler, SchedulerRef};
+use crate::sst::location::FlatPath;
 
 /// Scheduler mocker.
 pub(crate) struct SchedulerEnv {
@@ -44,7 +48,11 @@ impl SchedulerEnv {
         let mut builder = Fs::default();
         builder.root(path.path().to_str().unwrap());
         let object_store = ObjectStore::new(builder).unwrap().finish();
-        let access_layer = Arc::new(AccessLayer::new("", object_store.clone()));
+        let access_layer = Arc::new(AccessLayer::new(
+            "",
+            object_store.clone(),
+            Arc::new(FlatPath),
+        ));
 
         SchedulerEnv {
             path: create_temp_dir(""),
@@ -66,7 +74,16 @@ impl SchedulerEnv {
     ) -> CompactionScheduler {
         let scheduler = self.get_scheduler();
 
-        CompactionScheduler::new(scheduler, request_sender, Arc::new(CacheManager::default()))
+        CompactionScheduler::new(
+            scheduler,
+            request_sender,
+            Arc::new(CacheManager::default()),
+            Arc::new(CompactionLimiter::new(
+                MitoConfig::default().max_concurrent_compactions,
+            )),
+            Arc::new(CompactionPauser::default()),
+            Arc::new(SystemClock),
+        )
     }
 
     /// Creates a new flush scheduler.
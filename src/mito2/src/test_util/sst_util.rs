@@ -108,6 +108,10 @@ pub fn sst_file_handle(start_ms: i64, end_ms: i64) -> FileHandle {
             file_size: 0,
             available_indexes: Default::default(),
             index_file_size: 0,
+            rolled_up: false,
+            index_stats: Vec::new(),
+            footer_checksum: None,
+            index_file_id: None,
         },
         file_purger,
     )
@@ -26,6 +26,7 @@ use store_api::storage::RegionId;
 
 use crate::manifest::action::RegionEdit;
 use crate::memtable::{MemtableBuilder, MemtableBuilderRef};
+use crate::region::options::DuplicateMode;
 use crate::region::version::{Version, VersionBuilder, VersionControl};
 use crate::sst::file::{FileId, FileMeta};
 use crate::sst::file_purger::FilePurgerRef;
@@ -98,6 +99,10 @@ impl VersionControlBuilder {
                 file_size: 0, // We don't care file size.
                 available_indexes: Default::default(),
                 index_file_size: 0,
+                rolled_up: false,
+                index_stats: Vec::new(),
+                footer_checksum: None,
+                index_file_id: None,
             },
         );
         self
@@ -105,7 +110,9 @@ impl VersionControlBuilder {
 
     pub(crate) fn build_version(&self) -> Version {
         let metadata = Arc::new(self.metadata.clone());
-        let mutable = self.memtable_builder.build(&metadata);
+        let mutable = self
+            .memtable_builder
+            .build(&metadata, DuplicateMode::default());
         VersionBuilder::new(metadata, mutable)
             .add_files(self.file_purger.clone(), self.files.values().cloned())
             .build()
@@ -140,6 +147,10 @@ pub(crate) fn apply_edit(
                 file_size: 0, // We don't care file size.
                 available_indexes: Default::default(),
                 index_file_size: 0,
+                rolled_up: false,
+                index_stats: Vec::new(),
+                footer_checksum: None,
+                index_file_id: None,
             }
         })
         .collect();
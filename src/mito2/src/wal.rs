@@ -31,7 +31,8 @@ use store_api::logstore::{AppendBatchResponse, LogStore};
 use store_api::storage::RegionId;
 
 use crate::error::{
-    DecodeWalSnafu, DeleteWalSnafu, EncodeWalSnafu, ReadWalSnafu, Result, WriteWalSnafu,
+    DecodeWalSnafu, DeleteWalSnafu, EncodeWalSnafu, ReadWalSnafu, Result, SyncWalSnafu,
+    WriteWalSnafu,
 };
 
 /// WAL entry id.
@@ -110,6 +111,15 @@ impl<S: LogStore> Wal<S> {
             .map_err(BoxedError::new)
             .context(DeleteWalSnafu { region_id })
     }
+
+    /// Persists all entries written to the WAL to durable storage.
+    pub async fn sync(&self) -> Result<()> {
+        self.store
+            .sync()
+            .await
+            .map_err(BoxedError::new)
+            .context(SyncWalSnafu)
+    }
 }
 
 /// Decode Wal entry from log store.
@@ -19,8 +19,10 @@ mod handle_catchup;
 mod handle_close;
 mod handle_compaction;
 mod handle_create;
+mod handle_delete_range;
 mod handle_drop;
 mod handle_flush;
+mod handle_index;
 mod handle_open;
 mod handle_truncate;
 mod handle_write;
@@ -33,29 +35,38 @@ use std::time::Duration;
 
 use common_runtime::JoinHandle;
 use common_telemetry::{error, info, warn};
+use common_time::clock::{ClockRef, SystemClock};
 use futures::future::try_join_all;
 use object_store::manager::ObjectStoreManagerRef;
+use object_store::ObjectStore;
 use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::region_engine::SetReadonlyResponse;
+use store_api::region_request::RegionCompactRequest;
 use store_api::storage::RegionId;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, oneshot, Mutex};
 
+use crate::access_layer::{new_fs_object_store, FsStoreConfig};
 use crate::cache::write_cache::{WriteCache, WriteCacheRef};
 use crate::cache::{CacheManager, CacheManagerRef};
-use crate::compaction::CompactionScheduler;
-use crate::config::MitoConfig;
-use crate::error::{JoinSnafu, Result, WorkerStoppedSnafu};
-use crate::flush::{FlushScheduler, WriteBufferManagerImpl, WriteBufferManagerRef};
+use crate::compaction::limiter::{CompactionLimiter, CompactionLimiterRef};
+use crate::compaction::{CompactionPauser, CompactionPauserRef, CompactionScheduler};
+use crate::config::{IntermediateBackend, MitoConfig};
+use crate::error::{JoinSnafu, RegionNotFoundSnafu, Result, SyncRegionSnafu, WorkerStoppedSnafu};
+use crate::flush::{
+    AdaptiveFlushPolicy, FlushScheduler, WriteBufferManagerImpl, WriteBufferManagerRef,
+};
 use crate::memtable::time_series::TimeSeriesMemtableBuilder;
 use crate::memtable::MemtableBuilderRef;
 use crate::region::{MitoRegionRef, RegionMap, RegionMapRef};
 use crate::request::{
-    BackgroundNotify, DdlRequest, SenderDdlRequest, SenderWriteRequest, WorkerRequest,
+    BackgroundNotify, DdlRequest, OptionOutputTx, SenderDdlRequest, SenderWriteRequest,
+    WorkerRequest,
 };
 use crate::schedule::scheduler::{LocalScheduler, SchedulerRef};
-use crate::wal::Wal;
+use crate::sst::index::limiter::{IndexBuildLimiter, IndexBuildLimiterRef};
+use crate::wal::{EntryId, Wal};
 
 /// Identifier for a worker.
 pub(crate) type WorkerId = u32;
@@ -106,6 +117,8 @@ pub(crate) struct WorkerGroup {
     scheduler: SchedulerRef,
     /// Cache.
     cache_manager: CacheManagerRef,
+    /// Pauser of compactions in all workers.
+    compaction_pauser: CompactionPauserRef,
 }
 
 impl WorkerGroup {
@@ -117,19 +130,26 @@ impl WorkerGroup {
         log_store: Arc<S>,
         object_store_manager: ObjectStoreManagerRef,
     ) -> Result<WorkerGroup> {
-        let write_buffer_manager = Arc::new(WriteBufferManagerImpl::new(
-            config.global_write_buffer_size.as_bytes() as usize,
-        ));
+        let write_buffer_manager = Arc::new(write_buffer_manager_from_config(&config));
         let scheduler = Arc::new(LocalScheduler::new(config.max_background_jobs));
+        let compaction_limiter =
+            Arc::new(CompactionLimiter::new(config.max_concurrent_compactions));
+        let compaction_pauser = Arc::new(CompactionPauser::default());
         let write_cache = write_cache_from_config(&config, object_store_manager.clone()).await?;
+        let intermediate_store = intermediate_store_from_config(&config).await?;
+        let index_build_limiter =
+            Arc::new(IndexBuildLimiter::new(config.max_concurrent_index_builds));
         let cache_manager = Arc::new(
             CacheManager::builder()
                 .sst_meta_cache_size(config.sst_meta_cache_size.as_bytes())
                 .vector_cache_size(config.vector_cache_size.as_bytes())
                 .page_cache_size(config.page_cache_size.as_bytes())
+                .sst_reader_cache_size(config.sst_reader_cache_size)
+                .row_group_hint_cache_size(config.row_group_hint_cache_size as u64)
                 .write_cache(write_cache)
                 .build(),
         );
+        let clock: ClockRef = Arc::new(SystemClock);
 
         let workers = (0..config.num_workers)
             .map(|id| {
@@ -140,8 +160,13 @@ impl WorkerGroup {
                     object_store_manager: object_store_manager.clone(),
                     write_buffer_manager: write_buffer_manager.clone(),
                     scheduler: scheduler.clone(),
+                    compaction_limiter: compaction_limiter.clone(),
+                    compaction_pauser: compaction_pauser.clone(),
                     listener: WorkerListener::default(),
                     cache_manager: cache_manager.clone(),
+                    intermediate_store: intermediate_store.clone(),
+                    index_build_limiter: index_build_limiter.clone(),
+                    clock: clock.clone(),
                 }
                 .start()
             })
@@ -151,6 +176,7 @@ impl WorkerGroup {
             workers,
             scheduler,
             cache_manager,
+            compaction_pauser,
         })
     }
 
@@ -187,11 +213,45 @@ impl WorkerGroup {
         self.worker(region_id).get_region(region_id)
     }
 
+    /// Returns all regions in the group.
+    pub(crate) fn list_regions(&self) -> Vec<MitoRegionRef> {
+        self.workers
+            .iter()
+            .flat_map(|worker| worker.regions.list_regions())
+            .collect()
+    }
+
     /// Returns cache of the group.
     pub(crate) fn cache_manager(&self) -> CacheManagerRef {
         self.cache_manager.clone()
     }
 
+    /// Pauses compactions of the region, or all regions if `region_id` is `None`.
+    pub(crate) fn pause_compaction(&self, region_id: Option<RegionId>) {
+        self.compaction_pauser.pause(region_id);
+    }
+
+    /// Resumes compactions of the region, or all regions if `region_id` is `None`,
+    /// and schedules compactions for resumed regions.
+    pub(crate) async fn resume_compaction(&self, region_id: Option<RegionId>) -> Result<()> {
+        self.compaction_pauser.resume(region_id);
+
+        let regions = match region_id {
+            Some(region_id) => self.get_region(region_id).into_iter().collect(),
+            None => self.list_regions(),
+        };
+        for region in regions {
+            let request = WorkerRequest::Ddl(SenderDdlRequest {
+                region_id: region.region_id,
+                sender: OptionOutputTx::none(),
+                request: DdlRequest::Compact(RegionCompactRequest {}),
+            });
+            self.submit_to_worker(region.region_id, request).await?;
+        }
+
+        Ok(())
+    }
+
     /// Get worker for specific `region_id`.
     fn worker(&self, region_id: RegionId) -> &RegionWorker {
         let mut hasher = DefaultHasher::new();
@@ -206,7 +266,7 @@ impl WorkerGroup {
 // Tests methods.
 #[cfg(any(test, feature = "test"))]
 impl WorkerGroup {
-    /// Starts a worker group with `write_buffer_manager` and `listener` for tests.
+    /// Starts a worker group with `write_buffer_manager`, `listener` and `clock` for tests.
     ///
     /// The number of workers should be power of two.
     pub(crate) async fn start_for_test<S: LogStore>(
@@ -215,22 +275,29 @@ impl WorkerGroup {
         object_store_manager: ObjectStoreManagerRef,
         write_buffer_manager: Option<WriteBufferManagerRef>,
         listener: Option<crate::engine::listener::EventListenerRef>,
+        clock: Option<ClockRef>,
     ) -> Result<WorkerGroup> {
-        let write_buffer_manager = write_buffer_manager.unwrap_or_else(|| {
-            Arc::new(WriteBufferManagerImpl::new(
-                config.global_write_buffer_size.as_bytes() as usize,
-            ))
-        });
+        let write_buffer_manager = write_buffer_manager
+            .unwrap_or_else(|| Arc::new(write_buffer_manager_from_config(&config)));
         let scheduler = Arc::new(LocalScheduler::new(config.max_background_jobs));
+        let compaction_limiter =
+            Arc::new(CompactionLimiter::new(config.max_concurrent_compactions));
+        let compaction_pauser = Arc::new(CompactionPauser::default());
         let write_cache = write_cache_from_config(&config, object_store_manager.clone()).await?;
+        let intermediate_store = intermediate_store_from_config(&config).await?;
+        let index_build_limiter =
+            Arc::new(IndexBuildLimiter::new(config.max_concurrent_index_builds));
         let cache_manager = Arc::new(
             CacheManager::builder()
                 .sst_meta_cache_size(config.sst_meta_cache_size.as_bytes())
                 .vector_cache_size(config.vector_cache_size.as_bytes())
                 .page_cache_size(config.page_cache_size.as_bytes())
+                .sst_reader_cache_size(config.sst_reader_cache_size)
+                .row_group_hint_cache_size(config.row_group_hint_cache_size as u64)
                 .write_cache(write_cache)
                 .build(),
         );
+        let clock = clock.unwrap_or_else(|| Arc::new(SystemClock));
 
         let workers = (0..config.num_workers)
             .map(|id| {
@@ -241,8 +308,13 @@ impl WorkerGroup {
                     object_store_manager: object_store_manager.clone(),
                     write_buffer_manager: write_buffer_manager.clone(),
                     scheduler: scheduler.clone(),
+                    compaction_limiter: compaction_limiter.clone(),
+                    compaction_pauser: compaction_pauser.clone(),
                     listener: WorkerListener::new(listener.clone()),
                     cache_manager: cache_manager.clone(),
+                    intermediate_store: intermediate_store.clone(),
+                    index_build_limiter: index_build_limiter.clone(),
+                    clock: clock.clone(),
                 }
                 .start()
             })
@@ -252,6 +324,7 @@ impl WorkerGroup {
             workers,
             scheduler,
             cache_manager,
+            compaction_pauser,
         })
     }
 }
@@ -260,6 +333,16 @@ fn value_to_index(value: usize, num_workers: usize) -> usize {
     value % num_workers
 }
 
+/// Returns the write buffer manager with the flush policy in the `config`.
+fn write_buffer_manager_from_config(config: &MitoConfig) -> WriteBufferManagerImpl {
+    let manager = WriteBufferManagerImpl::new(config.global_write_buffer_size.as_bytes() as usize);
+    if config.adaptive_flush {
+        manager.with_adaptive_flush(AdaptiveFlushPolicy::new(config))
+    } else {
+        manager
+    }
+}
+
 async fn write_cache_from_config(
     config: &MitoConfig,
     object_store_manager: ObjectStoreManagerRef,
@@ -272,14 +355,43 @@ async fn write_cache_from_config(
     warn!("Write cache is an experimental feature");
 
     let cache = WriteCache::new_fs(
-        &config.experimental_write_cache_path,
+        &FsStoreConfig::new(&config.experimental_write_cache_path)
+            .with_concurrency(config.experimental_write_cache_concurrency),
         object_store_manager,
         config.experimental_write_cache_size,
+        config.experimental_write_cache_eviction_policy,
+    )
+    .await?
+    .with_bypass_size(
+        (config.experimental_write_cache_bypass_size.as_bytes() > 0)
+            .then_some(config.experimental_write_cache_bypass_size),
     )
-    .await?;
+    .with_local_write_buffer_size(
+        (config.experimental_write_cache_write_buffer_size.as_bytes() > 0)
+            .then_some(config.experimental_write_cache_write_buffer_size),
+    )
+    .with_staging_compression(
+        config
+            .experimental_write_cache_staging_compression
+            .compression(),
+    );
     Ok(Some(Arc::new(cache)))
 }
 
+/// Returns the local store of index intermediate files, or `None` if intermediate
+/// files are stored in the object store of each region.
+async fn intermediate_store_from_config(config: &MitoConfig) -> Result<Option<ObjectStore>> {
+    match config.index_intermediate_backend {
+        IntermediateBackend::ObjectStore => Ok(None),
+        IntermediateBackend::LocalFs => {
+            let store_config = FsStoreConfig::new(&config.index_intermediate_path)
+                .with_concurrency(config.index_intermediate_concurrency);
+            let store = new_fs_object_store(&store_config).await?;
+            Ok(Some(store))
+        }
+    }
+}
+
 /// Worker start config.
 struct WorkerStarter<S> {
     id: WorkerId,
@@ -288,8 +400,14 @@ struct WorkerStarter<S> {
     object_store_manager: ObjectStoreManagerRef,
     write_buffer_manager: WriteBufferManagerRef,
     scheduler: SchedulerRef,
+    compaction_limiter: CompactionLimiterRef,
+    compaction_pauser: CompactionPauserRef,
     listener: WorkerListener,
     cache_manager: CacheManagerRef,
+    /// Local store of index intermediate files.
+    intermediate_store: Option<ObjectStore>,
+    index_build_limiter: IndexBuildLimiterRef,
+    clock: ClockRef,
 }
 
 impl<S: LogStore> WorkerStarter<S> {
@@ -309,9 +427,10 @@ impl<S: LogStore> WorkerStarter<S> {
             wal: Wal::new(self.log_store),
             object_store_manager: self.object_store_manager.clone(),
             running: running.clone(),
-            memtable_builder: Arc::new(TimeSeriesMemtableBuilder::new(Some(
-                self.write_buffer_manager.clone(),
-            ))),
+            memtable_builder: Arc::new(
+                TimeSeriesMemtableBuilder::new(Some(self.write_buffer_manager.clone()))
+                    .with_clock(self.clock.clone()),
+            ),
             scheduler: self.scheduler.clone(),
             write_buffer_manager: self.write_buffer_manager,
             flush_scheduler: FlushScheduler::new(self.scheduler.clone()),
@@ -319,10 +438,16 @@ impl<S: LogStore> WorkerStarter<S> {
                 self.scheduler,
                 sender.clone(),
                 self.cache_manager.clone(),
+                self.compaction_limiter,
+                self.compaction_pauser,
+                self.clock.clone(),
             ),
             stalled_requests: StalledRequests::default(),
             listener: self.listener,
             cache_manager: self.cache_manager,
+            intermediate_store: self.intermediate_store,
+            index_build_limiter: self.index_build_limiter,
+            clock: self.clock,
         };
         let handle = common_runtime::spawn_write(async move {
             worker_thread.run().await;
@@ -479,6 +604,13 @@ struct RegionWorkerLoop<S> {
     listener: WorkerListener,
     /// Cache.
     cache_manager: CacheManagerRef,
+    /// Local store of index intermediate files, `None` means using the object store
+    /// of the region.
+    intermediate_store: Option<ObjectStore>,
+    /// Limiter of concurrent index builds in the node.
+    index_build_limiter: IndexBuildLimiterRef,
+    /// Clock to read the wall-clock time.
+    clock: ClockRef,
 }
 
 impl<S: LogStore> RegionWorkerLoop<S> {
@@ -508,6 +640,10 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 }
             }
 
+            if !self.config.write_coalesce_window.is_zero() {
+                self.coalesce_write_requests(&mut buffer).await;
+            }
+
             self.handle_requests(&mut buffer).await;
         }
 
@@ -516,12 +652,45 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         info!("Exit region worker thread {}", self.id);
     }
 
+    /// Waits for more write requests within the coalescing window so they are written
+    /// in one batch.
+    ///
+    /// It stops waiting once other requests arrive, e.g. flush or stop requests, so they
+    /// drain the coalesced write requests without waiting for the window.
+    async fn coalesce_write_requests(&mut self, buffer: &mut RequestBuffer) {
+        let mut num_rows = 0;
+        for request in buffer.iter() {
+            let WorkerRequest::Write(sender_req) = request else {
+                return;
+            };
+            num_rows += sender_req.request.rows.rows.len();
+        }
+
+        let deadline = tokio::time::Instant::now() + self.config.write_coalesce_window;
+        while buffer.len() < self.config.worker_request_batch_size
+            && num_rows < self.config.write_coalesce_max_rows
+        {
+            // Stops if the window elapses or the channel is closed.
+            let Ok(Some(request)) = tokio::time::timeout_at(deadline, self.receiver.recv()).await
+            else {
+                return;
+            };
+            let WorkerRequest::Write(sender_req) = &request else {
+                buffer.push(request);
+                return;
+            };
+            num_rows += sender_req.request.rows.rows.len();
+            buffer.push(request);
+        }
+    }
+
     /// Dispatches and processes requests.
     ///
     /// `buffer` should be empty.
     async fn handle_requests(&mut self, buffer: &mut RequestBuffer) {
         let mut write_requests = Vec::with_capacity(buffer.len());
         let mut ddl_requests = Vec::with_capacity(buffer.len());
+        let mut sync_requests = Vec::new();
         for worker_req in buffer.drain(..) {
             match worker_req {
                 WorkerRequest::Write(sender_req) => {
@@ -537,6 +706,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 WorkerRequest::SetReadonlyGracefully { region_id, sender } => {
                     self.set_readonly_gracefully(region_id, sender).await;
                 }
+                WorkerRequest::Sync { region_id, sender } => {
+                    sync_requests.push((region_id, sender));
+                }
                 // We receive a stop signal, but we still want to process remaining
                 // requests. The worker thread will then check the running flag and
                 // then exit.
@@ -550,6 +722,10 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         // considering existing write requests.
         self.handle_write_requests(write_requests, true).await;
 
+        // Syncs the WAL after handling write requests so the synced entries include
+        // writes received before the sync requests.
+        self.handle_sync_requests(sync_requests).await;
+
         self.handle_ddl_requests(ddl_requests).await;
     }
 
@@ -581,6 +757,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 }
                 DdlRequest::Truncate(_) => self.handle_truncate_request(ddl.region_id).await,
                 DdlRequest::Catchup(req) => self.handle_catchup_request(ddl.region_id, req).await,
+                DdlRequest::DeleteRange(req) => {
+                    self.handle_delete_range_request(ddl.region_id, req).await
+                }
             };
 
             ddl.sender.send(res);
@@ -598,6 +777,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 self.handle_compaction_finished(region_id, req).await
             }
             BackgroundNotify::CompactionFailed(req) => self.handle_compaction_failure(req).await,
+            BackgroundNotify::IndexBuildFinished(req) => {
+                self.handle_index_build_finished(region_id, req).await
+            }
         }
     }
 
@@ -616,6 +798,41 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             let _ = sender.send(SetReadonlyResponse::NotFound);
         }
     }
+
+    /// Handles sync requests.
+    async fn handle_sync_requests(
+        &mut self,
+        sync_requests: Vec<(RegionId, oneshot::Sender<Result<EntryId>>)>,
+    ) {
+        let mut to_sync = Vec::with_capacity(sync_requests.len());
+        for (region_id, sender) in sync_requests {
+            let Some(region) = self.regions.get_region(region_id) else {
+                let _ = sender.send(RegionNotFoundSnafu { region_id }.fail());
+                continue;
+            };
+
+            // Reads the last entry id before syncing so the returned entry is durable.
+            let last_entry_id = region.version_control.current().last_entry_id;
+            to_sync.push((region_id, last_entry_id, sender));
+        }
+        if to_sync.is_empty() {
+            return;
+        }
+
+        // Syncs the WAL in background so the worker keeps handling requests while the
+        // log store flushes its files, one sync persists entries of all regions.
+        let wal = self.wal.clone();
+        common_runtime::spawn_bg(async move {
+            let result = wal.sync().await.map_err(Arc::new);
+            for (region_id, last_entry_id, sender) in to_sync {
+                let result = match &result {
+                    Ok(()) => Ok(last_entry_id),
+                    Err(e) => Err(e.clone()).context(SyncRegionSnafu { region_id }),
+                };
+                let _ = sender.send(result);
+            }
+        });
+    }
 }
 
 impl<S> RegionWorkerLoop<S> {
@@ -658,6 +875,16 @@ impl WorkerListener {
         let _ = region_id;
     }
 
+    /// Worker writes a batch of write requests.
+    pub(crate) fn on_write_batch(&self, num_requests: usize) {
+        #[cfg(any(test, feature = "test"))]
+        if let Some(listener) = &self.listener {
+            listener.on_write_batch(num_requests);
+        }
+        // Avoid compiler warning.
+        let _ = num_requests;
+    }
+
     /// Engine is stalled.
     pub(crate) fn on_write_stall(&self) {
         #[cfg(any(test, feature = "test"))]
@@ -132,6 +132,7 @@ async fn alter_region_schema(
     // Persist the metadata to region's manifest.
     let change = RegionChange {
         metadata: new_meta.clone(),
+        sst_path_layout: None,
     };
     let action_list = RegionMetaActionList::with_action(RegionMetaAction::Change(change));
     region.manifest_manager.update(action_list).await?;
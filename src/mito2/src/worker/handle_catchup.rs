@@ -56,6 +56,10 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                     self.scheduler.clone(),
                 )
                 .cache(Some(self.cache_manager.clone()))
+                .intermediate_store(self.intermediate_store.clone())
+                .index_build_limiter(Some(self.index_build_limiter.clone()))
+                .request_sender(Some(self.sender.clone()))
+                .clock(self.clock.clone())
                 .options(region.version().options.clone())
                 .skip_wal_replay(true)
                 .open(&self.config, &self.wal)
@@ -79,6 +83,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             flushed_entry_id,
             &region.version_control,
             self.config.allow_stale_entries,
+            &self.clock,
         )
         .await?;
         info!(
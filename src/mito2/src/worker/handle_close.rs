@@ -19,7 +19,7 @@ use store_api::region_request::AffectedRows;
 use store_api::storage::RegionId;
 
 use crate::error::Result;
-use crate::metrics::REGION_COUNT;
+use crate::metrics::{remove_region_metrics, REGION_COUNT};
 use crate::worker::RegionWorkerLoop;
 
 impl<S> RegionWorkerLoop<S> {
@@ -43,6 +43,7 @@ impl<S> RegionWorkerLoop<S> {
         info!("Region {} closed", region_id);
 
         REGION_COUNT.dec();
+        remove_region_metrics(region_id);
 
         Ok(0)
     }
@@ -28,7 +28,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         region_id: RegionId,
         mut sender: OptionOutputTx,
     ) {
-        let Some(region) = self.regions.writable_region_or(region_id, &mut sender) else {
+        let Some(region) = self.regions.mutable_region_or(region_id, &mut sender) else {
             return;
         };
         COMPACTION_REQUEST_COUNT.inc();
@@ -88,6 +88,11 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         // compaction finished.
         request.on_success();
 
+        if region.is_frozen() {
+            // The region is frozen while compacting, cancels pending compactions.
+            self.compaction_scheduler.on_region_frozen(region_id);
+            return;
+        }
         // Schedule next compaction if necessary.
         self.compaction_scheduler
             .on_compaction_finished(region_id, self.config.clone());
@@ -65,6 +65,10 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         .metadata(metadata)
         .parse_options(request.options)?
         .cache(Some(self.cache_manager.clone()))
+        .intermediate_store(self.intermediate_store.clone())
+        .index_build_limiter(Some(self.index_build_limiter.clone()))
+        .request_sender(Some(self.sender.clone()))
+        .clock(self.clock.clone())
         .create_or_open(&self.config, &self.wal)
         .await?;
 
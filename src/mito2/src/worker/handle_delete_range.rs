@@ -0,0 +1,88 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling delete range requests.
+
+use common_telemetry::info;
+use store_api::logstore::LogStore;
+use store_api::region_request::{AffectedRows, RegionDeleteRangeRequest};
+use store_api::storage::RegionId;
+
+use crate::error::Result;
+use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
+use crate::metrics::DELETE_FILES_DROPPED_TOTAL;
+use crate::sst::file::FileHandle;
+use crate::worker::RegionWorkerLoop;
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Removes SSTs whose time range is fully covered by the `request`.
+    ///
+    /// Rows in other SSTs and memtables are left to the caller. Returns the number of
+    /// removed SSTs.
+    pub(crate) async fn handle_delete_range_request(
+        &mut self,
+        region_id: RegionId,
+        request: RegionDeleteRangeRequest,
+    ) -> Result<AffectedRows> {
+        let region = self.regions.mutable_region(region_id)?;
+
+        let version = region.version();
+        // Files under compaction are still referenced by the compaction task, deletes
+        // them row by row instead.
+        let files_to_remove: Vec<_> = version
+            .ssts
+            .levels()
+            .iter()
+            .flat_map(|level| level.files())
+            .filter(|file| !file.compacting() && file_in_delete_range(file, &request))
+            .map(|file| file.meta())
+            .collect();
+        if files_to_remove.is_empty() {
+            return Ok(0);
+        }
+
+        let num_files = files_to_remove.len();
+        let edit = RegionEdit {
+            files_to_add: Vec::new(),
+            files_to_remove,
+            compaction_time_window: None,
+            flushed_entry_id: None,
+            flushed_sequence: None,
+        };
+        let action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit.clone()));
+        region.manifest_manager.update(action_list).await?;
+
+        region
+            .version_control
+            .apply_edit(edit, &[], region.file_purger.clone());
+        DELETE_FILES_DROPPED_TOTAL.inc_by(num_files as u64);
+
+        info!(
+            "Region {} removes {} files covered by delete range [{}, {})",
+            region_id,
+            num_files,
+            request.start.to_iso8601_string(),
+            request.end.to_iso8601_string()
+        );
+
+        Ok(num_files)
+    }
+}
+
+/// Returns true if all rows in the `file` are in the range of the `request`.
+fn file_in_delete_range(file: &FileHandle, request: &RegionDeleteRangeRequest) -> bool {
+    // end timestamp of a SST is inclusive.
+    let (start, end) = file.time_range();
+    request.start <= start && end < request.end
+}
@@ -26,7 +26,7 @@ use store_api::storage::RegionId;
 use tokio::time::sleep;
 
 use crate::error::{OpenDalSnafu, Result};
-use crate::metrics::REGION_COUNT;
+use crate::metrics::{remove_region_metrics, REGION_COUNT};
 use crate::region::RegionMapRef;
 use crate::worker::{RegionWorkerLoop, DROPPING_MARKER_FILE};
 
@@ -68,6 +68,7 @@ impl<S> RegionWorkerLoop<S> {
         );
 
         REGION_COUNT.dec();
+        remove_region_metrics(region_id);
 
         // detach a background task to delete the region dir
         let region_dir = region.access_layer.region_dir().to_owned();
@@ -139,12 +140,14 @@ pub(crate) async fn remove_region_dir_once(
     region_path: &str,
     object_store: &ObjectStore,
 ) -> Result<bool> {
-    // list all files under the given region path to check if there are un-deleted parquet files
+    // list all files under the given region path to check if there are un-deleted parquet files,
+    // SSTs may be under sub directories of the region path, depending on the path strategy
     let mut has_parquet_file = false;
     // record all paths that neither ends with .parquet nor the marker file
     let mut files_to_remove_first = vec![];
     let mut files = object_store
         .lister_with(region_path)
+        .recursive(true)
         .await
         .context(OpenDalSnafu)?;
     while let Some(file) = files.try_next().await.context(OpenDalSnafu)? {
@@ -17,7 +17,6 @@
 use std::sync::Arc;
 
 use common_telemetry::{error, info, warn};
-use common_time::util::current_time_millis;
 use store_api::logstore::LogStore;
 use store_api::region_request::RegionFlushRequest;
 use store_api::storage::RegionId;
@@ -80,7 +79,7 @@ impl<S> RegionWorkerLoop<S> {
     /// Find some regions to flush to reduce write buffer usage.
     fn flush_regions_on_engine_full(&mut self) -> Result<()> {
         let regions = self.regions.list_regions();
-        let now = current_time_millis();
+        let now = self.clock.now_millis();
         let min_last_flush_time = now - self.config.auto_flush_interval.as_millis() as i64;
         let mut max_mutable_size = 0;
         // Region with max mutable memtable size.
@@ -194,7 +193,11 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             &request.memtables_to_remove,
             region.file_purger.clone(),
         );
-        region.update_flush_millis();
+        region.update_flush_millis(self.clock.now_millis());
+        region
+            .version()
+            .memtables
+            .update_metrics(&region.memtable_metrics, self.clock.now_millis());
 
         // Delete wal.
         info!(
@@ -229,19 +232,21 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         // We already stalled these requests, don't stall them again.
         self.handle_write_requests(stalled.requests, false).await;
 
-        // Schedules compaction.
-        if let Err(e) = self.compaction_scheduler.schedule_compaction(
-            region.region_id,
-            &region.version_control,
-            &region.access_layer,
-            &region.file_purger,
-            OptionOutputTx::none(),
-            self.config.clone(),
-        ) {
-            warn!(
-                "Failed to schedule compaction after flush, region: {}, err: {}",
-                region.region_id, e
-            );
+        // Schedules compaction, frozen regions don't compact.
+        if !region.is_frozen() {
+            if let Err(e) = self.compaction_scheduler.schedule_compaction(
+                region.region_id,
+                &region.version_control,
+                &region.access_layer,
+                &region.file_purger,
+                OptionOutputTx::none(),
+                self.config.clone(),
+            ) {
+                warn!(
+                    "Failed to schedule compaction after flush, region: {}, err: {}",
+                    region.region_id, e
+                );
+            }
         }
 
         self.listener.on_flush_success(region_id);
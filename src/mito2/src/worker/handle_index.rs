@@ -0,0 +1,101 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling index related requests.
+
+use common_telemetry::{error, info};
+use store_api::logstore::LogStore;
+use store_api::storage::RegionId;
+
+use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
+use crate::request::{IndexBuildFinished, OnFailure};
+use crate::sst::file_purger::PurgeRequest;
+use crate::worker::RegionWorkerLoop;
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Handles indexes of SSTs are built, updates metas of the SSTs in the region
+    /// manifest and version.
+    pub(crate) async fn handle_index_build_finished(
+        &mut self,
+        region_id: RegionId,
+        mut request: IndexBuildFinished,
+    ) {
+        let Some(region) = self.regions.writable_region_or(region_id, &mut request) else {
+            return;
+        };
+
+        // Files might be compacted while building indexes, only updates files still
+        // in the region so we won't add them back. New index files of compacted files
+        // are orphans, removes them.
+        let version = region.version();
+        let (file_metas, orphans): (Vec<_>, Vec<_>) = std::mem::take(&mut request.file_metas)
+            .into_iter()
+            .partition(|file| {
+                version
+                    .ssts
+                    .levels()
+                    .get(file.level as usize)
+                    .is_some_and(|level| level.files.contains_key(&file.file_id))
+            });
+        for file_meta in orphans {
+            info!(
+                "Remove index of compacted file, region: {}, file_id: {}, index_file_id: {}",
+                region_id,
+                file_meta.file_id,
+                file_meta.index_file_id()
+            );
+            region.file_purger.send_request(PurgeRequest {
+                file_meta,
+                index_only: true,
+            });
+        }
+        if file_metas.is_empty() {
+            request.sender.send(Ok(0));
+            return;
+        }
+
+        // Updating an existing file in the manifest replaces its meta.
+        let edit = RegionEdit {
+            files_to_add: file_metas.clone(),
+            files_to_remove: Vec::new(),
+            compaction_time_window: None,
+            flushed_entry_id: None,
+            flushed_sequence: None,
+        };
+        let action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit));
+        if let Err(e) = region.manifest_manager.update(action_list).await {
+            error!(e; "Failed to update manifest, region: {}", region_id);
+            // New index files are not committed.
+            for file_meta in file_metas {
+                region.file_purger.send_request(PurgeRequest {
+                    file_meta,
+                    index_only: true,
+                });
+            }
+            request.on_failure(e);
+            return;
+        }
+
+        let num_files = file_metas.len();
+        region
+            .version_control
+            .update_files(file_metas, region.file_purger.clone());
+        info!(
+            "Updated indexes of {} files, region: {}",
+            num_files, region_id
+        );
+
+        request.sender.send(Ok(num_files));
+    }
+}
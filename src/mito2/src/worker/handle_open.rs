@@ -14,9 +14,10 @@
 
 //! Handling open request.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
-use common_telemetry::info;
+use common_telemetry::{info, warn};
 use object_store::util::join_path;
 use snafu::{OptionExt, ResultExt};
 use store_api::logstore::LogStore;
@@ -26,6 +27,7 @@ use store_api::storage::RegionId;
 use crate::error::{ObjectStoreNotFoundSnafu, OpenDalSnafu, RegionNotFoundSnafu, Result};
 use crate::metrics::REGION_COUNT;
 use crate::region::opener::RegionOpener;
+use crate::sst::index::creator::reclaim_orphan_intermediate_files;
 use crate::worker::handle_drop::remove_region_dir_once;
 use crate::worker::{RegionWorkerLoop, DROPPING_MARKER_FILE};
 
@@ -62,6 +64,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         info!("Try to open region {}", region_id);
 
         // Open region from specific region dir.
+        let region_dir = request.region_dir.clone();
         let region = RegionOpener::new(
             region_id,
             &request.region_dir,
@@ -72,6 +75,10 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         .skip_wal_replay(request.skip_wal_replay)
         .parse_options(request.options)?
         .cache(Some(self.cache_manager.clone()))
+        .intermediate_store(self.intermediate_store.clone())
+        .index_build_limiter(Some(self.index_build_limiter.clone()))
+        .request_sender(Some(self.sender.clone()))
+        .clock(self.clock.clone())
         .open(&self.config, &self.wal)
         .await?;
 
@@ -79,9 +86,47 @@ impl<S: LogStore> RegionWorkerLoop<S> {
 
         REGION_COUNT.inc();
 
+        // Uploads of the region interrupted by the last restart can be resolved now
+        // we know which SSTs the region commits.
+        if let Some(write_cache) = self.cache_manager.write_cache().cloned() {
+            let committed_files: HashSet<_> = region
+                .version()
+                .ssts
+                .levels()
+                .iter()
+                .flat_map(|level| level.files.keys().copied())
+                .collect();
+            common_runtime::spawn_bg(async move {
+                write_cache
+                    .resolve_pending_uploads(region_id, &committed_files)
+                    .await;
+            });
+        }
+
+        let path_strategy = region.access_layer.path_strategy().clone();
         // Insert the MitoRegion into the RegionMap.
         self.regions.insert_region(Arc::new(region));
 
+        // Index creations of the region can't be in progress before it's opened, removes
+        // intermediate files they left in background.
+        let intermediate_store = self
+            .intermediate_store
+            .clone()
+            .unwrap_or_else(|| object_store.clone());
+        let clock = self.clock.clone();
+        common_runtime::spawn_bg(async move {
+            if let Err(err) = reclaim_orphan_intermediate_files(
+                intermediate_store,
+                &region_dir,
+                path_strategy.as_ref(),
+                clock.as_ref(),
+            )
+            .await
+            {
+                warn!(err; "Failed to reclaim intermediate files of region {}", region_id);
+            }
+        });
+
         Ok(0)
     }
 }
@@ -57,6 +57,8 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             truncated_sequence,
             &self.memtable_builder,
         );
+        // All series are removed.
+        region.series_limiter.clear();
 
         // Make all data obsolete.
         self.wal
@@ -18,15 +18,14 @@ use std::collections::{hash_map, HashMap};
 use std::sync::Arc;
 
 use store_api::logstore::LogStore;
-use store_api::metadata::RegionMetadata;
 use store_api::storage::RegionId;
 
-use crate::error::{RejectWriteSnafu, Result};
+use crate::error::RejectWriteSnafu;
 use crate::metrics::{
     WRITE_REJECT_TOTAL, WRITE_ROWS_TOTAL, WRITE_STAGE_ELAPSED, WRITE_STALL_TOTAL,
 };
 use crate::region_write_ctx::RegionWriteCtx;
-use crate::request::{SenderWriteRequest, WriteRequest};
+use crate::request::SenderWriteRequest;
 use crate::worker::RegionWorkerLoop;
 
 impl<S: LogStore> RegionWorkerLoop<S> {
@@ -60,6 +59,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             return;
         }
 
+        self.listener.on_write_batch(write_requests.len());
         let mut region_ctxs = self.prepare_region_write_ctx(write_requests);
 
         // Write to WAL.
@@ -99,8 +99,9 @@ impl<S: LogStore> RegionWorkerLoop<S> {
             let _timer = WRITE_STAGE_ELAPSED
                 .with_label_values(&["write_memtable"])
                 .start_timer();
+            let now = self.clock.now_millis();
             for mut region_ctx in region_ctxs.into_values() {
-                region_ctx.write_memtable();
+                region_ctx.write_memtable(now);
                 put_rows += region_ctx.put_num;
                 delete_rows += region_ctx.delete_num;
             }
@@ -138,9 +139,9 @@ impl<S> RegionWorkerLoop<S> {
             if let hash_map::Entry::Vacant(e) = region_ctxs.entry(region_id) {
                 let Some(region) = self
                     .regions
-                    .writable_region_or(region_id, &mut sender_req.sender)
+                    .mutable_region_or(region_id, &mut sender_req.sender)
                 else {
-                    // No such region or the region is read only.
+                    // No such region or the region is read only or frozen.
                     continue;
                 };
 
@@ -148,7 +149,10 @@ impl<S> RegionWorkerLoop<S> {
                     region.region_id,
                     &region.version_control,
                     region.wal_options.clone(),
-                );
+                )
+                .with_write_subscribers(region.write_subscribers.clone())
+                .with_series_limiter(region.series_limiter.clone())
+                .with_memtable_metrics(region.memtable_metrics.clone());
 
                 e.insert(region_ctx);
             }
@@ -157,14 +161,22 @@ impl<S> RegionWorkerLoop<S> {
             let region_ctx = region_ctxs.get_mut(&region_id).unwrap();
 
             // Checks whether request schema is compatible with region schema.
-            if let Err(e) =
-                maybe_fill_missing_columns(&mut sender_req.request, &region_ctx.version().metadata)
+            if let Err(e) = sender_req
+                .request
+                .maybe_fill_missing_columns(&region_ctx.version().metadata)
             {
                 sender_req.sender.send(Err(e));
 
                 continue;
             }
 
+            // Rejects requests creating too many series before writing them to the WAL.
+            if let Err(e) = region_ctx.check_series_limit(&sender_req.request) {
+                sender_req.sender.send(Err(e));
+
+                continue;
+            }
+
             // Collect requests by region.
             region_ctx.push_mutation(
                 sender_req.request.op_type as i32,
@@ -197,19 +209,3 @@ fn reject_write_requests(write_requests: Vec<SenderWriteRequest>) {
         );
     }
 }
-
-/// Checks the schema and fill missing columns.
-fn maybe_fill_missing_columns(request: &mut WriteRequest, metadata: &RegionMetadata) -> Result<()> {
-    if let Err(e) = request.check_schema(metadata) {
-        if e.is_fill_default() {
-            // TODO(yingwen): Add metrics for this case.
-            // We need to fill default value. The write request may be a request
-            // sent before changing the schema.
-            request.fill_missing_columns(metadata)?;
-        } else {
-            return Err(e);
-        }
-    }
-
-    Ok(())
-}
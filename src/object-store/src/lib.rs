@@ -15,7 +15,7 @@
 pub use opendal::raw::{normalize_path as raw_normalize_path, HttpClient};
 pub use opendal::{
     services, Builder as ObjectStoreBuilder, Entry, EntryMode, Error, ErrorKind, Lister, Metakey,
-    Operator as ObjectStore, Reader, Result, Writer,
+    Operator as ObjectStore, Reader, Result, Scheme, Writer,
 };
 
 pub mod layers;
@@ -13,6 +13,13 @@
 // limitations under the License.
 
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use opendal::raw::*;
 
 use crate::{ObjectStore, Result};
 
@@ -62,3 +69,224 @@ pub fn s3_test_config() -> Option<TestS3Config> {
 
     None
 }
+
+/// A layer that delays read, write and list operations of a store, to test
+/// operations on slow stores.
+///
+/// Only the next `n` operations set by [DelayLayer::delay_next] are delayed.
+#[derive(Debug, Clone)]
+pub struct DelayLayer {
+    delay: Duration,
+    /// Number of operations to delay.
+    num_delayed: Arc<AtomicUsize>,
+    /// Number of operations called.
+    num_calls: Arc<AtomicUsize>,
+}
+
+impl DelayLayer {
+    /// Creates a layer that delays operations by `delay`.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            num_delayed: Arc::new(AtomicUsize::new(0)),
+            num_calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Delays the next `n` operations.
+    pub fn delay_next(&self, n: usize) {
+        self.num_delayed.store(n, Ordering::Relaxed);
+    }
+
+    /// Returns the number of read, write and list operations called.
+    pub fn num_calls(&self) -> usize {
+        self.num_calls.load(Ordering::Relaxed)
+    }
+
+    async fn maybe_delay(&self) {
+        self.num_calls.fetch_add(1, Ordering::Relaxed);
+        let delayed = self
+            .num_delayed
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok();
+        if delayed {
+            tokio::time::sleep(self.delay).await;
+        }
+    }
+}
+
+impl<A: Accessor> Layer<A> for DelayLayer {
+    type LayeredAccessor = DelayAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        DelayAccessor {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DelayAccessor<A> {
+    inner: A,
+    layer: DelayLayer,
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for DelayAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Lister = A::Lister;
+    type BlockingLister = A::BlockingLister;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.layer.maybe_delay().await;
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.layer.maybe_delay().await;
+        self.inner.write(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Lister)> {
+        self.layer.maybe_delay().await;
+        self.inner.list(path, args).await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingLister)> {
+        self.inner.blocking_list(path, args)
+    }
+}
+
+/// A layer that counts pages of list operations of a store as if the store
+/// returned entries page by page, to test pagination of list operations.
+///
+/// The size of a page is the limit of the list operation, or the default page
+/// size of the layer if the limit isn't set.
+#[derive(Debug, Clone)]
+pub struct ListPageLayer {
+    default_page_size: usize,
+    /// Number of pages listed.
+    num_pages: Arc<AtomicUsize>,
+}
+
+impl ListPageLayer {
+    /// Creates a layer with the `default_page_size` of list operations.
+    pub fn new(default_page_size: usize) -> Self {
+        Self {
+            default_page_size,
+            num_pages: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns the number of pages listed.
+    pub fn num_pages(&self) -> usize {
+        self.num_pages.load(Ordering::Relaxed)
+    }
+}
+
+impl<A: Accessor> Layer<A> for ListPageLayer {
+    type LayeredAccessor = ListPageAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        ListPageAccessor {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ListPageAccessor<A> {
+    inner: A,
+    layer: ListPageLayer,
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for ListPageAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Lister = PageCountLister<A::Lister>;
+    type BlockingLister = A::BlockingLister;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Lister)> {
+        let page_size = args.limit().unwrap_or(self.layer.default_page_size);
+        let (rp, lister) = self.inner.list(path, args).await?;
+        Ok((
+            rp,
+            PageCountLister {
+                inner: lister,
+                page_size,
+                remaining: 0,
+                num_pages: self.layer.num_pages.clone(),
+            },
+        ))
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingLister)> {
+        self.inner.blocking_list(path, args)
+    }
+}
+
+/// Lister that counts a page once it has listed all entries of the previous page.
+pub struct PageCountLister<L> {
+    inner: L,
+    page_size: usize,
+    /// Number of entries remaining in the current page.
+    remaining: usize,
+    num_pages: Arc<AtomicUsize>,
+}
+
+impl<L: oio::List> oio::List for PageCountLister<L> {
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<oio::Entry>>> {
+        if self.remaining == 0 {
+            // Fetches the next page.
+            self.num_pages.fetch_add(1, Ordering::Relaxed);
+            self.remaining = self.page_size.max(1);
+        }
+        let poll = self.inner.poll_next(cx);
+        if let Poll::Ready(Ok(Some(_))) = &poll {
+            self.remaining -= 1;
+        }
+        poll
+    }
+}
@@ -12,13 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use fs2::FileExt;
 use futures::TryStreamExt;
+use lazy_static::lazy_static;
 use opendal::layers::{LoggingLayer, TracingLayer};
 use opendal::{Entry, Lister};
 
 use crate::layers::PrometheusMetricsLayer;
 use crate::ObjectStore;
 
+/// Max number of entries object stores return in a page of a list operation,
+/// e.g. S3 returns at most 1000 keys in a response.
+pub const MAX_LIST_PAGE_SIZE: usize = 1000;
+
+/// Returns the number of entries in a page to list a directory, `None` means using the
+/// default page size of the store.
+///
+/// A `page_size` of 0 uses the default page size, and the page size is capped to
+/// [MAX_LIST_PAGE_SIZE].
+pub fn list_page_size(page_size: usize) -> Option<usize> {
+    (page_size > 0).then_some(page_size.min(MAX_LIST_PAGE_SIZE))
+}
+
 /// Collect all entries from the [Lister].
 pub async fn collect(stream: Lister) -> Result<Vec<Entry>, opendal::Error> {
     stream.try_collect::<Vec<_>>().await
@@ -81,6 +102,46 @@ pub fn join_path(parent: &str, child: &str) -> String {
     opendal::raw::normalize_path(&output)
 }
 
+lazy_static! {
+    /// Lock files of local dirs locked by the process, keyed by their paths. Files are
+    /// never closed so the process holds the locks until it exits.
+    static ref DIR_LOCKS: Mutex<HashMap<PathBuf, File>> = Mutex::new(HashMap::new());
+}
+
+/// Locks the local `dir` exclusively for the lifetime of the process, so the process
+/// can clean the dir without deleting files another process writes, e.g. processes
+/// share the same data home by mistake.
+///
+/// The lock is an `flock` on the `{dir}.lock` file beside the dir, so cleaning the dir
+/// doesn't remove it. The OS releases the lock once the process exits, even if the
+/// process crashes. Locking a dir the process already locks does nothing.
+///
+/// Returns an error of [io::ErrorKind::WouldBlock] if another process locks the dir.
+pub fn lock_dir(dir: &str) -> io::Result<()> {
+    let lock_path = PathBuf::from(format!("{}.lock", dir.trim_end_matches('/')));
+    let mut locks = DIR_LOCKS.lock().unwrap();
+    if locks.contains_key(&lock_path) {
+        return Ok(());
+    }
+
+    if let Some(parent) = lock_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(&lock_path)?;
+    file.try_lock_exclusive().map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("{} is locked by another process: {e}", lock_path.display()),
+        )
+    })?;
+    locks.insert(lock_path, file);
+
+    Ok(())
+}
+
 /// Attaches instrument layers to the object store.
 pub fn with_instrument_layers(object_store: ObjectStore) -> ObjectStore {
     object_store
@@ -97,8 +158,32 @@ pub fn with_instrument_layers(object_store: ObjectStore) -> ObjectStore {
 
 #[cfg(test)]
 mod tests {
+    use common_test_util::temp_dir::create_temp_dir;
+
     use super::*;
 
+    #[test]
+    fn test_lock_dir() {
+        let temp_dir = create_temp_dir("");
+        let dir = format!("{}/.tmp/", temp_dir.path().to_str().unwrap());
+        let lock_path = temp_dir.path().join(".tmp.lock");
+
+        // Another process locks the dir, `flock` locks conflict between open files
+        // even in the same process.
+        let other = File::create(&lock_path).unwrap();
+        other.lock_exclusive().unwrap();
+        let err = lock_dir(&dir).unwrap_err();
+        assert_eq!(io::ErrorKind::WouldBlock, err.kind(), "{err}");
+
+        // The other process exits.
+        drop(other);
+        lock_dir(&dir).unwrap();
+        // Locks the dir again.
+        lock_dir(&dir).unwrap();
+        let other = File::open(&lock_path).unwrap();
+        assert!(other.try_lock_exclusive().is_err());
+    }
+
     #[test]
     fn test_normalize_dir() {
         assert_eq!("/", normalize_dir("/"));
@@ -34,6 +34,7 @@ use crate::error::{
     CatalogSnafu, FindRegionLeaderSnafu, InvalidDeleteRequestSnafu, JoinTaskSnafu,
     MissingTimeIndexColumnSnafu, RequestDeletesSnafu, Result, TableNotFoundSnafu,
 };
+use crate::read_only::ReadOnlyStateRef;
 use crate::region_req_factory::RegionRequestFactory;
 use crate::req_convert::delete::{ColumnToRow, RowToRegion, TableToRegion};
 
@@ -41,6 +42,7 @@ pub struct Deleter {
     catalog_manager: CatalogManagerRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_manager: DatanodeManagerRef,
+    read_only: ReadOnlyStateRef,
 }
 
 pub type DeleterRef = Arc<Deleter>;
@@ -50,11 +52,13 @@ impl Deleter {
         catalog_manager: CatalogManagerRef,
         partition_manager: PartitionRuleManagerRef,
         datanode_manager: DatanodeManagerRef,
+        read_only: ReadOnlyStateRef,
     ) -> Self {
         Self {
             catalog_manager,
             partition_manager,
             datanode_manager,
+            read_only,
         }
     }
 
@@ -120,6 +124,7 @@ impl Deleter {
         requests: RegionDeleteRequests,
         ctx: &QueryContextRef,
     ) -> Result<AffectedRows> {
+        self.read_only.ensure_writable()?;
         let request_factory = RegionRequestFactory::new(RegionRequestHeader {
             tracing_context: TracingContext::from_current_span().to_w3c(),
             dbname: ctx.get_db_string(),
@@ -173,7 +178,7 @@ impl Deleter {
     ) -> Result<RowDeleteRequests> {
         for req in &mut requests.deletes {
             let catalog = ctx.current_catalog();
-            let schema = ctx.current_schema();
+            let schema = &ctx.current_schema();
             let table = self.get_table(catalog, schema, &req.table_name).await?;
             let key_column_names = self.key_column_names(&table)?;
 
@@ -18,11 +18,17 @@ use common_datasource::file_format::Format;
 use common_error::ext::{BoxedError, ErrorExt};
 use common_error::status_code::StatusCode;
 use common_macro::stack_trace_debug;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
 use datafusion::parquet;
 use datatypes::arrow::error::ArrowError;
 use datatypes::value::Value;
 use servers::define_into_tonic_status;
 use snafu::{Location, Snafu};
+use store_api::storage::RegionId;
+use table::metadata::TableId;
+
+use crate::req_convert::common::InvalidValue;
 
 #[derive(Snafu)]
 #[snafu(visibility(pub))]
@@ -67,6 +73,27 @@ pub enum Error {
         source: common_meta::error::Error,
     },
 
+    #[snafu(display("Failed to sync region {}", region_id))]
+    RequestSync {
+        region_id: RegionId,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Failed to freeze or unfreeze region {}", region_id))]
+    RequestFreeze {
+        region_id: RegionId,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Failed to pause or resume compaction of region {}", region_id))]
+    RequestPauseCompaction {
+        region_id: RegionId,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
     #[snafu(display("Failed to parse SQL"))]
     ParseSql {
         location: Location,
@@ -106,9 +133,53 @@ pub enum Error {
     #[snafu(display("Invalid InsertRequest, reason: {}", reason))]
     InvalidInsertRequest { reason: String, location: Location },
 
+    #[snafu(display(
+        "Invalid values in InsertRequest of table {}, {} invalid values found: {}",
+        table_name,
+        num_invalid,
+        invalid_values.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    ))]
+    InvalidRowValues {
+        table_name: String,
+        /// Invalid values in row order, up to a limited number.
+        invalid_values: Vec<InvalidValue>,
+        num_invalid: usize,
+        location: Location,
+    },
+
     #[snafu(display("Invalid DeleteRequest, reason: {}", reason))]
     InvalidDeleteRequest { reason: String, location: Location },
 
+    #[snafu(display(
+        "Timestamp {:?} of column {} in table {} is more precise than unit {:?} of the column",
+        timestamp,
+        column,
+        table_name,
+        target_unit
+    ))]
+    TimestampPrecisionLoss {
+        table_name: String,
+        column: String,
+        timestamp: Timestamp,
+        target_unit: TimeUnit,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Timestamp {:?} of column {} in table {} overflows unit {:?} of the column",
+        timestamp,
+        column,
+        table_name,
+        target_unit
+    ))]
+    TimestampOverflow {
+        table_name: String,
+        column: String,
+        timestamp: Timestamp,
+        target_unit: TimeUnit,
+        location: Location,
+    },
+
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound { table_name: String },
 
@@ -171,6 +242,35 @@ pub enum Error {
     #[snafu(display("Schema {} already exists", name))]
     SchemaExists { name: String, location: Location },
 
+    #[snafu(display("The instance is read-only, DDL and DML are rejected"))]
+    ReadOnly { location: Location },
+
+    #[snafu(display(
+        "Write rate limit of table {} exceeded, limit: {} rows per second",
+        table_id,
+        limit
+    ))]
+    TableWriteRateLimited {
+        table_id: TableId,
+        limit: u64,
+        location: Location,
+    },
+
+    #[snafu(display("Schema {} is not empty, use CASCADE to drop its tables", name))]
+    SchemaNotEmpty { name: String, location: Location },
+
+    #[snafu(display("Catalog {} not found", name))]
+    CatalogNotFound { name: String, location: Location },
+
+    #[snafu(display("Catalog {} already exists", name))]
+    CatalogExists { name: String, location: Location },
+
+    #[snafu(display("Catalog {} is not empty, use CASCADE to drop its schemas", name))]
+    CatalogNotEmpty { name: String, location: Location },
+
+    #[snafu(display("{} is reserved and cannot be dropped", name))]
+    DropReserved { name: String, location: Location },
+
     #[snafu(display("Table occurs error"))]
     Table {
         location: Location,
@@ -498,10 +598,18 @@ impl ErrorExt for Error {
         match self {
             Error::InvalidSql { .. }
             | Error::InvalidInsertRequest { .. }
+            | Error::InvalidRowValues { .. }
             | Error::InvalidDeleteRequest { .. }
+            | Error::TimestampPrecisionLoss { .. }
+            | Error::TimestampOverflow { .. }
             | Error::IllegalPrimaryKeysDef { .. }
             | Error::SchemaNotFound { .. }
             | Error::SchemaExists { .. }
+            | Error::SchemaNotEmpty { .. }
+            | Error::CatalogNotFound { .. }
+            | Error::CatalogExists { .. }
+            | Error::CatalogNotEmpty { .. }
+            | Error::DropReserved { .. }
             | Error::ColumnNotFound { .. }
             | Error::BuildRegex { .. }
             | Error::InvalidSchema { .. }
@@ -518,6 +626,10 @@ impl ErrorExt for Error {
 
             Error::TableAlreadyExists { .. } => StatusCode::TableAlreadyExists,
 
+            Error::ReadOnly { .. } => StatusCode::InstanceReadOnly,
+
+            Error::TableWriteRateLimited { .. } => StatusCode::RateLimited,
+
             Error::NotSupported { .. } => StatusCode::Unsupported,
 
             Error::TableMetadataManager { source, .. } => source.status_code(),
@@ -542,6 +654,9 @@ impl ErrorExt for Error {
 
             Error::RequestInserts { source, .. } => source.status_code(),
             Error::RequestDeletes { source, .. } => source.status_code(),
+            Error::RequestSync { source, .. }
+            | Error::RequestFreeze { source, .. }
+            | Error::RequestPauseCompaction { source, .. } => source.status_code(),
 
             Error::ColumnDataType { source, .. } | Error::InvalidColumnDef { source, .. } => {
                 source.status_code()
@@ -35,23 +35,30 @@ use partition::manager::PartitionRuleManagerRef;
 use session::context::QueryContextRef;
 use snafu::prelude::*;
 use sql::statements::insert::Insert;
+use store_api::storage::RegionId;
 use table::engine::TableReference;
+use table::metadata::TableId;
 use table::requests::InsertRequest as TableInsertRequest;
 use table::TableRef;
 
 use crate::error::{
-    CatalogSnafu, FindNewColumnsOnInsertionSnafu, FindRegionLeaderSnafu, InvalidInsertRequestSnafu,
-    JoinTaskSnafu, RequestInsertsSnafu, Result, TableNotFoundSnafu,
+    CatalogSnafu, FindNewColumnsOnInsertionSnafu, FindRegionLeaderSnafu,
+    FindTablePartitionRuleSnafu, InvalidInsertRequestSnafu, JoinTaskSnafu, RequestFreezeSnafu,
+    RequestInsertsSnafu, RequestPauseCompactionSnafu, RequestSyncSnafu, Result, TableNotFoundSnafu,
 };
 use crate::expr_factory::CreateExprFactory;
+use crate::read_only::ReadOnlyStateRef;
 use crate::region_req_factory::RegionRequestFactory;
 use crate::req_convert::insert::{ColumnToRow, RowToRegion, StatementToRegion, TableToRegion};
 use crate::statement::StatementExecutor;
+use crate::write_limiter::TableWriteLimiterRef;
 
 pub struct Inserter {
     catalog_manager: CatalogManagerRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_manager: DatanodeManagerRef,
+    read_only: ReadOnlyStateRef,
+    write_limiter: TableWriteLimiterRef,
 }
 
 pub type InserterRef = Arc<Inserter>;
@@ -61,11 +68,15 @@ impl Inserter {
         catalog_manager: CatalogManagerRef,
         partition_manager: PartitionRuleManagerRef,
         datanode_manager: DatanodeManagerRef,
+        read_only: ReadOnlyStateRef,
+        write_limiter: TableWriteLimiterRef,
     ) -> Self {
         Self {
             catalog_manager,
             partition_manager,
             datanode_manager,
+            read_only,
+            write_limiter,
         }
     }
 
@@ -144,6 +155,99 @@ impl Inserter {
         let affected_rows = self.do_request(inserts, ctx).await?;
         Ok(Output::AffectedRows(affected_rows as _))
     }
+
+    /// Syncs the WAL of all regions of the table so inserts acknowledged before are
+    /// durable.
+    pub async fn sync_table(&self, table: &TableRef) -> Result<()> {
+        let table_info = table.table_info();
+        let partitions = self
+            .partition_manager
+            .find_table_partitions(table_info.table_id())
+            .await
+            .context(FindTablePartitionRuleSnafu {
+                table_name: table_info.full_table_name(),
+            })?;
+
+        let tasks = partitions.into_iter().map(|partition| async move {
+            let region_id = partition.id;
+            let peer = self
+                .partition_manager
+                .find_region_leader(region_id)
+                .await
+                .context(FindRegionLeaderSnafu)?;
+            self.datanode_manager
+                .datanode(&peer)
+                .await
+                .sync_region(region_id)
+                .await
+                .context(RequestSyncSnafu { region_id })
+        });
+        future::try_join_all(tasks).await?;
+
+        Ok(())
+    }
+
+    /// Freezes or unfreezes all regions of the table, a frozen table rejects inserts
+    /// and deletes but still serves queries.
+    pub async fn freeze_table(&self, table: &TableRef, frozen: bool) -> Result<()> {
+        let table_info = table.table_info();
+        let partitions = self
+            .partition_manager
+            .find_table_partitions(table_info.table_id())
+            .await
+            .context(FindTablePartitionRuleSnafu {
+                table_name: table_info.full_table_name(),
+            })?;
+
+        let tasks = partitions.into_iter().map(|partition| async move {
+            let region_id = partition.id;
+            let peer = self
+                .partition_manager
+                .find_region_leader(region_id)
+                .await
+                .context(FindRegionLeaderSnafu)?;
+            self.datanode_manager
+                .datanode(&peer)
+                .await
+                .set_region_frozen(region_id, frozen)
+                .await
+                .context(RequestFreezeSnafu { region_id })
+        });
+        future::try_join_all(tasks).await?;
+
+        Ok(())
+    }
+
+    /// Pauses or resumes compactions of all regions of the table, explicit compactions
+    /// of a paused table fail.
+    pub async fn pause_compaction(&self, table: &TableRef, paused: bool) -> Result<()> {
+        let table_info = table.table_info();
+        let partitions = self
+            .partition_manager
+            .find_table_partitions(table_info.table_id())
+            .await
+            .context(FindTablePartitionRuleSnafu {
+                table_name: table_info.full_table_name(),
+            })?;
+
+        let tasks = partitions.into_iter().map(|partition| async move {
+            let region_id = partition.id;
+            let peer = self
+                .partition_manager
+                .find_region_leader(region_id)
+                .await
+                .context(FindRegionLeaderSnafu)?;
+            self.datanode_manager
+                .datanode(&peer)
+                .await
+                .set_region_compaction_paused(region_id, paused)
+                .await
+                .context(RequestPauseCompactionSnafu { region_id })
+        });
+        future::try_join_all(tasks).await?;
+
+        Ok(())
+    }
 }
 
 impl Inserter {
@@ -152,7 +256,9 @@ impl Inserter {
         requests: RegionInsertRequests,
         ctx: &QueryContextRef,
     ) -> Result<AffectedRows> {
-        write_meter!(ctx.current_catalog(), ctx.current_schema(), requests);
+        self.read_only.ensure_writable()?;
+        self.acquire_write_rate(&requests)?;
+        write_meter!(ctx.current_catalog(), &ctx.current_schema(), requests);
         let request_factory = RegionRequestFactory::new(RegionRequestHeader {
             tracing_context: TracingContext::from_current_span().to_w3c(),
             dbname: ctx.get_db_string(),
@@ -181,6 +287,17 @@ impl Inserter {
         Ok(affected_rows)
     }
 
+    /// Rejects the requests if any table is over its write rate limit.
+    fn acquire_write_rate(&self, requests: &RegionInsertRequests) -> Result<()> {
+        let mut rows_by_table: HashMap<TableId, u64> = HashMap::new();
+        for req in &requests.requests {
+            let rows = req.rows.as_ref().map(|r| r.rows.len()).unwrap_or_default();
+            let table_id = RegionId::from(req.region_id).table_id();
+            *rows_by_table.entry(table_id).or_default() += rows as u64;
+        }
+        self.write_limiter.acquire(&rows_by_table)
+    }
+
     async fn group_requests_by_peer(
         &self,
         requests: RegionInsertRequests,
@@ -211,7 +328,7 @@ impl Inserter {
         // TODO(jeremy): create and alter in batch?
         for req in &requests.inserts {
             let catalog = ctx.current_catalog();
-            let schema = ctx.current_schema();
+            let schema = &ctx.current_schema();
             let table = self.get_table(catalog, schema, &req.table_name).await?;
             match table {
                 Some(table) => {
@@ -246,7 +363,7 @@ impl Inserter {
         statement_executor: &StatementExecutor,
     ) -> Result<()> {
         let catalog_name = ctx.current_catalog();
-        let schema_name = ctx.current_schema();
+        let schema_name = &ctx.current_schema();
         let table_name = table.table_info().name.clone();
 
         let request_schema = req.rows.as_ref().unwrap().schema.as_slice();
@@ -295,8 +412,8 @@ impl Inserter {
         ctx: &QueryContextRef,
         statement_executor: &StatementExecutor,
     ) -> Result<()> {
-        let table_ref =
-            TableReference::full(ctx.current_catalog(), ctx.current_schema(), &req.table_name);
+        let schema_name = ctx.current_schema();
+        let table_ref = TableReference::full(ctx.current_catalog(), &schema_name, &req.table_name);
 
         let request_schema = req.rows.as_ref().unwrap().schema.as_slice();
         let create_table_expr = &mut build_create_table_expr(&table_ref, request_schema)?;
@@ -17,9 +17,11 @@ pub mod error;
 pub mod expr_factory;
 pub mod insert;
 pub mod metrics;
+pub mod read_only;
 pub mod region_req_factory;
 pub mod req_convert;
 pub mod statement;
 pub mod table;
 #[cfg(test)]
 pub(crate) mod tests;
+pub mod write_limiter;
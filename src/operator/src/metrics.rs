@@ -26,6 +26,11 @@ lazy_static! {
         "table operator ingest rows"
     )
     .unwrap();
+    pub static ref DIST_WRITE_RATE_LIMITED_COUNT: IntCounter = register_int_counter!(
+        "greptime_table_operator_write_rate_limited",
+        "table operator writes rejected by the rate limit"
+    )
+    .unwrap();
     pub static ref DIST_DELETE_ROW_COUNT: IntCounter = register_int_counter!(
         "greptime_table_operator_delete_rows",
         "table operator delete rows"
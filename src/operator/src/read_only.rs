@@ -0,0 +1,74 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use snafu::ensure;
+
+use crate::error::{ReadOnlySnafu, Result};
+
+/// Whether the instance rejects all the DDL and DML, while reads still proceed.
+///
+/// It's shared by the statement executor, inserter and deleter of an instance,
+/// and can be switched at runtime, e.g. when promoting a replica.
+#[derive(Debug, Default)]
+pub struct ReadOnlyState {
+    read_only: AtomicBool,
+}
+
+pub type ReadOnlyStateRef = Arc<ReadOnlyState>;
+
+impl ReadOnlyState {
+    pub fn new(read_only: bool) -> Self {
+        Self {
+            read_only: AtomicBool::new(read_only),
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::Relaxed);
+    }
+
+    /// Returns an error if the instance is read-only.
+    pub fn ensure_writable(&self) -> Result<()> {
+        ensure!(!self.is_read_only(), ReadOnlySnafu);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_toggle_read_only() {
+        let state = ReadOnlyState::default();
+        assert!(state.ensure_writable().is_ok());
+
+        state.set_read_only(true);
+        let err = state.ensure_writable().unwrap_err();
+        assert_eq!(StatusCode::InstanceReadOnly, err.status_code());
+
+        state.set_read_only(false);
+        assert!(state.ensure_writable().is_ok());
+    }
+}
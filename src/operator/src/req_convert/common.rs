@@ -15,21 +15,191 @@
 pub(crate) mod partitioner;
 
 use std::collections::HashMap;
+use std::fmt;
 
-use api::helper::ColumnDataTypeWrapper;
+use api::helper::{
+    pb_value_to_value_ref, proto_value_type, value_to_grpc_value, ColumnDataTypeWrapper,
+};
 use api::v1::value::ValueData;
 use api::v1::{Column, ColumnDataType, ColumnSchema, Row, Rows, SemanticType, Value};
 use common_base::BitVec;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::Schema;
+use datatypes::value::{Value as DtValue, ValueRef};
 use datatypes::vectors::VectorRef;
+use session::context::TimestampPrecisionAction;
 use snafu::prelude::*;
 use snafu::ResultExt;
 use table::metadata::TableInfo;
 
 use crate::error::{
-    ColumnDataTypeSnafu, ColumnNotFoundSnafu, InvalidInsertRequestSnafu,
-    MissingTimeIndexColumnSnafu, Result,
+    ColumnDataTypeSnafu, ColumnNotFoundSnafu, InvalidInsertRequestSnafu, InvalidRowValuesSnafu,
+    MissingTimeIndexColumnSnafu, Result, TimestampOverflowSnafu, TimestampPrecisionLossSnafu,
 };
 
+/// Max number of invalid values reported by [validate_row_values].
+const MAX_REPORTED_INVALID_VALUES: usize = 10;
+
+/// A value that doesn't match its column in rows to insert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// Index of the row in the request.
+    pub row: usize,
+    /// Name of the column.
+    pub column: String,
+    pub kind: InvalidValueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidValueKind {
+    /// Type of the value differs from the type of the column.
+    TypeMismatch {
+        expected: ColumnDataType,
+        given: ColumnDataType,
+    },
+    /// The value is null but the column is not null.
+    NullInNotNull,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            InvalidValueKind::TypeMismatch { expected, given } => write!(
+                f,
+                "row {} column '{}' expects type {}, given: {}",
+                self.row,
+                self.column,
+                expected.as_str_name(),
+                given.as_str_name()
+            ),
+            InvalidValueKind::NullInNotNull => write!(
+                f,
+                "row {} column '{}' is not null, given: null",
+                self.row, self.column
+            ),
+        }
+    }
+}
+
+/// Checks every value in `rows` against its column.
+///
+/// A value must have the type declared in the schema of `rows`, and can't be null if the
+/// column in `table_schema` is not null. Returns an error reporting the first invalid
+/// values with their row indexes and column names.
+pub fn validate_row_values(table_name: &str, rows: &Rows, table_schema: &Schema) -> Result<()> {
+    let not_null: Vec<_> = rows
+        .schema
+        .iter()
+        .map(|column| {
+            table_schema
+                .column_schema_by_name(&column.column_name)
+                .map(|c| !c.is_nullable())
+                .unwrap_or(false)
+        })
+        .collect();
+
+    let mut invalid_values = Vec::new();
+    let mut num_invalid = 0;
+    for (row_idx, row) in rows.rows.iter().enumerate() {
+        for ((value, column), not_null) in row.values.iter().zip(&rows.schema).zip(&not_null) {
+            let kind = match proto_value_type(value) {
+                Some(given) if given as i32 != column.datatype => {
+                    let Ok(expected) = ColumnDataType::try_from(column.datatype) else {
+                        // Unknown column types are rejected by the type check of columns.
+                        continue;
+                    };
+                    InvalidValueKind::TypeMismatch { expected, given }
+                }
+                None if *not_null => InvalidValueKind::NullInNotNull,
+                _ => continue,
+            };
+
+            num_invalid += 1;
+            if invalid_values.len() < MAX_REPORTED_INVALID_VALUES {
+                invalid_values.push(InvalidValue {
+                    row: row_idx,
+                    column: column.column_name.clone(),
+                    kind,
+                });
+            }
+        }
+    }
+
+    ensure!(
+        num_invalid == 0,
+        InvalidRowValuesSnafu {
+            table_name,
+            invalid_values,
+            num_invalid,
+        }
+    );
+    Ok(())
+}
+
+/// Converts timestamps in `rows` to the units of their columns in `table_schema`.
+///
+/// Timestamps more precise than the units of their columns are truncated, or rejected
+/// if the `action` is [TimestampPrecisionAction::Error].
+pub fn align_timestamp_units(
+    table_name: &str,
+    rows: &mut Rows,
+    table_schema: &Schema,
+    action: TimestampPrecisionAction,
+) -> Result<()> {
+    for (idx, column) in rows.schema.iter_mut().enumerate() {
+        let Some(column_schema) = table_schema.column_schema_by_name(&column.column_name) else {
+            continue;
+        };
+        let ConcreteDataType::Timestamp(target_type) = &column_schema.data_type else {
+            continue;
+        };
+        let Ok(wrapper) = ColumnDataTypeWrapper::try_new(column.datatype, None) else {
+            continue;
+        };
+        match ConcreteDataType::from(wrapper) {
+            ConcreteDataType::Timestamp(given_type) if given_type != *target_type => {}
+            _ => continue,
+        }
+
+        let target_unit = target_type.unit();
+        for row in &mut rows.rows {
+            let Some(value) = row.values.get_mut(idx) else {
+                continue;
+            };
+            // Values of other types are rejected by [validate_row_values].
+            let ValueRef::Timestamp(timestamp) = pb_value_to_value_ref(value, &None) else {
+                continue;
+            };
+            let converted =
+                timestamp
+                    .convert_to(target_unit)
+                    .with_context(|| TimestampOverflowSnafu {
+                        table_name,
+                        column: &column.column_name,
+                        timestamp,
+                        target_unit,
+                    })?;
+            ensure!(
+                action == TimestampPrecisionAction::Truncate || converted == timestamp,
+                TimestampPrecisionLossSnafu {
+                    table_name,
+                    column: &column.column_name,
+                    timestamp,
+                    target_unit,
+                }
+            );
+            *value = value_to_grpc_value(DtValue::Timestamp(converted));
+        }
+
+        let (datatype, _) = ColumnDataTypeWrapper::try_from(column_schema.data_type.clone())
+            .context(ColumnDataTypeSnafu)?
+            .to_parts();
+        column.datatype = datatype as i32;
+    }
+
+    Ok(())
+}
+
 pub fn columns_to_rows(columns: Vec<Column>, row_count: u32) -> Result<Rows> {
     let row_count = row_count as usize;
     let column_count = columns.len();
@@ -257,8 +427,10 @@ mod tests {
     use api::v1::column::Values;
     use api::v1::SemanticType;
     use common_base::bit_vec::prelude::*;
+    use datatypes::schema::ColumnSchema as DtColumnSchema;
 
     use super::*;
+    use crate::error::Error;
 
     #[test]
     fn test_request_column_to_row() {
@@ -374,4 +546,185 @@ mod tests {
         let row_count = 3;
         assert!(columns_to_rows(columns, row_count).is_err());
     }
+
+    fn new_row(host: Option<&str>, cpu: ValueData) -> Row {
+        Row {
+            values: vec![
+                Value {
+                    value_data: host.map(|v| ValueData::StringValue(v.to_string())),
+                },
+                Value {
+                    value_data: Some(cpu),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validate_row_values() {
+        let table_schema = Schema::new(vec![
+            DtColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            DtColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]);
+        let schema = vec![
+            ColumnSchema {
+                column_name: "host".to_string(),
+                datatype: ColumnDataType::String as i32,
+                semantic_type: SemanticType::Tag as i32,
+                ..Default::default()
+            },
+            ColumnSchema {
+                column_name: "cpu".to_string(),
+                datatype: ColumnDataType::Float64 as i32,
+                semantic_type: SemanticType::Field as i32,
+                ..Default::default()
+            },
+        ];
+        let mut rows = Rows {
+            schema,
+            rows: vec![
+                new_row(Some("a"), ValueData::F64Value(1.0)),
+                new_row(Some("b"), ValueData::F64Value(2.0)),
+                new_row(Some("c"), ValueData::F64Value(3.0)),
+            ],
+        };
+        validate_row_values("demo", &rows, &table_schema).unwrap();
+
+        // Type mismatch at row 2.
+        rows.rows[2] = new_row(Some("c"), ValueData::StringValue("3.0".to_string()));
+        let err = validate_row_values("demo", &rows, &table_schema).unwrap_err();
+        let Error::InvalidRowValues {
+            invalid_values,
+            num_invalid,
+            ..
+        } = &err
+        else {
+            unreachable!("unexpected error: {err:?}");
+        };
+        assert_eq!(1, *num_invalid);
+        assert_eq!(
+            vec![InvalidValue {
+                row: 2,
+                column: "cpu".to_string(),
+                kind: InvalidValueKind::TypeMismatch {
+                    expected: ColumnDataType::Float64,
+                    given: ColumnDataType::String,
+                },
+            }],
+            *invalid_values
+        );
+        assert!(err
+            .to_string()
+            .contains("row 2 column 'cpu' expects type FLOAT64, given: STRING"));
+
+        // Null in the not null column at row 1.
+        rows.rows[1] = new_row(None, ValueData::F64Value(2.0));
+        let err = validate_row_values("demo", &rows, &table_schema).unwrap_err();
+        let Error::InvalidRowValues { invalid_values, .. } = &err else {
+            unreachable!("unexpected error: {err:?}");
+        };
+        assert_eq!(
+            InvalidValue {
+                row: 1,
+                column: "host".to_string(),
+                kind: InvalidValueKind::NullInNotNull,
+            },
+            invalid_values[0]
+        );
+        assert_eq!(2, invalid_values.len());
+
+        // Only reports a limited number of invalid values.
+        rows.rows = (0..MAX_REPORTED_INVALID_VALUES + 5)
+            .map(|_| new_row(None, ValueData::F64Value(1.0)))
+            .collect();
+        let err = validate_row_values("demo", &rows, &table_schema).unwrap_err();
+        let Error::InvalidRowValues {
+            invalid_values,
+            num_invalid,
+            ..
+        } = &err
+        else {
+            unreachable!("unexpected error: {err:?}");
+        };
+        assert_eq!(MAX_REPORTED_INVALID_VALUES + 5, *num_invalid);
+        assert_eq!(MAX_REPORTED_INVALID_VALUES, invalid_values.len());
+    }
+
+    #[test]
+    fn test_align_timestamp_units() {
+        let table_schema = Schema::new(vec![DtColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_second_datatype(),
+            false,
+        )]);
+        let new_rows = |values: &[i64]| Rows {
+            schema: vec![ColumnSchema {
+                column_name: "ts".to_string(),
+                datatype: ColumnDataType::TimestampMillisecond as i32,
+                semantic_type: SemanticType::Timestamp as i32,
+                ..Default::default()
+            }],
+            rows: values
+                .iter()
+                .map(|v| Row {
+                    values: vec![Value {
+                        value_data: Some(ValueData::TimestampMillisecondValue(*v)),
+                    }],
+                })
+                .collect(),
+        };
+
+        // Truncates timestamps to the unit of the column.
+        let mut rows = new_rows(&[1000, 2500]);
+        align_timestamp_units(
+            "demo",
+            &mut rows,
+            &table_schema,
+            TimestampPrecisionAction::Truncate,
+        )
+        .unwrap();
+        assert_eq!(
+            ColumnDataType::TimestampSecond as i32,
+            rows.schema[0].datatype
+        );
+        let values: Vec<_> = rows
+            .rows
+            .iter()
+            .map(|row| row.values[0].value_data.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                Some(ValueData::TimestampSecondValue(1)),
+                Some(ValueData::TimestampSecondValue(2))
+            ],
+            values
+        );
+
+        // Rejects timestamps more precise than the column.
+        let mut rows = new_rows(&[1000, 2500]);
+        let err = align_timestamp_units(
+            "demo",
+            &mut rows,
+            &table_schema,
+            TimestampPrecisionAction::Error,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::TimestampPrecisionLoss { .. }),
+            "unexpected error: {err:?}"
+        );
+        // Timestamps that fit the unit are accepted.
+        let mut rows = new_rows(&[1000, 2000]);
+        align_timestamp_units(
+            "demo",
+            &mut rows,
+            &table_schema,
+            TimestampPrecisionAction::Error,
+        )
+        .unwrap();
+        assert_eq!(
+            ColumnDataType::TimestampSecond as i32,
+            rows.schema[0].datatype
+        );
+    }
 }
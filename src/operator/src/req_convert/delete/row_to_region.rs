@@ -62,7 +62,7 @@ impl<'a> RowToRegion<'a> {
 
     async fn get_table(&self, table_name: &str) -> Result<TableRef> {
         let catalog_name = self.ctx.current_catalog();
-        let schema_name = self.ctx.current_schema();
+        let schema_name = &self.ctx.current_schema();
         self.catalog_manager
             .table(catalog_name, schema_name, table_name)
             .await
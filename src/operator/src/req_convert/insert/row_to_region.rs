@@ -22,6 +22,7 @@ use table::TableRef;
 
 use crate::error::{CatalogSnafu, Result, TableNotFoundSnafu};
 use crate::req_convert::common::partitioner::Partitioner;
+use crate::req_convert::common::{align_timestamp_units, validate_row_values};
 
 pub struct RowToRegion<'a> {
     catalog_manager: &'a dyn CatalogManager,
@@ -47,9 +48,18 @@ impl<'a> RowToRegion<'a> {
         for request in requests.inserts {
             let table = self.get_table(&request.table_name).await?;
             let table_id = table.table_info().table_id();
+            let mut rows = request.rows.unwrap_or_default();
+            let table_schema = table.schema();
+            validate_row_values(&request.table_name, &rows, &table_schema)?;
+            align_timestamp_units(
+                &request.table_name,
+                &mut rows,
+                &table_schema,
+                self.ctx.timestamp_precision_action(),
+            )?;
 
             let requests = Partitioner::new(self.partition_manager)
-                .partition_insert_requests(table_id, request.rows.unwrap_or_default())
+                .partition_insert_requests(table_id, rows)
                 .await?;
 
             region_request.extend(requests);
@@ -62,7 +72,7 @@ impl<'a> RowToRegion<'a> {
 
     async fn get_table(&self, table_name: &str) -> Result<TableRef> {
         let catalog_name = self.ctx.current_catalog();
-        let schema_name = self.ctx.current_schema();
+        let schema_name = &self.ctx.current_schema();
         self.catalog_manager
             .table(catalog_name, schema_name, table_name)
             .await
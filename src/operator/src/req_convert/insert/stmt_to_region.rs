@@ -18,7 +18,7 @@ use api::v1::{ColumnSchema as GrpcColumnSchema, Row, Rows, Value as GrpcValue};
 use catalog::CatalogManager;
 use datatypes::schema::{ColumnSchema, SchemaRef};
 use partition::manager::PartitionRuleManager;
-use session::context::QueryContext;
+use session::context::{QueryContext, TimestampPrecisionAction};
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::statements;
 use sql::statements::insert::Insert;
@@ -110,7 +110,11 @@ impl<'a> StatementToRegion<'a> {
             schema.push(grpc_column_schema);
 
             for (sql_row, grpc_row) in sql_rows.iter().zip(rows.iter_mut()) {
-                let value = sql_value_to_grpc_value(column_schema, &sql_row[i])?;
+                let value = sql_value_to_grpc_value(
+                    column_schema,
+                    &sql_row[i],
+                    self.ctx.timestamp_precision_action(),
+                )?;
                 grpc_row.values.push(value);
             }
         }
@@ -135,7 +139,7 @@ impl<'a> StatementToRegion<'a> {
         match &obj_name.0[..] {
             [table] => Ok((
                 self.ctx.current_catalog().to_owned(),
-                self.ctx.current_schema().to_owned(),
+                self.ctx.current_schema(),
                 table.value.clone(),
             )),
             [schema, table] => Ok((
@@ -169,7 +173,11 @@ fn column_names<'a>(stmt: &'a Insert, table_schema: &'a SchemaRef) -> Vec<&'a St
     }
 }
 
-fn sql_value_to_grpc_value(column_schema: &ColumnSchema, sql_val: &SqlValue) -> Result<GrpcValue> {
+fn sql_value_to_grpc_value(
+    column_schema: &ColumnSchema,
+    sql_val: &SqlValue,
+    timestamp_precision_action: TimestampPrecisionAction,
+) -> Result<GrpcValue> {
     let column = &column_schema.name;
     let value = if replace_default(sql_val) {
         let default_value = column_schema
@@ -182,8 +190,15 @@ fn sql_value_to_grpc_value(column_schema: &ColumnSchema, sql_val: &SqlValue) ->
             column: column.clone(),
         })?
     } else {
-        statements::sql_value_to_value(column, &column_schema.data_type, sql_val)
-            .context(ParseSqlSnafu)?
+        match timestamp_precision_action {
+            TimestampPrecisionAction::Truncate => {
+                statements::sql_value_to_value(column, &column_schema.data_type, sql_val)
+            }
+            TimestampPrecisionAction::Error => {
+                statements::sql_value_to_exact_value(column, &column_schema.data_type, sql_val)
+            }
+        }
+        .context(ParseSqlSnafu)?
     };
 
     let grpc_value = value_to_grpc_value(value);
@@ -39,22 +39,24 @@ use partition::manager::{PartitionRuleManager, PartitionRuleManagerRef};
 use query::parser::QueryStatement;
 use query::plan::LogicalPlan;
 use query::QueryEngineRef;
-use session::context::QueryContextRef;
-use snafu::{OptionExt, ResultExt};
+use session::context::{QueryContextRef, TimestampPrecisionAction};
+use snafu::{ensure, OptionExt, ResultExt};
 use sql::statements::copy::{CopyDatabaseArgument, CopyTable, CopyTableArgument};
+use sql::statements::set_variables::SetVariables;
 use sql::statements::statement::Statement;
 use sql::statements::OptionMap;
 use sql::util::format_raw_object_name;
-use sqlparser::ast::ObjectName;
+use sqlparser::ast::{Expr, Ident, ObjectName, Value as SqlValue};
 use table::engine::TableReference;
 use table::requests::{CopyDatabaseRequest, CopyDirection, CopyTableRequest};
 use table::TableRef;
 
 use crate::error::{
     self, CatalogSnafu, ExecLogicalPlanSnafu, ExternalSnafu, InvalidSqlSnafu, PlanStatementSnafu,
-    Result, TableNotFoundSnafu,
+    Result, SchemaNotFoundSnafu, TableNotFoundSnafu,
 };
 use crate::insert::InserterRef;
+use crate::read_only::ReadOnlyStateRef;
 use crate::statement::backup::{COPY_DATABASE_TIME_END_KEY, COPY_DATABASE_TIME_START_KEY};
 use crate::table::table_idents_to_full_name;
 
@@ -67,6 +69,7 @@ pub struct StatementExecutor {
     partition_manager: PartitionRuleManagerRef,
     cache_invalidator: CacheInvalidatorRef,
     inserter: InserterRef,
+    read_only: ReadOnlyStateRef,
 }
 
 impl StatementExecutor {
@@ -77,6 +80,7 @@ impl StatementExecutor {
         kv_backend: KvBackendRef,
         cache_invalidator: CacheInvalidatorRef,
         inserter: InserterRef,
+        read_only: ReadOnlyStateRef,
     ) -> Self {
         Self {
             catalog_manager,
@@ -86,6 +90,7 @@ impl StatementExecutor {
             partition_manager: Arc::new(PartitionRuleManager::new(kv_backend)),
             cache_invalidator,
             inserter,
+            read_only,
         }
     }
 
@@ -161,6 +166,30 @@ impl StatementExecutor {
                 let table_name = TableName::new(catalog, schema, table);
                 self.truncate_table(table_name).await
             }
+            Statement::SyncTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(stmt.table_name(), query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.sync_table(table_name).await
+            }
+            Statement::FreezeTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(stmt.table_name(), query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.freeze_table(table_name, stmt.frozen()).await
+            }
+            Statement::PauseCompaction(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(stmt.table_name(), query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table_name = TableName::new(catalog, schema, table);
+                self.pause_compaction(table_name, stmt.paused()).await
+            }
 
             Statement::CreateDatabase(stmt) => {
                 self.create_database(
@@ -171,6 +200,34 @@ impl StatementExecutor {
                 .await
             }
 
+            Statement::DropDatabase(stmt) => {
+                self.drop_database(
+                    query_ctx.current_catalog(),
+                    &format_raw_object_name(stmt.name()),
+                    stmt.drop_if_exists(),
+                    stmt.cascade(),
+                )
+                .await
+            }
+
+            Statement::CreateCatalog(stmt) => {
+                self.create_catalog(&format_raw_object_name(&stmt.name), stmt.if_not_exists)
+                    .await
+            }
+
+            Statement::DropCatalog(stmt) => {
+                self.drop_catalog(
+                    &format_raw_object_name(stmt.name()),
+                    stmt.drop_if_exists(),
+                    stmt.cascade(),
+                )
+                .await
+            }
+
+            Statement::Use(db) => self.use_database(db, query_ctx).await,
+
+            Statement::SetVariables(set_var) => self.set_variables(set_var, query_ctx),
+
             Statement::ShowCreateTable(show) => {
                 let (catalog, schema, table) =
                     table_idents_to_full_name(&show.table_name, query_ctx.clone())
@@ -191,6 +248,94 @@ impl StatementExecutor {
         }
     }
 
+    async fn use_database(&self, db: String, query_ctx: QueryContextRef) -> Result<Output> {
+        let catalog = query_ctx.current_catalog();
+        ensure!(
+            self.catalog_manager
+                .schema_exists(catalog, &db)
+                .await
+                .context(CatalogSnafu)?,
+            SchemaNotFoundSnafu { schema_info: &db }
+        );
+
+        query_ctx.set_current_schema(&db);
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Sets session variables for the statements that follow.
+    fn set_variables(&self, set_var: SetVariables, query_ctx: QueryContextRef) -> Result<Output> {
+        let variable = set_var.variable.to_string();
+        if !variable.eq_ignore_ascii_case(TimestampPrecisionAction::VARIABLE_NAME) {
+            return error::NotSupportedSnafu {
+                feat: format!("SET {variable}"),
+            }
+            .fail();
+        }
+
+        let action = match set_var.value.as_slice() {
+            [Expr::Value(SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s))]
+            | [Expr::Identifier(Ident { value: s, .. })] => TimestampPrecisionAction::from_name(s),
+            _ => None,
+        }
+        .with_context(|| InvalidSqlSnafu {
+            err_msg: format!(
+                "invalid value of {variable}: {}, expect 'truncate' or 'error'",
+                set_var
+                    .value
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })?;
+        query_ctx.set_timestamp_precision_action(action);
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Syncs the WAL of the table's regions.
+    async fn sync_table(&self, table_name: TableName) -> Result<Output> {
+        let table = self
+            .get_table(&TableReference::full(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            ))
+            .await?;
+        self.inserter.sync_table(&table).await?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Freezes or unfreezes the table's regions.
+    async fn freeze_table(&self, table_name: TableName, frozen: bool) -> Result<Output> {
+        let table = self
+            .get_table(&TableReference::full(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            ))
+            .await?;
+        self.inserter.freeze_table(&table, frozen).await?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Pauses or resumes compactions of the table's regions.
+    async fn pause_compaction(&self, table_name: TableName, paused: bool) -> Result<Output> {
+        let table = self
+            .get_table(&TableReference::full(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            ))
+            .await?;
+        self.inserter.pause_compaction(&table, paused).await?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
     pub async fn plan(
         &self,
         stmt: QueryStatement,
@@ -17,21 +17,28 @@ use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
 use api::v1::{column_def, AlterExpr, CreateTableExpr};
+use catalog::event::CatalogEvent;
 use catalog::CatalogManagerRef;
 use chrono::Utc;
-use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_catalog::consts::{
+    DEFAULT_CATALOG_NAME, DEFAULT_PRIVATE_SCHEMA_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME,
+};
 use common_catalog::format_full_table_name;
 use common_meta::cache_invalidator::Context;
+use common_meta::ddl::table_meta::PLACEMENT_HINTS_KEY;
 use common_meta::ddl::ExecutorContext;
+use common_meta::key::catalog_name::CatalogNameKey;
 use common_meta::key::schema_name::{SchemaNameKey, SchemaNameValue};
 use common_meta::key::NAME_PATTERN;
 use common_meta::rpc::ddl::{DdlTask, SubmitDdlTaskRequest, SubmitDdlTaskResponse};
 use common_meta::rpc::router::{Partition, Partition as MetaPartition};
 use common_meta::table_name::TableName;
+use common_meta::DatanodeId;
 use common_query::Output;
 use common_telemetry::{info, tracing};
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::RawSchema;
+use futures_util::TryStreamExt;
 use lazy_static::lazy_static;
 use partition::partition::{PartitionBound, PartitionDef};
 use regex::Regex;
@@ -86,6 +93,7 @@ impl StatementExecutor {
         create_table: &mut CreateTableExpr,
         partitions: Option<Partitions>,
     ) -> Result<TableRef> {
+        self.read_only.ensure_writable()?;
         let _timer = crate::metrics::DIST_CREATE_TABLE.start_timer();
         let schema = self
             .table_metadata_manager
@@ -97,7 +105,8 @@ impl StatementExecutor {
             .await
             .context(TableMetadataManagerSnafu)?;
 
-        let Some(schema_opts) = schema else {
+        // A dropping schema doesn't accept new tables.
+        let Some(schema_opts) = schema.filter(|schema| !schema.dropping) else {
             return SchemaNotFoundSnafu {
                 schema_info: &create_table.schema_name,
             }
@@ -142,6 +151,7 @@ impl StatementExecutor {
             &create_table.table_name,
         );
 
+        let placement_hints = find_placement_hints(&partitions);
         let (partitions, partition_cols) = parse_partitions(create_table, partitions)?;
 
         validate_partition_columns(create_table, &partition_cols)?;
@@ -149,13 +159,24 @@ impl StatementExecutor {
         let mut table_info = create_table_info(create_table, partition_cols, schema_opts)?;
 
         let resp = self
-            .create_table_procedure(create_table, partitions, table_info.clone())
+            .create_table_procedure(
+                create_table,
+                partitions,
+                placement_hints,
+                table_info.clone(),
+            )
             .await?;
 
         let table_id = resp.table_id.context(error::UnexpectedSnafu {
             violated: "expected table_id",
         })?;
         info!("Successfully created table '{table_name}' with table id {table_id}");
+        self.catalog_manager
+            .event_notifier()
+            .notify(CatalogEvent::TableCreated {
+                table_name,
+                table_id,
+            });
 
         table_info.ident.table_id = table_id;
 
@@ -169,6 +190,7 @@ impl StatementExecutor {
 
     #[tracing::instrument(skip_all)]
     pub async fn drop_table(&self, table_name: TableName, drop_if_exists: bool) -> Result<Output> {
+        self.read_only.ensure_writable()?;
         if let Some(table) = self
             .catalog_manager
             .table(
@@ -194,6 +216,13 @@ impl StatementExecutor {
                 .await
                 .context(error::InvalidateTableCacheSnafu)?;
 
+            self.catalog_manager
+                .event_notifier()
+                .notify(CatalogEvent::TableDropped {
+                    table_name,
+                    table_id,
+                });
+
             Ok(Output::AffectedRows(0))
         } else if drop_if_exists {
             // DROP TABLE IF EXISTS meets table not found - ignored
@@ -208,6 +237,7 @@ impl StatementExecutor {
 
     #[tracing::instrument(skip_all)]
     pub async fn truncate_table(&self, table_name: TableName) -> Result<Output> {
+        self.read_only.ensure_writable()?;
         let table = self
             .catalog_manager
             .table(
@@ -271,6 +301,7 @@ impl StatementExecutor {
     }
 
     pub async fn alter_table_inner(&self, expr: AlterExpr) -> Result<Output> {
+        self.read_only.ensure_writable()?;
         let catalog_name = if expr.catalog_name.is_empty() {
             DEFAULT_CATALOG_NAME
         } else {
@@ -318,14 +349,19 @@ impl StatementExecutor {
             .await
             .context(error::InvalidateTableCacheSnafu)?;
 
+        let table_name = TableName::new(catalog_name, schema_name, table_name);
         self.cache_invalidator
-            .invalidate_table_name(
-                &Context::default(),
-                TableName::new(catalog_name, schema_name, table_name),
-            )
+            .invalidate_table_name(&Context::default(), table_name.clone())
             .await
             .context(error::InvalidateTableCacheSnafu)?;
 
+        self.catalog_manager
+            .event_notifier()
+            .notify(CatalogEvent::TableAltered {
+                table_name,
+                table_id,
+            });
+
         Ok(Output::AffectedRows(0))
     }
 
@@ -333,12 +369,23 @@ impl StatementExecutor {
         &self,
         create_table: &CreateTableExpr,
         partitions: Vec<Partition>,
+        placement_hints: HashMap<usize, DatanodeId>,
         table_info: RawTableInfo,
     ) -> Result<SubmitDdlTaskResponse> {
         let partitions = partitions.into_iter().map(Into::into).collect();
 
+        let mut create_table = create_table.clone();
+        if !placement_hints.is_empty() {
+            // Carries the placement hints to the meta, which honors them when allocating regions.
+            let placement_hints =
+                serde_json::to_string(&placement_hints).context(error::EncodeJsonSnafu)?;
+            let _ = create_table
+                .table_options
+                .insert(PLACEMENT_HINTS_KEY.to_string(), placement_hints);
+        }
+
         let request = SubmitDdlTaskRequest {
-            task: DdlTask::new_create_table(create_table.clone(), partitions, table_info),
+            task: DdlTask::new_create_table(create_table, partitions, table_info),
         };
 
         self.ddl_executor
@@ -396,6 +443,7 @@ impl StatementExecutor {
         database: &str,
         create_if_not_exists: bool,
     ) -> Result<Output> {
+        self.read_only.ensure_writable()?;
         ensure!(
             NAME_PATTERN_REG.is_match(catalog),
             error::UnexpectedSnafu {
@@ -435,6 +483,201 @@ impl StatementExecutor {
 
         Ok(Output::AffectedRows(1))
     }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_database(
+        &self,
+        catalog: &str,
+        database: &str,
+        drop_if_exists: bool,
+        cascade: bool,
+    ) -> Result<Output> {
+        self.read_only.ensure_writable()?;
+        ensure!(
+            ![
+                DEFAULT_SCHEMA_NAME,
+                DEFAULT_PRIVATE_SCHEMA_NAME,
+                INFORMATION_SCHEMA_NAME
+            ]
+            .contains(&database),
+            error::DropReservedSnafu { name: database }
+        );
+
+        self.drop_database_inner(catalog, database, drop_if_exists, cascade)
+            .await
+    }
+
+    /// Drops the database and its tables.
+    ///
+    /// The database is marked as dropping before dropping its tables so it rejects
+    /// new tables, and its key is only deleted once all tables are dropped. If the
+    /// drop fails halfway, the database is kept and dropping it again finishes the job.
+    async fn drop_database_inner(
+        &self,
+        catalog: &str,
+        database: &str,
+        drop_if_exists: bool,
+        cascade: bool,
+    ) -> Result<Output> {
+        let schema_manager = self.table_metadata_manager.schema_manager();
+        let schema_key = SchemaNameKey::new(catalog, database);
+        let exists = schema_manager
+            .exists(schema_key)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        if !exists {
+            return if drop_if_exists {
+                Ok(Output::AffectedRows(0))
+            } else {
+                SchemaNotFoundSnafu {
+                    schema_info: database,
+                }
+                .fail()
+            };
+        }
+
+        let mut tables = self.list_table_names(catalog, database).await?;
+        ensure!(
+            cascade || tables.is_empty(),
+            error::SchemaNotEmptySnafu { name: database }
+        );
+
+        let marked = schema_manager
+            .set_dropping(schema_key)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        if !marked {
+            // The database is dropped concurrently.
+            return Ok(Output::AffectedRows(0));
+        }
+
+        // Tables created before the database is marked as dropping may be missed
+        // by the first listing, lists tables again until there is no table left.
+        while !tables.is_empty() {
+            ensure!(cascade, error::SchemaNotEmptySnafu { name: database });
+            for table in tables {
+                let _ = self
+                    .drop_table(TableName::new(catalog, database, table), true)
+                    .await?;
+            }
+            tables = self.list_table_names(catalog, database).await?;
+        }
+
+        schema_manager
+            .delete(schema_key)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(Output::AffectedRows(1))
+    }
+
+    async fn list_table_names(&self, catalog: &str, database: &str) -> Result<Vec<String>> {
+        let tables = self
+            .table_metadata_manager
+            .table_name_manager()
+            .tables(catalog, database)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(tables.into_iter().map(|(table, _)| table).collect())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn create_catalog(
+        &self,
+        catalog: &str,
+        create_if_not_exists: bool,
+    ) -> Result<Output> {
+        self.read_only.ensure_writable()?;
+        ensure!(
+            NAME_PATTERN_REG.is_match(catalog),
+            error::UnexpectedSnafu {
+                violated: format!("Invalid catalog name: {}", catalog)
+            }
+        );
+
+        let catalog_key = CatalogNameKey::new(catalog);
+        let exists = self
+            .table_metadata_manager
+            .catalog_manager()
+            .exists(catalog_key)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        if exists {
+            return if create_if_not_exists {
+                Ok(Output::AffectedRows(1))
+            } else {
+                error::CatalogExistsSnafu { name: catalog }.fail()
+            };
+        }
+
+        self.table_metadata_manager
+            .catalog_manager()
+            .create(catalog_key, false)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(Output::AffectedRows(1))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_catalog(
+        &self,
+        catalog: &str,
+        drop_if_exists: bool,
+        cascade: bool,
+    ) -> Result<Output> {
+        self.read_only.ensure_writable()?;
+        ensure!(
+            catalog != DEFAULT_CATALOG_NAME,
+            error::DropReservedSnafu { name: catalog }
+        );
+
+        let catalog_key = CatalogNameKey::new(catalog);
+        let exists = self
+            .table_metadata_manager
+            .catalog_manager()
+            .exists(catalog_key)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        if !exists {
+            return if drop_if_exists {
+                Ok(Output::AffectedRows(0))
+            } else {
+                error::CatalogNotFoundSnafu { name: catalog }.fail()
+            };
+        }
+
+        let schemas = self
+            .table_metadata_manager
+            .schema_manager()
+            .schema_names(catalog)
+            .await
+            .try_collect::<Vec<_>>()
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        ensure!(
+            cascade || schemas.is_empty(),
+            error::CatalogNotEmptySnafu { name: catalog }
+        );
+
+        for schema in schemas {
+            let _ = self
+                .drop_database_inner(catalog, &schema, true, true)
+                .await?;
+        }
+
+        self.table_metadata_manager
+            .catalog_manager()
+            .delete(catalog_key)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        Ok(Output::AffectedRows(1))
+    }
 }
 
 fn validate_partition_columns(
@@ -573,6 +816,15 @@ fn find_partition_columns(partitions: &Option<Partitions>) -> Result<Vec<String>
     Ok(columns)
 }
 
+/// Finds the placement hints of partitions, keyed by the partition's index.
+fn find_placement_hints(partitions: &Option<Partitions>) -> HashMap<usize, DatanodeId> {
+    partitions
+        .iter()
+        .flat_map(|x| x.entries.iter().enumerate())
+        .filter_map(|(i, entry)| entry.placement_hint.map(|hint| (i, hint)))
+        .collect()
+}
+
 fn find_partition_entries(
     create_table: &CreateTableExpr,
     partitions: &Option<Partitions>,
@@ -696,6 +948,17 @@ PARTITION BY RANGE COLUMNS (b, a) (
 ENGINE=mito",
                 r#"[{"column_list":["b","a"],"value_list":["{\"Value\":{\"String\":\"hz\"}}","{\"Value\":{\"Int32\":10}}"]},{"column_list":["b","a"],"value_list":["{\"Value\":{\"String\":\"sh\"}}","{\"Value\":{\"Int32\":20}}"]},{"column_list":["b","a"],"value_list":["\"MaxValue\"","\"MaxValue\""]}]"#,
             ),
+            (
+                r"
+CREATE TABLE rcx ( a INT, b STRING, c TIMESTAMP, TIME INDEX (c) )
+PARTITION BY RANGE COLUMNS (b, a) (
+  PARTITION r0 VALUES LESS THAN ('hz', 10),
+  PARTITION r1 VALUES LESS THAN ('hz', MAXVALUE),
+  PARTITION r2 VALUES LESS THAN (MAXVALUE, MAXVALUE),
+)
+ENGINE=mito",
+                r#"[{"column_list":["b","a"],"value_list":["{\"Value\":{\"String\":\"hz\"}}","{\"Value\":{\"Int32\":10}}"]},{"column_list":["b","a"],"value_list":["{\"Value\":{\"String\":\"hz\"}}","\"MaxValue\""]},{"column_list":["b","a"],"value_list":["\"MaxValue\"","\"MaxValue\""]}]"#,
+            ),
         ];
         for (sql, expected) in cases {
             let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
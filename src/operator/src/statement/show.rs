@@ -103,6 +103,7 @@ fn create_partitions_stmt(partitions: Vec<PartitionInfo>) -> Result<Option<Parti
             Ok(PartitionEntry {
                 name: name[..].into(),
                 value_list,
+                placement_hint: info.placement_hint,
             })
         })
         .collect::<Result<Vec<_>>>()?;
@@ -36,7 +36,7 @@ pub fn table_idents_to_full_name(
     match &obj_name.0[..] {
         [table] => Ok((
             query_ctx.current_catalog().to_owned(),
-            query_ctx.current_schema().to_owned(),
+            query_ctx.current_schema(),
             table.value.clone(),
         )),
         [schema, table] => Ok((
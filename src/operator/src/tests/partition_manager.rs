@@ -416,6 +416,107 @@ async fn test_find_regions() {
     ));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_find_regions_by_composite_partition() {
+    let kv_backend = MetaKvBackend {
+        client: Arc::new(MetaClient::default()),
+    };
+    let partition_manager = Arc::new(PartitionRuleManager::new(Arc::new(kv_backend)));
+
+    // PARTITION BY RANGE COLUMNS (a, b) (
+    //   PARTITION r1 VALUES LESS THAN (10, 100),
+    //   PARTITION r2 VALUES LESS THAN (10, MAXVALUE),
+    //   PARTITION r3 VALUES LESS THAN (20, 100),
+    //   PARTITION r4 VALUES LESS THAN (MAXVALUE, MAXVALUE),
+    // )
+    let partition_rule: PartitionRuleRef = Arc::new(RangeColumnsPartitionRule::new(
+        vec!["a".to_string(), "b".to_string()],
+        vec![
+            vec![
+                PartitionBound::Value(10_i32.into()),
+                PartitionBound::Value(100_i32.into()),
+            ],
+            vec![
+                PartitionBound::Value(10_i32.into()),
+                PartitionBound::MaxValue,
+            ],
+            vec![
+                PartitionBound::Value(20_i32.into()),
+                PartitionBound::Value(100_i32.into()),
+            ],
+            vec![PartitionBound::MaxValue, PartitionBound::MaxValue],
+        ],
+        vec![0_u32, 1, 2, 3],
+    )) as _;
+
+    // test routing rows by all partition columns
+    assert_eq!(
+        partition_rule
+            .find_region(&[10_i32.into(), 50_i32.into()])
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        partition_rule
+            .find_region(&[10_i32.into(), 100_i32.into()])
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        partition_rule
+            .find_region(&[15_i32.into(), 1_i32.into()])
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        partition_rule
+            .find_region(&[20_i32.into(), 100_i32.into()])
+            .unwrap(),
+        3
+    );
+
+    let test = |filters: Vec<Expr>, expect_regions: Vec<RegionNumber>| {
+        let mut regions = partition_manager
+            .find_regions_by_filters(partition_rule.clone(), filters.as_slice())
+            .unwrap();
+        regions.sort();
+        assert_eq!(regions, expect_regions);
+    };
+
+    // test pruning by the first partition column only
+    test(
+        vec![binary_expr(col("a"), Operator::Eq, lit(10)).into()], // a == 10
+        vec![0, 1],
+    );
+    test(
+        vec![binary_expr(col("a"), Operator::Gt, lit(10)).into()], // a > 10
+        vec![2, 3],
+    );
+
+    // test pruning by the second partition column only
+    test(
+        vec![binary_expr(col("b"), Operator::Lt, lit(50)).into()], // b < 50
+        vec![0, 2, 3],
+    );
+
+    // test pruning by both partition columns
+    test(
+        vec![and(
+            binary_expr(col("a"), Operator::Eq, lit(10)),
+            binary_expr(col("b"), Operator::GtEq, lit(100)),
+        )
+        .into()], // a == 10 AND b >= 100
+        vec![0, 1],
+    );
+    test(
+        vec![
+            binary_expr(col("a"), Operator::Eq, lit(10)).into(),
+            binary_expr(col("b"), Operator::Lt, lit(50)).into(),
+        ], // [a == 10, b < 50]
+        vec![0],
+    );
+}
+
 #[derive(Default)]
 struct MockCollector {
     pub write_sum: AtomicU32,
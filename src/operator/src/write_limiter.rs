@@ -0,0 +1,257 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use table::metadata::TableId;
+
+use crate::error::{Result, TableWriteRateLimitedSnafu};
+use crate::metrics::DIST_WRITE_RATE_LIMITED_COUNT;
+
+/// Interval to remove buckets of tables that are idle long enough to be full.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Limits the rows written to each table per second, so a noisy table can't starve
+/// the others.
+///
+/// Each table has a token bucket holding at most one second of its limit, so bursts
+/// within that allowance are permitted. Writes over the limit are rejected instead of
+/// being delayed. The limits can be adjusted at runtime.
+///
+/// The limiter only counts writes of the node it lives in. Each frontend enforces the
+/// limit separately, so a table in a cluster accepts up to the limit times the number
+/// of frontends.
+#[derive(Debug, Default)]
+pub struct TableWriteLimiter {
+    /// Max rows per second of tables without their own limit, 0 means unlimited.
+    default_limit: AtomicU64,
+    inner: Mutex<Inner>,
+}
+
+pub type TableWriteLimiterRef = Arc<TableWriteLimiter>;
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Limits of tables overriding the default limit.
+    table_limits: HashMap<TableId, u64>,
+    buckets: HashMap<TableId, TokenBucket>,
+    last_prune: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, limit: u64, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit as f64).min(limit as f64);
+        self.last_refill = now;
+    }
+
+    /// Whether `rows` can be written. A write larger than the limit is permitted when
+    /// the bucket is full, and the following writes wait until the bucket refills.
+    fn permits(&self, limit: u64, rows: u64) -> bool {
+        self.tokens >= rows as f64 || self.tokens >= limit as f64
+    }
+}
+
+impl TableWriteLimiter {
+    /// Creates a limiter allowing `rows_per_second` rows for each table, 0 means unlimited.
+    pub fn new(rows_per_second: u64) -> Self {
+        Self {
+            default_limit: AtomicU64::new(rows_per_second),
+            inner: Mutex::default(),
+        }
+    }
+
+    pub fn rate_limit(&self) -> u64 {
+        self.default_limit.load(Ordering::Relaxed)
+    }
+
+    /// Sets the max rows per second of tables without their own limit, 0 means unlimited.
+    pub fn set_rate_limit(&self, rows_per_second: u64) {
+        self.default_limit.store(rows_per_second, Ordering::Relaxed);
+    }
+
+    /// Overrides the limit of the table, `None` restores the default limit.
+    pub fn set_table_rate_limit(&self, table_id: TableId, rows_per_second: Option<u64>) {
+        let mut inner = self.inner.lock().unwrap();
+        // The bucket is refilled with the new limit.
+        let _ = inner.buckets.remove(&table_id);
+        match rows_per_second {
+            Some(limit) => {
+                let _ = inner.table_limits.insert(table_id, limit);
+            }
+            None => {
+                let _ = inner.table_limits.remove(&table_id);
+            }
+        }
+    }
+
+    /// Acquires the allowance to write the rows of each table, returns an error without
+    /// consuming any allowance if any table is over its limit.
+    pub fn acquire(&self, rows: &HashMap<TableId, u64>) -> Result<()> {
+        self.acquire_at(rows, Instant::now())
+    }
+
+    fn acquire_at(&self, rows: &HashMap<TableId, u64>, now: Instant) -> Result<()> {
+        let default_limit = self.rate_limit();
+        let mut inner = self.inner.lock().unwrap();
+        let Inner {
+            table_limits,
+            buckets,
+            last_prune,
+        } = &mut *inner;
+        let limit_of = |table_id| {
+            table_limits
+                .get(&table_id)
+                .copied()
+                .unwrap_or(default_limit)
+        };
+        if last_prune.map_or(true, |last| {
+            now.saturating_duration_since(last) >= PRUNE_INTERVAL
+        }) {
+            // A full bucket is the same as a new one, so buckets of idle or dropped tables
+            // don't pile up.
+            buckets.retain(|table_id, bucket| {
+                let limit = limit_of(*table_id);
+                bucket.refill(limit, now);
+                limit > 0 && bucket.tokens < limit as f64
+            });
+            *last_prune = Some(now);
+        }
+        if default_limit == 0 && table_limits.is_empty() {
+            return Ok(());
+        }
+
+        for (&table_id, &rows) in rows {
+            let limit = limit_of(table_id);
+            if limit == 0 {
+                continue;
+            }
+            let bucket = buckets.entry(table_id).or_insert_with(|| TokenBucket {
+                tokens: limit as f64,
+                last_refill: now,
+            });
+            bucket.refill(limit, now);
+            if !bucket.permits(limit, rows) {
+                DIST_WRITE_RATE_LIMITED_COUNT.inc();
+                return TableWriteRateLimitedSnafu { table_id, limit }.fail();
+            }
+        }
+        for (&table_id, &rows) in rows {
+            if let Some(bucket) = buckets.get_mut(&table_id) {
+                if limit_of(table_id) > 0 {
+                    bucket.tokens -= rows as f64;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_write_rate_limit() {
+        let limiter = TableWriteLimiter::new(100);
+        let now = Instant::now();
+        let rows = HashMap::from([(1, 60), (2, 60)]);
+
+        // Bursts within the allowance of each table.
+        limiter.acquire_at(&rows, now).unwrap();
+        let err = limiter.acquire_at(&rows, now).unwrap_err();
+        assert_eq!(StatusCode::RateLimited, err.status_code());
+        // Rejected writes don't consume the allowance of other tables.
+        limiter.acquire_at(&HashMap::from([(2, 40)]), now).unwrap();
+
+        // Succeeds after the bucket refills.
+        let now = now + Duration::from_millis(200);
+        assert!(limiter.acquire_at(&rows, now).is_err());
+        let now = now + Duration::from_secs(1);
+        limiter.acquire_at(&rows, now).unwrap();
+
+        // A write larger than the limit is permitted when the bucket is full.
+        let now = now + Duration::from_secs(1);
+        limiter.acquire_at(&HashMap::from([(1, 150)]), now).unwrap();
+        assert!(limiter.acquire_at(&HashMap::from([(1, 1)]), now).is_err());
+        let now = now + Duration::from_secs(1);
+        limiter.acquire_at(&HashMap::from([(1, 1)]), now).unwrap();
+    }
+
+    #[test]
+    fn test_prune_idle_buckets() {
+        let limiter = TableWriteLimiter::new(100);
+        let now = Instant::now();
+        limiter
+            .acquire_at(&HashMap::from([(1, 10_000), (2, 10)]), now)
+            .unwrap();
+        assert_eq!(2, limiter.inner.lock().unwrap().buckets.len());
+
+        // The bucket of table 2 is full again but table 1 still has a debt.
+        let now = now + PRUNE_INTERVAL;
+        limiter.acquire_at(&HashMap::new(), now).unwrap();
+        let buckets: Vec<_> = limiter
+            .inner
+            .lock()
+            .unwrap()
+            .buckets
+            .keys()
+            .copied()
+            .collect();
+        assert_eq!(vec![1], buckets);
+
+        limiter.set_rate_limit(0);
+        let now = now + PRUNE_INTERVAL;
+        limiter.acquire_at(&HashMap::new(), now).unwrap();
+        assert!(limiter.inner.lock().unwrap().buckets.is_empty());
+    }
+
+    #[test]
+    fn test_adjust_write_rate_limit() {
+        let limiter = TableWriteLimiter::default();
+        let now = Instant::now();
+        let rows = HashMap::from([(1, 1000)]);
+        limiter.acquire_at(&rows, now).unwrap();
+
+        limiter.set_table_rate_limit(1, Some(10));
+        limiter.acquire_at(&HashMap::from([(1, 10)]), now).unwrap();
+        assert!(limiter.acquire_at(&rows, now).is_err());
+        // Other tables are still unlimited.
+        limiter
+            .acquire_at(&HashMap::from([(2, 1000)]), now)
+            .unwrap();
+
+        limiter.set_rate_limit(10);
+        assert!(limiter.acquire_at(&HashMap::from([(2, 1000)]), now).is_ok());
+        assert!(limiter.acquire_at(&HashMap::from([(2, 1)]), now).is_err());
+
+        limiter.set_rate_limit(0);
+        limiter.set_table_rate_limit(1, None);
+        limiter.acquire_at(&rows, now).unwrap();
+    }
+}
@@ -51,27 +51,18 @@ pub struct RangeColumnsPartitionRule {
     value_lists: Vec<Vec<PartitionBound>>,
     regions: Vec<RegionNumber>,
 
-    // TODO(LFC): Implement finding regions by all partitioning columns, not by the first one only.
-    // Singled out the first partitioning column's bounds for finding regions by range.
+    // The possible values of each partitioning column in each region, acted as a cache so we
+    // don't need to recalculate them every time when finding regions by range.
     //
-    // Theoretically, finding regions in `value_list`s should use all the partition columns values
-    // as a whole in the comparison (think of how Rust's vector is compared to each other). And
-    // this is how we do it if provided with concrete values (see `find_region` method).
+    // A region covers the tuples in `[value_lists[i - 1], value_lists[i])`, compared as a whole
+    // (think of how Rust's vector is compared to each other). Within such a tuple range, the
+    // columns in the common prefix of both bounds are fixed to a single value, the first column
+    // that differs is bounded by the two bounds' values, and the rest columns are unconstrained.
+    // So a filter on any partitioning column could be used to prune regions, not only the first
+    // one.
     //
-    // However, when we need to find regions by range, for example, a filter of "x < 100" defined
-    // in SQL, currently I'm not quite sure how that could be implemented. Especially facing the complex
-    // filter expression like "a < 1 AND (b > 2 OR c != 3)".
-    //
-    // So I decided to use the first partitioning column temporarily in finding regions by range,
-    // and further investigate how MySQL (and others) implemented this feature in detail.
-    //
-    // Finding regions only using the first partitioning column is fine. It might return regions that
-    // actually do not contain the range's value (causing unnecessary table scans), but will
-    // not lose any data that should have been scanned.
-    //
-    // The following two fields are acted as caches, so we don't need to recalculate them every time.
-    first_column_bounds: Vec<PartitionBound>,
-    first_column_regions: Vec<Vec<RegionNumber>>,
+    // `None` indicates the region can't hold any tuple (its lower bound equals to its upper bound).
+    column_ranges: Vec<Option<Vec<ColumnRange>>>,
 }
 
 impl RangeColumnsPartitionRule {
@@ -82,44 +73,35 @@ impl RangeColumnsPartitionRule {
         value_lists: Vec<Vec<PartitionBound>>,
         regions: Vec<RegionNumber>,
     ) -> Self {
-        // An example range columns partition rule to calculate the first column bounds and regions:
+        // An example range columns partition rule to calculate the column ranges:
         // SQL:
         //   PARTITION p1 VALUES LESS THAN (10, 'c'),
         //   PARTITION p2 VALUES LESS THAN (20, 'h'),
         //   PARTITION p3 VALUES LESS THAN (20, 'm'),
-        //   PARTITION p4 VALUES LESS THAN (50, 'p'),
-        //   PARTITION p5 VALUES LESS THAN (MAXVALUE, 'x'),
-        //   PARTITION p6 VALUES LESS THAN (MAXVALUE, MAXVALUE),
-        // first column bounds:
-        //   [10, 20, 50, MAXVALUE]
-        // first column regions:
-        //   [[1], [2, 3], [4], [5, 6]]
-
-        let first_column_bounds = value_lists
+        //   PARTITION p4 VALUES LESS THAN (MAXVALUE, MAXVALUE),
+        // column ranges:
+        //   p1: a <= 10, b unconstrained
+        //   p2: 10 <= a <= 20, b unconstrained
+        //   p3: a = 20, 'h' <= b < 'm'
+        //   p4: a >= 20, b unconstrained
+        let column_ranges = value_lists
             .iter()
-            .map(|x| &x[0])
-            .collect::<Vec<&PartitionBound>>();
-
-        let mut distinct_bounds = Vec::<PartitionBound>::new();
-        distinct_bounds.push(first_column_bounds[0].clone());
-        let mut first_column_regions = Vec::<Vec<RegionNumber>>::new();
-        first_column_regions.push(vec![regions[0]]);
-
-        for i in 1..first_column_bounds.len() {
-            if first_column_bounds[i] == &distinct_bounds[distinct_bounds.len() - 1] {
-                first_column_regions[distinct_bounds.len() - 1].push(regions[i]);
-            } else {
-                distinct_bounds.push(first_column_bounds[i].clone());
-                first_column_regions.push(vec![regions[i]]);
-            }
-        }
+            .enumerate()
+            .map(|(i, upper)| {
+                let lower = if i == 0 {
+                    None
+                } else {
+                    Some(value_lists[i - 1].as_slice())
+                };
+                ColumnRange::from_bounds(lower, upper)
+            })
+            .collect();
 
         Self {
             column_list,
             value_lists,
             regions,
-            first_column_bounds: distinct_bounds,
-            first_column_regions,
+            column_ranges,
         }
     }
 
@@ -166,44 +148,35 @@ impl PartitionRule for RangeColumnsPartitionRule {
         })
     }
 
+    /// All the `exprs` must be satisfied by the rows in the returned regions. A region is excluded
+    /// only if some expr can't be satisfied by any value of its column in that region.
     fn find_regions_by_exprs(&self, exprs: &[PartitionExpr]) -> Result<Vec<RegionNumber>> {
         let regions =
             if !exprs.is_empty() && exprs.iter().all(|x| self.column_list.contains(&x.column)) {
-                let PartitionExpr {
-                    column: _,
-                    op,
-                    value,
-                } = exprs
+                let exprs = exprs
                     .iter()
-                    .find(|x| x.column == self.column_list[0])
-                    // "unwrap" is safe because we have checked that "self.column_list" contains all columns in "exprs"
-                    .unwrap();
-
-                let regions = &self.first_column_regions;
-                match self
-                    .first_column_bounds
-                    .binary_search(&PartitionBound::Value(value.clone()))
-                {
-                    Ok(i) => match op {
-                        Operator::Lt => &regions[..=i],
-                        Operator::LtEq => &regions[..=(i + 1)],
-                        Operator::Eq => &regions[(i + 1)..=(i + 1)],
-                        Operator::Gt | Operator::GtEq => &regions[(i + 1)..],
-                        Operator::NotEq => &regions[..],
-                        _ => unimplemented!(),
-                    },
-                    Err(i) => match op {
-                        Operator::Lt | Operator::LtEq => &regions[..=i],
-                        Operator::Eq => &regions[i..=i],
-                        Operator::Gt | Operator::GtEq => &regions[i..],
-                        Operator::NotEq => &regions[..],
-                        _ => unimplemented!(),
-                    },
-                }
-                .iter()
-                .flatten()
-                .cloned()
-                .collect::<Vec<RegionNumber>>()
+                    .map(|x| {
+                        // "unwrap" is safe because we have checked that "self.column_list" contains all columns in "exprs"
+                        let index = self
+                            .column_list
+                            .iter()
+                            .position(|c| c == &x.column)
+                            .unwrap();
+                        (index, x.op, PartitionBound::Value(x.value.clone()))
+                    })
+                    .collect::<Vec<_>>();
+
+                self.regions
+                    .iter()
+                    .zip(self.column_ranges.iter())
+                    .filter_map(|(region, ranges)| {
+                        let ranges = ranges.as_ref()?;
+                        exprs
+                            .iter()
+                            .all(|(index, op, value)| ranges[*index].may_satisfy(*op, value))
+                            .then_some(*region)
+                    })
+                    .collect::<Vec<RegionNumber>>()
             } else {
                 self.regions.clone()
             };
@@ -211,6 +184,118 @@ impl PartitionRule for RangeColumnsPartitionRule {
     }
 }
 
+/// One side of a [ColumnRange]. `None` bound means unbounded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnBound {
+    value: PartitionBound,
+    inclusive: bool,
+}
+
+/// The range of values a single partitioning column could have in one region.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct ColumnRange {
+    low: Option<ColumnBound>,
+    high: Option<ColumnBound>,
+}
+
+impl ColumnRange {
+    /// Calculates the ranges of all partitioning columns for the region whose tuples lie in
+    /// `[lower, upper)`. A `None` lower means there's no lower bound (the first region).
+    ///
+    /// Returns `None` if the region can't hold any tuple.
+    fn from_bounds(
+        lower: Option<&[PartitionBound]>,
+        upper: &[PartitionBound],
+    ) -> Option<Vec<ColumnRange>> {
+        let n = upper.len();
+        let mut ranges = vec![ColumnRange::default(); n];
+
+        let Some(lower) = lower else {
+            ranges[0].high = Self::high_bound(upper, 0);
+            return Some(ranges);
+        };
+        if lower >= upper {
+            return None;
+        }
+
+        // The length of the common prefix of the two bounds. It's less than "n" because the
+        // bounds are not equal.
+        let p = lower
+            .iter()
+            .zip(upper.iter())
+            .take_while(|(l, u)| l == u)
+            .count();
+        for (range, value) in ranges.iter_mut().zip(lower[..p].iter()) {
+            let bound = Some(ColumnBound {
+                value: value.clone(),
+                inclusive: true,
+            });
+            range.low = bound.clone();
+            range.high = bound;
+        }
+        ranges[p].low = Some(ColumnBound {
+            value: lower[p].clone(),
+            // The tuple "(lower[p], ...)" is in the region, unless all the trailing columns of the
+            // lower bound are MAXVALUE (which no actual value could reach).
+            inclusive: p + 1 == n
+                || lower[p + 1..]
+                    .iter()
+                    .any(|x| x != &PartitionBound::MaxValue),
+        });
+        ranges[p].high = Self::high_bound(upper, p);
+        Some(ranges)
+    }
+
+    fn high_bound(upper: &[PartitionBound], index: usize) -> Option<ColumnBound> {
+        match &upper[index] {
+            PartitionBound::MaxValue => None,
+            value => Some(ColumnBound {
+                value: value.clone(),
+                // The tuple "(upper[index], ...)" could still be in the region if there are
+                // trailing columns that are less than the upper bound's.
+                inclusive: index + 1 < upper.len(),
+            }),
+        }
+    }
+
+    /// Returns whether there could be some value in this range that satisfies "value op x".
+    fn may_satisfy(&self, op: Operator, x: &PartitionBound) -> bool {
+        let low_le = || {
+            self.low.as_ref().map_or(true, |l| {
+                if l.inclusive {
+                    &l.value <= x
+                } else {
+                    &l.value < x
+                }
+            })
+        };
+        let high_ge = || {
+            self.high.as_ref().map_or(true, |h| {
+                if h.inclusive {
+                    &h.value >= x
+                } else {
+                    &h.value > x
+                }
+            })
+        };
+        match op {
+            Operator::Eq => low_le() && high_ge(),
+            Operator::NotEq => match (&self.low, &self.high) {
+                (Some(l), Some(h)) if l.inclusive && h.inclusive && l.value == h.value => {
+                    &l.value != x
+                }
+                _ => true,
+            },
+            Operator::Lt => self.low.as_ref().map_or(true, |l| &l.value < x),
+            Operator::LtEq => low_le(),
+            Operator::Gt => self.high.as_ref().map_or(true, |h| &h.value > x),
+            Operator::GtEq => high_ge(),
+            // Not supported operators, the region can't be excluded.
+            _ => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -256,20 +341,11 @@ mod tests {
         );
 
         let test = |op: Operator, value: &str, expected_regions: Vec<RegionNumber>| {
-            let exprs = vec![
-                // Intentionally fix column b's partition expr to "b < 1". If we support finding
-                // regions by both columns("a" and "b") in the future, some test cases should fail.
-                PartitionExpr {
-                    column: "b".to_string(),
-                    op: Operator::Lt,
-                    value: 1_i32.into(),
-                },
-                PartitionExpr {
-                    column: "a".to_string(),
-                    op,
-                    value: value.into(),
-                },
-            ];
+            let exprs = vec![PartitionExpr {
+                column: "a".to_string(),
+                op,
+                value: value.into(),
+            }];
             let regions = rule.find_regions_by_exprs(&exprs).unwrap();
             assert_eq!(
                 regions,
@@ -279,13 +355,14 @@ mod tests {
 
         test(Operator::NotEq, "hz", vec![1, 2, 3, 4, 5, 6]);
         test(Operator::NotEq, "what", vec![1, 2, 3, 4, 5, 6]);
+        test(Operator::NotEq, "sh", vec![1, 2, 4, 5, 6]);
 
         test(Operator::GtEq, "ab", vec![1, 2, 3, 4, 5, 6]);
-        test(Operator::GtEq, "hz", vec![2, 3, 4, 5, 6]);
+        test(Operator::GtEq, "hz", vec![1, 2, 3, 4, 5, 6]);
         test(Operator::GtEq, "ijk", vec![2, 3, 4, 5, 6]);
-        test(Operator::GtEq, "sh", vec![4, 5, 6]);
+        test(Operator::GtEq, "sh", vec![2, 3, 4, 5, 6]);
         test(Operator::GtEq, "ssh", vec![4, 5, 6]);
-        test(Operator::GtEq, "sz", vec![5, 6]);
+        test(Operator::GtEq, "sz", vec![4, 5, 6]);
         test(Operator::GtEq, "zz", vec![5, 6]);
 
         test(Operator::Gt, "ab", vec![1, 2, 3, 4, 5, 6]);
@@ -297,28 +374,61 @@ mod tests {
         test(Operator::Gt, "zz", vec![5, 6]);
 
         test(Operator::Eq, "ab", vec![1]);
-        test(Operator::Eq, "hz", vec![2, 3]);
-        test(Operator::Eq, "ijk", vec![2, 3]);
-        test(Operator::Eq, "sh", vec![4]);
+        test(Operator::Eq, "hz", vec![1, 2]);
+        test(Operator::Eq, "ijk", vec![2]);
+        test(Operator::Eq, "sh", vec![2, 3, 4]);
         test(Operator::Eq, "ssh", vec![4]);
-        test(Operator::Eq, "sz", vec![5, 6]);
-        test(Operator::Eq, "zz", vec![5, 6]);
+        test(Operator::Eq, "sz", vec![4, 5]);
+        test(Operator::Eq, "zz", vec![5]);
 
         test(Operator::Lt, "ab", vec![1]);
         test(Operator::Lt, "hz", vec![1]);
-        test(Operator::Lt, "ijk", vec![1, 2, 3]);
-        test(Operator::Lt, "sh", vec![1, 2, 3]);
+        test(Operator::Lt, "ijk", vec![1, 2]);
+        test(Operator::Lt, "sh", vec![1, 2]);
         test(Operator::Lt, "ssh", vec![1, 2, 3, 4]);
         test(Operator::Lt, "sz", vec![1, 2, 3, 4]);
-        test(Operator::Lt, "zz", vec![1, 2, 3, 4, 5, 6]);
+        test(Operator::Lt, "zz", vec![1, 2, 3, 4, 5]);
 
         test(Operator::LtEq, "ab", vec![1]);
-        test(Operator::LtEq, "hz", vec![1, 2, 3]);
-        test(Operator::LtEq, "ijk", vec![1, 2, 3]);
+        test(Operator::LtEq, "hz", vec![1, 2]);
+        test(Operator::LtEq, "ijk", vec![1, 2]);
         test(Operator::LtEq, "sh", vec![1, 2, 3, 4]);
         test(Operator::LtEq, "ssh", vec![1, 2, 3, 4]);
-        test(Operator::LtEq, "sz", vec![1, 2, 3, 4, 5, 6]);
-        test(Operator::LtEq, "zz", vec![1, 2, 3, 4, 5, 6]);
+        test(Operator::LtEq, "sz", vec![1, 2, 3, 4, 5]);
+        test(Operator::LtEq, "zz", vec![1, 2, 3, 4, 5]);
+
+        // Finding regions by the second partitioning column.
+        let test_b = |op: Operator, value: i32, expected_regions: Vec<RegionNumber>| {
+            let exprs = vec![PartitionExpr {
+                column: "b".to_string(),
+                op,
+                value: value.into(),
+            }];
+            let regions = rule.find_regions_by_exprs(&exprs).unwrap();
+            assert_eq!(regions, expected_regions);
+        };
+        // Only "p3" (a = 'sh', 20 <= b < 50) and "p6" (a = MAXVALUE, b >= 200) constrain "b".
+        test_b(Operator::Lt, 1, vec![1, 2, 4, 5]);
+        test_b(Operator::Lt, 21, vec![1, 2, 3, 4, 5]);
+        test_b(Operator::Eq, 50, vec![1, 2, 4, 5]);
+        test_b(Operator::GtEq, 50, vec![1, 2, 4, 5, 6]);
+        test_b(Operator::Gt, 49, vec![1, 2, 3, 4, 5, 6]);
+
+        // Multiple exprs must all be satisfied.
+        let exprs = vec![
+            PartitionExpr {
+                column: "b".to_string(),
+                op: Operator::Lt,
+                value: 1_i32.into(),
+            },
+            PartitionExpr {
+                column: "a".to_string(),
+                op: Operator::Eq,
+                value: "sh".into(),
+            },
+        ];
+        let regions = rule.find_regions_by_exprs(&exprs).unwrap();
+        assert_eq!(regions, vec![2, 4]);
 
         // If trying to find regions that is not partitioning column, return all regions.
         let exprs = vec![
@@ -337,6 +447,63 @@ mod tests {
         assert_eq!(regions, vec![1, 2, 3, 4, 5, 6]);
     }
 
+    #[test]
+    fn test_find_regions_with_trailing_max_value() {
+        // PARTITION BY RANGE COLUMNS(a, b)
+        //   PARTITION p1 VALUES LESS THAN (10, 100),
+        //   PARTITION p2 VALUES LESS THAN (10, MAXVALUE),
+        //   PARTITION p3 VALUES LESS THAN (20, 100),
+        //   PARTITION p4 VALUES LESS THAN (MAXVALUE, MAXVALUE),
+        let rule = RangeColumnsPartitionRule::new(
+            vec!["a".to_string(), "b".to_string()],
+            vec![
+                vec![
+                    PartitionBound::Value(10_i32.into()),
+                    PartitionBound::Value(100_i32.into()),
+                ],
+                vec![
+                    PartitionBound::Value(10_i32.into()),
+                    PartitionBound::MaxValue,
+                ],
+                vec![
+                    PartitionBound::Value(20_i32.into()),
+                    PartitionBound::Value(100_i32.into()),
+                ],
+                vec![PartitionBound::MaxValue, PartitionBound::MaxValue],
+            ],
+            vec![1, 2, 3, 4],
+        );
+
+        let test = |exprs: Vec<(&str, Operator, i32)>, expected_regions: Vec<RegionNumber>| {
+            let exprs = exprs
+                .into_iter()
+                .map(|(column, op, value)| PartitionExpr::new(column, op, value.into()))
+                .collect::<Vec<_>>();
+            let regions = rule.find_regions_by_exprs(&exprs).unwrap();
+            assert_eq!(regions, expected_regions);
+        };
+
+        // No tuple "(10, b)" could reach "(10, MAXVALUE)", so "p3" never holds "a = 10".
+        test(vec![("a", Operator::Eq, 10)], vec![1, 2]);
+        test(vec![("a", Operator::Gt, 10)], vec![3, 4]);
+        test(vec![("a", Operator::GtEq, 20)], vec![3, 4]);
+        test(vec![("b", Operator::Lt, 50)], vec![1, 3, 4]);
+        test(
+            vec![("a", Operator::Eq, 10), ("b", Operator::Lt, 50)],
+            vec![1],
+        );
+        test(
+            vec![("a", Operator::Eq, 10), ("b", Operator::GtEq, 100)],
+            vec![1, 2],
+        );
+
+        assert_matches!(rule.find_region(&[10_i32.into(), 50_i32.into()]), Ok(1));
+        assert_matches!(rule.find_region(&[10_i32.into(), 100_i32.into()]), Ok(2));
+        assert_matches!(rule.find_region(&[10_i32.into(), 9999_i32.into()]), Ok(2));
+        assert_matches!(rule.find_region(&[11_i32.into(), 0_i32.into()]), Ok(3));
+        assert_matches!(rule.find_region(&[20_i32.into(), 100_i32.into()]), Ok(4));
+    }
+
     #[test]
     fn test_find_region() {
         // PARTITION BY RANGE COLUMNS(a) (
@@ -16,11 +16,13 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use api::v1::Rows;
+use common_meta::ddl::table_meta::PLACEMENT_HINT_KEY;
 use common_meta::key::table_route::TableRouteManager;
 use common_meta::kv_backend::KvBackendRef;
 use common_meta::peer::Peer;
 use common_meta::rpc::router;
 use common_meta::rpc::router::RegionRoute;
+use common_meta::DatanodeId;
 use common_query::prelude::Expr;
 use datafusion_expr::{BinaryExpr, Expr as DfExpr, Operator};
 use datatypes::prelude::Value;
@@ -56,6 +58,8 @@ pub struct PartitionRuleManager {
 pub struct PartitionInfo {
     pub id: RegionId,
     pub partition: PartitionDef,
+    /// The id of the datanode that the region prefers to be placed on.
+    pub placement_hint: Option<DatanodeId>,
 }
 
 impl PartitionRuleManager {
@@ -92,10 +96,16 @@ impl PartitionRuleManager {
                     table_id,
                 })?;
             let partition_def = PartitionDef::try_from(partition)?;
+            let placement_hint = r
+                .region
+                .attrs
+                .get(PLACEMENT_HINT_KEY)
+                .and_then(|x| x.parse().ok());
 
             partitions.push(PartitionInfo {
                 id: r.region.id,
                 partition: partition_def,
+                placement_hint,
             });
         }
         partitions.sort_by(|a, b| {
@@ -117,6 +117,28 @@ pub enum Error {
     InvalidBlobAreaEnd { offset: u64, location: Location },
 }
 
+impl Error {
+    /// Returns true if the puffin file is corrupt, e.g. it's truncated or its metadata
+    /// can't be decoded. IO errors other than an unexpected end of the file aren't
+    /// decode errors as they might be temporary.
+    pub fn is_decode_error(&self) -> bool {
+        match self {
+            Error::Seek { error, .. } | Error::Read { error, .. } => {
+                error.kind() == std::io::ErrorKind::UnexpectedEof
+            }
+            Error::MagicNotMatched { .. }
+            | Error::BytesToInteger { .. }
+            | Error::DeserializeJson { .. }
+            | Error::ParseStageNotMatch { .. }
+            | Error::UnexpectedFooterPayloadSize { .. }
+            | Error::UnexpectedPuffinFileSize { .. }
+            | Error::InvalidBlobOffset { .. }
+            | Error::InvalidBlobAreaEnd { .. } => true,
+            _ => false,
+        }
+    }
+}
+
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         use Error::*;
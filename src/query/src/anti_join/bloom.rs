@@ -0,0 +1,101 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A bloom filter of hashes of values.
+
+/// False positive rate of the bloom filter.
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// A bloom filter that tests whether a value might be in a set by its hash.
+///
+/// It never returns false negatives, false positives need an exact check.
+#[derive(Debug)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Creates a bloom filter for `num_items` values.
+    pub fn with_capacity(num_items: usize) -> BloomFilter {
+        let num_items = num_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-num_items * FALSE_POSITIVE_RATE.ln() / (ln2 * ln2)).ceil() as u64;
+        let num_bits = num_bits.max(64);
+        let num_hashes = ((num_bits as f64 / num_items * ln2).round() as u32).max(1);
+
+        BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Adds a value by its `hash`.
+    pub fn insert(&mut self, hash: u64) {
+        for bit in self.bit_indices(hash) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns false if the value with the `hash` is definitely not in the filter.
+    pub fn may_contain(&self, hash: u64) -> bool {
+        self.bit_indices(hash)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Returns indices of bits of the `hash`, derives hashes from the `hash` by
+    /// double hashing.
+    fn bit_indices(&self, hash: u64) -> impl Iterator<Item = u64> + '_ {
+        let h1 = hash;
+        // Makes the step odd so it never stays at the same bit.
+        let h2 = hash.rotate_left(32) | 1;
+        (0..self.num_hashes as u64)
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let state = RandomState::new();
+        let hash = |value: u64| state.hash_one(value);
+        let mut filter = BloomFilter::with_capacity(1000);
+        for value in 0..1000 {
+            filter.insert(hash(value));
+        }
+
+        // No false negatives.
+        assert!((0..1000).all(|value| filter.may_contain(hash(value))));
+        // Few false positives.
+        let false_positives = (1000..11000)
+            .filter(|value| filter.may_contain(hash(*value)))
+            .count();
+        assert!(false_positives < 500, "false positives: {false_positives}");
+    }
+
+    #[test]
+    fn test_empty_bloom_filter() {
+        let filter = BloomFilter::with_capacity(0);
+        assert!(!filter.may_contain(0));
+        assert!(!filter.may_contain(u64::MAX));
+    }
+}
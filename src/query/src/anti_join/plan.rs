@@ -0,0 +1,456 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Logical and physical plans of the bloom filter anti join.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use ahash::RandomState;
+use arrow::compute::{cast, filter_record_batch};
+use arrow_schema::{DataType, SchemaRef};
+use common_query::DfPhysicalPlan;
+use common_recordbatch::DfSendableRecordBatchStream;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::SessionState;
+use datafusion::physical_plan::metrics::{
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    collect, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+    PhysicalSortExpr, Statistics,
+};
+use datafusion_common::{DFSchemaRef, DataFusionError, ScalarValue};
+use datafusion_expr::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion_physical_expr::create_physical_expr;
+use datafusion_physical_expr::hash_utils::create_hashes;
+use datatypes::arrow::array::{Array, BooleanArray};
+use datatypes::arrow::record_batch::RecordBatch;
+use futures::{StreamExt, TryStreamExt};
+use tokio::sync::OnceCell;
+
+use crate::anti_join::bloom::BloomFilter;
+
+/// Returns rows of `input` whose `expr` is `NOT IN` values of the single column
+/// of `subquery`, with the semantics of SQL:
+/// - all rows are returned if the subquery is empty, even if `expr` is NULL
+/// - otherwise no row is returned if the subquery contains NULL
+/// - otherwise rows whose `expr` is NULL are not returned
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BloomAntiJoin {
+    /// The incoming logical plan.
+    pub input: Arc<LogicalPlan>,
+    /// Plan of values to reject.
+    pub subquery: Arc<LogicalPlan>,
+    /// Expression to check against values of the subquery.
+    pub expr: Expr,
+    pub schema: DFSchemaRef,
+}
+
+impl BloomAntiJoin {
+    pub fn new(input: Arc<LogicalPlan>, subquery: Arc<LogicalPlan>, expr: Expr) -> Self {
+        let schema = input.schema().clone();
+        Self {
+            input,
+            subquery,
+            expr,
+            schema,
+        }
+    }
+
+    pub fn to_execution_plan(
+        &self,
+        exec_input: Arc<dyn ExecutionPlan>,
+        exec_subquery: Arc<dyn ExecutionPlan>,
+        session_state: &SessionState,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        let expr = create_physical_expr(
+            &self.expr,
+            self.input.schema(),
+            &exec_input.schema(),
+            session_state.execution_props(),
+        )?;
+        Ok(Arc::new(BloomAntiJoinExec::new(
+            exec_input,
+            exec_subquery,
+            expr,
+        )))
+    }
+}
+
+impl UserDefinedLogicalNodeCore for BloomAntiJoin {
+    fn name(&self) -> &str {
+        "BloomAntiJoin"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input, &self.subquery]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![self.expr.clone()]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "BloomAntiJoin: {} NOT IN subquery", self.expr)
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(1, exprs.len());
+        assert_eq!(2, inputs.len());
+
+        Self::new(
+            Arc::new(inputs[0].clone()),
+            Arc::new(inputs[1].clone()),
+            exprs[0].clone(),
+        )
+    }
+}
+
+/// Executes [BloomAntiJoin]. It collects values of the subquery once and shares
+/// them between partitions of the input.
+#[derive(Debug)]
+pub struct BloomAntiJoinExec {
+    input: Arc<dyn ExecutionPlan>,
+    subquery: Arc<dyn ExecutionPlan>,
+    expr: Arc<dyn PhysicalExpr>,
+    block_list: Arc<OnceCell<Arc<BlockList>>>,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl BloomAntiJoinExec {
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        subquery: Arc<dyn ExecutionPlan>,
+        expr: Arc<dyn PhysicalExpr>,
+    ) -> Self {
+        Self {
+            input,
+            subquery,
+            expr,
+            block_list: Arc::new(OnceCell::new()),
+            metric: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl DisplayAs for BloomAntiJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "BloomAntiJoinExec: expr={}", self.expr)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for BloomAntiJoinExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn DfPhysicalPlan>> {
+        vec![self.input.clone(), self.subquery.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn DfPhysicalPlan>>,
+    ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
+        assert_eq!(2, children.len());
+        Ok(Arc::new(Self::new(
+            children[0].clone(),
+            children[1].clone(),
+            self.expr.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<common_query::physical_plan::TaskContext>,
+    ) -> DfResult<DfSendableRecordBatchStream> {
+        let data_type = self.expr.data_type(&self.input.schema())?;
+        let input = self.input.execute(partition, context.clone())?;
+        let subquery = self.subquery.clone();
+        let block_list = self.block_list.clone();
+        let expr = self.expr.clone();
+        let baseline_metric = BaselineMetrics::new(&self.metric, partition);
+        let skipped_rows =
+            MetricBuilder::new(&self.metric).counter("exact_check_skipped_rows", partition);
+
+        let stream = futures::stream::once(async move {
+            // Only one partition collects the subquery, others wait for it.
+            let block_list = block_list
+                .get_or_try_init(|| async move {
+                    let batches = collect(subquery, context).await?;
+                    BlockList::try_new(&batches, data_type).map(Arc::new)
+                })
+                .await?
+                .clone();
+            let stream = input.map(move |batch| {
+                let batch = block_list.filter(&expr, batch?, &skipped_rows)?;
+                baseline_metric.record_output(batch.num_rows());
+                Ok(batch)
+            });
+            Ok::<_, DataFusionError>(stream)
+        })
+        .try_flatten();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.input.schema(),
+            stream,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Values of the subquery to reject.
+#[derive(Debug)]
+struct BlockList {
+    /// Type of values, the same as the type of the checked expression.
+    data_type: DataType,
+    /// Number of values, including NULLs.
+    num_values: usize,
+    /// Whether any value is NULL.
+    has_null: bool,
+    random_state: RandomState,
+    /// Bloom filter of hashes of non-null values.
+    bloom_filter: BloomFilter,
+    /// Non-null values for the exact check, as the bloom filter has false positives.
+    values: HashSet<ScalarValue>,
+}
+
+impl BlockList {
+    /// Builds the block list from the first column of `batches`, values are cast to
+    /// the `data_type`.
+    fn try_new(batches: &[RecordBatch], data_type: DataType) -> DfResult<BlockList> {
+        let num_values = batches.iter().map(|batch| batch.num_rows()).sum();
+        let random_state = RandomState::new();
+        let mut bloom_filter = BloomFilter::with_capacity(num_values);
+        let mut values = HashSet::with_capacity(num_values);
+        let mut has_null = false;
+        let mut hashes = Vec::new();
+        for batch in batches {
+            let array = cast(batch.column(0), &data_type)?;
+            hashes.clear();
+            hashes.resize(array.len(), 0);
+            create_hashes(&[array.clone()], &random_state, &mut hashes)?;
+            for (i, hash) in hashes.iter().enumerate() {
+                if array.is_null(i) {
+                    has_null = true;
+                    continue;
+                }
+                bloom_filter.insert(*hash);
+                let _ = values.insert(ScalarValue::try_from_array(&array, i)?);
+            }
+        }
+
+        Ok(BlockList {
+            data_type,
+            num_values,
+            has_null,
+            random_state,
+            bloom_filter,
+            values,
+        })
+    }
+
+    /// Filters rows of the `batch` whose `expr` is not in the list. Rows the bloom filter
+    /// proves not in the list skip the exact check, they are counted in `skipped_rows`.
+    fn filter(
+        &self,
+        expr: &Arc<dyn PhysicalExpr>,
+        batch: RecordBatch,
+        skipped_rows: &Count,
+    ) -> DfResult<RecordBatch> {
+        if self.num_values == 0 {
+            return Ok(batch);
+        }
+        if self.has_null {
+            return Ok(RecordBatch::new_empty(batch.schema()));
+        }
+
+        let array = cast(
+            &expr.evaluate(&batch)?.into_array(batch.num_rows()),
+            &self.data_type,
+        )?;
+        let mut hashes = vec![0; array.len()];
+        create_hashes(&[array.clone()], &self.random_state, &mut hashes)?;
+        let mut num_skipped = 0;
+        let mask = (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    return Ok(Some(false));
+                }
+                if !self.bloom_filter.may_contain(hashes[i]) {
+                    num_skipped += 1;
+                    return Ok(Some(true));
+                }
+                let value = ScalarValue::try_from_array(&array, i)?;
+                Ok(Some(!self.values.contains(&value)))
+            })
+            .collect::<DfResult<BooleanArray>>()?;
+        skipped_rows.add(num_skipped);
+
+        Ok(filter_record_batch(&batch, &mask)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_schema::{Field, Schema};
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
+    use datafusion_physical_expr::expressions::Column;
+    use datatypes::arrow::array::{ArrayRef, Int32Array, Int64Array};
+
+    use super::*;
+
+    fn new_memory_exec(name: &str, data_type: DataType, arrays: Vec<ArrayRef>) -> Arc<MemoryExec> {
+        let schema = Arc::new(Schema::new(vec![Field::new(name, data_type, true)]));
+        // Each array is a partition.
+        let partitions: Vec<_> = arrays
+            .into_iter()
+            .map(|array| vec![RecordBatch::try_new(schema.clone(), vec![array]).unwrap()])
+            .collect();
+        Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap())
+    }
+
+    async fn do_anti_join_test(subquery: Vec<Option<i32>>, mut expected: Vec<Option<i64>>) {
+        let input = new_memory_exec(
+            "id",
+            DataType::Int64,
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(3)])),
+                Arc::new(Int64Array::from_iter((4..1000).map(Some))),
+            ],
+        );
+        // The subquery has a different type so values are cast.
+        let subquery = new_memory_exec(
+            "blocked",
+            DataType::Int32,
+            vec![Arc::new(Int32Array::from(subquery))],
+        );
+        let exec = Arc::new(BloomAntiJoinExec::new(
+            input,
+            subquery,
+            Arc::new(Column::new("id", 0)),
+        ));
+
+        let session_context = SessionContext::default();
+        let batches = collect(exec.clone(), session_context.task_ctx())
+            .await
+            .unwrap();
+        // Partitions are merged in any order.
+        let mut actual: Vec<_> = batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                array.iter().collect::<Vec<_>>()
+            })
+            .collect();
+        actual.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(expected, actual);
+    }
+
+    #[tokio::test]
+    async fn test_anti_join() {
+        let blocked: Vec<_> = (2..1000).step_by(2).map(Some).collect();
+        let expected: Vec<_> = (1..1000).step_by(2).map(|v| Some(v as i64)).collect();
+        do_anti_join_test(blocked, expected).await;
+    }
+
+    #[tokio::test]
+    async fn test_anti_join_null_in_subquery() {
+        // No row is returned if the subquery has NULL.
+        do_anti_join_test(vec![Some(1), None], vec![]).await;
+    }
+
+    #[tokio::test]
+    async fn test_anti_join_empty_subquery() {
+        // All rows including NULL are returned if the subquery is empty.
+        let mut expected = vec![Some(1), Some(2), None, Some(3)];
+        expected.extend((4..1000).map(Some));
+        do_anti_join_test(vec![], expected).await;
+    }
+
+    #[tokio::test]
+    async fn test_anti_join_skip_exact_check() {
+        let input = new_memory_exec(
+            "id",
+            DataType::Int64,
+            vec![Arc::new(Int64Array::from_iter((0..1000).map(Some)))],
+        );
+        let subquery = new_memory_exec(
+            "blocked",
+            DataType::Int64,
+            vec![Arc::new(Int64Array::from(vec![10, 20, 30]))],
+        );
+        let exec = Arc::new(BloomAntiJoinExec::new(
+            input,
+            subquery,
+            Arc::new(Column::new("id", 0)),
+        ));
+
+        let session_context = SessionContext::default();
+        let batches = collect(exec.clone(), session_context.task_ctx())
+            .await
+            .unwrap();
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(997, num_rows);
+
+        // Most rows are not in the bloom filter so they skip the exact check.
+        let metrics = exec.metrics().unwrap();
+        let skipped = metrics
+            .sum_by_name("exact_check_skipped_rows")
+            .unwrap()
+            .as_usize();
+        assert!(skipped > 900, "skipped: {skipped}");
+        assert_eq!(Some(997), metrics.output_rows());
+    }
+}
@@ -24,7 +24,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_base::Plugins;
 use common_error::ext::BoxedError;
-use common_function::function::FunctionRef;
+use common_function::function::{FunctionRef, ScalarFunctionMetaRef};
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_function::scalars::udf::create_udf;
 use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalPlanAdapter};
@@ -36,6 +36,7 @@ use common_recordbatch::{
 };
 use common_telemetry::tracing;
 use datafusion::common::Column;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
 use datafusion::physical_plan::analyze::AnalyzeExec;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::ExecutionPlan;
@@ -58,8 +59,10 @@ use crate::error::{
     MissingTableMutationHandlerSnafu, MissingTimestampColumnSnafu, QueryExecutionSnafu, Result,
     TableNotFoundSnafu, UnimplementedSnafu, UnsupportedExprSnafu,
 };
+use crate::exec_stats::ExecStatsStream;
 use crate::executor::QueryExecutor;
 use crate::logical_optimizer::LogicalOptimizer;
+use crate::optimizer::predicate_order::PredicateOrderRule;
 use crate::physical_optimizer::PhysicalOptimizer;
 use crate::physical_planner::PhysicalPlanner;
 use crate::physical_wrapper::PhysicalPlanWrapperRef;
@@ -113,7 +116,7 @@ impl DatafusionQueryEngine {
         );
 
         let default_catalog = &query_ctx.current_catalog().to_owned();
-        let default_schema = &query_ctx.current_schema().to_owned();
+        let default_schema = &query_ctx.current_schema();
         let table_name = dml.table_name.resolve(default_catalog, default_schema);
         let table = self.find_table(&table_name).await?;
 
@@ -278,6 +281,12 @@ impl QueryEngine for DatafusionQueryEngine {
         self.state.register_udf(create_udf(func));
     }
 
+    /// Scalar function names are looked up in lowercase unless quoted, the same as
+    /// [QueryEngine::register_aggregate_function].
+    fn register_scalar_function(&self, func: ScalarFunctionMetaRef) {
+        self.state.register_udf(create_udf(func.create()));
+    }
+
     fn read_table(&self, table: TableRef) -> Result<DataFrame> {
         Ok(DataFrame::DataFusion(
             self.state
@@ -355,6 +364,20 @@ impl PhysicalOptimizer for DatafusionQueryEngine {
 
         let state = ctx.state();
         let config = state.config_options();
+        let ordered_predicates = ctx.query_ctx().ordered_predicates();
+        let optimize = |mut plan: Arc<dyn ExecutionPlan>| -> Result<Arc<dyn ExecutionPlan>> {
+            for optimizer in state.physical_optimizers() {
+                plan = optimizer.optimize(plan, config).context(DataFusionSnafu)?;
+            }
+            // Splits filters after other rules so they don't merge the filters again.
+            // Queries with the hint keep the order of their predicates.
+            if !ordered_predicates {
+                plan = PredicateOrderRule
+                    .optimize(plan, config)
+                    .context(DataFusionSnafu)?;
+            }
+            Ok(plan)
+        };
         let df_plan = plan
             .as_any()
             .downcast_ref::<PhysicalPlanAdapter>()
@@ -366,23 +389,12 @@ impl PhysicalOptimizer for DatafusionQueryEngine {
         // skip optimize AnalyzeExec plan
         let optimized_plan =
             if let Some(analyze_plan) = df_plan.as_any().downcast_ref::<AnalyzeExec>() {
-                let mut new_plan = analyze_plan.input().clone();
-                for optimizer in state.physical_optimizers() {
-                    new_plan = optimizer
-                        .optimize(new_plan, config)
-                        .context(DataFusionSnafu)?;
-                }
+                let new_plan = optimize(analyze_plan.input().clone())?;
                 Arc::new(analyze_plan.clone())
                     .with_new_children(vec![new_plan])
                     .unwrap()
             } else {
-                let mut new_plan = df_plan;
-                for optimizer in state.physical_optimizers() {
-                    new_plan = optimizer
-                        .optimize(new_plan, config)
-                        .context(DataFusionSnafu)?;
-                }
-                new_plan
+                optimize(df_plan)?
             };
 
         Ok(Arc::new(PhysicalPlanAdapter::new(
@@ -402,13 +414,13 @@ impl QueryExecutor for DatafusionQueryEngine {
         let _timer = metrics::METRIC_EXEC_PLAN_ELAPSED.start_timer();
         let task_ctx = ctx.build_task_ctx();
 
-        match plan.output_partitioning().partition_count() {
-            0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
-            1 => Ok(plan
+        let stream = match plan.output_partitioning().partition_count() {
+            0 => return Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
+            1 => plan
                 .execute(0, task_ctx)
                 .context(error::ExecutePhysicalPlanSnafu)
                 .map_err(BoxedError::new)
-                .context(QueryExecutionSnafu))?,
+                .context(QueryExecutionSnafu)?,
             _ => {
                 // merge into a single partition
                 let plan =
@@ -424,9 +436,10 @@ impl QueryExecutor for DatafusionQueryEngine {
                     .context(error::ConvertDfRecordBatchStreamSnafu)
                     .map_err(BoxedError::new)
                     .context(QueryExecutionSnafu)?;
-                Ok(Box::pin(stream))
+                Box::pin(stream)
             }
-        }
+        };
+        Ok(Box::pin(ExecStatsStream::new(stream, plan.clone())))
     }
 }
 
@@ -85,9 +85,16 @@ async fn resolve_tables(
         if let Entry::Vacant(v) = tables.entry(resolved_name.to_string()) {
             // Try our best to resolve the tables here, but we don't return an error if table is not found,
             // because the table name may be a temporary name of CTE or view, they can't be found until plan
-            // execution.
-            if let Ok(table) = table_provider.resolve_table(table_name).await {
-                let _ = v.insert(table);
+            // execution. A missing catalog or schema can't be a CTE though, so it is reported directly.
+            match table_provider.resolve_table(table_name).await {
+                Ok(table) => {
+                    let _ = v.insert(table);
+                }
+                Err(
+                    e @ (catalog::error::Error::SchemaNotFound { .. }
+                    | catalog::error::Error::CatalogNotFound { .. }),
+                ) => return Err(e).context(CatalogSnafu),
+                Err(_) => {}
             }
         }
     }
@@ -25,7 +25,8 @@ use common_query::physical_plan::TaskContext;
 use common_recordbatch::adapter::DfRecordBatchStreamAdapter;
 use common_recordbatch::error::ExternalSnafu;
 use common_recordbatch::{
-    DfSendableRecordBatchStream, RecordBatch, RecordBatchStreamWrapper, SendableRecordBatchStream,
+    DfSendableRecordBatchStream, RecordBatch, RecordBatchMetrics, RecordBatchStreamWrapper,
+    SendableRecordBatchStream,
 };
 use common_telemetry::tracing;
 use common_telemetry::tracing_context::TracingContext;
@@ -40,7 +41,10 @@ use datatypes::schema::{Schema, SchemaRef};
 use futures_util::StreamExt;
 use greptime_proto::v1::region::{QueryRequest, RegionRequestHeader};
 use snafu::ResultExt;
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, ScanRequest};
+use table::table::metrics::{
+    INDEX_APPLIED_FILES_METRIC, INDEX_POSTINGS_METRIC, INDEX_SELECTED_ROW_GROUPS_METRIC,
+};
 use tokio::time::Instant;
 
 use crate::error::ConvertSchemaSnafu;
@@ -120,6 +124,8 @@ pub struct MergeScanExec {
     substrait_plan: Bytes,
     schema: SchemaRef,
     arrow_schema: ArrowSchemaRef,
+    /// Scan request of the table, its hints are passed to regions.
+    scan_request: ScanRequest,
     region_query_handler: RegionQueryHandlerRef,
     metric: ExecutionPlanMetricsSet,
 }
@@ -140,6 +146,7 @@ impl MergeScanExec {
         regions: Vec<RegionId>,
         substrait_plan: Bytes,
         arrow_schema: &ArrowSchema,
+        scan_request: ScanRequest,
         region_query_handler: RegionQueryHandlerRef,
     ) -> Result<Self> {
         let arrow_schema_without_metadata = Self::arrow_schema_without_metadata(arrow_schema);
@@ -151,6 +158,7 @@ impl MergeScanExec {
             substrait_plan,
             schema: schema_without_metadata,
             arrow_schema: arrow_schema_without_metadata,
+            scan_request,
             region_query_handler,
             metric: ExecutionPlanMetricsSet::new(),
         })
@@ -166,7 +174,18 @@ impl MergeScanExec {
 
         let dbname = context.task_id().unwrap_or_default();
 
-        let tracing_context = TracingContext::from_current_span().to_w3c();
+        let mut tracing_context = TracingContext::from_current_span().to_w3c();
+        // The header of region requests has no field for hints of the scan.
+        self.scan_request.encode_hints(&mut tracing_context);
+        let follow = self.scan_request.follow;
+        let new_request = move |region_id: RegionId| QueryRequest {
+            header: Some(RegionRequestHeader {
+                tracing_context: tracing_context.clone(),
+                dbname: dbname.clone(),
+            }),
+            region_id: region_id.into(),
+            plan: substrait_plan.clone(),
+        };
 
         let stream = Box::pin(stream!({
             METRIC_MERGE_SCAN_REGIONS.observe(regions.len() as f64);
@@ -174,46 +193,67 @@ impl MergeScanExec {
             let mut ready_timer = metric.ready_time().timer();
             let mut first_consume_timer = Some(metric.first_consume_time().timer());
 
-            for region_id in regions {
-                let request = QueryRequest {
-                    header: Some(RegionRequestHeader {
-                        tracing_context: tracing_context.clone(),
-                        dbname: dbname.clone(),
-                    }),
-                    region_id: region_id.into(),
-                    plan: substrait_plan.clone(),
-                };
-                let mut stream = region_query_handler
-                    .do_get(request)
-                    .await
-                    .map_err(|e| {
-                        METRIC_MERGE_SCAN_ERRORS_TOTAL.inc();
-                        BoxedError::new(e)
-                    })
-                    .context(ExternalSnafu)?;
-
+            if follow {
+                // Follow scans of regions never end, so we poll all regions at the same time.
+                let mut streams = Vec::with_capacity(regions.len());
+                for region_id in &regions {
+                    let stream = region_query_handler
+                        .do_get(new_request(*region_id))
+                        .await
+                        .map_err(|e| {
+                            METRIC_MERGE_SCAN_ERRORS_TOTAL.inc();
+                            BoxedError::new(e)
+                        })
+                        .context(ExternalSnafu)?;
+                    streams.push(stream);
+                }
                 ready_timer.stop();
 
-                let mut poll_duration = Duration::new(0, 0);
-
-                let mut poll_timer = Instant::now();
+                let mut stream = futures_util::stream::select_all(streams);
                 while let Some(batch) = stream.next().await {
-                    let poll_elapsed = poll_timer.elapsed();
-                    poll_duration += poll_elapsed;
-
                     let batch = batch?;
-                    // reconstruct batch using `self.schema`
-                    // to remove metadata and correct column name
                     let batch = RecordBatch::new(schema.clone(), batch.columns().iter().cloned())?;
                     metric.record_output_batch_rows(batch.num_rows());
-                    if let Some(first_consume_timer) = first_consume_timer.as_mut().take() {
-                        first_consume_timer.stop();
-                    }
                     yield Ok(batch);
-                    // reset poll timer
-                    poll_timer = Instant::now();
                 }
-                METRIC_MERGE_SCAN_POLL_ELAPSED.observe(poll_duration.as_secs_f64());
+            } else {
+                for region_id in regions {
+                    let mut stream = region_query_handler
+                        .do_get(new_request(region_id))
+                        .await
+                        .map_err(|e| {
+                            METRIC_MERGE_SCAN_ERRORS_TOTAL.inc();
+                            BoxedError::new(e)
+                        })
+                        .context(ExternalSnafu)?;
+
+                    ready_timer.stop();
+
+                    let mut poll_duration = Duration::new(0, 0);
+
+                    let mut poll_timer = Instant::now();
+                    while let Some(batch) = stream.next().await {
+                        let poll_elapsed = poll_timer.elapsed();
+                        poll_duration += poll_elapsed;
+
+                        let batch = batch?;
+                        // reconstruct batch using `self.schema`
+                        // to remove metadata and correct column name
+                        let batch =
+                            RecordBatch::new(schema.clone(), batch.columns().iter().cloned())?;
+                        metric.record_output_batch_rows(batch.num_rows());
+                        if let Some(first_consume_timer) = first_consume_timer.as_mut().take() {
+                            first_consume_timer.stop();
+                        }
+                        yield Ok(batch);
+                        // reset poll timer
+                        poll_timer = Instant::now();
+                    }
+                    METRIC_MERGE_SCAN_POLL_ELAPSED.observe(poll_duration.as_secs_f64());
+                    if let Some(region_metrics) = stream.metrics() {
+                        metric.record_region_metrics(&region_metrics);
+                    }
+                }
             }
         }));
 
@@ -313,6 +353,12 @@ struct MergeScanMetric {
     finish_time: Time,
     /// Count of rows fetched from remote
     output_rows: Count,
+    /// Count of SSTs whose indexes are applied by region scans
+    index_applied_files: Count,
+    /// Count of row groups selected by indexes of SSTs
+    index_selected_row_groups: Count,
+    /// Count of postings read from indexes of SSTs
+    index_postings: Count,
 }
 
 impl MergeScanMetric {
@@ -322,6 +368,10 @@ impl MergeScanMetric {
             first_consume_time: MetricBuilder::new(metric).subset_time("first_consume_time", 1),
             finish_time: MetricBuilder::new(metric).subset_time("finish_time", 1),
             output_rows: MetricBuilder::new(metric).output_rows(1),
+            index_applied_files: MetricBuilder::new(metric).counter(INDEX_APPLIED_FILES_METRIC, 1),
+            index_selected_row_groups: MetricBuilder::new(metric)
+                .counter(INDEX_SELECTED_ROW_GROUPS_METRIC, 1),
+            index_postings: MetricBuilder::new(metric).counter(INDEX_POSTINGS_METRIC, 1),
         }
     }
 
@@ -340,4 +390,13 @@ impl MergeScanMetric {
     pub fn record_output_batch_rows(&self, num_rows: usize) {
         self.output_rows.add(num_rows);
     }
+
+    /// Records metrics reported by the stream of a region once it is exhausted.
+    pub fn record_region_metrics(&self, metrics: &RecordBatchMetrics) {
+        self.index_applied_files
+            .add(metrics.index_applied_files as usize);
+        self.index_selected_row_groups
+            .add(metrics.index_selected_row_groups as usize);
+        self.index_postings.add(metrics.index_postings as usize);
+    }
 }
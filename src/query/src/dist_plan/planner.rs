@@ -30,7 +30,7 @@ use datafusion_common::TableReference;
 use datafusion_expr::{LogicalPlan, UserDefinedLogicalNode};
 use datafusion_optimizer::analyzer::Analyzer;
 use snafu::{OptionExt, ResultExt};
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, ScanRequest};
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 pub use table::metadata::TableType;
 use table::table::adapter::DfTableProviderAdapter;
@@ -85,7 +85,7 @@ impl ExtensionPlanner for DistExtensionPlanner {
         }
 
         let optimized_plan = self.optimize_input_logical_plan(session_state, input_plan)?;
-        let Some(table_name) = Self::extract_full_table_name(input_plan)? else {
+        let Some((table_name, scan_request)) = Self::extract_full_table_name(input_plan)? else {
             // no relation found in input plan, going to execute them locally
             return fallback(&optimized_plan).await;
         };
@@ -108,6 +108,7 @@ impl ExtensionPlanner for DistExtensionPlanner {
             regions,
             substrait_plan,
             &schema,
+            scan_request,
             self.region_query_handler.clone(),
         )?;
         Ok(Some(Arc::new(merge_scan_plan) as _))
@@ -115,11 +116,14 @@ impl ExtensionPlanner for DistExtensionPlanner {
 }
 
 impl DistExtensionPlanner {
-    /// Extract fully resolved table name from logical plan
-    fn extract_full_table_name(plan: &LogicalPlan) -> Result<Option<TableName>> {
+    /// Extract fully resolved table name and the scan request with hints of the table
+    /// from logical plan
+    fn extract_full_table_name(plan: &LogicalPlan) -> Result<Option<(TableName, ScanRequest)>> {
         let mut extractor = TableNameExtractor::default();
         let _ = plan.visit(&mut extractor)?;
-        Ok(extractor.table_name)
+        Ok(extractor
+            .table_name
+            .map(|table_name| (table_name, extractor.scan_request)))
     }
 
     /// Apply the fully resolved table name to the TableScan plan
@@ -160,6 +164,8 @@ impl DistExtensionPlanner {
 #[derive(Default)]
 struct TableNameExtractor {
     pub table_name: Option<TableName>,
+    /// Scan request of the table, with hints to pass to regions.
+    pub scan_request: ScanRequest,
 }
 
 impl TreeNodeVisitor for TableNameExtractor {
@@ -175,6 +181,7 @@ impl TreeNodeVisitor for TableNameExtractor {
                         .downcast_ref::<DfTableProviderAdapter>()
                     {
                         if provider.table().table_type() == TableType::Base {
+                            self.scan_request = provider.get_scan_req();
                             let info = provider.table().table_info();
                             self.table_name = Some(TableName::new(
                                 info.catalog_name.clone(),
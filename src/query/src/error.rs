@@ -250,6 +250,15 @@ pub enum Error {
 
     #[snafu(display("Range Query: {}", msg))]
     RangeQuery { msg: String, location: Location },
+
+    #[snafu(display(
+        "Query is too complex, the depth of nested queries exceeds {}",
+        max_depth
+    ))]
+    QueryTooDeep {
+        max_depth: usize,
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -274,7 +283,8 @@ impl ErrorExt for Error {
             | ConvertSchema { .. }
             | AddSystemTimeOverflow { .. }
             | ColumnSchemaIncompatible { .. }
-            | ColumnSchemaNoDefault { .. } => StatusCode::InvalidArguments,
+            | ColumnSchemaNoDefault { .. }
+            | QueryTooDeep { .. } => StatusCode::InvalidArguments,
 
             BuildBackend { .. } | ListObjects { .. } => StatusCode::StorageUnavailable,
             EncodeSubstraitLogicalPlan { source, .. } => source.status_code(),
@@ -0,0 +1,116 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Collects statistics of query executions, e.g. rows scanned, for clients.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::{
+    OrderOption, RecordBatch, RecordBatchMetrics, RecordBatchStream, SendableRecordBatchStream,
+};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use table::table::metrics::{
+    FETCHED_BYTES_METRIC, INDEX_APPLIED_FILES_METRIC, INDEX_POSTINGS_METRIC,
+    INDEX_SELECTED_ROW_GROUPS_METRIC,
+};
+
+/// Stream that reports the execution statistics of the plan producing it in
+/// [RecordBatchStream::metrics].
+pub(crate) struct ExecStatsStream {
+    stream: SendableRecordBatchStream,
+    plan: PhysicalPlanRef,
+    start: Instant,
+    /// Time elapsed when the stream is exhausted.
+    elapsed: Option<Duration>,
+}
+
+impl ExecStatsStream {
+    pub(crate) fn new(stream: SendableRecordBatchStream, plan: PhysicalPlanRef) -> Self {
+        Self {
+            stream,
+            plan,
+            start: Instant::now(),
+            elapsed: None,
+        }
+    }
+}
+
+impl RecordBatchStream for ExecStatsStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn output_ordering(&self) -> Option<&[OrderOption]> {
+        self.stream.output_ordering()
+    }
+
+    fn metrics(&self) -> Option<RecordBatchMetrics> {
+        let elapsed = self.elapsed.unwrap_or_else(|| self.start.elapsed());
+        let mut metrics = RecordBatchMetrics {
+            elapsed_ms: elapsed.as_millis() as u64,
+            ..Default::default()
+        };
+        collect_plan_metrics(&self.plan, &mut metrics);
+        Some(metrics)
+    }
+}
+
+impl Stream for ExecStatsStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.stream.as_mut().poll_next(cx);
+        if let Poll::Ready(None) = poll {
+            if self.elapsed.is_none() {
+                self.elapsed = Some(self.start.elapsed());
+            }
+        }
+        poll
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// Adds metrics of the `plan` and its children to `metrics`.
+fn collect_plan_metrics(plan: &PhysicalPlanRef, metrics: &mut RecordBatchMetrics) {
+    let children = plan.children();
+    if let Some(plan_metrics) = plan.metrics() {
+        // Rows output by leaves of the plan are rows scanned from tables.
+        if children.is_empty() {
+            metrics.scanned_rows += plan_metrics.output_rows().unwrap_or(0) as u64;
+        }
+        if let Some(fetched_bytes) = plan_metrics.sum_by_name(FETCHED_BYTES_METRIC) {
+            metrics.fetched_bytes += fetched_bytes.as_usize() as u64;
+        }
+        let sum = |name: &str| {
+            plan_metrics
+                .sum_by_name(name)
+                .map(|value| value.as_usize() as u64)
+                .unwrap_or(0)
+        };
+        metrics.index_applied_files += sum(INDEX_APPLIED_FILES_METRIC);
+        metrics.index_selected_row_groups += sum(INDEX_SELECTED_ROW_GROUPS_METRIC);
+        metrics.index_postings += sum(INDEX_POSTINGS_METRIC);
+    }
+    for child in &children {
+        collect_plan_metrics(child, metrics);
+    }
+}
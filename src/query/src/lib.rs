@@ -15,10 +15,12 @@
 #![feature(let_chains)]
 #![feature(int_roundings)]
 
+mod anti_join;
 pub mod dataframe;
 pub mod datafusion;
 pub mod dist_plan;
 pub mod error;
+mod exec_stats;
 pub mod executor;
 pub mod logical_optimizer;
 mod metrics;
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod not_in_subquery;
 pub mod order_hint;
+pub mod predicate_order;
 pub mod string_normalization;
 pub mod type_conversion;
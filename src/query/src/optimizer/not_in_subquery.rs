@@ -0,0 +1,85 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion_common::Result as DataFusionResult;
+use datafusion_expr::expr::InSubquery;
+use datafusion_expr::logical_plan::{Extension, Filter};
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::{Expr, LogicalPlan};
+use datafusion_optimizer::optimizer::ApplyOrder;
+use datafusion_optimizer::{OptimizerConfig, OptimizerRule};
+
+use crate::anti_join::plan::BloomAntiJoin;
+
+/// This rule rewrites a filter of `expr NOT IN (subquery)` to a [BloomAntiJoin],
+/// which rejects most rows by a bloom filter instead of a hash join. Other
+/// conjunctions of the filter are kept in a filter above the join.
+///
+/// Only uncorrelated subqueries are rewritten. It must run before the rule
+/// decorrelating subqueries to joins.
+pub struct NotInSubqueryRule;
+
+impl OptimizerRule for NotInSubqueryRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DataFusionResult<Option<LogicalPlan>> {
+        let LogicalPlan::Filter(filter) = plan else {
+            return Ok(None);
+        };
+
+        let mut not_in = None;
+        let mut others = Vec::new();
+        for expr in split_conjunction(&filter.predicate) {
+            match expr {
+                Expr::InSubquery(InSubquery {
+                    expr,
+                    subquery,
+                    negated: true,
+                }) if not_in.is_none()
+                    && subquery.outer_ref_columns.is_empty()
+                    && subquery.subquery.schema().fields().len() == 1 =>
+                {
+                    not_in = Some((expr.as_ref().clone(), subquery.subquery.clone()));
+                }
+                expr => others.push(expr.clone()),
+            }
+        }
+        let Some((expr, subquery)) = not_in else {
+            return Ok(None);
+        };
+
+        let join = LogicalPlan::Extension(Extension {
+            node: Arc::new(BloomAntiJoin::new(filter.input.clone(), subquery, expr)),
+        });
+        match conjunction(others) {
+            Some(predicate) => Ok(Some(LogicalPlan::Filter(Filter::try_new(
+                predicate,
+                Arc::new(join),
+            )?))),
+            None => Ok(Some(join)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "NotInSubqueryRule"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion::config::ConfigOptions;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::Result as DataFusionResult;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column, LikeExpr, Literal};
+use datafusion_physical_expr::{split_conjunction, PhysicalExpr, ScalarFunctionExpr};
+
+/// Cost of a comparison or any other cheap expression.
+const CHEAP_COST: usize = 1;
+/// Cost of a `LIKE` expression.
+const LIKE_COST: usize = 10;
+/// Cost of a regex match or a function call.
+const EXPENSIVE_COST: usize = 100;
+
+/// This rule splits a filter of AND-ed predicates into nested filters by the
+/// estimated cost of the predicates, so cheap predicates (e.g. column comparisons)
+/// are evaluated first and expensive predicates (e.g. regex matches and function
+/// calls) are only evaluated on rows the cheap predicates accept.
+///
+/// Predicates with the same cost keep their order in a filter. A row passes the
+/// nested filters iff all predicates are true, like the original filter, but
+/// expensive predicates are not evaluated on rows rejected before, assuming
+/// predicates, including UDFs, are free of side effects.
+///
+/// Queries can keep the order of predicates with the `ordered_predicates` hint.
+pub struct PredicateOrderRule;
+
+impl PredicateOrderRule {
+    pub const NAME: &'static str = "PredicateOrderRule";
+}
+
+impl PhysicalOptimizerRule for PredicateOrderRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&Self::split_filter)
+    }
+
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+impl PredicateOrderRule {
+    fn split_filter(
+        plan: Arc<dyn ExecutionPlan>,
+    ) -> DataFusionResult<Transformed<Arc<dyn ExecutionPlan>>> {
+        let Some(filter) = plan.as_any().downcast_ref::<FilterExec>() else {
+            return Ok(Transformed::No(plan));
+        };
+
+        let mut predicates: Vec<_> = split_conjunction(filter.predicate())
+            .into_iter()
+            .map(|predicate| (expr_cost(predicate), predicate.clone()))
+            .collect();
+        // Stable sort keeps the order of predicates with the same cost.
+        predicates.sort_by_key(|(cost, _)| *cost);
+        if predicates.first().map(|(cost, _)| cost) == predicates.last().map(|(cost, _)| cost) {
+            // All predicates have the same cost.
+            return Ok(Transformed::No(plan));
+        }
+
+        // The cheapest predicates are in the innermost filter.
+        let mut input = filter.input().clone();
+        let mut group = Vec::new();
+        let mut predicates = predicates.into_iter().peekable();
+        while let Some((cost, predicate)) = predicates.next() {
+            group.push(predicate);
+            if predicates.peek().map_or(true, |(next, _)| *next != cost) {
+                let predicate = std::mem::take(&mut group)
+                    .into_iter()
+                    .reduce(|acc, predicate| {
+                        Arc::new(BinaryExpr::new(acc, Operator::And, predicate)) as _
+                    })
+                    .unwrap();
+                input = Arc::new(FilterExec::try_new(predicate, input)?);
+            }
+        }
+        Ok(Transformed::Yes(input))
+    }
+}
+
+/// Estimates the cost to evaluate the `expr`, which is the sum of costs of all
+/// nodes in the expression.
+fn expr_cost(expr: &Arc<dyn PhysicalExpr>) -> usize {
+    let any = expr.as_any();
+    let cost = if any.is::<Column>() || any.is::<Literal>() {
+        0
+    } else if any.is::<ScalarFunctionExpr>() {
+        EXPENSIVE_COST
+    } else if any.is::<LikeExpr>() {
+        LIKE_COST
+    } else if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+        match binary.op() {
+            Operator::RegexMatch
+            | Operator::RegexIMatch
+            | Operator::RegexNotMatch
+            | Operator::RegexNotIMatch => EXPENSIVE_COST,
+            _ => CHEAP_COST,
+        }
+    } else {
+        CHEAP_COST
+    };
+
+    cost + expr.children().iter().map(expr_cost).sum::<usize>()
+}
@@ -269,7 +269,8 @@ mod test {
             having: None, \
             named_window: [], \
             qualify: None \
-            }), order_by: [], limit: None, offset: None, fetch: None, locks: [] } }))");
+            }), order_by: [], limit: None, offset: None, fetch: None, locks: [] }, \
+            limit_all: false, follow: false, as_of: None, hints: [], sample: None }))");
 
         assert_eq!(format!("{stmt:?}"), expected);
     }
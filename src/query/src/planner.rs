@@ -12,21 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::ControlFlow;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use catalog::table_source::DfTableSourceProvider;
 use common_error::ext::BoxedError;
 use common_telemetry::tracing;
+use common_time::Timestamp;
+use datafusion::datasource::DefaultTableSource;
 use datafusion::execution::context::SessionState;
+use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion_common::{Column, DataFusionError, OwnedTableReference};
+use datafusion_expr::{col, lit, LogicalPlan as DfLogicalPlan, LogicalPlanBuilder, TableScan};
 use datafusion_sql::planner::{ParserOptions, SqlToRel};
+use datatypes::value::{timestamp_to_scalar_value, OrderedF64};
 use promql::planner::PromPlanner;
 use promql_parser::parser::EvalStmt;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
+use sql::ast::{ObjectName, Query, Visit, Visitor};
+use sql::statements::hint::{QueryHint, NO_INDEX, ORDERED_PREDICATES, USE_INDEX};
+use sql::statements::query::{AsOfClause, TableSampleClause};
 use sql::statements::statement::Statement;
+use store_api::storage::{IndexHint, SampleMethod, TableSample};
+use table::table::adapter::DfTableProviderAdapter;
 
-use crate::error::{PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu};
+use crate::error::{
+    DataFusionSnafu, PlanSqlSnafu, QueryPlanSnafu, QueryTooDeepSnafu, Result, SqlSnafu,
+};
 use crate::parser::QueryStatement;
 use crate::plan::LogicalPlan;
 use crate::query_engine::QueryEngineState;
@@ -54,7 +69,32 @@ impl DfLogicalPlanner {
 
     #[tracing::instrument(skip_all)]
     async fn plan_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
+        check_query_depth(&stmt, self.engine_state.max_plan_depth())?;
         let df_stmt = (&stmt).try_into().context(SqlSnafu)?;
+        let index_hint = match &stmt {
+            Statement::Query(query) => index_hint(&query.hints),
+            _ => None,
+        };
+        let follow = matches!(&stmt, Statement::Query(query) if query.follow);
+        let ordered_predicates = matches!(&stmt, Statement::Query(query) if query
+            .hints
+            .iter()
+            .any(|hint| hint.name == ORDERED_PREDICATES));
+        let as_of = match &stmt {
+            Statement::Query(query) => query.as_of.clone(),
+            _ => None,
+        };
+        let sample = match &stmt {
+            Statement::Query(query) => query.sample.clone(),
+            _ => None,
+        };
+        // Follow queries never end, so they don't need the default limit.
+        let default_limit = match &stmt {
+            Statement::Query(query) if !query.follow => query_ctx.default_limit().filter(|_| {
+                query.inner.limit.is_none() && query.inner.fetch.is_none() && !query.limit_all
+            }),
+            _ => None,
+        };
 
         let table_provider = DfTableSourceProvider::new(
             self.engine_state.catalog_manager().clone(),
@@ -62,6 +102,10 @@ impl DfLogicalPlanner {
             query_ctx.as_ref(),
         );
 
+        // The physical optimizer keeps the order of predicates of the statement
+        // with the hint.
+        query_ctx.set_ordered_predicates(ordered_predicates);
+
         let context_provider = DfContextProviderAdapter::try_new(
             self.engine_state.clone(),
             self.session_state.clone(),
@@ -81,9 +125,31 @@ impl DfLogicalPlanner {
         let result = sql_to_rel
             .statement_to_plan(df_stmt)
             .context(PlanSqlSnafu)?;
-        let plan = RangePlanRewriter::new(table_provider)
+        let plan = RangePlanRewriter::new(table_provider, self.engine_state.clock().clone())
             .rewrite(result)
             .await?;
+        if let Some(index_hint) = index_hint {
+            for_each_table_adapter(&plan, |_, adapter| {
+                adapter.with_index_hint(index_hint.clone())
+            })?;
+        }
+        if follow {
+            for_each_table_adapter(&plan, |_, adapter| adapter.with_follow())?;
+        }
+        if let Some(sample) = sample {
+            sample_table(&plan, &sample)?;
+        }
+        let plan = match as_of {
+            Some(as_of) => filter_as_of(plan, &as_of)?,
+            None => plan,
+        };
+        let plan = match default_limit {
+            Some(limit) if !is_global_aggregate(&plan) => LogicalPlanBuilder::from(plan)
+                .limit(0, Some(limit))
+                .and_then(|builder| builder.build())
+                .context(DataFusionSnafu)?,
+            _ => plan,
+        };
         Ok(LogicalPlan::DfPlan(plan))
     }
 
@@ -102,6 +168,203 @@ impl DfLogicalPlanner {
     }
 }
 
+/// Rejects the `stmt` if its queries nest deeper than `max_depth`, as planning
+/// deeply nested subqueries may overflow the stack.
+fn check_query_depth(stmt: &Statement, max_depth: usize) -> Result<()> {
+    struct DepthVisitor {
+        depth: usize,
+        max_depth: usize,
+    }
+
+    impl Visitor for DepthVisitor {
+        type Break = ();
+
+        fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+            self.depth += 1;
+            if self.depth > self.max_depth {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+
+        fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+            self.depth -= 1;
+            ControlFlow::Continue(())
+        }
+    }
+
+    let mut visitor = DepthVisitor {
+        depth: 0,
+        max_depth,
+    };
+    ensure!(
+        stmt.visit(&mut visitor).is_continue(),
+        QueryTooDeepSnafu { max_depth }
+    );
+    Ok(())
+}
+
+/// Returns the index hint in the `hints` of a query.
+///
+/// The last index hint wins if there are multiple of them. Unknown hints are ignored.
+fn index_hint(hints: &[QueryHint]) -> Option<IndexHint> {
+    hints
+        .iter()
+        .fold(None, |index_hint, hint| match hint.name.as_str() {
+            USE_INDEX if !hint.args.is_empty() => Some(IndexHint::UseIndex(hint.args.clone())),
+            NO_INDEX => Some(IndexHint::NoIndex),
+            _ => index_hint,
+        })
+}
+
+/// Calls `f` on names and adapters of all table scans in the `plan`, e.g. to pass hints
+/// to the scans.
+fn for_each_table_adapter(
+    plan: &DfLogicalPlan,
+    mut f: impl FnMut(&OwnedTableReference, &DfTableProviderAdapter),
+) -> Result<()> {
+    let _ = plan
+        .apply(&mut |plan| {
+            if let DfLogicalPlan::TableScan(table_scan) = plan {
+                if let Some(adapter) = table_adapter(table_scan) {
+                    f(&table_scan.table_name, adapter);
+                }
+            }
+            Ok(VisitRecursion::Continue)
+        })
+        .context(DataFusionSnafu)?;
+    Ok(())
+}
+
+/// Returns the adapter of the table to scan if it is a table of GreptimeDB.
+fn table_adapter(table_scan: &TableScan) -> Option<&DfTableProviderAdapter> {
+    table_scan
+        .source
+        .as_any()
+        .downcast_ref::<DefaultTableSource>()
+        .and_then(|source| {
+            source
+                .table_provider
+                .as_any()
+                .downcast_ref::<DfTableProviderAdapter>()
+        })
+}
+
+/// Converts the canonicalized `name` of a table in a clause to a table reference.
+fn clause_table_reference(name: &ObjectName) -> Option<OwnedTableReference> {
+    let idents: Vec<_> = name.0.iter().map(|ident| ident.value.clone()).collect();
+    match idents.as_slice() {
+        [table] => Some(OwnedTableReference::bare(table.clone())),
+        [schema, table] => Some(OwnedTableReference::partial(schema.clone(), table.clone())),
+        [catalog, schema, table] => Some(OwnedTableReference::full(
+            catalog.clone(),
+            schema.clone(),
+            table.clone(),
+        )),
+        _ => None,
+    }
+}
+
+/// Passes the `TABLESAMPLE` clause to scans of the table it follows in the `plan`.
+fn sample_table(plan: &DfLogicalPlan, sample: &TableSampleClause) -> Result<()> {
+    let invalid_sample = || DataFusionError::Plan(format!("Invalid TABLESAMPLE clause {sample}"));
+    let method = match sample.method.as_str() {
+        "BERNOULLI" => SampleMethod::Bernoulli,
+        "SYSTEM" => SampleMethod::System,
+        _ => return Err(invalid_sample()).context(PlanSqlSnafu),
+    };
+    let percentage = sample
+        .percentage
+        .parse::<f64>()
+        .map_err(|_| invalid_sample())
+        .context(PlanSqlSnafu)?;
+    let table_sample = TableSample {
+        method,
+        percentage: OrderedF64::from(percentage),
+        seed: sample.seed,
+    };
+
+    let table = clause_table_reference(&sample.table)
+        .ok_or_else(invalid_sample)
+        .context(PlanSqlSnafu)?;
+    for_each_table_adapter(plan, |table_name, adapter| {
+        if table_name.resolved_eq(&table) {
+            adapter.with_sample(table_sample.clone());
+        }
+    })
+}
+
+/// Filters out rows newer than the `AS OF` clause from scans of the table it follows in
+/// the `plan`, so the query reads the table as it was at that point in time.
+///
+/// The scans are left unchanged if the table has no time index.
+fn filter_as_of(plan: DfLogicalPlan, as_of: &AsOfClause) -> Result<DfLogicalPlan> {
+    let invalid_as_of =
+        |e: String| DataFusionError::Plan(format!("Invalid AS OF clause {as_of}: {e}"));
+    let table = clause_table_reference(&as_of.table)
+        .ok_or_else(|| invalid_as_of(format!("invalid table name {}", as_of.table)))
+        .context(PlanSqlSnafu)?;
+    let as_of = Timestamp::from_str(&as_of.timestamp)
+        .map_err(|e| invalid_as_of(e.to_string()))
+        .context(PlanSqlSnafu)?;
+
+    plan.transform_up(&|plan| {
+        let DfLogicalPlan::TableScan(table_scan) = &plan else {
+            return Ok(Transformed::No(plan));
+        };
+        if !table_scan.table_name.resolved_eq(&table) {
+            return Ok(Transformed::No(plan));
+        }
+        let Some(adapter) = table_adapter(table_scan) else {
+            return Ok(Transformed::No(plan));
+        };
+        let schema = adapter.table().schema();
+        let Some((ts_name, unit)) = schema.timestamp_column().and_then(|column| {
+            column
+                .data_type
+                .as_timestamp()
+                .map(|ts_type| (column.name.clone(), ts_type.unit()))
+        }) else {
+            return Ok(Transformed::No(plan));
+        };
+        let ts_column = Column::new(Some(table_scan.table_name.clone()), ts_name);
+        if !table_scan.projected_schema.has_column(&ts_column) {
+            return Ok(Transformed::No(plan));
+        }
+        let value = as_of.convert_to(unit).ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "AS OF timestamp {as_of:?} is out of range of the time index of {}",
+                table_scan.table_name
+            ))
+        })?;
+
+        let plan = LogicalPlanBuilder::from(plan)
+            .filter(
+                col(ts_column).lt_eq(lit(timestamp_to_scalar_value(unit, Some(value.value())))),
+            )?
+            .build()?;
+        Ok(Transformed::Yes(plan))
+    })
+    .context(DataFusionSnafu)
+}
+
+/// Returns true if the `plan` aggregates all rows without GROUP BY, so it returns at
+/// most one row and the default limit is unnecessary.
+fn is_global_aggregate(plan: &DfLogicalPlan) -> bool {
+    match plan {
+        DfLogicalPlan::Aggregate(aggregate) => aggregate.group_expr.is_empty(),
+        DfLogicalPlan::Projection(_)
+        | DfLogicalPlan::Filter(_)
+        | DfLogicalPlan::Sort(_)
+        | DfLogicalPlan::SubqueryAlias(_) => plan
+            .inputs()
+            .first()
+            .is_some_and(|input| is_global_aggregate(input)),
+        _ => false,
+    }
+}
+
 #[async_trait]
 impl LogicalPlanner for DfLogicalPlanner {
     #[tracing::instrument(skip_all)]
@@ -112,3 +375,49 @@ impl LogicalPlanner for DfLogicalPlanner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sql::dialect::GreptimeDbDialect;
+    use sql::parser::ParserContext;
+
+    use super::*;
+
+    fn parse_index_hint(sql: &str) -> Option<IndexHint> {
+        match ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0)
+        {
+            Statement::Query(query) => index_hint(&query.hints),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_index_hint() {
+        assert_eq!(None, parse_index_hint("SELECT * FROM t"));
+        assert_eq!(
+            Some(IndexHint::NoIndex),
+            parse_index_hint("SELECT /*+ no_index */ * FROM t")
+        );
+        assert_eq!(
+            Some(IndexHint::UseIndex(vec![
+                "host".to_string(),
+                "Idc".to_string()
+            ])),
+            parse_index_hint("SELECT /*+ use_index(host, \"Idc\") */ * FROM t")
+        );
+        // The last hint wins.
+        assert_eq!(
+            Some(IndexHint::NoIndex),
+            parse_index_hint("SELECT /*+ use_index(host) foo(a) no_index */ * FROM t")
+        );
+        // Invalid hints.
+        assert_eq!(None, parse_index_hint("SELECT /*+ use_index() */ * FROM t"));
+        assert_eq!(None, parse_index_hint("SELECT /*+ unknown(a) */ * FROM t"));
+        assert_eq!(
+            None,
+            parse_index_hint("SELECT * FROM t WHERE a = '/*+ no_index */'")
+        );
+    }
+}
@@ -22,7 +22,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use catalog::CatalogManagerRef;
 use common_base::Plugins;
-use common_function::function::FunctionRef;
+use common_function::function::{FunctionRef, ScalarFunctionMetaRef};
 use common_function::function_registry::FUNCTION_REGISTRY;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::prelude::ScalarUdf;
@@ -70,6 +70,9 @@ pub trait QueryEngine: Send + Sync {
 
     fn register_function(&self, func: FunctionRef);
 
+    /// Registers a scalar function created from the `func` meta.
+    fn register_scalar_function(&self, func: ScalarFunctionMetaRef);
+
     /// Create a DataFrame from a table.
     fn read_table(&self, table: TableRef) -> Result<DataFrame>;
 }
@@ -17,9 +17,23 @@ use snafu::ensure;
 
 use crate::error::{QueryAccessDeniedSnafu, Result};
 
-#[derive(Default, Clone)]
+/// Default max depth of nested queries in a statement.
+pub const DEFAULT_MAX_PLAN_DEPTH: usize = 32;
+
+#[derive(Clone)]
 pub struct QueryOptions {
     pub disallow_cross_catalog_query: bool,
+    /// Max depth of nested queries, e.g. subqueries, in a statement.
+    pub max_plan_depth: usize,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            disallow_cross_catalog_query: false,
+            max_plan_depth: DEFAULT_MAX_PLAN_DEPTH,
+        }
+    }
 }
 
 // TODO(shuiyisong): remove one method after #559 is done
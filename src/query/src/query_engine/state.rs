@@ -18,10 +18,12 @@ use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
 use catalog::CatalogManagerRef;
+use chrono::{TimeZone, Utc};
 use common_base::Plugins;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::physical_plan::SessionContext;
 use common_query::prelude::ScalarUdf;
+use common_time::clock::{ClockRef, SystemClock};
 use datafusion::catalog::MemoryCatalogList;
 use datafusion::dataframe::DataFrame;
 use datafusion::error::Result as DfResult;
@@ -38,11 +40,13 @@ use substrait::extension_serializer::ExtensionSerializer;
 use table::table::adapter::DfTableProviderAdapter;
 use table::TableRef;
 
+use crate::anti_join::planner::BloomAntiJoinPlanner;
 use crate::dist_plan::{DistExtensionPlanner, DistPlannerAnalyzer};
+use crate::optimizer::not_in_subquery::NotInSubqueryRule;
 use crate::optimizer::order_hint::OrderHintRule;
 use crate::optimizer::string_normalization::StringNormalizationRule;
 use crate::optimizer::type_conversion::TypeConversionRule;
-use crate::query_engine::options::QueryOptions;
+use crate::query_engine::options::{QueryOptions, DEFAULT_MAX_PLAN_DEPTH};
 use crate::range_select::planner::RangeSelectPlanner;
 use crate::region_query::RegionQueryHandlerRef;
 use crate::table_mutation::TableMutationHandlerRef;
@@ -58,6 +62,8 @@ pub struct QueryEngineState {
     table_mutation_handler: Option<TableMutationHandlerRef>,
     aggregate_functions: Arc<RwLock<HashMap<String, AggregateFunctionMetaRef>>>,
     plugins: Plugins,
+    /// Clock to read the time of queries, e.g. `ALIGN TO NOW` of range queries.
+    clock: ClockRef,
 }
 
 impl fmt::Debug for QueryEngineState {
@@ -88,6 +94,8 @@ impl QueryEngineState {
             analyzer.rules.push(Arc::new(DistPlannerAnalyzer));
         }
         let mut optimizer = Optimizer::new();
+        // Rewrites `NOT IN` subqueries before they are decorrelated to joins.
+        optimizer.rules.insert(0, Arc::new(NotInSubqueryRule));
         optimizer.rules.push(Arc::new(OrderHintRule));
 
         let session_state = SessionState::new_with_config_rt_and_catalog_list(
@@ -104,6 +112,9 @@ impl QueryEngineState {
         .with_optimizer_rules(optimizer.rules);
 
         let df_context = SessionContext::new_with_state(session_state);
+        let clock = plugins
+            .get::<ClockRef>()
+            .unwrap_or_else(|| Arc::new(SystemClock));
 
         Self {
             df_context,
@@ -111,6 +122,7 @@ impl QueryEngineState {
             table_mutation_handler,
             aggregate_functions: Arc::new(RwLock::new(HashMap::new())),
             plugins,
+            clock,
         }
     }
 
@@ -169,8 +181,25 @@ impl QueryEngineState {
             .unwrap_or(false)
     }
 
+    pub(crate) fn max_plan_depth(&self) -> usize {
+        self.plugins
+            .map::<QueryOptions, _, _>(|x| x.max_plan_depth)
+            .unwrap_or(DEFAULT_MAX_PLAN_DEPTH)
+    }
+
+    /// Returns the clock to read the time of queries, which is the [ClockRef] in
+    /// plugins or the system clock.
+    pub(crate) fn clock(&self) -> &ClockRef {
+        &self.clock
+    }
+
+    /// Returns the session state for a new query, the query execution start time, which
+    /// is the value of `now()` and `current_timestamp`, is read from the clock.
     pub(crate) fn session_state(&self) -> SessionState {
-        self.df_context.state()
+        let mut state = self.df_context.state();
+        state.execution_props_mut().query_execution_start_time =
+            Utc.timestamp_millis_opt(self.clock.now_millis()).unwrap();
+        state
     }
 
     /// Create a DataFrame for a table
@@ -202,8 +231,11 @@ impl DfQueryPlanner {
         catalog_manager: CatalogManagerRef,
         region_query_handler: Option<RegionQueryHandlerRef>,
     ) -> Self {
-        let mut planners: Vec<Arc<dyn ExtensionPlanner + Send + Sync>> =
-            vec![Arc::new(PromExtensionPlanner), Arc::new(RangeSelectPlanner)];
+        let mut planners: Vec<Arc<dyn ExtensionPlanner + Send + Sync>> = vec![
+            Arc::new(PromExtensionPlanner),
+            Arc::new(RangeSelectPlanner),
+            Arc::new(BloomAntiJoinPlanner),
+        ];
         if let Some(region_query_handler) = region_query_handler {
             planners.push(Arc::new(DistExtensionPlanner::new(
                 catalog_manager,
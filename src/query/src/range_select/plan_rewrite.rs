@@ -20,6 +20,7 @@ use std::time::Duration;
 use arrow_schema::DataType;
 use async_recursion::async_recursion;
 use catalog::table_source::DfTableSourceProvider;
+use common_time::clock::{Clock, ClockRef};
 use common_time::interval::NANOS_PER_MILLI;
 use common_time::timestamp::TimeUnit;
 use common_time::{Interval, Timestamp};
@@ -56,6 +57,7 @@ pub struct RangeExprRewriter<'a> {
     /// Use `BTreeSet` to avoid in case like `avg(a) RANGE '5m' + avg(a) RANGE '5m'`, duplicate range expr `avg(a) RANGE '5m'` be calculate twice
     range_fn: BTreeSet<RangeFn>,
     sub_aggr: &'a Aggregate,
+    clock: &'a dyn Clock,
 }
 
 impl<'a> RangeExprRewriter<'a> {
@@ -131,14 +133,14 @@ fn parse_duration_expr(args: &[Expr], i: usize) -> DFResult<Duration> {
 
 /// Parse the `align to` clause and return a UTC timestamp with unit of millisecond,
 /// which is used as the basis for dividing time slot during the align operation.
-/// 1. NOW: align to current execute time
+/// 1. NOW: align to current execute time read from the `clock`
 /// 2. Timestamp string: align to specific timestamp
 /// 3. leave empty (as Default Option): align to unix epoch 0
-fn parse_align_to(args: &[Expr], i: usize) -> DFResult<i64> {
+fn parse_align_to(args: &[Expr], i: usize, clock: &dyn Clock) -> DFResult<i64> {
     let s = parse_str_expr(args, i)?;
     let upper = s.to_uppercase();
     match upper.as_str() {
-        "NOW" => return Ok(Timestamp::current_millis().value()),
+        "NOW" => return Ok(clock.now_millis()),
         // default align to unix epoch 0
         "" => return Ok(0),
         _ => (),
@@ -206,7 +208,7 @@ impl<'a> TreeNodeRewriter for RangeExprRewriter<'a> {
                     .map_err(|e| DataFusionError::Plan(e.to_string()))?;
                 let by = parse_expr_list(&func.args, 4, byc)?;
                 let align = parse_duration_expr(&func.args, byc + 4)?;
-                let align_to = parse_align_to(&func.args, byc + 5)?;
+                let align_to = parse_align_to(&func.args, byc + 5, self.clock)?;
                 let mut data_type = range_expr.get_type(self.input_plan.schema())?;
                 let mut need_cast = false;
                 let fill = Fill::try_from_str(parse_str_expr(&func.args, 2)?, &data_type)?;
@@ -247,11 +249,15 @@ impl<'a> TreeNodeRewriter for RangeExprRewriter<'a> {
 /// collecting info we need to generate RangeSelect Query LogicalPlan and rewrite th original LogicalPlan.
 pub struct RangePlanRewriter {
     table_provider: DfTableSourceProvider,
+    clock: ClockRef,
 }
 
 impl RangePlanRewriter {
-    pub fn new(table_provider: DfTableSourceProvider) -> Self {
-        Self { table_provider }
+    pub fn new(table_provider: DfTableSourceProvider, clock: ClockRef) -> Self {
+        Self {
+            table_provider,
+            clock,
+        }
     }
 
     pub async fn rewrite(&mut self, plan: LogicalPlan) -> Result<LogicalPlan> {
@@ -295,6 +301,7 @@ impl RangePlanRewriter {
                     by: vec![],
                     range_fn: BTreeSet::new(),
                     sub_aggr: aggr_plan,
+                    clock: self.clock.as_ref(),
                 };
                 let new_expr = expr
                     .iter()
@@ -462,6 +469,7 @@ mod test {
     use catalog::memory::MemoryCatalogManager;
     use catalog::RegisterTableRequest;
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_time::clock::MockClock;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, Schema};
     use session::context::QueryContext;
@@ -746,16 +754,18 @@ mod test {
     #[test]
     fn test_parse_align_to() {
         // test NOW
+        let clock = MockClock::new(1_000);
         let args = vec![Expr::Literal(ScalarValue::Utf8(Some("NOW".into())))];
-        let epsinon = parse_align_to(&args, 0).unwrap() - Timestamp::current_millis().value();
-        assert!(epsinon.abs() < 100);
+        assert_eq!(1_000, parse_align_to(&args, 0, &clock).unwrap());
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(2_000, parse_align_to(&args, 0, &clock).unwrap());
         // test default
         let args = vec![Expr::Literal(ScalarValue::Utf8(Some("".into())))];
-        assert!(parse_align_to(&args, 0).unwrap() == 0);
+        assert!(parse_align_to(&args, 0, &clock).unwrap() == 0);
         // test Timestamp
         let args = vec![Expr::Literal(ScalarValue::Utf8(Some(
             "1970-01-01T00:00:00+08:00".into(),
         )))];
-        assert!(parse_align_to(&args, 0).unwrap() == -8 * 60 * 60 * 1000);
+        assert!(parse_align_to(&args, 0, &clock).unwrap() == -8 * 60 * 60 * 1000);
     }
 }
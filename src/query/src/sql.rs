@@ -146,7 +146,7 @@ pub async fn show_tables(
     let schema_name = if let Some(database) = stmt.database {
         database
     } else {
-        query_ctx.current_schema().to_owned()
+        query_ctx.current_schema()
     };
     // TODO(sunng87): move this function into query_ctx
     let mut tables = catalog_manager
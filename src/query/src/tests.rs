@@ -21,10 +21,14 @@ use table::TableRef;
 use crate::parser::QueryLanguageParser;
 use crate::{QueryEngineFactory, QueryEngineRef};
 
+mod approx_percentile_test;
 mod argmax_test;
 mod argmin_test;
 mod mean_test;
+mod my_haversine_udf_example;
+mod my_retractable_sum_udaf_example;
 mod my_sum_udaf_example;
+mod my_weighted_avg_udaf_example;
 mod percentile_test;
 mod polyval_test;
 mod query_engine_test;
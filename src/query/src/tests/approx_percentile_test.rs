@@ -0,0 +1,87 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::*;
+use datatypes::schema::{ColumnSchema, Schema};
+use table::test_util::MemTable;
+
+use crate::error::Result;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+
+#[tokio::test]
+async fn test_approx_percentile() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    test_approx_percentile_with(
+        (1..=10).collect::<Vec<u32>>(),
+        r#"+-----+------+
+| p50 | p99  |
++-----+------+
+| 5.5 | 10.0 |
++-----+------+"#,
+    )
+    .await?;
+    test_approx_percentile_with(
+        (-5..15).map(|v| v as f64 / 2.0).collect::<Vec<f64>>(),
+        r#"+------+-----+
+| p50  | p99 |
++------+-----+
+| 2.25 | 7.0 |
++------+-----+"#,
+    )
+    .await?;
+    test_approx_percentile_with(
+        (1..=100).collect::<Vec<i64>>(),
+        r#"+--------+------+
+| p50    | p99  |
++--------+------+
+| 50.375 | 99.5 |
++--------+------+"#,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn test_approx_percentile_with<T>(numbers: Vec<T>, expected: &str) -> Result<()>
+where
+    T: WrapperType,
+{
+    let table_name = format!("{}_numbers", std::any::type_name::<T>());
+    let column_name = format!("{}_number", std::any::type_name::<T>());
+
+    let column_schemas = vec![ColumnSchema::new(
+        column_name.clone(),
+        T::LogicalType::build_data_type(),
+        true,
+    )];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let column: VectorRef = Arc::new(T::VectorType::from_vec(numbers));
+    let recordbatch = RecordBatch::new(schema, vec![column]).unwrap();
+    let testing_table = MemTable::table(&table_name, recordbatch);
+
+    let engine = new_query_engine_with_table(testing_table);
+
+    let sql = format!(
+        "select APPROX_PERCENTILE({column_name}, 50.0) as p50, P99({column_name}) as p99 from {table_name}"
+    );
+    let batches = exec_selection(engine, &sql).await;
+    let batches = RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches).unwrap();
+
+    let pretty_print = batches.pretty_print().unwrap();
+    assert_eq!(expected, pretty_print);
+    Ok(())
+}
@@ -0,0 +1,102 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function::function::{FunctionContext, ScalarFunctionMeta};
+use common_query::error::Result as QueryResult;
+use common_query::prelude::{Signature, Volatility};
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::*;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, Helper};
+use table::test_util::MemTable;
+
+use crate::error::Result;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance in kilometers between two points given in degrees.
+fn haversine(_func_ctx: FunctionContext, columns: &[VectorRef]) -> QueryResult<VectorRef> {
+    assert_eq!(4, columns.len());
+    let columns: Vec<&Float64Vector> = columns
+        .iter()
+        .map(|column| unsafe { Helper::static_cast(column) })
+        .collect();
+
+    let distances = (0..columns[0].len()).map(|row| {
+        let lat1 = columns[0].get_data(row)?.to_radians();
+        let lon1 = columns[1].get_data(row)?.to_radians();
+        let lat2 = columns[2].get_data(row)?.to_radians();
+        let lon2 = columns[3].get_data(row)?.to_radians();
+
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+    });
+
+    Ok(Arc::new(Float64Vector::from_owned_iterator(distances)))
+}
+
+#[tokio::test]
+async fn test_my_haversine() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let column_schemas = ["lat1", "lon1", "lat2", "lon2"]
+        .into_iter()
+        .map(|name| ColumnSchema::new(name, ConcreteDataType::float64_datatype(), true))
+        .collect();
+    let schema = Arc::new(Schema::new(column_schemas));
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(Float64Vector::from(vec![
+            Some(0.0),
+            Some(48.8566),
+            Some(40.7128),
+            None,
+        ])),
+        Arc::new(Float64Vector::from_slice([0.0, 2.3522, -74.006, 0.0])),
+        Arc::new(Float64Vector::from_slice([0.0, 51.5074, 34.0522, 0.0])),
+        Arc::new(Float64Vector::from_slice([1.0, -0.1278, -118.2437, 0.0])),
+    ];
+    let recordbatch = RecordBatch::new(schema, columns).unwrap();
+    let testing_table = MemTable::table("cities", recordbatch);
+
+    let engine = new_query_engine_with_table(testing_table);
+
+    engine.register_scalar_function(Arc::new(ScalarFunctionMeta::new(
+        "my_haversine",
+        Signature::exact(
+            vec![ConcreteDataType::float64_datatype(); 4],
+            Volatility::Immutable,
+        ),
+        ConcreteDataType::float64_datatype(),
+        Arc::new(|| Arc::new(haversine)),
+    )));
+
+    let sql = "select round(MY_HAVERSINE(lat1, lon1, lat2, lon2)) as distance from cities";
+    let batches = exec_selection(engine, sql).await;
+    let batches = RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches).unwrap();
+
+    let expected = r#"+----------+
+| distance |
++----------+
+| 111.0    |
+| 344.0    |
+| 3936.0   |
+|          |
++----------+"#;
+    assert_eq!(expected, batches.pretty_print().unwrap());
+    Ok(())
+}
@@ -0,0 +1,252 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use common_function::scalars::aggregate::AggregateFunctionMeta;
+use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{CreateAccumulatorSnafu, Result as QueryResult};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::*;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::types::{LogicalPrimitiveType, WrapperType};
+use datatypes::vectors::{Helper, Int64Vector};
+use datatypes::with_match_primitive_type_id;
+use num_traits::AsPrimitive;
+use table::test_util::MemTable;
+
+use crate::error::Result;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+
+/// A sum accumulator that supports removing values, so it can be used in sliding
+/// window frames.
+#[derive(Debug, Default)]
+struct MyRetractableSumAccumulator<T, SumT> {
+    sum: SumT,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, SumT> MyRetractableSumAccumulator<T, SumT>
+where
+    T: WrapperType,
+    SumT: WrapperType,
+    T::Native: AsPrimitive<SumT::Native>,
+    SumT::Native: std::ops::AddAssign + std::ops::SubAssign,
+{
+    #[inline(always)]
+    fn add(&mut self, v: T) {
+        let mut sum_native = self.sum.into_native();
+        sum_native += v.into_native().as_();
+        self.sum = SumT::from_native(sum_native);
+    }
+
+    #[inline(always)]
+    fn sub(&mut self, v: T) {
+        let mut sum_native = self.sum.into_native();
+        sum_native -= v.into_native().as_();
+        self.sum = SumT::from_native(sum_native);
+    }
+
+    #[inline(always)]
+    fn merge(&mut self, s: SumT) {
+        let mut sum_native = self.sum.into_native();
+        sum_native += s.into_native();
+        self.sum = SumT::from_native(sum_native);
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+struct MyRetractableSumAccumulatorCreator {}
+
+impl AggregateFunctionCreator for MyRetractableSumAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            let input_type = &types[0];
+            with_match_primitive_type_id!(
+                input_type.logical_type_id(),
+                |$S| {
+                    Ok(Box::new(MyRetractableSumAccumulator::<<$S as LogicalPrimitiveType>::Wrapper, <<$S as LogicalPrimitiveType>::LargestType as LogicalPrimitiveType>::Wrapper>::default()))
+                },
+                {
+                    let err_msg = format!(
+                        "\"MY_RETRACTABLE_SUM\" aggregate function not support data type {:?}",
+                        input_type.logical_type_id(),
+                    );
+                    CreateAccumulatorSnafu { err_msg }.fail()?
+                }
+            )
+        });
+        creator
+    }
+
+    fn output_type(&self) -> QueryResult<ConcreteDataType> {
+        let input_type = &self.input_types()?[0];
+        with_match_primitive_type_id!(
+            input_type.logical_type_id(),
+            |$S| {
+                Ok(<<$S as LogicalPrimitiveType>::LargestType>::build_data_type())
+            },
+            {
+                unreachable!()
+            }
+        )
+    }
+
+    fn state_types(&self) -> QueryResult<Vec<ConcreteDataType>> {
+        Ok(vec![self.output_type()?])
+    }
+}
+
+impl<T, SumT> Accumulator for MyRetractableSumAccumulator<T, SumT>
+where
+    T: WrapperType,
+    SumT: WrapperType,
+    T::Native: AsPrimitive<SumT::Native>,
+    SumT::Native: std::ops::AddAssign + std::ops::SubAssign,
+{
+    fn state(&self) -> QueryResult<Vec<Value>> {
+        Ok(vec![self.sum.into()])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> QueryResult<()> {
+        if values.is_empty() {
+            return Ok(());
+        };
+        let column = &values[0];
+        let column: &<T as Scalar>::VectorType = unsafe { Helper::static_cast(column) };
+        for v in column.iter_data().flatten() {
+            self.add(v)
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> QueryResult<()> {
+        if states.is_empty() {
+            return Ok(());
+        };
+        let states = &states[0];
+        let states: &<SumT as Scalar>::VectorType = unsafe { Helper::static_cast(states) };
+        for s in states.iter_data().flatten() {
+            self.merge(s)
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> QueryResult<Value> {
+        Ok(self.sum.into())
+    }
+
+    fn retract_batch(&mut self, values: &[VectorRef]) -> QueryResult<()> {
+        if values.is_empty() {
+            return Ok(());
+        };
+        let column = &values[0];
+        let column: &<T as Scalar>::VectorType = unsafe { Helper::static_cast(column) };
+        for v in column.iter_data().flatten() {
+            self.sub(v)
+        }
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_retract_batch() {
+    let mut accumulator = MyRetractableSumAccumulator::<i64, i64>::default();
+    let values: VectorRef = Arc::new(Int64Vector::from(vec![Some(1), None, Some(2), Some(3)]));
+    accumulator.update_batch(&[values]).unwrap();
+    assert_eq!(Value::Int64(6), accumulator.evaluate().unwrap());
+
+    let values: VectorRef = Arc::new(Int64Vector::from(vec![Some(1), None]));
+    accumulator.retract_batch(&[values]).unwrap();
+    assert_eq!(Value::Int64(5), accumulator.evaluate().unwrap());
+}
+
+#[tokio::test]
+async fn test_my_retractable_sum() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    // Each row sums itself and the row before it.
+    test_my_retractable_sum_with(
+        (1..=5).collect::<Vec<u32>>(),
+        r#"+--------------------+
+| my_retractable_sum |
++--------------------+
+| 1                  |
+| 3                  |
+| 5                  |
+| 7                  |
+| 9                  |
++--------------------+"#,
+    )
+    .await?;
+    test_my_retractable_sum_with(
+        vec![-1.0f64, 1.0, 2.5, 3.0],
+        r#"+--------------------+
+| my_retractable_sum |
++--------------------+
+| -1.0               |
+| 0.0                |
+| 3.5                |
+| 5.5                |
++--------------------+"#,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn test_my_retractable_sum_with<T>(numbers: Vec<T>, expected: &str) -> Result<()>
+where
+    T: WrapperType,
+{
+    let table_name = format!("{}_numbers", std::any::type_name::<T>());
+    let column_name = format!("{}_number", std::any::type_name::<T>());
+
+    let column_schemas = vec![ColumnSchema::new(
+        column_name.clone(),
+        T::LogicalType::build_data_type(),
+        true,
+    )];
+    let schema = Arc::new(Schema::new(column_schemas.clone()));
+    let column: VectorRef = Arc::new(T::VectorType::from_vec(numbers));
+    let recordbatch = RecordBatch::new(schema, vec![column]).unwrap();
+    let testing_table = MemTable::table(&table_name, recordbatch);
+
+    let engine = new_query_engine_with_table(testing_table);
+
+    engine.register_aggregate_function(Arc::new(AggregateFunctionMeta::new(
+        "my_retractable_sum",
+        1,
+        Arc::new(|| Arc::new(MyRetractableSumAccumulatorCreator::default())),
+    )));
+
+    // The window frame slides, so rows leaving the frame are retracted.
+    let sql = format!(
+        "select MY_RETRACTABLE_SUM({column_name}) over (order by {column_name} rows between 1 preceding and current row) as my_retractable_sum from {table_name} order by {column_name}"
+    );
+    let batches = exec_selection(engine, &sql).await;
+    let batches = RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches).unwrap();
+
+    let pretty_print = batches.pretty_print().unwrap();
+    assert_eq!(expected, pretty_print);
+    Ok(())
+}
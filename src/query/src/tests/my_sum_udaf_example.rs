@@ -16,21 +16,25 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use common_function::scalars::aggregate::AggregateFunctionMeta;
+use common_function::scalars::aggregate::{checked_add, AggregateFunctionMeta};
 use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
 use common_query::error::{CreateAccumulatorSnafu, Result as QueryResult};
 use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
 use common_query::prelude::*;
-use common_recordbatch::{RecordBatch, RecordBatches};
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch, RecordBatches};
+use datatypes::arrow::datatypes::ArrowNativeTypeOp;
 use datatypes::prelude::*;
 use datatypes::schema::{ColumnSchema, Schema};
 use datatypes::types::{LogicalPrimitiveType, WrapperType};
-use datatypes::vectors::Helper;
+use datatypes::vectors::{Helper, Int64Vector, UInt32Vector};
 use datatypes::with_match_primitive_type_id;
 use num_traits::AsPrimitive;
+use session::context::QueryContext;
 use table::test_util::MemTable;
 
 use crate::error::Result;
+use crate::parser::QueryLanguageParser;
 use crate::tests::{exec_selection, new_query_engine_with_table};
 
 #[derive(Debug, Default)]
@@ -44,20 +48,17 @@ where
     T: WrapperType,
     SumT: WrapperType,
     T::Native: AsPrimitive<SumT::Native>,
-    SumT::Native: std::ops::AddAssign,
+    SumT::Native: ArrowNativeTypeOp,
 {
     #[inline(always)]
-    fn add(&mut self, v: T) {
-        let mut sum_native = self.sum.into_native();
-        sum_native += v.into_native().as_();
-        self.sum = SumT::from_native(sum_native);
+    fn add(&mut self, v: T) -> QueryResult<()> {
+        self.merge(SumT::from_native(v.into_native().as_()))
     }
 
     #[inline(always)]
-    fn merge(&mut self, s: SumT) {
-        let mut sum_native = self.sum.into_native();
-        sum_native += s.into_native();
-        self.sum = SumT::from_native(sum_native);
+    fn merge(&mut self, s: SumT) -> QueryResult<()> {
+        self.sum = checked_add(self.sum, s)?;
+        Ok(())
     }
 }
 
@@ -109,7 +110,7 @@ where
     T: WrapperType,
     SumT: WrapperType,
     T::Native: AsPrimitive<SumT::Native>,
-    SumT::Native: std::ops::AddAssign,
+    SumT::Native: ArrowNativeTypeOp,
 {
     fn state(&self) -> QueryResult<Vec<Value>> {
         Ok(vec![self.sum.into()])
@@ -122,7 +123,7 @@ where
         let column = &values[0];
         let column: &<T as Scalar>::VectorType = unsafe { Helper::static_cast(column) };
         for v in column.iter_data().flatten() {
-            self.add(v)
+            self.add(v)?;
         }
         Ok(())
     }
@@ -134,7 +135,7 @@ where
         let states = &states[0];
         let states: &<SumT as Scalar>::VectorType = unsafe { Helper::static_cast(states) };
         for s in states.iter_data().flatten() {
-            self.merge(s)
+            self.merge(s)?;
         }
         Ok(())
     }
@@ -187,6 +188,92 @@ async fn test_my_sum() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_my_sum_overflow() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let column_schemas = vec![ColumnSchema::new(
+        "number",
+        ConcreteDataType::int64_datatype(),
+        true,
+    )];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let column: VectorRef = Arc::new(Int64Vector::from_slice([i64::MAX, 1]));
+    let recordbatch = RecordBatch::new(schema, vec![column]).unwrap();
+    let testing_table = MemTable::table("numbers", recordbatch);
+
+    let engine = new_query_engine_with_table(testing_table);
+    engine.register_aggregate_function(Arc::new(AggregateFunctionMeta::new(
+        "my_sum",
+        1,
+        Arc::new(|| Arc::new(MySumAccumulatorCreator::default())),
+    )));
+
+    let sql = "select MY_SUM(number) as my_sum from numbers";
+    let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+    let plan = engine
+        .planner()
+        .plan(stmt, QueryContext::arc())
+        .await
+        .unwrap();
+    let Output::Stream(stream) = engine.execute(plan, QueryContext::arc()).await.unwrap() else {
+        unreachable!()
+    };
+    // The sum reports an error instead of wrapping around to a negative value.
+    let err = util::collect(stream).await.unwrap_err();
+    let msg = format!("{err:?}");
+    assert!(
+        msg.contains("Overflow when adding 1 to 9223372036854775807"),
+        "unexpected error: {msg}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_my_sum_empty_result_schema() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let column_schemas = vec![ColumnSchema::new(
+        "number",
+        ConcreteDataType::uint32_datatype(),
+        true,
+    )];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let column: VectorRef = Arc::new(UInt32Vector::from_slice([1, 2, 3]));
+    let recordbatch = RecordBatch::new(schema, vec![column]).unwrap();
+    let testing_table = MemTable::table("numbers", recordbatch);
+
+    let engine = new_query_engine_with_table(testing_table);
+    engine.register_aggregate_function(Arc::new(AggregateFunctionMeta::new(
+        "my_sum",
+        1,
+        Arc::new(|| Arc::new(MySumAccumulatorCreator::default())),
+    )));
+
+    let sql = "select MY_SUM(number) as my_sum from numbers where 1 = 0 group by number";
+    let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+    let plan = engine
+        .planner()
+        .plan(stmt, QueryContext::arc())
+        .await
+        .unwrap();
+    let output = engine.execute(plan, QueryContext::arc()).await.unwrap();
+
+    // The schema is available before polling the stream.
+    let schema = output.schema().unwrap();
+    assert_eq!(1, schema.num_columns());
+    let column_schema = &schema.column_schemas()[0];
+    assert_eq!("my_sum", column_schema.name);
+    assert_eq!(ConcreteDataType::uint64_datatype(), column_schema.data_type);
+
+    let Output::Stream(stream) = output else {
+        unreachable!()
+    };
+    let batches = util::collect(stream).await.unwrap();
+    assert_eq!(0, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    Ok(())
+}
+
 async fn test_my_sum_with<T>(numbers: Vec<T>, expected: &str) -> Result<()>
 where
     T: WrapperType,
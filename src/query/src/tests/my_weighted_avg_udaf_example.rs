@@ -0,0 +1,246 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use common_function::scalars::aggregate::AggregateFunctionMeta;
+use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{CreateAccumulatorSnafu, InvalidInputStateSnafu, Result as QueryResult};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::*;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::types::{LogicalPrimitiveType, WrapperType};
+use datatypes::vectors::{Float64Vector, Helper};
+use datatypes::with_match_primitive_type_id;
+use num_traits::AsPrimitive;
+use snafu::ensure;
+use table::test_util::MemTable;
+
+use crate::error::Result;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+
+/// Accumulator of the weighted mean of values of type `V` with weights of type `W`.
+#[derive(Debug, Default)]
+struct MyWeightedAvgAccumulator<V, W> {
+    weighted_sum: f64,
+    weight_sum: f64,
+    _phantom: PhantomData<(V, W)>,
+}
+
+impl<V, W> Accumulator for MyWeightedAvgAccumulator<V, W>
+where
+    V: WrapperType,
+    W: WrapperType,
+    V::Native: AsPrimitive<f64>,
+    W::Native: AsPrimitive<f64>,
+{
+    fn state(&self) -> QueryResult<Vec<Value>> {
+        Ok(vec![self.weighted_sum.into(), self.weight_sum.into()])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> QueryResult<()> {
+        if values.is_empty() {
+            return Ok(());
+        };
+        // One vector for each argument: the values and the weights.
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+        let value_column: &<V as Scalar>::VectorType = unsafe { Helper::static_cast(&values[0]) };
+        let weight_column: &<W as Scalar>::VectorType = unsafe { Helper::static_cast(&values[1]) };
+        for (v, w) in value_column.iter_data().zip(weight_column.iter_data()) {
+            if let (Some(v), Some(w)) = (v, w) {
+                let w: f64 = w.into_native().as_();
+                self.weighted_sum += v.into_native().as_() * w;
+                self.weight_sum += w;
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> QueryResult<()> {
+        if states.is_empty() {
+            return Ok(());
+        };
+        ensure!(states.len() == 2, InvalidInputStateSnafu);
+        let weighted_sums: &Float64Vector = unsafe { Helper::static_cast(&states[0]) };
+        let weight_sums: &Float64Vector = unsafe { Helper::static_cast(&states[1]) };
+        for (weighted_sum, weight_sum) in weighted_sums.iter_data().zip(weight_sums.iter_data()) {
+            self.weighted_sum += weighted_sum.unwrap_or_default();
+            self.weight_sum += weight_sum.unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> QueryResult<Value> {
+        if self.weight_sum == 0.0 {
+            return Ok(Value::Null);
+        }
+        Ok((self.weighted_sum / self.weight_sum).into())
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+struct MyWeightedAvgAccumulatorCreator {}
+
+/// Creates the accumulator of values of type `V` with weights of `weight_type`.
+fn create_accumulator<V>(weight_type: &ConcreteDataType) -> QueryResult<Box<dyn Accumulator>>
+where
+    V: WrapperType,
+    V::Native: AsPrimitive<f64>,
+{
+    with_match_primitive_type_id!(
+        weight_type.logical_type_id(),
+        |$S| {
+            Ok(Box::new(MyWeightedAvgAccumulator::<V, <$S as LogicalPrimitiveType>::Wrapper>::default()))
+        },
+        {
+            let err_msg = format!(
+                "\"MY_WEIGHTED_AVG\" aggregate function not support weight type {:?}",
+                weight_type.logical_type_id(),
+            );
+            CreateAccumulatorSnafu { err_msg }.fail()
+        }
+    )
+}
+
+impl AggregateFunctionCreator for MyWeightedAvgAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            ensure!(types.len() == 2, InvalidInputStateSnafu);
+            let value_type = &types[0];
+            with_match_primitive_type_id!(
+                value_type.logical_type_id(),
+                |$S| {
+                    create_accumulator::<<$S as LogicalPrimitiveType>::Wrapper>(&types[1])
+                },
+                {
+                    let err_msg = format!(
+                        "\"MY_WEIGHTED_AVG\" aggregate function not support data type {:?}",
+                        value_type.logical_type_id(),
+                    );
+                    CreateAccumulatorSnafu { err_msg }.fail()?
+                }
+            )
+        });
+        creator
+    }
+
+    fn output_type(&self) -> QueryResult<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> QueryResult<Vec<ConcreteDataType>> {
+        Ok(vec![
+            ConcreteDataType::float64_datatype(),
+            ConcreteDataType::float64_datatype(),
+        ])
+    }
+}
+
+#[tokio::test]
+async fn test_my_weighted_avg() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    test_my_weighted_avg_with(
+        vec![10u32, 20, 30],
+        vec![1u32, 2, 7],
+        r#"+-----------------+
+| my_weighted_avg |
++-----------------+
+| 26.0            |
++-----------------+"#,
+    )
+    .await?;
+    test_my_weighted_avg_with(
+        vec![-1.5f64, 2.5],
+        vec![3i8, 1],
+        r#"+-----------------+
+| my_weighted_avg |
++-----------------+
+| -0.5            |
++-----------------+"#,
+    )
+    .await?;
+    test_my_weighted_avg_with(
+        vec![1i64, 2],
+        vec![0.0f32, 0.0],
+        r#"+-----------------+
+| my_weighted_avg |
++-----------------+
+|                 |
++-----------------+"#,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn test_my_weighted_avg_with<V, W>(
+    values: Vec<V>,
+    weights: Vec<W>,
+    expected: &str,
+) -> Result<()>
+where
+    V: WrapperType,
+    W: WrapperType,
+{
+    let table_name = format!(
+        "{}_{}_numbers",
+        std::any::type_name::<V>(),
+        std::any::type_name::<W>()
+    );
+    let value_column = format!("{}_value", std::any::type_name::<V>());
+    let weight_column = format!("{}_weight", std::any::type_name::<W>());
+
+    let column_schemas = vec![
+        ColumnSchema::new(
+            value_column.clone(),
+            V::LogicalType::build_data_type(),
+            true,
+        ),
+        ColumnSchema::new(
+            weight_column.clone(),
+            W::LogicalType::build_data_type(),
+            true,
+        ),
+    ];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(V::VectorType::from_vec(values)),
+        Arc::new(W::VectorType::from_vec(weights)),
+    ];
+    let recordbatch = RecordBatch::new(schema, columns).unwrap();
+    let testing_table = MemTable::table(&table_name, recordbatch);
+
+    let engine = new_query_engine_with_table(testing_table);
+
+    engine.register_aggregate_function(Arc::new(AggregateFunctionMeta::new(
+        "my_weighted_avg",
+        2,
+        Arc::new(|| Arc::new(MyWeightedAvgAccumulatorCreator::default())),
+    )));
+
+    let sql = format!(
+        "select MY_WEIGHTED_AVG({value_column}, {weight_column}) as my_weighted_avg from {table_name}"
+    );
+    let batches = exec_selection(engine, &sql).await;
+    let batches = RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches).unwrap();
+
+    let pretty_print = batches.pretty_print().unwrap();
+    assert_eq!(expected, pretty_print);
+    Ok(())
+}
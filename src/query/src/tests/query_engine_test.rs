@@ -12,22 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use catalog::memory::MemoryCatalogManager;
-use catalog::RegisterTableRequest;
+use catalog::{RegisterSchemaRequest, RegisterTableRequest};
 use common_base::Plugins;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, NUMBERS_TABLE_ID};
-use common_error::ext::BoxedError;
+use common_error::ext::{BoxedError, ErrorExt};
+use common_error::status_code::StatusCode;
 use common_query::prelude::{create_udf, make_scalar_function, Volatility};
 use common_query::Output;
-use common_recordbatch::{util, RecordBatch};
+use common_recordbatch::{util, RecordBatch, RecordBatches};
+use common_time::clock::{ClockRef, MockClock};
 use datafusion::datasource::DefaultTableSource;
 use datafusion_expr::logical_plan::builder::LogicalPlanBuilder;
+use datafusion_expr::LogicalPlan as DfLogicalPlan;
 use datatypes::prelude::*;
 use datatypes::schema::{ColumnSchema, Schema};
-use datatypes::vectors::UInt32Vector;
-use session::context::QueryContext;
+use datatypes::vectors::{BooleanVector, UInt32Vector};
+use futures_util::StreamExt;
+use session::context::{QueryContext, QueryContextBuilder};
 use snafu::ResultExt;
 use table::table::adapter::DfTableProviderAdapter;
 use table::table::numbers::{NumbersTable, NUMBERS_TABLE_NAME};
@@ -38,8 +44,9 @@ use crate::parser::QueryLanguageParser;
 use crate::plan::LogicalPlan;
 use crate::query_engine::options::QueryOptions;
 use crate::query_engine::QueryEngineFactory;
-use crate::tests::exec_selection;
 use crate::tests::pow::pow;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+use crate::QueryEngineRef;
 
 #[tokio::test]
 async fn test_datafusion_query_engine() -> Result<()> {
@@ -103,6 +110,110 @@ async fn test_datafusion_query_engine() -> Result<()> {
     Ok(())
 }
 
+/// Returns an engine with a `numbers` table of `num_rows` rows.
+fn new_numbers_engine(num_rows: u32) -> QueryEngineRef {
+    let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+        "number",
+        ConcreteDataType::uint32_datatype(),
+        false,
+    )]));
+    let columns: Vec<VectorRef> = vec![Arc::new(UInt32Vector::from_slice(
+        (0..num_rows).collect::<Vec<_>>(),
+    ))];
+    let recordbatch = RecordBatch::new(schema, columns).unwrap();
+    new_query_engine_with_table(MemTable::table("numbers", recordbatch))
+}
+
+#[tokio::test]
+async fn test_exec_stats() {
+    let engine = new_numbers_engine(100);
+
+    let sql = "select number from numbers where number % 2 = 0";
+    let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+    let plan = engine
+        .planner()
+        .plan(stmt, QueryContext::arc())
+        .await
+        .unwrap();
+    let Output::Stream(mut stream) = engine.execute(plan, QueryContext::arc()).await.unwrap()
+    else {
+        unreachable!()
+    };
+    let mut num_rows = 0;
+    while let Some(batch) = stream.next().await {
+        num_rows += batch.unwrap().num_rows();
+    }
+    assert_eq!(50, num_rows);
+
+    // All rows in the table are scanned, though only half of them are returned.
+    let metrics = stream.metrics().unwrap();
+    assert_eq!(100, metrics.scanned_rows);
+    assert_eq!(0, metrics.fetched_bytes);
+}
+
+#[tokio::test]
+async fn test_default_limit() {
+    let engine = new_numbers_engine(100);
+    let query_ctx = QueryContextBuilder::default()
+        .default_limit(Some(10))
+        .build();
+    let plan_sql = |sql| {
+        let engine = engine.clone();
+        let query_ctx = query_ctx.clone();
+        async move {
+            let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+            engine.planner().plan(stmt, query_ctx).await.unwrap()
+        }
+    };
+    let num_rows = |plan| {
+        let engine = engine.clone();
+        let query_ctx = query_ctx.clone();
+        async move {
+            let Output::Stream(stream) = engine.execute(plan, query_ctx).await.unwrap() else {
+                unreachable!()
+            };
+            let batches = util::collect(stream).await.unwrap();
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+        }
+    };
+
+    // Unbounded selects are capped by the default limit.
+    let plan = plan_sql("select number from numbers").await;
+    assert_eq!(10, num_rows(plan).await);
+    // An explicit limit overrides the default one.
+    let plan = plan_sql("select number from numbers limit 20").await;
+    assert_eq!(20, num_rows(plan).await);
+    let plan = plan_sql("select number from numbers limit all").await;
+    assert_eq!(100, num_rows(plan).await);
+
+    // Aggregates without GROUP BY are not limited.
+    let LogicalPlan::DfPlan(plan) = plan_sql("select count(*) from numbers").await;
+    assert!(!matches!(plan, DfLogicalPlan::Limit(_)), "{plan:?}");
+    let LogicalPlan::DfPlan(plan) =
+        plan_sql("select number % 3, count(*) from numbers group by number % 3").await;
+    assert!(matches!(plan, DfLogicalPlan::Limit(_)), "{plan:?}");
+}
+
+#[tokio::test]
+async fn test_not_in_subquery() {
+    let engine = new_numbers_engine(100);
+    let num_rows = |batches: Vec<RecordBatch>| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+
+    let sql = "select number from numbers where number not in (select number from numbers where number % 3 = 0)";
+    let batches = exec_selection(engine.clone(), sql).await;
+    assert_eq!(66, num_rows(batches));
+
+    // Other predicates are kept.
+    let sql = "select number from numbers where number < 10 and number not in (select number from numbers where number % 3 = 0)";
+    let batches = exec_selection(engine.clone(), sql).await;
+    assert_eq!(6, num_rows(batches));
+
+    // An empty subquery rejects nothing.
+    let sql = "select number from numbers where number not in (select number from numbers where number > 100)";
+    let batches = exec_selection(engine, sql).await;
+    assert_eq!(100, num_rows(batches));
+}
+
 fn catalog_manager() -> Result<Arc<MemoryCatalogManager>> {
     let catalog_manager = catalog::memory::new_memory_catalog_manager().unwrap();
     let req = RegisterTableRequest {
@@ -126,6 +237,7 @@ async fn test_query_validate() -> Result<()> {
     let plugins = Plugins::new();
     plugins.insert(QueryOptions {
         disallow_cross_catalog_query: true,
+        ..Default::default()
     });
 
     let factory = QueryEngineFactory::new_with_plugins(catalog_list, None, None, false, plugins);
@@ -147,6 +259,93 @@ async fn test_query_validate() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_query_depth() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let catalog_list = catalog_manager()?;
+
+    let plugins = Plugins::new();
+    plugins.insert(QueryOptions {
+        max_plan_depth: 3,
+        ..Default::default()
+    });
+
+    let factory = QueryEngineFactory::new_with_plugins(catalog_list, None, None, false, plugins);
+    let engine = factory.query_engine();
+
+    // Nests `depth` queries.
+    let nested_sql = |depth: usize| {
+        let mut sql = "select number from numbers".to_string();
+        for _ in 1..depth {
+            sql = format!("select number from ({sql})");
+        }
+        sql
+    };
+
+    let stmt = QueryLanguageParser::parse_sql(&nested_sql(3)).unwrap();
+    assert!(engine
+        .planner()
+        .plan(stmt, QueryContext::arc())
+        .await
+        .is_ok());
+
+    let stmt = QueryLanguageParser::parse_sql(&nested_sql(4)).unwrap();
+    let err = engine
+        .planner()
+        .plan(stmt, QueryContext::arc())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, crate::error::Error::QueryTooDeep { .. }),
+        "{err:?}"
+    );
+
+    // Subqueries in expressions are also nested queries.
+    let stmt = QueryLanguageParser::parse_sql(
+        "select number from numbers where number in \
+        (select number from numbers where number in \
+        (select number from numbers where number in (select number from numbers)))",
+    )
+    .unwrap();
+    let err = engine
+        .planner()
+        .plan(stmt, QueryContext::arc())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, crate::error::Error::QueryTooDeep { .. }),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_now_from_clock() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let catalog_list = catalog_manager()?;
+
+    let clock = Arc::new(MockClock::new(1_000_000));
+    let plugins = Plugins::new();
+    plugins.insert::<ClockRef>(clock.clone());
+
+    let factory = QueryEngineFactory::new_with_plugins(catalog_list, None, None, false, plugins);
+    let engine = factory.query_engine();
+
+    let query_now = |engine: QueryEngineRef| async move {
+        let batches = exec_selection(engine, "select now(), current_timestamp()").await;
+        let batches = RecordBatches::try_new(batches[0].schema.clone(), batches).unwrap();
+        batches.pretty_print().unwrap()
+    };
+
+    let output = query_now(engine.clone()).await;
+    assert_eq!(2, output.matches("1970-01-01T00:16:40").count(), "{output}");
+
+    clock.advance(Duration::from_secs(60));
+    let output = query_now(engine).await;
+    assert_eq!(2, output.matches("1970-01-01T00:17:40").count(), "{output}");
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_udf() -> Result<()> {
     common_telemetry::init_default_ut_logging();
@@ -189,3 +388,158 @@ async fn test_udf() -> Result<()> {
 
     Ok(())
 }
+
+fn multi_schema_catalog_manager() -> Arc<MemoryCatalogManager> {
+    let catalog_manager = catalog::memory::new_memory_catalog_manager().unwrap();
+    for (table_id, schema_name) in [(1024, "s1"), (1025, "s2")] {
+        let _ = catalog_manager
+            .register_schema_sync(RegisterSchemaRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: schema_name.to_string(),
+            })
+            .unwrap();
+
+        let column_schemas = vec![
+            ColumnSchema::new("id", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("v", ConcreteDataType::uint32_datatype(), false),
+        ];
+        let schema = Arc::new(Schema::new(column_schemas));
+        let offset = if schema_name == "s1" { 10 } else { 20 };
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_slice([1, 2, 3])),
+            Arc::new(UInt32Vector::from_slice([
+                offset + 1,
+                offset + 2,
+                offset + 3,
+            ])),
+        ];
+        let recordbatch = RecordBatch::new(schema, columns).unwrap();
+        let table = MemTable::new_with_catalog(
+            "t",
+            recordbatch,
+            table_id,
+            DEFAULT_CATALOG_NAME.to_string(),
+            schema_name.to_string(),
+            vec![0],
+        );
+        let req = RegisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: schema_name.to_string(),
+            table_name: "t".to_string(),
+            table_id,
+            table,
+        };
+        let _ = catalog_manager.register_table_sync(req).unwrap();
+    }
+    catalog_manager
+}
+
+#[tokio::test]
+async fn test_query_fully_qualified_names_across_schemas() {
+    common_telemetry::init_default_ut_logging();
+    let factory = QueryEngineFactory::new(multi_schema_catalog_manager(), None, None, false);
+    let engine = factory.query_engine();
+    // The default schema holds neither of the tables.
+    let query_ctx = QueryContext::with(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME);
+
+    let stmt = QueryLanguageParser::parse_sql(
+        "select a.id, a.v as v1, b.v as v2 from greptime.s1.t a join greptime.s2.t b on a.id = b.id order by a.id",
+    )
+    .unwrap();
+    let plan = engine
+        .planner()
+        .plan(stmt, query_ctx.clone())
+        .await
+        .unwrap();
+    let Output::Stream(stream) = engine.execute(plan, query_ctx.clone()).await.unwrap() else {
+        unreachable!()
+    };
+    let schema = stream.schema();
+    let batches = RecordBatches::try_new(schema, util::collect(stream).await.unwrap()).unwrap();
+    let expected = "\
++----+----+----+
+| id | v1 | v2 |
++----+----+----+
+| 1  | 11 | 21 |
+| 2  | 12 | 22 |
+| 3  | 13 | 23 |
++----+----+----+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // The unqualified column name exists in both schemas' tables.
+    let stmt = QueryLanguageParser::parse_sql(
+        "select id from greptime.s1.t a join greptime.s2.t b on a.id = b.id",
+    )
+    .unwrap();
+    assert!(engine
+        .planner()
+        .plan(stmt, query_ctx.clone())
+        .await
+        .is_err());
+
+    // The unqualified table name is only looked up in the default schema.
+    let stmt = QueryLanguageParser::parse_sql("select * from t").unwrap();
+    let err = engine
+        .planner()
+        .plan(stmt, query_ctx.clone())
+        .await
+        .unwrap_err();
+    assert_ne!(StatusCode::DatabaseNotFound, err.status_code());
+
+    let stmt = QueryLanguageParser::parse_sql("select * from greptime.s1.not_exist").unwrap();
+    let err = engine
+        .planner()
+        .plan(stmt, query_ctx.clone())
+        .await
+        .unwrap_err();
+    assert_ne!(StatusCode::DatabaseNotFound, err.status_code());
+
+    let stmt = QueryLanguageParser::parse_sql("select * from greptime.s3.t").unwrap();
+    let err = engine.planner().plan(stmt, query_ctx).await.unwrap_err();
+    assert_eq!(StatusCode::DatabaseNotFound, err.status_code());
+}
+
+#[tokio::test]
+async fn test_predicate_order() {
+    let engine = new_numbers_engine(100);
+    // Counts rows evaluated by the expensive predicate.
+    let evaluated = Arc::new(AtomicUsize::new(0));
+    let counter = evaluated.clone();
+    let expensive = make_scalar_function(move |args: &[VectorRef]| {
+        let _ = counter.fetch_add(args[0].len(), Ordering::Relaxed);
+        Ok(Arc::new(BooleanVector::from(vec![true; args[0].len()])) as _)
+    });
+    engine.register_udf(create_udf(
+        "expensive",
+        vec![ConcreteDataType::uint32_datatype()],
+        Arc::new(ConcreteDataType::boolean_datatype()),
+        Volatility::Immutable,
+        expensive,
+    ));
+    let num_rows = |batches: Vec<RecordBatch>| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+
+    // The cheap predicate filters rows before the expensive one.
+    let sql = "select number from numbers where expensive(number) and number < 10";
+    let batches = exec_selection(engine.clone(), sql).await;
+    assert_eq!(10, num_rows(batches));
+    assert_eq!(10, evaluated.swap(0, Ordering::Relaxed));
+
+    // The hint keeps predicates in the written order, the planner passes it to the
+    // physical optimizer by the query context.
+    let query_ctx = QueryContext::arc();
+    let sql = "select /*+ ordered_predicates */ number from numbers \
+        where expensive(number) and number < 10";
+    let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+    let plan = engine
+        .planner()
+        .plan(stmt, query_ctx.clone())
+        .await
+        .unwrap();
+    assert!(query_ctx.ordered_predicates());
+    let Output::Stream(stream) = engine.execute(plan, query_ctx).await.unwrap() else {
+        unreachable!()
+    };
+    let batches = util::collect(stream).await.unwrap();
+    assert_eq!(10, num_rows(batches));
+    assert_eq!(100, evaluated.load(Ordering::Relaxed));
+}
@@ -124,6 +124,13 @@ impl TimeRangeTester {
         assert_eq!(expect, range);
     }
 
+    async fn range_of(&self, sql: &str) -> TimestampRange {
+        let _ = exec_selection(self.engine.clone(), sql).await;
+        let filters = self.get_filters();
+
+        TimeRangePredicateBuilder::new("ts", TimeUnit::Millisecond, &filters).build()
+    }
+
     fn get_filters(&self) -> Vec<Expr> {
         self.filter.write().unwrap().drain(..).collect()
     }
@@ -224,3 +231,52 @@ async fn test_range_filter() {
         )
         .await;
 }
+
+#[tokio::test]
+async fn test_relative_range_filter() {
+    let tester = create_test_engine();
+    let before = Timestamp::current_millis();
+    let range = tester
+        .range_of("select * from m where ts > now() - interval '1 hour';")
+        .await;
+    let after = Timestamp::current_millis();
+
+    // `now()` is folded into a constant before pushing down filters.
+    assert!(range.end().is_none());
+    let start = range.start().as_ref().unwrap().value();
+    assert!(start > before.value() - 3_600_000);
+    assert!(start <= after.value() - 3_600_000 + 1);
+}
+
+#[tokio::test]
+async fn test_as_of_filter() {
+    let tester = create_test_engine();
+    tester
+        .check(
+            "select * from m as of '1970-01-01T00:00:00.499Z'",
+            TimestampRange::until_end(Timestamp::new(499, TimeUnit::Millisecond), true),
+        )
+        .await;
+
+    // The AS OF bound intersects with filters in the WHERE clause.
+    tester
+        .check(
+            "select * from m as of '1970-01-01T00:00:00.499Z' where ts >= 100",
+            TimestampRange::with_unit(100, 500, TimeUnit::Millisecond).unwrap(),
+        )
+        .await;
+    tester
+        .check(
+            "select * from m as of '1970-01-01T00:00:00.499Z' where ts >= 600",
+            TimestampRange::empty(),
+        )
+        .await;
+
+    // The AS OF bound only applies to the table it follows.
+    tester
+        .check(
+            "select * from m join numbers as of '1970-01-01T00:00:00.499Z' on m.v = numbers.number",
+            TimestampRange::min_to_max(),
+        )
+        .await;
+}
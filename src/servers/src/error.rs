@@ -148,11 +148,22 @@ pub enum Error {
     #[snafu(display("Invalid query: {}", reason))]
     InvalidQuery { reason: String, location: Location },
 
-    #[snafu(display("Failed to parse InfluxDB line protocol"))]
-    InfluxdbLineProtocol {
+    #[snafu(display("Malformed InfluxDB line protocol at line {}: {}", line, reason))]
+    InfluxdbMalformedLine {
+        line: usize,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Timestamp {} in precision {} is more precise than the millisecond timestamp column",
+        timestamp,
+        precision
+    ))]
+    TimestampPrecisionLoss {
+        timestamp: i64,
+        precision: String,
         location: Location,
-        #[snafu(source)]
-        error: influxdb_line_protocol::Error,
     },
 
     #[snafu(display("Failed to write InfluxDB line protocol"))]
@@ -470,7 +481,8 @@ impl ErrorExt for Error {
             NotSupported { .. }
             | InvalidParameter { .. }
             | InvalidQuery { .. }
-            | InfluxdbLineProtocol { .. }
+            | InfluxdbMalformedLine { .. }
+            | TimestampPrecisionLoss { .. }
             | ConnResetByPeer { .. }
             | InvalidOpentsdbLine { .. }
             | InvalidOpentsdbJsonRequest { .. }
@@ -570,9 +582,11 @@ pub fn status_to_tonic_code(status_code: StatusCode) -> Code {
         | StatusCode::UserPasswordMismatch
         | StatusCode::AuthHeaderNotFound
         | StatusCode::InvalidAuthHeader => Code::Unauthenticated,
-        StatusCode::AccessDenied | StatusCode::PermissionDenied | StatusCode::RegionReadonly => {
-            Code::PermissionDenied
-        }
+        StatusCode::AccessDenied
+        | StatusCode::PermissionDenied
+        | StatusCode::RegionReadonly
+        | StatusCode::RegionFrozen
+        | StatusCode::InstanceReadOnly => Code::PermissionDenied,
     }
 }
 
@@ -623,7 +637,8 @@ impl IntoResponse for Error {
     fn into_response(self) -> Response {
         let error_msg = self.output_msg();
         let status = match self {
-            Error::InfluxdbLineProtocol { .. }
+            Error::InfluxdbMalformedLine { .. }
+            | Error::TimestampPrecisionLoss { .. }
             | Error::InfluxdbLinesWrite { .. }
             | Error::PromSeriesWrite { .. }
             | Error::InvalidOpentsdbLine { .. }
@@ -633,6 +648,7 @@ impl IntoResponse for Error {
             | Error::DecompressPromRemoteRequest { .. }
             | Error::InvalidPromRemoteRequest { .. }
             | Error::InvalidQuery { .. }
+            | Error::InvalidParameter { .. }
             | Error::TimePrecision { .. } => HttpStatusCode::BAD_REQUEST,
             _ => {
                 if self.status_code().should_log_error() {
@@ -24,7 +24,7 @@ use arrow_flight::{
     HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
 };
 use async_trait::async_trait;
-use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_grpc::flight::{is_metrics_requested, FlightEncoder, FlightMessage};
 use common_query::Output;
 use common_telemetry::tracing_context::TracingContext;
 use futures::Stream;
@@ -34,7 +34,7 @@ use tonic::{Request, Response, Status, Streaming};
 
 use crate::error;
 pub use crate::grpc::flight::stream::FlightRecordBatchStream;
-use crate::grpc::greptime_handler::GreptimeRequestHandler;
+use crate::grpc::greptime_handler::{timestamp_precision_action, GreptimeRequestHandler};
 use crate::grpc::TonicResult;
 
 pub type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync + 'static>>;
@@ -148,14 +148,18 @@ impl FlightCraft for GreptimeRequestHandler {
         &self,
         request: Request<Ticket>,
     ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        let send_metrics = is_metrics_requested(request.metadata());
+        let timestamp_precision_action = timestamp_precision_action(request.metadata())?;
         let ticket = request.into_inner().ticket;
         let request =
             GreptimeRequest::decode(ticket.as_ref()).context(error::InvalidFlightTicketSnafu)?;
 
-        let output = self.handle_request(request).await?;
+        let output = self
+            .handle_request(request, timestamp_precision_action)
+            .await?;
 
         let stream: Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send + Sync>> =
-            to_flight_data_stream(output, TracingContext::new());
+            to_flight_data_stream(output, TracingContext::new(), send_metrics);
         Ok(Response::new(stream))
     }
 }
@@ -163,14 +167,15 @@ impl FlightCraft for GreptimeRequestHandler {
 fn to_flight_data_stream(
     output: Output,
     tracing_context: TracingContext,
+    send_metrics: bool,
 ) -> TonicStream<FlightData> {
     match output {
         Output::Stream(stream) => {
-            let stream = FlightRecordBatchStream::new(stream, tracing_context);
+            let stream = FlightRecordBatchStream::new(stream, tracing_context, send_metrics);
             Box::pin(stream) as _
         }
         Output::RecordBatches(x) => {
-            let stream = FlightRecordBatchStream::new(x.as_stream(), tracing_context);
+            let stream = FlightRecordBatchStream::new(x.as_stream(), tracing_context, send_metrics);
             Box::pin(stream) as _
         }
         Output::AffectedRows(rows) => {
@@ -41,10 +41,16 @@ pub struct FlightRecordBatchStream {
 }
 
 impl FlightRecordBatchStream {
-    pub fn new(recordbatches: SendableRecordBatchStream, tracing_context: TracingContext) -> Self {
+    /// Creates a stream to send `recordbatches`, followed by their metrics if
+    /// `send_metrics` is true, e.g. the client asks for metrics.
+    pub fn new(
+        recordbatches: SendableRecordBatchStream,
+        tracing_context: TracingContext,
+        send_metrics: bool,
+    ) -> Self {
         let (tx, rx) = mpsc::channel::<TonicResult<FlightMessage>>(1);
         let join_handle = common_runtime::spawn_read(async move {
-            Self::flight_data_stream(recordbatches, tx)
+            Self::flight_data_stream(recordbatches, tx, send_metrics)
                 .trace(tracing_context.attach(info_span!("flight_data_stream")))
                 .await
         });
@@ -59,6 +65,7 @@ impl FlightRecordBatchStream {
     async fn flight_data_stream(
         mut recordbatches: SendableRecordBatchStream,
         mut tx: Sender<TonicResult<FlightMessage>>,
+        send_metrics: bool,
     ) {
         let schema = recordbatches.schema();
         if let Err(e) = tx.send(Ok(FlightMessage::Schema(schema))).await {
@@ -83,6 +90,16 @@ impl FlightRecordBatchStream {
                 }
             }
         }
+
+        if !send_metrics {
+            return;
+        }
+        // Sends the metrics as a trailer, they are complete once the stream is exhausted.
+        if let Some(metrics) = recordbatches.metrics() {
+            if let Err(e) = tx.send(Ok(FlightMessage::Metrics(metrics))).await {
+                warn!("stop sending Flight data, err: {e}");
+            }
+        }
     }
 }
 
@@ -149,7 +166,8 @@ mod test {
         let recordbatches = RecordBatches::try_new(schema.clone(), vec![recordbatch.clone()])
             .unwrap()
             .as_stream();
-        let mut stream = FlightRecordBatchStream::new(recordbatches, TracingContext::default());
+        let mut stream =
+            FlightRecordBatchStream::new(recordbatches, TracingContext::default(), false);
 
         let mut raw_data = Vec::with_capacity(2);
         raw_data.push(stream.next().await.unwrap().unwrap());
@@ -28,11 +28,13 @@ use common_error::status_code::StatusCode;
 use common_query::Output;
 use common_runtime::Runtime;
 use common_telemetry::logging;
-use session::context::{QueryContextBuilder, QueryContextRef};
+use session::context::{QueryContextBuilder, QueryContextRef, TimestampPrecisionAction};
 use snafu::{OptionExt, ResultExt};
+use tonic::metadata::MetadataMap;
 
 use crate::error::Error::UnsupportedAuthScheme;
 use crate::error::{AuthSnafu, InvalidQuerySnafu, JoinTaskSnafu, NotFoundAuthHeaderSnafu, Result};
+use crate::http::header::{self, GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION};
 use crate::metrics::{METRIC_AUTH_FAILURE, METRIC_SERVER_GRPC_DB_REQUEST_TIMER};
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
 
@@ -56,13 +58,18 @@ impl GreptimeRequestHandler {
         }
     }
 
-    pub(crate) async fn handle_request(&self, request: GreptimeRequest) -> Result<Output> {
+    pub(crate) async fn handle_request(
+        &self,
+        request: GreptimeRequest,
+        timestamp_precision_action: TimestampPrecisionAction,
+    ) -> Result<Output> {
         let query = request.request.context(InvalidQuerySnafu {
             reason: "Expecting non-empty GreptimeRequest.",
         })?;
 
         let header = request.header.as_ref();
         let query_ctx = create_query_context(header);
+        query_ctx.set_timestamp_precision_action(timestamp_precision_action);
         let user_info = auth(self.user_provider.clone(), header, &query_ctx).await?;
         query_ctx.set_current_user(user_info);
 
@@ -121,7 +128,7 @@ pub(crate) async fn auth(
                 Identity::UserId(&username, None),
                 Password::PlainText(password.into()),
                 query_ctx.current_catalog(),
-                query_ctx.current_schema(),
+                &query_ctx.current_schema(),
             )
             .await
             .context(AuthSnafu),
@@ -138,6 +145,16 @@ pub(crate) async fn auth(
     })
 }
 
+/// Returns the [TimestampPrecisionAction] set by the metadata of the gRPC request.
+pub(crate) fn timestamp_precision_action(
+    metadata: &MetadataMap,
+) -> Result<TimestampPrecisionAction> {
+    let value = metadata
+        .get(GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION)
+        .map(|value| value.to_str().unwrap_or_default());
+    header::timestamp_precision_action(value)
+}
+
 pub(crate) fn create_query_context(header: Option<&RequestHeader>) -> QueryContextRef {
     let (catalog, schema) = header
         .map(|header| {
@@ -0,0 +1,77 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Readiness of the components a server depends on.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+/// Result of checking a component, `Err` holds the reason why the component is not ready.
+pub type ComponentHealth = std::result::Result<(), String>;
+
+/// Reports whether a component is ready to serve requests.
+#[async_trait]
+pub trait HealthIndicator: Send + Sync {
+    /// Name of the component.
+    fn name(&self) -> &str;
+
+    /// Checks the component.
+    async fn check(&self) -> ComponentHealth;
+}
+
+pub type HealthIndicatorRef = Arc<dyn HealthIndicator>;
+
+/// A component that is not ready until its owner marks it ready, e.g. regions are
+/// ready once they are opened and their WAL is replayed.
+pub struct ReadinessIndicator {
+    name: String,
+    /// Reason reported while the component is not ready.
+    pending_reason: String,
+    ready: AtomicBool,
+}
+
+impl ReadinessIndicator {
+    pub fn new(name: impl Into<String>, pending_reason: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            pending_reason: pending_reason.into(),
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    pub fn set_ready(&self) {
+        self.ready.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl HealthIndicator for ReadinessIndicator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> ComponentHealth {
+        if self.is_ready() {
+            Ok(())
+        } else {
+            Err(self.pending_reason.clone())
+        }
+    }
+}
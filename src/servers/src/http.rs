@@ -30,11 +30,11 @@ use axum::{routing, BoxError, Extension, Router};
 use common_base::readable_size::ReadableSize;
 use common_base::Plugins;
 use common_error::status_code::StatusCode;
-use common_recordbatch::RecordBatch;
+use common_recordbatch::{RecordBatch, RecordBatchMetrics};
 use common_telemetry::logging::{error, info};
 use common_time::timestamp::TimeUnit;
 use common_time::Timestamp;
-use datatypes::data_type::DataType;
+use datatypes::data_type::{ConcreteDataType, DataType};
 use futures::FutureExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -49,9 +49,11 @@ use tower_http::trace::TraceLayer;
 use self::authorize::AuthState;
 use crate::configurator::ConfiguratorRef;
 use crate::error::{AlreadyStartedSnafu, Error, Result, StartHttpSnafu, ToJsonSnafu};
+use crate::health::HealthIndicatorRef;
 use crate::http::csv_result::CsvResponse;
 use crate::http::error_result::ErrorResponse;
 use crate::http::greptime_result_v1::GreptimedbV1Response;
+use crate::http::handler::HealthState;
 use crate::http::influxdb::{influxdb_health, influxdb_ping, influxdb_write_v1, influxdb_write_v2};
 use crate::http::influxdb_result_v1::InfluxdbV1Response;
 use crate::http::prometheus::{
@@ -112,6 +114,7 @@ pub struct HttpServer {
     user_provider: Option<UserProviderRef>,
     metrics_handler: Option<MetricsHandler>,
     greptime_config_options: Option<String>,
+    health_indicators: Vec<HealthIndicatorRef>,
     plugins: Plugins,
 }
 
@@ -144,11 +147,67 @@ impl Default for HttpOptions {
 pub struct ColumnSchema {
     name: String,
     data_type: String,
+    /// Whether the column may contain null values.
+    nullable: bool,
+    /// Digits of the fractional seconds of timestamp and time columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    precision: Option<u64>,
+    /// Type of the items of list columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item_type: Option<String>,
 }
 
 impl ColumnSchema {
-    pub fn new(name: String, data_type: String) -> ColumnSchema {
-        ColumnSchema { name, data_type }
+    pub fn new(name: String, data_type: String, nullable: bool) -> ColumnSchema {
+        ColumnSchema {
+            name,
+            data_type,
+            nullable,
+            precision: None,
+            item_type: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data_type(&self) -> &str {
+        &self.data_type
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    pub fn precision(&self) -> Option<u64> {
+        self.precision
+    }
+
+    pub fn item_type(&self) -> Option<&str> {
+        self.item_type.as_deref()
+    }
+}
+
+impl From<&datatypes::schema::ColumnSchema> for ColumnSchema {
+    fn from(column_schema: &datatypes::schema::ColumnSchema) -> ColumnSchema {
+        let data_type = &column_schema.data_type;
+        let precision = match data_type {
+            ConcreteDataType::Timestamp(t) => Some(t.precision()),
+            ConcreteDataType::Time(t) => Some(t.precision()),
+            _ => None,
+        };
+        let item_type = data_type
+            .as_list()
+            .map(|list_type| list_type.item_type().name());
+
+        ColumnSchema {
+            name: column_schema.name.clone(),
+            data_type: data_type.name(),
+            nullable: column_schema.is_nullable(),
+            precision,
+            item_type,
+        }
     }
 }
 
@@ -163,6 +222,10 @@ impl Schema {
             column_schemas: columns,
         }
     }
+
+    pub fn column_schemas(&self) -> &[ColumnSchema] {
+        &self.column_schemas
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
@@ -190,6 +253,15 @@ impl HttpRecordsOutput {
     pub fn rows(&self) -> &Vec<Vec<Value>> {
         &self.rows
     }
+
+    /// Replaces null values in the rows with `null_value` strings.
+    pub(crate) fn replace_nulls(&mut self, null_value: &str) {
+        for value in self.rows.iter_mut().flatten() {
+            if value.is_null() {
+                *value = Value::String(null_value.to_string());
+            }
+        }
+    }
 }
 
 impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
@@ -212,10 +284,7 @@ impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
                     .schema
                     .column_schemas()
                     .iter()
-                    .map(|cs| ColumnSchema {
-                        name: cs.name.clone(),
-                        data_type: cs.data_type.name(),
-                    })
+                    .map(ColumnSchema::from)
                     .collect(),
             };
 
@@ -242,6 +311,27 @@ impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
     }
 }
 
+/// Statistics of executing the queries in a request.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
+pub struct ExecutionStats {
+    /// Rows read by the scans of the queries.
+    pub scanned_rows: u64,
+    /// Bytes fetched from the storage.
+    pub fetched_bytes: u64,
+    /// Time elapsed to execute the queries in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+impl From<RecordBatchMetrics> for ExecutionStats {
+    fn from(metrics: RecordBatchMetrics) -> Self {
+        ExecutionStats {
+            scanned_rows: metrics.scanned_rows,
+            fetched_bytes: metrics.fetched_bytes,
+            elapsed_ms: metrics.elapsed_ms,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum GreptimeQueryOutput {
@@ -338,6 +428,25 @@ impl HttpResponse {
             HttpResponse::Error(resp) => resp.with_execution_time(execution_time).into(),
         }
     }
+
+    /// Renders null values as `null_value`, only `csv` and `greptimedb_v1` formats
+    /// support it.
+    pub fn with_null_value(self, null_value: String) -> Self {
+        match self {
+            HttpResponse::Csv(resp) => resp.with_null_value(null_value).into(),
+            HttpResponse::GreptimedbV1(resp) => resp.with_null_value(&null_value).into(),
+            resp @ (HttpResponse::InfluxdbV1(_) | HttpResponse::Error(_)) => resp,
+        }
+    }
+
+    /// Returns the warnings raised by the query, only `greptimedb_v1` format supports it.
+    pub fn with_warnings(self, warnings: Vec<String>) -> Self {
+        match self {
+            HttpResponse::GreptimedbV1(resp) => resp.with_warnings(warnings).into(),
+            resp
+            @ (HttpResponse::Csv(_) | HttpResponse::InfluxdbV1(_) | HttpResponse::Error(_)) => resp,
+        }
+    }
 }
 
 impl IntoResponse for HttpResponse {
@@ -420,6 +529,7 @@ impl HttpServerBuilder {
                 metrics_handler: None,
                 shutdown_tx: Mutex::new(None),
                 greptime_config_options: None,
+                health_indicators: Vec::new(),
                 plugins: Default::default(),
             },
         }
@@ -485,6 +595,12 @@ impl HttpServerBuilder {
         self
     }
 
+    /// Adds a component the `/health` endpoint reports the readiness of.
+    pub fn with_health_indicator(&mut self, indicator: HealthIndicatorRef) -> &mut Self {
+        self.inner.health_indicators.push(indicator);
+        self
+    }
+
     pub fn build(&mut self) -> HttpServer {
         std::mem::take(self).inner
     }
@@ -558,9 +674,11 @@ impl HttpServer {
             router = router.nest("", self.route_metrics(metrics_handler));
         }
 
-        router = router.route(
-            "/health",
-            routing::get(handler::health).post(handler::health),
+        router = router.nest(
+            "",
+            self.route_health(HealthState {
+                indicators: self.health_indicators.clone(),
+            }),
         );
 
         let config_router = self
@@ -640,6 +758,15 @@ impl HttpServer {
             .with_state(metrics_handler)
     }
 
+    fn route_health<S>(&self, health_state: HealthState) -> Router<S> {
+        Router::new()
+            .route(
+                "/health",
+                routing::get(handler::health).post(handler::health),
+            )
+            .with_state(health_state)
+    }
+
     fn route_sql<S>(&self, api_state: ApiState) -> ApiRouter<S> {
         ApiRouter::new()
             .api_route(
@@ -927,6 +1054,41 @@ mod test {
         assert_eq!(res.status(), StatusCode::REQUEST_TIMEOUT);
     }
 
+    #[test]
+    fn test_column_schema_conversion() {
+        let column_schemas = [
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_microsecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("t", ConcreteDataType::time_second_datatype(), true),
+            ColumnSchema::new(
+                "tags",
+                ConcreteDataType::list_datatype(ConcreteDataType::string_datatype()),
+                true,
+            ),
+            ColumnSchema::new("value", ConcreteDataType::float64_datatype(), true),
+        ];
+        let json = serde_json::to_value(
+            column_schemas
+                .iter()
+                .map(super::ColumnSchema::from)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::json!([
+                {"name": "ts", "data_type": "TimestampMicrosecond", "nullable": false, "precision": 6},
+                {"name": "t", "data_type": "TimeSecond", "nullable": true, "precision": 0},
+                {"name": "tags", "data_type": "List<String>", "nullable": true, "item_type": "String"},
+                {"name": "value", "data_type": "Float64", "nullable": true},
+            ]),
+            json
+        );
+    }
+
     #[tokio::test]
     async fn test_recordbatches_conversion() {
         let column_schemas = vec![
@@ -968,6 +1130,8 @@ mod test {
                         let schema = r.schema.as_ref().unwrap();
                         assert_eq!(schema.column_schemas[0].name, "numbers");
                         assert_eq!(schema.column_schemas[0].data_type, "UInt32");
+                        assert!(!schema.column_schemas[0].nullable);
+                        assert!(schema.column_schemas[1].nullable);
                         assert_eq!(r.rows[0][0], serde_json::Value::from(1));
                         assert_eq!(r.rows[0][1], serde_json::Value::Null);
                     } else {
@@ -993,6 +1157,8 @@ mod test {
                         let schema = r.schema.as_ref().unwrap();
                         assert_eq!(schema.column_schemas[0].name, "numbers");
                         assert_eq!(schema.column_schemas[0].data_type, "UInt32");
+                        assert!(!schema.column_schemas[0].nullable);
+                        assert!(schema.column_schemas[1].nullable);
                         assert_eq!(r.rows[0][0], serde_json::Value::from(1));
                         assert_eq!(r.rows[0][1], serde_json::Value::Null);
                     } else {
@@ -1003,4 +1169,53 @@ mod test {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_null_value() {
+        let column_schemas = vec![
+            ColumnSchema::new("numbers", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("strings", ConcreteDataType::string_datatype(), true),
+        ];
+        let schema = Arc::new(Schema::new(column_schemas));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_slice(vec![1, 2])),
+            Arc::new(StringVector::from(vec![None, Some("NA")])),
+        ];
+        let recordbatch = RecordBatch::new(schema.clone(), columns).unwrap();
+        let new_outputs = || {
+            let recordbatches =
+                RecordBatches::try_new(schema.clone(), vec![recordbatch.clone()]).unwrap();
+            vec![Ok(Output::RecordBatches(recordbatches))]
+        };
+
+        let resp = GreptimedbV1Response::from_output(new_outputs())
+            .await
+            .with_null_value(String::new());
+        let HttpResponse::GreptimedbV1(resp) = resp else {
+            panic!("invalid response type");
+        };
+        let GreptimeQueryOutput::Records(r) = &resp.output[0] else {
+            panic!("invalid output type");
+        };
+        assert_eq!(r.rows[0][1], serde_json::Value::from(""));
+        assert_eq!(r.rows[1][1], serde_json::Value::from("NA"));
+
+        let resp = CsvResponse::from_output(new_outputs())
+            .await
+            .with_null_value("NA".to_string());
+        let body = hyper::body::to_bytes(resp.into_response().into_body())
+            .await
+            .unwrap();
+        assert_eq!(
+            "1,NA\n2,\"NA\"\n",
+            String::from_utf8(body.to_vec()).unwrap()
+        );
+
+        for null_value in ["", "NA", "null"] {
+            assert!(csv_result::is_csv_null_value(null_value), "{null_value}");
+        }
+        for null_value in ["1", "-1.5", "true", "a,b", "\"x", "a\nb"] {
+            assert!(!csv_result::is_csv_null_value(null_value), "{null_value}");
+        }
+    }
 }
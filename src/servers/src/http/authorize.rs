@@ -28,7 +28,9 @@ use secrecy::SecretString;
 use session::context::QueryContext;
 use snafu::{ensure, OptionExt, ResultExt};
 
-use super::header::GreptimeDbName;
+use super::header::{
+    timestamp_precision_action, GreptimeDbName, GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION,
+};
 use super::{ResponseFormat, PUBLIC_APIS};
 use crate::error::{
     self, InvalidAuthorizationHeaderSnafu, InvalidParameterSnafu, InvisibleASCIISnafu,
@@ -57,6 +59,14 @@ pub async fn inner_auth<B>(
     // 1. prepare
     let (catalog, schema) = extract_catalog_and_schema(&req);
     let query_ctx = QueryContext::with(catalog, schema);
+    let action_header = req
+        .headers()
+        .get(GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION)
+        .map(|value| value.to_str().unwrap_or_default());
+    match timestamp_precision_action(action_header) {
+        Ok(action) => query_ctx.set_timestamp_precision_action(action),
+        Err(e) => return Err(e.into_response()),
+    }
     let need_auth = need_auth(&req);
     let is_influxdb = req.uri().path().contains("influxdb");
 
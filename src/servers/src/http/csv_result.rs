@@ -22,15 +22,19 @@ use itertools::Itertools;
 use mime_guess::mime;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::http::error_result::ErrorResponse;
 use crate::http::header::{GREPTIME_DB_HEADER_EXECUTION_TIME, GREPTIME_DB_HEADER_FORMAT};
-use crate::http::{handler, GreptimeQueryOutput, HttpResponse, ResponseFormat};
+use crate::http::{handler, GreptimeQueryOutput, HttpRecordsOutput, HttpResponse, ResponseFormat};
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct CsvResponse {
     output: Vec<GreptimeQueryOutput>,
     execution_time_ms: u64,
+    /// Text of null values, nulls are rendered as `null` if it's `None`.
+    #[serde(skip)]
+    null_value: Option<String>,
 }
 
 impl CsvResponse {
@@ -48,6 +52,7 @@ impl CsvResponse {
                     HttpResponse::Csv(CsvResponse {
                         output,
                         execution_time_ms: 0,
+                        null_value: None,
                     })
                 }
             }
@@ -66,6 +71,41 @@ impl CsvResponse {
     pub fn execution_time_ms(&self) -> u64 {
         self.execution_time_ms
     }
+
+    /// Renders null values as `null_value`, which should be checked by [is_csv_null_value].
+    pub fn with_null_value(mut self, null_value: String) -> Self {
+        self.null_value = Some(null_value);
+        self
+    }
+}
+
+/// Returns whether `null_value` can represent nulls in csv without being mistaken for
+/// other values.
+///
+/// Strings are quoted in csv, so the text of nulls can't contain quotes or separators,
+/// and can't be a number or a boolean.
+pub fn is_csv_null_value(null_value: &str) -> bool {
+    !null_value.contains([',', '"', '\n', '\r'])
+        && !matches!(
+            serde_json::from_str(null_value),
+            Ok(Value::Number(_) | Value::Bool(_))
+        )
+}
+
+/// Renders the rows of `records` as csv lines.
+fn records_to_csv(records: HttpRecordsOutput, null_value: Option<&str>) -> String {
+    let mut result = String::new();
+    for row in records.rows {
+        let row = row
+            .iter()
+            .map(|v| match (v, null_value) {
+                (Value::Null, Some(null_value)) => null_value.to_string(),
+                _ => v.to_string(),
+            })
+            .join(",");
+        writeln!(result, "{row}").unwrap();
+    }
+    result
 }
 
 impl IntoResponse for CsvResponse {
@@ -83,12 +123,7 @@ impl IntoResponse for CsvResponse {
                 format!("{n}\n")
             }
             Some(GreptimeQueryOutput::Records(records)) => {
-                let mut result = String::new();
-                for row in records.rows {
-                    let row = row.iter().map(|v| v.to_string()).join(",");
-                    writeln!(result, "{row}").unwrap();
-                }
-                result
+                records_to_csv(records, self.null_value.as_deref())
             }
         };
 
@@ -19,22 +19,31 @@ use reqwest::header::HeaderValue;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::http::header::{GREPTIME_DB_HEADER_EXECUTION_TIME, GREPTIME_DB_HEADER_FORMAT};
-use crate::http::{handler, GreptimeQueryOutput, HttpResponse, ResponseFormat};
+use crate::http::header::{
+    GREPTIME_DB_HEADER_EXECUTION_TIME, GREPTIME_DB_HEADER_FORMAT, GREPTIME_DB_HEADER_STATS,
+};
+use crate::http::{handler, ExecutionStats, GreptimeQueryOutput, HttpResponse, ResponseFormat};
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GreptimedbV1Response {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub(crate) output: Vec<GreptimeQueryOutput>,
     pub(crate) execution_time_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) stats: Option<ExecutionStats>,
+    /// Warnings raised by the query, e.g. results are truncated.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub(crate) warnings: Vec<String>,
 }
 
 impl GreptimedbV1Response {
     pub async fn from_output(outputs: Vec<crate::error::Result<Output>>) -> HttpResponse {
-        match handler::from_output(ResponseFormat::GreptimedbV1, outputs).await {
-            Ok(output) => HttpResponse::GreptimedbV1(Self {
+        match handler::from_output_with_stats(ResponseFormat::GreptimedbV1, outputs).await {
+            Ok((output, stats)) => HttpResponse::GreptimedbV1(Self {
                 output,
                 execution_time_ms: 0,
+                stats,
+                warnings: vec![],
             }),
             Err(err) => HttpResponse::Error(err),
         }
@@ -49,14 +58,41 @@ impl GreptimedbV1Response {
         self
     }
 
+    /// Renders null values in the records as `null_value` strings.
+    pub fn with_null_value(mut self, null_value: &str) -> Self {
+        for output in &mut self.output {
+            if let GreptimeQueryOutput::Records(records) = output {
+                records.replace_nulls(null_value);
+            }
+        }
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     pub fn execution_time_ms(&self) -> u64 {
         self.execution_time_ms
     }
+
+    pub fn stats(&self) -> Option<&ExecutionStats> {
+        self.stats.as_ref()
+    }
 }
 
 impl IntoResponse for GreptimedbV1Response {
     fn into_response(self) -> Response {
         let execution_time = self.execution_time_ms;
+        let stats = self
+            .stats
+            .as_ref()
+            .and_then(|stats| serde_json::to_string(stats).ok());
         let mut resp = Json(self).into_response();
         resp.headers_mut().insert(
             GREPTIME_DB_HEADER_FORMAT,
@@ -66,6 +102,9 @@ impl IntoResponse for GreptimedbV1Response {
             GREPTIME_DB_HEADER_EXECUTION_TIME,
             HeaderValue::from(execution_time),
         );
+        if let Some(stats) = stats.and_then(|stats| HeaderValue::from_str(&stats).ok()) {
+            resp.headers_mut().insert(GREPTIME_DB_HEADER_STATS, stats);
+        }
         resp
     }
 }
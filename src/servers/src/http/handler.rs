@@ -23,19 +23,21 @@ use axum::{Extension, Form};
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_query::Output;
-use common_recordbatch::util;
+use common_recordbatch::RecordBatchMetrics;
+use futures::TryStreamExt;
 use query::parser::PromQuery;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use session::context::QueryContextRef;
 
-use crate::http::csv_result::CsvResponse;
+use crate::health::HealthIndicatorRef;
+use crate::http::csv_result::{is_csv_null_value, CsvResponse};
 use crate::http::error_result::ErrorResponse;
 use crate::http::greptime_result_v1::GreptimedbV1Response;
 use crate::http::influxdb_result_v1::InfluxdbV1Response;
 use crate::http::{
-    ApiState, Epoch, GreptimeOptionsConfigState, GreptimeQueryOutput, HttpRecordsOutput,
-    HttpResponse, ResponseFormat,
+    ApiState, Epoch, ExecutionStats, GreptimeOptionsConfigState, GreptimeQueryOutput,
+    HttpRecordsOutput, HttpResponse, ResponseFormat,
 };
 use crate::metrics_handler::MetricsHandler;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
@@ -56,6 +58,9 @@ pub struct SqlQuery {
     // specified time precision. Maybe greptimedb format can support this
     // param too.
     pub epoch: Option<String>,
+    // (Optional) text to render null values as in `greptimedb_v1` and `csv` formats,
+    // nulls are rendered as `null` by default.
+    pub null_value: Option<String>,
 }
 
 /// Handler to execute sql
@@ -82,6 +87,17 @@ pub async fn sql(
         .or(form_params.epoch)
         .map(|s| s.to_lowercase())
         .map(|s| Epoch::parse(s.as_str()).unwrap_or(Epoch::Millisecond));
+    let null_value = query_params.null_value.or(form_params.null_value);
+    if format == ResponseFormat::Csv {
+        // Nulls must be distinguishable from other values in csv.
+        if let Some(null_value) = null_value.as_ref().filter(|v| !is_csv_null_value(v)) {
+            return HttpResponse::Error(ErrorResponse::from_error_message(
+                format,
+                StatusCode::InvalidArguments,
+                format!("null_value {null_value:?} is ambiguous in csv format"),
+            ));
+        }
+    }
 
     let _timer = crate::metrics::METRIC_HTTP_SQL_ELAPSED
         .with_label_values(&[db.as_str()])
@@ -91,7 +107,7 @@ pub async fn sql(
         if let Some((status, msg)) = validate_schema(sql_handler.clone(), query_ctx.clone()).await {
             Err((status, msg))
         } else {
-            Ok(sql_handler.do_query(sql, query_ctx).await)
+            Ok(sql_handler.do_query(sql, query_ctx.clone()).await)
         }
     } else {
         Err((
@@ -115,8 +131,14 @@ pub async fn sql(
         ResponseFormat::GreptimedbV1 => GreptimedbV1Response::from_output(outputs).await,
         ResponseFormat::InfluxdbV1 => InfluxdbV1Response::from_output(outputs, epoch).await,
     };
+    let resp = match null_value {
+        Some(null_value) => resp.with_null_value(null_value),
+        None => resp,
+    };
 
-    resp.with_execution_time(start.elapsed().as_millis() as u64)
+    // Results are collected into the response, so all warnings are raised.
+    resp.with_warnings(query_ctx.warnings())
+        .with_execution_time(start.elapsed().as_millis() as u64)
 }
 
 /// Create a response from query result
@@ -124,17 +146,29 @@ pub async fn from_output(
     ty: ResponseFormat,
     outputs: Vec<crate::error::Result<Output>>,
 ) -> Result<Vec<GreptimeQueryOutput>, ErrorResponse> {
+    from_output_with_stats(ty, outputs)
+        .await
+        .map(|(results, _)| results)
+}
+
+/// Converts the `outputs` and returns them with the statistics of executing the queries,
+/// if any of the queries reports them.
+pub async fn from_output_with_stats(
+    ty: ResponseFormat,
+    outputs: Vec<crate::error::Result<Output>>,
+) -> Result<(Vec<GreptimeQueryOutput>, Option<ExecutionStats>), ErrorResponse> {
     // TODO(sunng87): this api response structure cannot represent error well.
     //  It hides successful execution results from error response
     let mut results = Vec::with_capacity(outputs.len());
+    let mut metrics: Option<RecordBatchMetrics> = None;
     for out in outputs {
         match out {
             Ok(Output::AffectedRows(rows)) => {
                 results.push(GreptimeQueryOutput::AffectedRows(rows));
             }
-            Ok(Output::Stream(stream)) => {
+            Ok(Output::Stream(mut stream)) => {
                 // TODO(sunng87): streaming response
-                match util::collect(stream).await {
+                match (&mut stream).try_collect::<Vec<_>>().await {
                     Ok(rows) => match HttpRecordsOutput::try_from(rows) {
                         Ok(rows) => {
                             results.push(GreptimeQueryOutput::Records(rows));
@@ -147,6 +181,12 @@ pub async fn from_output(
                         return Err(ErrorResponse::from_error(ty, err));
                     }
                 }
+                // Stats are complete once the stream is exhausted.
+                if let Some(stream_metrics) = stream.metrics() {
+                    metrics
+                        .get_or_insert_with(Default::default)
+                        .merge(&stream_metrics);
+                }
             }
             Ok(Output::RecordBatches(rbs)) => match HttpRecordsOutput::try_from(rbs.take()) {
                 Ok(rows) => {
@@ -162,7 +202,7 @@ pub async fn from_output(
         }
     }
 
-    Ok(results)
+    Ok((results, metrics.map(ExecutionStats::from)))
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -240,15 +280,70 @@ pub async fn metrics(
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct HealthQuery {}
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ready,
+    NotReady,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
-pub struct HealthResponse {}
+pub struct ComponentStatus {
+    pub name: String,
+    pub ready: bool,
+    /// Why the component is not ready.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct HealthResponse {
+    pub status: HealthStatus,
+    pub components: Vec<ComponentStatus>,
+}
+
+#[derive(Clone, Default)]
+pub struct HealthState {
+    pub indicators: Vec<HealthIndicatorRef>,
+}
 
 /// Handler to export healthy check
 ///
-/// Currently simply return status "200 OK" (default) with an empty json payload "{}"
+/// Returns status "200 OK" if all components are ready, otherwise "503 Service Unavailable".
+/// The payload lists the state of each component.
 #[axum_macros::debug_handler]
-pub async fn health(Query(_params): Query<HealthQuery>) -> Json<HealthResponse> {
-    Json(HealthResponse {})
+pub async fn health(
+    State(state): State<HealthState>,
+    Query(_params): Query<HealthQuery>,
+) -> (axum::http::StatusCode, Json<HealthResponse>) {
+    let components: Vec<_> =
+        futures::future::join_all(state.indicators.iter().map(|indicator| async move {
+            let result = indicator.check().await;
+            ComponentStatus {
+                name: indicator.name().to_string(),
+                ready: result.is_ok(),
+                message: result.err(),
+            }
+        }))
+        .await;
+
+    if components.iter().all(|component| component.ready) {
+        (
+            axum::http::StatusCode::OK,
+            Json(HealthResponse {
+                status: HealthStatus::Ready,
+                components,
+            }),
+        )
+    } else {
+        (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthResponse {
+                status: HealthStatus::NotReady,
+                components,
+            }),
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -288,7 +383,7 @@ async fn validate_schema(
     query_ctx: QueryContextRef,
 ) -> Option<(StatusCode, String)> {
     match sql_handler
-        .is_valid_schema(query_ctx.current_catalog(), query_ctx.current_schema())
+        .is_valid_schema(query_ctx.current_catalog(), &query_ctx.current_schema())
         .await
     {
         Ok(true) => None,
@@ -13,9 +13,17 @@
 // limitations under the License.
 
 use headers::{Header, HeaderName, HeaderValue};
+use session::context::TimestampPrecisionAction;
+use snafu::OptionExt;
+
+use crate::error::{InvalidParameterSnafu, Result};
 
 pub const GREPTIME_DB_HEADER_FORMAT: &str = "x-greptime-format";
 pub const GREPTIME_DB_HEADER_EXECUTION_TIME: &str = "x-greptime-execution-time";
+pub const GREPTIME_DB_HEADER_STATS: &str = "x-greptime-stats";
+/// Header of HTTP and gRPC requests setting the [TimestampPrecisionAction] of the insertions.
+pub const GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION: &str =
+    "x-greptime-timestamp-precision-action";
 
 pub static GREPTIME_DB_HEADER_NAME: HeaderName = HeaderName::from_static("x-greptime-name");
 
@@ -53,3 +61,16 @@ impl GreptimeDbName {
         self.0.as_ref()
     }
 }
+
+/// Parses the value of the [GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION] header, the
+/// action is [TimestampPrecisionAction::Truncate] if the header is absent.
+pub(crate) fn timestamp_precision_action(value: Option<&str>) -> Result<TimestampPrecisionAction> {
+    let Some(value) = value else {
+        return Ok(TimestampPrecisionAction::default());
+    };
+    TimestampPrecisionAction::from_name(value).with_context(|| InvalidParameterSnafu {
+        reason: format!(
+            "invalid {GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION}: {value}, expect 'truncate' or 'error'"
+        ),
+    })
+}
@@ -14,16 +14,19 @@
 
 use std::collections::HashMap;
 
-use axum::extract::{Query, State};
+use axum::extract::{Query, RawBody, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Extension;
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_grpc::writer::Precision;
+use hyper::body::HttpBody;
+use hyper::Body;
 use session::context::QueryContextRef;
+use snafu::ResultExt;
 
-use crate::error::{Result, TimePrecisionSnafu};
-use crate::influxdb::InfluxdbRequest;
+use crate::error::{HyperSnafu, Result, TimePrecisionSnafu};
+use crate::influxdb::InfluxdbLineBatcher;
 use crate::query_handler::InfluxdbLineProtocolHandlerRef;
 
 // https://docs.influxdata.com/influxdb/v1.8/tools/api/#ping-http-endpoint
@@ -43,7 +46,7 @@ pub async fn influxdb_write_v1(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
     Query(mut params): Query<HashMap<String, String>>,
     Extension(query_ctx): Extension<QueryContextRef>,
-    lines: String,
+    RawBody(body): RawBody,
 ) -> Result<impl IntoResponse> {
     let db = params
         .remove("db")
@@ -53,8 +56,9 @@ pub async fn influxdb_write_v1(
         .get("precision")
         .map(|val| parse_time_precision(val))
         .transpose()?;
+    let skip_malformed = params.contains_key("skip_malformed");
 
-    influxdb_write(&db, precision, lines, handler, query_ctx).await
+    influxdb_write(&db, precision, skip_malformed, body, handler, query_ctx).await
 }
 
 #[axum_macros::debug_handler]
@@ -62,7 +66,7 @@ pub async fn influxdb_write_v2(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
     Query(mut params): Query<HashMap<String, String>>,
     Extension(query_ctx): Extension<QueryContextRef>,
-    lines: String,
+    RawBody(body): RawBody,
 ) -> Result<impl IntoResponse> {
     let db = params
         .remove("bucket")
@@ -72,14 +76,27 @@ pub async fn influxdb_write_v2(
         .get("precision")
         .map(|val| parse_time_precision(val))
         .transpose()?;
+    let skip_malformed = params.contains_key("skip_malformed");
 
-    influxdb_write(&db, precision, lines, handler, query_ctx).await
+    influxdb_write(&db, precision, skip_malformed, body, handler, query_ctx).await
 }
 
+/// Max number of lines inserted at once when the payload is streaming.
+const INFLUXDB_WRITE_BATCH_LINES: usize = 1024;
+/// Max size of a line, which bounds the incomplete line buffered between chunks.
+const INFLUXDB_MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Inserts the lines in `body` in batches as they arrive, so a large payload is never
+/// buffered as a whole.
+///
+/// Malformed lines are skipped if `skip_malformed` is true, otherwise the write fails at
+/// the first batch with a malformed line. Each batch is inserted as a whole, so none of
+/// the lines in the failed batch are inserted while batches before it remain inserted.
 pub async fn influxdb_write(
     db: &str,
     precision: Option<Precision>,
-    lines: String,
+    skip_malformed: bool,
+    mut body: Body,
     handler: InfluxdbLineProtocolHandlerRef,
     ctx: QueryContextRef,
 ) -> Result<impl IntoResponse> {
@@ -87,8 +104,22 @@ pub async fn influxdb_write(
         .with_label_values(&[db])
         .start_timer();
 
-    let request = InfluxdbRequest { precision, lines };
-    handler.exec(request, ctx).await?;
+    let mut batcher = InfluxdbLineBatcher::new(
+        precision,
+        INFLUXDB_WRITE_BATCH_LINES,
+        INFLUXDB_MAX_LINE_BYTES,
+        skip_malformed,
+    );
+    while let Some(chunk) = body.data().await {
+        batcher.push(&chunk.context(HyperSnafu)?);
+        while let Some(request) = batcher.next_batch()? {
+            handler.exec(request, ctx.clone()).await?;
+        }
+    }
+    batcher.finish();
+    while let Some(request) = batcher.next_batch()? {
+        handler.exec(request, ctx.clone()).await?;
+    }
 
     Ok((StatusCode::NO_CONTENT, ()))
 }
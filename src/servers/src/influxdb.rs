@@ -15,79 +15,275 @@
 use api::v1::value::ValueData;
 use api::v1::{ColumnDataType, RowInsertRequests};
 use common_grpc::writer::Precision;
+use common_telemetry::warn;
 use influxdb_line_protocol::{parse_lines, FieldValue};
-use snafu::ResultExt;
+use session::context::TimestampPrecisionAction;
+use snafu::ensure;
 
-use crate::error::{Error, InfluxdbLineProtocolSnafu};
+use crate::error::{Error, InfluxdbMalformedLineSnafu, Result, TimestampPrecisionLossSnafu};
 use crate::row_writer::{self, MultiTableData};
 
 pub const INFLUXDB_TIMESTAMP_COLUMN_NAME: &str = "ts";
 pub const DEFAULT_TIME_PRECISION: Precision = Precision::Nanosecond;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct InfluxdbRequest {
     pub precision: Option<Precision>,
     pub lines: String,
+    /// Skips malformed lines instead of rejecting the request.
+    pub skip_malformed: bool,
+    /// Number of lines before `lines` in the payload, to report the position of
+    /// malformed lines when the payload is split into several requests.
+    pub line_offset: usize,
 }
 
 impl TryFrom<InfluxdbRequest> for RowInsertRequests {
     type Error = Error;
 
-    fn try_from(value: InfluxdbRequest) -> Result<Self, Self::Error> {
-        let lines = parse_lines(&value.lines)
-            .collect::<influxdb_line_protocol::Result<Vec<_>>>()
-            .context(InfluxdbLineProtocolSnafu)?;
+    fn try_from(value: InfluxdbRequest) -> std::result::Result<Self, Self::Error> {
+        to_row_insert_requests(value, TimestampPrecisionAction::Truncate)
+    }
+}
+
+/// Converts the line protocol `request` into row insert requests.
+///
+/// Timestamps are written in milliseconds, timestamps more precise than milliseconds
+/// are truncated, or rejected if the `action` is [TimestampPrecisionAction::Error].
+///
+/// Each line is parsed once, the request is rejected as a whole if any line is malformed
+/// unless `skip_malformed` of the request is set.
+pub fn to_row_insert_requests(
+    request: InfluxdbRequest,
+    action: TimestampPrecisionAction,
+) -> Result<RowInsertRequests> {
+    let mut multi_table_data = MultiTableData::new();
+
+    for (i, text) in request.lines.lines().enumerate() {
+        let line_number = request.line_offset + i + 1;
+        let line = match parse_lines(text).next() {
+            Some(Ok(line)) => line,
+            Some(Err(e)) if request.skip_malformed => {
+                warn!("Skip malformed InfluxDB line {}: {}", line_number, e);
+                continue;
+            }
+            Some(Err(e)) => {
+                return InfluxdbMalformedLineSnafu {
+                    line: line_number,
+                    reason: e.to_string(),
+                }
+                .fail();
+            }
+            // Empty line or comment.
+            None => continue,
+        };
+        let table_name = line.series.measurement.as_str();
+        let tags = &line.series.tag_set;
+        let fields = &line.field_set;
+        let ts = line.timestamp;
+        // tags.len + fields.len + timestamp(+1)
+        let num_columns = tags.as_ref().map(|x| x.len()).unwrap_or(0) + fields.len() + 1;
+
+        let table_data = multi_table_data.get_or_default_table_data(table_name, num_columns, 0);
+        let mut one_row = table_data.alloc_one_row();
+
+        // tags
+        if let Some(tags) = tags {
+            let kvs = tags.iter().map(|(k, v)| (k.to_string(), v.as_str()));
+            row_writer::write_tags(table_data, kvs, &mut one_row)?;
+        }
+
+        // fields
+        let fields = fields.iter().map(|(k, v)| {
+            let (datatype, value) = match v {
+                FieldValue::I64(v) => (ColumnDataType::Int64, ValueData::I64Value(*v)),
+                FieldValue::U64(v) => (ColumnDataType::Uint64, ValueData::U64Value(*v)),
+                FieldValue::F64(v) => (ColumnDataType::Float64, ValueData::F64Value(*v)),
+                FieldValue::String(v) => (
+                    ColumnDataType::String,
+                    ValueData::StringValue(v.to_string()),
+                ),
+                FieldValue::Boolean(v) => (ColumnDataType::Boolean, ValueData::BoolValue(*v)),
+            };
+            (k.to_string(), datatype, value)
+        });
+        row_writer::write_fields(table_data, fields, &mut one_row)?;
+
+        // timestamp
+        let precision = unwrap_or_default_precision(request.precision);
+        if let Some(ts) = ts.filter(|_| action == TimestampPrecisionAction::Error) {
+            ensure_millisecond_precision(precision, ts)?;
+        }
+        row_writer::write_ts_precision(
+            table_data,
+            INFLUXDB_TIMESTAMP_COLUMN_NAME,
+            ts,
+            precision,
+            &mut one_row,
+        )?;
+
+        table_data.add_row(one_row);
+    }
+
+    Ok(multi_table_data.into_row_insert_requests().0)
+}
 
-        let mut multi_table_data = MultiTableData::new();
+/// Splits the chunks of a streaming line protocol payload into batches of complete lines,
+/// so the payload can be inserted before it's fully received.
+///
+/// A line may span several chunks, and the last line of the payload is not required to
+/// end with a newline. Lines are parsed when the batches are converted into row inserts.
+#[derive(Debug)]
+pub struct InfluxdbLineBatcher {
+    precision: Option<Precision>,
+    /// Received bytes that are not returned in batches yet. Once all batches are taken,
+    /// it only holds the incomplete line, which never exceeds `max_line_bytes`.
+    pending: Vec<u8>,
+    max_batch_lines: usize,
+    max_line_bytes: usize,
+    /// Number of lines taken, to report the position of malformed lines.
+    line_number: usize,
+    skip_malformed: bool,
+    /// Whether the rest of an oversized line is being skipped.
+    skipping_line: bool,
+    finished: bool,
+}
 
-        for line in &lines {
-            let table_name = line.series.measurement.as_str();
-            let tags = &line.series.tag_set;
-            let fields = &line.field_set;
-            let ts = line.timestamp;
-            // tags.len + fields.len + timestamp(+1)
-            let num_columns = tags.as_ref().map(|x| x.len()).unwrap_or(0) + fields.len() + 1;
+impl InfluxdbLineBatcher {
+    /// Creates a batcher returning batches of at most `max_batch_lines` lines.
+    ///
+    /// Lines longer than `max_line_bytes` and lines that are not valid utf8 are malformed.
+    /// Malformed lines are skipped if `skip_malformed` is true, otherwise they fail the
+    /// batch they belong to.
+    pub fn new(
+        precision: Option<Precision>,
+        max_batch_lines: usize,
+        max_line_bytes: usize,
+        skip_malformed: bool,
+    ) -> Self {
+        Self {
+            precision,
+            pending: Vec::new(),
+            max_batch_lines: max_batch_lines.max(1),
+            max_line_bytes,
+            line_number: 0,
+            skip_malformed,
+            skipping_line: false,
+            finished: false,
+        }
+    }
 
-            let table_data = multi_table_data.get_or_default_table_data(table_name, num_columns, 0);
-            let mut one_row = table_data.alloc_one_row();
+    /// Pushes a chunk of the payload.
+    ///
+    /// The caller should take all batches by [InfluxdbLineBatcher::next_batch()] before
+    /// pushing the next chunk.
+    pub fn push(&mut self, mut chunk: &[u8]) {
+        if self.skipping_line {
+            let Some(pos) = chunk.iter().position(|b| *b == b'\n') else {
+                return;
+            };
+            chunk = &chunk[pos + 1..];
+            self.skipping_line = false;
+        }
+        self.pending.extend_from_slice(chunk);
+    }
 
-            // tags
-            if let Some(tags) = tags {
-                let kvs = tags.iter().map(|(k, v)| (k.to_string(), v.as_str()));
-                row_writer::write_tags(table_data, kvs, &mut one_row)?;
+    /// Marks the end of the payload, so the final line without a trailing newline
+    /// is returned in the last batch.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Takes the next batch of complete lines, returns `None` if there are not
+    /// enough lines to fill a batch before the payload is finished.
+    pub fn next_batch(&mut self) -> Result<Option<InfluxdbRequest>> {
+        let line_offset = self.line_number;
+        let mut lines = String::new();
+        let mut num_lines = 0;
+        let mut consumed = 0;
+        while num_lines < self.max_batch_lines {
+            let rest = &self.pending[consumed..];
+            let (line, len) = match rest.iter().position(|b| *b == b'\n') {
+                Some(pos) => (&rest[..pos], pos + 1),
+                None if self.finished && !rest.is_empty() => (rest, rest.len()),
+                None if rest.len() > self.max_line_bytes => {
+                    // Skips the rest of the oversized line in following chunks.
+                    consumed = self.pending.len();
+                    num_lines += 1;
+                    self.line_number += 1;
+                    self.on_malformed(self.oversized_reason())?;
+                    self.skipping_line = true;
+                    lines.push('\n');
+                    break;
+                }
+                None => break,
+            };
+            consumed += len;
+            num_lines += 1;
+            self.line_number += 1;
+
+            let malformed = match std::str::from_utf8(line) {
+                Ok(line) if line.len() <= self.max_line_bytes => {
+                    lines.push_str(line);
+                    None
+                }
+                Ok(_) => Some(self.oversized_reason()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(reason) = malformed {
+                self.on_malformed(reason)?;
             }
+            // A skipped line is kept as an empty line so line numbers in the batch
+            // don't change.
+            lines.push('\n');
+        }
+        let _ = self.pending.drain(..consumed);
 
-            // fields
-            let fields = fields.iter().map(|(k, v)| {
-                let (datatype, value) = match v {
-                    FieldValue::I64(v) => (ColumnDataType::Int64, ValueData::I64Value(*v)),
-                    FieldValue::U64(v) => (ColumnDataType::Uint64, ValueData::U64Value(*v)),
-                    FieldValue::F64(v) => (ColumnDataType::Float64, ValueData::F64Value(*v)),
-                    FieldValue::String(v) => (
-                        ColumnDataType::String,
-                        ValueData::StringValue(v.to_string()),
-                    ),
-                    FieldValue::Boolean(v) => (ColumnDataType::Boolean, ValueData::BoolValue(*v)),
-                };
-                (k.to_string(), datatype, value)
-            });
-            row_writer::write_fields(table_data, fields, &mut one_row)?;
-
-            // timestamp
-            let precision = unwrap_or_default_precision(value.precision);
-            row_writer::write_ts_precision(
-                table_data,
-                INFLUXDB_TIMESTAMP_COLUMN_NAME,
-                ts,
-                precision,
-                &mut one_row,
-            )?;
-
-            table_data.add_row(one_row);
+        if num_lines == 0 {
+            return Ok(None);
         }
+        Ok(Some(InfluxdbRequest {
+            precision: self.precision,
+            lines,
+            skip_malformed: self.skip_malformed,
+            line_offset,
+        }))
+    }
 
-        Ok(multi_table_data.into_row_insert_requests().0)
+    fn oversized_reason(&self) -> String {
+        format!("line exceeds {} bytes", self.max_line_bytes)
     }
+
+    fn on_malformed(&self, reason: String) -> Result<()> {
+        if self.skip_malformed {
+            warn!(
+                "Skip malformed InfluxDB line {}: {}",
+                self.line_number, reason
+            );
+            return Ok(());
+        }
+        InfluxdbMalformedLineSnafu {
+            line: self.line_number,
+            reason,
+        }
+        .fail()
+    }
+}
+
+/// Returns an error if the timestamp `ts` in `precision` loses precision in milliseconds.
+fn ensure_millisecond_precision(precision: Precision, ts: i64) -> Result<()> {
+    let divisor = match precision {
+        Precision::Nanosecond => 1_000_000,
+        Precision::Microsecond => 1_000,
+        _ => 1,
+    };
+    ensure!(
+        ts % divisor == 0,
+        TimestampPrecisionLossSnafu {
+            timestamp: ts,
+            precision: precision.to_string(),
+        }
+    );
+    Ok(())
 }
 
 #[inline]
@@ -116,8 +312,8 @@ monitor2,host=host3 cpu=66.5 1663840496100023102
 monitor2,host=host4 cpu=66.3,memory=1029 1663840496400340003";
 
         let influxdb_req = InfluxdbRequest {
-            precision: None,
             lines: lines.to_string(),
+            ..Default::default()
         };
 
         let requests: RowInsertRequests = influxdb_req.try_into().unwrap();
@@ -132,6 +328,36 @@ monitor2,host=host4 cpu=66.3,memory=1029 1663840496400340003";
         }
     }
 
+    #[test]
+    fn test_timestamp_precision_action() {
+        let new_request = |lines: &str| InfluxdbRequest {
+            precision: Some(Precision::Microsecond),
+            lines: lines.to_string(),
+            ..Default::default()
+        };
+
+        // Timestamps in milliseconds are accepted.
+        let request = new_request("monitor,host=host1 cpu=66.6 1663840496100000");
+        let requests = to_row_insert_requests(request, TimestampPrecisionAction::Error).unwrap();
+        assert_eq!(1, requests.inserts.len());
+
+        let request = new_request("monitor,host=host1 cpu=66.6 1663840496100023");
+        let err = to_row_insert_requests(request, TimestampPrecisionAction::Error).unwrap_err();
+        assert!(
+            matches!(err, Error::TimestampPrecisionLoss { .. }),
+            "unexpected error: {err:?}"
+        );
+        // Truncates the timestamp by default.
+        let request = new_request("monitor,host=host1 cpu=66.6 1663840496100023");
+        let requests: RowInsertRequests = request.try_into().unwrap();
+        let rows = requests.inserts[0].rows.as_ref().unwrap();
+        let ts = rows.rows[0].values.last().unwrap();
+        assert_eq!(
+            Some(ValueData::TimestampMillisecondValue(1663840496100)),
+            ts.value_data
+        );
+    }
+
     fn assert_monitor1_rows(rows: &Option<Rows>) {
         let rows = rows.as_ref().unwrap();
         let schema = &rows.schema;
@@ -302,4 +528,85 @@ monitor2,host=host4 cpu=66.3,memory=1029 1663840496400340003";
             _ => panic!(),
         }
     }
+
+    /// Takes all batches of the `batcher`, returns lines of each batch.
+    fn take_batches(batcher: &mut InfluxdbLineBatcher) -> Vec<String> {
+        std::iter::from_fn(|| batcher.next_batch().unwrap())
+            .map(|request| request.lines)
+            .collect()
+    }
+
+    #[test]
+    fn test_line_batcher() {
+        let mut batcher = InfluxdbLineBatcher::new(None, 2, 1024, false);
+        // The second line spans two chunks.
+        batcher.push(b"m,host=a cpu=1 1\nm,host=b");
+        assert_eq!(vec!["m,host=a cpu=1 1\n"], take_batches(&mut batcher));
+        batcher.push(b" cpu=2 2\n\n# comment\nm,host=c cpu=3 3\r\n");
+        assert_eq!(
+            vec!["m,host=b cpu=2 2\n\n", "# comment\nm,host=c cpu=3 3\r\n"],
+            take_batches(&mut batcher)
+        );
+        // The final line has no trailing newline.
+        batcher.push(b"m,host=d cpu=4 4");
+        assert!(take_batches(&mut batcher).is_empty());
+        batcher.finish();
+        let request = batcher.next_batch().unwrap().unwrap();
+        assert_eq!("m,host=d cpu=4 4\n", request.lines);
+        assert_eq!(5, request.line_offset);
+        assert!(batcher.next_batch().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_line_batcher_malformed() {
+        // Invalid utf8 fails the batch.
+        let mut batcher = InfluxdbLineBatcher::new(None, 10, 1024, false);
+        batcher.push(b"m cpu=1 1\nm,host=\xff cpu=2 2\n");
+        let err = batcher.next_batch().unwrap_err();
+        assert!(
+            matches!(err, Error::InfluxdbMalformedLine { line: 2, .. }),
+            "{err:?}"
+        );
+
+        // The incomplete line can't exceed the limit.
+        let mut batcher = InfluxdbLineBatcher::new(None, 10, 16, false);
+        batcher.push(b"m cpu=1 1\nm,host=a,region=b cpu=2");
+        let err = batcher.next_batch().unwrap_err();
+        assert!(
+            matches!(err, Error::InfluxdbMalformedLine { line: 2, .. }),
+            "{err:?}"
+        );
+
+        // Skipped lines are kept as empty lines.
+        let mut batcher = InfluxdbLineBatcher::new(None, 10, 16, true);
+        batcher.push(b"m cpu=1 1\nm,host=\xff cpu=2 2\nm,host=a,region=b cpu=3");
+        assert_eq!(vec!["m cpu=1 1\n\n\n"], take_batches(&mut batcher));
+        assert!(batcher.pending.is_empty());
+        // The rest of the oversized line is skipped.
+        batcher.push(b",cpu=4 4\nm cpu=5 5");
+        batcher.finish();
+        assert_eq!(vec!["m cpu=5 5\n"], take_batches(&mut batcher));
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let new_request = |skip_malformed| InfluxdbRequest {
+            lines: "m cpu=1 1\n\nm cpu= 2\nm cpu=3 3\n".to_string(),
+            skip_malformed,
+            line_offset: 10,
+            ..Default::default()
+        };
+
+        let err = to_row_insert_requests(new_request(false), TimestampPrecisionAction::Truncate)
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::InfluxdbMalformedLine { line: 13, .. }),
+            "{err:?}"
+        );
+
+        let requests =
+            to_row_insert_requests(new_request(true), TimestampPrecisionAction::Truncate).unwrap();
+        let rows = requests.inserts[0].rows.as_ref().unwrap();
+        assert_eq!(2, rows.rows.len());
+    }
 }
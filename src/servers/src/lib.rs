@@ -24,6 +24,7 @@ pub mod configurator;
 pub mod error;
 pub mod export_metrics;
 pub mod grpc;
+pub mod health;
 pub mod heartbeat_options;
 pub mod http;
 pub mod influxdb;
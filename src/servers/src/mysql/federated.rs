@@ -24,11 +24,11 @@ use common_time::timezone::system_timezone_name;
 use common_time::Timezone;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnSchema, Schema};
-use datatypes::vectors::StringVector;
+use datatypes::vectors::{StringVector, UInt32Vector};
 use once_cell::sync::Lazy;
 use regex::bytes::RegexSet;
 use regex::Regex;
-use session::context::QueryContextRef;
+use session::context::{QueryContextRef, ResultLimitAction};
 use session::SessionRef;
 
 static SELECT_VAR_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new("(?i)^(SELECT @@(.*))").unwrap());
@@ -56,6 +56,22 @@ static SHOW_SQL_MODE_PATTERN: Lazy<Regex> =
 static SET_TIME_ZONE_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)^SET TIME_ZONE\s*=\s*'(\S+)'").unwrap());
 
+// Result limit settings, 0 means unlimited.
+static SET_MAX_RESULT_ROWS_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SET MAX_RESULT_ROWS\s*=\s*(\d+)").unwrap());
+static SET_MAX_RESULT_BYTES_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SET MAX_RESULT_BYTES\s*=\s*(\d+)").unwrap());
+static SET_DEFAULT_LIMIT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SET DEFAULT_LIMIT\s*=\s*(\d+)").unwrap());
+static SET_RESULT_LIMIT_ACTION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SET RESULT_LIMIT_ACTION\s*=\s*'(truncate|error)'").unwrap());
+
+static SHOW_WARNINGS_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(/\* ApplicationName=(.*))?SHOW WARNINGS").unwrap());
+
+/// Code of the warnings in `SHOW WARNINGS`, MySQL's `ER_UNKNOWN_ERROR`.
+const WARNING_CODE: u32 = 1105;
+
 static OTHER_NOT_SUPPORTED_STMT: Lazy<RegexSet> = Lazy::new(|| {
     RegexSet::new([
         // Txn.
@@ -97,8 +113,6 @@ static OTHER_NOT_SUPPORTED_STMT: Lazy<RegexSet> = Lazy::new(|| {
         "(?i)^(/\\*!40101 SET(.*) \\*/)$",
 
         // DBeaver.
-        "(?i)^(SHOW WARNINGS)",
-        "(?i)^(/\\* ApplicationName=(.*)SHOW WARNINGS)",
         "(?i)^(/\\* ApplicationName=(.*)SHOW PLUGINS)",
         "(?i)^(/\\* ApplicationName=(.*)SHOW COLLATION)",
         "(?i)^(/\\* ApplicationName=(.*)SHOW CHARSET)",
@@ -171,6 +185,36 @@ fn show_variables(name: &str, value: &str) -> RecordBatches {
         .unwrap()
 }
 
+// Recordbatches for show warnings statement.
+// Format is:
+// | Level   | Code | Message |
+// | Warning | 1105 | xx      |
+fn show_warnings(warnings: Vec<String>) -> RecordBatches {
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new("Level", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("Code", ConcreteDataType::uint32_datatype(), true),
+        ColumnSchema::new("Message", ConcreteDataType::string_datatype(), true),
+    ]));
+    let columns = vec![
+        Arc::new(StringVector::from(vec!["Warning"; warnings.len()])) as _,
+        Arc::new(UInt32Vector::from_vec(vec![WARNING_CODE; warnings.len()])) as _,
+        Arc::new(StringVector::from(warnings)) as _,
+    ];
+    RecordBatches::try_from_columns(schema, columns)
+        // unwrap is safe because the schema and data are definitely able to form a recordbatch
+        .unwrap()
+}
+
+/// Returns true if the query is `SHOW WARNINGS`, which keeps the warnings of the last
+/// statement.
+pub(crate) fn is_show_warnings(query: &str) -> bool {
+    SHOW_WARNINGS_PATTERN.is_match(query)
+}
+
+fn check_show_warnings(query: &str, session: SessionRef) -> Option<Output> {
+    is_show_warnings(query).then(|| Output::RecordBatches(show_warnings(session.warnings())))
+}
+
 fn select_variable(query: &str, query_context: QueryContextRef) -> Option<Output> {
     let mut fields = vec![];
     let mut values = vec![];
@@ -271,7 +315,31 @@ fn check_set_variables(query: &str, session: SessionRef) -> Option<Output> {
         }
     }
 
-    None
+    if let Some(captures) = SET_DEFAULT_LIMIT_PATTERN.captures(query) {
+        let default_limit = captures.get(1).unwrap().as_str().parse().ok()?;
+        session.set_default_limit((default_limit > 0).then_some(default_limit));
+        return Some(Output::AffectedRows(0));
+    }
+
+    let mut result_limit = session.result_limit();
+    if let Some(captures) = SET_MAX_RESULT_ROWS_PATTERN.captures(query) {
+        let max_rows = captures.get(1).unwrap().as_str().parse().ok()?;
+        result_limit.max_rows = (max_rows > 0).then_some(max_rows);
+    } else if let Some(captures) = SET_MAX_RESULT_BYTES_PATTERN.captures(query) {
+        let max_bytes = captures.get(1).unwrap().as_str().parse().ok()?;
+        result_limit.max_bytes = (max_bytes > 0).then_some(max_bytes);
+    } else if let Some(captures) = SET_RESULT_LIMIT_ACTION_PATTERN.captures(query) {
+        result_limit.action = if captures[1].eq_ignore_ascii_case("error") {
+            ResultLimitAction::Error
+        } else {
+            ResultLimitAction::Truncate
+        };
+    } else {
+        return None;
+    }
+    session.set_result_limit(result_limit);
+
+    Some(Output::AffectedRows(0))
 }
 
 // Check for SET or others query, this is the final check of the federated query.
@@ -281,7 +349,7 @@ fn check_others(query: &str, query_ctx: QueryContextRef) -> Option<Output> {
     }
 
     let recordbatches = if SELECT_DATABASE_PATTERN.is_match(query) {
-        let schema = query_ctx.current_schema();
+        let schema = &query_ctx.current_schema();
         Some(select_function("database()", schema))
     } else if SELECT_TIME_DIFF_FUNC_PATTERN.is_match(query) {
         Some(select_function(
@@ -311,6 +379,7 @@ pub(crate) fn check(
     check_select_variable(query, query_ctx.clone())
         // Then to check "show variables like ...".
         .or_else(|| check_show_variables(query))
+        .or_else(|| check_show_warnings(query, session.clone()))
         .or_else(|| check_set_variables(query, session.clone()))
         // Last check
         .or_else(|| check_others(query, query_ctx))
@@ -452,4 +521,74 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_show_warnings() {
+        let session = Arc::new(Session::new(None, Channel::Mysql));
+        assert!(check("SHOW WARNINGS", QueryContext::arc(), session.clone()).is_some());
+
+        session.set_warnings(vec!["Results are truncated".to_string()]);
+        let output = check("show warnings", QueryContext::arc(), session.clone());
+        match output.unwrap() {
+            Output::RecordBatches(r) => {
+                let expected = "\
++---------+------+-----------------------+
+| Level   | Code | Message               |
++---------+------+-----------------------+
+| Warning | 1105 | Results are truncated |
++---------+------+-----------------------+";
+                assert_eq!(r.pretty_print().unwrap(), expected)
+            }
+            _ => unreachable!(),
+        }
+        assert!(is_show_warnings(
+            "/* ApplicationName=DBeaver 22.3.4 - Main */ SHOW WARNINGS"
+        ));
+        assert!(!is_show_warnings("SHOW TABLES"));
+    }
+
+    #[test]
+    fn test_set_result_limit() {
+        let session = Arc::new(Session::new(None, Channel::Mysql));
+        assert!(session.new_query_context().result_limit().is_unlimited());
+
+        for query in [
+            "SET max_result_rows = 10",
+            "set MAX_RESULT_BYTES=1024",
+            "SET result_limit_action = 'ERROR'",
+        ] {
+            let output = check(query, QueryContext::arc(), session.clone());
+            assert!(matches!(output, Some(Output::AffectedRows(0))), "{query}");
+        }
+        let result_limit = session.new_query_context().result_limit();
+        assert_eq!(Some(10), result_limit.max_rows);
+        assert_eq!(Some(1024), result_limit.max_bytes);
+        assert_eq!(ResultLimitAction::Error, result_limit.action);
+
+        // 0 removes the limit.
+        let _ = check(
+            "SET max_result_rows = 0",
+            QueryContext::arc(),
+            session.clone(),
+        );
+        assert_eq!(None, session.result_limit().max_rows);
+    }
+
+    #[test]
+    fn test_set_default_limit() {
+        let session = Arc::new(Session::new(None, Channel::Mysql));
+        assert_eq!(None, session.new_query_context().default_limit());
+
+        let output = check(
+            "SET default_limit = 100",
+            QueryContext::arc(),
+            session.clone(),
+        );
+        assert!(matches!(output, Some(Output::AffectedRows(0))));
+        assert_eq!(Some(100), session.new_query_context().default_limit());
+
+        // 0 disables the default limit.
+        let _ = check("set DEFAULT_LIMIT=0", QueryContext::arc(), session.clone());
+        assert_eq!(None, session.default_limit());
+    }
 }
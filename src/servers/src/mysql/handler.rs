@@ -99,7 +99,21 @@ impl MysqlInstanceShim {
         {
             vec![Ok(output)]
         } else {
-            self.query_handler.do_query(query, query_ctx).await
+            let outputs = self.query_handler.do_query(query, query_ctx.clone()).await;
+            // Keeps the session in sync in case a `USE` or `SET` statement has changed it.
+            self.session.set_schema(query_ctx.current_schema());
+            self.session
+                .set_timestamp_precision_action(query_ctx.timestamp_precision_action());
+            outputs
+        }
+    }
+
+    /// Keeps warnings raised by the `query` in the session for `SHOW WARNINGS`, the results
+    /// are written so all warnings are raised.
+    fn keep_warnings(&self, query: &str, query_ctx: &QueryContextRef) {
+        // Like MySQL, `SHOW WARNINGS` doesn't clear the warnings.
+        if !crate::mysql::federated::is_show_warnings(query) {
+            self.session.set_warnings(query_ctx.warnings());
         }
     }
 
@@ -274,6 +288,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
             }
             Some(sql_plan) => sql_plan,
         };
+        let raw_query = sql_plan.query.clone();
 
         let outputs = match sql_plan.plan {
             Some(plan) => {
@@ -320,7 +335,8 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
             }
         };
 
-        writer::write_output(w, query_ctx, outputs).await?;
+        writer::write_output(w, query_ctx.clone(), outputs).await?;
+        self.keep_warnings(&raw_query, &query_ctx);
 
         Ok(())
     }
@@ -344,7 +360,8 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
             .with_label_values(&[crate::metrics::METRIC_MYSQL_TEXTQUERY, db.as_str()])
             .start_timer();
         let outputs = self.do_query(query, query_ctx.clone()).await;
-        writer::write_output(writer, query_ctx, outputs).await?;
+        writer::write_output(writer, query_ctx.clone(), outputs).await?;
+        self.keep_warnings(query, &query_ctx);
         Ok(())
     }
 
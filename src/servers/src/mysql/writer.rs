@@ -96,7 +96,9 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     Self::write_query_result(query_result, self.writer, self.query_context).await?;
                 }
                 Output::AffectedRows(rows) => {
-                    let next_writer = Self::write_affected_rows(self.writer, rows).await?;
+                    let warnings = self.query_context.warnings().len();
+                    let next_writer =
+                        Self::write_affected_rows(self.writer, rows, warnings).await?;
                     return Ok(Some(MysqlResultWriter::new(
                         next_writer,
                         self.query_context,
@@ -117,10 +119,12 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     async fn write_affected_rows(
         w: QueryResultWriter<'a, W>,
         rows: usize,
+        warnings: usize,
     ) -> Result<QueryResultWriter<'a, W>> {
         let next_writer = w
             .complete_one(OkResponse {
                 affected_rows: rows as u64,
+                warnings: warnings.min(u16::MAX as usize) as u16,
                 ..Default::default()
             })
             .await?;
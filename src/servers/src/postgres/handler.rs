@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Debug;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -20,14 +21,21 @@ use common_query::Output;
 use common_recordbatch::error::Result as RecordBatchResult;
 use common_recordbatch::RecordBatch;
 use datatypes::schema::SchemaRef;
-use futures::{future, stream, Stream, StreamExt};
+use futures::{future, stream, Sink, SinkExt, Stream, StreamExt};
 use pgwire::api::portal::{Format, Portal};
-use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler, StatementOrPortal};
+use pgwire::api::query::{
+    send_execution_response, send_query_response, ExtendedQueryHandler, SimpleQueryHandler,
+    StatementOrPortal,
+};
 use pgwire::api::results::{DataRowEncoder, DescribeResponse, QueryResponse, Response, Tag};
 use pgwire::api::stmt::QueryParser;
-use pgwire::api::{ClientInfo, Type};
+use pgwire::api::{ClientInfo, PgWireConnectionState, Type};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
+use pgwire::messages::response::{EmptyQueryResponse, ReadyForQuery, READY_STATUS_IDLE};
+use pgwire::messages::simplequery::Query as PgQuery;
+use pgwire::messages::PgWireBackendMessage;
 use query::query_engine::DescribeResult;
+use session::context::QueryContextRef;
 use session::Session;
 use sql::dialect::PostgreSqlDialect;
 use sql::parser::ParserContext;
@@ -40,6 +48,55 @@ use crate::SqlPlan;
 
 #[async_trait]
 impl SimpleQueryHandler for PostgresServerHandler {
+    /// Sends the warnings raised by the query as notices after its results, as warnings
+    /// are raised while sending the results.
+    async fn on_query<C>(&self, client: &mut C, query: PgQuery) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        client.set_state(PgWireConnectionState::QueryInProgress);
+        let query_ctx = self.session.new_query_context();
+        let responses = self.query(query.query(), query_ctx.clone()).await?;
+        if responses.is_empty() {
+            client
+                .feed(PgWireBackendMessage::EmptyQueryResponse(EmptyQueryResponse))
+                .await?;
+        }
+        for response in responses {
+            match response {
+                Response::EmptyQuery => {
+                    client
+                        .feed(PgWireBackendMessage::EmptyQueryResponse(EmptyQueryResponse))
+                        .await?;
+                }
+                Response::Query(results) => send_query_response(client, results, true).await?,
+                Response::Execution(tag) => send_execution_response(client, tag).await?,
+                Response::Error(e) => {
+                    client
+                        .feed(PgWireBackendMessage::ErrorResponse((*e).into()))
+                        .await?;
+                }
+            }
+        }
+        for warning in query_ctx.warnings() {
+            let notice = ErrorInfo::new("WARNING".to_string(), "01000".to_string(), warning);
+            client
+                .feed(PgWireBackendMessage::NoticeResponse(notice.into()))
+                .await?;
+        }
+        client
+            .feed(PgWireBackendMessage::ReadyForQuery(ReadyForQuery::new(
+                READY_STATUS_IDLE,
+            )))
+            .await?;
+        client.flush().await?;
+        client.set_state(PgWireConnectionState::ReadyForQuery);
+
+        Ok(())
+    }
+
     async fn do_query<'a, C>(
         &self,
         _client: &mut C,
@@ -48,12 +105,25 @@ impl SimpleQueryHandler for PostgresServerHandler {
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        let query_ctx = self.session.new_query_context();
+        self.query(query, self.session.new_query_context()).await
+    }
+}
+
+impl PostgresServerHandler {
+    async fn query<'a>(
+        &self,
+        query: &'a str,
+        query_ctx: QueryContextRef,
+    ) -> PgWireResult<Vec<Response<'a>>> {
         let db = query_ctx.get_db_string();
         let _timer = crate::metrics::METRIC_POSTGRES_QUERY_TIMER
             .with_label_values(&[crate::metrics::METRIC_POSTGRES_SIMPLE_QUERY, db.as_str()])
             .start_timer();
-        let outputs = self.query_handler.do_query(query, query_ctx).await;
+        let outputs = self.query_handler.do_query(query, query_ctx.clone()).await;
+        // Keeps the session in sync in case a `USE` or `SET` statement has changed it.
+        self.session.set_schema(query_ctx.current_schema());
+        self.session
+            .set_timestamp_precision_action(query_ctx.timestamp_precision_action());
 
         let mut results = Vec::with_capacity(outputs.len());
 
@@ -20,7 +20,8 @@ use axum::http;
 use http_body::Body;
 use hyper::{Request, StatusCode};
 use servers::http::authorize::inner_auth;
-use session::context::QueryContextRef;
+use servers::http::header::GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION;
+use session::context::{QueryContextRef, TimestampPrecisionAction};
 
 #[tokio::test]
 async fn test_http_auth() {
@@ -123,6 +124,37 @@ async fn test_whitelist_no_auth() {
     assert!(req.is_ok());
 }
 
+#[tokio::test]
+async fn test_timestamp_precision_action_header() {
+    let req = mock_http_request(None, None).unwrap();
+    let req = inner_auth(None, req).await.unwrap();
+    let ctx: &QueryContextRef = req.extensions().get().unwrap();
+    assert_eq!(
+        TimestampPrecisionAction::Truncate,
+        ctx.timestamp_precision_action()
+    );
+
+    let mut req = mock_http_request(None, None).unwrap();
+    let _ = req.headers_mut().insert(
+        GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION,
+        "error".parse().unwrap(),
+    );
+    let req = inner_auth(None, req).await.unwrap();
+    let ctx: &QueryContextRef = req.extensions().get().unwrap();
+    assert_eq!(
+        TimestampPrecisionAction::Error,
+        ctx.timestamp_precision_action()
+    );
+
+    let mut req = mock_http_request(None, None).unwrap();
+    let _ = req.headers_mut().insert(
+        GREPTIME_DB_HEADER_TIMESTAMP_PRECISION_ACTION,
+        "round".parse().unwrap(),
+    );
+    let resp = inner_auth(None, req).await.unwrap_err();
+    assert_eq!(StatusCode::BAD_REQUEST, resp.status());
+}
+
 // copy from http::authorize
 fn mock_http_request(
     auth_header: Option<&str>,
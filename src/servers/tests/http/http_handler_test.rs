@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use axum::body::{Body, Bytes};
 use axum::extract::{Json, Query, RawBody, State};
@@ -23,6 +24,7 @@ use headers::HeaderValue;
 use http_body::combinators::UnsyncBoxBody;
 use hyper::Response;
 use mime_guess::mime;
+use servers::health::ReadinessIndicator;
 use servers::http::{
     handler as http_handler, script as script_handler, ApiState, GreptimeOptionsConfigState,
     GreptimeQueryOutput, HttpResponse,
@@ -52,6 +54,7 @@ async fn test_sql_not_provided() {
             sql: None,
             format: Some(format.to_string()),
             epoch: None,
+            null_value: None,
         };
 
         let HttpResponse::Error(resp) = http_handler::sql(
@@ -104,7 +107,8 @@ async fn test_sql_output_rows() {
     "column_schemas": [
       {
         "name": "SUM(numbers.uint32s)",
-        "data_type": "UInt64"
+        "data_type": "UInt64",
+        "nullable": true
       }
     ]
   },
@@ -194,7 +198,8 @@ async fn test_sql_form() {
     "column_schemas": [
       {
         "name": "SUM(numbers.uint32s)",
-        "data_type": "UInt64"
+        "data_type": "UInt64",
+        "nullable": true
       }
     ]
   },
@@ -339,7 +344,8 @@ def test(n) -> vector[i64]:
     "column_schemas": [
       {
         "name": "n",
-        "data_type": "Int64"
+        "data_type": "Int64",
+        "nullable": false
       }
     ]
   },
@@ -406,7 +412,8 @@ def test(n, **params)  -> vector[i64]:
     "column_schemas": [
       {
         "name": "n",
-        "data_type": "Int64"
+        "data_type": "Int64",
+        "nullable": false
       }
     ]
   },
@@ -456,6 +463,7 @@ fn create_query(format: &str) -> Query<http_handler::SqlQuery> {
         db: None,
         format: Some(format.to_string()),
         epoch: None,
+        null_value: None,
     })
 }
 
@@ -465,17 +473,22 @@ fn create_form(format: &str) -> Form<http_handler::SqlQuery> {
         db: None,
         format: Some(format.to_string()),
         epoch: None,
+        null_value: None,
     })
 }
 
-/// Currently the payload of response should be simply an empty json "{}";
 #[tokio::test]
 async fn test_health() {
-    let expected_json = http_handler::HealthResponse {};
-    let expected_json_str = "{}".to_string();
+    let expected_json = http_handler::HealthResponse {
+        status: http_handler::HealthStatus::Ready,
+        components: vec![],
+    };
+    let expected_json_str = r#"{"status":"ready","components":[]}"#.to_string();
 
     let query = http_handler::HealthQuery {};
-    let Json(json) = http_handler::health(Query(query)).await;
+    let (status, Json(json)) =
+        http_handler::health(State(http_handler::HealthState::default()), Query(query)).await;
+    assert_eq!(status, axum::http::StatusCode::OK);
     assert_eq!(json, expected_json);
     assert_eq!(
         serde_json::ser::to_string(&json).unwrap(),
@@ -483,6 +496,46 @@ async fn test_health() {
     );
 }
 
+#[tokio::test]
+async fn test_health_not_ready() {
+    let regions = Arc::new(ReadinessIndicator::new("regions", "opening regions"));
+    let object_store = Arc::new(ReadinessIndicator::new("object_store", "unreachable"));
+    object_store.set_ready();
+    let state = http_handler::HealthState {
+        indicators: vec![object_store as _, regions.clone() as _],
+    };
+
+    // Regions are not opened yet.
+    let (status, Json(json)) =
+        http_handler::health(State(state.clone()), Query(http_handler::HealthQuery {})).await;
+    assert_eq!(status, axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        json,
+        http_handler::HealthResponse {
+            status: http_handler::HealthStatus::NotReady,
+            components: vec![
+                http_handler::ComponentStatus {
+                    name: "object_store".to_string(),
+                    ready: true,
+                    message: None,
+                },
+                http_handler::ComponentStatus {
+                    name: "regions".to_string(),
+                    ready: false,
+                    message: Some("opening regions".to_string()),
+                },
+            ],
+        }
+    );
+
+    regions.set_ready();
+    let (status, Json(json)) =
+        http_handler::health(State(state), Query(http_handler::HealthQuery {})).await;
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(json.status, http_handler::HealthStatus::Ready);
+    assert!(json.components.iter().all(|component| component.ready));
+}
+
 #[tokio::test]
 async fn test_status() {
     let hostname = hostname::get()
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use api::v1::greptime_request::Request;
@@ -27,12 +28,13 @@ use query::plan::LogicalPlan;
 use query::query_engine::DescribeResult;
 use servers::error::{Error, Result};
 use servers::http::header::GREPTIME_DB_HEADER_FORMAT;
+use servers::http::influxdb::influxdb_write;
 use servers::http::{HttpOptions, HttpServerBuilder};
 use servers::influxdb::InfluxdbRequest;
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::InfluxdbLineProtocolHandler;
-use session::context::QueryContextRef;
+use session::context::{QueryContext, QueryContextRef};
 use tokio::sync::mpsc;
 
 struct DummyInstance {
@@ -59,7 +61,7 @@ impl InfluxdbLineProtocolHandler for DummyInstance {
         for expr in requests.inserts {
             let _ = self
                 .tx
-                .send((ctx.current_schema().to_owned(), expr.table_name))
+                .send((ctx.current_schema(), expr.table_name))
                 .await;
         }
 
@@ -231,3 +233,103 @@ async fn test_influxdb_write() {
         ]
     );
 }
+
+/// Counts the rows and requests it receives.
+#[derive(Default)]
+struct RowCounter {
+    rows: AtomicUsize,
+    requests: AtomicUsize,
+}
+
+#[async_trait]
+impl InfluxdbLineProtocolHandler for RowCounter {
+    async fn exec(&self, request: InfluxdbRequest, _ctx: QueryContextRef) -> Result<()> {
+        let requests: RowInsertRequests = request.try_into()?;
+        for insert in requests.inserts {
+            let rows = insert.rows.map(|rows| rows.rows.len()).unwrap_or(0);
+            let _ = self.rows.fetch_add(rows, Ordering::Relaxed);
+        }
+        let _ = self.requests.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Splits `payload` into chunks of `chunk_size` bytes and streams them as the body.
+fn chunked_body(payload: String, chunk_size: usize) -> hyper::Body {
+    let chunks = payload
+        .into_bytes()
+        .chunks(chunk_size)
+        .map(|chunk| Ok::<_, std::io::Error>(chunk.to_vec()))
+        .collect::<Vec<_>>();
+    hyper::Body::wrap_stream(futures::stream::iter(chunks))
+}
+
+#[tokio::test]
+async fn test_influxdb_write_streaming() {
+    let num_lines = 10_000;
+    let lines = (0..num_lines)
+        .map(|i| {
+            format!(
+                "monitor,host=host{} cpu={}.5 {}",
+                i % 7,
+                i,
+                1664370459457010101i64 + i
+            )
+        })
+        .collect::<Vec<_>>();
+    // The payload doesn't end with a newline so the last chunk is a partial line.
+    let payload = lines.join("\n");
+
+    let counter = Arc::new(RowCounter::default());
+    let result = influxdb_write(
+        "public",
+        None,
+        false,
+        chunked_body(payload, 777),
+        counter.clone(),
+        QueryContext::arc(),
+    )
+    .await;
+    assert!(result.is_ok());
+    assert_eq!(num_lines as usize, counter.rows.load(Ordering::Relaxed));
+    // Lines are inserted in batches instead of all at once.
+    assert!(counter.requests.load(Ordering::Relaxed) > 1);
+
+    // A malformed line in the middle of the payload.
+    let mut malformed_lines = lines.clone();
+    malformed_lines.insert(5000, "monitor,host=host1 cpu=".to_string());
+    let payload = malformed_lines.join("\n");
+
+    let counter = Arc::new(RowCounter::default());
+    let result = influxdb_write(
+        "public",
+        None,
+        false,
+        chunked_body(payload.clone(), 777),
+        counter.clone(),
+        QueryContext::arc(),
+    )
+    .await;
+    let Err(err) = result else {
+        panic!("expect error");
+    };
+    assert!(
+        matches!(err, Error::InfluxdbMalformedLine { line: 5001, .. }),
+        "{err:?}"
+    );
+    // Only batches before the batch with the malformed line are inserted.
+    assert_eq!(4096, counter.rows.load(Ordering::Relaxed));
+
+    let counter = Arc::new(RowCounter::default());
+    let result = influxdb_write(
+        "public",
+        None,
+        true,
+        chunked_body(payload, 777),
+        counter.clone(),
+        QueryContext::arc(),
+    )
+    .await;
+    assert!(result.is_ok());
+    assert_eq!(num_lines as usize, counter.rows.load(Ordering::Relaxed));
+}
@@ -14,7 +14,8 @@
 
 use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use api::v1::region::RegionRequestHeader;
 use arc_swap::ArcSwap;
@@ -34,10 +35,24 @@ pub type ConnInfoRef = Arc<ConnInfo>;
 #[builder(build_fn(skip))]
 pub struct QueryContext {
     current_catalog: String,
-    current_schema: String,
+    #[builder(setter(custom))]
+    current_schema: ArcSwap<String>,
     current_user: ArcSwap<Option<UserInfoRef>>,
     timezone: Timezone,
     sql_dialect: Box<dyn Dialect + Send + Sync>,
+    /// Whether the statements being executed evaluate predicates in the order they
+    /// are written instead of reordering them by cost.
+    #[builder(setter(skip))]
+    ordered_predicates: AtomicBool,
+    result_limit: ResultLimit,
+    /// Limit applied to queries without a LIMIT clause.
+    default_limit: Option<usize>,
+    /// What to do when inserting timestamps more precise than their columns.
+    #[builder(setter(custom))]
+    timestamp_precision_action: ArcSwap<TimestampPrecisionAction>,
+    /// Warnings raised while executing the statements, e.g. results are truncated.
+    #[builder(setter(skip))]
+    warnings: Mutex<Vec<String>>,
 }
 
 impl Display for QueryContext {
@@ -56,10 +71,15 @@ impl From<&RegionRequestHeader> for QueryContext {
         let (catalog, schema) = parse_catalog_and_schema_from_db_string(&value.dbname);
         QueryContext {
             current_catalog: catalog.to_string(),
-            current_schema: schema.to_string(),
+            current_schema: ArcSwap::new(Arc::new(schema.to_string())),
             current_user: Default::default(),
             timezone: get_timezone(None),
             sql_dialect: Box::new(GreptimeDbDialect {}),
+            ordered_predicates: Default::default(),
+            result_limit: ResultLimit::default(),
+            default_limit: None,
+            timestamp_precision_action: Default::default(),
+            warnings: Default::default(),
         }
     }
 }
@@ -95,8 +115,14 @@ impl QueryContext {
     }
 
     #[inline]
-    pub fn current_schema(&self) -> &str {
-        &self.current_schema
+    pub fn current_schema(&self) -> String {
+        self.current_schema.load().as_ref().clone()
+    }
+
+    /// Switches the default schema, e.g. on `USE`, for the statements that follow.
+    #[inline]
+    pub fn set_current_schema(&self, schema: &str) {
+        self.current_schema.store(Arc::new(schema.to_string()));
     }
 
     #[inline]
@@ -112,7 +138,7 @@ impl QueryContext {
     pub fn get_db_string(&self) -> String {
         let catalog = self.current_catalog();
         let schema = self.current_schema();
-        build_db_string(catalog, schema)
+        build_db_string(catalog, &schema)
     }
 
     #[inline]
@@ -129,9 +155,62 @@ impl QueryContext {
     pub fn set_current_user(&self, user: Option<UserInfoRef>) {
         let _ = self.current_user.swap(Arc::new(user));
     }
+
+    #[inline]
+    pub fn ordered_predicates(&self) -> bool {
+        self.ordered_predicates.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the statements that follow keep the order of their predicates.
+    #[inline]
+    pub fn set_ordered_predicates(&self, ordered_predicates: bool) {
+        self.ordered_predicates
+            .store(ordered_predicates, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn result_limit(&self) -> ResultLimit {
+        self.result_limit
+    }
+
+    #[inline]
+    pub fn default_limit(&self) -> Option<usize> {
+        self.default_limit
+    }
+
+    #[inline]
+    pub fn timestamp_precision_action(&self) -> TimestampPrecisionAction {
+        **self.timestamp_precision_action.load()
+    }
+
+    /// Sets what to do when the insertions that follow have timestamps more precise
+    /// than their columns, e.g. on `SET timestamp_precision_action = 'error'`.
+    #[inline]
+    pub fn set_timestamp_precision_action(&self, action: TimestampPrecisionAction) {
+        self.timestamp_precision_action.store(Arc::new(action));
+    }
+
+    pub fn add_warning(&self, warning: String) {
+        self.warnings.lock().unwrap().push(warning);
+    }
+
+    /// Returns the warnings raised so far.
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.lock().unwrap().clone()
+    }
 }
 
 impl QueryContextBuilder {
+    pub fn current_schema(mut self, schema: String) -> Self {
+        self.current_schema = Some(ArcSwap::new(Arc::new(schema)));
+        self
+    }
+
+    pub fn timestamp_precision_action(mut self, action: TimestampPrecisionAction) -> Self {
+        self.timestamp_precision_action = Some(ArcSwap::new(Arc::new(action)));
+        self
+    }
+
     pub fn build(self) -> QueryContextRef {
         Arc::new(QueryContext {
             current_catalog: self
@@ -139,7 +218,7 @@ impl QueryContextBuilder {
                 .unwrap_or_else(|| DEFAULT_CATALOG_NAME.to_string()),
             current_schema: self
                 .current_schema
-                .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string()),
+                .unwrap_or_else(|| ArcSwap::new(Arc::new(DEFAULT_SCHEMA_NAME.to_string()))),
             current_user: self
                 .current_user
                 .unwrap_or_else(|| ArcSwap::new(Arc::new(None))),
@@ -147,10 +226,68 @@ impl QueryContextBuilder {
             sql_dialect: self
                 .sql_dialect
                 .unwrap_or_else(|| Box::new(GreptimeDbDialect {})),
+            ordered_predicates: Default::default(),
+            result_limit: self.result_limit.unwrap_or_default(),
+            default_limit: self.default_limit.unwrap_or_default(),
+            timestamp_precision_action: self
+                .timestamp_precision_action
+                .unwrap_or_else(|| ArcSwap::new(Arc::new(TimestampPrecisionAction::default()))),
+            warnings: Default::default(),
         })
     }
 }
 
+/// What to do when the results of a query exceed the [ResultLimit].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultLimitAction {
+    /// Returns the results within the limit and raises a warning.
+    #[default]
+    Truncate,
+    /// Fails the query.
+    Error,
+}
+
+/// Limits the size of the results returned to the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResultLimit {
+    pub max_rows: Option<usize>,
+    pub max_bytes: Option<usize>,
+    pub action: ResultLimitAction,
+}
+
+impl ResultLimit {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_rows.is_none() && self.max_bytes.is_none()
+    }
+}
+
+/// What to do when inserting a timestamp more precise than the unit of its column,
+/// e.g. inserting `2023-01-01 00:00:00.123` into a `TIMESTAMP(0)` column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampPrecisionAction {
+    /// Truncates the timestamp to the unit of the column.
+    #[default]
+    Truncate,
+    /// Fails the insertion.
+    Error,
+}
+
+impl TimestampPrecisionAction {
+    /// Name of the session variable and the request header setting the action.
+    pub const VARIABLE_NAME: &'static str = "timestamp_precision_action";
+
+    /// Parses the action from its name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<TimestampPrecisionAction> {
+        if name.eq_ignore_ascii_case("truncate") {
+            Some(TimestampPrecisionAction::Truncate)
+        } else if name.eq_ignore_ascii_case("error") {
+            Some(TimestampPrecisionAction::Error)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ConnInfo {
     pub client_addr: Option<SocketAddr>,
@@ -235,4 +372,28 @@ mod test {
         let context = QueryContext::with(DEFAULT_CATALOG_NAME, "test");
         assert_eq!("test", context.get_db_string());
     }
+
+    #[test]
+    fn test_timestamp_precision_action() {
+        assert_eq!(
+            Some(TimestampPrecisionAction::Error),
+            TimestampPrecisionAction::from_name("ERROR")
+        );
+        assert_eq!(
+            Some(TimestampPrecisionAction::Truncate),
+            TimestampPrecisionAction::from_name("truncate")
+        );
+        assert_eq!(None, TimestampPrecisionAction::from_name("round"));
+
+        let context = QueryContext::arc();
+        assert_eq!(
+            TimestampPrecisionAction::Truncate,
+            context.timestamp_precision_action()
+        );
+        context.set_timestamp_precision_action(TimestampPrecisionAction::Error);
+        assert_eq!(
+            TimestampPrecisionAction::Error,
+            context.timestamp_precision_action()
+        );
+    }
 }
@@ -25,7 +25,7 @@ use common_time::timezone::get_timezone;
 use common_time::Timezone;
 use context::QueryContextBuilder;
 
-use crate::context::{Channel, ConnInfo, QueryContextRef};
+use crate::context::{Channel, ConnInfo, QueryContextRef, ResultLimit, TimestampPrecisionAction};
 
 /// Session for persistent connection such as MySQL, PostgreSQL etc.
 #[derive(Debug)]
@@ -35,6 +35,11 @@ pub struct Session {
     user_info: ArcSwap<UserInfoRef>,
     conn_info: ConnInfo,
     timezone: ArcSwap<Timezone>,
+    result_limit: ArcSwap<ResultLimit>,
+    default_limit: ArcSwap<Option<usize>>,
+    timestamp_precision_action: ArcSwap<TimestampPrecisionAction>,
+    /// Warnings raised by the last statement.
+    warnings: ArcSwap<Vec<String>>,
 }
 
 pub type SessionRef = Arc<Session>;
@@ -47,6 +52,10 @@ impl Session {
             user_info: ArcSwap::new(Arc::new(auth::userinfo_by_name(None))),
             conn_info: ConnInfo::new(addr, channel),
             timezone: ArcSwap::new(Arc::new(get_timezone(None))),
+            result_limit: ArcSwap::new(Arc::new(ResultLimit::default())),
+            default_limit: ArcSwap::new(Arc::new(None)),
+            timestamp_precision_action: ArcSwap::new(Arc::new(TimestampPrecisionAction::default())),
+            warnings: ArcSwap::new(Arc::new(Vec::new())),
         }
     }
 
@@ -60,6 +69,9 @@ impl Session {
             .current_schema(self.schema.load().to_string())
             .sql_dialect(self.conn_info.channel.dialect())
             .timezone((**self.timezone.load()).clone())
+            .result_limit(**self.result_limit.load())
+            .default_limit(**self.default_limit.load())
+            .timestamp_precision_action(**self.timestamp_precision_action.load())
             .build()
     }
 
@@ -83,6 +95,51 @@ impl Session {
         let _ = self.timezone.swap(Arc::new(tz));
     }
 
+    #[inline]
+    pub fn result_limit(&self) -> ResultLimit {
+        **self.result_limit.load()
+    }
+
+    /// Sets the limit of the results of the queries that follow.
+    #[inline]
+    pub fn set_result_limit(&self, result_limit: ResultLimit) {
+        self.result_limit.store(Arc::new(result_limit));
+    }
+
+    #[inline]
+    pub fn default_limit(&self) -> Option<usize> {
+        **self.default_limit.load()
+    }
+
+    /// Sets the limit applied to the queries that follow if they have no LIMIT clause.
+    #[inline]
+    pub fn set_default_limit(&self, default_limit: Option<usize>) {
+        self.default_limit.store(Arc::new(default_limit));
+    }
+
+    #[inline]
+    pub fn timestamp_precision_action(&self) -> TimestampPrecisionAction {
+        **self.timestamp_precision_action.load()
+    }
+
+    /// Sets what to do when the insertions that follow have timestamps more precise
+    /// than their columns.
+    #[inline]
+    pub fn set_timestamp_precision_action(&self, action: TimestampPrecisionAction) {
+        self.timestamp_precision_action.store(Arc::new(action));
+    }
+
+    #[inline]
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.load().as_ref().clone()
+    }
+
+    /// Keeps the warnings raised by the last statement, e.g. for `SHOW WARNINGS`.
+    #[inline]
+    pub fn set_warnings(&self, warnings: Vec<String>) {
+        self.warnings.store(Arc::new(warnings));
+    }
+
     #[inline]
     pub fn user_info(&self) -> UserInfoRef {
         self.user_info.load().clone().as_ref().clone()
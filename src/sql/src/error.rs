@@ -145,6 +145,18 @@ pub enum Error {
         target_unit: TimeUnit,
     },
 
+    #[snafu(display(
+        "Timestamp {:?} is more precise than unit {:?} of column {}",
+        timestamp,
+        target_unit,
+        column_name
+    ))]
+    TimestampPrecisionLoss {
+        column_name: String,
+        timestamp: Timestamp,
+        target_unit: TimeUnit,
+    },
+
     #[snafu(display("Unable to convert statement {} to DataFusion statement", statement))]
     ConvertToDfStatement {
         statement: String,
@@ -183,6 +195,7 @@ impl ErrorExt for Error {
             | InvalidTableName { .. }
             | InvalidSqlValue { .. }
             | TimestampOverflow { .. }
+            | TimestampPrecisionLoss { .. }
             | InvalidTableOption { .. }
             | InvalidCast { .. } => StatusCode::InvalidArguments,
 
@@ -17,11 +17,12 @@ use sqlparser::ast::Ident;
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError, ParserOptions};
-use sqlparser::tokenizer::{Token, TokenWithLocation};
+use sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
 
 use crate::ast::{Expr, ObjectName};
 use crate::error::{self, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::parsers::{compaction_parser, freeze_parser, sync_parser, tql_parser};
+use crate::statements::hint::HintComments;
 use crate::statements::statement::Statement;
 use crate::statements::transform_statements;
 
@@ -29,6 +30,8 @@ use crate::statements::transform_statements;
 pub struct ParserContext<'a> {
     pub(crate) parser: Parser<'a>,
     pub(crate) sql: &'a str,
+    /// Dialect of the parser, to create a parser of rewritten tokens.
+    pub(crate) dialect: &'a dyn Dialect,
 }
 
 impl<'a> ParserContext<'a> {
@@ -36,11 +39,20 @@ impl<'a> ParserContext<'a> {
     pub fn create_with_dialect(sql: &'a str, dialect: &dyn Dialect) -> Result<Vec<Statement>> {
         let mut stmts: Vec<Statement> = Vec::new();
 
+        // Tokenizes the sql by ourselves to keep comments, which the parser skips.
+        let tokens = Tokenizer::new(dialect, sql)
+            .tokenize_with_location()
+            .map_err(ParserError::from)
+            .context(SyntaxSnafu)?;
+        let mut hint_comments = HintComments::new(&tokens);
         let parser = Parser::new(dialect)
             .with_options(ParserOptions::new().with_trailing_commas(true))
-            .try_with_sql(sql)
-            .context(SyntaxSnafu)?;
-        let mut parser_ctx = ParserContext { sql, parser };
+            .with_tokens_with_locations(tokens);
+        let mut parser_ctx = ParserContext {
+            sql,
+            parser,
+            dialect,
+        };
 
         let mut expecting_statement_delimiter = false;
         loop {
@@ -56,7 +68,11 @@ impl<'a> ParserContext<'a> {
                 return parser_ctx.unsupported(parser_ctx.peek_token_as_string());
             }
 
-            let statement = parser_ctx.parse_statement()?;
+            let mut statement = parser_ctx.parse_statement()?;
+            let hints = hint_comments.take_before(&parser_ctx.parser.peek_token());
+            if let Statement::Query(query) = &mut statement {
+                query.hints = hints;
+            }
             stmts.push(statement);
             expecting_statement_delimiter = true;
         }
@@ -117,12 +133,58 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::TRUNCATE => self.parse_truncate(),
 
+                    Keyword::SET => self.parse_set_variables(),
+
+                    Keyword::USE => {
+                        let _ = self.parser.next_token();
+
+                        let database_name =
+                            self.parser
+                                .parse_identifier()
+                                .context(error::UnexpectedSnafu {
+                                    sql: self.sql,
+                                    expected: "a database name",
+                                    actual: self.peek_token_as_string(),
+                                })?;
+                        Ok(Statement::Use(
+                            Self::canonicalize_identifier(database_name).value,
+                        ))
+                    }
+
                     Keyword::NoKeyword
                         if w.value.to_uppercase() == tql_parser::TQL && w.quote_style.is_none() =>
                     {
                         self.parse_tql()
                     }
 
+                    _ if w.value.to_uppercase() == sync_parser::SYNC && w.quote_style.is_none() => {
+                        self.parse_sync()
+                    }
+
+                    _ if w.value.to_uppercase() == freeze_parser::FREEZE
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_freeze(true)
+                    }
+
+                    _ if w.value.to_uppercase() == freeze_parser::UNFREEZE
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_freeze(false)
+                    }
+
+                    _ if w.value.to_uppercase() == compaction_parser::PAUSE
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_pause_compaction(true)
+                    }
+
+                    _ if w.value.to_uppercase() == compaction_parser::RESUME
+                        && w.quote_style.is_none() =>
+                    {
+                        self.parse_pause_compaction(false)
+                    }
+
                     // todo(hl) support more statements.
                     _ => self.unsupported(self.peek_token_as_string()),
                 }
@@ -253,4 +315,18 @@ mod tests {
             ConcreteDataType::timestamp_millisecond_datatype(),
         );
     }
+
+    #[test]
+    pub fn test_parse_use() {
+        let sql = "USE MySchema";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(Statement::Use("myschema".to_string()), stmts.pop().unwrap());
+
+        let sql = "USE `MySchema`";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(Statement::Use("MySchema".to_string()), stmts.pop().unwrap());
+
+        let sql = "USE";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
 }
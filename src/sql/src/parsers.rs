@@ -13,14 +13,18 @@
 // limitations under the License.
 
 mod alter_parser;
+pub(crate) mod compaction_parser;
 pub(crate) mod copy_parser;
 pub(crate) mod create_parser;
 pub(crate) mod delete_parser;
 pub(crate) mod describe_parser;
 pub(crate) mod drop_parser;
 pub(crate) mod explain_parser;
+pub(crate) mod freeze_parser;
 pub(crate) mod insert_parser;
 pub(crate) mod query_parser;
+pub(crate) mod set_var_parser;
 pub(crate) mod show_parser;
+pub(crate) mod sync_parser;
 pub(crate) mod tql_parser;
 pub(crate) mod truncate_parser;
@@ -0,0 +1,106 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::{ensure, ResultExt};
+use sqlparser::keywords::Keyword;
+
+use crate::error::{self, InvalidTableNameSnafu, Result};
+use crate::parser::ParserContext;
+use crate::statements::compaction::PauseCompaction;
+use crate::statements::statement::Statement;
+
+pub const PAUSE: &str = "PAUSE";
+pub const RESUME: &str = "RESUME";
+const COMPACTION: &str = "COMPACTION";
+
+/// `PAUSE COMPACTION TABLE table_name;` or `RESUME COMPACTION TABLE table_name;`
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_pause_compaction(&mut self, paused: bool) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        if !self.consume_token(COMPACTION) {
+            return self.expected(COMPACTION, self.parser.peek_token());
+        }
+        self.parser
+            .expect_keyword(Keyword::TABLE)
+            .context(error::SyntaxSnafu)?;
+
+        let raw_table_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        let table_ident = Self::canonicalize_object_name(raw_table_ident);
+
+        ensure!(
+            !table_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_ident.to_string()
+            }
+        );
+
+        Ok(Statement::PauseCompaction(PauseCompaction::new(
+            table_ident,
+            paused,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::ast::{Ident, ObjectName};
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+
+    #[test]
+    pub fn test_parse_pause_compaction() {
+        let sql = "PAUSE COMPACTION TABLE foo";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::PauseCompaction(PauseCompaction::new(
+                ObjectName(vec![Ident::new("foo")]),
+                true
+            ))
+        );
+
+        let sql = "resume compaction table my_schema.foo";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::PauseCompaction(PauseCompaction::new(
+                ObjectName(vec![Ident::new("my_schema"), Ident::new("foo")]),
+                false
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_invalid_pause_compaction() {
+        let sql = "PAUSE TABLE foo";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        assert!(result.is_err(), "result is: {result:?}");
+
+        let sql = "RESUME COMPACTION foo";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        assert!(result.is_err(), "result is: {result:?}");
+
+        let sql = "PAUSE COMPACTION TABLE";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        assert!(result.is_err(), "result is: {result:?}");
+    }
+}
@@ -34,7 +34,8 @@ use crate::error::{
 };
 use crate::parser::ParserContext;
 use crate::statements::create::{
-    CreateDatabase, CreateExternalTable, CreateTable, PartitionEntry, Partitions, TIME_INDEX,
+    CreateCatalog, CreateDatabase, CreateExternalTable, CreateTable, PartitionEntry, Partitions,
+    TIME_INDEX,
 };
 use crate::statements::statement::Statement;
 use crate::statements::{
@@ -44,9 +45,11 @@ use crate::util::parse_option_string;
 
 pub const ENGINE: &str = "ENGINE";
 pub const MAXVALUE: &str = "MAXVALUE";
+pub const CATALOG: &str = "CATALOG";
 
 static LESS: Lazy<Token> = Lazy::new(|| Token::make_keyword("LESS"));
 static THAN: Lazy<Token> = Lazy::new(|| Token::make_keyword("THAN"));
+static DATANODE: Lazy<Token> = Lazy::new(|| Token::make_keyword("DATANODE"));
 
 /// Parses create [table] statement
 impl<'a> ParserContext<'a> {
@@ -59,6 +62,8 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::EXTERNAL => self.parse_create_external_table(),
 
+                _ if w.value.eq_ignore_ascii_case(CATALOG) => self.parse_create_catalog(),
+
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
@@ -137,6 +142,28 @@ impl<'a> ParserContext<'a> {
         }))
     }
 
+    fn parse_create_catalog(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let catalog_name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a catalog name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Statement::CreateCatalog(CreateCatalog {
+            name: catalog_name,
+            if_not_exists,
+        }))
+    }
+
     fn parse_create_table(&mut self) -> Result<Statement> {
         let _ = self.parser.next_token();
         let if_not_exists =
@@ -237,7 +264,28 @@ impl<'a> ParserContext<'a> {
 
         let value_list = self.parse_comma_separated(Self::parse_value_list)?;
 
-        Ok(PartitionEntry { name, value_list })
+        let placement_hint = self.parse_placement_hint()?;
+
+        Ok(PartitionEntry {
+            name,
+            value_list,
+            placement_hint,
+        })
+    }
+
+    // Parses the optional "ON DATANODE <datanode id>" of a partition entry.
+    fn parse_placement_hint(&mut self) -> Result<Option<u64>> {
+        if !self.parser.parse_keyword(Keyword::ON) {
+            return Ok(None);
+        }
+        self.parser
+            .expect_token(&DATANODE)
+            .context(error::SyntaxSnafu)?;
+        let datanode_id = self
+            .parser
+            .parse_literal_uint()
+            .context(error::SyntaxSnafu)?;
+        Ok(Some(datanode_id))
     }
 
     fn parse_value_list(&mut self) -> Result<SqlValue> {
@@ -998,6 +1046,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_catalog() {
+        let sql = "create catalog if not exists tenant";
+        let stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::CreateCatalog(c) => {
+                assert_eq!(c.name.to_string(), "tenant");
+                assert!(c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_validate_create() {
         let sql = r"
@@ -1184,6 +1247,55 @@ ENGINE=mito";
         }
     }
 
+    #[test]
+    fn test_parse_create_table_with_placement_hints() {
+        let sql = r"
+CREATE TABLE monitor (
+  host_id    INT,
+  idc        STRING,
+  ts         TIMESTAMP,
+  TIME INDEX (ts),
+  PRIMARY KEY (host_id),
+)
+PARTITION BY RANGE COLUMNS(host_id) (
+  PARTITION r0 VALUES LESS THAN (1000) ON DATANODE 1,
+  PARTITION r1 VALUES LESS THAN (2000),
+  PARTITION r2 VALUES LESS THAN (MAXVALUE) ON DATANODE 3,
+)
+ENGINE=mito";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(result.len(), 1);
+        match &result[0] {
+            Statement::CreateTable(c) => {
+                let entries = &c.partitions.as_ref().unwrap().entries;
+                let placement_hints = entries.iter().map(|x| x.placement_hint).collect::<Vec<_>>();
+                assert_eq!(placement_hints, vec![Some(1), None, Some(3)]);
+                assert_eq!(
+                    entries[0].to_string(),
+                    "PARTITION r0 VALUES LESS THAN (1000) ON DATANODE 1"
+                );
+                assert_eq!(
+                    entries[1].to_string(),
+                    "PARTITION r1 VALUES LESS THAN (2000)"
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = r"
+CREATE TABLE monitor (
+  host_id    INT,
+  ts         TIMESTAMP,
+  TIME INDEX (ts),
+  PRIMARY KEY (host_id),
+)
+PARTITION BY RANGE COLUMNS(host_id) (
+  PARTITION r0 VALUES LESS THAN (MAXVALUE) ON DATANODE foo,
+)
+ENGINE=mito";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+
     #[test]
     fn test_parse_create_table_with_timestamp_index() {
         let sql1 = r"
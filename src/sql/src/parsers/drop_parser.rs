@@ -14,19 +14,33 @@
 
 use snafu::{ensure, ResultExt};
 use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::Token;
 
 use crate::error::{self, InvalidTableNameSnafu, Result};
 use crate::parser::ParserContext;
-use crate::statements::drop::DropTable;
+use crate::parsers::create_parser::CATALOG;
+use crate::statements::drop::{DropCatalog, DropDatabase, DropTable};
 use crate::statements::statement::Statement;
 
 /// DROP statement parser implementation
 impl<'a> ParserContext<'a> {
     pub(crate) fn parse_drop(&mut self) -> Result<Statement> {
         let _ = self.parser.next_token();
-        if !self.matches_keyword(Keyword::TABLE) {
-            return self.unsupported(self.peek_token_as_string());
+        match self.parser.peek_token().token {
+            Token::Word(w) => match w.keyword {
+                Keyword::TABLE => self.parse_drop_table(),
+
+                Keyword::SCHEMA | Keyword::DATABASE => self.parse_drop_database(),
+
+                _ if w.value.eq_ignore_ascii_case(CATALOG) => self.parse_drop_catalog(),
+
+                _ => self.unsupported(w.to_string()),
+            },
+            unexpected => self.unsupported(unexpected.to_string()),
         }
+    }
+
+    fn parse_drop_table(&mut self) -> Result<Statement> {
         let _ = self.parser.next_token();
 
         let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
@@ -48,6 +62,48 @@ impl<'a> ParserContext<'a> {
 
         Ok(Statement::DropTable(DropTable::new(table_ident, if_exists)))
     }
+
+    fn parse_drop_database(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let database_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a database name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        let cascade = self.parser.parse_keyword(Keyword::CASCADE);
+
+        Ok(Statement::DropDatabase(DropDatabase::new(
+            database_name,
+            if_exists,
+            cascade,
+        )))
+    }
+
+    fn parse_drop_catalog(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let catalog_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a catalog name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        let cascade = self.parser.parse_keyword(Keyword::CASCADE);
+
+        Ok(Statement::DropCatalog(DropCatalog::new(
+            catalog_name,
+            if_exists,
+            cascade,
+        )))
+    }
 }
 
 #[cfg(test)]
@@ -101,4 +157,52 @@ mod tests {
             ))
         )
     }
+
+    #[test]
+    pub fn test_drop_database() {
+        let sql = "DROP DATABASE foo";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropDatabase(DropDatabase::new(
+                ObjectName(vec![Ident::new("foo")]),
+                false,
+                false
+            ))
+        );
+
+        let sql = "DROP SCHEMA IF EXISTS foo CASCADE";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropDatabase(DropDatabase::new(
+                ObjectName(vec![Ident::new("foo")]),
+                true,
+                true
+            ))
+        );
+
+        let sql = "DROP DATABASE";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+
+    #[test]
+    pub fn test_drop_catalog() {
+        let sql = "DROP CATALOG foo CASCADE";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropCatalog(DropCatalog::new(
+                ObjectName(vec![Ident::new("foo")]),
+                false,
+                true
+            ))
+        );
+
+        let sql = "DROP VIEW foo";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
 }
@@ -12,28 +12,366 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
+use std::str::FromStr;
+
+use common_time::Timestamp;
 use snafu::prelude::*;
+use sqlparser::ast::{Ident, ObjectName, Query as SpQuery, SetExpr, TableFactor};
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::{Parser, ParserOptions};
+use sqlparser::tokenizer::Token;
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
-use crate::statements::query::Query;
+use crate::statements::query::{AsOfClause, Query, TableSampleClause};
 use crate::statements::statement::Statement;
 
+/// Keyword at the end of a query to follow rows inserted later.
+const FOLLOW: &str = "FOLLOW";
+/// Keyword of the clause after a table to sample the table.
+const TABLESAMPLE: &str = "TABLESAMPLE";
+/// Keyword of the seed option of the `TABLESAMPLE` clause.
+const REPEATABLE: &str = "REPEATABLE";
+/// Sampling methods of the `TABLESAMPLE` clause.
+const SAMPLE_METHODS: [&str; 2] = ["BERNOULLI", "SYSTEM"];
+
 impl<'a> ParserContext<'a> {
     /// Parses select and it's variants.
     pub(crate) fn parse_query(&mut self) -> Result<Statement> {
-        let spquery = self.parser.parse_query().context(error::SyntaxSnafu)?;
+        let as_of = self.take_as_of()?;
+        let sample = self.take_table_sample()?;
+        let limit_all = self.peek_limit_all();
+        let trailing_follow = self.peek_follow();
+        let mut spquery = self.parser.parse_query().context(error::SyntaxSnafu)?;
+        let follow =
+            trailing_follow && (self.consume_follow() || remove_follow_alias(&mut spquery));
+
+        let mut query = Query::try_from(spquery)?;
+        query.limit_all = limit_all;
+        query.follow = follow;
+        query.as_of = as_of;
+        query.sample = sample;
+        Ok(Statement::Query(Box::new(query)))
+    }
+
+    /// Removes the `AS OF '<timestamp>'` clause after a table from the query to parse and
+    /// returns it.
+    ///
+    /// The parser takes `AS OF` as the alias of the table and then fails on the timestamp,
+    /// so we replace the parser with a parser of the remaining tokens without the clause.
+    fn take_as_of(&mut self) -> Result<Option<AsOfClause>> {
+        if !self.sql.to_ascii_uppercase().contains("OF") {
+            return Ok(None);
+        }
+
+        let mut as_of = None;
+        for n in 0.. {
+            match self.parser.peek_nth_token(n).token {
+                Token::EOF | Token::SemiColon => break,
+                Token::Word(word) if word.keyword == Keyword::AS => {
+                    let is_of = matches!(
+                        self.parser.peek_nth_token(n + 1).token,
+                        Token::Word(word) if word.keyword == Keyword::OF
+                    );
+                    if let (true, Token::SingleQuotedString(timestamp)) =
+                        (is_of, self.parser.peek_nth_token(n + 2).token)
+                    {
+                        ensure!(
+                            as_of.is_none(),
+                            error::InvalidSqlSnafu {
+                                msg: "a query only supports one AS OF clause",
+                            }
+                        );
+                        as_of = Some((n, timestamp));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some((start, timestamp)) = as_of else {
+            return Ok(None);
+        };
+        let table = self
+            .peek_table_before(start)
+            .context(error::InvalidSqlSnafu {
+                msg: "AS OF must follow a table",
+            })?;
+        if let Err(e) = Timestamp::from_str(&timestamp) {
+            return error::InvalidSqlSnafu {
+                msg: format!("invalid AS OF timestamp '{timestamp}': {e}"),
+            }
+            .fail();
+        }
+
+        self.remove_tokens(start..start + 3);
+
+        Ok(Some(AsOfClause { table, timestamp }))
+    }
+
+    /// Removes the `TABLESAMPLE <method> (<percentage>) [REPEATABLE (<seed>)]` clause after
+    /// a table from the query to parse and returns it.
+    ///
+    /// The parser doesn't support the clause, so we replace the parser with a parser of the
+    /// remaining tokens without the clause.
+    fn take_table_sample(&mut self) -> Result<Option<TableSampleClause>> {
+        if !self.sql.to_ascii_uppercase().contains(TABLESAMPLE) {
+            return Ok(None);
+        }
+
+        let mut start = None;
+        for n in 0.. {
+            match self.parser.peek_nth_token(n).token {
+                Token::EOF | Token::SemiColon => break,
+                token if is_unquoted_word(&token, TABLESAMPLE) => {
+                    ensure!(
+                        start.is_none(),
+                        error::InvalidSqlSnafu {
+                            msg: "a query only supports one TABLESAMPLE clause",
+                        }
+                    );
+                    start = Some(n);
+                }
+                _ => {}
+            }
+        }
+        let Some(start) = start else {
+            return Ok(None);
+        };
+        let table = self
+            .peek_table_before(start)
+            .context(error::InvalidSqlSnafu {
+                msg: "TABLESAMPLE must follow a table",
+            })?;
+
+        let method = match self.parser.peek_nth_token(start + 1).token {
+            Token::Word(word) if SAMPLE_METHODS.contains(&word.value.to_uppercase().as_str()) => {
+                word.value.to_uppercase()
+            }
+            token => return error::InvalidSqlSnafu {
+                msg: format!(
+                    "expect sampling method BERNOULLI or SYSTEM after TABLESAMPLE, found {token}"
+                ),
+            }
+            .fail(),
+        };
+        let percentage = self.peek_parenthesized_number(start + 2, "TABLESAMPLE percentage")?;
+        ensure!(
+            percentage
+                .parse::<f64>()
+                .is_ok_and(|percentage| (0.0..=100.0).contains(&percentage)),
+            error::InvalidSqlSnafu {
+                msg: format!("TABLESAMPLE percentage {percentage} is not in range [0, 100]"),
+            }
+        );
+
+        let mut end = start + 5;
+        let mut seed = None;
+        if is_unquoted_word(&self.parser.peek_nth_token(end).token, REPEATABLE) {
+            let value = self.peek_parenthesized_number(end + 1, "REPEATABLE seed")?;
+            seed = Some(value.parse::<u64>().ok().context(error::InvalidSqlSnafu {
+                msg: format!("invalid REPEATABLE seed {value}"),
+            })?);
+            end += 4;
+        }
+        self.remove_tokens(start..end);
+
+        Ok(Some(TableSampleClause {
+            table,
+            method,
+            percentage,
+            seed,
+        }))
+    }
+
+    /// Returns the number in parentheses starting from the `n`th token to parse.
+    fn peek_parenthesized_number(&self, n: usize, expected: &str) -> Result<String> {
+        match (
+            self.parser.peek_nth_token(n).token,
+            self.parser.peek_nth_token(n + 1).token,
+            self.parser.peek_nth_token(n + 2).token,
+        ) {
+            (Token::LParen, Token::Number(number, _), Token::RParen) => Ok(number),
+            _ => error::InvalidSqlSnafu {
+                msg: format!("expect {expected} in parentheses"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Returns the canonicalized name of the table before the `n`th token to parse, the
+    /// alias of the table is skipped.
+    fn peek_table_before(&self, n: usize) -> Option<ObjectName> {
+        let token = |i: usize| self.parser.peek_nth_token(i).token;
+        let mut end = n.checked_sub(1)?;
+        match (end.checked_sub(1).map(token), token(end)) {
+            // `t AS alias`
+            (Some(Token::Word(prev)), Token::Word(_))
+                if prev.keyword == Keyword::AS && end >= 2 =>
+            {
+                end -= 2
+            }
+            // `t alias`
+            (Some(Token::Word(prev)), Token::Word(_))
+                if !matches!(prev.keyword, Keyword::FROM | Keyword::JOIN) =>
+            {
+                end -= 1
+            }
+            _ => {}
+        }
+
+        let mut idents = Vec::new();
+        loop {
+            let Token::Word(word) = token(end) else {
+                return None;
+            };
+            idents.push(Ident {
+                value: word.value,
+                quote_style: word.quote_style,
+            });
+            if end >= 2 && token(end - 1) == Token::Period {
+                end -= 2;
+            } else {
+                break;
+            }
+        }
+        idents.reverse();
+
+        Some(Self::canonicalize_object_name(ObjectName(idents)))
+    }
+
+    /// Replaces the parser with a parser of the remaining tokens without tokens in the
+    /// `range`, which is relative to the next token to parse.
+    fn remove_tokens(&mut self, range: Range<usize>) {
+        let mut tokens = Vec::new();
+        loop {
+            let token = self.parser.next_token();
+            if token.token == Token::EOF {
+                break;
+            }
+            tokens.push(token);
+        }
+        let _ = tokens.drain(range);
+        self.parser = Parser::new(self.dialect)
+            .with_options(ParserOptions::new().with_trailing_commas(true))
+            .with_tokens_with_locations(tokens);
+    }
+
+    /// Returns true if the last token of the query to parse is `FOLLOW`, but not an alias
+    /// like `AS follow` or a qualified name like `t.follow`.
+    fn peek_follow(&self) -> bool {
+        if !self.sql.to_ascii_uppercase().contains(FOLLOW) {
+            return false;
+        }
+
+        let mut prev = Token::EOF;
+        let mut last = Token::EOF;
+        for n in 0.. {
+            match self.parser.peek_nth_token(n).token {
+                Token::EOF | Token::SemiColon => break,
+                token => prev = std::mem::replace(&mut last, token),
+            }
+        }
+        let prev_is_as = matches!(&prev, Token::Word(word) if word.keyword == Keyword::AS);
+        is_follow(&last) && !prev_is_as && prev != Token::Period
+    }
+
+    /// Consumes the next token if it is `FOLLOW`.
+    fn consume_follow(&mut self) -> bool {
+        if is_follow(&self.parser.peek_token().token) {
+            let _ = self.parser.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the query to parse has a `LIMIT ALL` clause outside of subqueries.
+    ///
+    /// The parser drops `LIMIT ALL` as it is the same as no limit, so we have to look
+    /// for it before parsing.
+    fn peek_limit_all(&self) -> bool {
+        if !self.sql.to_ascii_uppercase().contains("ALL") {
+            return false;
+        }
+
+        let mut depth = 0usize;
+        let mut prev_is_limit = false;
+        for n in 0.. {
+            let token = self.parser.peek_nth_token(n).token;
+            let is_limit = match &token {
+                Token::EOF => return false,
+                Token::SemiColon if depth == 0 => return false,
+                Token::LParen => {
+                    depth += 1;
+                    false
+                }
+                Token::RParen => {
+                    depth = depth.saturating_sub(1);
+                    false
+                }
+                Token::Word(word) if depth == 0 => {
+                    if prev_is_limit && word.keyword == Keyword::ALL {
+                        return true;
+                    }
+                    word.keyword == Keyword::LIMIT
+                }
+                _ => false,
+            };
+            prev_is_limit = is_limit;
+        }
+        false
+    }
+}
+
+/// Returns true if the `token` is an unquoted `FOLLOW`.
+fn is_follow(token: &Token) -> bool {
+    is_unquoted_word(token, FOLLOW)
+}
 
-        Ok(Statement::Query(Box::new(Query::try_from(spquery)?)))
+/// Returns true if the `token` is the unquoted `word`, case insensitive.
+fn is_unquoted_word(token: &Token, word: &str) -> bool {
+    match token {
+        Token::Word(w) => w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word),
+        _ => false,
     }
 }
 
+/// `FOLLOW` isn't a keyword of the parser, so the parser takes it as the alias of the
+/// last table in `SELECT * FROM t FOLLOW`. Removes the alias and returns true in this case.
+fn remove_follow_alias(query: &mut SpQuery) -> bool {
+    let SetExpr::Select(select) = query.body.as_mut() else {
+        return false;
+    };
+    let Some(table) = select.from.last_mut() else {
+        return false;
+    };
+    let relation = match table.joins.last_mut() {
+        Some(join) => &mut join.relation,
+        None => &mut table.relation,
+    };
+    let (TableFactor::Table { alias, .. } | TableFactor::Derived { alias, .. }) = relation else {
+        return false;
+    };
+    let is_follow_alias = alias.as_ref().is_some_and(|alias| {
+        alias.columns.is_empty()
+            && alias.name.quote_style.is_none()
+            && alias.name.value.eq_ignore_ascii_case(FOLLOW)
+    });
+    if is_follow_alias {
+        *alias = None;
+    }
+    is_follow_alias
+}
+
 #[cfg(test)]
 mod tests {
     use common_error::ext::ErrorExt;
+    use sqlparser::ast::{Ident, ObjectName};
 
     use crate::dialect::GreptimeDbDialect;
     use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
 
     #[test]
     pub fn test_parse_query() {
@@ -45,6 +383,177 @@ mod tests {
         let _ = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
     }
 
+    #[test]
+    pub fn test_parse_limit_all() {
+        let parse = |sql| match ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0)
+        {
+            Statement::Query(query) => query,
+            _ => unreachable!(),
+        };
+
+        assert!(parse("SELECT * FROM t LIMIT ALL").limit_all);
+        assert!(parse("select * from t limit all offset 10").limit_all);
+        assert!(!parse("SELECT * FROM t").limit_all);
+        assert!(!parse("SELECT * FROM t LIMIT 10").limit_all);
+        // `LIMIT ALL` in subqueries doesn't count.
+        assert!(!parse("SELECT * FROM (SELECT * FROM t LIMIT ALL) LIMIT 10").limit_all);
+        assert!(!parse("SELECT * FROM t; SELECT * FROM t LIMIT ALL").limit_all);
+    }
+
+    #[test]
+    pub fn test_parse_follow() {
+        let parse = |sql| match ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0)
+        {
+            Statement::Query(query) => query,
+            _ => unreachable!(),
+        };
+
+        let query = parse("SELECT * FROM t FOLLOW");
+        assert!(query.follow);
+        assert_eq!("SELECT * FROM t FOLLOW", query.to_string());
+        assert!(parse("SELECT * FROM t WHERE a > 1 follow;").follow);
+        assert!(parse("SELECT * FROM t ORDER BY ts LIMIT 10 FOLLOW").follow);
+        assert!(parse("SELECT * FROM t1 JOIN t2 ON t1.a = t2.a FOLLOW").follow);
+        assert!(!parse("SELECT * FROM t").follow);
+
+        // Aliases and columns named follow.
+        let query = parse("SELECT * FROM t AS follow");
+        assert!(!query.follow);
+        assert_eq!("SELECT * FROM t AS follow", query.to_string());
+        assert!(!parse("SELECT * FROM t \"follow\"").follow);
+        assert!(!parse("SELECT * FROM t WHERE a = t.follow").follow);
+        assert!(!parse("SELECT * FROM t WHERE a = follow").follow);
+        assert!(!parse("SELECT * FROM (SELECT * FROM t FOLLOW)").follow);
+    }
+
+    #[test]
+    pub fn test_parse_as_of() {
+        let parse = |sql| match ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0)
+        {
+            Statement::Query(query) => query,
+            _ => unreachable!(),
+        };
+
+        let query = parse("SELECT * FROM t AS OF '2023-01-01 00:00:00'");
+        let as_of = query.as_of.as_ref().unwrap();
+        assert_eq!(ObjectName(vec![Ident::new("t")]), as_of.table);
+        assert_eq!("2023-01-01 00:00:00", as_of.timestamp);
+        assert_eq!(
+            "SELECT * FROM t AS OF '2023-01-01 00:00:00'",
+            query.to_string()
+        );
+        let query = parse("select * from T as of '2023-01-01T00:00:00Z' where a > 1 order by ts");
+        assert_eq!(
+            "2023-01-01T00:00:00Z",
+            query.as_of.as_ref().unwrap().timestamp
+        );
+        assert_eq!(
+            "SELECT * FROM T AS OF '2023-01-01T00:00:00Z' WHERE a > 1 ORDER BY ts",
+            query.to_string()
+        );
+        // The clause only applies to the table it follows.
+        let query =
+            parse("SELECT * FROM t1 JOIN db.t2 AS x AS OF '2023-01-01 00:00:00' ON t1.a = x.a");
+        assert_eq!(
+            ObjectName(vec![Ident::new("db"), Ident::new("t2")]),
+            query.as_of.as_ref().unwrap().table
+        );
+        assert_eq!(
+            "SELECT * FROM t1 JOIN db.t2 AS OF '2023-01-01 00:00:00' AS x ON t1.a = x.a",
+            query.to_string()
+        );
+        // Both clauses after the same table.
+        let query = parse("SELECT * FROM t AS OF '2023-01-01 00:00:00' TABLESAMPLE SYSTEM (1)");
+        assert_eq!(
+            "SELECT * FROM t AS OF '2023-01-01 00:00:00' TABLESAMPLE SYSTEM (1)",
+            query.to_string()
+        );
+        assert!(parse("SELECT * FROM t").as_of.is_none());
+        // Aliases named with `AS` are not AS OF clauses.
+        let query = parse("SELECT * FROM t AS of_t");
+        assert!(query.as_of.is_none());
+        assert_eq!("SELECT * FROM t AS of_t", query.to_string());
+
+        let result = ParserContext::create_with_dialect(
+            "SELECT * FROM t AS OF 'not a timestamp'",
+            &GreptimeDbDialect {},
+        );
+        assert!(result.is_err());
+        let result = ParserContext::create_with_dialect(
+            "SELECT * FROM t1 AS OF '2023-01-01 00:00:00', t2 AS OF '2023-01-01 00:00:00'",
+            &GreptimeDbDialect {},
+        );
+        assert!(result.is_err());
+        let result = ParserContext::create_with_dialect(
+            "SELECT * FROM (SELECT * FROM t) AS OF '2023-01-01 00:00:00'",
+            &GreptimeDbDialect {},
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_parse_table_sample() {
+        let parse = |sql| match ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0)
+        {
+            Statement::Query(query) => query,
+            _ => unreachable!(),
+        };
+
+        let query = parse("SELECT * FROM t TABLESAMPLE BERNOULLI (10) WHERE a > 1");
+        let sample = query.sample.as_ref().unwrap();
+        assert_eq!(ObjectName(vec![Ident::new("t")]), sample.table);
+        assert_eq!("BERNOULLI", sample.method);
+        assert_eq!("10", sample.percentage);
+        assert_eq!(None, sample.seed);
+        assert_eq!(
+            "SELECT * FROM t TABLESAMPLE BERNOULLI (10) WHERE a > 1",
+            query.to_string()
+        );
+
+        let query = parse("select * from Db.T as x tablesample system(0.5) repeatable(42)");
+        let sample = query.sample.as_ref().unwrap();
+        assert_eq!(
+            ObjectName(vec![Ident::new("db"), Ident::new("t")]),
+            sample.table
+        );
+        assert_eq!("SYSTEM", sample.method);
+        assert_eq!("0.5", sample.percentage);
+        assert_eq!(Some(42), sample.seed);
+        assert_eq!(
+            "SELECT * FROM Db.T TABLESAMPLE SYSTEM (0.5) REPEATABLE (42) AS x",
+            query.to_string()
+        );
+        // The clause only applies to the table it follows.
+        let query = parse("SELECT * FROM t1 JOIN t2 TABLESAMPLE BERNOULLI (1) ON t1.a = t2.a");
+        assert_eq!(
+            ObjectName(vec![Ident::new("t2")]),
+            query.sample.as_ref().unwrap().table
+        );
+        assert!(parse("SELECT * FROM t").sample.is_none());
+
+        for sql in [
+            "SELECT * FROM t TABLESAMPLE (10)",
+            "SELECT * FROM t TABLESAMPLE BLOCK (10)",
+            "SELECT * FROM t TABLESAMPLE BERNOULLI (101)",
+            "SELECT * FROM t TABLESAMPLE BERNOULLI (10) REPEATABLE (-1)",
+            "SELECT * FROM (SELECT * FROM t) TABLESAMPLE BERNOULLI (10)",
+            "SELECT * FROM t1 TABLESAMPLE SYSTEM (1), t2 TABLESAMPLE SYSTEM (1)",
+        ] {
+            assert!(
+                ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err(),
+                "{sql}"
+            );
+        }
+    }
+
     #[test]
     pub fn test_parse_invalid_query() {
         let sql = "SELECT * FROM table_1 WHERE";
@@ -0,0 +1,73 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::ast::Statement as SpStatement;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::set_variables::SetVariables;
+use crate::statements::statement::Statement;
+
+/// SET variables statement.
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_set_variables(&mut self) -> Result<Statement> {
+        let statement = self.parser.parse_statement().context(error::SyntaxSnafu)?;
+        match statement {
+            SpStatement::SetVariable {
+                local: false,
+                hivevar: false,
+                variable,
+                value,
+                ..
+            } => Ok(Statement::SetVariables(SetVariables { variable, value })),
+            unexpected => self.unsupported(unexpected.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::ast::{Expr, Ident, ObjectName, Value};
+
+    use super::*;
+    use crate::dialect::{GreptimeDbDialect, MySqlDialect};
+
+    #[test]
+    pub fn test_parse_set_variables() {
+        let sql = "SET timestamp_precision_action = 'error'";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(
+            Statement::SetVariables(SetVariables {
+                variable: ObjectName(vec![Ident::new("timestamp_precision_action")]),
+                value: vec![Expr::Value(Value::SingleQuotedString("error".to_string()))],
+            }),
+            stmts.pop().unwrap()
+        );
+
+        let sql = "set TIMESTAMP_PRECISION_ACTION = truncate";
+        let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+        assert_eq!(
+            Statement::SetVariables(SetVariables {
+                variable: ObjectName(vec![Ident::new("TIMESTAMP_PRECISION_ACTION")]),
+                value: vec![Expr::Identifier(Ident::new("truncate"))],
+            }),
+            stmts.pop().unwrap()
+        );
+
+        // Only session variables are supported.
+        let sql = "SET LOCAL timestamp_precision_action = 'error'";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+}
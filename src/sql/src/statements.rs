@@ -13,17 +13,22 @@
 // limitations under the License.
 
 pub mod alter;
+pub mod compaction;
 pub mod copy;
 pub mod create;
 pub mod delete;
 pub mod describe;
 pub mod drop;
 pub mod explain;
+pub mod freeze;
+pub mod hint;
 pub mod insert;
 mod option_map;
 pub mod query;
+pub mod set_variables;
 pub mod show;
 pub mod statement;
+pub mod sync;
 pub mod tql;
 mod transform;
 pub mod truncate;
@@ -53,13 +58,15 @@ use crate::ast::{
 use crate::error::{
     self, ColumnTypeMismatchSnafu, ConvertSqlValueSnafu, ConvertToGrpcDataTypeSnafu,
     ConvertValueSnafu, InvalidCastSnafu, InvalidSqlValueSnafu, ParseSqlValueSnafu, Result,
-    SerializeColumnDefaultConstraintSnafu, TimestampOverflowSnafu, UnsupportedDefaultValueSnafu,
+    SerializeColumnDefaultConstraintSnafu, TimestampOverflowSnafu, TimestampPrecisionLossSnafu,
+    UnsupportedDefaultValueSnafu,
 };
 
 fn parse_string_to_value(
     column_name: &str,
     s: String,
     data_type: &ConcreteDataType,
+    truncate_timestamp: bool,
 ) -> Result<Value> {
     ensure!(
         data_type.is_stringifiable(),
@@ -94,12 +101,19 @@ fn parse_string_to_value(
         }
         ConcreteDataType::Timestamp(t) => {
             if let Ok(ts) = Timestamp::from_str(&s) {
-                Ok(Value::Timestamp(ts.convert_to(t.unit()).context(
-                    TimestampOverflowSnafu {
+                let converted = ts.convert_to(t.unit()).context(TimestampOverflowSnafu {
+                    timestamp: ts,
+                    target_unit: t.unit(),
+                })?;
+                ensure!(
+                    truncate_timestamp || converted == ts,
+                    TimestampPrecisionLossSnafu {
+                        column_name,
                         timestamp: ts,
                         target_unit: t.unit(),
-                    },
-                )?))
+                    }
+                );
+                Ok(Value::Timestamp(converted))
             } else {
                 ParseSqlValueSnafu {
                     msg: format!("Failed to parse {s} to Timestamp value"),
@@ -210,10 +224,31 @@ where
     }
 }
 
+/// Converts the sql value into a value of the data type, truncates timestamps more
+/// precise than the data type.
 pub fn sql_value_to_value(
     column_name: &str,
     data_type: &ConcreteDataType,
     sql_val: &SqlValue,
+) -> Result<Value> {
+    convert_sql_value(column_name, data_type, sql_val, true)
+}
+
+/// Converts the sql value into a value of the data type like [sql_value_to_value], but
+/// returns an error instead of truncating timestamps more precise than the data type.
+pub fn sql_value_to_exact_value(
+    column_name: &str,
+    data_type: &ConcreteDataType,
+    sql_val: &SqlValue,
+) -> Result<Value> {
+    convert_sql_value(column_name, data_type, sql_val, false)
+}
+
+fn convert_sql_value(
+    column_name: &str,
+    data_type: &ConcreteDataType,
+    sql_val: &SqlValue,
+    truncate_timestamp: bool,
 ) -> Result<Value> {
     let value = match sql_val {
         SqlValue::Number(n, _) => sql_number_to_value(data_type, n)?,
@@ -231,7 +266,7 @@ pub fn sql_value_to_value(
             (*b).into()
         }
         SqlValue::DoubleQuotedString(s) | SqlValue::SingleQuotedString(s) => {
-            parse_string_to_value(column_name, s.clone(), data_type)?
+            parse_string_to_value(column_name, s.clone(), data_type, truncate_timestamp)?
         }
         SqlValue::HexStringLiteral(s) => parse_hex_string(s)?,
         SqlValue::Placeholder(s) => return InvalidSqlValueSnafu { value: s }.fail(),
@@ -728,6 +763,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_millisecond_datatype(),
+            true,
         )
         .unwrap()
         {
@@ -744,6 +780,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Second),
+            true,
         )
         .unwrap()
         {
@@ -760,6 +797,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Microsecond),
+            true,
         )
         .unwrap()
         {
@@ -776,6 +814,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+            true,
         )
         .unwrap()
         {
@@ -792,10 +831,37 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+            true,
         )
         .is_err());
     }
 
+    #[test]
+    fn test_sql_value_to_exact_timestamp() {
+        let sql_val = SqlValue::SingleQuotedString("2022-02-22T00:01:01.123Z".to_string());
+        let second_type = ConcreteDataType::timestamp_datatype(TimeUnit::Second);
+        let nanosecond_type = ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond);
+
+        // Truncates the timestamp by default.
+        let value = sql_value_to_value("ts", &second_type, &sql_val).unwrap();
+        assert_eq!(Value::Timestamp(Timestamp::new_second(1645488061)), value);
+        let err = sql_value_to_exact_value("ts", &second_type, &sql_val).unwrap_err();
+        assert!(
+            matches!(err, error::Error::TimestampPrecisionLoss { .. }),
+            "unexpected error: {err:?}"
+        );
+
+        // Timestamps that fit the unit are not lost.
+        let value = sql_value_to_exact_value("ts", &nanosecond_type, &sql_val).unwrap();
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_nanosecond(1645488061123000000)),
+            value
+        );
+        let sql_val = SqlValue::SingleQuotedString("2022-02-22T00:01:01Z".to_string());
+        let value = sql_value_to_exact_value("ts", &second_type, &sql_val).unwrap();
+        assert_eq!(Value::Timestamp(Timestamp::new_second(1645488061)), value);
+    }
+
     #[test]
     pub fn test_parse_column_default_constraint() {
         let bool_value = sqlparser::ast::Value::Boolean(true);
@@ -0,0 +1,40 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+use sqlparser_derive::{Visit, VisitMut};
+
+/// PAUSE COMPACTION TABLE and RESUME COMPACTION TABLE statements.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct PauseCompaction {
+    table_name: ObjectName,
+    paused: bool,
+}
+
+impl PauseCompaction {
+    /// Creates a statement for `PAUSE COMPACTION TABLE` if `paused` is true, otherwise
+    /// for `RESUME COMPACTION TABLE`.
+    pub fn new(table_name: ObjectName, paused: bool) -> Self {
+        Self { table_name, paused }
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        &self.table_name
+    }
+
+    /// Returns true to pause compactions of the table and false to resume them.
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+}
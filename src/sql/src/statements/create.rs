@@ -144,6 +144,8 @@ impl Partitions {
 pub struct PartitionEntry {
     pub name: Ident,
     pub value_list: Vec<SqlValue>,
+    /// The id of the datanode that the partition prefers to be placed on.
+    pub placement_hint: Option<u64>,
 }
 
 impl Display for PartitionEntry {
@@ -153,7 +155,11 @@ impl Display for PartitionEntry {
             "PARTITION {} VALUES LESS THAN ({})",
             self.name,
             format_list_comma!(self.value_list),
-        )
+        )?;
+        if let Some(datanode_id) = self.placement_hint {
+            write!(f, " ON DATANODE {datanode_id}")?;
+        }
+        Ok(())
     }
 }
 
@@ -205,6 +211,13 @@ pub struct CreateDatabase {
     pub if_not_exists: bool,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Visit, VisitMut)]
+pub struct CreateCatalog {
+    pub name: ObjectName,
+    /// Create if not exists
+    pub if_not_exists: bool,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Visit, VisitMut)]
 pub struct CreateExternalTable {
     /// Table name
@@ -40,3 +40,69 @@ impl DropTable {
         self.drop_if_exists
     }
 }
+
+/// DROP DATABASE statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct DropDatabase {
+    name: ObjectName,
+    /// drop database if exists
+    drop_if_exists: bool,
+    /// drop the tables inside the database as well
+    cascade: bool,
+}
+
+impl DropDatabase {
+    /// Creates a statement for `DROP DATABASE`
+    pub fn new(name: ObjectName, if_exists: bool, cascade: bool) -> Self {
+        Self {
+            name,
+            drop_if_exists: if_exists,
+            cascade,
+        }
+    }
+
+    pub fn name(&self) -> &ObjectName {
+        &self.name
+    }
+
+    pub fn drop_if_exists(&self) -> bool {
+        self.drop_if_exists
+    }
+
+    pub fn cascade(&self) -> bool {
+        self.cascade
+    }
+}
+
+/// DROP CATALOG statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct DropCatalog {
+    name: ObjectName,
+    /// drop catalog if exists
+    drop_if_exists: bool,
+    /// drop the databases inside the catalog as well
+    cascade: bool,
+}
+
+impl DropCatalog {
+    /// Creates a statement for `DROP CATALOG`
+    pub fn new(name: ObjectName, if_exists: bool, cascade: bool) -> Self {
+        Self {
+            name,
+            drop_if_exists: if_exists,
+            cascade,
+        }
+    }
+
+    pub fn name(&self) -> &ObjectName {
+        &self.name
+    }
+
+    pub fn drop_if_exists(&self) -> bool {
+        self.drop_if_exists
+    }
+
+    pub fn cascade(&self) -> bool {
+        self.cascade
+    }
+}
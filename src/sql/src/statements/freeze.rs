@@ -0,0 +1,40 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+use sqlparser_derive::{Visit, VisitMut};
+
+/// FREEZE TABLE and UNFREEZE TABLE statements.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct FreezeTable {
+    table_name: ObjectName,
+    frozen: bool,
+}
+
+impl FreezeTable {
+    /// Creates a statement for `FREEZE TABLE` if `frozen` is true, otherwise for
+    /// `UNFREEZE TABLE`.
+    pub fn new(table_name: ObjectName, frozen: bool) -> Self {
+        Self { table_name, frozen }
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        &self.table_name
+    }
+
+    /// Returns true to freeze the table and false to unfreeze it.
+    pub fn frozen(&self) -> bool {
+        self.frozen
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optimizer hints in comments, e.g. `SELECT /*+ no_index */ * FROM t`.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use sqlparser::tokenizer::{Token, TokenWithLocation, Whitespace};
+use sqlparser_derive::{Visit, VisitMut};
+
+/// Hint to only use indexes of the columns in its arguments.
+pub const USE_INDEX: &str = "use_index";
+/// Hint to not use any index.
+pub const NO_INDEX: &str = "no_index";
+/// Hint to evaluate predicates in the order they are written.
+pub const ORDERED_PREDICATES: &str = "ordered_predicates";
+
+/// Prefix of the body of a hint comment, a comment like `/* no_index */` isn't a hint.
+const HINT_PREFIX: char = '+';
+
+/// A hint in a hint comment, e.g. `use_index(host, idc)` in `/*+ use_index(host, idc) */`.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct QueryHint {
+    /// Name of the hint in lowercase.
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl fmt::Display for QueryHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.args.is_empty() {
+            write!(f, "({})", self.args.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Hints of the hint comments in a SQL, in the order of the comments.
+pub(crate) struct HintComments {
+    /// Locations (line and column) of comments and hints in them.
+    comments: VecDeque<((u64, u64), Vec<QueryHint>)>,
+}
+
+impl HintComments {
+    /// Collects hints from the comment tokens in `tokens`.
+    pub(crate) fn new(tokens: &[TokenWithLocation]) -> Self {
+        let comments = tokens
+            .iter()
+            .filter_map(|token| match &token.token {
+                Token::Whitespace(Whitespace::MultiLineComment(body)) => {
+                    body.strip_prefix(HINT_PREFIX).map(|body| {
+                        (
+                            (token.location.line, token.location.column),
+                            parse_hints(body),
+                        )
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self { comments }
+    }
+
+    /// Takes hints of the comments before the `end` token of a statement, or all the
+    /// remaining hints if `end` is the end of the SQL.
+    pub(crate) fn take_before(&mut self, end: &TokenWithLocation) -> Vec<QueryHint> {
+        let end_location = (end.location.line, end.location.column);
+        let mut hints = Vec::new();
+        while let Some((location, _)) = self.comments.front() {
+            if end.token != Token::EOF && *location >= end_location {
+                break;
+            }
+            if let Some((_, comment_hints)) = self.comments.pop_front() {
+                hints.extend(comment_hints);
+            }
+        }
+
+        hints
+    }
+}
+
+/// Parses hints like `name(arg1, arg2) name` in the body of a hint comment.
+fn parse_hints(body: &str) -> Vec<QueryHint> {
+    let mut hints = Vec::new();
+    let mut rest = body.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '(' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut args = Vec::new();
+        if let Some(args_start) = rest.strip_prefix('(') {
+            let args_end = args_start.find(')').unwrap_or(args_start.len());
+            args = args_start[..args_end]
+                .split(',')
+                .map(|arg| arg.trim().trim_matches(|c| c == '"' || c == '`'))
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| arg.to_string())
+                .collect();
+            rest = args_start[(args_end + 1).min(args_start.len())..].trim_start();
+        }
+
+        if !name.is_empty() {
+            hints.push(QueryHint { name, args });
+        }
+    }
+
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    fn parse_query_hints(sql: &str) -> Vec<Vec<QueryHint>> {
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .into_iter()
+            .map(|stmt| match stmt {
+                Statement::Query(query) => query.hints,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn hint(name: &str, args: &[&str]) -> QueryHint {
+        QueryHint {
+            name: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_hints() {
+        assert_eq!(
+            vec![Vec::<QueryHint>::new()],
+            parse_query_hints("SELECT * FROM t")
+        );
+        assert_eq!(
+            vec![Vec::<QueryHint>::new()],
+            parse_query_hints("SELECT /* no_index */ * FROM t")
+        );
+        assert_eq!(
+            vec![vec![hint(NO_INDEX, &[])]],
+            parse_query_hints("SELECT /*+NO_INDEX*/ * FROM t")
+        );
+        assert_eq!(
+            vec![vec![
+                hint(USE_INDEX, &["host", "Idc"]),
+                hint(ORDERED_PREDICATES, &[])
+            ]],
+            parse_query_hints(
+                "SELECT /*+ use_index(host, \"Idc\") */ * FROM t /*+ ordered_predicates */"
+            )
+        );
+        // Hints in string literals are not hints.
+        assert_eq!(
+            vec![Vec::<QueryHint>::new()],
+            parse_query_hints("SELECT * FROM t WHERE a = '/*+ no_index */'")
+        );
+        // Hints belong to the statement they are in.
+        assert_eq!(
+            vec![vec![hint(NO_INDEX, &[])], vec![hint(USE_INDEX, &["a"])]],
+            parse_query_hints(
+                "SELECT /*+ no_index */ * FROM t; /*+ use_index(a) */ SELECT * FROM t"
+            )
+        );
+    }
+
+    #[test]
+    fn test_display_hints() {
+        let sql = "/*+ use_index(host, idc) no_index */ SELECT * FROM t";
+        let stmt = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0);
+        assert_eq!(sql, stmt.to_string());
+        assert_eq!(
+            vec![vec![hint(USE_INDEX, &["host", "idc"]), hint(NO_INDEX, &[])]],
+            parse_query_hints(&stmt.to_string())
+        );
+    }
+}
@@ -13,16 +13,69 @@
 // limitations under the License.
 
 use std::fmt;
+use std::ops::ControlFlow;
 
-use sqlparser::ast::Query as SpQuery;
+use sqlparser::ast::{visit_relations_mut, Ident, ObjectName, Query as SpQuery};
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::error::Error;
+use crate::parser::ParserContext;
+use crate::statements::hint::QueryHint;
 
 /// Query statement instance.
 #[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
 pub struct Query {
     pub inner: SpQuery,
+    /// Whether the query has an explicit `LIMIT ALL`, which the parser drops from `inner`.
+    pub limit_all: bool,
+    /// Whether the query ends with `FOLLOW`, which keeps returning rows inserted after
+    /// the existing rows.
+    pub follow: bool,
+    /// The `AS OF` clause after a table, the query only reads rows of the table with
+    /// timestamps not later than the clause.
+    pub as_of: Option<AsOfClause>,
+    /// Hints in the hint comments of the query, e.g. `/*+ no_index */`.
+    pub hints: Vec<QueryHint>,
+    /// The `TABLESAMPLE` clause after a table, the query only reads a sample of the table.
+    pub sample: Option<TableSampleClause>,
+}
+
+/// The `AS OF '<timestamp>'` clause after a table.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct AsOfClause {
+    /// Canonicalized name of the table to read.
+    pub table: ObjectName,
+    /// Timestamp to read the table at.
+    pub timestamp: String,
+}
+
+impl fmt::Display for AsOfClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AS OF '{}'", self.timestamp)
+    }
+}
+
+/// The `TABLESAMPLE <method> (<percentage>) [REPEATABLE (<seed>)]` clause after a table.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct TableSampleClause {
+    /// Canonicalized name of the table to sample.
+    pub table: ObjectName,
+    /// Sampling method in uppercase, `BERNOULLI` or `SYSTEM`.
+    pub method: String,
+    /// Percentage of the table to return, in range `[0, 100]`.
+    pub percentage: String,
+    /// Seed of the `REPEATABLE` option.
+    pub seed: Option<u64>,
+}
+
+impl fmt::Display for TableSampleClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TABLESAMPLE {} ({})", self.method, self.percentage)?;
+        if let Some(seed) = self.seed {
+            write!(f, " REPEATABLE ({seed})")?;
+        }
+        Ok(())
+    }
 }
 
 /// Automatically converts from sqlparser Query instance to SqlQuery.
@@ -30,7 +83,14 @@ impl TryFrom<SpQuery> for Query {
     type Error = Error;
 
     fn try_from(q: SpQuery) -> Result<Self, Self::Error> {
-        Ok(Query { inner: q })
+        Ok(Query {
+            inner: q,
+            limit_all: false,
+            follow: false,
+            as_of: None,
+            hints: vec![],
+            sample: None,
+        })
     }
 }
 
@@ -44,11 +104,51 @@ impl TryFrom<Query> for SpQuery {
 
 impl fmt::Display for Query {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.inner)?;
+        if !self.hints.is_empty() {
+            let hints: Vec<_> = self.hints.iter().map(|hint| hint.to_string()).collect();
+            write!(f, "/*+ {} */ ", hints.join(" "))?;
+        }
+        match (&self.as_of, &self.sample) {
+            (None, None) => write!(f, "{}", self.inner)?,
+            (Some(as_of), Some(sample)) if as_of.table == sample.table => write!(
+                f,
+                "{}",
+                with_table_clause(&self.inner, &as_of.table, format!("{as_of} {sample}"))
+            )?,
+            (as_of, sample) => {
+                let mut inner = self.inner.clone();
+                if let Some(as_of) = as_of {
+                    inner = with_table_clause(&inner, &as_of.table, as_of);
+                }
+                if let Some(sample) = sample {
+                    inner = with_table_clause(&inner, &sample.table, sample);
+                }
+                write!(f, "{inner}")?
+            }
+        }
+        if self.follow {
+            write!(f, " FOLLOW")?;
+        }
         Ok(())
     }
 }
 
+/// Returns a copy of the `query` with the `clause` after the first relation named `table`.
+fn with_table_clause(query: &SpQuery, table: &ObjectName, clause: impl fmt::Display) -> SpQuery {
+    let mut query = query.clone();
+    let mut clause = Some(clause);
+    let _ = visit_relations_mut(&mut query, |name| {
+        if ParserContext::canonicalize_object_name(name.clone()) == *table {
+            if let Some(clause) = clause.take() {
+                // The parser has no AST for the clause, so we display it as part of the name.
+                *name = ObjectName(vec![Ident::new(format!("{name} {clause}"))]);
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    query
+}
+
 #[cfg(test)]
 mod test {
 
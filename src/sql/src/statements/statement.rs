@@ -18,14 +18,18 @@ use sqlparser_derive::{Visit, VisitMut};
 
 use crate::error::{ConvertToDfStatementSnafu, Error};
 use crate::statements::alter::AlterTable;
-use crate::statements::create::{CreateDatabase, CreateExternalTable, CreateTable};
+use crate::statements::compaction::PauseCompaction;
+use crate::statements::create::{CreateCatalog, CreateDatabase, CreateExternalTable, CreateTable};
 use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropCatalog, DropDatabase, DropTable};
 use crate::statements::explain::Explain;
+use crate::statements::freeze::FreezeTable;
 use crate::statements::insert::Insert;
 use crate::statements::query::Query;
+use crate::statements::set_variables::SetVariables;
 use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::sync::SyncTable;
 use crate::statements::tql::Tql;
 use crate::statements::truncate::TruncateTable;
 
@@ -47,6 +51,12 @@ pub enum Statement {
     DropTable(DropTable),
     // CREATE DATABASE
     CreateDatabase(CreateDatabase),
+    // DROP DATABASE
+    DropDatabase(DropDatabase),
+    // CREATE CATALOG
+    CreateCatalog(CreateCatalog),
+    // DROP CATALOG
+    DropCatalog(DropCatalog),
     /// ALTER TABLE
     Alter(AlterTable),
     // Databases.
@@ -64,6 +74,16 @@ pub enum Statement {
     Tql(Tql),
     // TRUNCATE TABLE
     TruncateTable(TruncateTable),
+    // SYNC TABLE
+    SyncTable(SyncTable),
+    // FREEZE TABLE or UNFREEZE TABLE
+    FreezeTable(FreezeTable),
+    // PAUSE COMPACTION TABLE or RESUME COMPACTION TABLE
+    PauseCompaction(PauseCompaction),
+    // USE
+    Use(String),
+    // SET VARIABLES
+    SetVariables(SetVariables),
 }
 
 /// Comment hints from SQL.
@@ -0,0 +1,105 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of indexes of SSTs in the node.
+//!
+//! Engines register themselves as providers so the catalog can expose statistics
+//! of indexes in the node without depending on the engines.
+
+use std::sync::{Mutex, Weak};
+
+use crate::storage::{ColumnId, RegionId};
+
+/// Statistics of the index of a column in a SST, recorded while building the index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexStatistics {
+    pub region_id: RegionId,
+    /// Id of the SST file.
+    pub file_id: String,
+    pub column_id: ColumnId,
+    pub column_name: String,
+    /// Number of distinct non-null values of the column.
+    pub distinct_count: u64,
+    /// Size of the index of the column in bytes.
+    pub index_size: u64,
+    /// Average length of the postings of each value, in segments.
+    pub avg_postings_len: f64,
+}
+
+/// Provides statistics of indexes of SSTs, e.g. a region engine.
+pub trait IndexStatisticsProvider: Send + Sync {
+    /// Returns statistics of indexes of all SSTs of the provider.
+    fn index_statistics(&self) -> Vec<IndexStatistics>;
+}
+
+static PROVIDERS: Mutex<Vec<Weak<dyn IndexStatisticsProvider>>> = Mutex::new(Vec::new());
+
+/// Registers the `provider` of the node, it is unregistered once it is dropped.
+pub fn register_provider(provider: Weak<dyn IndexStatisticsProvider>) {
+    let mut providers = PROVIDERS.lock().unwrap();
+    providers.retain(|provider| provider.strong_count() > 0);
+    providers.push(provider);
+}
+
+/// Returns statistics of indexes from all providers in the node.
+pub fn index_statistics() -> Vec<IndexStatistics> {
+    let providers: Vec<_> = PROVIDERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|provider| provider.upgrade())
+        .collect();
+    providers
+        .iter()
+        .flat_map(|provider| provider.index_statistics())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    struct MockProvider(RegionId);
+
+    impl IndexStatisticsProvider for MockProvider {
+        fn index_statistics(&self) -> Vec<IndexStatistics> {
+            vec![IndexStatistics {
+                region_id: self.0,
+                file_id: "file".to_string(),
+                column_id: 1,
+                column_name: "tag".to_string(),
+                distinct_count: 10,
+                index_size: 100,
+                avg_postings_len: 1.0,
+            }]
+        }
+    }
+
+    #[test]
+    fn test_dropped_provider() {
+        let region_id = RegionId::new(1024, 1);
+        let provider: Arc<dyn IndexStatisticsProvider> = Arc::new(MockProvider(region_id));
+        register_provider(Arc::downgrade(&provider));
+        assert!(index_statistics()
+            .iter()
+            .any(|stats| stats.region_id == region_id));
+
+        drop(provider);
+        assert!(!index_statistics()
+            .iter()
+            .any(|stats| stats.region_id == region_id));
+    }
+}
@@ -16,6 +16,7 @@
 //! Storage related APIs
 
 pub mod data_source;
+pub mod index_statistics;
 pub mod logstore;
 pub mod manifest;
 pub mod metadata;
@@ -78,6 +78,10 @@ pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     /// so that the log store can safely delete those entries. This method does not guarantee
     /// that the obsolete entries are deleted immediately.
     async fn obsolete(&self, ns: Self::Namespace, entry_id: EntryId) -> Result<(), Self::Error>;
+
+    /// Forces all appended entries to be persisted to durable storage, regardless of
+    /// the configured sync policy.
+    async fn sync(&self) -> Result<(), Self::Error>;
 }
 
 /// The response of an `append` operation.
@@ -22,6 +22,7 @@ use api::greptime_proto::v1::meta::{GrantedRegion as PbGrantedRegion, RegionRole
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_recordbatch::SendableRecordBatchStream;
+use datatypes::value::Value;
 use serde::{Deserialize, Serialize};
 
 use crate::logstore::entry;
@@ -138,6 +139,31 @@ pub trait RegionEngine: Send + Sync {
         request: ScanRequest,
     ) -> Result<SendableRecordBatchStream, BoxedError>;
 
+    /// Counts rows of the region for the `request` without scanning rows, e.g. from
+    /// metadata of files, to answer `COUNT(*)`.
+    ///
+    /// Returns `None` if the engine must scan rows to count them.
+    async fn count_rows(
+        &self,
+        _region_id: RegionId,
+        _request: ScanRequest,
+    ) -> Result<Option<u64>, BoxedError> {
+        Ok(None)
+    }
+
+    /// Returns the min and max values of the `column` in the region for the `request`
+    /// without scanning rows, e.g. from statistics of files, to answer `MIN` and `MAX`.
+    ///
+    /// Returns `None` if the engine must scan rows to find the values.
+    async fn min_max(
+        &self,
+        _region_id: RegionId,
+        _column: &str,
+        _request: ScanRequest,
+    ) -> Result<Option<(Value, Value)>, BoxedError> {
+        Ok(None)
+    }
+
     /// Retrieves region's metadata.
     async fn get_metadata(&self, region_id: RegionId) -> Result<RegionMetadataRef, BoxedError>;
 
@@ -162,6 +188,22 @@ pub trait RegionEngine: Send + Sync {
         region_id: RegionId,
     ) -> Result<SetReadonlyResponse, BoxedError>;
 
+    /// Freezes or unfreezes a region.
+    ///
+    /// A frozen region rejects writes and compactions but still serves reads. The engine
+    /// persists the state so the region is still frozen after reopening.
+    async fn set_frozen(&self, region_id: RegionId, frozen: bool) -> Result<(), BoxedError>;
+
+    /// Pauses or resumes compactions of a region.
+    ///
+    /// Explicit compactions of a paused region fail. Unlike frozen state, the state is not
+    /// persisted and the region compacts normally after reopening.
+    async fn set_compaction_paused(
+        &self,
+        region_id: RegionId,
+        paused: bool,
+    ) -> Result<(), BoxedError>;
+
     /// Indicates region role.
     ///
     /// Returns the `None` if the region is not found.
@@ -18,6 +18,7 @@ use std::fmt::{self};
 use api::v1::add_column_location::LocationType;
 use api::v1::region::{alter_request, region_request, AlterRequest};
 use api::v1::{self, Rows, SemanticType};
+use common_time::Timestamp;
 use snafu::{ensure, OptionExt};
 use strum::IntoStaticStr;
 
@@ -44,6 +45,8 @@ pub enum RegionRequest {
     Compact(RegionCompactRequest),
     Truncate(RegionTruncateRequest),
     Catchup(RegionCatchupRequest),
+    DeleteRange(RegionDeleteRangeRequest),
+    Sync(RegionSyncRequest),
 }
 
 impl RegionRequest {
@@ -62,6 +65,8 @@ impl RegionRequest {
             RegionRequest::Compact(_) => "compact",
             RegionRequest::Truncate(_) => "truncate",
             RegionRequest::Catchup(_) => "catchup",
+            RegionRequest::DeleteRange(_) => "delete_range",
+            RegionRequest::Sync(_) => "sync",
         }
     }
 
@@ -469,6 +474,24 @@ pub struct RegionCatchupRequest {
     pub entry_id: Option<entry::Id>,
 }
 
+/// Delete range request.
+///
+/// Deletes all rows whose time index is in the range `[start, end)`.
+#[derive(Debug)]
+pub struct RegionDeleteRangeRequest {
+    /// Inclusive start of the range.
+    pub start: Timestamp,
+    /// Exclusive end of the range.
+    pub end: Timestamp,
+}
+
+/// Sync region request.
+///
+/// Forces writes to the region acknowledged before the request to be persisted in
+/// the WAL.
+#[derive(Debug, Default)]
+pub struct RegionSyncRequest {}
+
 impl fmt::Display for RegionRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -483,6 +506,8 @@ impl fmt::Display for RegionRequest {
             RegionRequest::Compact(_) => write!(f, "Compact"),
             RegionRequest::Truncate(_) => write!(f, "Truncate"),
             RegionRequest::Catchup(_) => write!(f, "Catchup"),
+            RegionRequest::DeleteRange(_) => write!(f, "DeleteRange"),
+            RegionRequest::Sync(_) => write!(f, "Sync"),
         }
     }
 }
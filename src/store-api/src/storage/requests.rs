@@ -12,8 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use common_query::logical_plan::Expr;
 use common_recordbatch::OrderOption;
+use datatypes::value::OrderedF64;
+use serde::{Deserialize, Serialize};
+
+/// Key of the index hint in headers of region query requests.
+const INDEX_HINT_HEADER_KEY: &str = "x-greptime-scan-index-hint";
+/// Key of the follow flag in headers of region query requests.
+const FOLLOW_HEADER_KEY: &str = "x-greptime-scan-follow";
+/// Key of the table sample in headers of region query requests.
+const SAMPLE_HEADER_KEY: &str = "x-greptime-scan-sample";
 
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct ScanRequest {
@@ -29,4 +40,136 @@ pub struct ScanRequest {
     /// If set, it contains the amount of rows needed by the caller,
     /// The data source should return *at least* this number of rows if available.
     pub limit: Option<usize>,
+    /// Returns only a sample of the data if set.
+    pub sample: Option<TableSample>,
+    /// Overrides whether the scan uses indexes.
+    pub index_hint: Option<IndexHint>,
+    /// Preferred order to read the data sources of the table, e.g. memtables and SSTs.
+    /// This is only a hint and it's ignored if the output ordering is set.
+    pub source_order: Option<SourceOrder>,
+    /// Keeps the scan open after returning the existing rows and returns rows written
+    /// to the table afterwards (`SELECT ... FOLLOW`).
+    pub follow: bool,
+}
+
+impl ScanRequest {
+    /// Writes hints of the scan to the `header` of region query requests.
+    ///
+    /// The plan sent to regions only keeps the table to scan, so the scan request
+    /// built by the region loses the hints without the header.
+    pub fn encode_hints(&self, header: &mut HashMap<String, String>) {
+        if let Some(index_hint) = &self.index_hint {
+            // Serializing hints to json never fails.
+            let _ = header.insert(
+                INDEX_HINT_HEADER_KEY.to_string(),
+                serde_json::to_string(index_hint).unwrap(),
+            );
+        }
+        if self.follow {
+            let _ = header.insert(FOLLOW_HEADER_KEY.to_string(), true.to_string());
+        }
+        if let Some(sample) = &self.sample {
+            let _ = header.insert(
+                SAMPLE_HEADER_KEY.to_string(),
+                serde_json::to_string(sample).unwrap(),
+            );
+        }
+    }
+
+    /// Reads hints written by [ScanRequest::encode_hints()] from the `header`,
+    /// invalid hints are ignored.
+    pub fn decode_hints(&mut self, header: &HashMap<String, String>) {
+        self.index_hint = header
+            .get(INDEX_HINT_HEADER_KEY)
+            .and_then(|index_hint| serde_json::from_str(index_hint).ok());
+        self.follow = header
+            .get(FOLLOW_HEADER_KEY)
+            .and_then(|follow| follow.parse().ok())
+            .unwrap_or(false);
+        self.sample = header
+            .get(SAMPLE_HEADER_KEY)
+            .and_then(|sample| serde_json::from_str(sample).ok());
+    }
+}
+
+/// Order of the data sources to read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceOrder {
+    /// Reads sources with the newest data first, e.g. for queries on recent data.
+    NewestFirst,
+    /// Reads sources with the oldest data first.
+    OldestFirst,
+}
+
+/// Hint from the query about how to use indexes in a scan.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexHint {
+    /// Only uses indexes of these columns (`/*+ use_index(col, ...) */`).
+    UseIndex(Vec<String>),
+    /// Doesn't use any index (`/*+ no_index */`).
+    NoIndex,
+}
+
+/// Method to sample the data of a table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SampleMethod {
+    /// Samples each row independently.
+    Bernoulli,
+    /// Samples blocks of rows, e.g. row groups of SSTs. It is cheaper than
+    /// [SampleMethod::Bernoulli] as unsampled blocks are never read.
+    System,
+}
+
+/// Sampling options of a scan (`TABLESAMPLE`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableSample {
+    pub method: SampleMethod,
+    /// Percentage of the data to return, in range `[0, 100]`.
+    pub percentage: OrderedF64,
+    /// Seed of the sampler. Scans with the same seed return the same sample of
+    /// the same data.
+    pub seed: Option<u64>,
+}
+
+impl TableSample {
+    /// Returns the probability that a row (or a block) is sampled.
+    pub fn probability(&self) -> f64 {
+        self.percentage.0 / 100.0
+    }
+
+    /// Returns true if the percentage is in range `[0, 100]`.
+    pub fn is_valid(&self) -> bool {
+        (0.0..=100.0).contains(&self.percentage.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_hints() {
+        let mut header = HashMap::new();
+        ScanRequest::default().encode_hints(&mut header);
+        assert!(header.is_empty());
+
+        let request = ScanRequest {
+            index_hint: Some(IndexHint::UseIndex(vec!["host".to_string()])),
+            follow: true,
+            sample: Some(TableSample {
+                method: SampleMethod::System,
+                percentage: OrderedF64::from(10.0),
+                seed: Some(42),
+            }),
+            ..Default::default()
+        };
+        request.encode_hints(&mut header);
+        let mut decoded = ScanRequest::default();
+        decoded.decode_hints(&header);
+        assert_eq!(request, decoded);
+
+        let _ = header.insert(INDEX_HINT_HEADER_KEY.to_string(), "invalid".to_string());
+        decoded.decode_hints(&header);
+        assert_eq!(None, decoded.index_hint);
+    }
 }
@@ -21,11 +21,12 @@ use common_time::timestamp::TimeUnit;
 use common_time::Timestamp;
 use datafusion::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
 use datafusion_common::ToDFSchema;
-use datafusion_expr::expr::InList;
+use datafusion_expr::expr::{Cast, InList, TryCast};
 use datafusion_expr::{Between, BinaryExpr, Operator};
 use datafusion_physical_expr::execution_props::ExecutionProps;
 use datafusion_physical_expr::{create_physical_expr, PhysicalExpr};
 use datatypes::arrow;
+use datatypes::arrow::datatypes::DataType;
 use datatypes::value::scalar_value_to_timestamp;
 use snafu::ResultExt;
 
@@ -268,18 +269,39 @@ impl<'a> TimeRangePredicateBuilder<'a> {
 
     fn get_timestamp_filter(&self, left: &DfExpr, right: &DfExpr) -> Option<(Timestamp, bool)> {
         let (col, lit, reverse) = match (left, right) {
-            (DfExpr::Column(column), DfExpr::Literal(scalar)) => (column, scalar, false),
-            (DfExpr::Literal(scalar), DfExpr::Column(column)) => (column, scalar, true),
+            (col, DfExpr::Literal(scalar)) => (col, scalar, false),
+            (DfExpr::Literal(scalar), col) => (col, scalar, true),
             _ => {
                 return None;
             }
         };
-        if col.name != self.ts_col_name {
+        if !self.is_ts_column(col) {
             return None;
         }
         scalar_value_to_timestamp(lit).map(|t| (t, reverse))
     }
 
+    /// Returns whether the `expr` is the timestamp column or a lossless cast of it.
+    ///
+    /// Comparing the timestamp column with an expr like `now() - INTERVAL '1 hour'`
+    /// casts the column to the type of the folded constant, e.g. a timestamp with
+    /// time zone. The cast doesn't change values as long as the target unit is not
+    /// coarser than the unit of the column.
+    fn is_ts_column(&self, expr: &DfExpr) -> bool {
+        match expr {
+            DfExpr::Column(col) => col.name == self.ts_col_name,
+            DfExpr::Cast(Cast { expr, data_type })
+            | DfExpr::TryCast(TryCast { expr, data_type }) => {
+                let DataType::Timestamp(unit, _) = data_type else {
+                    return false;
+                };
+                TimeUnit::from(unit).factor() <= self.ts_col_unit.factor()
+                    && matches!(expr.as_ref(), DfExpr::Column(col) if col.name == self.ts_col_name)
+            }
+            _ => false,
+        }
+    }
+
     fn extract_from_between_expr(
         &self,
         expr: &DfExpr,
@@ -353,9 +375,9 @@ mod tests {
     use common_test_util::temp_dir::{create_temp_dir, TempDir};
     use datafusion::parquet::arrow::ArrowWriter;
     use datafusion_common::{Column, ScalarValue};
-    use datafusion_expr::{col, lit, BinaryExpr, Literal, Operator};
+    use datafusion_expr::{cast, col, lit, try_cast, BinaryExpr, Literal, Operator};
     use datatypes::arrow::array::Int32Array;
-    use datatypes::arrow::datatypes::{DataType, Field, Schema};
+    use datatypes::arrow::datatypes::{DataType, Field, Schema, TimeUnit as ArrowTimeUnit};
     use datatypes::arrow::record_batch::RecordBatch;
     use datatypes::arrow_array::StringArray;
     use parquet::arrow::ParquetRecordBatchStreamBuilder;
@@ -528,6 +550,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cast_ts_column() {
+        let tz = Some("+00:00".into());
+        // CAST(ts AS Timestamp(ms, +00:00)) > 1ms
+        check_build_predicate(
+            cast(
+                col("ts"),
+                DataType::Timestamp(ArrowTimeUnit::Millisecond, tz.clone()),
+            )
+            .gt(lit(ScalarValue::TimestampMillisecond(Some(1), tz.clone()))),
+            TimestampRange::from_start(Timestamp::new_millisecond(2)),
+        );
+
+        // 1001us >= TRY_CAST(ts AS Timestamp(us, +00:00))
+        check_build_predicate(
+            lit(ScalarValue::TimestampMicrosecond(Some(1001), tz.clone())).gt_eq(try_cast(
+                col("ts"),
+                DataType::Timestamp(ArrowTimeUnit::Microsecond, tz.clone()),
+            )),
+            TimestampRange::until_end(Timestamp::new_millisecond(1), true),
+        );
+
+        // Casting to a coarser unit truncates values of the column.
+        check_build_predicate(
+            cast(
+                col("ts"),
+                DataType::Timestamp(ArrowTimeUnit::Second, tz.clone()),
+            )
+            .lt_eq(lit(ScalarValue::TimestampSecond(Some(1), tz))),
+            TimestampRange::min_to_max(),
+        );
+
+        // Casting another column.
+        check_build_predicate(
+            cast(
+                col("other"),
+                DataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+            )
+            .gt(lit(ScalarValue::TimestampMillisecond(Some(1), None))),
+            TimestampRange::min_to_max(),
+        );
+    }
+
     async fn gen_test_parquet_file(dir: &TempDir, cnt: usize) -> (String, Arc<Schema>) {
         let path = dir
             .path()
@@ -86,6 +86,16 @@ pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
 pub const TTL_KEY: &str = "ttl";
 pub const REGIONS_KEY: &str = "regions";
 pub const STORAGE_KEY: &str = "storage";
+pub const DUPLICATE_MODE_KEY: &str = "duplicate_mode";
+pub const COMPACTION_TYPE_KEY: &str = "compaction.type";
+pub const COMPACTION_LEVELED_MAX_LEVEL0_FILES_KEY: &str = "compaction.leveled.max_level0_files";
+pub const COMPACTION_LEVELED_BASE_LEVEL_SIZE_KEY: &str = "compaction.leveled.base_level_size";
+pub const COMPACTION_LEVELED_FANOUT_KEY: &str = "compaction.leveled.fanout";
+pub const ROLLUP_AFTER_KEY: &str = "rollup.after";
+pub const ROLLUP_INTERVAL_KEY: &str = "rollup.interval";
+pub const ROLLUP_AGGREGATION_KEY: &str = "rollup.aggregation";
+pub const SERIES_LIMIT_KEY: &str = "series_limit";
+pub const INVERTED_INDEX_COLUMNS_KEY: &str = "index.inverted_index.columns";
 
 impl TryFrom<&HashMap<String, String>> for TableOptions {
     type Error = error::Error;
@@ -343,6 +353,16 @@ pub fn valid_table_option(key: &str) -> bool {
             | TTL_KEY
             | REGIONS_KEY
             | STORAGE_KEY
+            | DUPLICATE_MODE_KEY
+            | COMPACTION_TYPE_KEY
+            | COMPACTION_LEVELED_MAX_LEVEL0_FILES_KEY
+            | COMPACTION_LEVELED_BASE_LEVEL_SIZE_KEY
+            | COMPACTION_LEVELED_FANOUT_KEY
+            | ROLLUP_AFTER_KEY
+            | ROLLUP_INTERVAL_KEY
+            | ROLLUP_AGGREGATION_KEY
+            | SERIES_LIMIT_KEY
+            | INVERTED_INDEX_COLUMNS_KEY
             | PHYSICAL_TABLE_METADATA_KEY
             | LOGICAL_TABLE_METADATA_KEY
     ) | is_supported_in_s3(key)
@@ -371,6 +391,12 @@ mod tests {
         assert!(valid_table_option(REGIONS_KEY));
         assert!(valid_table_option(WRITE_BUFFER_SIZE_KEY));
         assert!(valid_table_option(STORAGE_KEY));
+        assert!(valid_table_option(DUPLICATE_MODE_KEY));
+        assert!(valid_table_option(COMPACTION_TYPE_KEY));
+        assert!(valid_table_option(COMPACTION_LEVELED_FANOUT_KEY));
+        assert!(valid_table_option(ROLLUP_AFTER_KEY));
+        assert!(valid_table_option(SERIES_LIMIT_KEY));
+        assert!(valid_table_option(INVERTED_INDEX_COLUMNS_KEY));
         assert!(!valid_table_option("foo"));
     }
 
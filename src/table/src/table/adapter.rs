@@ -27,7 +27,7 @@ use datafusion_expr::expr::Expr as DfExpr;
 use datafusion_expr::TableProviderFilterPushDown as DfTableProviderFilterPushDown;
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalSortExpr;
-use store_api::storage::ScanRequest;
+use store_api::storage::{IndexHint, ScanRequest, TableSample};
 
 use super::scan::StreamScanAdapter;
 use crate::table::{TableRef, TableType};
@@ -54,7 +54,18 @@ impl DfTableProviderAdapter {
         self.scan_req.lock().unwrap().output_ordering = Some(order_opts.to_vec());
     }
 
-    #[cfg(feature = "testing")]
+    pub fn with_index_hint(&self, index_hint: IndexHint) {
+        self.scan_req.lock().unwrap().index_hint = Some(index_hint);
+    }
+
+    pub fn with_follow(&self) {
+        self.scan_req.lock().unwrap().follow = true;
+    }
+
+    pub fn with_sample(&self, sample: TableSample) {
+        self.scan_req.lock().unwrap().sample = Some(sample);
+    }
+
     pub fn get_scan_req(&self) -> ScanRequest {
         self.scan_req.lock().unwrap().clone()
     }
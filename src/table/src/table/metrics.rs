@@ -16,6 +16,15 @@ use datafusion::physical_plan::metrics::{
     Count, ExecutionPlanMetricsSet, Gauge, MetricBuilder, Timestamp,
 };
 
+/// Name of the metric that counts bytes fetched from the storage.
+pub const FETCHED_BYTES_METRIC: &str = "fetched_bytes";
+/// Name of the metric that counts SSTs whose indexes are applied.
+pub const INDEX_APPLIED_FILES_METRIC: &str = "index_applied_files";
+/// Name of the metric that counts row groups selected by indexes of SSTs.
+pub const INDEX_SELECTED_ROW_GROUPS_METRIC: &str = "index_selected_row_groups";
+/// Name of the metric that counts postings read from indexes of SSTs.
+pub const INDEX_POSTINGS_METRIC: &str = "index_postings";
+
 /// This metrics struct is used to record and hold memory usage
 /// of result batch in [`crate::table::scan::StreamWithMetricWrapper`]
 /// during query execution, indicating size of the dataset.
@@ -26,6 +35,14 @@ pub struct MemoryUsageMetrics {
     mem_used: Gauge,
     // number of rows in output
     output_rows: Count,
+    // bytes fetched from the storage
+    fetched_bytes: Count,
+    // SSTs whose indexes are applied
+    index_applied_files: Count,
+    // row groups selected by indexes
+    index_selected_row_groups: Count,
+    // postings read from indexes
+    index_postings: Count,
 }
 
 impl MemoryUsageMetrics {
@@ -38,6 +55,12 @@ impl MemoryUsageMetrics {
             end_time: MetricBuilder::new(metrics).end_timestamp(partition),
             mem_used: MetricBuilder::new(metrics).mem_used(partition),
             output_rows: MetricBuilder::new(metrics).output_rows(partition),
+            fetched_bytes: MetricBuilder::new(metrics).counter(FETCHED_BYTES_METRIC, partition),
+            index_applied_files: MetricBuilder::new(metrics)
+                .counter(INDEX_APPLIED_FILES_METRIC, partition),
+            index_selected_row_groups: MetricBuilder::new(metrics)
+                .counter(INDEX_SELECTED_ROW_GROUPS_METRIC, partition),
+            index_postings: MetricBuilder::new(metrics).counter(INDEX_POSTINGS_METRIC, partition),
         }
     }
 
@@ -49,6 +72,17 @@ impl MemoryUsageMetrics {
         self.output_rows.add(num_rows);
     }
 
+    pub fn record_fetched_bytes(&self, fetched_bytes: usize) {
+        self.fetched_bytes.add(fetched_bytes);
+    }
+
+    /// Records how indexes of SSTs are applied by the scan.
+    pub fn record_index(&self, applied_files: usize, selected_row_groups: usize, postings: usize) {
+        self.index_applied_files.add(applied_files);
+        self.index_selected_row_groups.add(selected_row_groups);
+        self.index_postings.add(postings);
+    }
+
     /// Record the end time of the query
     pub fn try_done(&self) {
         if self.end_time.value().is_none() {
@@ -135,6 +135,17 @@ impl Stream for StreamWithMetricWrapper {
             this.metric.record_mem_usage(batch_mem_size);
             this.metric.record_output(record_batch.num_rows());
         }
+        if let Poll::Ready(None) = &poll {
+            if let Some(metrics) = this.stream.metrics() {
+                this.metric
+                    .record_fetched_bytes(metrics.fetched_bytes as usize);
+                this.metric.record_index(
+                    metrics.index_applied_files as usize,
+                    metrics.index_selected_row_groups as usize,
+                    metrics.index_postings as usize,
+                );
+            }
+        }
 
         poll
     }
@@ -68,8 +68,8 @@ mod test {
 monitor1,host=host1 cpu=66.6,memory=1024
 monitor1,host=host2 memory=1027";
         let request = InfluxdbRequest {
-            precision: None,
             lines: lines.to_string(),
+            ..Default::default()
         };
         assert!(instance.exec(request, QueryContext::arc()).await.is_ok());
 
@@ -97,8 +97,8 @@ monitor1,host=host2 memory=1027";
 monitor1,host=host1 cpu=66.6,memory=1024 1663840496100023100
 monitor1,host=host2 memory=1027 1663840496400340001";
         let request = InfluxdbRequest {
-            precision: None,
             lines: lines.to_string(),
+            ..Default::default()
         };
         instance.exec(request, QueryContext::arc()).await.unwrap();
 
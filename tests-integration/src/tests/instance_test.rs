@@ -15,7 +15,11 @@
 use std::env;
 use std::sync::Arc;
 
-use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use catalog::event::CatalogEvent;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_meta::table_name::TableName;
 use common_query::Output;
 use common_recordbatch::util;
 use common_telemetry::logging;
@@ -28,7 +32,9 @@ use operator::error::Error as OperatorError;
 use rstest::rstest;
 use rstest_reuse::apply;
 use servers::query_handler::sql::SqlQueryHandler;
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{
+    QueryContext, QueryContextBuilder, QueryContextRef, TimestampPrecisionAction,
+};
 
 use crate::tests::test_util::{
     both_instances_cases, both_instances_cases_with_custom_storages, check_unordered_output_stream,
@@ -341,6 +347,111 @@ async fn test_execute_insert(instance: Arc<dyn MockInstance>) {
     assert!(matches!(output, Output::AffectedRows(2)));
 }
 
+#[apply(both_instances_cases)]
+async fn test_execute_insert_timestamp_precision(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    for (table, precision) in [("ts_second", 0), ("ts_nanosecond", 9)] {
+        let output = execute_sql(
+            &instance,
+            &format!("create table {table}(host string, ts timestamp({precision}) time index)"),
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let output = execute_sql(
+            &instance,
+            &format!(
+                "insert into {table} values ('host1', '2023-01-01T00:00:01Z'), ('host2', 1672531202)"
+            ),
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(2)));
+    }
+
+    // Both tables keep values in their own precisions.
+    let output = execute_sql(&instance, "select * from ts_second order by host").await;
+    let expected = "\
++-------+---------------------+
+| host  | ts                  |
++-------+---------------------+
+| host1 | 2023-01-01T00:00:01 |
+| host2 | 2023-01-01T00:00:02 |
++-------+---------------------+";
+    check_output_stream(output, expected).await;
+    let output = execute_sql(&instance, "select * from ts_nanosecond order by host").await;
+    let expected = "\
++-------+-------------------------------+
+| host  | ts                            |
++-------+-------------------------------+
+| host1 | 2023-01-01T00:00:01           |
+| host2 | 1970-01-01T00:00:01.672531202 |
++-------+-------------------------------+";
+    check_output_stream(output, expected).await;
+
+    // Inserts a timestamp more precise than the second precision column.
+    let query_ctx = QueryContext::arc();
+    let output = execute_sql_with(
+        &instance,
+        "SET timestamp_precision_action = 'error'",
+        query_ctx.clone(),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert_eq!(
+        TimestampPrecisionAction::Error,
+        query_ctx.timestamp_precision_action()
+    );
+    let sql = "insert into ts_second values ('host3', '2023-01-01T00:00:03.123456789Z')";
+    let err = try_execute_sql_with(&instance, sql, query_ctx)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::TableOperation {
+                source: OperatorError::ParseSql { .. },
+                ..
+            }
+        ),
+        "unexpected error: {err:?}"
+    );
+    // The nanosecond precision column keeps the timestamp.
+    let sql = "insert into ts_nanosecond values ('host3', '2023-01-01T00:00:03.123456789Z')";
+    let query_ctx = QueryContextBuilder::default()
+        .timestamp_precision_action(TimestampPrecisionAction::Error)
+        .build();
+    let output = execute_sql_with(&instance, sql, query_ctx).await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    // Truncates the timestamp by default.
+    let output = execute_sql(
+        &instance,
+        "insert into ts_second values ('host3', '2023-01-01T00:00:03.123456789Z')",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql(&instance, "select ts from ts_second where host = 'host3'").await;
+    let expected = "\
++---------------------+
+| ts                  |
++---------------------+
+| 2023-01-01T00:00:03 |
++---------------------+";
+    check_output_stream(output, expected).await;
+    let output = execute_sql(
+        &instance,
+        "select ts from ts_nanosecond where host = 'host3'",
+    )
+    .await;
+    let expected = "\
++-------------------------------+
+| ts                            |
++-------------------------------+
+| 2023-01-01T00:00:03.123456789 |
++-------------------------------+";
+    check_output_stream(output, expected).await;
+}
+
 #[apply(both_instances_cases)]
 async fn test_execute_insert_by_select(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -1435,6 +1546,224 @@ async fn test_use_database(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expected).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_read_only(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let output = execute_sql(
+        &instance,
+        "create table ro_demo(host string, cpu double, ts timestamp time index)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(
+        &instance,
+        "insert into ro_demo(host, cpu, ts) values ('host1', 1.0, 1655276557000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    instance.set_read_only(true);
+    assert!(instance.is_read_only());
+
+    let err = try_execute_sql(
+        &instance,
+        "insert into ro_demo(host, cpu, ts) values ('host2', 2.0, 1655276558000)",
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(StatusCode::InstanceReadOnly, err.status_code());
+    assert!(
+        try_execute_sql(&instance, "delete from ro_demo where host = 'host1'")
+            .await
+            .is_err()
+    );
+    assert!(try_execute_sql(&instance, "drop table ro_demo")
+        .await
+        .is_err());
+
+    let output = execute_sql(&instance, "select host from ro_demo").await;
+    let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+";
+    check_output_stream(output, expected).await;
+
+    // Writable again after promotion.
+    instance.set_read_only(false);
+    let output = execute_sql(
+        &instance,
+        "insert into ro_demo(host, cpu, ts) values ('host2', 2.0, 1655276558000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+}
+
+#[apply(both_instances_cases)]
+async fn test_catalog_events(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+    let mut subscription = instance.catalog_manager().event_notifier().subscribe();
+
+    let output = execute_sql(
+        &instance,
+        "create table event_demo(host string, ts timestamp time index)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(&instance, "alter table event_demo add column cpu double").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(&instance, "drop table event_demo").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let table_name = TableName::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "event_demo");
+    let CatalogEvent::TableCreated {
+        table_name: created,
+        table_id,
+    } = subscription.recv().await.unwrap()
+    else {
+        unreachable!()
+    };
+    assert_eq!(table_name, created);
+    assert_eq!(
+        Some(CatalogEvent::TableAltered {
+            table_name: table_name.clone(),
+            table_id,
+        }),
+        subscription.recv().await
+    );
+    assert_eq!(
+        Some(CatalogEvent::TableDropped {
+            table_name,
+            table_id,
+        }),
+        subscription.recv().await
+    );
+    assert_eq!(None, subscription.try_recv());
+}
+
+#[apply(both_instances_cases)]
+async fn test_use_statement(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let output = execute_sql(&instance, "create database db2").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = execute_sql(
+        &instance,
+        "create table db2.tb2(col_i32 int, ts timestamp, TIME INDEX(ts))",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(
+        &instance,
+        "insert into db2.tb2(col_i32, ts) values (2, 1655276557000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let query_ctx = QueryContext::arc();
+    let output = execute_sql_with(&instance, "use db2", query_ctx.clone()).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert_eq!("db2", query_ctx.current_schema());
+
+    let output = execute_sql_with(&instance, "select col_i32 from tb2", query_ctx.clone()).await;
+    let expected = "\
++---------+
+| col_i32 |
++---------+
+| 2       |
++---------+";
+    check_output_stream(output, expected).await;
+
+    // Switching to a nonexistent schema leaves the context unchanged.
+    assert!(
+        try_execute_sql_with(&instance, "use not_exist", query_ctx.clone())
+            .await
+            .is_err()
+    );
+    assert_eq!("db2", query_ctx.current_schema());
+
+    let output = execute_sql_with(&instance, "use public", query_ctx.clone()).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql_with(
+        &instance,
+        "select number from numbers limit 1",
+        query_ctx.clone(),
+    )
+    .await;
+    let expected = "\
++--------+
+| number |
++--------+
+| 0      |
++--------+";
+    check_output_stream(output, expected).await;
+    assert!(
+        try_execute_sql_with(&instance, "select col_i32 from tb2", query_ctx)
+            .await
+            .is_err()
+    );
+}
+
+#[apply(both_instances_cases)]
+async fn test_drop_database(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let output = execute_sql(&instance, "create schema tenant").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    assert!(try_execute_sql(&instance, "create schema tenant")
+        .await
+        .is_err());
+    let output = execute_sql(&instance, "create schema if not exists tenant").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql(
+        &instance,
+        "create table tenant.tb1(col_i32 int, ts timestamp, TIME INDEX(ts))",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // Dropping a non-empty schema requires CASCADE.
+    assert!(try_execute_sql(&instance, "drop schema tenant")
+        .await
+        .is_err());
+
+    let output = execute_sql(&instance, "drop schema tenant cascade").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    assert!(try_execute_sql(&instance, "select * from tenant.tb1")
+        .await
+        .is_err());
+    assert!(try_execute_sql(&instance, "drop schema tenant")
+        .await
+        .is_err());
+    let output = execute_sql(&instance, "drop schema if exists tenant").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // The schema can be recreated without any leftovers.
+    let output = execute_sql(&instance, "create schema tenant").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    assert!(try_execute_sql(&instance, "select * from tenant.tb1")
+        .await
+        .is_err());
+
+    // Reserved schemas and catalogs can't be dropped.
+    for sql in [
+        "drop schema public",
+        "drop schema if exists public cascade",
+        "drop schema information_schema",
+        "drop schema greptime_private",
+        "drop catalog greptime cascade",
+    ] {
+        let err = try_execute_sql(&instance, sql).await.unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code(), "{sql}");
+    }
+    let output = execute_sql(&instance, "show tables from public").await;
+    assert!(matches!(output, Output::Stream(_)));
+}
+
 #[apply(both_instances_cases)]
 async fn test_delete(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -21,7 +21,7 @@ use common_error::status_code::StatusCode as ErrorCode;
 use serde_json::json;
 use servers::http::error_result::ErrorResponse;
 use servers::http::greptime_result_v1::GreptimedbV1Response;
-use servers::http::handler::HealthResponse;
+use servers::http::handler::{HealthResponse, HealthStatus};
 use servers::http::influxdb_result_v1::{InfluxdbOutput, InfluxdbV1Response};
 use servers::http::prometheus::{PrometheusJsonResponse, PrometheusResponse};
 use servers::http::GreptimeQueryOutput;
@@ -141,7 +141,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records" :{"schema":{"column_schemas":[{"name":"number","data_type":"UInt32"}]},"rows":[[0],[1],[2],[3],[4],[5],[6],[7],[8],[9]]}
+            "records" :{"schema":{"column_schemas":[{"name":"number","data_type":"UInt32","nullable":false}]},"rows":[[0],[1],[2],[3],[4],[5],[6],[7],[8],[9]]}
         })).unwrap()
     );
 
@@ -175,6 +175,7 @@ pub async fn test_sql_api(store_type: StorageType) {
         .send()
         .await;
     assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("x-greptime-stats").is_some());
 
     let body = serde_json::from_str::<GreptimedbV1Response>(&res.text().await).unwrap();
     let output = body.output();
@@ -183,9 +184,11 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String"},{"name":"cpu","data_type":"Float64"},{"name":"memory","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[["host",66.6,1024.0,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"host","data_type":"String","nullable":false},{"name":"cpu","data_type":"Float64","nullable":true},{"name":"memory","data_type":"Float64","nullable":true},{"name":"ts","data_type":"TimestampMillisecond","nullable":false,"precision":3}]},"rows":[["host",66.6,1024.0,0]]}
         })).unwrap()
     );
+    // The only row in the table is scanned.
+    assert_eq!(1, body.stats().unwrap().scanned_rows);
 
     // select with projections
     let res = client
@@ -201,7 +204,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","nullable":true},{"name":"ts","data_type":"TimestampMillisecond","nullable":false,"precision":3}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -218,7 +221,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         output[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"c","data_type":"Float64"},{"name":"time","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"c","data_type":"Float64","nullable":true},{"name":"time","data_type":"TimestampMillisecond","nullable":false,"precision":3}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -235,7 +238,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         outputs[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","nullable":true},{"name":"ts","data_type":"TimestampMillisecond","nullable":false,"precision":3}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
     assert_eq!(
@@ -270,7 +273,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         outputs[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","nullable":true},{"name":"ts","data_type":"TimestampMillisecond","nullable":false,"precision":3}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -296,7 +299,7 @@ pub async fn test_sql_api(store_type: StorageType) {
     assert_eq!(
         outputs[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64"},{"name":"ts","data_type":"TimestampMillisecond"}]},"rows":[[66.6,0]]}
+            "records":{"schema":{"column_schemas":[{"name":"cpu","data_type":"Float64","nullable":true},{"name":"ts","data_type":"TimestampMillisecond","nullable":false,"precision":3}]},"rows":[[66.6,0]]}
         })).unwrap()
     );
 
@@ -556,7 +559,7 @@ def test(n) -> vector[f64]:
     assert_eq!(
         output[0],
         serde_json::from_value::<GreptimeQueryOutput>(json!({
-            "records":{"schema":{"column_schemas":[{"name":"n","data_type":"Float64"}]},"rows":[[1.0],[2.0],[3.0],[4.0],[5.0],[6.0],[7.0],[8.0],[9.0],[10.0]]}
+            "records":{"schema":{"column_schemas":[{"name":"n","data_type":"Float64","nullable":false}]},"rows":[[1.0],[2.0],[3.0],[4.0],[5.0],[6.0],[7.0],[8.0],[9.0],[10.0]]}
         })).unwrap()
     );
 
@@ -578,11 +581,9 @@ pub async fn test_health_api(store_type: StorageType) {
     let body_text = res_post.text().await;
     assert_eq!(body_text, res_get.text().await);
 
-    // currently health api simply returns an empty json `{}`, which can be deserialized to an empty `HealthResponse`
-    assert_eq!(body_text, "{}");
-
     let body = serde_json::from_str::<HealthResponse>(&body_text).unwrap();
-    assert_eq!(body, HealthResponse {});
+    assert_eq!(body.status, HealthStatus::Ready);
+    assert!(body.components.iter().all(|component| component.ready));
 }
 
 pub async fn test_status_api(store_type: StorageType) {
@@ -621,6 +622,9 @@ purge_threshold = "4GiB"
 
 [frontend]
 mode = "standalone"
+drain_timeout = "30s"
+max_plan_depth = 32
+table_write_rate_limit = 0
 
 [frontend.heartbeat]
 interval = "18s"
@@ -723,21 +727,59 @@ providers = []
 [datanode.region_engine.mito]
 worker_channel_size = 128
 worker_request_batch_size = 64
+write_coalesce_window = "0s"
+write_coalesce_max_rows = 16384
+max_open_regions = 0
 manifest_checkpoint_distance = 10
 compress_manifest = false
+list_page_size = 0
 max_background_jobs = 4
 auto_flush_interval = "30m"
 global_write_buffer_size = "1GiB"
 global_write_buffer_reject_size = "2GiB"
+adaptive_flush = false
+adaptive_flush_window = "10s"
+adaptive_flush_base_rate = "8MiB"
+adaptive_flush_min_percent = 25
+adaptive_flush_max_percent = 150
 sst_meta_cache_size = "128MiB"
 vector_cache_size = "512MiB"
 page_cache_size = "512MiB"
+sst_reader_cache_size = 0
+row_group_hint_cache_size = 0
 enable_experimental_write_cache = false
 experimental_write_cache_path = ""
 experimental_write_cache_size = "512MiB"
+experimental_write_cache_eviction_policy = "lru"
+experimental_write_cache_bypass_size = "0KiB"
+experimental_write_cache_staging_compression = "sst"
+experimental_write_cache_write_buffer_size = "0KiB"
+experimental_write_cache_concurrency = 0
 sst_write_buffer_size = "8MiB"
+sst_parquet_version = "default"
+sst_compression = "zstd"
+sst_zstd_level = 1
+sst_path_layout = "flat"
+sst_mirror_storage = ""
+verify_sst_checksum = false
+sst_prefetch_row_groups = 0
+compaction_target_file_size = "0KiB"
+flush_target_file_size = "0KiB"
+flush_target_file_rows = 0
+sst_retry_max_retries = 0
+sst_retry_base_delay = "100ms"
+sst_retry_max_delay = "10s"
+sst_retry_jitter = true
 parallel_scan_channel_size = 32
 allow_stale_entries = false
+index_intermediate_write_buffer_size = "8KiB"
+index_intermediate_backend = "object_store"
+index_intermediate_path = ""
+index_intermediate_concurrency = 0
+index_intermediate_op_timeout = "30s"
+index_intermediate_op_max_retries = 3
+index_intermediate_max_bytes = "0KiB"
+index_intermediate_open_concurrency = 8
 
 [[datanode.region_engine]]
 
@@ -777,6 +819,8 @@ fn drop_lines_with_inconsistent_results(input: String) -> String {
         "scope =",
         "num_workers =",
         "scan_parallelism =",
+        "max_concurrent_compactions =",
+        "max_concurrent_index_builds =",
     ];
 
     input
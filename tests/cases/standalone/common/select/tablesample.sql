@@ -0,0 +1,23 @@
+CREATE TABLE sample_t (
+  ts TIMESTAMP(3) TIME INDEX,
+  host STRING PRIMARY KEY,
+  val DOUBLE,
+);
+
+INSERT INTO TABLE sample_t VALUES
+    (0, 'a', 1.0),
+    (1, 'b', 2.0),
+    (2, 'c', 3.0),
+    (3, 'd', 4.0);
+
+SELECT COUNT(*) FROM sample_t TABLESAMPLE BERNOULLI (0);
+
+SELECT COUNT(*) FROM sample_t TABLESAMPLE SYSTEM (0) REPEATABLE (42);
+
+SELECT * FROM sample_t AS s TABLESAMPLE BERNOULLI (100) REPEATABLE (42) ORDER BY ts;
+
+SELECT * FROM sample_t TABLESAMPLE BERNOULLI (101);
+
+SELECT * FROM sample_t TABLESAMPLE BLOCK (10);
+
+DROP TABLE sample_t;
@@ -0,0 +1,17 @@
+PAUSE COMPACTION TABLE not_exists_table;
+
+CREATE TABLE monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, TIME INDEX (ts), PRIMARY KEY(host));
+
+PAUSE COMPACTION TABLE monitor;
+
+INSERT INTO monitor(ts, host, cpu) VALUES (1695217652000, 'host1', 66.6);
+
+SELECT ts, host, cpu FROM monitor ORDER BY ts;
+
+RESUME COMPACTION TABLE monitor;
+
+INSERT INTO monitor(ts, host, cpu) VALUES (1695217654000, 'host2', 77.7);
+
+SELECT ts, host, cpu FROM monitor ORDER BY ts;
+
+DROP TABLE monitor;
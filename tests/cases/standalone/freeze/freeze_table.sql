@@ -0,0 +1,20 @@
+FREEZE TABLE not_exists_table;
+
+CREATE TABLE monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, TIME INDEX (ts), PRIMARY KEY(host));
+
+INSERT INTO monitor(ts, host, cpu) VALUES (1695217652000, 'host1', 66.6);
+
+FREEZE TABLE monitor;
+
+-- SQLNESS REPLACE (Region\s\d+\(\d+\,\s\d+\)) Region
+INSERT INTO monitor(ts, host, cpu) VALUES (1695217654000, 'host2', 77.7);
+
+SELECT ts, host, cpu FROM monitor ORDER BY ts;
+
+UNFREEZE TABLE monitor;
+
+INSERT INTO monitor(ts, host, cpu) VALUES (1695217654000, 'host2', 77.7);
+
+SELECT ts, host, cpu FROM monitor ORDER BY ts;
+
+DROP TABLE monitor;
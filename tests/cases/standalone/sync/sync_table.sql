@@ -0,0 +1,11 @@
+SYNC TABLE not_exists_table;
+
+CREATE TABLE monitor (host STRING, ts TIMESTAMP, cpu DOUBLE, TIME INDEX (ts), PRIMARY KEY(host));
+
+INSERT INTO monitor(ts, host, cpu) VALUES (1695217652000, 'host1', 66.6), (1695217654000, 'host2', 77.7);
+
+SYNC TABLE monitor;
+
+SELECT ts, host, cpu FROM monitor ORDER BY ts;
+
+DROP TABLE monitor;